@@ -1,5 +1,6 @@
 use lotus_extra::math::PiecewiseLinearFunction;
-use lotus_script::var::set_var;
+
+use crate::api::variable::set_var;
 
 /// An animation controller that manages animation state and position.
 ///
@@ -5,10 +5,9 @@
 //! application, monitoring of physical properties, and interaction with
 //! the underlying simulation system.
 
-use lotus_script::{
-    var::{get_var, set_var},
-    vehicle::{Axle, RailQuality, SurfaceType, VehicleError},
-};
+use lotus_script::vehicle::{Axle, RailQuality, SurfaceType, VehicleError};
+
+use crate::api::variable::{get_var, set_var};
 
 /// API interface for managing railway vehicle axles.
 ///
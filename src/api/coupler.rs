@@ -1,9 +1,9 @@
-use lotus_script::{
-    message::Coupling,
-    var::{get_var, set_var},
-};
+use lotus_script::message::Coupling;
 
-use super::mock_enums::CouplingState;
+use super::{
+    mock_enums::CouplingState,
+    variable::{get_var, set_var},
+};
 
 /// API wrapper for train coupler functionality.
 ///
@@ -231,4 +231,23 @@ impl ApiCoupler {
             value,
         );
     }
+
+    /// Opens or closes a message bus across the coupler.
+    ///
+    /// # Arguments
+    ///
+    /// * `bus` - Name of the bus
+    /// * `open` - `true` to open the bus, `false` to close it
+    #[cfg(not(test))]
+    pub fn set_bus(&self, bus: &str, open: bool) {
+        if open {
+            self.coupler.open_bus(bus);
+        } else {
+            self.coupler.close_bus(bus);
+        }
+    }
+
+    /// Test builds run without the simulator, there is no bus to open.
+    #[cfg(test)]
+    pub fn set_bus(&self, _bus: &str, _open: bool) {}
 }
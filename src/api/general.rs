@@ -5,9 +5,12 @@
 //! the lower-level `lotus_script` crate functionality with convenient,
 //! type-safe functions.
 
-use lotus_script::prelude::get_var;
-use lotus_script::var::set_var;
-use lotus_script::{input::mouse_delta, math::Vec2};
+use lotus_script::math::Vec2;
+
+use crate::api::{
+    replay::replayed_delta,
+    variable::{get_var, set_var},
+};
 
 /// Gets the night texture setting.
 ///
@@ -179,8 +182,47 @@ pub fn set_hint(hint: &str) {
 /// The delta values are reset each frame, so this should be called once per
 /// frame to get accurate movement data.
 #[must_use]
+#[cfg(not(test))]
 pub fn mouse_move() -> Vec2 {
-    mouse_delta()
+    lotus_script::input::mouse_delta()
+}
+
+/// Test builds run without the simulator, the mouse does not move.
+#[cfg(test)]
+pub fn mouse_move() -> Vec2 {
+    Vec2::ZERO
+}
+
+/// Gets the time of the last frame.
+///
+/// All components take their frame time from here, so that a running
+/// [`Replayer`](crate::api::replay::Replayer) can provide the recorded times.
+///
+/// # Returns
+///
+/// Returns the frame time in seconds.
+///
+/// # Examples
+///
+/// ```rust
+/// timer += delta();
+/// ```
+#[must_use]
+pub fn delta() -> f32 {
+    replayed_delta().unwrap_or_else(simulator_delta)
+}
+
+/// Frame time of the simulator.
+#[cfg(not(test))]
+fn simulator_delta() -> f32 {
+    lotus_script::time::delta()
+}
+
+/// Test builds run without the simulator, there the time only passes in a
+/// replay.
+#[cfg(test)]
+fn simulator_delta() -> f32 {
+    0.0
 }
 
 /// Gets the current signal state.
@@ -8,7 +8,9 @@
 //! - [`KeyEvent`]: Handles key press/release state tracking with cabin awareness
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::action::state;
+use lotus_script::input::ActionState;
+
+use crate::api::{replay::replayed_action, trainer::trainer_action};
 
 /// A key event handler that tracks press/release states with cabin awareness.
 ///
@@ -82,6 +84,14 @@ impl KeyEvent {
         }
     }
 
+    /// Returns the state of the action, overridden by the trainer mode or taken
+    /// from a running replay if any.
+    fn action_state(ev: &str) -> ActionState {
+        trainer_action(ev)
+            .or_else(|| replayed_action(ev))
+            .unwrap_or_else(|| simulator_action(ev))
+    }
+
     /// Checks if the current event matches the associated cabin.
     ///
     /// This method verifies that the event's cabin assignment matches the
@@ -94,7 +104,7 @@ impl KeyEvent {
     fn matching_cab(&self) -> bool {
         match (self.cab_side, &self.name) {
             (Some(cab), Some(ev)) => {
-                if let Some(ev_side) = state(ev).cockpit_index {
+                if let Some(ev_side) = Self::action_state(ev).cockpit_index {
                     (cab == CockpitSide::A && ev_side == 0)
                         || (cab == CockpitSide::B && ev_side == 1)
                 } else {
@@ -139,7 +149,7 @@ impl KeyEvent {
     #[must_use]
     pub fn is_just_pressed(&mut self) -> bool {
         let action = if let Some(ev) = &self.name {
-            state(ev).kind.is_just_pressed() && self.matching_cab()
+            Self::action_state(ev).kind.is_just_pressed() && self.matching_cab()
         } else {
             false
         };
@@ -180,7 +190,7 @@ impl KeyEvent {
     #[must_use]
     pub fn is_just_released(&mut self) -> bool {
         let action = if let Some(ev) = &self.name {
-            state(ev).kind.is_just_released() && self.matching_cab()
+            Self::action_state(ev).kind.is_just_released() && self.matching_cab()
         } else {
             false
         };
@@ -221,7 +231,7 @@ impl KeyEvent {
     #[must_use]
     pub fn is_pressed(&mut self) -> bool {
         let action = if let Some(ev) = &self.name {
-            state(ev).kind.is_pressed() && self.matching_cab()
+            Self::action_state(ev).kind.is_pressed() && self.matching_cab()
         } else {
             false
         };
@@ -260,7 +270,7 @@ impl KeyEvent {
     #[must_use]
     pub fn is_released(&mut self) -> bool {
         let action = if let Some(ev) = &self.name {
-            state(ev).kind.is_released() && self.matching_cab()
+            Self::action_state(ev).kind.is_released() && self.matching_cab()
         } else {
             false
         };
@@ -270,3 +280,20 @@ impl KeyEvent {
         result
     }
 }
+
+/// State of the action in the simulator.
+#[cfg(not(test))]
+fn simulator_action(ev: &str) -> ActionState {
+    lotus_script::action::state(ev)
+}
+
+/// Test builds run without the simulator, all actions are idle outside of a
+/// replay.
+#[cfg(test)]
+fn simulator_action(_ev: &str) -> ActionState {
+    ActionState {
+        kind: lotus_script::input::ActionStateKind::None,
+        cockpit_index: None,
+        uv: None,
+    }
+}
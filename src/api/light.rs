@@ -4,13 +4,17 @@
 //! light bulbs with smooth transitions, blink relays, and simple blinkers for different
 //! lighting effects and animations.
 
-use lotus_script::{time::delta, var::set_var};
+use crate::{
+    api::{general::delta, variable::set_var},
+    elements::std::relays::FlasherRelay,
+    management::fault_manager::lamp_burnt_out,
+};
 
 /// A basic light structure that can control brightness through lotus_script variables.
 ///
 /// The `Light` struct provides a simple interface for controlling light sources
 /// by setting variables in the lotus_script environment.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Light {
     /// The name of the light variable in the lotus_script environment.
     /// If `None`, the light operations will be ignored.
@@ -42,6 +46,8 @@ impl Light {
     ///
     /// Updates the lotus_script variable with the new brightness level.
     /// If no name was provided during construction, this operation is ignored.
+    /// A lamp burnt out by the [`FaultManager`](crate::management::fault_manager::FaultManager)
+    /// stays dark.
     ///
     /// # Arguments
     ///
@@ -57,7 +63,12 @@ impl Light {
     /// ```
     pub fn set_brightness(&self, new_level: f32) {
         if let Some(light) = &self.name {
-            set_var(light, new_level);
+            let level = if lamp_burnt_out(light) {
+                0.0
+            } else {
+                new_level
+            };
+            set_var(light, level);
         }
     }
 }
@@ -130,115 +141,8 @@ impl LightBulb {
 
 //=========================================================================
 
-/// A blink relay that provides timed on/off cycles with state change detection.
-///
-/// `BlinkRelais` creates a periodic blinking pattern with configurable intervals
-/// and provides feedback when the state changes, making it useful for triggering
-/// events at specific blink transitions.
-#[derive(Debug, Default)]
-pub struct BlinkRelais {
-    /// Time interval for one complete blink cycle
-    interval: f32,
-    /// Duration for which the relay stays "on" during each cycle
-    on_time: f32,
-    /// Current timer value
-    timer: f32,
-    /// Current state of the relay
-    pub is_on: bool,
-    /// Timer value to set when reset() is called
-    reset_time: f32,
-}
-
-impl BlinkRelais {
-    /// Creates a new `BlinkRelais` instance.
-    ///
-    /// # Arguments
-    ///
-    /// * `interval` - Total duration of one blink cycle
-    /// * `on_time` - Duration for which the relay stays "on" (must be ≤ interval)
-    /// * `reset_time` - Timer value to use when reset() is called
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use pandemist_vehicle_elements::BlinkRelais;
-    ///
-    /// // Blink every 2 seconds, stay on for 0.5 seconds
-    /// let mut relay = BlinkRelais::new(2.0, 0.5, 0.0);
-    /// ```
-    pub fn new(interval: f32, on_time: f32, reset_time: f32) -> Self {
-        Self {
-            interval,
-            on_time,
-            timer: 0.0,
-            is_on: false,
-            reset_time,
-        }
-    }
-
-    /// Updates the blink relay and returns state change information.
-    ///
-    /// This method should be called regularly to update the relay's state.
-    /// It returns information about state changes that occurred during this tick.
-    ///
-    /// # Returns
-    ///
-    /// * `1` - Relay just turned on
-    /// * `-1` - Relay just turned off  
-    /// * `0` - No state change occurred
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use pandemist_vehicle_elements::BlinkRelais;
-    ///
-    /// let mut relay = BlinkRelais::new(1.0, 0.3, 0.0);
-    ///
-    /// // In your update loop:
-    /// match relay.tick() {
-    ///     1 => println!("Relay turned ON"),
-    ///     -1 => println!("Relay turned OFF"),
-    ///     _ => {} // No change
-    /// }
-    /// ```
-    pub fn tick(&mut self) -> i32 {
-        self.timer += delta();
-        if self.timer > self.interval {
-            self.timer -= self.interval;
-        }
-
-        let new_on = self.timer < self.on_time;
-
-        let result = if new_on && !self.is_on {
-            1
-        } else if !new_on && self.is_on {
-            -1
-        } else {
-            0
-        };
-
-        self.is_on = new_on;
-        result
-    }
-
-    /// Resets the relay to its initial state.
-    ///
-    /// Sets the timer to the configured reset time and turns the relay off.
-    /// This can be used to synchronize the relay or restart its cycle.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use pandemist_vehicle_elements::BlinkRelais;
-    ///
-    /// let mut relay = BlinkRelais::new(2.0, 0.5, 1.0);
-    /// relay.reset(); // Timer set to 1.0, relay turned off
-    /// ```
-    pub fn reset(&mut self) {
-        self.timer = self.reset_time;
-        self.is_on = false;
-    }
-}
+/// Former name of the [`FlasherRelay`], kept for existing vehicles.
+pub type BlinkRelais = FlasherRelay;
 
 //=========================================================================
 
@@ -0,0 +1,278 @@
+//! Abstraction of the simulator outputs for unit tests.
+//!
+//! The traits in this module describe what a component needs from the
+//! simulator: animations, sounds, lights, script variables and messages. The
+//! existing API types ([`Animation`], [`Sound`], [`Light`]) implement them as
+//! passthrough to the simulator, the `Mock*` types are test doubles which only
+//! store what was written, so the result of a component can be checked in a
+//! test without the simulator runtime.
+//!
+//! Components which take their outputs as type parameters, like the doors
+//! and the compressor, are created on the test doubles with their
+//! `builder_with_outputs` constructor.
+//!
+//! # Examples
+//!
+//! ```rust
+//! fn update_lamp(lamp: &mut impl LightOutput, on: bool) {
+//!     lamp.set_brightness(on as u8 as f32);
+//! }
+//!
+//! // In the vehicle script
+//! update_lamp(&mut Light::new(Some("L_Lamp")), true);
+//!
+//! // In a test
+//! let mut lamp = MockLight::default();
+//! update_lamp(&mut lamp, true);
+//! assert_eq!(lamp.brightness, 1.0);
+//! ```
+
+use std::collections::HashMap;
+
+use lotus_script::prelude::{send_message, Message, MessageTarget, MessageType};
+
+use crate::api::{
+    animation::Animation,
+    light::Light,
+    sound::Sound,
+    variable::{get_var, set_var},
+};
+
+/// Output to an animation.
+pub trait AnimationOutput {
+    /// Sets the animation position.
+    fn set(&mut self, pos: f32);
+}
+
+/// Output to a sound.
+pub trait SoundOutput {
+    /// Starts the sound.
+    fn start(&mut self);
+    /// Stops the sound.
+    fn stop(&mut self);
+    /// Sets the volume of the sound.
+    fn update_volume(&mut self, value: f32);
+    /// Sets the pitch of the sound.
+    fn update_pitch(&mut self, value: f32);
+
+    /// Starts or stops the sound.
+    fn start_stop(&mut self, value: bool) {
+        if value {
+            self.start();
+        } else {
+            self.stop();
+        }
+    }
+}
+
+/// Output to a light.
+pub trait LightOutput {
+    /// Sets the brightness of the light.
+    fn set_brightness(&mut self, value: f32);
+}
+
+/// Access to script variables.
+pub trait VariableStore {
+    fn get_bool(&self, name: &str) -> bool;
+    fn set_bool(&mut self, name: &str, value: bool);
+    fn get_i32(&self, name: &str) -> i32;
+    fn set_i32(&mut self, name: &str, value: i32);
+    fn get_f32(&self, name: &str) -> f32;
+    fn set_f32(&mut self, name: &str, value: f32);
+}
+
+/// Sending of messages.
+pub trait MessageSender {
+    /// Sends the message to the given targets.
+    fn send<T: MessageType>(&mut self, message: &T, targets: Vec<MessageTarget>);
+}
+
+//=========================================================================
+// Passthrough to the simulator
+//=========================================================================
+
+impl AnimationOutput for Animation {
+    fn set(&mut self, pos: f32) {
+        Animation::set(self, pos);
+    }
+}
+
+impl SoundOutput for Sound {
+    fn start(&mut self) {
+        Sound::start(self);
+    }
+
+    fn stop(&mut self) {
+        Sound::stop(self);
+    }
+
+    fn update_volume(&mut self, value: f32) {
+        Sound::update_volume(self, value);
+    }
+
+    fn update_pitch(&mut self, value: f32) {
+        Sound::update_pitch(self, value);
+    }
+
+    fn start_stop(&mut self, value: bool) {
+        Sound::start_stop(self, value);
+    }
+}
+
+impl LightOutput for Light {
+    fn set_brightness(&mut self, value: f32) {
+        Light::set_brightness(self, value);
+    }
+}
+
+/// Script variables of the simulator.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LotusVariables;
+
+impl VariableStore for LotusVariables {
+    fn get_bool(&self, name: &str) -> bool {
+        get_var::<bool>(name)
+    }
+
+    fn set_bool(&mut self, name: &str, value: bool) {
+        set_var(name, value);
+    }
+
+    fn get_i32(&self, name: &str) -> i32 {
+        get_var::<i32>(name)
+    }
+
+    fn set_i32(&mut self, name: &str, value: i32) {
+        set_var(name, value);
+    }
+
+    fn get_f32(&self, name: &str) -> f32 {
+        get_var::<f32>(name)
+    }
+
+    fn set_f32(&mut self, name: &str, value: f32) {
+        set_var(name, value);
+    }
+}
+
+/// Message sending of the simulator.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LotusMessages;
+
+impl MessageSender for LotusMessages {
+    fn send<T: MessageType>(&mut self, message: &T, targets: Vec<MessageTarget>) {
+        send_message(message, targets);
+    }
+}
+
+//=========================================================================
+// Test doubles
+//=========================================================================
+
+/// Animation storing the last position.
+#[derive(Debug, Default, Clone)]
+pub struct MockAnimation {
+    pub pos: f32,
+}
+
+impl AnimationOutput for MockAnimation {
+    fn set(&mut self, pos: f32) {
+        self.pos = pos;
+    }
+}
+
+/// Sound storing its state and counting the starts.
+#[derive(Debug, Default, Clone)]
+pub struct MockSound {
+    pub playing: bool,
+    pub start_count: u32,
+    pub volume: f32,
+    pub pitch: f32,
+}
+
+impl SoundOutput for MockSound {
+    fn start(&mut self) {
+        self.playing = true;
+        self.start_count += 1;
+    }
+
+    fn stop(&mut self) {
+        self.playing = false;
+    }
+
+    fn update_volume(&mut self, value: f32) {
+        self.volume = value;
+    }
+
+    fn update_pitch(&mut self, value: f32) {
+        self.pitch = value;
+    }
+}
+
+/// Light storing the last brightness.
+#[derive(Debug, Default, Clone)]
+pub struct MockLight {
+    pub brightness: f32,
+}
+
+impl LightOutput for MockLight {
+    fn set_brightness(&mut self, value: f32) {
+        self.brightness = value;
+    }
+}
+
+/// Script variables held in memory, unset variables read as default.
+#[derive(Debug, Default, Clone)]
+pub struct MockVariables {
+    pub bools: HashMap<String, bool>,
+    pub ints: HashMap<String, i32>,
+    pub floats: HashMap<String, f32>,
+}
+
+impl VariableStore for MockVariables {
+    fn get_bool(&self, name: &str) -> bool {
+        self.bools.get(name).copied().unwrap_or_default()
+    }
+
+    fn set_bool(&mut self, name: &str, value: bool) {
+        self.bools.insert(name.into(), value);
+    }
+
+    fn get_i32(&self, name: &str) -> i32 {
+        self.ints.get(name).copied().unwrap_or_default()
+    }
+
+    fn set_i32(&mut self, name: &str, value: i32) {
+        self.ints.insert(name.into(), value);
+    }
+
+    fn get_f32(&self, name: &str) -> f32 {
+        self.floats.get(name).copied().unwrap_or_default()
+    }
+
+    fn set_f32(&mut self, name: &str, value: f32) {
+        self.floats.insert(name.into(), value);
+    }
+}
+
+/// Collects the sent messages instead of sending them.
+#[derive(Debug, Default, Clone)]
+pub struct MockMessages {
+    pub sent: Vec<(Message, Vec<MessageTarget>)>,
+}
+
+impl MockMessages {
+    /// Returns the sent messages of the given type.
+    pub fn of_type<T: MessageType>(&self) -> Vec<T> {
+        self.sent
+            .iter()
+            .filter_map(|(msg, _)| msg.value::<T>().ok())
+            .collect()
+    }
+}
+
+impl MessageSender for MockMessages {
+    fn send<T: MessageType>(&mut self, message: &T, targets: Vec<MessageTarget>) {
+        self.sent.push((Message::new(message), targets));
+    }
+}
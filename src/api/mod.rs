@@ -6,9 +6,14 @@ pub mod electrical_supply;
 pub mod general;
 pub mod key_event;
 pub mod light;
+pub mod mock;
 pub mod mock_enums;
+pub mod random;
+pub mod replay;
 pub mod simulation_settings;
 pub mod sound;
+pub mod texture;
+pub mod trainer;
 pub mod variable;
 pub mod vehicle_door;
 pub mod vehicle_infos;
@@ -0,0 +1,66 @@
+//! Random numbers for the components.
+//!
+//! The components take their random numbers from here instead of calling the
+//! simulator directly. Test builds run without the simulator and get a fixed,
+//! repeatable sequence per test thread.
+
+use std::ops::{Bound, RangeBounds};
+
+/// Generates a random f32 value within the specified range.
+///
+/// The bounds are taken as limits of the value, not as integer steps:
+/// `gen_f32(0.0..1.0)` covers the whole interval from 0.0 to 1.0.
+///
+/// # Arguments
+///
+/// * `range` - A range specification (e.g., `0.0..1.0`, `-5.0..=5.0`)
+///
+/// # Returns
+///
+/// A random f32 value within the specified range
+///
+/// # Examples
+///
+/// ```rust
+/// let value = gen_f32(0.0..1.0);
+/// assert!((0.0..=1.0).contains(&value));
+/// ```
+#[must_use]
+pub fn gen_f32(range: impl RangeBounds<f32>) -> f32 {
+    let min = match range.start_bound() {
+        Bound::Included(min) | Bound::Excluded(min) => *min,
+        Bound::Unbounded => 0.0,
+    };
+
+    let max = match range.end_bound() {
+        Bound::Included(max) | Bound::Excluded(max) => *max,
+        Bound::Unbounded => f32::MAX,
+    };
+
+    min + (unit() as f32) * (max - min)
+}
+
+/// Random value between 0.0 and 1.0 from the simulator.
+#[cfg(not(test))]
+fn unit() -> f64 {
+    lotus_script::rand::gen_f64()
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_SEED: std::cell::Cell<u64> = const { std::cell::Cell::new(0x2545_f491_4f6c_dd1d) };
+}
+
+/// Test builds run without the simulator, the values come from a xorshift
+/// generator with a fixed seed.
+#[cfg(test)]
+fn unit() -> f64 {
+    TEST_SEED.with(|seed| {
+        let mut x = seed.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        seed.set(x);
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
@@ -0,0 +1,277 @@
+//! Recording and replay of key events, messages and frame times.
+//!
+//! This module allows to record the input of a vehicle script frame by frame
+//! and to play it back later against the components. While a replay is
+//! running, [`KeyEvent`](crate::api::key_event::KeyEvent) and
+//! [`delta`](crate::api::general::delta) take their values from the recording
+//! instead of the simulator, so door sequences, brake logic or the propagation
+//! over the coupling lines can be reproduced exactly.
+//!
+//! # Examples
+//!
+//! Recording in the vehicle script:
+//!
+//! ```rust
+//! let mut recorder = Recorder::new(vec!["Door_Open", "Door_Close"]);
+//!
+//! // In on_message:
+//! recorder.record_message(&msg);
+//!
+//! // At the end of the main loop:
+//! recorder.record_frame();
+//!
+//! // When done:
+//! let json = recorder.recording().to_json();
+//! ```
+//!
+//! Replaying the recording:
+//!
+//! ```rust
+//! let mut replayer = Replayer::new(Recording::from_json(&json).unwrap());
+//!
+//! while let Some(messages) = replayer.next_frame() {
+//!     for msg in messages {
+//!         doors.on_message(msg);
+//!     }
+//!     doors.tick();
+//! }
+//! ```
+
+use std::{cell::RefCell, collections::HashMap};
+
+use lotus_script::{action::state, input::ActionState, prelude::Message, time};
+use serde::{Deserialize, Serialize};
+
+/// Input of a single frame.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Frame {
+    /// Frame time in seconds
+    pub delta: f32,
+    /// States of the watched key events which were not idle
+    pub actions: HashMap<String, ActionState>,
+    /// Messages received during the frame
+    pub messages: Vec<Message>,
+}
+
+/// Recorded sequence of frames.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Recording {
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    /// Serializes the recording to JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Recording: serialization failed")
+    }
+
+    /// Reads a recording from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parser error if the string is no valid recording.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+//=========================================================================
+
+/// Records the input of the vehicle script.
+pub struct Recorder {
+    events: Vec<String>,
+    current: Frame,
+    recording: Recording,
+}
+
+impl Recorder {
+    /// Creates a recorder watching the given key events.
+    pub fn new(events: Vec<&str>) -> Self {
+        Self {
+            events: events.into_iter().map(Into::into).collect(),
+            current: Frame::default(),
+            recording: Recording::default(),
+        }
+    }
+
+    /// Adds a message received in the current frame.
+    pub fn record_message(&mut self, msg: &Message) {
+        self.current.messages.push(msg.clone());
+    }
+
+    /// Completes the current frame with the frame time and the key event states.
+    pub fn record_frame(&mut self) {
+        self.current.delta = time::delta();
+
+        for ev in &self.events {
+            let action = state(ev);
+            if !action.kind.is_released() || action.kind.is_just_released() {
+                self.current.actions.insert(ev.clone(), action);
+            }
+        }
+
+        self.recording
+            .frames
+            .push(std::mem::take(&mut self.current));
+    }
+
+    /// Returns the frames recorded so far.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+}
+
+//=========================================================================
+
+thread_local! {
+    static REPLAY_FRAME: RefCell<Option<Frame>> = const { RefCell::new(None) };
+}
+
+/// Returns the frame time of the running replay.
+pub(crate) fn replayed_delta() -> Option<f32> {
+    REPLAY_FRAME.with(|frame| frame.borrow().as_ref().map(|f| f.delta))
+}
+
+/// Returns the state of a key event in the running replay, idle if it was
+/// not recorded in the current frame.
+pub(crate) fn replayed_action(ev: &str) -> Option<ActionState> {
+    REPLAY_FRAME.with(|frame| {
+        frame.borrow().as_ref().map(|f| {
+            f.actions.get(ev).copied().unwrap_or(ActionState {
+                kind: lotus_script::input::ActionStateKind::None,
+                cockpit_index: None,
+                uv: None,
+            })
+        })
+    })
+}
+
+/// Plays back a [`Recording`] frame by frame.
+///
+/// The replay ends when all frames are played or the replayer is dropped.
+pub struct Replayer {
+    frames: std::vec::IntoIter<Frame>,
+}
+
+impl Replayer {
+    /// Creates a replayer for the recording.
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            frames: recording.frames.into_iter(),
+        }
+    }
+
+    /// Activates the next frame.
+    ///
+    /// # Returns
+    ///
+    /// The messages of the frame, which have to be passed to the components, or
+    /// `None` if the recording is finished.
+    pub fn next_frame(&mut self) -> Option<Vec<Message>> {
+        let Some(mut frame) = self.frames.next() else {
+            self.stop();
+            return None;
+        };
+        let messages = std::mem::take(&mut frame.messages);
+        REPLAY_FRAME.with(|current| *current.borrow_mut() = Some(frame));
+        Some(messages)
+    }
+
+    /// Ends the replay, the input is taken from the simulator again.
+    pub fn stop(&mut self) {
+        REPLAY_FRAME.with(|current| *current.borrow_mut() = None);
+    }
+}
+
+impl Drop for Replayer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Calls `tick` for the given number of frames with a fixed frame time, for
+/// tests of components which integrate over [`delta`](crate::api::general::delta).
+#[cfg(test)]
+pub(crate) fn run_frames(count: usize, frame_time: f32, mut tick: impl FnMut()) {
+    let mut replayer = Replayer::new(Recording {
+        frames: vec![
+            Frame {
+                delta: frame_time,
+                ..Default::default()
+            };
+            count
+        ],
+    });
+    while replayer.next_frame().is_some() {
+        tick();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{general::delta, key_event::KeyEvent},
+        messages::std_coupling_messages::StdEmergencyBrake,
+    };
+
+    // Aufgezeichnet: Taste gedrückt, gehalten, losgelassen, Notbremse über die Kupplung
+    const RECORDING: &str = r#"{"frames":[
+        {"delta":0.02,"actions":{"Door_Open":{"kind":1}},"messages":[]},
+        {"delta":0.03,"actions":{"Door_Open":{"kind":2}},"messages":[]},
+        {"delta":0.05,"actions":{"Door_Open":{"kind":3}},"messages":[
+            {"meta":{"namespace":"Std_Coupler","identifier":"EmergencyBrake","bus":null},
+             "source":{"coupling":null,"module_slot_index":null,"module_slot_cockpit_index":null},
+             "value":{"active":true}}]}
+    ]}"#;
+
+    #[test]
+    fn test_replay_recording() {
+        let mut replayer = Replayer::new(Recording::from_json(RECORDING).unwrap());
+        let mut key = KeyEvent::new(Some("Door_Open"), None);
+
+        let mut time = 0.0;
+        let mut pressed = Vec::new();
+        let mut brake = false;
+
+        while let Some(messages) = replayer.next_frame() {
+            for msg in messages {
+                msg.handle::<StdEmergencyBrake>(|m| {
+                    brake = m.active;
+                    Ok(())
+                })
+                .expect("StdEmergencyBrake: message handle failed");
+            }
+            time += delta();
+            pressed.push((
+                key.is_just_pressed(),
+                key.is_pressed(),
+                key.is_just_released(),
+            ));
+        }
+
+        assert!((time - 0.1).abs() < 1e-6);
+        assert_eq!(
+            pressed,
+            vec![
+                (true, true, false),
+                (false, true, false),
+                (false, false, true)
+            ]
+        );
+        assert!(brake);
+
+        // Nach dem Ende der Aufzeichnung kommt die Zeit wieder vom Simulator
+        assert_eq!(replayed_delta(), None);
+    }
+
+    #[test]
+    fn test_recording_roundtrip() {
+        let recording = Recording::from_json(RECORDING).unwrap();
+        let copy = Recording::from_json(&recording.to_json()).unwrap();
+        assert_eq!(copy.frames.len(), 3);
+        assert_eq!(copy.frames[2].messages.len(), 1);
+        assert!(copy.frames[2].messages[0].has_type::<StdEmergencyBrake>());
+    }
+}
@@ -4,9 +4,10 @@
 //! the initial state and behavior of the train simulation. All settings are retrieved
 //! from the underlying script system using the lotus_script variable system.
 
-use lotus_script::var::get_var;
-
-use super::mock_enums::{PlayerInitPos, VehicleInitState};
+use super::{
+    mock_enums::{PlayerInitPos, VehicleInitState},
+    variable::get_var,
+};
 
 /// Determines if realistic electric supply simulation is enabled.
 ///
@@ -8,8 +8,10 @@
 //! - Basic sound start/stop control
 //! - Volume and pitch manipulation
 //! - Gradual volume transitions
+//! - Volume and pitch ramps with attack and release rates
 //! - Sound sequences with end sounds
 //! - Complex sound chains with start, loop, and end sounds
+//! - Pools of alternative samples with volume and pitch jitter
 //!
 //! # Examples
 //!
@@ -26,9 +28,12 @@
 //! vol_sound.tick(true); // Gradually increase volume (till 1.0)
 //! ```
 
-use lotus_script::{time::delta, var::set_var};
-
-use crate::api::variable::get_var;
+use crate::api::{
+    general::delta,
+    mock::SoundOutput,
+    random::gen_f32,
+    variable::{get_var, set_var},
+};
 
 /// Represents the target state for sound playback.
 ///
@@ -325,6 +330,198 @@ impl Sound {
             set_var(snd, value);
         }
     }
+
+    /// Reads the sound's pitch, 1.0 for Sounds without pitch control
+    /// variable.
+    pub fn get_pitch(&self) -> f32 {
+        if let Some(snd) = &self.name_pitch {
+            get_var::<f32>(snd)
+        } else {
+            1.0
+        }
+    }
+}
+
+//=========================================================================
+
+/// Order in which a [`SoundPool`] plays its samples.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PoolOrder {
+    /// Random sample, never the same one twice in a row (default)
+    #[default]
+    Random,
+    /// One sample after the other
+    RoundRobin,
+}
+
+/// A set of alternative samples for the same action.
+///
+/// Each start plays one of the samples, so repeated actions like button
+/// presses or door slams do not sound identical. Samples with volume or pitch
+/// variables additionally get a random deviation on each start, see
+/// [`SoundPool::with_jitter`]. Pools created with [`SoundPool::new_simple`]
+/// have no such variables, there the jitter has no effect.
+///
+/// An empty pool behaves like a sound without variable and does nothing.
+///
+/// # Examples
+///
+/// ```rust
+/// use sound::{PoolOrder, Sound, SoundPool};
+///
+/// let mut click = SoundPool::new_simple(&["click_1", "click_2", "click_3"], PoolOrder::Random);
+///
+/// let mut slam = SoundPool::new_with_vars(
+///     &[
+///         ("slam_1", "slam_1_vol", "slam_1_pitch"),
+///         ("slam_2", "slam_2_vol", "slam_2_pitch"),
+///     ],
+///     PoolOrder::RoundRobin,
+/// )
+/// .with_jitter(0.15, 0.05);
+///
+/// click.start();
+/// slam.start();
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct SoundPool<S = Sound> {
+    /// The alternative samples
+    samples: Vec<S>,
+    /// Order in which the samples are played
+    order: PoolOrder,
+    /// Maximum reduction of the volume per start
+    volume_jitter: f32,
+    /// Maximum deviation of the pitch per start
+    pitch_jitter: f32,
+    /// Index of the sample started last
+    last: Option<usize>,
+}
+
+impl SoundPool {
+    /// Creates a new SoundPool of samples with only playback control.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - Variable names of the samples
+    /// * `order` - Order in which the samples are played
+    pub fn new_simple(names: &[&str], order: PoolOrder) -> Self {
+        Self::new(
+            names
+                .iter()
+                .map(|name| Sound::new_simple(Some(name)))
+                .collect(),
+            order,
+        )
+    }
+
+    /// Creates a new SoundPool of samples with volume and pitch variables,
+    /// as needed for the jitter.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Variable names of each sample as (sound, volume, pitch)
+    /// * `order` - Order in which the samples are played
+    pub fn new_with_vars(samples: &[(&str, &str, &str)], order: PoolOrder) -> Self {
+        Self::new(
+            samples
+                .iter()
+                .map(|(name, vol, pitch)| Sound::new(Some(name), Some(vol), Some(pitch)))
+                .collect(),
+            order,
+        )
+    }
+}
+
+impl<S: SoundOutput> SoundPool<S> {
+    /// Creates a new SoundPool from sounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The alternative samples
+    /// * `order` - Order in which the samples are played
+    pub fn new(samples: Vec<S>, order: PoolOrder) -> Self {
+        Self {
+            samples,
+            order,
+            volume_jitter: 0.0,
+            pitch_jitter: 0.0,
+            last: None,
+        }
+    }
+
+    /// Sets the random deviation applied on each start.
+    ///
+    /// Only samples with volume and pitch variables are affected, e.g. from
+    /// [`SoundPool::new_with_vars`].
+    ///
+    /// # Arguments
+    ///
+    /// * `volume` - Maximum reduction of the volume, e.g. 0.1 for down to 0.9
+    /// * `pitch` - Maximum deviation of the pitch, e.g. 0.05 for 0.95 to 1.05
+    pub fn with_jitter(mut self, volume: f32, pitch: f32) -> Self {
+        self.volume_jitter = volume;
+        self.pitch_jitter = pitch;
+        self
+    }
+
+    /// Adds a sample to the pool.
+    pub fn push(&mut self, sound: S) {
+        self.samples.push(sound);
+    }
+
+    /// Number of samples in the pool.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether the pool has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Starts the next sample according to the order of the pool.
+    pub fn start(&mut self) {
+        let count = self.samples.len();
+        if count == 0 {
+            return;
+        }
+
+        let index = match (self.order, self.last) {
+            (PoolOrder::RoundRobin, Some(last)) => (last + 1) % count,
+            (PoolOrder::RoundRobin, None) => 0,
+            (PoolOrder::Random, Some(last)) if count > 1 => {
+                // Zufällig unter den übrigen Samples, damit keines direkt wiederholt wird
+                let offset = ((gen_f32(0.0..1.0) * (count - 1) as f32) as usize).min(count - 2);
+                (last + 1 + offset) % count
+            }
+            (PoolOrder::Random, _) => ((gen_f32(0.0..1.0) * count as f32) as usize).min(count - 1),
+        };
+        self.start_sample(index);
+    }
+
+    /// Starts the sample with the given index, e.g. chosen by the caller.
+    /// Indices outside the pool are ignored.
+    pub fn start_sample(&mut self, index: usize) {
+        let Some(sample) = self.samples.get_mut(index) else {
+            return;
+        };
+
+        if self.volume_jitter > 0.0 {
+            sample.update_volume(1.0 - gen_f32(0.0..=self.volume_jitter));
+        }
+        if self.pitch_jitter > 0.0 {
+            sample.update_pitch(1.0 + gen_f32(-self.pitch_jitter..=self.pitch_jitter));
+        }
+        sample.start();
+        self.last = Some(index);
+    }
+
+    /// Stops all samples of the pool.
+    pub fn stop(&mut self) {
+        for sample in &mut self.samples {
+            sample.stop();
+        }
+    }
 }
 
 //=========================================================================
@@ -417,6 +614,204 @@ impl SoundWithVol {
 
 //=========================================================================
 
+/// A value following a target with separate rates for rising and falling.
+///
+/// Used as attack/release envelope for sound parameters, so values like a
+/// motor speed do not make the volume or pitch jump between frames.
+///
+/// # Examples
+///
+/// ```rust
+/// use sound::ParamRamp;
+///
+/// // Rises within 0.5 s, falls within 2 s from 0.0 to 1.0
+/// let mut ramp = ParamRamp::new(2.0, 0.5);
+///
+/// // In your game loop:
+/// let volume = ramp.tick(motor_speed);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParamRamp {
+    /// Rise per second, infinite for an immediate jump
+    pub attack: f32,
+    /// Fall per second, infinite for an immediate jump
+    pub release: f32,
+    /// Current value
+    pub value: f32,
+}
+
+impl Default for ParamRamp {
+    fn default() -> Self {
+        Self::new(f32::INFINITY, f32::INFINITY)
+    }
+}
+
+impl ParamRamp {
+    /// Creates a new ParamRamp starting at 0.0.
+    ///
+    /// # Arguments
+    ///
+    /// * `attack` - Rise per second
+    /// * `release` - Fall per second
+    pub fn new(attack: f32, release: f32) -> Self {
+        Self {
+            attack,
+            release,
+            value: 0.0,
+        }
+    }
+
+    /// Moves the value towards the target and returns it.
+    pub fn tick(&mut self, target: f32) -> f32 {
+        let rate = if target > self.value {
+            self.attack
+        } else {
+            self.release
+        };
+
+        self.value = if rate.is_infinite() {
+            target
+        } else if target > self.value {
+            (self.value + rate * delta()).min(target)
+        } else {
+            (self.value - rate * delta()).max(target)
+        };
+        self.value
+    }
+}
+
+//=========================================================================
+
+/// A looping sound whose volume and pitch follow ramped targets.
+///
+/// The sound is started as soon as the ramped volume rises above zero and
+/// stopped once it has fallen back to zero, so the release of the volume
+/// lets the sound fade out instead of cutting it off.
+///
+/// # Examples
+///
+/// ```rust
+/// use sound::SoundWithRamp;
+///
+/// let mut fan = SoundWithRamp::new(Some("fan"), Some("fan_vol"), Some("fan_pitch"))
+///     .volume_ramp(1.0, 0.3)
+///     .pitch_ramp(0.5, 0.2);
+///
+/// // In your game loop:
+/// fan.tick(fan_on as u8 as f32, 0.6 + 0.4 * fan_speed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoundWithRamp<S = Sound> {
+    /// The underlying sound controller
+    snd: S,
+    /// Envelope of the volume
+    volume: ParamRamp,
+    /// Envelope of the pitch
+    pitch: ParamRamp,
+    /// Whether the sound is playing
+    running: bool,
+}
+
+impl Default for SoundWithRamp {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
+
+impl SoundWithRamp {
+    /// Creates a new SoundWithRamp, without ramps the parameters jump to
+    /// their targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `name_sound` - Optional variable name for sound playback control
+    /// * `name_volume` - Optional variable name for volume control
+    /// * `name_pitch` - Optional variable name for pitch control
+    pub fn new(
+        name_sound: Option<&str>,
+        name_volume: Option<&str>,
+        name_pitch: Option<&str>,
+    ) -> Self {
+        Self::with_output(Sound::new(name_sound, name_volume, name_pitch))
+    }
+}
+
+impl<S: SoundOutput> SoundWithRamp<S> {
+    /// Creates a new SoundWithRamp on any sound output, e.g. a
+    /// [`MockSound`](crate::api::mock::MockSound) in tests.
+    pub fn with_output(snd: S) -> Self {
+        Self {
+            snd,
+            volume: ParamRamp::default(),
+            pitch: ParamRamp {
+                value: 1.0,
+                ..Default::default()
+            },
+            running: false,
+        }
+    }
+
+    /// Sets the attack and release rates of the volume per second.
+    pub fn volume_ramp(mut self, attack: f32, release: f32) -> Self {
+        self.volume.attack = attack;
+        self.volume.release = release;
+        self
+    }
+
+    /// Sets the attack and release rates of the pitch per second.
+    pub fn pitch_ramp(mut self, attack: f32, release: f32) -> Self {
+        self.pitch.attack = attack;
+        self.pitch.release = release;
+        self
+    }
+
+    /// Current ramped volume.
+    pub fn volume(&self) -> f32 {
+        self.volume.value
+    }
+
+    /// Current ramped pitch.
+    pub fn pitch(&self) -> f32 {
+        self.pitch.value
+    }
+
+    /// Whether the sound is playing.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Moves volume and pitch towards their targets and starts or stops the
+    /// sound.
+    ///
+    /// # Arguments
+    ///
+    /// * `volume` - Target volume, 0.0 lets the sound fade out and stop
+    /// * `pitch` - Target pitch multiplier
+    pub fn tick(&mut self, volume: f32, pitch: f32) {
+        let volume = self.volume.tick(volume.max(0.0));
+        let pitch = self.pitch.tick(pitch);
+
+        self.snd.update_volume(volume);
+        self.snd.update_pitch(pitch);
+
+        let running = volume > 0.0;
+        if running != self.running {
+            self.snd.start_stop(running);
+            self.running = running;
+        }
+    }
+
+    /// Stops the sound at once without release.
+    pub fn stop(&mut self) {
+        self.volume.value = 0.0;
+        self.snd.update_volume(0.0);
+        self.snd.stop();
+        self.running = false;
+    }
+}
+
+//=========================================================================
+
 /// A sound controller that plays an end sound when the main sound stops.
 ///
 /// This struct manages two sounds: a main sound and an end sound. When the
@@ -596,3 +991,72 @@ impl SoundWithStartAndEnd {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{mock::MockSound, replay::run_frames};
+
+    /// Starts the pool several times and returns the indices of the started samples
+    fn started(pool: &mut SoundPool<MockSound>, starts: usize) -> Vec<usize> {
+        (0..starts)
+            .map(|_| {
+                let counts: Vec<u32> = pool.samples.iter().map(|s| s.start_count).collect();
+                pool.start();
+                pool.samples
+                    .iter()
+                    .zip(counts)
+                    .position(|(s, count)| s.start_count > count)
+                    .expect("no sample started")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pool_round_robin() {
+        let mut pool = SoundPool::new(vec![MockSound::default(); 3], PoolOrder::RoundRobin);
+        assert_eq!(started(&mut pool, 7), vec![0, 1, 2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_pool_random_without_repeats() {
+        let mut pool =
+            SoundPool::new(vec![MockSound::default(); 3], PoolOrder::Random).with_jitter(0.2, 0.1);
+        let indices = started(&mut pool, 30);
+
+        assert!(indices.iter().all(|&i| i < 3));
+        assert!(indices.windows(2).all(|w| w[0] != w[1]));
+        // Kein fester Umlauf: auf ein Sample folgt mal das eine, mal das andere
+        assert!(indices.windows(3).any(|w| w[0] == w[2]));
+        assert!(indices.windows(3).any(|w| w[0] != w[2]));
+        for sample in &pool.samples {
+            assert!((0.8..=1.0).contains(&sample.volume));
+            assert!((0.9..=1.1).contains(&sample.pitch));
+        }
+    }
+
+    #[test]
+    fn test_param_ramp() {
+        let mut ramp = ParamRamp::new(2.0, 0.5);
+
+        // Anstieg in 0,5 s auf 1,0
+        run_frames(4, 0.1, || {
+            ramp.tick(1.0);
+        });
+        assert!((ramp.value - 0.8).abs() < 1e-4);
+        run_frames(2, 0.1, || {
+            ramp.tick(1.0);
+        });
+        assert_eq!(ramp.value, 1.0);
+
+        // Abfall langsamer
+        run_frames(10, 0.1, || {
+            ramp.tick(0.0);
+        });
+        assert!((ramp.value - 0.5).abs() < 1e-4);
+
+        let mut jump = ParamRamp::default();
+        assert_eq!(jump.tick(0.7), 0.7);
+        assert_eq!(jump.tick(0.2), 0.2);
+    }
+}
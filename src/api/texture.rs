@@ -0,0 +1,72 @@
+//! Script textures for displays drawn by the script.
+//!
+//! The [`ScriptTexture`] creates a texture at runtime and applies it to a
+//! texture slot of the model, e.g. for dot-matrix displays or screens.
+
+use lotus_script::graphics::{
+    textures::{DrawPixel, Texture, TextureAction},
+    Color,
+};
+
+/// Texture drawn by the script and applied to a texture slot.
+pub struct ScriptTexture {
+    /// The texture, `None` if no slot was given and drawing is ignored
+    texture: Option<Texture>,
+    width: u32,
+    height: u32,
+}
+
+impl ScriptTexture {
+    /// Creates a new script texture.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Optional name of the texture slot. If `None`, drawing operations will be ignored.
+    /// * `width` - Width in pixels
+    /// * `height` - Height in pixels
+    pub fn new(name: Option<&str>, width: u32, height: u32) -> Self {
+        let texture = name.map(|name| {
+            let mut texture = Texture::create((width, height));
+            texture.apply_to(name);
+            texture
+        });
+
+        Self {
+            texture,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Fills the whole texture with a color.
+    pub fn clear(&mut self, color: Color) {
+        if let Some(texture) = &mut self.texture {
+            texture.add_action(TextureAction::Clear(color));
+        }
+    }
+
+    /// Draws single pixels given as `(x, y, color)`.
+    pub fn draw_pixels(&mut self, pixels: impl IntoIterator<Item = (u32, u32, Color)>) {
+        if let Some(texture) = &mut self.texture {
+            let pixels: Box<[DrawPixel]> = pixels.into_iter().map(DrawPixel::from).collect();
+            if !pixels.is_empty() {
+                texture.add_action(TextureAction::DrawPixels(pixels));
+            }
+        }
+    }
+
+    /// Executes the drawing operations of this frame.
+    pub fn flush(&mut self) {
+        if let Some(texture) = &mut self.texture {
+            texture.flush();
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Trainer mode for driving school and scenarios.
+//!
+//! An instructor or a scenario can block or force single inputs of the vehicle
+//! with the [`TrainerCommand`] message. While an input is blocked,
+//! [`KeyEvent`](crate::api::key_event::KeyEvent) reports it as released no
+//! matter what the driver does; while it is forced, it is reported as pressed.
+//! Because every element of this crate reads its input through `KeyEvent`,
+//! e.g. the throttle lever can be blocked without any change to the element.
+//!
+//! Besides key events, components can query named flags with [`is_forced`]
+//! and [`is_blocked`], e.g. to force a door fault with the flag `"Door_1_Fault"`.
+//!
+//! # Examples
+//!
+//! In the vehicle script:
+//!
+//! ```rust
+//! let mut trainer = TrainerMode::new();
+//!
+//! // In on_message:
+//! trainer.on_message(msg);
+//!
+//! // At the end of the main loop:
+//! trainer.tick();
+//! ```
+//!
+//! Blocking the throttle from a scenario or an instructor script:
+//!
+//! ```rust
+//! send_message(
+//!     &TrainerCommand {
+//!         input: "Throttle_Plus".to_string(),
+//!         action: TrainerAction::Block,
+//!         cab: None,
+//!     },
+//!     [MessageTarget::Broadcast { across_couplings: true, include_self: true }],
+//! );
+//! ```
+
+use std::{cell::RefCell, collections::HashMap};
+
+use lotus_extra::vehicle::CockpitSide;
+use lotus_script::{
+    input::{ActionState, ActionStateKind},
+    prelude::{message_type, Message},
+};
+use serde::{Deserialize, Serialize};
+
+/// Action of a trainer command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrainerAction {
+    /// The input is reported as released
+    Block,
+    /// The input is reported as pressed
+    Force,
+    /// The override of the input is removed
+    Clear,
+    /// The overrides of all inputs are removed, the input name is ignored
+    ClearAll,
+}
+
+/// Command of the instructor or the scenario.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrainerCommand {
+    /// Name of the key event or of the flag
+    pub input: String,
+    /// Action to apply
+    pub action: TrainerAction,
+    /// Cab in which a forced key event is pressed
+    pub cab: Option<CockpitSide>,
+}
+
+message_type!(TrainerCommand, "Pan_Trainer", "Command");
+
+#[derive(Debug, Clone, Copy)]
+enum Override {
+    Blocked,
+    Forced {
+        kind: ActionStateKind,
+        cockpit_index: Option<u8>,
+    },
+    Released {
+        cockpit_index: Option<u8>,
+    },
+}
+
+thread_local! {
+    static OVERRIDES: RefCell<HashMap<String, Override>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the state of a key event overridden by the trainer mode.
+pub(crate) fn trainer_action(ev: &str) -> Option<ActionState> {
+    OVERRIDES.with(|overrides| {
+        overrides.borrow().get(ev).map(|o| {
+            let (kind, cockpit_index) = match *o {
+                Override::Blocked => (ActionStateKind::None, None),
+                Override::Forced {
+                    kind,
+                    cockpit_index,
+                } => (kind, cockpit_index),
+                Override::Released { cockpit_index } => {
+                    (ActionStateKind::JustReleased, cockpit_index)
+                }
+            };
+            ActionState {
+                kind,
+                cockpit_index,
+                uv: None,
+            }
+        })
+    })
+}
+
+/// Whether the input or flag is forced by the trainer mode.
+#[must_use]
+pub fn is_forced(input: &str) -> bool {
+    OVERRIDES
+        .with(|overrides| matches!(overrides.borrow().get(input), Some(Override::Forced { .. })))
+}
+
+/// Whether the input or flag is blocked by the trainer mode.
+#[must_use]
+pub fn is_blocked(input: &str) -> bool {
+    OVERRIDES.with(|overrides| matches!(overrides.borrow().get(input), Some(Override::Blocked)))
+}
+
+/// Receives the trainer commands and manages the input overrides.
+#[derive(Debug, Default)]
+pub struct TrainerMode;
+
+impl TrainerMode {
+    /// Creates a new trainer mode, no input is overridden.
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Handles the [`TrainerCommand`] messages.
+    pub fn on_message(&mut self, msg: Message) {
+        msg.handle::<TrainerCommand>(|m| {
+            match m.action {
+                TrainerAction::Block => self.block(&m.input),
+                TrainerAction::Force => self.force(&m.input, m.cab),
+                TrainerAction::Clear => self.clear(&m.input),
+                TrainerAction::ClearAll => self.clear_all(),
+            }
+            Ok(())
+        })
+        .expect("TrainerCommand: message handle failed");
+    }
+
+    /// Advances the edges of forced and released inputs, call once per frame
+    /// at the end of the main loop.
+    pub fn tick(&mut self) {
+        OVERRIDES.with(|overrides| {
+            let mut overrides = overrides.borrow_mut();
+            overrides.retain(|_, o| !matches!(o, Override::Released { .. }));
+            for o in overrides.values_mut() {
+                if let Override::Forced { kind, .. } = o {
+                    *kind = ActionStateKind::Pressed;
+                }
+            }
+        });
+    }
+
+    /// Blocks an input.
+    pub fn block(&mut self, input: &str) {
+        Self::set(input, Override::Blocked);
+    }
+
+    /// Forces an input, it is reported as just pressed in the current frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Name of the key event or of the flag
+    /// * `cab` - Cab in which the key event is pressed, needed for key events
+    ///   bound to a cab
+    pub fn force(&mut self, input: &str, cab: Option<CockpitSide>) {
+        let cockpit_index = cab.map(|cab| if cab == CockpitSide::A { 0 } else { 1 });
+        Self::set(
+            input,
+            Override::Forced {
+                kind: ActionStateKind::JustPressed,
+                cockpit_index,
+            },
+        );
+    }
+
+    /// Removes the override of an input, a forced input is reported as just
+    /// released in the current frame.
+    pub fn clear(&mut self, input: &str) {
+        OVERRIDES.with(|overrides| {
+            let mut overrides = overrides.borrow_mut();
+            match overrides.get(input) {
+                Some(Override::Forced { cockpit_index, .. }) => {
+                    let released = Override::Released {
+                        cockpit_index: *cockpit_index,
+                    };
+                    overrides.insert(input.to_string(), released);
+                }
+                _ => {
+                    overrides.remove(input);
+                }
+            }
+        });
+    }
+
+    /// Removes the overrides of all inputs.
+    pub fn clear_all(&mut self) {
+        OVERRIDES.with(|overrides| overrides.borrow_mut().clear());
+    }
+
+    fn set(input: &str, value: Override) {
+        OVERRIDES.with(|overrides| {
+            overrides.borrow_mut().insert(input.to_string(), value);
+        });
+    }
+}
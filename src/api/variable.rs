@@ -2,6 +2,12 @@
 //!
 //! This module provides a type-safe way to handle variables with different types
 //! through the `Variable` struct and associated utility functions.
+//!
+//! Test builds run without the simulator: there [`get_var`] and [`set_var`]
+//! work on variables held in memory per test thread.
+
+#[cfg(test)]
+use std::{any::Any, cell::RefCell, collections::HashMap};
 
 use lotus_script::var::VariableType;
 
@@ -147,6 +153,7 @@ impl<T: VariableType> Variable<T> {
 /// let value: i32 = get_var::<i32>("counter");
 /// let message: String = get_var::<String>("greeting");
 /// ```
+#[cfg(not(test))]
 pub fn get_var<T: VariableType>(name: &str) -> T::Output {
     T::get_var(name)
 }
@@ -173,6 +180,103 @@ pub fn get_var<T: VariableType>(name: &str) -> T::Output {
 /// set_var("counter", 42i32);
 /// set_var("greeting", "Hello, World!".to_string());
 /// ```
+#[cfg(not(test))]
 pub fn set_var<T: VariableType>(name: &str, var: T) {
     T::set_var(name, var);
 }
+
+/// Reads a variable written with [`store_persistent`].
+///
+/// Unset variables read as 0, so a stored 0 can not be told apart from a
+/// vehicle without saved state. Therefore [`store_persistent`] also sets the
+/// flag `<name>_Stored`. Values saved without the flag by older versions
+/// count as stored if they differ from 0.
+///
+/// # Returns
+///
+/// The stored value, or `None` if the variable was never stored.
+///
+/// # Examples
+///
+/// ```rust
+/// let mileage_km = load_persistent::<f64>("Odometer").unwrap_or(init_km);
+/// ```
+pub fn load_persistent<T>(name: &str) -> Option<T::Output>
+where
+    T: VariableType + Into<T::Output>,
+    T::Output: Default + Clone + PartialEq + 'static,
+{
+    let value = get_var::<T>(name);
+    (get_var::<bool>(&stored_flag(name)) || value != T::Output::default()).then_some(value)
+}
+
+/// Writes a variable to be read back with [`load_persistent`].
+pub fn store_persistent<T>(name: &str, value: T)
+where
+    T: VariableType + Into<T::Output>,
+    T::Output: 'static,
+{
+    set_var(name, value);
+    set_var(&stored_flag(name), true);
+}
+
+fn stored_flag(name: &str) -> String {
+    format!("{name}_Stored")
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_VARS: RefCell<HashMap<String, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Reads a variable of the test, unset variables or variables of another type
+/// read as default.
+#[cfg(test)]
+pub fn get_var<T: VariableType>(name: &str) -> T::Output
+where
+    T::Output: Default + Clone + 'static,
+{
+    TEST_VARS.with(|vars| {
+        vars.borrow()
+            .get(name)
+            .and_then(|value| value.downcast_ref::<T::Output>())
+            .cloned()
+            .unwrap_or_default()
+    })
+}
+
+/// Writes a variable of the test.
+#[cfg(test)]
+pub fn set_var<T: VariableType + Into<T::Output>>(name: &str, var: T)
+where
+    T::Output: 'static,
+{
+    TEST_VARS.with(|vars| {
+        vars.borrow_mut()
+            .insert(name.to_string(), Box::new(var.into()));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persistent_zero() {
+        assert_eq!(load_persistent::<f64>("Odometer"), None);
+
+        store_persistent("Odometer", 0.0f64);
+        assert_eq!(load_persistent::<f64>("Odometer"), Some(0.0));
+        store_persistent("Odometer", 12.5f64);
+        assert_eq!(load_persistent::<f64>("Odometer"), Some(12.5));
+    }
+
+    #[test]
+    fn test_persistent_without_flag() {
+        // Stand einer älteren Version ohne Kennung
+        set_var("Wear", 0.3f32);
+        assert_eq!(load_persistent::<f32>("Wear"), Some(0.3));
+        set_var("Cycles", 0u32);
+        assert_eq!(load_persistent::<u32>("Cycles"), None);
+    }
+}
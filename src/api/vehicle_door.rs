@@ -3,7 +3,7 @@
 //! This module provides functionality to control and monitor vehicle doors,
 //! interfacing with the Lotus Script variable system for state management.
 
-use lotus_script::var::{get_var, set_var};
+use crate::api::variable::{get_var, set_var};
 
 /// Represents a single vehicle door with entry and exit capabilities.
 ///
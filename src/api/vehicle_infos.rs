@@ -5,10 +5,11 @@
 
 use lotus_script::{
     math::Vec3,
-    var::{get_var, set_var},
     vehicle::{acceleration_vs_ground, velocity_vs_ground},
 };
 
+use crate::api::variable::{get_var, set_var};
+
 /// Gets the current vehicle number identifier.
 ///
 /// Returns the vehicle number as stored in the Lotus Script variable system.
@@ -1,4 +1,4 @@
-use lotus_script::var::{get_var, set_var};
+use crate::api::variable::{get_var, set_var};
 
 /// A visibility flag manager that provides a convenient interface for managing
 /// boolean visibility states using lotus_script variables.
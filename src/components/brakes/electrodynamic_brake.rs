@@ -0,0 +1,306 @@
+//! Electrodynamic brake ("E-Bremse") with regeneration and braking resistors.
+//!
+//! The [`ElectrodynamicBrake`] uses the traction motors as generators. As long
+//! as the overhead line is receptive, the braking energy is fed back into the
+//! line; otherwise the brake switches over to the braking resistors on the
+//! roof. The resistors heat up with the braking power and are cooled by the
+//! air stream and a fan, which runs up with the temperature. When they get too
+//! hot, the electrodynamic force is reduced.
+//!
+//! The brake demand is blended: the electrodynamic brake takes as much of the
+//! requested force as it can, the remainder is given as demand for the
+//! friction brake, e.g. a [`HydraulicUnit`](super::hydraulic_unit::HydraulicUnit).
+//!
+//! Forces are given in N, powers in kW and temperatures in °C.
+
+use crate::{
+    api::{general::delta, sound::Sound},
+    components::hvac::fan::VentilationFan,
+};
+
+/// Where the energy of the electrodynamic brake goes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EdBrakeMode {
+    /// No electrodynamic braking
+    #[default]
+    Off,
+    /// Energy fed back into the overhead line
+    Regeneration,
+    /// Energy converted into heat in the braking resistors
+    Resistor,
+}
+
+pub struct ElectrodynamicBrakeBuilder {
+    max_force: f32,
+    max_power: f32,
+    service_force: f32,
+    fade_speeds: (f32, f32),
+    max_line_voltage: f32,
+
+    heat_capacity: f32,
+    cooling: f32,
+    fan_cooling: f32,
+    ambient_temp: f32,
+    derate_temps: (f32, f32),
+    fan_temps: (f32, f32),
+
+    fan: VentilationFan,
+    snd_switchover: Sound,
+}
+
+impl ElectrodynamicBrakeBuilder {
+    /// Maximum electrical braking power in kW, limits the force at high speed.
+    pub fn max_power(mut self, max_power: f32) -> Self {
+        self.max_power = max_power;
+        self
+    }
+
+    /// Speeds in km/h between which the electrodynamic force fades in, below
+    /// the lower speed only the friction brake acts.
+    pub fn fade_speeds(mut self, zero_speed: f32, full_speed: f32) -> Self {
+        self.fade_speeds = (zero_speed, full_speed);
+        self
+    }
+
+    /// Line voltage from 0.0 to 1.0+ above which the line is not receptive.
+    pub fn max_line_voltage(mut self, max_line_voltage: f32) -> Self {
+        self.max_line_voltage = max_line_voltage;
+        self
+    }
+
+    /// Thermal model of the braking resistors.
+    ///
+    /// # Arguments
+    ///
+    /// * `heat_capacity` - Heat capacity in kJ/K
+    /// * `cooling` - Heat dissipation without fan in kW/K
+    /// * `fan_cooling` - Additional heat dissipation with the fan at full speed in kW/K
+    pub fn resistor(mut self, heat_capacity: f32, cooling: f32, fan_cooling: f32) -> Self {
+        self.heat_capacity = heat_capacity;
+        self.cooling = cooling;
+        self.fan_cooling = fan_cooling;
+        self
+    }
+
+    /// Ambient temperature, also the initial temperature of the resistors.
+    pub fn ambient_temp(mut self, ambient_temp: f32) -> Self {
+        self.ambient_temp = ambient_temp;
+        self
+    }
+
+    /// Resistor temperatures at which the force starts to be reduced and at
+    /// which it reaches zero.
+    pub fn derate_temps(mut self, start_temp: f32, max_temp: f32) -> Self {
+        self.derate_temps = (start_temp, max_temp);
+        self
+    }
+
+    /// Resistor fan, which starts at the first temperature and runs at full
+    /// speed from the second one.
+    pub fn fan(mut self, fan: VentilationFan, on_temp: f32, full_temp: f32) -> Self {
+        self.fan = fan;
+        self.fan_temps = (on_temp, full_temp);
+        self
+    }
+
+    /// Sound of the contactor switching between line and resistors.
+    pub fn snd_switchover(mut self, name: impl Into<String>) -> Self {
+        self.snd_switchover = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> ElectrodynamicBrake {
+        ElectrodynamicBrake {
+            max_force: self.max_force,
+            max_power: self.max_power,
+            service_force: self.service_force,
+            fade_speeds: self.fade_speeds,
+            max_line_voltage: self.max_line_voltage,
+            heat_capacity: self.heat_capacity,
+            cooling: self.cooling,
+            fan_cooling: self.fan_cooling,
+            ambient_temp: self.ambient_temp,
+            derate_temps: self.derate_temps,
+            fan_temps: self.fan_temps,
+            snd_switchover: self.snd_switchover,
+            fan: self.fan,
+            mode: EdBrakeMode::Off,
+            force: 0.0,
+            regen_power: 0.0,
+            resistor_power: 0.0,
+            resistor_temp: self.ambient_temp,
+            friction_demand: 0.0,
+        }
+    }
+}
+
+/// Electrodynamic brake with regeneration, braking resistors and blending.
+///
+/// # Example
+///
+/// ```rust
+/// let mut ed_brake = ElectrodynamicBrake::builder(60_000.0, 90_000.0)
+///     .max_power(600.0)
+///     .fade_speeds(3.0, 8.0)
+///     .fan(
+///         VentilationFan::builder()
+///             .snd_run("Snd_Resistor_Fan", "Snd_Fan_Vol", "Snd_Fan_Pitch")
+///             .build(),
+///         150.0,
+///         300.0,
+///     )
+///     .build();
+///
+/// // In the main loop:
+/// ed_brake.tick(
+///     voltage > 0.7,
+///     v_ground(),
+///     controller.brake(),
+///     pantograph.voltage_norm,
+///     !powerline.deficit,
+/// );
+/// friction_brake.tick(voltage > 0.7, ed_brake.friction_demand);
+/// ```
+pub struct ElectrodynamicBrake {
+    max_force: f32,
+    max_power: f32,
+    service_force: f32,
+    fade_speeds: (f32, f32),
+    max_line_voltage: f32,
+
+    heat_capacity: f32,
+    cooling: f32,
+    fan_cooling: f32,
+    ambient_temp: f32,
+    derate_temps: (f32, f32),
+    fan_temps: (f32, f32),
+
+    snd_switchover: Sound,
+
+    /// Fan of the braking resistors
+    pub fan: VentilationFan,
+
+    /// Where the braking energy goes
+    pub mode: EdBrakeMode,
+    /// Electrodynamic brake force
+    pub force: f32,
+    /// Power fed back into the overhead line
+    pub regen_power: f32,
+    /// Power converted in the braking resistors
+    pub resistor_power: f32,
+    /// Temperature of the braking resistors
+    pub resistor_temp: f32,
+    /// Remaining demand for the friction brake from 0.0 to 1.0
+    pub friction_demand: f32,
+}
+
+impl ElectrodynamicBrake {
+    /// Creates a new electrodynamic brake builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_force` - Maximum electrodynamic brake force
+    /// * `service_force` - Brake force requested at a demand of 1.0, which is
+    ///   also the force of the friction brake at a friction demand of 1.0
+    pub fn builder(max_force: f32, service_force: f32) -> ElectrodynamicBrakeBuilder {
+        ElectrodynamicBrakeBuilder {
+            max_force,
+            max_power: 500.0,
+            service_force,
+            fade_speeds: (3.0, 8.0),
+            max_line_voltage: 1.15,
+            heat_capacity: 200.0,
+            cooling: 0.5,
+            fan_cooling: 2.5,
+            ambient_temp: 20.0,
+            derate_temps: (500.0, 600.0),
+            fan_temps: (150.0, 300.0),
+            fan: VentilationFan::builder().build(),
+            snd_switchover: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the brake, the resistors and the fan.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the traction control is supplied
+    /// * `speed` - Current speed in m/s
+    /// * `demand` - Brake demand from 0.0 to 1.0
+    /// * `line_voltage` - Voltage at the pantograph from 0.0 to 1.0
+    /// * `line_receptive` - Whether other consumers on the line can take the energy
+    pub fn tick(
+        &mut self,
+        power: bool,
+        speed: f32,
+        demand: f32,
+        line_voltage: f32,
+        line_receptive: bool,
+    ) {
+        let v = speed.abs();
+        let requested = demand.clamp(0.0, 1.0) * self.service_force;
+
+        let (zero_speed, full_speed) = self.fade_speeds;
+        let fade = ((v * 3.6 - zero_speed) / (full_speed - zero_speed).max(0.1)).clamp(0.0, 1.0);
+
+        let receptive =
+            line_receptive && line_voltage > 0.5 && line_voltage < self.max_line_voltage;
+        let mode = if !power || requested <= 0.0 || fade <= 0.0 {
+            EdBrakeMode::Off
+        } else if receptive {
+            EdBrakeMode::Regeneration
+        } else {
+            EdBrakeMode::Resistor
+        };
+
+        // Überhitzte Widerstände nehmen weniger Leistung auf
+        let (derate_start, derate_max) = self.derate_temps;
+        let thermal = if mode == EdBrakeMode::Resistor {
+            ((derate_max - self.resistor_temp) / (derate_max - derate_start).max(1.0))
+                .clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        let power_limit = self.max_power * 1000.0 / v.max(0.1);
+        self.force = if mode == EdBrakeMode::Off {
+            0.0
+        } else {
+            requested
+                .min(self.max_force * fade * thermal)
+                .min(power_limit)
+        };
+
+        if mode != self.mode && mode != EdBrakeMode::Off && self.mode != EdBrakeMode::Off {
+            self.snd_switchover.start();
+        }
+        self.mode = mode;
+
+        let electric_power = self.force * v / 1000.0;
+        (self.regen_power, self.resistor_power) = match self.mode {
+            EdBrakeMode::Regeneration => (electric_power, 0.0),
+            EdBrakeMode::Resistor => (0.0, electric_power),
+            EdBrakeMode::Off => (0.0, 0.0),
+        };
+
+        // Erwärmung durch die Bremsleistung, Kühlung durch Fahrtwind und Lüfter
+        let dissipation = (self.cooling + self.fan_cooling * self.fan.speed)
+            * (self.resistor_temp - self.ambient_temp);
+        self.resistor_temp +=
+            (self.resistor_power - dissipation) / self.heat_capacity.max(1.0) * delta();
+
+        let (fan_on, fan_full) = self.fan_temps;
+        let fan_level = if self.resistor_temp > fan_on {
+            ((self.resistor_temp - fan_on) / (fan_full - fan_on).max(1.0)).clamp(0.1, 1.0)
+        } else {
+            0.0
+        };
+        self.fan.tick(power, fan_level);
+
+        self.friction_demand = if self.service_force > 0.0 {
+            ((requested - self.force) / self.service_force).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+    }
+}
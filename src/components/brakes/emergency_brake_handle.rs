@@ -0,0 +1,260 @@
+//! Passenger emergency brake handle ("Fahrgastnotbremse").
+//!
+//! The [`EmergencyBrakeHandle`] is a sealed handle in the passenger
+//! compartment. Pulling it tears the seal and the handle stays latched in the
+//! pulled position until it is reset with the square key. While the handle is
+//! pulled, the emergency brake is requested on the `CouplerEmergencyBrake`
+//! line. The driver can suppress the brake demand with the emergency brake
+//! override ("Notbremsüberbrückung"), e.g. to leave a tunnel; the alarm stays
+//! active. Pulling and overriding are reported as diagnostic messages.
+
+use lotus_extra::vehicle::CockpitSide;
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{
+        animation::Animation, key_event::KeyEvent, light::Light, sound::Sound,
+        visible_flag::Visiblility,
+    },
+    elements::tech::key_switch::KeySwitch,
+    messages::{
+        coupling_handler::UniversalCouplingLine,
+        diagnostic_messages::{DiagnosticFaultKind, DiagnosticMessageSender},
+        gt6n_coupling_messages::CouplerEmergencyBrake,
+    },
+};
+
+pub struct EmergencyBrakeHandleBuilder {
+    cab_side: Option<CockpitSide>,
+
+    anim: Animation,
+    key_pull: KeyEvent,
+
+    seal: Option<Visiblility>,
+    reset_key: Option<KeySwitch>,
+
+    lm_alarm: Light,
+
+    snd_pull: Sound,
+    snd_reset: Sound,
+    snd_alarm: Sound,
+}
+
+impl EmergencyBrakeHandleBuilder {
+    /// Visibility of the seal, the seal is torn when the handle is pulled.
+    pub fn seal(mut self, name: impl Into<String>) -> Self {
+        self.seal = Some(Visiblility::new_visible(name));
+        self
+    }
+
+    /// Square key resetting the pulled handle when turned to +1.
+    pub fn reset_key(mut self, key: KeySwitch) -> Self {
+        self.reset_key = Some(key);
+        self
+    }
+
+    /// Alarm lamp lit while a handle in the train is pulled.
+    pub fn alarm_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_alarm = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the pulled handle.
+    pub fn snd_pull(mut self, name: impl Into<String>) -> Self {
+        self.snd_pull = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the handle snapping back on reset.
+    pub fn snd_reset(mut self, name: impl Into<String>) -> Self {
+        self.snd_reset = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Alarm buzzer sounding while a handle in the train is pulled.
+    pub fn snd_alarm(mut self, name: impl Into<String>) -> Self {
+        self.snd_alarm = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> EmergencyBrakeHandle {
+        EmergencyBrakeHandle {
+            cab_side: self.cab_side,
+            anim: self.anim,
+            key_pull: self.key_pull,
+            seal: self.seal,
+            reset_key: self.reset_key,
+            lm_alarm: self.lm_alarm,
+            snd_pull: self.snd_pull,
+            snd_reset: self.snd_reset,
+            snd_alarm: self.snd_alarm,
+            emergency_brake_line: UniversalCouplingLine::new(CouplerEmergencyBrake, (true, true)),
+            diagnostic: DiagnosticMessageSender::new(),
+            pulled: false,
+            seal_torn: false,
+            alarm: false,
+            brake_demand: false,
+            overridden: false,
+        }
+    }
+}
+
+/// Sealed passenger emergency brake handle with square key reset.
+///
+/// # Example
+///
+/// ```rust
+/// let mut handle = EmergencyBrakeHandle::builder("NB_Handle_1", "NB_Pull_1", None)
+///     .seal("NB_Seal_1")
+///     .reset_key(KeySwitch::builder(KeyDepot::new("Key_Square"), "NB_Reset_1", "NB_Key_Vis_1", None).max_spring().build())
+///     .snd_pull("Snd_NB_Pull")
+///     .snd_alarm("Snd_NB_Alarm")
+///     .build();
+///
+/// // In on_message:
+/// handle.on_message(msg);
+///
+/// // In the main loop:
+/// handle.tick(voltage > 0.5, nb_override_button.value(true));
+/// let emergency_brake = handle.brake_demand;
+/// ```
+pub struct EmergencyBrakeHandle {
+    cab_side: Option<CockpitSide>,
+
+    anim: Animation,
+    key_pull: KeyEvent,
+
+    seal: Option<Visiblility>,
+    reset_key: Option<KeySwitch>,
+
+    lm_alarm: Light,
+
+    snd_pull: Sound,
+    snd_reset: Sound,
+    snd_alarm: Sound,
+
+    emergency_brake_line: UniversalCouplingLine<bool, CouplerEmergencyBrake>,
+    diagnostic: DiagnosticMessageSender,
+
+    /// Whether this handle is pulled and latched
+    pub pulled: bool,
+    /// Whether the seal of this handle is torn
+    pub seal_torn: bool,
+    /// Whether a handle anywhere in the train is pulled
+    pub alarm: bool,
+    /// Whether the emergency brake is requested, false while overridden
+    pub brake_demand: bool,
+    /// Whether the driver overrides an active emergency brake request
+    pub overridden: bool,
+}
+
+impl EmergencyBrakeHandle {
+    /// Creates a new emergency brake handle builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Animation of the handle, 0.0 home and 1.0 pulled
+    /// * `event_name` - Key event pulling the handle
+    /// * `cab_side` - Optional cab side for key event handling and diagnostics
+    pub fn builder(
+        animation_name: impl Into<String>,
+        event_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> EmergencyBrakeHandleBuilder {
+        EmergencyBrakeHandleBuilder {
+            cab_side,
+            anim: Animation::new(Some(&animation_name.into())),
+            key_pull: KeyEvent::new(Some(&event_name.into()), cab_side),
+            seal: None,
+            reset_key: None,
+            lm_alarm: Light::new(None),
+            snd_pull: Sound::new_simple(None),
+            snd_reset: Sound::new_simple(None),
+            snd_alarm: Sound::new_simple(None),
+        }
+    }
+
+    /// Passes messages to the `CouplerEmergencyBrake` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.emergency_brake_line.on_message(msg);
+    }
+
+    /// Updates the handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the alarm lamp, the buzzer and the diagnostics are supplied
+    /// * `override_active` - Whether the driver operates the emergency brake override
+    pub fn tick(&mut self, power: bool, override_active: bool) {
+        if !self.pulled && self.key_pull.is_just_pressed() {
+            self.pull();
+        }
+
+        if let Some(key) = &mut self.reset_key {
+            key.tick();
+            if self.pulled && key.value(true) > 0 {
+                self.reset();
+            }
+        }
+
+        self.anim.set(self.pulled as u8 as f32);
+
+        self.emergency_brake_line.update_local(self.pulled);
+        self.alarm = self.emergency_brake_line.get_value();
+
+        // Überbrückung nur bei anstehender Notbremse wirksam
+        self.overridden = self.alarm && override_active;
+        self.brake_demand = self.alarm && !self.overridden;
+
+        self.lm_alarm
+            .set_brightness((power && self.alarm) as u8 as f32);
+        self.snd_alarm.start_stop(power && self.alarm);
+
+        if power {
+            self.diagnostic.send(
+                DiagnosticFaultKind::Fahrgastnotbremse,
+                self.pulled,
+                self.cab_side,
+            );
+            self.diagnostic.send(
+                DiagnosticFaultKind::FGnotbremseUeberbrueckt,
+                self.overridden,
+                self.cab_side,
+            );
+        }
+    }
+
+    /// Pulls the handle, e.g. triggered by a scenario.
+    pub fn pull(&mut self) {
+        if self.pulled {
+            return;
+        }
+
+        self.pulled = true;
+        self.snd_pull.start();
+
+        // Plombe reißt beim ersten Ziehen
+        if let Some(seal) = &mut self.seal {
+            if !self.seal_torn {
+                seal.make_invisible();
+                self.seal_torn = true;
+            }
+        }
+    }
+
+    /// Resets the pulled handle, the seal stays torn.
+    pub fn reset(&mut self) {
+        if self.pulled {
+            self.pulled = false;
+            self.snd_reset.start();
+        }
+    }
+
+    /// Replaces the torn seal, e.g. in the workshop.
+    pub fn renew_seal(&mut self) {
+        if let Some(seal) = &mut self.seal {
+            seal.make_visible();
+        }
+        self.seal_torn = false;
+    }
+}
@@ -0,0 +1,138 @@
+//! Hill holder ("Anfahrhilfe").
+//!
+//! The [`HillHolder`] keeps the brake applied after the vehicle came to a
+//! standstill, so that it does not roll back on gradients while the driver
+//! changes from the brake to traction. The hold is released with a ramp as
+//! soon as the traction demand exceeds the threshold.
+
+use crate::api::{general::delta, light::Light};
+
+pub struct HillHolderBuilder {
+    standstill_speed: f32,
+    hold_demand: f32,
+    traction_threshold: f32,
+    release_time: f32,
+
+    lm_active: Light,
+}
+
+impl HillHolderBuilder {
+    /// Speed in m/s below which the vehicle is considered standing.
+    pub fn standstill_speed(mut self, standstill_speed: f32) -> Self {
+        self.standstill_speed = standstill_speed;
+        self
+    }
+
+    /// Traction demand from 0.0 to 1.0 above which the hold is released.
+    pub fn traction_threshold(mut self, traction_threshold: f32) -> Self {
+        self.traction_threshold = traction_threshold;
+        self
+    }
+
+    /// Time in seconds in which the held brake demand is ramped down.
+    pub fn release_time(mut self, release_time: f32) -> Self {
+        self.release_time = release_time;
+        self
+    }
+
+    /// Dashboard lamp lit while the brake is held.
+    pub fn indicator_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_active = Light::new(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> HillHolder {
+        HillHolder {
+            standstill_speed: self.standstill_speed,
+            hold_demand: self.hold_demand,
+            traction_threshold: self.traction_threshold,
+            release_time: self.release_time,
+            hold_level: 0.0,
+            holding: false,
+            demand: 0.0,
+            lm_active: self.lm_active,
+        }
+    }
+}
+
+/// Holds the brake at standstill until traction is demanded.
+///
+/// # Example
+///
+/// ```rust
+/// let mut hill_holder = HillHolder::builder(0.3)
+///     .traction_threshold(0.15)
+///     .indicator_light("L_Anfahrhilfe")
+///     .build();
+///
+/// // In the main loop:
+/// hill_holder.tick(voltage > 0.7, v_ground(), controller.traction(), controller.brake());
+/// brake.tick(voltage > 0.7, hill_holder.demand);
+/// ```
+pub struct HillHolder {
+    standstill_speed: f32,
+    hold_demand: f32,
+    traction_threshold: f32,
+    release_time: f32,
+
+    hold_level: f32,
+
+    /// Whether the brake is held
+    pub holding: bool,
+    /// Brake demand from 0.0 to 1.0 including the held brake
+    pub demand: f32,
+
+    lm_active: Light,
+}
+
+impl HillHolder {
+    /// Creates a new hill holder builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `hold_demand` - Brake demand from 0.0 to 1.0 held at standstill
+    pub fn builder(hold_demand: f32) -> HillHolderBuilder {
+        HillHolderBuilder {
+            standstill_speed: 0.1,
+            hold_demand,
+            traction_threshold: 0.1,
+            release_time: 1.0,
+            lm_active: Light::new(None),
+        }
+    }
+
+    /// Updates the hold.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the brake control is supplied
+    /// * `speed` - Current speed in m/s
+    /// * `traction` - Traction demand from 0.0 to 1.0
+    /// * `brake` - Brake demand of the driver from 0.0 to 1.0
+    pub fn tick(&mut self, power: bool, speed: f32, traction: f32, brake: f32) {
+        let standstill = speed.abs() < self.standstill_speed;
+
+        // Halten, sobald das Fahrzeug mit Bremse zum Stehen gekommen ist
+        if power && standstill && brake > 0.0 && traction <= 0.0 {
+            self.holding = true;
+        }
+        if !power || traction > self.traction_threshold {
+            self.holding = false;
+        }
+        if !power {
+            self.hold_level = 0.0;
+        }
+
+        self.hold_level = if self.holding {
+            self.hold_demand
+        } else {
+            // Lösen mit Rampe
+            (self.hold_level - self.hold_demand * delta() / self.release_time).max(0.0)
+        };
+
+        self.demand = brake.max(self.hold_level);
+
+        self.lm_active
+            .set_brightness((power && self.holding) as u8 as f32);
+    }
+}
@@ -0,0 +1,207 @@
+//! Electro-hydraulic brake unit.
+//!
+//! The [`HydraulicUnit`] keeps a hydraulic accumulator charged with a motor
+//! driven pump and feeds the brake cylinders of the bogies through a
+//! proportional valve. The cylinder pressure follows the brake demand with
+//! the lag of the valve and is converted into brake force on the axles.
+//!
+//! All pressures are given in bar, forces in N.
+
+use crate::api::{axis::ApiRailAxis, general::delta, sound::Sound};
+
+struct BrakeBogie {
+    max_pressure: f32,
+    force_per_bar: f32,
+    axles: Vec<ApiRailAxis>,
+
+    pressure: f32,
+}
+
+pub struct HydraulicUnitBuilder {
+    cut_in_pressure: f32,
+    cut_out_pressure: f32,
+    pump_rate: f32,
+    consumption: f32,
+    valve_lag: f32,
+
+    bogies: Vec<BrakeBogie>,
+
+    snd_pump: Sound,
+}
+
+impl HydraulicUnitBuilder {
+    /// Accumulator pressures at which the pump is switched on and off.
+    pub fn pump_governor(mut self, cut_in_pressure: f32, cut_out_pressure: f32) -> Self {
+        self.cut_in_pressure = cut_in_pressure;
+        self.cut_out_pressure = cut_out_pressure;
+        self
+    }
+
+    /// Pressure rise of the accumulator in bar/s with the pump running.
+    pub fn pump_rate(mut self, pump_rate: f32) -> Self {
+        self.pump_rate = pump_rate;
+        self
+    }
+
+    /// Accumulator pressure drop per bar of cylinder pressure rise.
+    pub fn consumption(mut self, consumption: f32) -> Self {
+        self.consumption = consumption;
+        self
+    }
+
+    /// Time constant in seconds of the proportional valve.
+    pub fn valve_lag(mut self, valve_lag: f32) -> Self {
+        self.valve_lag = valve_lag;
+        self
+    }
+
+    /// Adds a bogie with its brake cylinders.
+    ///
+    /// # Arguments
+    ///
+    /// * `bogie_index` - Index of the bogie
+    /// * `axle_count` - Number of braked axles of the bogie
+    /// * `max_pressure` - Cylinder pressure at full demand
+    /// * `force_per_bar` - Brake force per axle and bar of cylinder pressure
+    pub fn add_bogie(
+        mut self,
+        bogie_index: usize,
+        axle_count: usize,
+        max_pressure: f32,
+        force_per_bar: f32,
+    ) -> Self {
+        self.bogies.push(BrakeBogie {
+            max_pressure,
+            force_per_bar,
+            axles: (0..axle_count)
+                .map(|axle_index| ApiRailAxis::new(axle_index, bogie_index))
+                .collect(),
+            pressure: 0.0,
+        });
+        self
+    }
+
+    pub fn snd_pump(mut self, name: impl Into<String>) -> Self {
+        self.snd_pump = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> HydraulicUnit {
+        HydraulicUnit {
+            cut_in_pressure: self.cut_in_pressure,
+            cut_out_pressure: self.cut_out_pressure,
+            pump_rate: self.pump_rate,
+            consumption: self.consumption,
+            valve_lag: self.valve_lag,
+            bogies: self.bogies,
+            pump_running: false,
+            accumulator_pressure: 0.0,
+            brake_force: 0.0,
+            snd_pump: self.snd_pump,
+        }
+    }
+}
+
+/// Pump, accumulator and proportional valve of an electro-hydraulic brake.
+///
+/// # Example
+///
+/// ```rust
+/// let mut brake = HydraulicUnit::builder()
+///     .add_bogie(0, 2, 80.0, 150.0)
+///     .add_bogie(1, 2, 80.0, 150.0)
+///     .snd_pump("Snd_Hydraulic_Pump")
+///     .build();
+///
+/// // In the main loop:
+/// brake.tick(voltage > 0.7, controller.brake());
+/// gauge.tick(brake.cylinder_pressure(0), 0.0);
+/// ```
+pub struct HydraulicUnit {
+    cut_in_pressure: f32,
+    cut_out_pressure: f32,
+    pump_rate: f32,
+    consumption: f32,
+    valve_lag: f32,
+
+    bogies: Vec<BrakeBogie>,
+
+    /// Whether the pump motor is running
+    pub pump_running: bool,
+    /// Pressure in the accumulator
+    pub accumulator_pressure: f32,
+    /// Brake force of all axles together
+    pub brake_force: f32,
+
+    snd_pump: Sound,
+}
+
+impl HydraulicUnit {
+    pub fn builder() -> HydraulicUnitBuilder {
+        HydraulicUnitBuilder {
+            cut_in_pressure: 110.0,
+            cut_out_pressure: 140.0,
+            pump_rate: 10.0,
+            consumption: 0.1,
+            valve_lag: 0.3,
+            bogies: Vec::new(),
+            snd_pump: Sound::new_simple(None),
+        }
+    }
+
+    /// Starts with a charged accumulator, e.g. for a vehicle placed ready for service.
+    pub fn init_charged(mut self) -> Self {
+        self.accumulator_pressure = self.cut_out_pressure;
+        self
+    }
+
+    /// Returns the cylinder pressure of the bogie with the given position in
+    /// the order of [`HydraulicUnitBuilder::add_bogie`].
+    pub fn cylinder_pressure(&self, bogie: usize) -> f32 {
+        self.bogies.get(bogie).map_or(0.0, |b| b.pressure)
+    }
+
+    /// Updates pump, accumulator and brake cylinders.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the unit is supplied
+    /// * `demand` - Brake demand from 0.0 to 1.0
+    pub fn tick(&mut self, power: bool, demand: f32) {
+        // Pumpe mit Druckschalter
+        let running_last = self.pump_running;
+        if self.accumulator_pressure < self.cut_in_pressure {
+            self.pump_running = power;
+        } else if self.accumulator_pressure >= self.cut_out_pressure || !power {
+            self.pump_running = false;
+        }
+        if self.pump_running != running_last {
+            self.snd_pump.start_stop(self.pump_running);
+        }
+
+        if self.pump_running {
+            self.accumulator_pressure += self.pump_rate * delta();
+        }
+
+        let demand = if power { demand.clamp(0.0, 1.0) } else { 0.0 };
+
+        self.brake_force = 0.0;
+        for bogie in &mut self.bogies {
+            // Proportionalventil mit Verzögerung
+            let target = (demand * bogie.max_pressure).min(self.accumulator_pressure);
+            let change = (target - bogie.pressure) * (delta() / self.valve_lag).min(1.0);
+            bogie.pressure += change;
+
+            if change > 0.0 {
+                self.accumulator_pressure =
+                    (self.accumulator_pressure - change * self.consumption).max(0.0);
+            }
+
+            let force = bogie.pressure * bogie.force_per_bar;
+            for axle in &bogie.axles {
+                axle.set_brakeforce(force);
+            }
+            self.brake_force += force * bogie.axles.len() as f32;
+        }
+    }
+}
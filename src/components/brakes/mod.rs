@@ -0,0 +1,6 @@
+pub mod electrodynamic_brake;
+pub mod emergency_brake_handle;
+pub mod hill_holder;
+pub mod hydraulic_unit;
+pub mod spring_brake;
+pub mod stop_brake;
@@ -0,0 +1,223 @@
+//! Spring brake ("Federspeicherbremse").
+//!
+//! The [`SpringBrakeActuator`] is held released by pressure. When the release
+//! pressure drops below the threshold, e.g. because the brake is commanded
+//! on over the `CouplerSpringBrake` line or the supply fails, the springs
+//! apply the brake. Without pressure it can be released mechanically with a
+//! crank.
+
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{animation::Animation, general::delta, light::Light, sound::Sound},
+    elements::tech::cranc::Crank,
+    messages::{
+        coupling_handler::UniversalCouplingLine, gt6n_coupling_messages::CouplerSpringBrake,
+    },
+};
+
+pub struct SpringBrakeActuatorBuilder {
+    apply_pressure: f32,
+    release_pressure: f32,
+    fill_rate: f32,
+    vent_rate: f32,
+    brake_force: f32,
+
+    release_crank: Option<Crank>,
+    release_turns: f32,
+
+    anim: Animation,
+    lm_applied: Light,
+
+    snd_apply: Sound,
+    snd_release: Sound,
+}
+
+impl SpringBrakeActuatorBuilder {
+    /// Pressures at which the springs apply and release the brake.
+    pub fn thresholds(mut self, apply_pressure: f32, release_pressure: f32) -> Self {
+        self.apply_pressure = apply_pressure;
+        self.release_pressure = release_pressure;
+        self
+    }
+
+    /// Pressure change rates in bar/s when filling and venting the cylinder.
+    pub fn rates(mut self, fill_rate: f32, vent_rate: f32) -> Self {
+        self.fill_rate = fill_rate;
+        self.vent_rate = vent_rate;
+        self
+    }
+
+    /// Crank for the mechanical release and the crank position at which the
+    /// brake is released.
+    pub fn release_crank(mut self, crank: Crank, release_turns: f32) -> Self {
+        self.release_crank = Some(crank);
+        self.release_turns = release_turns;
+        self
+    }
+
+    /// Animation of the brake from 0.0 (released) to 1.0 (applied).
+    pub fn animation(mut self, name: impl Into<String>) -> Self {
+        self.anim = Animation::new(Some(&name.into()));
+        self
+    }
+
+    /// Dashboard lamp lit while the brake is applied.
+    pub fn status_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_applied = Light::new(Some(&name.into()));
+        self
+    }
+
+    pub fn sounds(
+        mut self,
+        sound_apply_name: impl Into<String>,
+        sound_release_name: impl Into<String>,
+    ) -> Self {
+        self.snd_apply = Sound::new_simple(Some(&sound_apply_name.into()));
+        self.snd_release = Sound::new_simple(Some(&sound_release_name.into()));
+        self
+    }
+
+    pub fn build(self) -> SpringBrakeActuator {
+        SpringBrakeActuator {
+            apply_pressure: self.apply_pressure,
+            release_pressure: self.release_pressure,
+            fill_rate: self.fill_rate,
+            vent_rate: self.vent_rate,
+            brake_force_max: self.brake_force,
+            release_crank: self.release_crank,
+            release_turns: self.release_turns,
+            spring_brake_line: UniversalCouplingLine::new(CouplerSpringBrake, (true, true)),
+            pressure: 0.0,
+            springs_applied: true,
+            applied: true,
+            mechanically_released: false,
+            brake_force: self.brake_force,
+            anim: self.anim,
+            lm_applied: self.lm_applied,
+            snd_apply: self.snd_apply,
+            snd_release: self.snd_release,
+        }
+    }
+}
+
+/// Pressure released spring brake with mechanical release.
+///
+/// # Example
+///
+/// ```rust
+/// let mut spring_brake = SpringBrakeActuator::builder(12000.0)
+///     .release_crank(Crank::builder("A_FSP_Crank", None).max(5.0).event_plus("FSP_Release").build(), 5.0)
+///     .status_light("L_FSP")
+///     .build();
+///
+/// // In on_message:
+/// spring_brake.on_message(msg);
+///
+/// // In the main loop:
+/// spring_brake.tick(voltage > 0.7, parking_brake_switch, reservoir.pressure);
+/// ```
+pub struct SpringBrakeActuator {
+    apply_pressure: f32,
+    release_pressure: f32,
+    fill_rate: f32,
+    vent_rate: f32,
+    brake_force_max: f32,
+
+    release_crank: Option<Crank>,
+    release_turns: f32,
+
+    spring_brake_line: UniversalCouplingLine<bool, CouplerSpringBrake>,
+
+    /// Pressure in the release cylinder
+    pub pressure: f32,
+    springs_applied: bool,
+    /// Whether the brake is applied
+    pub applied: bool,
+    /// Whether the brake is released with the crank
+    pub mechanically_released: bool,
+    /// Current brake force in N
+    pub brake_force: f32,
+
+    anim: Animation,
+    lm_applied: Light,
+
+    snd_apply: Sound,
+    snd_release: Sound,
+}
+
+impl SpringBrakeActuator {
+    /// Creates a new spring brake builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `brake_force` - Brake force in N with the brake applied
+    pub fn builder(brake_force: f32) -> SpringBrakeActuatorBuilder {
+        SpringBrakeActuatorBuilder {
+            apply_pressure: 3.5,
+            release_pressure: 4.5,
+            fill_rate: 2.0,
+            vent_rate: 5.0,
+            brake_force,
+            release_crank: None,
+            release_turns: 1.0,
+            anim: Animation::new(None),
+            lm_applied: Light::new(None),
+            snd_apply: Sound::new_simple(None),
+            snd_release: Sound::new_simple(None),
+        }
+    }
+
+    /// Passes messages to the `CouplerSpringBrake` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.spring_brake_line.on_message(msg);
+    }
+
+    /// Updates the brake.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the release valve is supplied, the brake applies without
+    /// * `apply` - Local command to apply the brake, e.g. the parking brake switch
+    /// * `supply_pressure` - Pressure available to release the brake
+    pub fn tick(&mut self, power: bool, apply: bool, supply_pressure: f32) {
+        self.spring_brake_line.update_local(apply);
+        let commanded = self.spring_brake_line.get_value() || !power;
+
+        // Lösezylinder füllen oder entlüften
+        if commanded {
+            self.pressure = (self.pressure - self.vent_rate * delta()).max(0.0);
+        } else if self.pressure < supply_pressure {
+            self.pressure = (self.pressure + self.fill_rate * delta()).min(supply_pressure);
+        } else {
+            self.pressure = supply_pressure;
+        }
+
+        if self.pressure < self.apply_pressure {
+            self.springs_applied = true;
+        } else if self.pressure > self.release_pressure {
+            self.springs_applied = false;
+        }
+
+        if let Some(crank) = &mut self.release_crank {
+            crank.tick();
+            self.mechanically_released = crank.pos >= self.release_turns;
+        }
+
+        let applied = self.springs_applied && !self.mechanically_released;
+        if applied != self.applied {
+            if applied {
+                self.snd_apply.start();
+            } else {
+                self.snd_release.start();
+            }
+            self.applied = applied;
+        }
+
+        self.brake_force = applied as u8 as f32 * self.brake_force_max;
+
+        self.anim.set(applied as u8 as f32);
+        self.lm_applied
+            .set_brightness((power && applied) as u8 as f32);
+    }
+}
@@ -0,0 +1,187 @@
+//! Stop brake ("Haltestellenbremse").
+//!
+//! The [`StopBrakeController`] applies a holding brake automatically while the
+//! vehicle stands at a stop with released doors, so that it cannot move while
+//! passengers board. The brake is only released on a traction demand once the
+//! doors of the whole consist are closed again; for this the controller runs
+//! the `CouplerDoorsClosed` coupling line.
+
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{general::delta, light::Light},
+    messages::{
+        coupling_handler::UniversalCouplingLine, gt6n_coupling_messages::CouplerDoorsClosed,
+    },
+};
+
+pub struct StopBrakeControllerBuilder {
+    hold_demand: f32,
+    standstill_speed: f32,
+    traction_threshold: f32,
+    release_time: f32,
+
+    lm_active: Light,
+}
+
+impl StopBrakeControllerBuilder {
+    /// Speed in m/s below which the vehicle is considered standing.
+    pub fn standstill_speed(mut self, standstill_speed: f32) -> Self {
+        self.standstill_speed = standstill_speed;
+        self
+    }
+
+    /// Traction demand from 0.0 to 1.0 above which the brake is released.
+    pub fn traction_threshold(mut self, traction_threshold: f32) -> Self {
+        self.traction_threshold = traction_threshold;
+        self
+    }
+
+    /// Time in seconds in which the held brake demand is ramped down.
+    pub fn release_time(mut self, release_time: f32) -> Self {
+        self.release_time = release_time;
+        self
+    }
+
+    /// Dashboard lamp lit while the stop brake is applied.
+    pub fn indicator_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_active = Light::new(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> StopBrakeController {
+        StopBrakeController {
+            hold_demand: self.hold_demand,
+            standstill_speed: self.standstill_speed,
+            traction_threshold: self.traction_threshold,
+            release_time: self.release_time,
+            lm_active: self.lm_active,
+            doors_closed_line: UniversalCouplingLine::new(CouplerDoorsClosed, (true, true)),
+            hold_level: 0.0,
+            holding: false,
+            doors_closed: true,
+            traction_blocked: false,
+            demand: 0.0,
+        }
+    }
+}
+
+/// Holding brake at stops, released on traction once all doors are closed.
+///
+/// # Example
+///
+/// ```rust
+/// let mut stop_brake = StopBrakeController::builder(0.4)
+///     .traction_threshold(0.1)
+///     .indicator_light("L_Haltestellenbremse")
+///     .build();
+///
+/// // In on_message:
+/// stop_brake.on_message(msg);
+///
+/// // In the main loop:
+/// stop_brake.tick(
+///     voltage > 0.7,
+///     v_ground(),
+///     door_controller.released,
+///     door_controller.doors_closed,
+///     controller.traction(),
+///     controller.brake(),
+/// );
+/// brake.tick(voltage > 0.7, stop_brake.demand);
+/// ```
+pub struct StopBrakeController {
+    hold_demand: f32,
+    standstill_speed: f32,
+    traction_threshold: f32,
+    release_time: f32,
+
+    lm_active: Light,
+
+    doors_closed_line: UniversalCouplingLine<bool, CouplerDoorsClosed>,
+
+    hold_level: f32,
+
+    /// Whether the stop brake is applied
+    pub holding: bool,
+    /// Whether the doors of the whole consist are closed
+    pub doors_closed: bool,
+    /// Whether a traction demand is suppressed by the stop brake
+    pub traction_blocked: bool,
+    /// Brake demand from 0.0 to 1.0 including the stop brake
+    pub demand: f32,
+}
+
+impl StopBrakeController {
+    /// Creates a new stop brake builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `hold_demand` - Brake demand from 0.0 to 1.0 applied at the stop
+    pub fn builder(hold_demand: f32) -> StopBrakeControllerBuilder {
+        StopBrakeControllerBuilder {
+            hold_demand,
+            standstill_speed: 0.1,
+            traction_threshold: 0.1,
+            release_time: 1.0,
+            lm_active: Light::new(None),
+        }
+    }
+
+    /// Passes messages to the `CouplerDoorsClosed` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.doors_closed_line.on_message(msg);
+    }
+
+    /// Updates the stop brake.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the brake control is supplied
+    /// * `speed` - Current speed in m/s
+    /// * `doors_released` - Whether the doors of this car are released
+    /// * `doors_closed` - Whether all doors of this car are closed
+    /// * `traction` - Traction demand from 0.0 to 1.0
+    /// * `brake` - Brake demand of the driver from 0.0 to 1.0
+    pub fn tick(
+        &mut self,
+        power: bool,
+        speed: f32,
+        doors_released: bool,
+        doors_closed: bool,
+        traction: f32,
+        brake: f32,
+    ) {
+        self.doors_closed_line.update_local(doors_closed);
+        self.doors_closed = self.doors_closed_line.get_value();
+
+        let standstill = speed.abs() < self.standstill_speed;
+
+        // Anlegen im Stand, sobald die Türen freigegeben oder offen sind
+        if power && standstill && (doors_released || !self.doors_closed) {
+            self.holding = true;
+        }
+
+        // Lösen erst mit Fahrbefehl und geschlossenen Türen im ganzen Zug
+        let releasable = !doors_released && self.doors_closed;
+        if !power || (releasable && traction > self.traction_threshold) {
+            self.holding = false;
+        }
+        if !power {
+            self.hold_level = 0.0;
+        }
+
+        self.hold_level = if self.holding {
+            self.hold_demand
+        } else {
+            // Lösen mit Rampe
+            (self.hold_level - self.hold_demand * delta() / self.release_time).max(0.0)
+        };
+
+        self.traction_blocked = self.holding && traction > 0.0;
+        self.demand = brake.max(self.hold_level);
+
+        self.lm_active
+            .set_brightness((power && self.holding) as u8 as f32);
+    }
+}
@@ -0,0 +1,569 @@
+//! Operating terminal of the IBIS ("IBIS-Terminal", TM).
+//!
+//! The [`IbisTerminal`] is registered on the TrainBus as
+//! [`PeripheryKind::IbisTerminal`]. The driver enters line, course and
+//! destination on the numeric keypad; committed inputs are sent to the IBIS
+//! master as [`IbisInput`], a committed destination also as
+//! [`DestinationCode`] to the destination displays. The terminal also collects the fault reports of
+//! the periphery and lists them, a selected fault is acknowledged with
+//! [`IbisFaultAcknowledge`]. A further page shows the state of the doors.
+//!
+//! The pages are rendered row by row onto a [`DotMatrixDisplay`], whose text
+//! areas are used as the rows of the terminal.
+//!
+//! ## Operation
+//!
+//! - Main page: line, course, destination and the number of faults
+//! - `Menu`: opens the menu, the entries are selected with their digit or
+//!   with `Up`/`Down` and `Enter`
+//! - Input: digits are entered, `Enter` commits, `Clear` deletes the last
+//!   digit or returns to the menu
+//! - Fault list: `Up`/`Down` selects a fault, `Enter` acknowledges it
+
+use lotus_script::{
+    message::{send_message, Coupling, MessageTarget},
+    prelude::Message,
+};
+
+use crate::{
+    api::{general::delta, light::Light, sound::Sound},
+    elements::{std::dot_matrix::DotMatrixDisplay, tech::buttons::PushButton},
+    management::{
+        enums::door_enums::DoorState,
+        trainbus::{
+            IbisFaultAcknowledge, IbisFaultReport, PeripheryFault, PeripheryKind, TrainBusPeriferie,
+        },
+    },
+    messages::ibis_messages::{DestinationCode, IbisInput},
+};
+
+/// Entries of the menu
+const MENU: [&str; 5] = ["Linie", "Kurs", "Ziel", "Störungen", "Türen"];
+
+/// Blink period of the fault lamp in seconds for unacknowledged faults
+const BLINK_PERIOD: f32 = 1.0;
+
+/// Key of the terminal keypad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalKey {
+    /// Digit from 0 to 9
+    Digit(u8),
+    /// Confirms the input or the selection
+    Enter,
+    /// Deletes the last digit or returns to the previous page
+    Clear,
+    /// Opens the menu or returns to the main page
+    Menu,
+    /// Moves the selection up
+    Up,
+    /// Moves the selection down
+    Down,
+}
+
+/// Field of the journey data entered on the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputField {
+    /// Line number
+    Line,
+    /// Course number ("Kurs")
+    Course,
+    /// Destination code ("Zielnummer")
+    Destination,
+}
+
+impl InputField {
+    fn title(&self) -> &'static str {
+        match self {
+            InputField::Line => "Linie",
+            InputField::Course => "Kurs",
+            InputField::Destination => "Ziel",
+        }
+    }
+
+    fn max_digits(&self) -> usize {
+        match self {
+            InputField::Line => 3,
+            InputField::Course => 3,
+            InputField::Destination => 4,
+        }
+    }
+}
+
+/// Page shown on the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalPage {
+    /// Journey data and fault count
+    Main,
+    /// Menu with the selected entry
+    Menu(usize),
+    /// Input of a journey data field
+    Input(InputField),
+    /// Fault list with the selected fault
+    Faults(usize),
+    /// State of the doors
+    Doors,
+}
+
+/// Fault reported by a periphery device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalFault {
+    /// Car of the device, counted from the master
+    pub car: u32,
+    /// Coupling of the master car over which the fault was reported
+    pub coupling: Option<Coupling>,
+    /// Kind of the device
+    pub kind: PeripheryKind,
+    /// Number of the device of its kind in the car
+    pub counter: u32,
+    /// Reported fault
+    pub state: PeripheryFault,
+    /// Whether the fault was acknowledged on the terminal
+    pub acknowledged: bool,
+}
+
+impl TerminalFault {
+    fn text(&self) -> String {
+        format!(
+            "W{} {}{} {}",
+            self.car,
+            self.kind.short_name(),
+            self.counter,
+            self.state.short_text()
+        )
+    }
+}
+
+pub struct IbisTerminalBuilder {
+    display: DotMatrixDisplay,
+    slot_index: i32,
+    rows: usize,
+
+    buttons: Vec<(TerminalKey, PushButton)>,
+
+    lm_fault: Light,
+    snd_key: Sound,
+}
+
+impl IbisTerminalBuilder {
+    /// Number of text areas of the display used as rows.
+    pub fn rows(mut self, rows: usize) -> Self {
+        self.rows = rows.max(2);
+        self
+    }
+
+    /// Assigns a key to a button of the keypad.
+    pub fn button(mut self, key: TerminalKey, button: PushButton) -> Self {
+        self.buttons.push((key, button));
+        self
+    }
+
+    /// Lamp blinking for unacknowledged faults and lit for acknowledged ones.
+    pub fn lm_fault(mut self, name: impl Into<String>) -> Self {
+        self.lm_fault = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Beep on each accepted key press.
+    pub fn snd_key(mut self, name: impl Into<String>) -> Self {
+        self.snd_key = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> IbisTerminal {
+        IbisTerminal {
+            display: self.display,
+            trainbus: TrainBusPeriferie::new(PeripheryKind::IbisTerminal, self.slot_index),
+            rows: self.rows,
+            buttons: self.buttons,
+            lm_fault: self.lm_fault,
+            snd_key: self.snd_key,
+            buffer: String::new(),
+            blink_timer: 0.0,
+            doors: Vec::new(),
+            page: TerminalPage::Main,
+            input: IbisInput::default(),
+            faults: Vec::new(),
+        }
+    }
+}
+
+/// IBIS terminal with keypad, menu, fault list and door status.
+///
+/// # Example
+///
+/// ```rust
+/// let mut terminal = IbisTerminal::builder(
+///     DotMatrixDisplay::builder(96, 32)
+///         .texture("Terminal_Display")
+///         .area(TextArea::new(0, 0, 96, 8), font.clone())
+///         .area(TextArea::new(0, 8, 96, 8), font.clone())
+///         .area(TextArea::new(0, 16, 96, 8), font.clone())
+///         .area(TextArea::new(0, 24, 96, 8), font)
+///         .build(),
+///     30,
+/// )
+/// .button(TerminalKey::Digit(1), PushButton::builder("Terminal_1", "Terminal_1", None).build())
+/// .button(TerminalKey::Enter, PushButton::builder("Terminal_E", "Terminal_E", None).build())
+/// .button(TerminalKey::Menu, PushButton::builder("Terminal_M", "Terminal_M", None).build())
+/// .lm_fault("L_Terminal_Stoerung")
+/// .snd_key("Snd_Terminal_Beep")
+/// .build();
+///
+/// // In on_message:
+/// terminal.on_message(msg);
+///
+/// // In the main loop:
+/// terminal.tick(voltage > 0.5, &door_states);
+/// ```
+pub struct IbisTerminal {
+    display: DotMatrixDisplay,
+    trainbus: TrainBusPeriferie,
+    rows: usize,
+
+    buttons: Vec<(TerminalKey, PushButton)>,
+
+    lm_fault: Light,
+    snd_key: Sound,
+
+    buffer: String,
+    blink_timer: f32,
+    doors: Vec<DoorState>,
+
+    /// Page currently shown
+    pub page: TerminalPage,
+    /// Journey data last entered
+    pub input: IbisInput,
+    /// Faults currently reported by the periphery
+    pub faults: Vec<TerminalFault>,
+}
+
+impl IbisTerminal {
+    /// Creates a new IBIS terminal builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `display` - Display whose text areas are used as rows
+    /// * `slot_index` - Slot index with which the terminal registers on the TrainBus
+    pub fn builder(display: DotMatrixDisplay, slot_index: i32) -> IbisTerminalBuilder {
+        IbisTerminalBuilder {
+            display,
+            slot_index,
+            rows: 4,
+            buttons: Vec::new(),
+            lm_fault: Light::new(None),
+            snd_key: Sound::new_simple(None),
+        }
+    }
+
+    /// Receives the fault reports and the inputs of other terminals.
+    pub fn on_message(&mut self, msg: Message) {
+        self.trainbus.on_message(msg.clone());
+
+        msg.handle::<IbisFaultReport>(|m| {
+            self.report(m);
+            Ok(())
+        })
+        .expect("IbisFaultReport: message handle failed");
+
+        msg.handle::<IbisInput>(|m| {
+            self.input = m;
+            Ok(())
+        })
+        .expect("IbisInput: message handle failed");
+    }
+
+    /// Number of faults not yet acknowledged.
+    pub fn unacknowledged(&self) -> usize {
+        self.faults.iter().filter(|f| !f.acknowledged).count()
+    }
+
+    /// Handles a key press, e.g. from an MFD.
+    pub fn press(&mut self, key: TerminalKey) {
+        self.snd_key.start();
+
+        self.page = match (self.page, key) {
+            (TerminalPage::Main, TerminalKey::Menu) => TerminalPage::Menu(0),
+            (TerminalPage::Main, _) => TerminalPage::Main,
+
+            (TerminalPage::Menu(_), TerminalKey::Menu | TerminalKey::Clear) => TerminalPage::Main,
+            (TerminalPage::Menu(i), TerminalKey::Up) => TerminalPage::Menu(i.saturating_sub(1)),
+            (TerminalPage::Menu(i), TerminalKey::Down) => {
+                TerminalPage::Menu((i + 1).min(MENU.len() - 1))
+            }
+            (TerminalPage::Menu(i), TerminalKey::Enter) => self.open(i),
+            (TerminalPage::Menu(i), TerminalKey::Digit(d)) => {
+                if (1..=MENU.len()).contains(&(d as usize)) {
+                    self.open(d as usize - 1)
+                } else {
+                    TerminalPage::Menu(i)
+                }
+            }
+
+            (TerminalPage::Input(_), TerminalKey::Menu) => TerminalPage::Main,
+            (TerminalPage::Input(field), TerminalKey::Digit(d)) => {
+                if self.buffer.len() < field.max_digits() {
+                    self.buffer.push(char::from(b'0' + d.min(9)));
+                }
+                TerminalPage::Input(field)
+            }
+            (TerminalPage::Input(field), TerminalKey::Clear) => {
+                if self.buffer.pop().is_some() {
+                    TerminalPage::Input(field)
+                } else {
+                    TerminalPage::Menu(field as usize)
+                }
+            }
+            (TerminalPage::Input(field), TerminalKey::Enter) => {
+                self.commit(field);
+                TerminalPage::Main
+            }
+            (TerminalPage::Input(field), _) => TerminalPage::Input(field),
+
+            (TerminalPage::Faults(_), TerminalKey::Menu) => TerminalPage::Main,
+            (TerminalPage::Faults(_), TerminalKey::Clear) => TerminalPage::Menu(3),
+            (TerminalPage::Faults(i), TerminalKey::Up) => TerminalPage::Faults(i.saturating_sub(1)),
+            (TerminalPage::Faults(i), TerminalKey::Down) => {
+                TerminalPage::Faults((i + 1).min(self.faults.len().saturating_sub(1)))
+            }
+            (TerminalPage::Faults(i), TerminalKey::Enter) => {
+                self.acknowledge(i);
+                TerminalPage::Faults(i)
+            }
+            (TerminalPage::Faults(i), _) => TerminalPage::Faults(i),
+
+            (TerminalPage::Doors, TerminalKey::Menu) => TerminalPage::Main,
+            (TerminalPage::Doors, TerminalKey::Clear) => TerminalPage::Menu(4),
+            (TerminalPage::Doors, _) => TerminalPage::Doors,
+        };
+    }
+
+    /// Updates the keypad, the pages and the display.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the terminal is supplied
+    /// * `doors` - State of the doors for the door page
+    pub fn tick(&mut self, power: bool, doors: &[DoorState]) {
+        let mut pressed = Vec::new();
+        for (key, button) in &mut self.buttons {
+            button.tick();
+            if button.is_just_pressed() {
+                pressed.push(*key);
+            }
+        }
+
+        if !power {
+            // Ohne Versorgung startet das Terminal wieder auf der Hauptseite
+            self.page = TerminalPage::Main;
+            self.buffer.clear();
+            self.blink_timer = 0.0;
+            self.lm_fault.set_brightness(0.0);
+            self.display.tick(false);
+            return;
+        }
+
+        self.trainbus.tick();
+
+        for key in pressed {
+            self.press(key);
+        }
+
+        self.doors.clear();
+        self.doors.extend_from_slice(doors);
+
+        for (i, row) in self.render().into_iter().enumerate() {
+            self.display.set_text(i, row);
+        }
+        self.display.tick(true);
+
+        self.blink_timer = (self.blink_timer + delta()) % BLINK_PERIOD;
+        let lit = if self.unacknowledged() > 0 {
+            self.blink_timer < BLINK_PERIOD / 2.0
+        } else {
+            !self.faults.is_empty()
+        };
+        self.lm_fault.set_brightness(lit as u8 as f32);
+    }
+
+    //===================================================================
+    // Internal
+    //===================================================================
+
+    fn report(&mut self, report: IbisFaultReport) {
+        let index = self.faults.iter().position(|f| {
+            f.car == report.car
+                && f.coupling == report.coupling
+                && f.counter == report.counter
+                && f.kind.is_same_kind(&report.kind)
+        });
+
+        match (index, report.state) {
+            (Some(i), PeripheryFault::Ok) => {
+                self.faults.remove(i);
+                if let TerminalPage::Faults(selected) = self.page {
+                    self.page =
+                        TerminalPage::Faults(selected.min(self.faults.len().saturating_sub(1)));
+                }
+            }
+            (Some(i), state) => {
+                let fault = &mut self.faults[i];
+                if fault.state != state {
+                    fault.state = state;
+                    fault.acknowledged = false;
+                }
+            }
+            (None, PeripheryFault::Ok) => {}
+            (None, state) => self.faults.push(TerminalFault {
+                car: report.car,
+                coupling: report.coupling,
+                kind: report.kind,
+                counter: report.counter,
+                state,
+                acknowledged: false,
+            }),
+        }
+    }
+
+    fn acknowledge(&mut self, index: usize) {
+        if let Some(fault) = self.faults.get_mut(index) {
+            if !fault.acknowledged {
+                fault.acknowledged = true;
+                send_message(
+                    &IbisFaultAcknowledge {
+                        car: fault.car,
+                        coupling: fault.coupling,
+                        kind: fault.kind.clone(),
+                        counter: fault.counter,
+                    },
+                    [MessageTarget::Broadcast {
+                        across_couplings: true,
+                        include_self: true,
+                    }],
+                );
+            }
+        }
+    }
+
+    fn open(&mut self, entry: usize) -> TerminalPage {
+        self.buffer.clear();
+        match entry {
+            0 => TerminalPage::Input(InputField::Line),
+            1 => TerminalPage::Input(InputField::Course),
+            2 => TerminalPage::Input(InputField::Destination),
+            3 => TerminalPage::Faults(0),
+            _ => TerminalPage::Doors,
+        }
+    }
+
+    fn commit(&mut self, field: InputField) {
+        let Ok(value) = std::mem::take(&mut self.buffer).parse::<u32>() else {
+            return;
+        };
+
+        match field {
+            InputField::Line => self.input.line = value,
+            InputField::Course => self.input.course = value,
+            InputField::Destination => {
+                self.input.destination = value;
+                send_message(
+                    &DestinationCode { code: value },
+                    [MessageTarget::Broadcast {
+                        across_couplings: true,
+                        include_self: true,
+                    }],
+                );
+            }
+        }
+
+        send_message(
+            &self.input,
+            [MessageTarget::Broadcast {
+                across_couplings: true,
+                include_self: true,
+            }],
+        );
+    }
+
+    fn render(&self) -> Vec<String> {
+        let mut rows = match self.page {
+            TerminalPage::Main => {
+                let mut rows = vec![
+                    format!("Li {}  Kurs {}", self.input.line, self.input.course),
+                    format!("Ziel {}", self.input.destination),
+                ];
+                if !self.faults.is_empty() {
+                    rows.push(format!(
+                        "Störungen {} ({} neu)",
+                        self.faults.len(),
+                        self.unacknowledged()
+                    ));
+                }
+                rows
+            }
+            TerminalPage::Menu(selected) => {
+                let entries: Vec<String> = MENU
+                    .iter()
+                    .enumerate()
+                    .map(|(i, entry)| {
+                        let cursor = if i == selected { '>' } else { ' ' };
+                        format!("{cursor}{} {entry}", i + 1)
+                    })
+                    .collect();
+                let mut rows = vec!["Menü".to_string()];
+                rows.extend(self.window(entries, selected));
+                rows
+            }
+            TerminalPage::Input(field) => vec![
+                format!("{} eingeben", field.title()),
+                format!("{}_", self.buffer),
+            ],
+            TerminalPage::Faults(selected) => {
+                if self.faults.is_empty() {
+                    vec!["Störungen".to_string(), "keine".to_string()]
+                } else {
+                    let entries: Vec<String> = self
+                        .faults
+                        .iter()
+                        .enumerate()
+                        .map(|(i, fault)| {
+                            let cursor = if i == selected { '>' } else { ' ' };
+                            let new = if fault.acknowledged { ' ' } else { '*' };
+                            format!("{cursor}{new}{}", fault.text())
+                        })
+                        .collect();
+                    let mut rows =
+                        vec![format!("Störungen {}/{}", selected + 1, self.faults.len())];
+                    rows.extend(self.window(entries, selected));
+                    rows
+                }
+            }
+            TerminalPage::Doors => {
+                let mut rows = vec!["Türen".to_string()];
+                rows.extend(self.doors.chunks(2).enumerate().map(|(i, pair)| {
+                    pair.iter()
+                        .enumerate()
+                        .map(|(j, state)| {
+                            let text = match state {
+                                DoorState::Closed => "zu",
+                                DoorState::Open => "auf",
+                                DoorState::Other => "--",
+                            };
+                            format!("T{} {text:<4}", i * 2 + j + 1)
+                        })
+                        .collect::<String>()
+                }));
+                rows
+            }
+        };
+
+        rows.resize(self.rows, String::new());
+        rows
+    }
+
+    /// Entries below the title row, scrolled so that the selection is visible.
+    fn window(&self, entries: Vec<String>, selected: usize) -> Vec<String> {
+        let visible = self.rows - 1;
+        let first = (selected + 1).saturating_sub(visible);
+        entries.into_iter().skip(first).take(visible).collect()
+    }
+}
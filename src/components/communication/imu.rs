@@ -0,0 +1,271 @@
+//! Inductive data transmission ("Induktive Meldeübertragung", IMU).
+//!
+//! The [`InductiveTransmission`] is registered on the TrainBus as
+//! [`PeripheryKind::InductivTransmissionModul`]. Wayside points are placed on
+//! the track as sensors. When the vehicle passes a point, the IMU transmits a
+//! telegram with the line and the course received from the IBIS master. The
+//! telegram is written as JSON into a variable, so that the map can switch
+//! the traffic light. A priority request is confirmed by the traffic light
+//! controller, which lights the confirmation lamp in the cab until the
+//! vehicle checks out at the next point.
+
+use std::collections::HashMap;
+
+use lotus_script::prelude::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        general::delta,
+        light::Light,
+        variable::{get_var, set_var},
+    },
+    management::trainbus::{PeripheryKind, TrainBusPeriferie},
+    messages::ibis_messages::PassengerInfo,
+};
+
+/// Kind of a wayside point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImuPointKind {
+    /// Pre-registration at the traffic light ("Voranmeldung")
+    Registration,
+    /// Priority request at the traffic light ("Anforderung")
+    Request,
+    /// Check out after passing the traffic light ("Abmeldung")
+    Checkout,
+}
+
+/// Telegram transmitted to a wayside point.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImuTelegram {
+    /// Sensor ID of the wayside point
+    pub point: u32,
+    /// Kind of the wayside point
+    pub kind: ImuPointKind,
+    /// Line number
+    pub line: String,
+    /// Course number
+    pub course: String,
+}
+
+pub struct InductiveTransmissionBuilder {
+    slot_index: i32,
+
+    points: HashMap<u32, ImuPointKind>,
+
+    telegram_var: Option<String>,
+    confirm_var: Option<String>,
+    confirm_delay: f32,
+
+    lm_confirm: Light,
+}
+
+impl InductiveTransmissionBuilder {
+    /// Maps a sensor ID to a wayside point.
+    pub fn add_point(mut self, sensor: u32, kind: ImuPointKind) -> Self {
+        self.points.insert(sensor, kind);
+        self
+    }
+
+    /// String variable into which the last telegram is written as JSON.
+    pub fn telegram_var(mut self, name: impl Into<String>) -> Self {
+        self.telegram_var = Some(name.into());
+        self
+    }
+
+    /// Bool variable with which the map confirms the request. Without it the
+    /// request is confirmed automatically after the confirm delay.
+    pub fn confirm_var(mut self, name: impl Into<String>) -> Self {
+        self.confirm_var = Some(name.into());
+        self
+    }
+
+    /// Time in seconds until the traffic light controller confirms a request.
+    pub fn confirm_delay(mut self, confirm_delay: f32) -> Self {
+        self.confirm_delay = confirm_delay;
+        self
+    }
+
+    /// Confirmation lamp in the cab.
+    pub fn confirm_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_confirm = Light::new(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> InductiveTransmission {
+        InductiveTransmission {
+            trainbus: TrainBusPeriferie::new(
+                PeripheryKind::InductivTransmissionModul,
+                self.slot_index,
+            ),
+            points: self.points,
+            telegram_var: self.telegram_var,
+            confirm_var: self.confirm_var,
+            confirm_delay: self.confirm_delay,
+            lm_confirm: self.lm_confirm,
+            pending: Vec::new(),
+            confirm_timer: 0.0,
+            line: String::new(),
+            course: String::new(),
+            last_telegram: None,
+            requested: false,
+            confirmed: false,
+        }
+    }
+}
+
+/// Vehicle device transmitting line and course to wayside points.
+///
+/// # Example
+///
+/// ```rust
+/// let mut imu = InductiveTransmission::builder(40)
+///     .add_point(3001, ImuPointKind::Request)
+///     .add_point(3002, ImuPointKind::Checkout)
+///     .telegram_var("IMU_Telegram")
+///     .confirm_light("L_IMU_Confirm")
+///     .build();
+///
+/// // In on_message:
+/// imu.on_message(msg);
+///
+/// // In on_trigger:
+/// imu.on_trigger(sensor, entering);
+///
+/// // In the main loop:
+/// imu.tick(voltage > 0.5);
+/// ```
+pub struct InductiveTransmission {
+    trainbus: TrainBusPeriferie,
+
+    points: HashMap<u32, ImuPointKind>,
+
+    telegram_var: Option<String>,
+    confirm_var: Option<String>,
+    confirm_delay: f32,
+
+    lm_confirm: Light,
+
+    pending: Vec<u32>,
+    confirm_timer: f32,
+
+    /// Line received from the IBIS master
+    pub line: String,
+    /// Course received from the IBIS master
+    pub course: String,
+    /// Last transmitted telegram
+    pub last_telegram: Option<ImuTelegram>,
+    /// Whether a priority request is active
+    pub requested: bool,
+    /// Whether the active request is confirmed by the traffic light
+    pub confirmed: bool,
+}
+
+impl InductiveTransmission {
+    /// Creates a new IMU builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - Slot index with which the IMU registers on the TrainBus
+    pub fn builder(slot_index: i32) -> InductiveTransmissionBuilder {
+        InductiveTransmissionBuilder {
+            slot_index,
+            points: HashMap::new(),
+            telegram_var: None,
+            confirm_var: None,
+            confirm_delay: 1.0,
+            lm_confirm: Light::new(None),
+        }
+    }
+
+    /// Receives line and course of the IBIS master.
+    pub fn on_message(&mut self, msg: Message) {
+        self.trainbus.on_message(msg.clone());
+
+        msg.handle::<PassengerInfo>(|m| {
+            self.line = m.line;
+            self.course = m.course;
+            Ok(())
+        })
+        .expect("PassengerInfo: message handle failed");
+    }
+
+    /// Handles sensor trigger events, unknown sensor IDs are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor` - The ID of the sensor that triggered
+    /// * `entering` - `true` if the vehicle enters the zone, `false` if leaving
+    pub fn on_trigger(&mut self, sensor: u32, entering: bool) {
+        if entering && self.points.contains_key(&sensor) {
+            self.pending.push(sensor);
+        }
+    }
+
+    /// Transmits the telegrams and updates the confirmation.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the IMU is supplied, no telegrams are sent without
+    pub fn tick(&mut self, power: bool) {
+        if !power {
+            self.pending.clear();
+            self.requested = false;
+            self.confirmed = false;
+            self.lm_confirm.set_brightness(0.0);
+            return;
+        }
+
+        self.trainbus.tick();
+
+        for sensor in std::mem::take(&mut self.pending) {
+            let kind = self.points[&sensor];
+            self.transmit(sensor, kind);
+
+            match kind {
+                ImuPointKind::Request => {
+                    self.requested = true;
+                    self.confirmed = false;
+                    self.confirm_timer = self.confirm_delay;
+                    if let Some(var) = &self.confirm_var {
+                        set_var(var, false);
+                    }
+                }
+                ImuPointKind::Checkout => {
+                    self.requested = false;
+                    self.confirmed = false;
+                }
+                ImuPointKind::Registration => {}
+            }
+        }
+
+        if self.requested && !self.confirmed {
+            self.confirmed = match &self.confirm_var {
+                Some(var) => get_var::<bool>(var),
+                None => {
+                    self.confirm_timer -= delta();
+                    self.confirm_timer <= 0.0
+                }
+            };
+        }
+
+        self.lm_confirm.set_brightness(self.confirmed as u8 as f32);
+    }
+
+    fn transmit(&mut self, point: u32, kind: ImuPointKind) {
+        let telegram = ImuTelegram {
+            point,
+            kind,
+            line: self.line.clone(),
+            course: self.course.clone(),
+        };
+
+        if let Some(var) = &self.telegram_var {
+            if let Ok(json) = serde_json::to_string(&telegram) {
+                set_var(var, json);
+            }
+        }
+
+        self.last_telegram = Some(telegram);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod ibis_terminal;
+pub mod imu;
+pub mod printer;
+pub mod radio;
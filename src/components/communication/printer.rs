@@ -0,0 +1,423 @@
+//! Thermal printer of a car ("Drucker", DRU).
+//!
+//! The [`Printer`] is registered on the TrainBus as [`PeripheryKind::Printer`]
+//! and prints the [`PrintJob`]s sent by the IBIS or the ticket machine one
+//! after the other. Each job is answered with a [`PrintResult`]. Every printed
+//! line uses up paper; once the roll is empty, the running job is lost and no
+//! further jobs are accepted. Now and then the paper jams during a job.
+//!
+//! A jam or an empty roll is cleared by opening the cover, removing the paper
+//! or inserting a new roll and closing the cover again. Jobs received in the
+//! meantime are rejected right away. Jams, an empty and a nearly empty roll
+//! and an open cover are reported as faults to the TrainBus.
+
+use std::collections::VecDeque;
+
+use lotus_script::{
+    message::{send_message, MessageTarget},
+    prelude::Message,
+};
+
+use crate::{
+    api::{
+        general::delta,
+        light::Light,
+        random::gen_f32,
+        sound::Sound,
+        variable::{get_var, set_var},
+    },
+    elements::tech::buttons::PushButton,
+    management::trainbus::{PeripheryFault, PeripheryKind, TrainBusPeriferie},
+    messages::ibis_messages::{PrintJob, PrintResult},
+};
+
+/// Blank lines fed after each job to tear off the print
+const FEED_LINES: f32 = 3.0;
+
+/// State of the printer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrinterState {
+    /// Waiting for jobs
+    #[default]
+    Ready,
+    /// Printing the current job
+    Printing,
+    /// Paper jammed, cleared with the cover
+    Jammed,
+    /// Paper roll empty
+    PaperOut,
+    /// Cover open
+    CoverOpen,
+}
+
+pub struct PrinterBuilder {
+    slot_index: i32,
+
+    capacity: f32,
+    low_level: f32,
+    line_time: f32,
+    jam_probability: f32,
+    persist_var: Option<String>,
+    output_var: Option<String>,
+
+    cover: Option<PushButton>,
+    lm_fault: Light,
+    snd_print: Sound,
+    snd_cut: Sound,
+}
+
+impl PrinterBuilder {
+    /// Paper of a full roll in lines.
+    pub fn capacity(mut self, lines: f32) -> Self {
+        self.capacity = lines;
+        self
+    }
+
+    /// Share of paper from 0.0 to 1.0 below which the roll is reported as
+    /// nearly empty.
+    pub fn low_level(mut self, level: f32) -> Self {
+        self.low_level = level;
+        self
+    }
+
+    /// Time in seconds to print one line.
+    pub fn line_time(mut self, time: f32) -> Self {
+        self.line_time = time;
+        self
+    }
+
+    /// Probability from 0.0 to 1.0 that a job jams the paper.
+    pub fn jam_probability(mut self, probability: f32) -> Self {
+        self.jam_probability = probability;
+        self
+    }
+
+    /// Float variable in which the paper used from the roll is stored.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// String variable showing the last print, one line per job line.
+    pub fn output_var(mut self, name: impl Into<String>) -> Self {
+        self.output_var = Some(name.into());
+        self
+    }
+
+    /// Toggle button opening and closing the cover.
+    pub fn cover(mut self, button: PushButton) -> Self {
+        self.cover = Some(button);
+        self
+    }
+
+    /// Fault lamp of the printer.
+    pub fn lm_fault(mut self, name: impl Into<String>) -> Self {
+        self.lm_fault = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Looping sound of the print head and paper feed.
+    pub fn snd_print(mut self, name: impl Into<String>) -> Self {
+        self.snd_print = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the paper cutter at the end of a job.
+    pub fn snd_cut(mut self, name: impl Into<String>) -> Self {
+        self.snd_cut = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> Printer {
+        let paper = match &self.persist_var {
+            Some(var_name) => (self.capacity - get_var::<f32>(var_name)).clamp(0.0, self.capacity),
+            None => self.capacity,
+        };
+
+        Printer {
+            trainbus: TrainBusPeriferie::new(PeripheryKind::Printer, self.slot_index),
+            capacity: self.capacity,
+            low_level: self.low_level,
+            line_time: self.line_time,
+            jam_probability: self.jam_probability,
+            persist_var: self.persist_var,
+            output_var: self.output_var,
+            cover: self.cover,
+            lm_fault: self.lm_fault,
+            snd_print: self.snd_print,
+            snd_cut: self.snd_cut,
+            queue: VecDeque::new(),
+            job: None,
+            progress: 0.0,
+            jam_at: None,
+            state: if paper > 0.0 {
+                PrinterState::Ready
+            } else {
+                PrinterState::PaperOut
+            },
+            paper,
+            cover_open: false,
+            last_print: Vec::new(),
+        }
+    }
+}
+
+/// Printer with job queue, paper supply and jams.
+///
+/// # Example
+///
+/// ```rust
+/// let mut printer = Printer::builder(60)
+///     .capacity(1500.0)
+///     .jam_probability(0.01)
+///     .persist("Drucker_Papier")
+///     .output_var("Drucker_Ausgabe")
+///     .cover(PushButton::builder_toggle_value_on_press("Drucker_Klappe", "Drucker_Klappe", None).build())
+///     .lm_fault("L_Drucker_Stoerung")
+///     .snd_print("Snd_Drucker")
+///     .build();
+///
+/// // In on_message:
+/// printer.on_message(msg);
+///
+/// // In the main loop:
+/// printer.tick(voltage > 0.5);
+/// ```
+pub struct Printer {
+    trainbus: TrainBusPeriferie,
+
+    capacity: f32,
+    low_level: f32,
+    line_time: f32,
+    jam_probability: f32,
+    persist_var: Option<String>,
+    output_var: Option<String>,
+
+    cover: Option<PushButton>,
+    lm_fault: Light,
+    snd_print: Sound,
+    snd_cut: Sound,
+
+    queue: VecDeque<PrintJob>,
+    job: Option<PrintJob>,
+    progress: f32,
+    jam_at: Option<f32>,
+
+    /// Current state
+    pub state: PrinterState,
+    /// Paper left on the roll in lines
+    pub paper: f32,
+    /// Whether the cover is open
+    pub cover_open: bool,
+    /// Lines of the last completed print
+    pub last_print: Vec<String>,
+}
+
+impl Printer {
+    /// Creates a new printer builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - Slot index with which the printer registers on the TrainBus
+    pub fn builder(slot_index: i32) -> PrinterBuilder {
+        PrinterBuilder {
+            slot_index,
+            capacity: 2000.0,
+            low_level: 0.1,
+            line_time: 0.15,
+            jam_probability: 0.005,
+            persist_var: None,
+            output_var: None,
+            cover: None,
+            lm_fault: Light::new(None),
+            snd_print: Sound::new_simple(None),
+            snd_cut: Sound::new_simple(None),
+        }
+    }
+
+    /// Receives the print jobs.
+    pub fn on_message(&mut self, msg: Message) {
+        self.trainbus.on_message(msg.clone());
+
+        msg.handle::<PrintJob>(|m| {
+            if self.accepts_jobs() {
+                self.queue.push_back(m);
+            } else {
+                Self::answer(m.id, false);
+            }
+            Ok(())
+        })
+        .expect("PrintJob: message handle failed");
+    }
+
+    /// Opens or closes the cover, e.g. from an MFD. Closing the cover clears
+    /// a jam.
+    pub fn set_cover(&mut self, open: bool) {
+        if open == self.cover_open {
+            return;
+        }
+        self.cover_open = open;
+
+        if open {
+            self.abort();
+            self.state = PrinterState::CoverOpen;
+        } else {
+            self.state = if self.paper > 0.0 {
+                PrinterState::Ready
+            } else {
+                PrinterState::PaperOut
+            };
+        }
+    }
+
+    /// Inserts a new paper roll, only possible with the cover open.
+    pub fn refill(&mut self) {
+        if self.cover_open {
+            self.paper = self.capacity;
+            self.store();
+        }
+    }
+
+    /// Paper level from 0.0 (empty) to 1.0 (full roll).
+    pub fn paper_level(&self) -> f32 {
+        if self.capacity > 0.0 {
+            self.paper / self.capacity
+        } else {
+            0.0
+        }
+    }
+
+    /// Updates the cover, prints the queued jobs and reports the faults.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the printer is supplied
+    pub fn tick(&mut self, power: bool) {
+        if let Some(cover) = &mut self.cover {
+            cover.tick();
+            let open = cover.value;
+            self.set_cover(open);
+        }
+
+        if !power {
+            // Ohne Versorgung gehen der laufende und die wartenden Aufträge verloren
+            self.abort();
+            if self.state == PrinterState::Printing {
+                self.state = PrinterState::Ready;
+            }
+            self.snd_print.start_stop(false);
+            self.lm_fault.set_brightness(0.0);
+            return;
+        }
+
+        match self.state {
+            PrinterState::Ready => {
+                if let Some(job) = self.queue.pop_front() {
+                    self.start(job);
+                }
+            }
+            PrinterState::Printing => self.print(),
+            _ => {}
+        }
+
+        self.snd_print
+            .start_stop(self.state == PrinterState::Printing);
+
+        let fault = self.fault();
+        self.lm_fault
+            .set_brightness((fault != PeripheryFault::Ok) as u8 as f32);
+        self.trainbus.tick();
+        self.trainbus.set_defect(fault);
+    }
+
+    //===================================================================
+    // Internal
+    //===================================================================
+
+    fn accepts_jobs(&self) -> bool {
+        matches!(self.state, PrinterState::Ready | PrinterState::Printing)
+    }
+
+    fn start(&mut self, job: PrintJob) {
+        let length = job.lines.len() as f32 + FEED_LINES;
+        self.jam_at = (gen_f32(0.0..1.0) < self.jam_probability).then(|| gen_f32(0.0..length));
+        self.progress = 0.0;
+        self.job = Some(job);
+        self.state = PrinterState::Printing;
+    }
+
+    fn print(&mut self) {
+        let Some(job) = &self.job else {
+            self.state = PrinterState::Ready;
+            return;
+        };
+        let length = job.lines.len() as f32 + FEED_LINES;
+
+        let step = (delta() / self.line_time.max(0.001))
+            .min(length - self.progress)
+            .min(self.paper);
+        self.progress += step;
+        self.paper -= step;
+        self.store();
+
+        if self.jam_at.is_some_and(|at| self.progress >= at) {
+            self.abort();
+            self.state = PrinterState::Jammed;
+        } else if self.progress >= length {
+            if let Some(job) = self.job.take() {
+                self.snd_cut.start();
+                Self::answer(job.id, true);
+                if let Some(var_name) = &self.output_var {
+                    set_var(var_name, job.lines.join("\n"));
+                }
+                self.last_print = job.lines;
+            }
+            self.state = PrinterState::Ready;
+        } else if self.paper <= 0.0 {
+            self.abort();
+            self.state = PrinterState::PaperOut;
+        }
+    }
+
+    /// Rejects the current and all queued jobs.
+    fn abort(&mut self) {
+        if let Some(job) = self.job.take() {
+            Self::answer(job.id, false);
+        }
+        for job in std::mem::take(&mut self.queue) {
+            Self::answer(job.id, false);
+        }
+        self.jam_at = None;
+    }
+
+    fn answer(id: u32, printed: bool) {
+        send_message(
+            &PrintResult { id, printed },
+            [MessageTarget::Broadcast {
+                across_couplings: false,
+                include_self: true,
+            }],
+        );
+    }
+
+    fn fault(&self) -> PeripheryFault {
+        match self.state {
+            PrinterState::Jammed | PrinterState::CoverOpen => PeripheryFault::Disrupted,
+            PrinterState::PaperOut => PeripheryFault::Undefined {
+                short_text: "Kein Papier".to_string(),
+                long_text: "Papierrolle leer".to_string(),
+            },
+            _ if self.paper_level() < self.low_level => PeripheryFault::Undefined {
+                short_text: "Papier".to_string(),
+                long_text: "Papierrolle fast leer".to_string(),
+            },
+            _ => PeripheryFault::Ok,
+        }
+    }
+
+    fn store(&self) {
+        if let Some(var_name) = &self.persist_var {
+            set_var(var_name, self.capacity - self.paper);
+        }
+    }
+}
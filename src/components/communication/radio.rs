@@ -0,0 +1,389 @@
+//! Cab radio ("Funk-Modul").
+//!
+//! The [`Radio`] is registered on the TrainBus as [`PeripheryKind::RadioModul`].
+//! The driver selects the channel with a step switch, requests a call to the
+//! dispatcher with the call button and acknowledges received messages with
+//! the acknowledge button. Dispatcher messages are canned voice recordings
+//! which are triggered by a scenario, either with [`Radio::dispatch`] or with
+//! the message id written into the trigger variable. A message is only heard
+//! on its channel; every transmission starts and ends with a burst of squelch
+//! noise.
+//!
+//! Received messages and call requests are kept in a log, which is written as
+//! JSON into a variable for the MFD.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        general::delta,
+        light::Light,
+        sound::Sound,
+        variable::{get_var, set_var},
+    },
+    elements::tech::{buttons::PushButton, switches::StepSwitch},
+    management::trainbus::{PeripheryKind, TrainBusPeriferie},
+};
+
+/// Number of entries kept in the radio log
+const LOG_LEN: usize = 20;
+
+/// Entry in the radio log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RadioLogEntry {
+    /// Channel of the transmission
+    pub channel: i32,
+    /// Text of the message
+    pub text: String,
+    /// Whether the entry is a call of the driver
+    pub outgoing: bool,
+    /// Whether the driver has acknowledged the message
+    pub acknowledged: bool,
+}
+
+/// Canned dispatcher voice message.
+#[derive(Debug)]
+struct DispatcherMessage {
+    id: String,
+    channel: Option<i32>,
+    text: String,
+    duration: f32,
+    snd: Sound,
+}
+
+pub struct RadioBuilder {
+    slot_index: i32,
+
+    channel_selector: Option<StepSwitch>,
+    call_button: Option<PushButton>,
+    ack_button: Option<PushButton>,
+
+    messages: Vec<DispatcherMessage>,
+    trigger_var: Option<String>,
+    log_var: Option<String>,
+
+    squelch_time: f32,
+
+    lm_call: Light,
+    lm_message: Light,
+
+    snd_squelch: Sound,
+}
+
+impl RadioBuilder {
+    /// Step switch selecting the channel, the switch value is the channel.
+    pub fn channel_selector(mut self, switch: StepSwitch) -> Self {
+        self.channel_selector = Some(switch);
+        self
+    }
+
+    /// Push button requesting a call to the dispatcher.
+    pub fn call_button(mut self, button: PushButton) -> Self {
+        self.call_button = Some(button);
+        self
+    }
+
+    /// Push button acknowledging the received messages.
+    pub fn ack_button(mut self, button: PushButton) -> Self {
+        self.ack_button = Some(button);
+        self
+    }
+
+    /// Adds a canned dispatcher voice message.
+    ///
+    /// * `id` - Id with which the message is triggered
+    /// * `channel` - Channel of the message, `None` is heard on all channels
+    /// * `sound_name` - Name of the voice recording
+    /// * `duration` - Length of the recording in seconds
+    /// * `text` - Text of the message shown in the log
+    pub fn dispatcher_message(
+        mut self,
+        id: impl Into<String>,
+        channel: Option<i32>,
+        sound_name: impl Into<String>,
+        duration: f32,
+        text: impl Into<String>,
+    ) -> Self {
+        self.messages.push(DispatcherMessage {
+            id: id.into(),
+            channel,
+            text: text.into(),
+            duration,
+            snd: Sound::new_simple(Some(&sound_name.into())),
+        });
+        self
+    }
+
+    /// String variable into which a scenario writes the id of a message to send.
+    pub fn trigger_var(mut self, name: impl Into<String>) -> Self {
+        self.trigger_var = Some(name.into());
+        self
+    }
+
+    /// String variable into which the log is written as JSON for the MFD.
+    pub fn log_var(mut self, name: impl Into<String>) -> Self {
+        self.log_var = Some(name.into());
+        self
+    }
+
+    /// Squelch noise at the start and the end of each transmission.
+    pub fn snd_squelch(mut self, name: impl Into<String>, squelch_time: f32) -> Self {
+        self.snd_squelch = Sound::new_simple(Some(&name.into()));
+        self.squelch_time = squelch_time;
+        self
+    }
+
+    /// Lamps of the pending call and of unacknowledged messages.
+    pub fn lights(mut self, call: impl Into<String>, message: impl Into<String>) -> Self {
+        self.lm_call = Light::new(Some(&call.into()));
+        self.lm_message = Light::new(Some(&message.into()));
+        self
+    }
+
+    pub fn build(self) -> Radio {
+        let channel = self
+            .channel_selector
+            .as_ref()
+            .map_or(0, |selector| selector.value(true));
+
+        Radio {
+            trainbus: TrainBusPeriferie::new(PeripheryKind::RadioModul, self.slot_index),
+            channel_selector: self.channel_selector,
+            call_button: self.call_button,
+            ack_button: self.ack_button,
+            messages: self.messages,
+            trigger_var: self.trigger_var,
+            log_var: self.log_var,
+            squelch_time: self.squelch_time,
+            lm_call: self.lm_call,
+            lm_message: self.lm_message,
+            snd_squelch: self.snd_squelch,
+            queue: Vec::new(),
+            playing: None,
+            squelch_timer: 0.0,
+            channel,
+            call_pending: false,
+            log: Vec::new(),
+        }
+    }
+}
+
+/// Cab radio with channel selector, dispatcher messages and log.
+///
+/// # Example
+///
+/// ```rust
+/// let mut radio = Radio::builder(30)
+///     .channel_selector(StepSwitch::builder("Radio_Channel", Some(CockpitSide::A)).min(1).max(4).build())
+///     .call_button(PushButton::builder("Radio_Call", "Radio_Call", Some(CockpitSide::A)).build())
+///     .ack_button(PushButton::builder("Radio_Ack", "Radio_Ack", Some(CockpitSide::A)).build())
+///     .dispatcher_message("delay", Some(1), "Snd_Radio_Delay", 6.0, "Bitte Verspätung melden")
+///     .trigger_var("Scenario_Radio")
+///     .log_var("MFD_Radio_Log")
+///     .snd_squelch("Snd_Radio_Squelch", 0.3)
+///     .build();
+///
+/// // In the main loop:
+/// radio.tick(voltage > 0.5);
+/// ```
+pub struct Radio {
+    trainbus: TrainBusPeriferie,
+
+    channel_selector: Option<StepSwitch>,
+    call_button: Option<PushButton>,
+    ack_button: Option<PushButton>,
+
+    messages: Vec<DispatcherMessage>,
+    trigger_var: Option<String>,
+    log_var: Option<String>,
+
+    squelch_time: f32,
+
+    lm_call: Light,
+    lm_message: Light,
+
+    snd_squelch: Sound,
+
+    queue: Vec<usize>,
+    playing: Option<(usize, f32)>,
+    squelch_timer: f32,
+
+    /// Selected channel
+    pub channel: i32,
+    /// Whether a call of the driver waits for the answer of the dispatcher
+    pub call_pending: bool,
+    /// Log of received messages and calls, newest last
+    pub log: Vec<RadioLogEntry>,
+}
+
+impl Radio {
+    /// Creates a new radio builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - Slot index with which the radio registers on the TrainBus
+    pub fn builder(slot_index: i32) -> RadioBuilder {
+        RadioBuilder {
+            slot_index,
+            channel_selector: None,
+            call_button: None,
+            ack_button: None,
+            messages: Vec::new(),
+            trigger_var: None,
+            log_var: None,
+            squelch_time: 0.3,
+            lm_call: Light::new(None),
+            lm_message: Light::new(None),
+            snd_squelch: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the radio.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the radio is supplied
+    pub fn tick(&mut self, power: bool) {
+        if let Some(selector) = &mut self.channel_selector {
+            selector.tick();
+        }
+        let call = match &mut self.call_button {
+            Some(button) => {
+                button.tick();
+                button.is_just_pressed()
+            }
+            None => false,
+        };
+        let ack = match &mut self.ack_button {
+            Some(button) => {
+                button.tick();
+                button.is_just_pressed()
+            }
+            None => false,
+        };
+
+        if !power {
+            if let Some((idx, _)) = self.playing.take() {
+                self.messages[idx].snd.stop();
+            }
+            self.queue.clear();
+            self.snd_squelch.stop();
+            self.lm_call.set_brightness(0.0);
+            self.lm_message.set_brightness(0.0);
+            return;
+        }
+
+        self.trainbus.tick();
+
+        if let Some(var) = &self.trigger_var {
+            let id = get_var::<String>(var);
+            if !id.is_empty() {
+                set_var(var, String::new());
+                self.dispatch(&id);
+            }
+        }
+
+        if let Some(selector) = &self.channel_selector {
+            let channel = selector.value(true);
+            if channel != self.channel {
+                self.channel = channel;
+                self.squelch();
+            }
+        }
+
+        if call && !self.call_pending {
+            self.call_pending = true;
+            self.squelch();
+            self.add_log(RadioLogEntry {
+                channel: self.channel,
+                text: "Sprechwunsch".to_string(),
+                outgoing: true,
+                acknowledged: true,
+            });
+        }
+
+        if ack {
+            if let Some(entry) = self.log.iter_mut().rev().find(|e| !e.acknowledged) {
+                entry.acknowledged = true;
+                self.write_log();
+            }
+        }
+
+        self.update_playback();
+
+        self.lm_call.set_brightness(self.call_pending as u8 as f32);
+        self.lm_message
+            .set_brightness(self.log.iter().any(|e| !e.acknowledged) as u8 as f32);
+    }
+
+    /// Sends a dispatcher message, it is heard if its channel is selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the dispatcher message
+    pub fn dispatch(&mut self, id: &str) {
+        if let Some(idx) = self.messages.iter().position(|m| m.id == id) {
+            self.queue.push(idx);
+        }
+    }
+
+    fn update_playback(&mut self) {
+        self.squelch_timer -= delta();
+        if self.squelch_timer <= 0.0 {
+            self.snd_squelch.stop();
+        }
+
+        if let Some((idx, timer)) = &mut self.playing {
+            *timer -= delta();
+            if *timer <= 0.0 {
+                self.messages[*idx].snd.stop();
+                self.playing = None;
+                self.squelch();
+            }
+            return;
+        }
+
+        // Nur Meldungen des eingestellten Kanals sind zu hören
+        let channel = self.channel;
+        self.queue
+            .retain(|&idx| self.messages[idx].channel.is_none_or(|c| c == channel));
+
+        if self.queue.is_empty() || self.squelch_timer > 0.0 {
+            return;
+        }
+
+        let idx = self.queue.remove(0);
+        self.messages[idx].snd.start();
+        self.playing = Some((idx, self.messages[idx].duration));
+
+        // Antwort des Fahrdienstleiters beendet den Sprechwunsch
+        self.call_pending = false;
+        self.add_log(RadioLogEntry {
+            channel,
+            text: self.messages[idx].text.clone(),
+            outgoing: false,
+            acknowledged: false,
+        });
+    }
+
+    fn squelch(&mut self) {
+        self.snd_squelch.start();
+        self.squelch_timer = self.squelch_time;
+    }
+
+    fn add_log(&mut self, entry: RadioLogEntry) {
+        self.log.push(entry);
+        if self.log.len() > LOG_LEN {
+            self.log.remove(0);
+        }
+        self.write_log();
+    }
+
+    fn write_log(&self) {
+        if let Some(var) = &self.log_var {
+            if let Ok(json) = serde_json::to_string(&self.log) {
+                set_var(var, json);
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Impact evaluation when coupling.
+//!
+//! The [`CouplingImpact`] watches a coupling and evaluates the closing speed
+//! at the moment the vehicles are coupled. The impact sound is scaled with the
+//! speed. Above the allowed speed the coupling is refused and the coupler is
+//! marked as damaged; a damaged coupler must not connect its electric coupler
+//! until it is repaired.
+
+use lotus_extra::vehicle::CockpitSide;
+use lotus_script::message::Coupling;
+
+use crate::api::{
+    coupler::ApiCoupler, key_event::KeyEvent, mock_enums::CouplingState, sound::Sound,
+};
+
+pub struct CouplingImpactBuilder {
+    coupling: Coupling,
+
+    max_speed: f32,
+    full_volume_speed: f32,
+
+    key_repair: KeyEvent,
+
+    snd_impact: Sound,
+    snd_damage: Sound,
+}
+
+impl CouplingImpactBuilder {
+    /// Closing speed in m/s above which the coupling is refused and damaged.
+    pub fn max_speed(mut self, max_speed: f32) -> Self {
+        self.max_speed = max_speed;
+        self
+    }
+
+    /// Closing speed in m/s at which the impact sound reaches full volume.
+    pub fn full_volume_speed(mut self, full_volume_speed: f32) -> Self {
+        self.full_volume_speed = full_volume_speed;
+        self
+    }
+
+    /// Key event repairing the coupler, e.g. in the workshop menu.
+    pub fn repair_event(mut self, name: impl Into<String>, cab_side: Option<CockpitSide>) -> Self {
+        self.key_repair = KeyEvent::new(Some(&name.into()), cab_side);
+        self
+    }
+
+    /// Impact sound, the volume is scaled with the closing speed.
+    pub fn snd_impact(mut self, name: impl Into<String>, volume_name: Option<&str>) -> Self {
+        self.snd_impact = Sound::new(Some(&name.into()), volume_name, None);
+        self
+    }
+
+    /// Sound played additionally if the coupler is damaged.
+    pub fn snd_damage(mut self, name: impl Into<String>) -> Self {
+        self.snd_damage = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> CouplingImpact {
+        let api_coupler = ApiCoupler::new(self.coupling);
+
+        CouplingImpact {
+            max_speed: self.max_speed,
+            full_volume_speed: self.full_volume_speed,
+            coupled_last: api_coupler.is_coupled(),
+            api_coupler,
+            key_repair: self.key_repair,
+            snd_impact: self.snd_impact,
+            snd_damage: self.snd_damage,
+            refused: false,
+            last_impact_speed: 0.0,
+            damaged: false,
+        }
+    }
+}
+
+/// Evaluates the closing speed when coupling.
+///
+/// # Example
+///
+/// ```rust
+/// let mut impact = CouplingImpact::builder(Coupling::Front)
+///     .max_speed(2.0)
+///     .snd_impact("Snd_Coupler_Impact", Some("Snd_Coupler_Impact_Vol"))
+///     .build();
+///
+/// // In the main loop:
+/// impact.tick(v_ground());
+/// coupler.damaged = impact.damaged;
+/// coupler.tick(voltage > 0.5);
+/// ```
+pub struct CouplingImpact {
+    max_speed: f32,
+    full_volume_speed: f32,
+
+    api_coupler: ApiCoupler,
+    key_repair: KeyEvent,
+
+    snd_impact: Sound,
+    snd_damage: Sound,
+
+    coupled_last: bool,
+    refused: bool,
+
+    /// Closing speed in m/s of the last coupling
+    pub last_impact_speed: f32,
+    /// Whether the coupler was damaged by a too hard impact
+    pub damaged: bool,
+}
+
+impl CouplingImpact {
+    /// Creates a new impact evaluation builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `coupling` - The coupling (front or rear) to watch
+    pub fn builder(coupling: Coupling) -> CouplingImpactBuilder {
+        CouplingImpactBuilder {
+            coupling,
+            max_speed: 2.0,
+            full_volume_speed: 1.5,
+            key_repair: KeyEvent::new(None, None),
+            snd_impact: Sound::new_simple(None),
+            snd_damage: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the evaluation.
+    ///
+    /// # Arguments
+    ///
+    /// * `closing_speed` - Speed in m/s at which the vehicles approach each
+    ///   other, usually the own speed when coupling onto a standing vehicle
+    pub fn tick(&mut self, closing_speed: f32) {
+        if self.key_repair.is_just_pressed() {
+            self.repair();
+        }
+
+        let coupled = self.api_coupler.is_coupled();
+
+        if coupled && !self.coupled_last {
+            let speed = closing_speed.abs();
+            self.last_impact_speed = speed;
+
+            self.snd_impact
+                .update_volume((speed / self.full_volume_speed.max(0.01)).min(1.0));
+            self.snd_impact.start();
+
+            // Zu harter Aufprall: Kuppeln verweigern und Kupplung beschädigen
+            if speed > self.max_speed {
+                self.api_coupler
+                    .set_coupling_state(CouplingState::Deactivated);
+                self.refused = true;
+                self.damaged = true;
+                self.snd_damage.start();
+            }
+        }
+
+        // Nach dem Trennen wieder kuppelbereit
+        if self.refused && !coupled {
+            self.api_coupler.set_coupling_state(CouplingState::Ready);
+            self.refused = false;
+        }
+
+        self.coupled_last = coupled;
+    }
+
+    /// Repairs the coupler, the electric coupler may connect again.
+    pub fn repair(&mut self) {
+        self.damaged = false;
+    }
+}
@@ -0,0 +1,305 @@
+//! Automatic coupler (Scharfenberg type) with electric coupler head.
+//!
+//! The [`ScharfenbergCoupler`] models the coupling sequence of an automatic
+//! coupler: the mechanical latch locks as soon as two vehicles are pushed
+//! together, the cover of the electric contacts opens and after a short delay
+//! the electric coupler is connected and reported via [`EcouplerSender`].
+//! The uncouple push button runs the sequence backwards: the electric coupler
+//! disconnects, the cover closes and the latch is released.
+//!
+//! The state of the contact cover is passed to the other vehicle with the
+//! [`Bag`](crate::messages::gt6n_coupling_messages::Bag) message. A coupler
+//! damaged by a too hard impact (see [`CouplingImpact`](super::impact::CouplingImpact))
+//! keeps the electric coupler disconnected.
+
+use lotus_extra::vehicle::CockpitSide;
+use lotus_script::message::Coupling;
+
+use crate::{
+    api::{
+        animation::Animation, coupler::ApiCoupler, general::delta, mock_enums::CouplingState,
+        sound::Sound,
+    },
+    elements::tech::buttons::PushButton,
+    management::trainbus::EcouplerSender,
+    messages::gt6n_coupling_messages::send_bag,
+};
+
+/// State of the mechanical latch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LatchState {
+    /// Latch is tensioned, the coupler locks on contact
+    #[default]
+    Ready,
+    /// Latch is locked, the vehicles are mechanically coupled
+    Locked,
+    /// Latch was released by the uncouple sequence
+    Released,
+}
+
+pub struct ScharfenbergCouplerBuilder {
+    coupling: Coupling,
+
+    cover_time: f32,
+    connect_delay: f32,
+    rearm_time: f32,
+
+    cover_anim: Animation,
+    uncouple_button: Option<PushButton>,
+
+    snd_latch: Sound,
+    snd_cover: Sound,
+    snd_release: Sound,
+}
+
+impl ScharfenbergCouplerBuilder {
+    /// Time in seconds the contact cover needs to open or close.
+    pub fn cover_time(mut self, cover_time: f32) -> Self {
+        self.cover_time = cover_time;
+        self
+    }
+
+    /// Time in seconds between the opened cover and the connected electric coupler.
+    pub fn connect_delay(mut self, connect_delay: f32) -> Self {
+        self.connect_delay = connect_delay;
+        self
+    }
+
+    /// Time in seconds after the separation until the latch is ready again.
+    pub fn rearm_time(mut self, rearm_time: f32) -> Self {
+        self.rearm_time = rearm_time;
+        self
+    }
+
+    /// Animation of the contact cover, 0.0 closed and 1.0 open.
+    pub fn cover_animation(mut self, name: impl Into<String>) -> Self {
+        self.cover_anim = Animation::new(Some(&name.into()));
+        self
+    }
+
+    /// Push button starting the uncouple sequence.
+    pub fn uncouple_button(
+        mut self,
+        animation_name: impl Into<String>,
+        event_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> Self {
+        self.uncouple_button =
+            Some(PushButton::builder(animation_name, event_name, cab_side).build());
+        self
+    }
+
+    /// Sound of the locking latch.
+    pub fn snd_latch(mut self, name: impl Into<String>) -> Self {
+        self.snd_latch = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the moving contact cover.
+    pub fn snd_cover(mut self, name: impl Into<String>) -> Self {
+        self.snd_cover = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the released latch.
+    pub fn snd_release(mut self, name: impl Into<String>) -> Self {
+        self.snd_release = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> ScharfenbergCoupler {
+        let api_coupler = ApiCoupler::new(self.coupling);
+
+        // Beim Start bereits gekuppelte Fahrzeuge sind vollständig verbunden
+        let coupled = api_coupler.is_coupled();
+
+        ScharfenbergCoupler {
+            cover_time: self.cover_time,
+            connect_delay: self.connect_delay,
+            rearm_time: self.rearm_time,
+            api_coupler,
+            ecoupler: EcouplerSender::new(),
+            cover_anim: self.cover_anim,
+            uncouple_button: self.uncouple_button,
+            snd_latch: self.snd_latch,
+            snd_cover: self.snd_cover,
+            snd_release: self.snd_release,
+            connect_timer: if coupled { self.connect_delay } else { 0.0 },
+            rearm_timer: 0.0,
+            cover_open_last: false,
+            latch: if coupled {
+                LatchState::Locked
+            } else {
+                LatchState::Ready
+            },
+            cover_pos: coupled as u8 as f32,
+            el_coupled: false,
+            damaged: false,
+        }
+    }
+}
+
+/// Automatic coupler with mechanical latch and electric coupler head.
+///
+/// # Example
+///
+/// ```rust
+/// let mut coupler = ScharfenbergCoupler::builder(Coupling::Front)
+///     .cover_animation("Coupler_F_Cover")
+///     .uncouple_button("Coupler_F_Uncouple", "Coupler_Uncouple", Some(CockpitSide::A))
+///     .snd_latch("Snd_Coupler_Latch")
+///     .build();
+///
+/// // In the main loop:
+/// coupler.tick(voltage > 0.5);
+/// ```
+pub struct ScharfenbergCoupler {
+    cover_time: f32,
+    connect_delay: f32,
+    rearm_time: f32,
+
+    api_coupler: ApiCoupler,
+    ecoupler: EcouplerSender,
+
+    cover_anim: Animation,
+    uncouple_button: Option<PushButton>,
+
+    snd_latch: Sound,
+    snd_cover: Sound,
+    snd_release: Sound,
+
+    connect_timer: f32,
+    rearm_timer: f32,
+    cover_open_last: bool,
+
+    /// State of the mechanical latch
+    pub latch: LatchState,
+    /// Position of the contact cover from 0.0 (closed) to 1.0 (open)
+    pub cover_pos: f32,
+    /// Whether the electric coupler is connected
+    pub el_coupled: bool,
+    /// Whether the coupler is damaged, blocks the electric coupler
+    pub damaged: bool,
+}
+
+impl ScharfenbergCoupler {
+    /// Creates a new coupler builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `coupling` - The coupling (front or rear) the coupler is mounted at
+    pub fn builder(coupling: Coupling) -> ScharfenbergCouplerBuilder {
+        ScharfenbergCouplerBuilder {
+            coupling,
+            cover_time: 1.5,
+            connect_delay: 1.0,
+            rearm_time: 2.0,
+            cover_anim: Animation::new(None),
+            uncouple_button: None,
+            snd_latch: Sound::new_simple(None),
+            snd_cover: Sound::new_simple(None),
+            snd_release: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the coupling sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the coupler control is supplied, the cover drive and
+    ///   the uncouple button need it
+    pub fn tick(&mut self, power: bool) {
+        let uncouple = match &mut self.uncouple_button {
+            Some(button) => {
+                button.tick();
+                button.is_just_pressed() && power
+            }
+            None => false,
+        };
+
+        self.update_latch(uncouple);
+        self.update_cover(power);
+
+        // E-Kupplung erst nach Ablauf der Verzögerung bei offener Abdeckung
+        if self.latch == LatchState::Locked && self.cover_pos >= 1.0 {
+            self.connect_timer = (self.connect_timer + delta()).min(self.connect_delay);
+        } else {
+            self.connect_timer = 0.0;
+        }
+
+        self.el_coupled = self.latch == LatchState::Locked
+            && self.cover_pos >= 1.0
+            && self.connect_timer >= self.connect_delay
+            && !self.damaged;
+
+        self.ecoupler
+            .update(self.el_coupled, self.api_coupler.coupler);
+    }
+
+    /// Whether the vehicles are mechanically coupled.
+    #[must_use]
+    pub fn mech_coupled(&self) -> bool {
+        self.latch == LatchState::Locked && self.api_coupler.is_coupled()
+    }
+
+    fn update_latch(&mut self, uncouple: bool) {
+        match self.latch {
+            LatchState::Ready => {
+                if self.api_coupler.is_coupled() {
+                    self.latch = LatchState::Locked;
+                    self.snd_latch.start();
+                }
+            }
+            LatchState::Locked => {
+                // Entkuppeln per Taster oder durch das andere Fahrzeug
+                if uncouple || !self.api_coupler.is_coupled() {
+                    self.latch = LatchState::Released;
+                    self.rearm_timer = self.rearm_time;
+                }
+            }
+            LatchState::Released => {
+                // Verriegelung erst nach geschlossener Abdeckung lösen
+                if self.cover_pos <= 0.0
+                    && self.api_coupler.coupling_state() == CouplingState::Coupled
+                {
+                    self.api_coupler
+                        .set_coupling_state(CouplingState::Deactivated);
+                    self.snd_release.start();
+                }
+
+                if !self.api_coupler.is_coupled() {
+                    self.rearm_timer -= delta();
+                    if self.rearm_timer <= 0.0 {
+                        self.api_coupler.set_coupling_state(CouplingState::Ready);
+                        self.latch = LatchState::Ready;
+                    }
+                }
+            }
+        }
+    }
+
+    fn update_cover(&mut self, power: bool) {
+        let target = (self.latch == LatchState::Locked) as u8 as f32;
+
+        if power && self.cover_pos != target {
+            let step = delta() / self.cover_time.max(0.01);
+            self.cover_pos = if target > self.cover_pos {
+                (self.cover_pos + step).min(target)
+            } else {
+                (self.cover_pos - step).max(target)
+            };
+            self.snd_cover.start();
+        } else {
+            self.snd_cover.stop();
+        }
+
+        self.cover_anim.set(self.cover_pos);
+
+        // Zustand der Abdeckung an das andere Fahrzeug melden
+        let cover_open = self.cover_pos > 0.5;
+        if cover_open != self.cover_open_last {
+            send_bag(cover_open, self.api_coupler.coupler);
+            self.cover_open_last = cover_open;
+        }
+    }
+}
@@ -1,23 +1,27 @@
 use std::f32::consts::PI;
 
-use lotus_extra::rand::gen_f32;
-use lotus_script::time::delta;
-
 use crate::{
     api::{
         animation::Animation,
-        general::mouse_move,
-        light::{BlinkRelais, Light},
-        sound::Sound,
+        general::{delta, mouse_move},
+        light::Light,
+        mock::{AnimationOutput, LightOutput, SoundOutput},
+        random::gen_f32,
+        sound::{Sound, SoundPool},
         vehicle_door::VehicleDoor,
     },
-    management::enums::door_enums::{DoorState, DoorTarget},
+    components::doors::{
+        door::{Door, DoorCycles},
+        door_warning::{ChimePattern, DoorWarning, DoorWarningBuilder},
+        obstacle::SensitiveEdgeDoor,
+    },
+    management::{
+        enums::door_enums::{DoorState, DoorTarget},
+        fault_manager::fault_active,
+    },
 };
 
-const DOORWARN_INTERVAL_IN: f32 = 0.777;
-const DOORWARN_INTERVAL_IN_HALF: f32 = DOORWARN_INTERVAL_IN / 2.0;
-
-pub struct AegElectricDoorBuilder {
+pub struct AegElectricDoorBuilder<A = Animation, S = Sound, L = Light> {
     id: usize,
     plug_radius: f32,
     shift: f32,
@@ -34,8 +38,8 @@ pub struct AegElectricDoorBuilder {
 
     pos: f32,
     speed: f32,
-    anim_x: Animation,
-    anim_y: Animation,
+    anim_x: A,
+    anim_y: A,
     close_timer: f32,
     regular_open_time: f32,
     min_open_time: f32,
@@ -45,40 +49,97 @@ pub struct AegElectricDoorBuilder {
 
     mouse_factor: f32,
 
+    fault: Option<String>,
+
     is_series_1: bool,
 
     state: DoorState,
 
     target: i32,
 
-    warn_relais: BlinkRelais,
-    lm_warn_in: Light,
+    warning: DoorWarningBuilder<S, L>,
+
+    cycle_spread: f32,
+    snd_close_end_alt: SoundPool<S>,
 
     emergency_door_unlock: bool,
     emergency_door_unlock_last: bool,
 
     open_flag: bool,
 
-    closed_while_warning: bool,
-
-    snd_open_start: Sound,
-    snd_open_end: Sound,
-    snd_close_start: Sound,
-    snd_close_end: Sound,
-    snd_door_close: Sound,
+    snd_open_start: S,
+    snd_open_end: S,
+    snd_close_start: S,
+    snd_close_end: S,
+    snd_door_close: S,
 
-    snd_open_start_2: Sound,
-    snd_open_end_2: Sound,
-    snd_close_start_2: Sound,
-    snd_close_end_2: Sound,
-    snd_door_close_2: Sound,
+    snd_open_start_2: S,
+    snd_open_end_2: S,
+    snd_close_start_2: S,
+    snd_close_end_2: S,
+    snd_door_close_2: S,
 
     pass_door: VehicleDoor,
-
-    snd_door_warn: Sound,
 }
 
 impl AegElectricDoorBuilder {
+    pub fn set_1st_series(
+        mut self,
+        sound_open_start_name: impl Into<String>,
+        sound_open_end_name: impl Into<String>,
+        sound_close_start_name: impl Into<String>,
+        sound_close_end_name: impl Into<String>,
+        sound_door_close_name: impl Into<String>,
+    ) -> Self {
+        self.is_series_1 = true;
+        self.snd_open_start = Sound::new_simple(Some(&sound_open_start_name.into()));
+        self.snd_open_end = Sound::new_simple(Some(&sound_open_end_name.into()));
+        self.snd_close_start = Sound::new_simple(Some(&sound_close_start_name.into()));
+        self.snd_close_end = Sound::new_simple(Some(&sound_close_end_name.into()));
+        self.snd_door_close = Sound::new_simple(Some(&sound_door_close_name.into()));
+
+        self.reflection_open = gen_f32(0.03..=0.05);
+        self.reflection_close = 0.05;
+        self
+    }
+
+    pub fn set_2nd_series(
+        mut self,
+        sound_open_start_name: impl Into<String>,
+        sound_open_end_name: impl Into<String>,
+        sound_close_start_name: impl Into<String>,
+        sound_close_end_name: impl Into<String>,
+        sound_door_close_name: impl Into<String>,
+    ) -> Self {
+        self.is_series_1 = false;
+        self.snd_open_start_2 = Sound::new_simple(Some(&sound_open_start_name.into()));
+        self.snd_open_end_2 = Sound::new_simple(Some(&sound_open_end_name.into()));
+        self.snd_close_start_2 = Sound::new_simple(Some(&sound_close_start_name.into()));
+        self.snd_close_end_2 = Sound::new_simple(Some(&sound_close_end_name.into()));
+        self.snd_door_close_2 = Sound::new_simple(Some(&sound_door_close_name.into()));
+        self.reflection_open = gen_f32(0.05..=0.07);
+        self.reflection_close = 0.07;
+        self
+    }
+
+    pub fn add_warning(
+        mut self,
+        light_name: impl Into<String>,
+        sound_name: impl Into<String>,
+    ) -> Self {
+        self.warning = self.warning.lamp(light_name).snd_interior(sound_name);
+        self
+    }
+
+    /// Adds an alternative sample of the closing end sound, one is chosen per cycle.
+    pub fn add_close_end_variant(mut self, name: impl Into<String>) -> Self {
+        self.snd_close_end_alt
+            .push(Sound::new_simple(Some(&name.into())));
+        self
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> AegElectricDoorBuilder<A, S, L> {
     pub fn plug_radius(mut self, plug_radius: f32) -> Self {
         self.plug_radius = plug_radius;
         self
@@ -140,47 +201,18 @@ impl AegElectricDoorBuilder {
         self
     }
 
-    pub fn set_1st_series(
-        mut self,
-        sound_open_start_name: impl Into<String>,
-        sound_open_end_name: impl Into<String>,
-        sound_close_start_name: impl Into<String>,
-        sound_close_end_name: impl Into<String>,
-        sound_door_close_name: impl Into<String>,
-    ) -> Self {
-        self.is_series_1 = true;
-        self.snd_open_start = Sound::new_simple(Some(&sound_open_start_name.into()));
-        self.snd_open_end = Sound::new_simple(Some(&sound_open_end_name.into()));
-        self.snd_close_start = Sound::new_simple(Some(&sound_close_start_name.into()));
-        self.snd_close_end = Sound::new_simple(Some(&sound_close_end_name.into()));
-        self.snd_door_close = Sound::new_simple(Some(&sound_door_close_name.into()));
-
-        self.reflection_open = gen_f32(0.03..=0.05);
-        self.reflection_close = 0.05;
-        self
-    }
-
-    pub fn set_2nd_series(
-        mut self,
-        sound_open_start_name: impl Into<String>,
-        sound_open_end_name: impl Into<String>,
-        sound_close_start_name: impl Into<String>,
-        sound_close_end_name: impl Into<String>,
-        sound_door_close_name: impl Into<String>,
-    ) -> Self {
-        self.is_series_1 = false;
-        self.snd_open_start_2 = Sound::new_simple(Some(&sound_open_start_name.into()));
-        self.snd_open_end_2 = Sound::new_simple(Some(&sound_open_end_name.into()));
-        self.snd_close_start_2 = Sound::new_simple(Some(&sound_close_start_name.into()));
-        self.snd_close_end_2 = Sound::new_simple(Some(&sound_close_end_name.into()));
-        self.snd_door_close_2 = Sound::new_simple(Some(&sound_door_close_name.into()));
-        self.reflection_open = gen_f32(0.05..=0.07);
-        self.reflection_close = 0.07;
+    /// Id of the fault in the fault manager with which the door drive fails.
+    pub fn fault(mut self, id: impl Into<String>) -> Self {
+        self.fault = Some(id.into());
         self
     }
 
     pub fn modify_warn_relais(mut self, interval: f32, on_time: f32, reset_time: f32) -> Self {
-        self.warn_relais = BlinkRelais::new(interval, on_time, reset_time);
+        self.warning = self.warning.pattern(ChimePattern::Pulsed {
+            interval,
+            on_time,
+            reset_time,
+        });
         self
     }
 
@@ -194,17 +226,20 @@ impl AegElectricDoorBuilder {
         self
     }
 
-    pub fn add_warning(
-        mut self,
-        light_name: impl Into<String>,
-        sound_name: impl Into<String>,
-    ) -> Self {
-        self.snd_door_warn = Sound::new_simple(Some(&sound_name.into()));
-        self.lm_warn_in = Light::new(Some(&light_name.into()));
+    /// Closing warning of the door, replaces
+    /// [`add_warning`](AegElectricDoorBuilder::add_warning).
+    pub fn door_warning(mut self, warning: DoorWarningBuilder<S, L>) -> Self {
+        self.warning = warning;
         self
     }
 
-    pub fn build(self) -> AegElectricDoor {
+    /// Maximum deviation of the drive speed from cycle to cycle, e.g. 0.05 for ±5 %.
+    pub fn cycle_variation(mut self, spread: f32) -> Self {
+        self.cycle_spread = spread;
+        self
+    }
+
+    pub fn build(self) -> AegElectricDoor<A, S, L> {
         AegElectricDoor {
             id: self.id,
             plug_radius: self.plug_radius,
@@ -229,15 +264,18 @@ impl AegElectricDoorBuilder {
             grabbing_a: self.grabbing_a,
             grabbing_b: self.grabbing_b,
             mouse_factor: self.mouse_factor,
+            fault: self.fault,
+            drive_factor: 1.0,
             is_series_1: self.is_series_1,
             state: self.state,
+            command: DoorTarget::default(),
             target: self.target,
-            warn_relais: self.warn_relais,
-            lm_warn_in: self.lm_warn_in,
+            warning: self.warning.build(),
+            cycles: DoorCycles::new(self.cycle_spread),
+            snd_close_end_alt: self.snd_close_end_alt,
             emergency_door_unlock: self.emergency_door_unlock,
             emergency_door_unlock_last: self.emergency_door_unlock_last,
             open_flag: self.open_flag,
-            closed_while_warning: self.closed_while_warning,
             snd_open_start: self.snd_open_start,
             snd_open_end: self.snd_open_end,
             snd_close_start: self.snd_close_start,
@@ -249,13 +287,12 @@ impl AegElectricDoorBuilder {
             snd_close_end_2: self.snd_close_end_2,
             snd_door_close_2: self.snd_door_close_2,
             pass_door: self.pass_door,
-            snd_door_warn: self.snd_door_warn,
         }
     }
 }
 
 #[derive(Debug)]
-pub struct AegElectricDoor {
+pub struct AegElectricDoor<A = Animation, S = Sound, L = Light> {
     id: usize,
     plug_radius: f32,
     shift: f32,
@@ -272,8 +309,8 @@ pub struct AegElectricDoor {
 
     pub pos: f32,
     speed: f32,
-    anim_x: Animation,
-    anim_y: Animation,
+    anim_x: A,
+    anim_y: A,
     close_timer: f32,
     regular_open_time: f32,
     min_open_time: f32,
@@ -282,37 +319,41 @@ pub struct AegElectricDoor {
 
     mouse_factor: f32,
 
+    fault: Option<String>,
+    /// Speed factor of the drive, e.g. reduced by wear
+    pub drive_factor: f32,
+
     is_series_1: bool,
 
     pub state: DoorState,
 
+    command: DoorTarget,
     target: i32,
 
-    warn_relais: BlinkRelais,
-    lm_warn_in: Light,
+    warning: DoorWarning<S, L>,
+
+    /// Open/close cycles of the door
+    pub cycles: DoorCycles,
+    snd_close_end_alt: SoundPool<S>,
 
     emergency_door_unlock: bool,
     emergency_door_unlock_last: bool,
 
     open_flag: bool,
 
-    closed_while_warning: bool,
+    snd_open_start: S,
+    snd_open_end: S,
+    snd_close_start: S,
+    snd_close_end: S,
+    snd_door_close: S,
 
-    snd_open_start: Sound,
-    snd_open_end: Sound,
-    snd_close_start: Sound,
-    snd_close_end: Sound,
-    snd_door_close: Sound,
-
-    snd_open_start_2: Sound,
-    snd_open_end_2: Sound,
-    snd_close_start_2: Sound,
-    snd_close_end_2: Sound,
-    snd_door_close_2: Sound,
+    snd_open_start_2: S,
+    snd_open_end_2: S,
+    snd_close_start_2: S,
+    snd_close_end_2: S,
+    snd_door_close_2: S,
 
     pass_door: VehicleDoor,
-
-    snd_door_warn: Sound,
 }
 
 impl AegElectricDoor {
@@ -321,6 +362,22 @@ impl AegElectricDoor {
         animation_x_name: impl Into<String>,
         animation_y_name: impl Into<String>,
     ) -> AegElectricDoorBuilder {
+        Self::builder_with_outputs(
+            id,
+            Animation::new(Some(&animation_x_name.into())),
+            Animation::new(Some(&animation_y_name.into())),
+        )
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> AegElectricDoor<A, S, L> {
+    /// Creates a builder on any outputs, e.g. the `Mock*` types of
+    /// [`mock`](crate::api::mock) in tests. Sounds and lamp start unset.
+    pub fn builder_with_outputs(id: usize, anim_x: A, anim_y: A) -> AegElectricDoorBuilder<A, S, L>
+    where
+        S: Default,
+        L: Default,
+    {
         AegElectricDoorBuilder {
             id,
             plug_radius: 0.06,
@@ -337,35 +394,35 @@ impl AegElectricDoor {
             reflection_close: 0.0,
             pos: 0.0,
             speed: 0.0,
-            anim_x: Animation::new(Some(&animation_x_name.into())),
-            anim_y: Animation::new(Some(&animation_y_name.into())),
+            anim_x,
+            anim_y,
             close_timer: 0.0,
             regular_open_time: 6.0,
             min_open_time: 2.0,
             grabbing_a: false,
             grabbing_b: false,
             mouse_factor: 1.0,
+            fault: None,
             is_series_1: false,
             state: DoorState::default(),
             target: 0,
-            warn_relais: BlinkRelais::new(DOORWARN_INTERVAL_IN, DOORWARN_INTERVAL_IN_HALF, 0.12),
-            lm_warn_in: Light::new(None),
+            warning: DoorWarningBuilder::default(),
+            cycle_spread: 0.0,
+            snd_close_end_alt: SoundPool::default(),
             emergency_door_unlock: false,
             emergency_door_unlock_last: false,
             open_flag: false,
-            closed_while_warning: false,
-            snd_open_start: Sound::new_simple(None),
-            snd_open_end: Sound::new_simple(None),
-            snd_close_start: Sound::new_simple(None),
-            snd_close_end: Sound::new_simple(None),
-            snd_door_close: Sound::new_simple(None),
-            snd_open_start_2: Sound::new_simple(None),
-            snd_open_end_2: Sound::new_simple(None),
-            snd_close_start_2: Sound::new_simple(None),
-            snd_close_end_2: Sound::new_simple(None),
-            snd_door_close_2: Sound::new_simple(None),
+            snd_open_start: S::default(),
+            snd_open_end: S::default(),
+            snd_close_start: S::default(),
+            snd_close_end: S::default(),
+            snd_door_close: S::default(),
+            snd_open_start_2: S::default(),
+            snd_open_end_2: S::default(),
+            snd_close_start_2: S::default(),
+            snd_close_end_2: S::default(),
+            snd_door_close_2: S::default(),
             pass_door: VehicleDoor::new(id, true, true),
-            snd_door_warn: Sound::new_simple(None),
         }
     }
 
@@ -382,10 +439,10 @@ impl AegElectricDoor {
             self.snd_door_close.start();
         }
         if (new_pos < 0.01 && self.pos >= 0.01) && self.is_series_1 {
-            self.snd_close_end.start();
+            self.start_close_end();
         }
         if (new_pos < 0.08 && self.pos >= 0.08) && !self.is_series_1 {
-            self.snd_close_end_2.start();
+            self.start_close_end();
         }
 
         if new_pos > 1.0 {
@@ -410,7 +467,23 @@ impl AegElectricDoor {
         }
 
         self.pos = new_pos;
+        self.update_animations();
+    }
+
+    /// Starts the closing end sound of the series or one of its variants.
+    fn start_close_end(&mut self) {
+        match self
+            .cycles
+            .sample(self.snd_close_end_alt.len() + 1)
+            .checked_sub(1)
+        {
+            Some(i) => self.snd_close_end_alt.start_sample(i),
+            None if self.is_series_1 => self.snd_close_end.start(),
+            None => self.snd_close_end_2.start(),
+        }
+    }
 
+    fn update_animations(&mut self) {
         if self.pos < 0.1 {
             self.anim_x
                 .set((self.pos * 5.0 * PI).sin() * self.plug_radius);
@@ -424,25 +497,12 @@ impl AegElectricDoor {
     }
 
     pub fn warn_tick(&mut self, power: bool, target: bool, spannung: f32) {
-        if target && self.state == DoorState::Closed {
-            self.closed_while_warning = true;
-        }
-        if !target {
-            self.closed_while_warning = false;
-        }
-
-        if target && power && !self.emergency_door_unlock && !self.closed_while_warning {
-            if self.warn_relais.tick() == 1 {
-                self.snd_door_warn.start();
-            }
-        } else {
-            self.warn_relais.reset();
-            self.snd_door_warn.stop();
-            self.lm_warn_in.set_brightness(0.0);
-        }
-
-        self.lm_warn_in
-            .set_brightness((self.warn_relais.is_on as u8 as f32) * spannung);
+        self.warning.tick(
+            power && !self.emergency_door_unlock,
+            target,
+            self.state,
+            spannung,
+        );
     }
 
     pub fn tick(
@@ -453,6 +513,9 @@ impl AegElectricDoor {
         emergency_door_unlock: bool,
         haltewunsch: bool,
     ) {
+        // Ausgefallener Antrieb verhält sich wie ohne Versorgung
+        let power = power && !self.fault.as_deref().is_some_and(fault_active);
+
         self.emergency_door_unlock = emergency_door_unlock;
 
         let lichtschranke_frei = !self.pass_door.occupied();
@@ -658,6 +721,7 @@ impl AegElectricDoor {
                 self.snd_door_close_2.stop();
                 self.snd_close_end_2.stop();
             }
+            self.snd_close_end_alt.stop();
         }
 
         let mouse_delta_x = mouse_move().x * self.mouse_factor;
@@ -703,7 +767,10 @@ impl AegElectricDoor {
                 self.open_end_speed
             };
 
-            self.move_door((v_soll - self.speed) * self.traction_stiftness);
+            self.move_door(
+                (v_soll * self.drive_factor * self.cycles.speed_factor - self.speed)
+                    * self.traction_stiftness,
+            );
         }
 
         if self.target < 0 {
@@ -721,7 +788,10 @@ impl AegElectricDoor {
                 -self.close_end_speed
             };
 
-            self.move_door((v_soll - self.speed) * self.traction_stiftness);
+            self.move_door(
+                (v_soll * self.drive_factor * self.cycles.speed_factor - self.speed)
+                    * self.traction_stiftness,
+            );
         }
 
         if self.pos == 1.0 {
@@ -736,5 +806,75 @@ impl AegElectricDoor {
         self.pass_door.update_open(self.pos > 0.75);
         self.pass_door
             .update_released(door_target >= DoorTarget::Release);
+        self.cycles.update(self.state);
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> SensitiveEdgeDoor
+    for AegElectricDoor<A, S, L>
+{
+    fn opening_pos(&self) -> f32 {
+        self.pos
+    }
+
+    fn is_closing(&self) -> bool {
+        self.target < 0
+    }
+
+    fn hold_at(&mut self, pos: f32) {
+        if self.pos < pos {
+            self.pos = pos;
+            self.speed = self.speed.max(0.0);
+            self.update_animations();
+            self.state = DoorState::Other;
+        }
+    }
+
+    fn reopen(&mut self) {
+        self.target = 1;
+        self.open_flag = true;
+        self.close_timer = 0.0;
+    }
+
+    fn stop_drive(&mut self) {
+        self.target = 0;
+        self.speed = 0.0;
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> Door for AegElectricDoor<A, S, L> {
+    fn set_target(&mut self, target: DoorTarget) {
+        self.command = target;
+    }
+
+    fn tick(&mut self, power: bool, emergency_door_unlock: bool, request: bool) {
+        AegElectricDoor::tick(
+            self,
+            power,
+            self.command,
+            false,
+            emergency_door_unlock,
+            request,
+        );
+    }
+
+    fn warn_tick(&mut self, power: bool, target: bool, voltage: f32) {
+        AegElectricDoor::warn_tick(self, power, target, voltage);
+    }
+
+    fn state(&self) -> DoorState {
+        self.state
+    }
+
+    fn pos(&self) -> f32 {
+        self.pos
+    }
+
+    fn occupied(&self) -> bool {
+        self.pass_door.occupied()
+    }
+
+    fn cycles(&self) -> &DoorCycles {
+        &self.cycles
     }
 }
@@ -0,0 +1,296 @@
+//! Common interface of all passenger doors.
+//!
+//! The [`Door`] trait hides the drive of a door behind the [`DoorTarget`] and
+//! [`DoorState`] interface, so controllers, passenger counters or sound
+//! systems only have to be written once. The door command is set with
+//! [`Door::set_target`] and applied in the next [`Door::tick`].
+//!
+//! Each door counts its open/close cycles in [`DoorCycles`]. The statistics
+//! feed the [`DoorWear`](crate::management::wear::DoorWear) of the drive and
+//! are used by the doors themselves to vary the drive speed and the closing
+//! sound slightly from cycle to cycle.
+//!
+//! The [`DoorsCollection`] holds the doors of a car together with their
+//! vehicle side. Doors of different types can be mixed by collecting them as
+//! `Box<dyn Door>`.
+
+use crate::{
+    api::{general::delta, random::gen_f32},
+    management::enums::{
+        door_enums::{DoorSideTarget, DoorState, DoorTarget},
+        general_enums::Side,
+    },
+};
+
+/// Passenger door driven by a [`DoorTarget`].
+pub trait Door {
+    /// Sets the door command for the next tick.
+    fn set_target(&mut self, target: DoorTarget);
+
+    /// Updates the door with the last set command.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the door control is supplied
+    /// * `emergency_door_unlock` - Emergency unlock of the door
+    /// * `request` - Stop request at this door
+    fn tick(&mut self, power: bool, emergency_door_unlock: bool, request: bool);
+
+    /// Updates the closing warning of the door.
+    fn warn_tick(&mut self, power: bool, target: bool, voltage: f32);
+
+    /// Current state of the door.
+    fn state(&self) -> DoorState;
+
+    /// Opening position from 0.0 (closed) to 1.0 (open).
+    fn pos(&self) -> f32;
+
+    /// Whether a passenger is standing in the door.
+    fn occupied(&self) -> bool;
+
+    /// Open/close cycles of the door.
+    fn cycles(&self) -> &DoorCycles;
+}
+
+impl<D: Door + ?Sized> Door for Box<D> {
+    fn set_target(&mut self, target: DoorTarget) {
+        (**self).set_target(target);
+    }
+
+    fn tick(&mut self, power: bool, emergency_door_unlock: bool, request: bool) {
+        (**self).tick(power, emergency_door_unlock, request);
+    }
+
+    fn warn_tick(&mut self, power: bool, target: bool, voltage: f32) {
+        (**self).warn_tick(power, target, voltage);
+    }
+
+    fn state(&self) -> DoorState {
+        (**self).state()
+    }
+
+    fn pos(&self) -> f32 {
+        (**self).pos()
+    }
+
+    fn occupied(&self) -> bool {
+        (**self).occupied()
+    }
+
+    fn cycles(&self) -> &DoorCycles {
+        (**self).cycles()
+    }
+}
+
+//=========================================================================
+
+/// Statistics of the open/close cycles of a door.
+///
+/// A cycle starts when the door leaves the closed position and ends when it
+/// is closed again. At the end of each cycle a new speed factor and sample
+/// choice are drawn, so successive cycles differ slightly.
+///
+/// # Example
+///
+/// ```rust
+/// let mut cycles = DoorCycles::new(0.05);
+///
+/// // In the main loop:
+/// cycles.update(door.state);
+/// let speed = v_soll * cycles.speed_factor;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DoorCycles {
+    spread: f32,
+    timer: f32,
+    running: bool,
+    sample_seed: f32,
+
+    /// Completed cycles
+    pub count: u32,
+    /// Duration of the last completed cycle in seconds
+    pub last_duration: f32,
+    /// Speed factor of the current cycle around 1.0
+    pub speed_factor: f32,
+}
+
+impl DoorCycles {
+    /// Creates new cycle statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `spread` - Maximum deviation of the speed factor from 1.0 per cycle
+    pub fn new(spread: f32) -> Self {
+        let mut cycles = Self {
+            spread,
+            ..Default::default()
+        };
+        cycles.vary();
+        cycles
+    }
+
+    /// Counts the cycles, to be called once per tick with the door state.
+    pub fn update(&mut self, state: DoorState) {
+        if state == DoorState::Closed {
+            if self.running {
+                self.running = false;
+                self.count += 1;
+                self.last_duration = self.timer;
+                self.vary();
+            }
+        } else {
+            if !self.running {
+                self.running = true;
+                self.timer = 0.0;
+            }
+            self.timer += delta();
+        }
+    }
+
+    /// Index of the sample to play out of `count` alternatives in this cycle.
+    pub fn sample(&self, count: usize) -> usize {
+        ((self.sample_seed * count as f32) as usize).min(count.saturating_sub(1))
+    }
+
+    fn vary(&mut self) {
+        self.speed_factor = if self.spread > 0.0 {
+            1.0 + gen_f32(-self.spread..=self.spread)
+        } else {
+            1.0
+        };
+        self.sample_seed = gen_f32(0.0..1.0);
+    }
+}
+
+//=========================================================================
+
+/// Doors of a car with their vehicle side.
+///
+/// # Example
+///
+/// ```rust
+/// let mut doors: DoorsCollection<Box<dyn Door>> = DoorsCollection::new();
+/// doors.add(Side::Right, Box::new(AegElectricDoor::builder(0, "Door_0_X", "Door_0_Y").build()));
+/// doors.add(Side::Right, Box::new(SwingDoor::builder(1, "Door_1_A", "Door_1_B").build()));
+///
+/// // In the main loop:
+/// doors.set_target(DoorTarget::Release, DoorSideTarget::Right);
+/// doors.tick(power, false, &requests);
+/// let occupied = doors.any_occupied();
+/// ```
+pub struct DoorsCollection<D: Door> {
+    doors: Vec<(Side, D)>,
+}
+
+impl<D: Door> Default for DoorsCollection<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Door> DoorsCollection<D> {
+    pub fn new() -> Self {
+        Self { doors: Vec::new() }
+    }
+
+    /// Adds a door on the given vehicle side, the order defines the index.
+    pub fn add(&mut self, side: Side, door: D) {
+        self.doors.push((side, door));
+    }
+
+    /// Sets the door command, doors on sides not selected only get `Close` at
+    /// most.
+    pub fn set_target(&mut self, door_target: DoorTarget, side_target: DoorSideTarget) {
+        for (side, door) in &mut self.doors {
+            let target = if side_target.includes(*side) {
+                door_target
+            } else {
+                door_target.min(DoorTarget::Close)
+            };
+            door.set_target(target);
+        }
+    }
+
+    /// Updates all doors.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the door control is supplied
+    /// * `emergency_door_unlock` - Emergency unlock of all doors
+    /// * `requests` - Stop requests per door index, missing entries count as `false`
+    pub fn tick(&mut self, power: bool, emergency_door_unlock: bool, requests: &[bool]) {
+        for (i, (_, door)) in self.doors.iter_mut().enumerate() {
+            let request = requests.get(i).copied().unwrap_or(false);
+            door.tick(power, emergency_door_unlock, request);
+        }
+    }
+
+    /// Updates the closing warning of all doors on the selected sides.
+    pub fn warn_tick(
+        &mut self,
+        power: bool,
+        warn: bool,
+        side_target: DoorSideTarget,
+        voltage: f32,
+    ) {
+        for (side, door) in &mut self.doors {
+            door.warn_tick(power, warn && side_target.includes(*side), voltage);
+        }
+    }
+
+    /// Whether all doors are closed.
+    pub fn all_closed(&self) -> bool {
+        self.doors
+            .iter()
+            .all(|(_, door)| door.state() == DoorState::Closed)
+    }
+
+    /// Whether a passenger is standing in any of the doors.
+    pub fn any_occupied(&self) -> bool {
+        self.doors.iter().any(|(_, door)| door.occupied())
+    }
+
+    /// Combined state of all doors.
+    ///
+    /// Returns `Closed` or `Open` only if all doors agree, otherwise `Other`.
+    pub fn state(&self) -> DoorState {
+        if self.all_closed() {
+            DoorState::Closed
+        } else if !self.doors.is_empty()
+            && self
+                .doors
+                .iter()
+                .all(|(_, door)| door.state() == DoorState::Open)
+        {
+            DoorState::Open
+        } else {
+            DoorState::Other
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&D> {
+        self.doors.get(index).map(|(_, door)| door)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut D> {
+        self.doors.get_mut(index).map(|(_, door)| door)
+    }
+
+    /// Iterates over the doors with their side.
+    pub fn iter(&self) -> impl Iterator<Item = &(Side, D)> {
+        self.doors.iter()
+    }
+
+    /// Iterates mutably over the doors with their side.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut (Side, D)> {
+        self.doors.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.doors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doors.is_empty()
+    }
+}
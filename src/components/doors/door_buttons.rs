@@ -1,8 +1,8 @@
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
 use crate::api::{
     animation::Animation,
+    general::delta,
     key_event::KeyEvent,
     light::Light,
     variable::{get_var, set_var},
@@ -0,0 +1,221 @@
+//! Central control for all passenger doors of a car.
+//!
+//! The [`DoorController`] owns the doors of a vehicle and feeds them with the
+//! driver's door command. It takes care of the side selection, only lets the
+//! doors be released at standstill and collects the door states into a single
+//! "doors closed" signal, which can be passed on to the `CouplerDoorsClosed`
+//! coupling line.
+//!
+//! In a consist the door command arrives as a [`DoorCommand`] over the
+//! `CouplerDoorControl` line, with the platform side already turned into the
+//! view of this car; [`DoorController::tick_command`] only applies it if the
+//! car is included in the car mask of the command.
+
+use crate::{
+    components::doors::{
+        aeg_electric_door::AegElectricDoor,
+        door::{Door, DoorsCollection},
+    },
+    management::enums::{
+        door_enums::{DoorCommand, DoorSideTarget, DoorState, DoorTarget},
+        general_enums::Side,
+    },
+};
+
+/// Speed in m/s below which the vehicle counts as standing.
+const STANDSTILL_SPEED: f32 = 0.1;
+
+pub struct DoorControllerBuilder<D: Door = AegElectricDoor> {
+    doors: DoorsCollection<D>,
+    standstill_speed: f32,
+    car_index: usize,
+}
+
+impl<D: Door> DoorControllerBuilder<D> {
+    /// Adds a door on the given vehicle side.
+    ///
+    /// The order in which the doors are added defines their index, which is
+    /// also used for the stop requests passed to [`DoorController::tick`].
+    pub fn add_door(mut self, side: Side, door: D) -> Self {
+        self.doors.add(side, door);
+        self
+    }
+
+    /// Sets the speed in m/s below which the doors may be released.
+    pub fn standstill_speed(mut self, speed: f32) -> Self {
+        self.standstill_speed = speed;
+        self
+    }
+
+    /// Sets the index of this car in the consist, checked against the car
+    /// mask of a [`DoorCommand`].
+    pub fn car_index(mut self, index: usize) -> Self {
+        self.car_index = index;
+        self
+    }
+
+    pub fn build(self) -> DoorController<D> {
+        DoorController {
+            doors: self.doors,
+            standstill_speed: self.standstill_speed,
+            car_index: self.car_index,
+            standstill: true,
+            released: false,
+            doors_closed: false,
+        }
+    }
+}
+
+/// Owns all doors of a car and drives them from one door command.
+///
+/// Works with every door type implementing [`Door`], mixed door types can be
+/// added as `Box<dyn Door>`.
+///
+/// # Example
+///
+/// ```rust
+/// let mut doors = DoorController::builder()
+///     .add_door(Side::Right, AegElectricDoor::builder(0, "Door_0_X", "Door_0_Y").build())
+///     .add_door(Side::Right, AegElectricDoor::builder(1, "Door_1_X", "Door_1_Y").build())
+///     .add_door(Side::Left, AegElectricDoor::builder(2, "Door_2_X", "Door_2_Y").build())
+///     .build();
+///
+/// // In the main loop:
+/// doors.tick(power, v_ground(), DoorTarget::Release, DoorSideTarget::Right, false, &requests);
+///
+/// // Or with the door command of the consist:
+/// doors.tick_command(power, v_ground(), &door_line.get_value(), false, &requests);
+/// doors_closed_line.update_local(doors.doors_closed);
+/// ```
+pub struct DoorController<D: Door = AegElectricDoor> {
+    doors: DoorsCollection<D>,
+    standstill_speed: f32,
+    car_index: usize,
+
+    /// Whether the vehicle was standing during the last tick
+    pub standstill: bool,
+    /// Whether at least one door side is currently released
+    pub released: bool,
+    /// Whether all doors of this car are closed
+    pub doors_closed: bool,
+}
+
+impl<D: Door> DoorController<D> {
+    pub fn builder() -> DoorControllerBuilder<D> {
+        DoorControllerBuilder {
+            doors: DoorsCollection::new(),
+            standstill_speed: STANDSTILL_SPEED,
+            car_index: 0,
+        }
+    }
+
+    /// Updates all doors.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the door control is supplied
+    /// * `speed` - Current vehicle speed in m/s
+    /// * `door_target` - Door command of the driver
+    /// * `side_target` - Selected door side
+    /// * `emergency_door_unlock` - Emergency unlock of all doors
+    /// * `requests` - Stop requests per door index, missing entries count as `false`
+    pub fn tick(
+        &mut self,
+        power: bool,
+        speed: f32,
+        door_target: DoorTarget,
+        side_target: DoorSideTarget,
+        emergency_door_unlock: bool,
+        requests: &[bool],
+    ) {
+        self.standstill = speed.abs() <= self.standstill_speed;
+
+        // Doors may only be released or opened at standstill
+        let door_target = if self.standstill {
+            door_target
+        } else {
+            door_target.min(DoorTarget::Close)
+        };
+
+        self.released = door_target >= DoorTarget::Release && side_target != DoorSideTarget::None;
+
+        self.doors.set_target(door_target, side_target);
+        self.doors.tick(power, emergency_door_unlock, requests);
+
+        self.doors_closed = self.doors.all_closed();
+    }
+
+    /// Updates all doors from a door command of the consist.
+    ///
+    /// If this car is not included in the car mask, its doors are kept closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the door control is supplied
+    /// * `speed` - Current vehicle speed in m/s
+    /// * `command` - Door command, e.g. from the `CouplerDoorControl` line
+    /// * `emergency_door_unlock` - Emergency unlock of all doors
+    /// * `requests` - Stop requests per door index, missing entries count as `false`
+    pub fn tick_command(
+        &mut self,
+        power: bool,
+        speed: f32,
+        command: &DoorCommand,
+        emergency_door_unlock: bool,
+        requests: &[bool],
+    ) {
+        let (door_target, side_target) = if command.includes_car(self.car_index) {
+            (command.target, command.side)
+        } else {
+            (command.target.min(DoorTarget::Close), DoorSideTarget::None)
+        };
+
+        self.tick(
+            power,
+            speed,
+            door_target,
+            side_target,
+            emergency_door_unlock,
+            requests,
+        );
+    }
+
+    /// Updates the closing warning of all doors on the released sides.
+    pub fn warn_tick(
+        &mut self,
+        power: bool,
+        warn: bool,
+        side_target: DoorSideTarget,
+        voltage: f32,
+    ) {
+        self.doors.warn_tick(power, warn, side_target, voltage);
+    }
+
+    /// Combined state of all doors.
+    ///
+    /// Returns `Closed` or `Open` only if all doors agree, otherwise `Other`.
+    pub fn state(&self) -> DoorState {
+        self.doors.state()
+    }
+
+    /// All doors of the car.
+    pub fn doors(&self) -> &DoorsCollection<D> {
+        &self.doors
+    }
+
+    pub fn door(&self, index: usize) -> Option<&D> {
+        self.doors.get(index)
+    }
+
+    pub fn door_mut(&mut self, index: usize) -> Option<&mut D> {
+        self.doors.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.doors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doors.is_empty()
+    }
+}
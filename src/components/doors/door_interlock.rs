@@ -0,0 +1,204 @@
+//! Interlock between doors and traction ("Türschleife").
+//!
+//! The [`DoorTractionInterlock`] only lets the doors be released below a speed
+//! threshold and inhibits traction as long as any door is not closed. For
+//! faulty door contacts the interlock can be bridged with a sealed switch;
+//! bridging, blocked releases and inhibited traction demands are logged as
+//! [`InterlockEvent`]s, which the vehicle passes on to its
+//! [`EventLog`](crate::management::event_log::EventLog).
+
+use crate::{
+    api::light::Light, elements::tech::seals::SealedSwitch,
+    management::enums::door_enums::DoorTarget,
+};
+
+/// Event of the door interlock for the fault memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterlockEvent {
+    /// Bridging switch turned on
+    BridgeOn,
+    /// Bridging switch turned off
+    BridgeOff,
+    /// Door release requested above the speed threshold
+    ReleaseBlocked,
+    /// Traction demanded while a door was not closed
+    TractionInhibited,
+}
+
+impl InterlockEvent {
+    /// Short text for displays and the fault memory.
+    pub fn text(&self) -> &'static str {
+        match self {
+            InterlockEvent::BridgeOn => "Türschleife überbrückt",
+            InterlockEvent::BridgeOff => "Überbrückung Türschleife aufgehoben",
+            InterlockEvent::ReleaseBlocked => "Türfreigabe bei Fahrt gesperrt",
+            InterlockEvent::TractionInhibited => "Fahrsperre: Türen nicht geschlossen",
+        }
+    }
+}
+
+pub struct DoorTractionInterlockBuilder {
+    release_speed: f32,
+    bridge: Option<SealedSwitch>,
+    lm_bridged: Light,
+}
+
+impl DoorTractionInterlockBuilder {
+    /// Speed in m/s above which the doors cannot be released.
+    pub fn release_speed(mut self, speed: f32) -> Self {
+        self.release_speed = speed;
+        self
+    }
+
+    /// Sealed switch bridging the interlock.
+    pub fn bridge(mut self, switch: SealedSwitch) -> Self {
+        self.bridge = Some(switch);
+        self
+    }
+
+    /// Lamp lit while the interlock is bridged.
+    pub fn lm_bridged(mut self, name: impl Into<String>) -> Self {
+        self.lm_bridged = Light::new(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> DoorTractionInterlock {
+        DoorTractionInterlock {
+            release_speed: self.release_speed,
+            bridge: self.bridge,
+            lm_bridged: self.lm_bridged,
+            events: Vec::new(),
+            release_blocked_last: false,
+            inhibited_last: false,
+            bridged: false,
+            door_target: DoorTarget::Close,
+            traction: 0.0,
+            traction_inhibited: false,
+        }
+    }
+}
+
+/// Speed-dependent door release and traction inhibit with sealed bridging.
+///
+/// # Example
+///
+/// ```rust
+/// let mut interlock = DoorTractionInterlock::builder()
+///     .release_speed(0.5)
+///     .bridge(SealedSwitch::new(
+///         Some(CockpitSide::A),
+///         "Tuerschleife_Plombe",
+///         "Tuerschleife_Plombe_Ab",
+///         Switch::builder("Tuerschleife_Schalter", Some(CockpitSide::A)).build(),
+///     ))
+///     .lm_bridged("L_Tuerschleife")
+///     .build();
+///
+/// // In the main loop:
+/// interlock.tick(voltage > 0.7, v_ground(), door_target, doors.doors_closed, controller.traction());
+/// doors.tick(power, v_ground(), interlock.door_target, side_target, false, &requests);
+/// traction.tick(interlock.traction);
+/// for event in interlock.take_events() {
+///     event_log.log(EventCategory::DoorFault, event.text());
+/// }
+/// ```
+pub struct DoorTractionInterlock {
+    release_speed: f32,
+    bridge: Option<SealedSwitch>,
+    lm_bridged: Light,
+
+    events: Vec<InterlockEvent>,
+    release_blocked_last: bool,
+    inhibited_last: bool,
+
+    /// Whether the interlock is bridged
+    pub bridged: bool,
+    /// Door command after the interlock
+    pub door_target: DoorTarget,
+    /// Traction demand after the interlock from 0.0 to 1.0
+    pub traction: f32,
+    /// Whether a traction demand is currently inhibited
+    pub traction_inhibited: bool,
+}
+
+impl DoorTractionInterlock {
+    /// Creates a new door interlock builder.
+    pub fn builder() -> DoorTractionInterlockBuilder {
+        DoorTractionInterlockBuilder {
+            release_speed: 0.5,
+            bridge: None,
+            lm_bridged: Light::new(None),
+        }
+    }
+
+    /// Updates the interlock.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the vehicle control is supplied
+    /// * `speed` - Current speed in m/s
+    /// * `door_target` - Door command of the driver
+    /// * `doors_closed` - Whether all doors of the consist are closed
+    /// * `traction` - Traction demand of the driver from 0.0 to 1.0
+    pub fn tick(
+        &mut self,
+        power: bool,
+        speed: f32,
+        door_target: DoorTarget,
+        doors_closed: bool,
+        traction: f32,
+    ) {
+        let bridged = match &mut self.bridge {
+            Some(bridge) => {
+                bridge.tick();
+                bridge.switch.value(power)
+            }
+            None => false,
+        };
+        if bridged != self.bridged {
+            self.log(if bridged {
+                InterlockEvent::BridgeOn
+            } else {
+                InterlockEvent::BridgeOff
+            });
+            self.bridged = bridged;
+        }
+
+        let release_blocked =
+            !bridged && speed.abs() > self.release_speed && door_target >= DoorTarget::Release;
+        if release_blocked && !self.release_blocked_last {
+            self.log(InterlockEvent::ReleaseBlocked);
+        }
+        self.release_blocked_last = release_blocked;
+
+        self.door_target = if release_blocked {
+            door_target.min(DoorTarget::Close)
+        } else {
+            door_target
+        };
+
+        self.traction_inhibited = !bridged && !doors_closed && traction > 0.0;
+        if self.traction_inhibited && !self.inhibited_last {
+            self.log(InterlockEvent::TractionInhibited);
+        }
+        self.inhibited_last = self.traction_inhibited;
+
+        self.traction = if self.traction_inhibited {
+            0.0
+        } else {
+            traction
+        };
+
+        self.lm_bridged
+            .set_brightness((power && bridged) as u8 as f32);
+    }
+
+    /// Returns the events logged since the last call.
+    pub fn take_events(&mut self) -> Vec<InterlockEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn log(&mut self, event: InterlockEvent) {
+        self.events.push(event);
+    }
+}
@@ -0,0 +1,201 @@
+//! Passenger door request button.
+//!
+//! Bundles the push button at a passenger door with the request latch, the
+//! illumination and the acknowledgement sound. A button can also be set up as
+//! "Kinderwagen" button, which additionally raises a buggy request for the
+//! `CouplerBuggyReqest` coupling line.
+
+use crate::{
+    api::{
+        light::Light,
+        sound::Sound,
+        variable::{get_var, set_var},
+        visible_flag::Visiblility,
+    },
+    elements::tech::buttons::PushButton,
+};
+
+pub struct DoorRequestButtonBuilder {
+    button: PushButton,
+
+    lm_release: Light,
+    lm_request: Light,
+    snd_request: Sound,
+
+    braille: Option<Visiblility>,
+
+    kinderwagen: bool,
+
+    ai_door_vars: Vec<String>,
+}
+
+impl DoorRequestButtonBuilder {
+    /// Light which is switched on while the door is released.
+    pub fn release_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_release = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Light which acknowledges a latched request.
+    pub fn request_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_request = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Sound played once when a request is latched.
+    pub fn request_sound(mut self, name: impl Into<String>) -> Self {
+        self.snd_request = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Shows the braille plate of the button.
+    pub fn braille(mut self, visibility_name: impl Into<String>) -> Self {
+        self.braille = Some(Visiblility::new(visibility_name));
+        self
+    }
+
+    /// Turns the button into a "Kinderwagen" button.
+    pub fn kinderwagen(mut self) -> Self {
+        self.kinderwagen = true;
+        self
+    }
+
+    /// Requests of the AI passengers at the given inner doors.
+    pub fn ai_doors_in(mut self, ai_doors: Vec<usize>) -> Self {
+        self.ai_door_vars
+            .extend(ai_doors.iter().map(|i| format!("DoorReqIn_{i}")));
+        self
+    }
+
+    /// Requests of the AI passengers at the given outer doors.
+    pub fn ai_doors_out(mut self, ai_doors: Vec<usize>) -> Self {
+        self.ai_door_vars
+            .extend(ai_doors.iter().map(|i| format!("DoorReqOut_{i}")));
+        self
+    }
+
+    pub fn build(self) -> DoorRequestButton {
+        let mut braille = self.braille;
+        if let Some(vis) = &mut braille {
+            vis.make_visible();
+        }
+
+        DoorRequestButton {
+            button: self.button,
+            lm_release: self.lm_release,
+            lm_request: self.lm_request,
+            snd_request: self.snd_request,
+            braille,
+            kinderwagen: self.kinderwagen,
+            ai_door_vars: self.ai_door_vars,
+            request: false,
+            buggy_request: false,
+        }
+    }
+}
+
+/// Door request button with latch, illumination and optional buggy request.
+///
+/// # Example
+///
+/// ```rust
+/// let mut btn = DoorRequestButton::builder(
+///     PushButton::builder("Btn_Door_1_Anim", "Door_1_Request", None).build(),
+/// )
+/// .release_light("L_Door_1_Release")
+/// .request_light("L_Door_1_Request")
+/// .kinderwagen()
+/// .ai_doors_out(vec![1])
+/// .build();
+///
+/// // In the main loop:
+/// let request = btn.tick(power, door_released, door.state == DoorState::Open, buggy_reset);
+/// buggy_line.update_local(btn.buggy_request);
+/// ```
+#[derive(Debug)]
+pub struct DoorRequestButton {
+    button: PushButton,
+
+    lm_release: Light,
+    lm_request: Light,
+    snd_request: Sound,
+
+    braille: Option<Visiblility>,
+
+    kinderwagen: bool,
+
+    ai_door_vars: Vec<String>,
+
+    /// Latched door request
+    pub request: bool,
+    /// Latched buggy request, only set by "Kinderwagen" buttons
+    pub buggy_request: bool,
+}
+
+impl DoorRequestButton {
+    pub fn builder(button: PushButton) -> DoorRequestButtonBuilder {
+        DoorRequestButtonBuilder {
+            button,
+            lm_release: Light::new(None),
+            lm_request: Light::new(None),
+            snd_request: Sound::new_simple(None),
+            braille: None,
+            kinderwagen: false,
+            ai_door_vars: Vec::new(),
+        }
+    }
+
+    /// Updates the button and returns the latched door request.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the door control is supplied
+    /// * `released` - Whether the door is released, lights the button
+    /// * `door_open` - Whether the door has opened, clears the door request
+    /// * `buggy_reset` - Clears the buggy request, e.g. from `CouplerBuggyReset`
+    pub fn tick(
+        &mut self,
+        power: bool,
+        released: bool,
+        door_open: bool,
+        buggy_reset: bool,
+    ) -> bool {
+        self.button.tick();
+
+        let mut pressed = self.button.is_just_pressed();
+
+        for var_name in &self.ai_door_vars {
+            pressed = pressed || get_var::<bool>(var_name);
+            set_var(var_name, false);
+        }
+
+        if pressed && power && !self.request {
+            self.request = true;
+            self.snd_request.start();
+        }
+
+        if pressed && power && self.kinderwagen {
+            self.buggy_request = true;
+        }
+
+        if door_open || !power {
+            self.request = false;
+        }
+
+        if buggy_reset || !power {
+            self.buggy_request = false;
+        }
+
+        self.lm_release
+            .set_brightness((power && released && !self.request) as u8 as f32);
+        self.lm_request
+            .set_brightness((power && (self.request || self.buggy_request)) as u8 as f32);
+
+        self.request
+    }
+
+    pub fn reset(&mut self) {
+        self.request = false;
+        self.buggy_request = false;
+    }
+}
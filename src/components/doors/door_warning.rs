@@ -0,0 +1,294 @@
+//! Closing warning of a passenger door ("Türschließwarnung").
+//!
+//! The [`DoorWarning`] sounds a chime on the interior and exterior speakers
+//! and lights the warning lamp while a door is about to close. The chime
+//! follows a [`ChimePattern`]: a continuous tone, evenly pulsed chimes or
+//! chimes getting faster towards the end of the warning.
+//!
+//! A door which is already closed when the warning starts is not warned, and
+//! once the door has closed the warning stays silent until the warning signal
+//! drops. The component is used by all door implementations and can also be
+//! used on its own for doors driven by the vehicle script.
+
+use crate::{
+    api::{
+        general::delta,
+        light::Light,
+        mock::{LightOutput, SoundOutput},
+        sound::Sound,
+    },
+    elements::std::relays::FlasherRelay,
+    management::enums::door_enums::DoorState,
+};
+
+const DOORWARN_INTERVAL: f32 = 0.777;
+const DOORWARN_INTERVAL_HALF: f32 = DOORWARN_INTERVAL / 2.0;
+
+/// Course of the chime during the warning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChimePattern {
+    /// Steady tone and lamp for the whole warning, the sound should loop
+    Continuous,
+    /// One chime per cycle
+    Pulsed {
+        /// Duration of one cycle in seconds
+        interval: f32,
+        /// Time in seconds the lamp is lit in each cycle
+        on_time: f32,
+        /// Position in seconds within the cycle at which the warning starts
+        reset_time: f32,
+    },
+    /// Chimes whose cycle shortens evenly during the warning
+    Ramp {
+        /// Duration of the first cycle in seconds
+        interval_start: f32,
+        /// Duration of the cycles at the end of the ramp in seconds
+        interval_end: f32,
+        /// Time in seconds until the end interval is reached
+        duration: f32,
+    },
+}
+
+impl Default for ChimePattern {
+    fn default() -> Self {
+        Self::Pulsed {
+            interval: DOORWARN_INTERVAL,
+            on_time: DOORWARN_INTERVAL_HALF,
+            reset_time: 0.12,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DoorWarningBuilder<S = Sound, L = Light> {
+    pattern: ChimePattern,
+    lm_warn: L,
+    snd_interior: S,
+    snd_exterior: S,
+}
+
+impl DoorWarningBuilder {
+    /// Warning lamp, dimmed with the supply voltage.
+    pub fn lamp(mut self, name: impl Into<String>) -> Self {
+        self.lm_warn = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Chime on the interior speaker.
+    pub fn snd_interior(mut self, name: impl Into<String>) -> Self {
+        self.snd_interior = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Chime on the exterior speaker.
+    pub fn snd_exterior(mut self, name: impl Into<String>) -> Self {
+        self.snd_exterior = Sound::new_simple(Some(&name.into()));
+        self
+    }
+}
+
+impl<S: SoundOutput, L: LightOutput> DoorWarningBuilder<S, L> {
+    /// Course of the chime.
+    pub fn pattern(mut self, pattern: ChimePattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    pub fn build(self) -> DoorWarning<S, L> {
+        let relay = match self.pattern {
+            ChimePattern::Pulsed {
+                interval,
+                on_time,
+                reset_time,
+            } => FlasherRelay::new(interval, on_time, reset_time),
+            _ => FlasherRelay::default(),
+        };
+
+        DoorWarning {
+            pattern: self.pattern,
+            relay,
+            lm_warn: self.lm_warn,
+            snd_interior: self.snd_interior,
+            snd_exterior: self.snd_exterior,
+            closed_while_warning: false,
+            elapsed: 0.0,
+            phase: 0.0,
+            active: false,
+            is_on: false,
+            exterior: true,
+        }
+    }
+}
+
+/// Chime and lamp warning of a closing door.
+///
+/// # Example
+///
+/// ```rust
+/// let mut warning = DoorWarning::builder()
+///     .pattern(ChimePattern::Ramp {
+///         interval_start: 1.0,
+///         interval_end: 0.4,
+///         duration: 3.0,
+///     })
+///     .lamp("L_Tuer_1_Warn")
+///     .snd_interior("Snd_Tuer_1_Warn_Innen")
+///     .snd_exterior("Snd_Tuer_1_Warn_Aussen")
+///     .build();
+///
+/// // In the main loop:
+/// warning.tick(voltage > 0.5, door_target == DoorTarget::Close, door.state, voltage);
+/// ```
+#[derive(Debug)]
+pub struct DoorWarning<S = Sound, L = Light> {
+    pattern: ChimePattern,
+    relay: FlasherRelay,
+
+    lm_warn: L,
+    snd_interior: S,
+    snd_exterior: S,
+
+    closed_while_warning: bool,
+    elapsed: f32,
+    phase: f32,
+
+    /// Whether the door is being warned
+    pub active: bool,
+    /// Whether the chime and lamp are in the on phase
+    pub is_on: bool,
+    /// Whether the exterior speaker is switched on
+    pub exterior: bool,
+}
+
+impl DoorWarning {
+    /// Creates a new door warning builder with the default pulsed chime.
+    pub fn builder() -> DoorWarningBuilder {
+        DoorWarningBuilder::default()
+    }
+}
+
+impl<S: SoundOutput, L: LightOutput> DoorWarning<S, L> {
+    /// Updates the warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the warning is supplied and not inhibited
+    /// * `target` - Whether the door is to be warned
+    /// * `state` - Current state of the door
+    /// * `voltage` - Supply voltage from 0.0 to 1.0 for the lamp
+    pub fn tick(&mut self, power: bool, target: bool, state: DoorState, voltage: f32) {
+        if target && state == DoorState::Closed {
+            self.closed_while_warning = true;
+        }
+        if !target {
+            self.closed_while_warning = false;
+        }
+
+        let warn = target && power && !self.closed_while_warning;
+        let was_on = self.is_on;
+        self.is_on = match self.pattern {
+            ChimePattern::Continuous => warn,
+            ChimePattern::Pulsed { .. } => {
+                self.relay.run(warn);
+                self.relay.is_on
+            }
+            ChimePattern::Ramp {
+                interval_start,
+                interval_end,
+                duration,
+            } => self.ramp(warn, interval_start, interval_end, duration),
+        };
+        self.active = warn;
+
+        if self.is_on && !was_on {
+            self.snd_interior.start();
+            if self.exterior {
+                self.snd_exterior.start();
+            }
+        }
+        if !warn {
+            self.snd_interior.stop();
+            self.snd_exterior.stop();
+        }
+
+        self.lm_warn
+            .set_brightness((self.is_on as u8 as f32) * voltage);
+    }
+
+    //===================================================================
+    // Internal
+    //===================================================================
+
+    fn ramp(&mut self, warn: bool, interval_start: f32, interval_end: f32, duration: f32) -> bool {
+        if !warn {
+            self.elapsed = 0.0;
+            self.phase = 0.0;
+            return false;
+        }
+
+        // Die Periode verkürzt sich linear bis zum Ende der Rampe
+        let progress = (self.elapsed / duration.max(0.001)).clamp(0.0, 1.0);
+        let interval = interval_start + (interval_end - interval_start) * progress;
+        let on = self.phase.fract() < 0.5;
+
+        self.elapsed += delta();
+        self.phase += delta() / interval.max(0.01);
+        on
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{
+        mock::{MockLight, MockSound},
+        replay::run_frames,
+    };
+
+    #[test]
+    fn test_pulsed_chime() {
+        let mut warning = DoorWarningBuilder::<MockSound, MockLight>::default().build();
+
+        let mut lamp_changes = 0;
+        let mut lamp_last = 0.0;
+        run_frames(40, 0.05, || {
+            warning.tick(true, true, DoorState::Open, 1.0);
+            if warning.lm_warn.brightness != lamp_last {
+                lamp_changes += 1;
+                lamp_last = warning.lm_warn.brightness;
+            }
+        });
+        assert!(warning.active);
+        assert!(lamp_changes >= 4);
+        assert_eq!(
+            warning.snd_interior.start_count,
+            warning.snd_exterior.start_count
+        );
+        assert!(warning.snd_interior.start_count >= 2);
+
+        warning.tick(true, false, DoorState::Open, 1.0);
+        assert_eq!(warning.lm_warn.brightness, 0.0);
+        assert!(!warning.snd_interior.playing);
+    }
+
+    #[test]
+    fn test_closed_door_not_warned() {
+        let mut warning = DoorWarningBuilder::<MockSound, MockLight>::default()
+            .pattern(ChimePattern::Continuous)
+            .build();
+
+        run_frames(10, 0.05, || {
+            warning.tick(true, true, DoorState::Closed, 1.0)
+        });
+        assert!(!warning.active);
+        assert_eq!(warning.snd_interior.start_count, 0);
+
+        // Erst ein neues Warnsignal warnt die wieder offene Tür
+        warning.tick(true, true, DoorState::Open, 1.0);
+        assert!(!warning.active);
+        warning.tick(true, false, DoorState::Open, 1.0);
+        warning.tick(true, true, DoorState::Open, 1.0);
+        assert!(warning.active);
+        assert_eq!(warning.lm_warn.brightness, 1.0);
+    }
+}
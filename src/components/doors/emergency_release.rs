@@ -0,0 +1,200 @@
+//! Emergency door release ("Notentriegelung").
+//!
+//! The [`EmergencyDoorRelease`] is the pull handle next to a passenger door.
+//! Pulling it dumps the air of the door drive with an audible hiss and unlocks
+//! the associated doors: pass [`EmergencyDoorRelease::unlocked`] as
+//! `emergency_door_unlock` to the door `tick`. The pulled handle is reported on
+//! the MFD and stays latched until the driver resets it with the key.
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::{
+    api::{animation::Animation, general::delta, key_event::KeyEvent, sound::Sound},
+    elements::tech::key_switch::KeySwitch,
+    messages::diagnostic_messages::{DiagnosticFaultKind, DiagnosticMessageSender},
+};
+
+pub struct EmergencyDoorReleaseBuilder {
+    cab_side: Option<CockpitSide>,
+
+    anim: Animation,
+    key_pull: KeyEvent,
+
+    dump_time: f32,
+
+    reset_key: Option<KeySwitch>,
+    diagnostic_kind: Option<DiagnosticFaultKind>,
+
+    snd_dump: Sound,
+    snd_reset: Sound,
+}
+
+impl EmergencyDoorReleaseBuilder {
+    /// Time in seconds until the door drive is vented after pulling the handle.
+    pub fn dump_time(mut self, dump_time: f32) -> Self {
+        self.dump_time = dump_time;
+        self
+    }
+
+    /// Driver's key resetting the pulled handle when turned to +1.
+    pub fn reset_key(mut self, key: KeySwitch) -> Self {
+        self.reset_key = Some(key);
+        self
+    }
+
+    /// Diagnostic message shown on the MFD while the handle is pulled,
+    /// e.g. [`DiagnosticFaultKind::NotentriegelungR1`].
+    pub fn diagnostic(mut self, kind: DiagnosticFaultKind) -> Self {
+        self.diagnostic_kind = Some(kind);
+        self
+    }
+
+    /// Sound of the air dump, plays while the door drive is vented.
+    pub fn snd_dump(mut self, name: impl Into<String>) -> Self {
+        self.snd_dump = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the handle snapping back on reset.
+    pub fn snd_reset(mut self, name: impl Into<String>) -> Self {
+        self.snd_reset = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> EmergencyDoorRelease {
+        EmergencyDoorRelease {
+            cab_side: self.cab_side,
+            anim: self.anim,
+            key_pull: self.key_pull,
+            dump_time: self.dump_time,
+            reset_key: self.reset_key,
+            diagnostic_kind: self.diagnostic_kind,
+            diagnostic: DiagnosticMessageSender::new(),
+            snd_dump: self.snd_dump,
+            snd_reset: self.snd_reset,
+            pulled: false,
+            drive_pressure: 1.0,
+            unlocked: false,
+        }
+    }
+}
+
+/// Pull handle unlocking the doors by venting the door drive.
+///
+/// # Example
+///
+/// ```rust
+/// let mut release = EmergencyDoorRelease::builder("NE_Handle_R1", "NE_Pull_R1", None)
+///     .reset_key(KeySwitch::builder(KeyDepot::new("Key_Driver"), "NE_Reset_R1", "NE_Key_Vis_R1", None).max_spring().build())
+///     .diagnostic(DiagnosticFaultKind::NotentriegelungR1)
+///     .snd_dump("Snd_NE_Dump")
+///     .build();
+///
+/// // In the main loop:
+/// release.tick(voltage > 0.5);
+/// door.tick(voltage > 0.5, target, false, release.unlocked, request);
+/// ```
+pub struct EmergencyDoorRelease {
+    cab_side: Option<CockpitSide>,
+
+    anim: Animation,
+    key_pull: KeyEvent,
+
+    dump_time: f32,
+
+    reset_key: Option<KeySwitch>,
+    diagnostic_kind: Option<DiagnosticFaultKind>,
+    diagnostic: DiagnosticMessageSender,
+
+    snd_dump: Sound,
+    snd_reset: Sound,
+
+    /// Whether the handle is pulled and latched
+    pub pulled: bool,
+    /// Share of the air remaining in the door drive from 0.0 to 1.0
+    pub drive_pressure: f32,
+    /// Whether the associated doors are unlocked, set once the drive is vented
+    pub unlocked: bool,
+}
+
+impl EmergencyDoorRelease {
+    /// Creates a new emergency door release builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Animation of the handle, 0.0 home and 1.0 pulled
+    /// * `event_name` - Key event pulling the handle
+    /// * `cab_side` - Optional cab side for key event handling and diagnostics
+    pub fn builder(
+        animation_name: impl Into<String>,
+        event_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> EmergencyDoorReleaseBuilder {
+        EmergencyDoorReleaseBuilder {
+            cab_side,
+            anim: Animation::new(Some(&animation_name.into())),
+            key_pull: KeyEvent::new(Some(&event_name.into()), cab_side),
+            dump_time: 1.5,
+            reset_key: None,
+            diagnostic_kind: None,
+            snd_dump: Sound::new_simple(None),
+            snd_reset: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the handle and the door drive.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the diagnostics are supplied
+    pub fn tick(&mut self, power: bool) {
+        if !self.pulled && self.key_pull.is_just_pressed() {
+            self.pull();
+        }
+
+        if let Some(key) = &mut self.reset_key {
+            key.tick();
+            if self.pulled && key.value(true) > 0 {
+                self.reset();
+            }
+        }
+
+        self.anim.set(self.pulled as u8 as f32);
+
+        // Türantrieb entlüften
+        if self.pulled && self.drive_pressure > 0.0 {
+            self.drive_pressure =
+                (self.drive_pressure - delta() / self.dump_time.max(0.01)).max(0.0);
+            if self.drive_pressure <= 0.0 {
+                self.snd_dump.stop();
+            }
+        }
+
+        // Türen erst nach dem Entlüften frei
+        self.unlocked = self.pulled && self.drive_pressure <= 0.0;
+
+        if power {
+            if let Some(kind) = self.diagnostic_kind {
+                self.diagnostic.send(kind, self.pulled, self.cab_side);
+            }
+        }
+    }
+
+    /// Pulls the handle, e.g. triggered by a scenario.
+    pub fn pull(&mut self) {
+        if !self.pulled {
+            self.pulled = true;
+            self.snd_dump.start();
+        }
+    }
+
+    /// Resets the pulled handle, the door drive is supplied again.
+    pub fn reset(&mut self) {
+        if self.pulled {
+            self.pulled = false;
+            self.drive_pressure = 1.0;
+            self.snd_dump.stop();
+            self.snd_reset.start();
+        }
+    }
+}
@@ -0,0 +1,834 @@
+//! Pneumatically actuated folding door (Falttür).
+//!
+//! The door is moved by a compressed air cylinder. The cylinder is filled and
+//! vented through a valve, so the door force depends on the available air
+//! pressure. Without pressure the door moves freely and is pushed around by
+//! wind gusts and the inclination of the vehicle, and it can be moved by hand.
+//!
+//! The folding kinematics are set up with sub-animations, which are all driven
+//! from the opening position of the door.
+
+use std::f32::consts::PI;
+
+use lotus_extra::math::PiecewiseLinearFunction;
+
+use crate::{
+    api::{
+        animation::{Animation, MappedAnimation},
+        general::{delta, mouse_move},
+        key_event::KeyEvent,
+        light::Light,
+        mock::{AnimationOutput, LightOutput, SoundOutput},
+        random::gen_f32,
+        sound::{Sound, SoundPool},
+        vehicle_door::VehicleDoor,
+    },
+    components::doors::{
+        door::{Door, DoorCycles},
+        door_warning::{ChimePattern, DoorWarning, DoorWarningBuilder},
+        obstacle::SensitiveEdgeDoor,
+    },
+    management::{
+        enums::door_enums::{DoorState, DoorTarget},
+        fault_manager::fault_active,
+    },
+};
+
+const GRAVITY: f32 = 9.81;
+
+/// Number of panels of a folding door.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FoldingDoorPanels {
+    /// One folding pair
+    #[default]
+    Two,
+    /// Two folding pairs opening to both sides, each covering half of the
+    /// opening. The second pair folds mirrored to the first one.
+    Four,
+}
+
+impl FoldingDoorPanels {
+    fn mass(&self) -> f32 {
+        match self {
+            FoldingDoorPanels::Two => 1.0,
+            FoldingDoorPanels::Four => 1.6,
+        }
+    }
+}
+
+pub struct FoldingDoorBuilder<A = Animation, S = Sound, L = Light> {
+    id: usize,
+    panels: FoldingDoorPanels,
+    mass: f32,
+    cylinder_force: f32,
+    nominal_pressure: f32,
+    fill_rate: f32,
+    vent_rate: f32,
+    throttle: f32,
+    cushion_zone: f32,
+    cushion: f32,
+    friction: f32,
+    reflection: f32,
+    wind_strength: f32,
+    gust_interval: f32,
+    grab_pressure: f32,
+    regular_open_time: f32,
+    min_open_time: f32,
+    mouse_factor: f32,
+
+    fault: Option<String>,
+
+    anim_fold: A,
+    anim_fold_b: Option<A>,
+    sub_animations: Vec<MappedAnimation>,
+
+    grab_a: KeyEvent,
+    grab_b: KeyEvent,
+
+    warning: DoorWarningBuilder<S, L>,
+
+    cycle_spread: f32,
+    snd_close_end_alt: SoundPool<S>,
+
+    snd_valve_open: S,
+    snd_valve_close: S,
+    snd_vent: S,
+    snd_open_end: S,
+    snd_close_end: S,
+}
+
+impl FoldingDoorBuilder {
+    /// Animation of the second panel pair of a [`FoldingDoorPanels::Four`]
+    /// door, it receives the mirrored fold angle in degrees.
+    pub fn animation_fold_b(self, name: impl Into<String>) -> Self {
+        self.second_pair(Animation::new(Some(&name.into())))
+    }
+
+    pub fn add_warning(
+        mut self,
+        light_name: impl Into<String>,
+        sound_name: impl Into<String>,
+    ) -> Self {
+        self.warning = self.warning.lamp(light_name).snd_interior(sound_name);
+        self
+    }
+
+    /// Adds an alternative sample of the closing end sound, one is chosen per cycle.
+    pub fn add_close_end_variant(mut self, name: impl Into<String>) -> Self {
+        self.snd_close_end_alt
+            .push(Sound::new_simple(Some(&name.into())));
+        self
+    }
+
+    pub fn valve_sounds(
+        mut self,
+        sound_valve_open_name: impl Into<String>,
+        sound_valve_close_name: impl Into<String>,
+        sound_vent_name: impl Into<String>,
+    ) -> Self {
+        self.snd_valve_open = Sound::new_simple(Some(&sound_valve_open_name.into()));
+        self.snd_valve_close = Sound::new_simple(Some(&sound_valve_close_name.into()));
+        self.snd_vent = Sound::new_simple(Some(&sound_vent_name.into()));
+        self
+    }
+
+    pub fn end_sounds(
+        mut self,
+        sound_open_end_name: impl Into<String>,
+        sound_close_end_name: impl Into<String>,
+    ) -> Self {
+        self.snd_open_end = Sound::new_simple(Some(&sound_open_end_name.into()));
+        self.snd_close_end = Sound::new_simple(Some(&sound_close_end_name.into()));
+        self
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> FoldingDoorBuilder<A, S, L> {
+    /// Adds an animation driven by the opening position of the door.
+    ///
+    /// The position from 0.0 (closed) to 1.0 (open) is mapped through `path`,
+    /// e.g. for guide arms, rollers or the second panel pair.
+    pub fn add_sub_animation(
+        mut self,
+        name: impl Into<String>,
+        path: Option<PiecewiseLinearFunction>,
+    ) -> Self {
+        self.sub_animations
+            .push(MappedAnimation::new(Some(&name.into()), path));
+        self
+    }
+
+    /// Output of the second panel pair of a [`FoldingDoorPanels::Four`] door.
+    pub fn second_pair(mut self, anim: A) -> Self {
+        self.anim_fold_b = Some(anim);
+        self
+    }
+
+    pub fn mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Force of the cylinder at the nominal pressure in bar.
+    pub fn cylinder(mut self, force: f32, nominal_pressure: f32) -> Self {
+        self.cylinder_force = force;
+        self.nominal_pressure = nominal_pressure;
+        self
+    }
+
+    /// Rates at which the cylinder is filled and vented.
+    pub fn valve_rates(mut self, fill_rate: f32, vent_rate: f32) -> Self {
+        self.fill_rate = fill_rate;
+        self.vent_rate = vent_rate;
+        self
+    }
+
+    /// Damping by the exhaust throttle of the cylinder.
+    pub fn throttle(mut self, throttle: f32) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Additional damping inside the end zones of the cylinder.
+    pub fn cushion(mut self, zone: f32, cushion: f32) -> Self {
+        self.cushion_zone = zone;
+        self.cushion = cushion;
+        self
+    }
+
+    pub fn friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub fn reflection(mut self, reflection: f32) -> Self {
+        self.reflection = reflection;
+        self
+    }
+
+    /// Strength and mean interval of the wind gusts acting on the door.
+    pub fn wind(mut self, strength: f32, gust_interval: f32) -> Self {
+        self.wind_strength = strength;
+        self.gust_interval = gust_interval;
+        self
+    }
+
+    /// Cylinder pressure in bar below which the door can be moved by hand.
+    pub fn grab_pressure(mut self, grab_pressure: f32) -> Self {
+        self.grab_pressure = grab_pressure;
+        self
+    }
+
+    pub fn regular_open_time(mut self, regular_open_time: f32) -> Self {
+        self.regular_open_time = regular_open_time;
+        self
+    }
+
+    pub fn min_open_time(mut self, min_open_time: f32) -> Self {
+        self.min_open_time = min_open_time;
+        self
+    }
+
+    pub fn mouse_factor(mut self, mouse_factor: f32) -> Self {
+        self.mouse_factor = mouse_factor;
+        self
+    }
+
+    /// Id of the fault in the fault manager with which the door drive fails.
+    pub fn fault(mut self, id: impl Into<String>) -> Self {
+        self.fault = Some(id.into());
+        self
+    }
+
+    pub fn grab_events(mut self, event_a: impl Into<String>, event_b: impl Into<String>) -> Self {
+        self.grab_a = KeyEvent::new(Some(&event_a.into()), None);
+        self.grab_b = KeyEvent::new(Some(&event_b.into()), None);
+        self
+    }
+
+    pub fn modify_warn_relais(mut self, interval: f32, on_time: f32, reset_time: f32) -> Self {
+        self.warning = self.warning.pattern(ChimePattern::Pulsed {
+            interval,
+            on_time,
+            reset_time,
+        });
+        self
+    }
+
+    /// Closing warning of the door, replaces
+    /// [`add_warning`](FoldingDoorBuilder::add_warning).
+    pub fn door_warning(mut self, warning: DoorWarningBuilder<S, L>) -> Self {
+        self.warning = warning;
+        self
+    }
+
+    /// Maximum deviation of the drive speed from cycle to cycle, e.g. 0.05 for ±5 %.
+    pub fn cycle_variation(mut self, spread: f32) -> Self {
+        self.cycle_spread = spread;
+        self
+    }
+
+    pub fn build(self) -> FoldingDoor<A, S, L> {
+        FoldingDoor {
+            id: self.id,
+            panels: self.panels,
+            mass: self.mass,
+            cylinder_force: self.cylinder_force,
+            nominal_pressure: self.nominal_pressure,
+            fill_rate: self.fill_rate,
+            vent_rate: self.vent_rate,
+            throttle: self.throttle,
+            cushion_zone: self.cushion_zone,
+            cushion: self.cushion,
+            friction: self.friction,
+            reflection: self.reflection,
+            wind_strength: self.wind_strength,
+            gust_interval: self.gust_interval,
+            grab_pressure: self.grab_pressure,
+            regular_open_time: self.regular_open_time,
+            min_open_time: self.min_open_time,
+            mouse_factor: self.mouse_factor,
+            fault: self.fault,
+            drive_factor: 1.0,
+            pos: 0.0,
+            speed: 0.0,
+            cylinder_pressure: 0.0,
+            valve: 0,
+            command: DoorTarget::default(),
+            target: 0,
+            close_timer: 0.0,
+            wind: 0.0,
+            wind_target: 0.0,
+            gust_timer: 0.0,
+            gradient: 0.0,
+            supply_pressure: self.nominal_pressure,
+            state: DoorState::Closed,
+            anim_fold: self.anim_fold,
+            anim_fold_b: match self.panels {
+                FoldingDoorPanels::Two => None,
+                FoldingDoorPanels::Four => self.anim_fold_b,
+            },
+            sub_animations: self.sub_animations,
+            grab_a: self.grab_a,
+            grab_b: self.grab_b,
+            warning: self.warning.build(),
+            cycles: DoorCycles::new(self.cycle_spread),
+            snd_close_end_alt: self.snd_close_end_alt,
+            snd_valve_open: self.snd_valve_open,
+            snd_valve_close: self.snd_valve_close,
+            snd_vent: self.snd_vent,
+            snd_open_end: self.snd_open_end,
+            snd_close_end: self.snd_close_end,
+            pass_door: VehicleDoor::new(self.id, true, true),
+        }
+    }
+}
+
+/// Folding door with two or four panels and pneumatic cylinder.
+///
+/// # Example
+///
+/// ```rust
+/// let mut door = FoldingDoor::builder(0, FoldingDoorPanels::Four, "Door_0_Fold")
+///     .animation_fold_b("Door_0_Fold_B")
+///     .add_sub_animation("Door_0_Arm", None)
+///     .valve_sounds("Snd_Door_0_In", "Snd_Door_0_Out", "Snd_Door_0_Vent")
+///     .grab_events("Door_0_Grab_A", "Door_0_Grab_B")
+///     .build();
+///
+/// // In the main loop:
+/// door.gradient = -0.02;
+/// door.tick(power, main_reservoir_pressure, DoorTarget::Release, false, request);
+/// ```
+#[derive(Debug)]
+pub struct FoldingDoor<A = Animation, S = Sound, L = Light> {
+    id: usize,
+    panels: FoldingDoorPanels,
+    mass: f32,
+    cylinder_force: f32,
+    nominal_pressure: f32,
+    fill_rate: f32,
+    vent_rate: f32,
+    throttle: f32,
+    cushion_zone: f32,
+    cushion: f32,
+    friction: f32,
+    reflection: f32,
+    wind_strength: f32,
+    gust_interval: f32,
+    grab_pressure: f32,
+    regular_open_time: f32,
+    min_open_time: f32,
+    mouse_factor: f32,
+
+    fault: Option<String>,
+    /// Speed factor of the drive, e.g. reduced by wear
+    pub drive_factor: f32,
+
+    /// Opening position from 0.0 (closed) to 1.0 (open)
+    pub pos: f32,
+    speed: f32,
+    /// Current pressure in the door cylinder in bar
+    pub cylinder_pressure: f32,
+    valve: i32,
+    command: DoorTarget,
+    target: i32,
+    close_timer: f32,
+    wind: f32,
+    wind_target: f32,
+    gust_timer: f32,
+    /// Inclination of the vehicle in opening direction, positive values pull
+    /// the door open
+    pub gradient: f32,
+    /// Pressure of the air supply in bar used by the [`Door`] interface
+    pub supply_pressure: f32,
+    pub state: DoorState,
+
+    anim_fold: A,
+    anim_fold_b: Option<A>,
+    sub_animations: Vec<MappedAnimation>,
+
+    grab_a: KeyEvent,
+    grab_b: KeyEvent,
+
+    warning: DoorWarning<S, L>,
+
+    /// Open/close cycles of the door
+    pub cycles: DoorCycles,
+    snd_close_end_alt: SoundPool<S>,
+
+    snd_valve_open: S,
+    snd_valve_close: S,
+    snd_vent: S,
+    snd_open_end: S,
+    snd_close_end: S,
+
+    pass_door: VehicleDoor,
+}
+
+impl FoldingDoor {
+    /// Creates a builder for a folding door.
+    ///
+    /// The animation `animation_fold_name` receives the fold angle of the
+    /// panels in degrees.
+    pub fn builder(
+        id: usize,
+        panels: FoldingDoorPanels,
+        animation_fold_name: impl Into<String>,
+    ) -> FoldingDoorBuilder {
+        Self::builder_with_outputs(
+            id,
+            panels,
+            Animation::new(Some(&animation_fold_name.into())),
+        )
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> FoldingDoor<A, S, L> {
+    /// Creates a builder on any outputs, e.g. the `Mock*` types of
+    /// [`mock`](crate::api::mock) in tests. Sounds and lamp start unset.
+    pub fn builder_with_outputs(
+        id: usize,
+        panels: FoldingDoorPanels,
+        anim_fold: A,
+    ) -> FoldingDoorBuilder<A, S, L>
+    where
+        S: Default,
+        L: Default,
+    {
+        FoldingDoorBuilder {
+            id,
+            panels,
+            mass: panels.mass(),
+            cylinder_force: 2.5,
+            nominal_pressure: 5.0,
+            fill_rate: 4.0,
+            vent_rate: 2.0,
+            throttle: gen_f32(1.8..=2.2),
+            cushion_zone: 0.1,
+            cushion: 6.0,
+            friction: 0.05,
+            reflection: 0.1,
+            wind_strength: 0.0,
+            gust_interval: 3.0,
+            grab_pressure: 0.5,
+            regular_open_time: 6.0,
+            min_open_time: 2.0,
+            mouse_factor: 1.0,
+            fault: None,
+            anim_fold,
+            anim_fold_b: None,
+            sub_animations: Vec::new(),
+            grab_a: KeyEvent::new(None, None),
+            grab_b: KeyEvent::new(None, None),
+            warning: DoorWarningBuilder::default(),
+            cycle_spread: 0.0,
+            snd_close_end_alt: SoundPool::default(),
+            snd_valve_open: S::default(),
+            snd_valve_close: S::default(),
+            snd_vent: S::default(),
+            snd_open_end: S::default(),
+            snd_close_end: S::default(),
+        }
+    }
+
+    fn wind_tick(&mut self) {
+        if self.wind_strength <= 0.0 {
+            self.wind = 0.0;
+            return;
+        }
+
+        self.gust_timer -= delta();
+        if self.gust_timer <= 0.0 {
+            self.gust_timer = gen_f32(0.5..=1.5) * self.gust_interval;
+            self.wind_target = gen_f32(-1.0..=1.0) * self.wind_strength;
+        }
+        self.wind += (self.wind_target - self.wind) * (delta() * 2.0).min(1.0);
+    }
+
+    fn move_door(&mut self, force: f32) {
+        let mut damping = self.throttle * self.cylinder_pressure / self.nominal_pressure;
+        if self.pos < self.cushion_zone || self.pos > 1.0 - self.cushion_zone {
+            damping += self.cushion;
+        }
+
+        let mut a = (force - damping * self.speed) / self.mass;
+        if self.speed != 0.0 {
+            a -= self.speed.signum() * self.friction;
+        }
+
+        let mut new_speed = self.speed + a * delta();
+        if force.abs() < self.friction * self.mass && new_speed * self.speed < 0.0 {
+            new_speed = 0.0;
+        }
+        self.speed = new_speed;
+
+        let mut new_pos = self.pos + self.speed * delta();
+
+        if new_pos > 1.0 {
+            new_pos = 1.0;
+            if self.speed > 0.05 {
+                self.snd_open_end.start();
+            }
+            self.speed = -self.speed * self.reflection;
+        } else if new_pos < 0.0 {
+            new_pos = 0.0;
+            if self.speed < -0.05 {
+                match self
+                    .cycles
+                    .sample(self.snd_close_end_alt.len() + 1)
+                    .checked_sub(1)
+                {
+                    Some(i) => self.snd_close_end_alt.start_sample(i),
+                    None => self.snd_close_end.start(),
+                }
+            }
+            self.speed = -self.speed * self.reflection;
+        }
+
+        self.pos = new_pos;
+    }
+
+    fn update_animations(&mut self) {
+        let angle = self.fold_angle();
+        self.anim_fold.set(angle);
+        if let Some(anim) = &mut self.anim_fold_b {
+            anim.set(-angle);
+        }
+        for anim in &mut self.sub_animations {
+            anim.set(self.pos);
+        }
+    }
+
+    pub fn warn_tick(&mut self, power: bool, target: bool, spannung: f32) {
+        self.warning.tick(power, target, self.state, spannung);
+    }
+
+    /// Updates the door.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the door valves are supplied
+    /// * `air_pressure` - Pressure of the air supply in bar
+    /// * `door_target` - Door command
+    /// * `emergency_door_unlock` - Vents the cylinder
+    /// * `haltewunsch` - Stop request at this door
+    pub fn tick(
+        &mut self,
+        power: bool,
+        air_pressure: f32,
+        door_target: DoorTarget,
+        emergency_door_unlock: bool,
+        haltewunsch: bool,
+    ) {
+        // Ausgefallener Antrieb verhält sich wie ohne Versorgung
+        let power = power && !self.fault.as_deref().is_some_and(fault_active);
+
+        let lichtschranke_frei = !self.pass_door.occupied();
+        let grabbing = self.grab_a.is_pressed() || self.grab_b.is_pressed();
+
+        // Ansteuerung
+        //----------------------------------------------
+
+        if power && !emergency_door_unlock {
+            if door_target == DoorTarget::Open
+                || (door_target == DoorTarget::Release && haltewunsch)
+            {
+                self.target = 1;
+            }
+
+            if door_target == DoorTarget::Release
+                && self.state == DoorState::Open
+                && lichtschranke_frei
+            {
+                self.close_timer += delta();
+                if self.close_timer > self.regular_open_time {
+                    self.target = -1;
+                }
+            } else if door_target == DoorTarget::Close && self.state == DoorState::Open {
+                self.close_timer += delta();
+                if self.close_timer > self.min_open_time {
+                    self.target = -1;
+                }
+            } else {
+                self.close_timer = 0.0;
+            }
+
+            // Reversieren bei Lichtschranke
+            if door_target == DoorTarget::Release
+                && self.state == DoorState::Other
+                && self.target < 0
+                && !lichtschranke_frei
+            {
+                self.target = 1;
+            }
+
+            if door_target == DoorTarget::FastClose {
+                self.target = -1;
+            }
+        } else {
+            self.target = 0;
+            self.close_timer = 0.0;
+        }
+
+        // Ventil und Zylinder
+        //----------------------------------------------
+
+        let valve_last = self.valve;
+        if self.target != 0 {
+            self.valve = self.target;
+        } else if !power || emergency_door_unlock {
+            self.valve = 0;
+        }
+
+        if valve_last != self.valve {
+            if self.valve > 0 {
+                self.snd_valve_open.start();
+            } else if self.valve < 0 {
+                self.snd_valve_close.start();
+            } else {
+                self.snd_vent.start();
+            }
+        }
+
+        if self.valve != 0 {
+            let supply = air_pressure.max(0.0);
+            self.cylinder_pressure += (supply - self.cylinder_pressure)
+                * (self.fill_rate * self.drive_factor * self.cycles.speed_factor * delta())
+                    .min(1.0);
+        } else {
+            self.cylinder_pressure -= self.cylinder_pressure * (self.vent_rate * delta()).min(1.0);
+        }
+
+        // Bewegung
+        //----------------------------------------------
+
+        self.wind_tick();
+
+        let movable_by_hand = self.cylinder_pressure < self.grab_pressure;
+
+        if grabbing && movable_by_hand {
+            let mouse_delta_x = mouse_move().x * self.mouse_factor;
+            self.pos = (self.pos + mouse_delta_x * delta()).clamp(0.0, 1.0);
+            self.speed = 0.0;
+        } else if grabbing {
+            self.speed = 0.0;
+        } else {
+            let cylinder = self.valve as f32 * self.cylinder_force * self.cylinder_pressure
+                / self.nominal_pressure;
+            let disturbance = self.wind + self.gradient * self.mass * GRAVITY;
+            self.move_door(cylinder + disturbance);
+        }
+
+        if (self.target > 0 && self.pos >= 1.0) || (self.target < 0 && self.pos <= 0.0) {
+            self.target = 0;
+        }
+
+        if self.pos >= 1.0 {
+            self.state = DoorState::Open;
+        } else if self.pos < 0.005 {
+            self.state = DoorState::Closed;
+        } else {
+            self.state = DoorState::Other;
+        }
+
+        self.update_animations();
+
+        self.pass_door.update_open(self.pos > 0.75);
+        self.pass_door
+            .update_released(door_target >= DoorTarget::Release);
+        self.cycles.update(self.state);
+    }
+
+    /// Fold angle of the panels in degrees for the current position.
+    ///
+    /// Each panel pair folds like a scissor, so the opening grows with
+    /// `1 - cos(angle)`. The two pairs of a four panel door cover half of the
+    /// opening each and fold by the same angle.
+    pub fn fold_angle(&self) -> f32 {
+        (1.0 - self.pos).clamp(-1.0, 1.0).acos() * 180.0 / PI
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> SensitiveEdgeDoor
+    for FoldingDoor<A, S, L>
+{
+    fn opening_pos(&self) -> f32 {
+        self.pos
+    }
+
+    fn is_closing(&self) -> bool {
+        self.valve < 0
+    }
+
+    fn hold_at(&mut self, pos: f32) {
+        if self.pos < pos {
+            self.pos = pos;
+            self.speed = self.speed.max(0.0);
+            self.update_animations();
+        }
+    }
+
+    fn reopen(&mut self) {
+        self.target = 1;
+        self.close_timer = 0.0;
+    }
+
+    fn stop_drive(&mut self) {
+        // Zylinder entlüften, die Tür bleibt stehen
+        self.target = 0;
+        if self.valve != 0 {
+            self.valve = 0;
+            self.snd_vent.start();
+        }
+        self.speed = 0.0;
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> Door for FoldingDoor<A, S, L> {
+    fn set_target(&mut self, target: DoorTarget) {
+        self.command = target;
+    }
+
+    fn tick(&mut self, power: bool, emergency_door_unlock: bool, request: bool) {
+        FoldingDoor::tick(
+            self,
+            power,
+            self.supply_pressure,
+            self.command,
+            emergency_door_unlock,
+            request,
+        );
+    }
+
+    fn warn_tick(&mut self, power: bool, target: bool, voltage: f32) {
+        FoldingDoor::warn_tick(self, power, target, voltage);
+    }
+
+    fn state(&self) -> DoorState {
+        self.state
+    }
+
+    fn pos(&self) -> f32 {
+        self.pos
+    }
+
+    fn occupied(&self) -> bool {
+        self.pass_door.occupied()
+    }
+
+    fn cycles(&self) -> &DoorCycles {
+        &self.cycles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{
+        mock::{MockAnimation, MockLight, MockSound},
+        replay::run_frames,
+    };
+
+    type MockDoor = FoldingDoor<MockAnimation, MockSound, MockLight>;
+
+    fn mock_door() -> MockDoor {
+        MockDoor::builder_with_outputs(0, FoldingDoorPanels::Two, MockAnimation::default())
+            .throttle(2.0)
+            .build()
+    }
+
+    #[test]
+    fn test_four_panels() {
+        let mut door =
+            MockDoor::builder_with_outputs(0, FoldingDoorPanels::Four, MockAnimation::default())
+                .second_pair(MockAnimation::default())
+                .throttle(2.0)
+                .build();
+
+        run_frames(20, 0.05, || {
+            door.tick(true, 5.0, DoorTarget::Open, false, false)
+        });
+        assert_eq!(door.state, DoorState::Other);
+        let pos_b = door.anim_fold_b.as_ref().unwrap().pos;
+        assert!(door.anim_fold.pos > 0.0);
+        assert_eq!(pos_b, -door.anim_fold.pos);
+
+        // Die schwerere Tür öffnet langsamer als die zweiflügelige
+        let mut two = mock_door();
+        run_frames(20, 0.05, || {
+            two.tick(true, 5.0, DoorTarget::Open, false, false)
+        });
+        assert!(two.pos > door.pos);
+    }
+
+    #[test]
+    fn test_open_and_close() {
+        let mut door = mock_door();
+
+        run_frames(100, 0.05, || {
+            door.tick(true, 5.0, DoorTarget::Open, false, false)
+        });
+        assert_eq!(door.state, DoorState::Open);
+        assert_eq!(door.snd_valve_open.start_count, 1);
+        assert!((door.anim_fold.pos - 90.0).abs() < 0.1);
+
+        run_frames(200, 0.05, || {
+            door.tick(true, 5.0, DoorTarget::Close, false, false)
+        });
+        assert_eq!(door.state, DoorState::Closed);
+        assert_eq!(door.snd_valve_close.start_count, 1);
+        assert_eq!(door.cycles.count, 1);
+    }
+
+    #[test]
+    fn test_no_movement_without_air() {
+        let mut door = mock_door();
+
+        run_frames(100, 0.05, || {
+            door.tick(true, 0.0, DoorTarget::Open, false, false)
+        });
+        assert_eq!(door.state, DoorState::Closed);
+        assert_eq!(door.anim_fold.pos, 0.0);
+    }
+}
@@ -1,2 +1,13 @@
 pub mod aeg_electric_door;
+pub mod door;
 pub mod door_buttons;
+pub mod door_controller;
+pub mod door_interlock;
+pub mod door_request_button;
+pub mod door_warning;
+pub mod emergency_release;
+pub mod folding_door;
+pub mod obstacle;
+pub mod ramp;
+pub mod sliding_step;
+pub mod swing_door;
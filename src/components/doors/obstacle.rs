@@ -0,0 +1,275 @@
+//! Obstacles in the door opening ("Einklemmschutz").
+//!
+//! The [`DoorObstacle`] places an obstruction into the opening of a door,
+//! either by chance while the door closes or set up from a scenario. A solid
+//! obstacle stops the closing leaves, the squeeze force builds up until the
+//! sensitive edge reopens the door. After the third reversal the door stays
+//! where it is and the obstacle alarm is raised until the obstruction is gone.
+//! Thin objects like straps are not detected by the edge: the door closes on
+//! them and only the trapped-object warning reports them.
+//!
+//! The model works with every door type implementing [`SensitiveEdgeDoor`].
+
+use crate::api::{general::delta, light::Light, random::gen_f32, sound::Sound};
+
+/// Number of reversals after which the obstacle alarm is raised
+const MAX_REVERSALS: u32 = 3;
+
+/// Door drive which can be stopped by an obstacle in its opening.
+pub trait SensitiveEdgeDoor {
+    /// Opening position from 0.0 (closed) to 1.0 (open).
+    fn opening_pos(&self) -> f32;
+
+    /// Whether the door drive is closing.
+    fn is_closing(&self) -> bool;
+
+    /// Stops the leaves at the given position, they cannot close further.
+    fn hold_at(&mut self, pos: f32);
+
+    /// Reopens the door after the sensitive edge has been triggered.
+    fn reopen(&mut self);
+
+    /// Switches the drive off, the leaves stay where they are.
+    fn stop_drive(&mut self);
+}
+
+/// Kind of obstruction in the door opening.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ObstacleKind {
+    /// Passenger or luggage, stops the leaves
+    #[default]
+    Solid,
+    /// Strap or coat tail, not detected by the sensitive edge
+    Thin,
+}
+
+pub struct DoorObstacleBuilder {
+    probability: f32,
+    thin_share: f32,
+    leave_probability: f32,
+    squeeze_rate: f32,
+    reverse_force: f32,
+    max_force: f32,
+
+    lm_alarm: Light,
+    lm_trapped: Light,
+    snd_alarm: Sound,
+}
+
+impl DoorObstacleBuilder {
+    /// Probability of an obstruction per closing cycle and the share of thin
+    /// objects among them.
+    pub fn random(mut self, probability: f32, thin_share: f32) -> Self {
+        self.probability = probability;
+        self.thin_share = thin_share;
+        self
+    }
+
+    /// Probability that a solid obstacle leaves the opening after a reversal.
+    pub fn leave_probability(mut self, leave_probability: f32) -> Self {
+        self.leave_probability = leave_probability;
+        self
+    }
+
+    /// Buildup of the squeeze force in N/s, the force in N at which the
+    /// sensitive edge reverses the door and the maximum force of the drive.
+    pub fn squeeze(mut self, squeeze_rate: f32, reverse_force: f32, max_force: f32) -> Self {
+        self.squeeze_rate = squeeze_rate;
+        self.reverse_force = reverse_force;
+        self.max_force = max_force;
+        self
+    }
+
+    /// Lamps of the obstacle alarm and the trapped-object warning.
+    pub fn lamps(mut self, alarm_name: impl Into<String>, trapped_name: impl Into<String>) -> Self {
+        self.lm_alarm = Light::new(Some(&alarm_name.into()));
+        self.lm_trapped = Light::new(Some(&trapped_name.into()));
+        self
+    }
+
+    pub fn snd_alarm(mut self, name: impl Into<String>) -> Self {
+        self.snd_alarm = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> DoorObstacle {
+        DoorObstacle {
+            probability: self.probability,
+            thin_share: self.thin_share,
+            leave_probability: self.leave_probability,
+            squeeze_rate: self.squeeze_rate,
+            reverse_force: self.reverse_force,
+            max_force: self.max_force,
+            lm_alarm: self.lm_alarm,
+            lm_trapped: self.lm_trapped,
+            snd_alarm: self.snd_alarm,
+            closing_last: false,
+            obstacle: None,
+            squeeze_force: 0.0,
+            reversals: 0,
+            alarm: false,
+            trapped: false,
+        }
+    }
+}
+
+/// Obstacle model with sensitive edge, reversals and alarm.
+///
+/// # Example
+///
+/// ```rust
+/// let mut obstacle = DoorObstacle::builder()
+///     .random(0.05, 0.2)
+///     .lamps("L_Door1_Alarm", "L_Door1_Trapped")
+///     .snd_alarm("Snd_Door1_Alarm")
+///     .build();
+///
+/// // Scenario:
+/// obstacle.place(ObstacleKind::Solid, 0.4);
+///
+/// // In the main loop:
+/// door.tick(power, door_target, false, false, request);
+/// obstacle.tick(&mut door, power);
+/// ```
+pub struct DoorObstacle {
+    probability: f32,
+    thin_share: f32,
+    leave_probability: f32,
+    squeeze_rate: f32,
+    reverse_force: f32,
+    max_force: f32,
+
+    lm_alarm: Light,
+    lm_trapped: Light,
+    snd_alarm: Sound,
+
+    closing_last: bool,
+    obstacle: Option<(ObstacleKind, f32)>,
+
+    /// Force on the obstacle in N
+    pub squeeze_force: f32,
+    /// Number of reversals caused by the current obstacle
+    pub reversals: u32,
+    /// Whether the door gave up closing after the last reversal
+    pub alarm: bool,
+    /// Whether an object is trapped in the closed door
+    pub trapped: bool,
+}
+
+impl DoorObstacle {
+    /// Creates a new obstacle builder, random obstacles are off by default.
+    pub fn builder() -> DoorObstacleBuilder {
+        DoorObstacleBuilder {
+            probability: 0.0,
+            thin_share: 0.2,
+            leave_probability: 0.5,
+            squeeze_rate: 400.0,
+            reverse_force: 150.0,
+            max_force: 300.0,
+            lm_alarm: Light::new(None),
+            lm_trapped: Light::new(None),
+            snd_alarm: Sound::new_simple(None),
+        }
+    }
+
+    /// Places an obstacle at the given opening position, e.g. from a scenario.
+    pub fn place(&mut self, kind: ObstacleKind, pos: f32) {
+        self.obstacle = Some((kind, pos.clamp(0.0, 1.0)));
+    }
+
+    /// Removes the obstacle from the opening.
+    pub fn remove(&mut self) {
+        self.obstacle = None;
+    }
+
+    /// Current obstacle and its opening position.
+    pub fn obstacle(&self) -> Option<(ObstacleKind, f32)> {
+        self.obstacle
+    }
+
+    /// Updates the obstacle and acts on the door.
+    ///
+    /// Has to be called after the `tick` of the door.
+    ///
+    /// # Arguments
+    ///
+    /// * `door` - Door the obstacle is placed in
+    /// * `power` - Whether the door control is supplied
+    pub fn tick(&mut self, door: &mut impl SensitiveEdgeDoor, power: bool) {
+        let closing = door.is_closing();
+        let pos = door.opening_pos();
+
+        // Zufälliges Hindernis beim Beginn des Schließens
+        if closing
+            && !self.closing_last
+            && self.obstacle.is_none()
+            && gen_f32(0.0..1.0) < self.probability
+        {
+            let kind = if gen_f32(0.0..1.0) < self.thin_share {
+                ObstacleKind::Thin
+            } else {
+                ObstacleKind::Solid
+            };
+            self.place(kind, gen_f32(0.1..0.9) * pos);
+        }
+        self.closing_last = closing;
+
+        let mut blocked = false;
+        match self.obstacle {
+            Some((ObstacleKind::Solid, obstacle_pos)) if pos <= obstacle_pos => {
+                door.hold_at(obstacle_pos);
+                blocked = closing;
+            }
+            Some((ObstacleKind::Thin, _)) => {
+                // Eingeklemmt, bis die Tür wieder öffnet
+                if pos < 0.005 {
+                    self.trapped = true;
+                } else if self.trapped && pos > 0.1 {
+                    self.trapped = false;
+                    self.obstacle = None;
+                }
+            }
+            _ => {}
+        }
+
+        if blocked {
+            self.squeeze_force =
+                (self.squeeze_force + self.squeeze_rate * delta()).min(self.max_force);
+        } else {
+            self.squeeze_force = 0.0;
+        }
+
+        if self.alarm {
+            if self.obstacle.is_none() {
+                self.alarm = false;
+                self.reversals = 0;
+                self.snd_alarm.stop();
+            } else if closing {
+                door.stop_drive();
+            }
+        } else if power && self.squeeze_force >= self.reverse_force {
+            self.squeeze_force = 0.0;
+            self.reversals += 1;
+
+            if self.reversals >= MAX_REVERSALS {
+                self.alarm = true;
+                door.stop_drive();
+                self.snd_alarm.start();
+            } else {
+                door.reopen();
+                if gen_f32(0.0..1.0) < self.leave_probability {
+                    self.obstacle = None;
+                }
+            }
+        }
+
+        if self.obstacle.is_none() && !self.alarm {
+            self.reversals = 0;
+        }
+
+        self.lm_alarm
+            .set_brightness((power && self.alarm) as u8 as f32);
+        self.lm_trapped
+            .set_brightness((power && self.trapped) as u8 as f32);
+    }
+}
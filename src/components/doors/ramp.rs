@@ -0,0 +1,335 @@
+//! Deployable wheelchair ramp at a passenger door.
+//!
+//! The ramp is either flipped out by hand or driven by an electric motor. It
+//! can only be deployed at standstill with the door open, and as long as it is
+//! not stowed, [`Ramp::interlock`] keeps the door from closing. Requests for
+//! the ramp are exchanged with the `CouplerBuggyReqest` and
+//! `CouplerBuggyReset` coupling lines.
+
+use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
+
+use crate::{
+    api::{
+        animation::MappedAnimation,
+        general::{delta, mouse_move},
+        key_event::KeyEvent,
+        light::Light,
+        sound::Sound,
+    },
+    elements::std::relays::FlasherRelay,
+    management::enums::door_enums::DoorTarget,
+};
+
+const RAMP_WARN_INTERVAL: f32 = 1.0;
+const RAMP_WARN_INTERVAL_HALF: f32 = RAMP_WARN_INTERVAL / 2.0;
+
+/// How the ramp is moved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RampDrive {
+    /// Flipped out and back by hand
+    #[default]
+    Manual,
+    /// Driven by an electric motor
+    Electric,
+}
+
+pub struct RampBuilder {
+    drive: RampDrive,
+    motor_speed: f32,
+    gravity: f32,
+    friction: f32,
+    mouse_factor: f32,
+
+    anim_name: String,
+    deploy_curve: Option<PiecewiseLinearFunction>,
+
+    key_grab: KeyEvent,
+
+    warn_relais: FlasherRelay,
+    lm_warn: Light,
+
+    snd_motor: Sound,
+    snd_deployed: Sound,
+    snd_stowed: Sound,
+    snd_warn: Sound,
+}
+
+impl RampBuilder {
+    /// Maps the deployment position from 0.0 (stowed) to 1.0 (deployed) to the
+    /// animation value.
+    pub fn deploy_curve(mut self, path: PiecewiseLinearFunction) -> Self {
+        self.deploy_curve = Some(path);
+        self
+    }
+
+    /// Makes the ramp motor driven with the given speed in 1/s.
+    pub fn electric(mut self, motor_speed: f32) -> Self {
+        self.drive = RampDrive::Electric;
+        self.motor_speed = motor_speed;
+        self
+    }
+
+    /// Gravity and friction acting on a manual ramp.
+    pub fn manual_physics(mut self, gravity: f32, friction: f32) -> Self {
+        self.gravity = gravity;
+        self.friction = friction;
+        self
+    }
+
+    pub fn mouse_factor(mut self, mouse_factor: f32) -> Self {
+        self.mouse_factor = mouse_factor;
+        self
+    }
+
+    pub fn grab_event(mut self, name: impl Into<String>, cab_side: Option<CockpitSide>) -> Self {
+        self.key_grab = KeyEvent::new(Some(&name.into()), cab_side);
+        self
+    }
+
+    pub fn add_warning(
+        mut self,
+        light_name: impl Into<String>,
+        sound_name: impl Into<String>,
+    ) -> Self {
+        self.lm_warn = Light::new(Some(&light_name.into()));
+        self.snd_warn = Sound::new_simple(Some(&sound_name.into()));
+        self
+    }
+
+    pub fn snd_motor(mut self, name: impl Into<String>) -> Self {
+        self.snd_motor = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn end_sounds(
+        mut self,
+        sound_deployed_name: impl Into<String>,
+        sound_stowed_name: impl Into<String>,
+    ) -> Self {
+        self.snd_deployed = Sound::new_simple(Some(&sound_deployed_name.into()));
+        self.snd_stowed = Sound::new_simple(Some(&sound_stowed_name.into()));
+        self
+    }
+
+    pub fn build(self) -> Ramp {
+        Ramp {
+            drive: self.drive,
+            motor_speed: self.motor_speed,
+            gravity: self.gravity,
+            friction: self.friction,
+            mouse_factor: self.mouse_factor,
+            pos: 0.0,
+            speed: 0.0,
+            in_use: false,
+            buggy_request: false,
+            buggy_reset: false,
+            anim: MappedAnimation::new(Some(&self.anim_name), self.deploy_curve),
+            key_grab: self.key_grab,
+            warn_relais: self.warn_relais,
+            lm_warn: self.lm_warn,
+            snd_motor: self.snd_motor,
+            snd_deployed: self.snd_deployed,
+            snd_stowed: self.snd_stowed,
+            snd_warn: self.snd_warn,
+        }
+    }
+}
+
+/// Manual or electric wheelchair ramp.
+///
+/// # Example
+///
+/// ```rust
+/// let mut ramp = Ramp::builder("Ramp_Door_1")
+///     .electric(1.0 / 6.0)
+///     .add_warning("L_Ramp_Warn", "Snd_Ramp_Warn")
+///     .build();
+///
+/// // In the main loop:
+/// buggy_req_line.update_local(btn.buggy_request);
+/// ramp.tick(power, standstill, door.state == DoorState::Open, deploy_switch, buggy_req_line.get_value());
+/// buggy_reset_line.update_local(ramp.buggy_reset);
+/// let door_target = ramp.interlock(door_target);
+/// ```
+#[derive(Debug)]
+pub struct Ramp {
+    drive: RampDrive,
+    motor_speed: f32,
+    gravity: f32,
+    friction: f32,
+    mouse_factor: f32,
+
+    /// Deployment position from 0.0 (stowed) to 1.0 (deployed)
+    pub pos: f32,
+    speed: f32,
+
+    /// Whether the ramp has been deployed since the last stowing
+    pub in_use: bool,
+    /// Whether a ramp is requested somewhere in the train
+    pub buggy_request: bool,
+    /// Set for one tick after the requested ramp has been stowed again
+    pub buggy_reset: bool,
+
+    anim: MappedAnimation,
+
+    key_grab: KeyEvent,
+
+    warn_relais: FlasherRelay,
+    lm_warn: Light,
+
+    snd_motor: Sound,
+    snd_deployed: Sound,
+    snd_stowed: Sound,
+    snd_warn: Sound,
+}
+
+impl Ramp {
+    pub fn builder(animation_name: impl Into<String>) -> RampBuilder {
+        RampBuilder {
+            drive: RampDrive::Manual,
+            motor_speed: 0.2,
+            gravity: 4.0,
+            friction: 1.0,
+            mouse_factor: 1.0,
+            anim_name: animation_name.into(),
+            deploy_curve: None,
+            key_grab: KeyEvent::new(None, None),
+            warn_relais: FlasherRelay::new(RAMP_WARN_INTERVAL, RAMP_WARN_INTERVAL_HALF, 0.1),
+            lm_warn: Light::new(None),
+            snd_motor: Sound::new_simple(None),
+            snd_deployed: Sound::new_simple(None),
+            snd_stowed: Sound::new_simple(None),
+            snd_warn: Sound::new_simple(None),
+        }
+    }
+
+    /// Whether the ramp is completely stowed.
+    pub fn stowed(&self) -> bool {
+        self.pos <= 0.0
+    }
+
+    /// Whether the ramp is completely deployed.
+    pub fn deployed(&self) -> bool {
+        self.pos >= 1.0
+    }
+
+    /// Keeps the door open as long as the ramp is not stowed.
+    pub fn interlock(&self, door_target: DoorTarget) -> DoorTarget {
+        if self.stowed() {
+            door_target
+        } else {
+            door_target.max(DoorTarget::Open)
+        }
+    }
+
+    fn manual_tick(&mut self, allowed: bool) {
+        if allowed && self.key_grab.is_pressed() {
+            let hand_delta = mouse_move().x * self.mouse_factor;
+            self.pos = (self.pos + hand_delta).clamp(0.0, 1.0);
+            // Bei pausierter Simulation keine Geschwindigkeit aus dem Mausweg ableiten
+            self.speed = if delta() > 0.0 {
+                hand_delta / delta()
+            } else {
+                0.0
+            };
+            return;
+        }
+
+        // Beyond the upright position the ramp falls to the respective end
+        let force = if self.pos > 0.5 {
+            self.gravity
+        } else {
+            -self.gravity
+        };
+        self.speed += force * delta();
+        if self.speed.abs() > 0.0001 {
+            let new_speed = self.speed - self.speed.signum() * self.friction * delta();
+            self.speed = if new_speed * self.speed < 0.0 {
+                0.0
+            } else {
+                new_speed
+            };
+        }
+        self.pos += self.speed * delta();
+    }
+
+    fn electric_tick(&mut self, allowed: bool, deploy: bool) {
+        let direction = if !allowed {
+            0.0
+        } else if deploy && !self.deployed() {
+            1.0
+        } else if !deploy && !self.stowed() {
+            -1.0
+        } else {
+            0.0
+        };
+
+        self.speed = direction * self.motor_speed;
+        self.pos += self.speed * delta();
+
+        self.snd_motor.start_stop(direction != 0.0);
+    }
+
+    /// Updates the ramp.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the ramp control is supplied
+    /// * `standstill` - Whether the vehicle is standing
+    /// * `door_open` - Whether the door in front of the ramp is open
+    /// * `deploy` - Deploy command for an electric ramp, ignored for manual ramps
+    /// * `buggy_request` - Combined value of the `CouplerBuggyReqest` line
+    pub fn tick(
+        &mut self,
+        power: bool,
+        standstill: bool,
+        door_open: bool,
+        deploy: bool,
+        buggy_request: bool,
+    ) {
+        let allowed = standstill && door_open;
+        let was_deployed = self.deployed();
+        let was_stowed = self.stowed();
+
+        match self.drive {
+            RampDrive::Manual => self.manual_tick(allowed),
+            RampDrive::Electric => self.electric_tick(allowed && power, deploy),
+        }
+
+        if self.pos >= 1.0 {
+            self.pos = 1.0;
+            self.speed = 0.0;
+        } else if self.pos <= 0.0 {
+            self.pos = 0.0;
+            self.speed = 0.0;
+        }
+
+        if self.deployed() && !was_deployed {
+            self.snd_deployed.start();
+            self.in_use = true;
+        }
+
+        self.buggy_reset = false;
+        if self.stowed() && !was_stowed {
+            self.snd_stowed.start();
+            if self.in_use {
+                self.buggy_reset = true;
+            }
+            self.in_use = false;
+        }
+
+        self.buggy_request = buggy_request && !self.buggy_reset;
+
+        let moving = self.speed != 0.0;
+        if self.warn_relais.run(power && moving) == 1 {
+            self.snd_warn.start();
+        }
+        if !(power && moving) {
+            self.snd_warn.stop();
+        }
+        self.lm_warn
+            .set_brightness(self.warn_relais.is_on as u8 as f32);
+
+        self.anim.set(self.pos);
+    }
+}
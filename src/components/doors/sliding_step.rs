@@ -0,0 +1,217 @@
+//! Retractable sliding step ("Schiebetritt") below a passenger door.
+//!
+//! The [`SlidingStep`] extends as soon as its door is released and retracts
+//! again once the door has closed. The door may only open when the step is
+//! fully extended, see [`SlidingStep::interlock`], and traction is only
+//! allowed with the step retracted. A passenger standing on the step or an
+//! obstruction stops the retraction; if the step is not retracted within the
+//! fault time, the fault is latched and has to be acknowledged with
+//! [`SlidingStep::reset_fault`].
+
+use crate::{
+    api::{animation::Animation, general::delta, light::Light, random::gen_f32, sound::Sound},
+    components::doors::door::Door,
+    management::enums::door_enums::{DoorState, DoorTarget},
+};
+
+pub struct SlidingStepBuilder {
+    animation: Animation,
+    travel_time: f32,
+    fault_time: f32,
+    obstacle_probability: f32,
+
+    lm_fault: Light,
+    snd_motor: Sound,
+    snd_end: Sound,
+}
+
+impl SlidingStepBuilder {
+    /// Time in seconds for a full extension or retraction.
+    pub fn travel_time(mut self, travel_time: f32) -> Self {
+        self.travel_time = travel_time;
+        self
+    }
+
+    /// Time in seconds after which a step not retracted is latched as fault.
+    pub fn fault_time(mut self, fault_time: f32) -> Self {
+        self.fault_time = fault_time;
+        self
+    }
+
+    /// Probability from 0.0 to 1.0 that an obstruction blocks a retraction.
+    pub fn obstacle_probability(mut self, probability: f32) -> Self {
+        self.obstacle_probability = probability;
+        self
+    }
+
+    /// Fault lamp, e.g. on the door fault panel.
+    pub fn lm_fault(mut self, name: impl Into<String>) -> Self {
+        self.lm_fault = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the step motor.
+    pub fn snd_motor(mut self, name: impl Into<String>) -> Self {
+        self.snd_motor = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the step reaching an end position.
+    pub fn snd_end(mut self, name: impl Into<String>) -> Self {
+        self.snd_end = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> SlidingStep {
+        SlidingStep {
+            animation: self.animation,
+            travel_time: self.travel_time,
+            fault_time: self.fault_time,
+            obstacle_probability: self.obstacle_probability,
+            lm_fault: self.lm_fault,
+            snd_motor: self.snd_motor,
+            snd_end: self.snd_end,
+            retract_timer: 0.0,
+            obstacle_timer: 0.0,
+            retracting_last: false,
+            pos: 0.0,
+            obstructed: false,
+            fault: false,
+        }
+    }
+}
+
+/// Sliding step with door interlock, obstacle detection and fault latch.
+///
+/// # Example
+///
+/// ```rust
+/// let mut step = SlidingStep::builder("A_Tritt_1")
+///     .travel_time(1.5)
+///     .fault_time(8.0)
+///     .lm_fault("L_Tritt_Stoerung")
+///     .snd_motor("Snd_Tritt_Motor")
+///     .build();
+///
+/// // In the main loop:
+/// step.tick(power, door_controller.released, door_controller.door(0).unwrap());
+/// let door_target = step.interlock(door_target);
+/// let traction_allowed = step.retracted();
+/// ```
+pub struct SlidingStep {
+    animation: Animation,
+    travel_time: f32,
+    fault_time: f32,
+    obstacle_probability: f32,
+
+    lm_fault: Light,
+    snd_motor: Sound,
+    snd_end: Sound,
+
+    retract_timer: f32,
+    obstacle_timer: f32,
+    retracting_last: bool,
+
+    /// Position from 0.0 (retracted) to 1.0 (extended)
+    pub pos: f32,
+    /// Whether the retraction is blocked by a passenger or an obstruction
+    pub obstructed: bool,
+    /// Whether the step failed to retract, latched until reset
+    pub fault: bool,
+}
+
+impl SlidingStep {
+    /// Creates a new sliding step builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Animation of the step from 0.0 (retracted) to 1.0 (extended)
+    pub fn builder(animation_name: impl Into<String>) -> SlidingStepBuilder {
+        SlidingStepBuilder {
+            animation: Animation::new(Some(&animation_name.into())),
+            travel_time: 2.0,
+            fault_time: 10.0,
+            obstacle_probability: 0.0,
+            lm_fault: Light::new(None),
+            snd_motor: Sound::new_simple(None),
+            snd_end: Sound::new_simple(None),
+        }
+    }
+
+    /// Whether the step is completely retracted.
+    pub fn retracted(&self) -> bool {
+        self.pos <= 0.0
+    }
+
+    /// Whether the step is completely extended.
+    pub fn extended(&self) -> bool {
+        self.pos >= 1.0
+    }
+
+    /// Keeps the door from opening until the step is extended.
+    pub fn interlock(&self, door_target: DoorTarget) -> DoorTarget {
+        if self.extended() {
+            door_target
+        } else {
+            door_target.min(DoorTarget::Release)
+        }
+    }
+
+    /// Acknowledges a latched fault, the step tries to retract again.
+    pub fn reset_fault(&mut self) {
+        self.fault = false;
+        self.retract_timer = 0.0;
+    }
+
+    /// Updates the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the door control is supplied
+    /// * `released` - Whether the door is released
+    /// * `door` - The door above the step
+    pub fn tick(&mut self, power: bool, released: bool, door: &impl Door) {
+        let extend = released;
+        let retract = !released && door.state() == DoorState::Closed && !self.retracted();
+
+        // Bei jedem Einfahren kann etwas im Weg liegen
+        if retract && !self.retracting_last && gen_f32(0.0..1.0) < self.obstacle_probability {
+            self.obstacle_timer = gen_f32(1.0..(2.0 * self.fault_time).max(1.5));
+        }
+        self.retracting_last = retract;
+        self.obstacle_timer = (self.obstacle_timer - delta()).max(0.0);
+
+        // Trittmatte: Fahrgast auf der Stufe hält das Einfahren an
+        self.obstructed = retract && (door.occupied() || self.obstacle_timer > 0.0);
+
+        let direction = if !power || self.fault {
+            0.0
+        } else if extend && !self.extended() {
+            1.0
+        } else if retract && !self.obstructed {
+            -1.0
+        } else {
+            0.0
+        };
+
+        let was_end = self.retracted() || self.extended();
+        self.pos = (self.pos + direction * delta() / self.travel_time.max(0.1)).clamp(0.0, 1.0);
+        if direction != 0.0 && (self.retracted() || self.extended()) && !was_end {
+            self.snd_end.start();
+        }
+        self.snd_motor.start_stop(direction != 0.0);
+
+        if retract {
+            self.retract_timer += delta();
+            if self.retract_timer > self.fault_time {
+                self.fault = true;
+            }
+        } else {
+            self.retract_timer = 0.0;
+        }
+
+        self.lm_fault
+            .set_brightness((power && self.fault) as u8 as f32);
+        self.animation.set(self.pos);
+    }
+}
@@ -0,0 +1,657 @@
+//! Outward swinging double-leaf door (Außenschwenktür).
+//!
+//! Both leaves are driven by a rotary actuator over a common linkage. The
+//! actuator is modelled with inertia, damping and a limited torque, so the
+//! leaves accelerate and brake smoothly. In the closed end position an end
+//! lock latches in, which is the only state reported as [`DoorState::Closed`].
+//!
+//! The door uses the same [`DoorTarget`]/[`DoorState`] interface as the
+//! [`AegElectricDoor`](crate::components::doors::aeg_electric_door::AegElectricDoor).
+
+use crate::{
+    api::{
+        animation::Animation,
+        general::{delta, mouse_move},
+        key_event::KeyEvent,
+        light::Light,
+        mock::{AnimationOutput, LightOutput, SoundOutput},
+        random::gen_f32,
+        sound::{Sound, SoundPool},
+        vehicle_door::VehicleDoor,
+    },
+    components::doors::{
+        door::{Door, DoorCycles},
+        door_warning::{ChimePattern, DoorWarning, DoorWarningBuilder},
+        obstacle::SensitiveEdgeDoor,
+    },
+    management::{
+        enums::door_enums::{DoorState, DoorTarget},
+        fault_manager::fault_active,
+    },
+};
+
+pub struct SwingDoorBuilder<A = Animation, S = Sound, L = Light> {
+    id: usize,
+    open_angle: f32,
+    inertia: f32,
+    damping: f32,
+    max_torque: f32,
+    stiffness: f32,
+    open_speed: f32,
+    close_speed: f32,
+    end_speed: f32,
+    end_zone: f32,
+    lock_pos: f32,
+    reflection: f32,
+    stall_speed: f32,
+    stall_time: f32,
+    regular_open_time: f32,
+    min_open_time: f32,
+    mouse_factor: f32,
+
+    fault: Option<String>,
+
+    anim_leaf_a: A,
+    anim_leaf_b: A,
+
+    grab_a: KeyEvent,
+    grab_b: KeyEvent,
+
+    warning: DoorWarningBuilder<S, L>,
+
+    cycle_spread: f32,
+    snd_lock_alt: SoundPool<S>,
+
+    snd_open: S,
+    snd_close: S,
+    snd_open_end: S,
+    snd_lock: S,
+    snd_unlock: S,
+    snd_reverse: S,
+}
+
+impl SwingDoorBuilder {
+    pub fn add_warning(
+        mut self,
+        light_name: impl Into<String>,
+        sound_name: impl Into<String>,
+    ) -> Self {
+        self.warning = self.warning.lamp(light_name).snd_interior(sound_name);
+        self
+    }
+
+    /// Adds an alternative sample of the lock sound, one is chosen per cycle.
+    pub fn add_lock_variant(mut self, name: impl Into<String>) -> Self {
+        self.snd_lock_alt
+            .push(Sound::new_simple(Some(&name.into())));
+        self
+    }
+
+    pub fn sounds(
+        mut self,
+        sound_open_name: impl Into<String>,
+        sound_close_name: impl Into<String>,
+        sound_open_end_name: impl Into<String>,
+    ) -> Self {
+        self.snd_open = Sound::new_simple(Some(&sound_open_name.into()));
+        self.snd_close = Sound::new_simple(Some(&sound_close_name.into()));
+        self.snd_open_end = Sound::new_simple(Some(&sound_open_end_name.into()));
+        self
+    }
+
+    pub fn lock_sounds(
+        mut self,
+        sound_lock_name: impl Into<String>,
+        sound_unlock_name: impl Into<String>,
+    ) -> Self {
+        self.snd_lock = Sound::new_simple(Some(&sound_lock_name.into()));
+        self.snd_unlock = Sound::new_simple(Some(&sound_unlock_name.into()));
+        self
+    }
+
+    pub fn snd_reverse(mut self, name: impl Into<String>) -> Self {
+        self.snd_reverse = Sound::new_simple(Some(&name.into()));
+        self
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> SwingDoorBuilder<A, S, L> {
+    /// Opening angle of the leaves in degrees.
+    pub fn open_angle(mut self, open_angle: f32) -> Self {
+        self.open_angle = open_angle;
+        self
+    }
+
+    /// Inertia, viscous damping and maximum torque of the rotary actuator.
+    pub fn actuator(mut self, inertia: f32, damping: f32, max_torque: f32) -> Self {
+        self.inertia = inertia;
+        self.damping = damping;
+        self.max_torque = max_torque;
+        self
+    }
+
+    pub fn stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    /// Nominal speeds while opening and closing, and the reduced speed inside
+    /// the end zone at both end positions.
+    pub fn speeds(mut self, open_speed: f32, close_speed: f32, end_speed: f32) -> Self {
+        self.open_speed = open_speed;
+        self.close_speed = close_speed;
+        self.end_speed = end_speed;
+        self
+    }
+
+    pub fn end_zone(mut self, end_zone: f32) -> Self {
+        self.end_zone = end_zone;
+        self
+    }
+
+    /// Position below which the end lock latches in.
+    pub fn lock_pos(mut self, lock_pos: f32) -> Self {
+        self.lock_pos = lock_pos;
+        self
+    }
+
+    pub fn reflection(mut self, reflection: f32) -> Self {
+        self.reflection = reflection;
+        self
+    }
+
+    /// The door reverses if it moves slower than `speed` for `time` seconds
+    /// while closing.
+    pub fn obstacle_detection(mut self, speed: f32, time: f32) -> Self {
+        self.stall_speed = speed;
+        self.stall_time = time;
+        self
+    }
+
+    pub fn regular_open_time(mut self, regular_open_time: f32) -> Self {
+        self.regular_open_time = regular_open_time;
+        self
+    }
+
+    pub fn min_open_time(mut self, min_open_time: f32) -> Self {
+        self.min_open_time = min_open_time;
+        self
+    }
+
+    pub fn mouse_factor(mut self, mouse_factor: f32) -> Self {
+        self.mouse_factor = mouse_factor;
+        self
+    }
+
+    /// Id of the fault in the fault manager with which the door drive fails.
+    pub fn fault(mut self, id: impl Into<String>) -> Self {
+        self.fault = Some(id.into());
+        self
+    }
+
+    pub fn grab_events(mut self, event_a: impl Into<String>, event_b: impl Into<String>) -> Self {
+        self.grab_a = KeyEvent::new(Some(&event_a.into()), None);
+        self.grab_b = KeyEvent::new(Some(&event_b.into()), None);
+        self
+    }
+
+    pub fn modify_warn_relais(mut self, interval: f32, on_time: f32, reset_time: f32) -> Self {
+        self.warning = self.warning.pattern(ChimePattern::Pulsed {
+            interval,
+            on_time,
+            reset_time,
+        });
+        self
+    }
+
+    /// Closing warning of the door, replaces
+    /// [`add_warning`](SwingDoorBuilder::add_warning).
+    pub fn door_warning(mut self, warning: DoorWarningBuilder<S, L>) -> Self {
+        self.warning = warning;
+        self
+    }
+
+    /// Maximum deviation of the drive speed from cycle to cycle, e.g. 0.05 for ±5 %.
+    pub fn cycle_variation(mut self, spread: f32) -> Self {
+        self.cycle_spread = spread;
+        self
+    }
+
+    pub fn build(self) -> SwingDoor<A, S, L> {
+        SwingDoor {
+            id: self.id,
+            open_angle: self.open_angle,
+            inertia: self.inertia,
+            damping: self.damping,
+            max_torque: self.max_torque,
+            stiffness: self.stiffness,
+            open_speed: self.open_speed,
+            close_speed: self.close_speed,
+            end_speed: self.end_speed,
+            end_zone: self.end_zone,
+            lock_pos: self.lock_pos,
+            reflection: self.reflection,
+            stall_speed: self.stall_speed,
+            stall_time: self.stall_time,
+            regular_open_time: self.regular_open_time,
+            min_open_time: self.min_open_time,
+            mouse_factor: self.mouse_factor,
+            fault: self.fault,
+            drive_factor: 1.0,
+            pos: 0.0,
+            speed: 0.0,
+            locked: true,
+            command: DoorTarget::default(),
+            target: 0,
+            close_timer: 0.0,
+            stall_timer: 0.0,
+            state: DoorState::Closed,
+            anim_leaf_a: self.anim_leaf_a,
+            anim_leaf_b: self.anim_leaf_b,
+            grab_a: self.grab_a,
+            grab_b: self.grab_b,
+            warning: self.warning.build(),
+            cycles: DoorCycles::new(self.cycle_spread),
+            snd_lock_alt: self.snd_lock_alt,
+            snd_open: self.snd_open,
+            snd_close: self.snd_close,
+            snd_open_end: self.snd_open_end,
+            snd_lock: self.snd_lock,
+            snd_unlock: self.snd_unlock,
+            snd_reverse: self.snd_reverse,
+            pass_door: VehicleDoor::new(self.id, true, true),
+        }
+    }
+}
+
+/// Outward swinging double-leaf door with rotary actuator and end lock.
+///
+/// # Example
+///
+/// ```rust
+/// let mut door = SwingDoor::builder(0, "Door_0_A", "Door_0_B")
+///     .open_angle(95.0)
+///     .lock_sounds("Snd_Door_0_Lock", "Snd_Door_0_Unlock")
+///     .grab_events("Door_0_Grab_A", "Door_0_Grab_B")
+///     .build();
+///
+/// // In the main loop:
+/// door.tick(power, DoorTarget::Release, false, request);
+/// ```
+#[derive(Debug)]
+pub struct SwingDoor<A = Animation, S = Sound, L = Light> {
+    id: usize,
+    open_angle: f32,
+    inertia: f32,
+    damping: f32,
+    max_torque: f32,
+    stiffness: f32,
+    open_speed: f32,
+    close_speed: f32,
+    end_speed: f32,
+    end_zone: f32,
+    lock_pos: f32,
+    reflection: f32,
+    stall_speed: f32,
+    stall_time: f32,
+    regular_open_time: f32,
+    min_open_time: f32,
+    mouse_factor: f32,
+
+    fault: Option<String>,
+    /// Speed factor of the drive, e.g. reduced by wear
+    pub drive_factor: f32,
+
+    /// Opening position from 0.0 (closed) to 1.0 (open)
+    pub pos: f32,
+    speed: f32,
+    /// Whether the end lock is latched in
+    pub locked: bool,
+    command: DoorTarget,
+    target: i32,
+    close_timer: f32,
+    stall_timer: f32,
+    pub state: DoorState,
+
+    anim_leaf_a: A,
+    anim_leaf_b: A,
+
+    grab_a: KeyEvent,
+    grab_b: KeyEvent,
+
+    warning: DoorWarning<S, L>,
+
+    /// Open/close cycles of the door
+    pub cycles: DoorCycles,
+    snd_lock_alt: SoundPool<S>,
+
+    snd_open: S,
+    snd_close: S,
+    snd_open_end: S,
+    snd_lock: S,
+    snd_unlock: S,
+    snd_reverse: S,
+
+    pass_door: VehicleDoor,
+}
+
+impl SwingDoor {
+    pub fn builder(
+        id: usize,
+        animation_leaf_a_name: impl Into<String>,
+        animation_leaf_b_name: impl Into<String>,
+    ) -> SwingDoorBuilder {
+        Self::builder_with_outputs(
+            id,
+            Animation::new(Some(&animation_leaf_a_name.into())),
+            Animation::new(Some(&animation_leaf_b_name.into())),
+        )
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> SwingDoor<A, S, L> {
+    /// Creates a builder on any outputs, e.g. the `Mock*` types of
+    /// [`mock`](crate::api::mock) in tests. Sounds and lamp start unset.
+    pub fn builder_with_outputs(
+        id: usize,
+        anim_leaf_a: A,
+        anim_leaf_b: A,
+    ) -> SwingDoorBuilder<A, S, L>
+    where
+        S: Default,
+        L: Default,
+    {
+        SwingDoorBuilder {
+            id,
+            open_angle: 90.0,
+            inertia: 1.0,
+            damping: 0.5,
+            max_torque: 2.0,
+            stiffness: 6.0,
+            open_speed: gen_f32(0.45..=0.5),
+            close_speed: gen_f32(0.38..=0.42),
+            end_speed: 0.12,
+            end_zone: 0.15,
+            lock_pos: 0.01,
+            reflection: 0.2,
+            stall_speed: 0.05,
+            stall_time: 0.3,
+            regular_open_time: 6.0,
+            min_open_time: 2.0,
+            mouse_factor: 1.0,
+            fault: None,
+            anim_leaf_a,
+            anim_leaf_b,
+            grab_a: KeyEvent::new(None, None),
+            grab_b: KeyEvent::new(None, None),
+            warning: DoorWarningBuilder::default(),
+            cycle_spread: 0.0,
+            snd_lock_alt: SoundPool::default(),
+            snd_open: S::default(),
+            snd_close: S::default(),
+            snd_open_end: S::default(),
+            snd_lock: S::default(),
+            snd_unlock: S::default(),
+            snd_reverse: S::default(),
+        }
+    }
+
+    fn move_door(&mut self, torque: f32) {
+        let accel = (torque - self.damping * self.speed) / self.inertia;
+        let mut new_speed = self.speed + accel * delta();
+        if torque == 0.0 && new_speed * self.speed < 0.0 {
+            new_speed = 0.0;
+        }
+        self.speed = new_speed;
+
+        let mut new_pos = self.pos + self.speed * delta();
+
+        if new_pos > 1.0 {
+            new_pos = 1.0;
+            if self.speed > self.end_speed * 0.5 {
+                self.snd_open_end.start();
+            }
+            self.speed = -self.speed * self.reflection;
+        } else if new_pos < 0.0 {
+            new_pos = 0.0;
+            self.speed = -self.speed * self.reflection;
+        }
+
+        self.pos = new_pos;
+    }
+
+    fn update_animations(&mut self) {
+        self.anim_leaf_a.set(self.pos * self.open_angle);
+        self.anim_leaf_b.set(self.pos * self.open_angle);
+    }
+
+    fn reverse(&mut self) {
+        self.target = 1;
+        self.stall_timer = 0.0;
+        self.snd_close.stop();
+        self.snd_reverse.start();
+    }
+
+    pub fn warn_tick(&mut self, power: bool, target: bool, spannung: f32) {
+        self.warning.tick(power, target, self.state, spannung);
+    }
+
+    pub fn tick(
+        &mut self,
+        power: bool,
+        door_target: DoorTarget,
+        emergency_door_unlock: bool,
+        haltewunsch: bool,
+    ) {
+        // Ausgefallener Antrieb verhält sich wie ohne Versorgung
+        let power = power && !self.fault.as_deref().is_some_and(fault_active);
+
+        let lichtschranke_frei = !self.pass_door.occupied();
+        let grabbing = self.grab_a.is_pressed() || self.grab_b.is_pressed();
+
+        // Ansteuerung
+        //----------------------------------------------
+
+        let target_last = self.target;
+
+        if power && !emergency_door_unlock {
+            if door_target == DoorTarget::Open
+                || (door_target == DoorTarget::Release && haltewunsch)
+            {
+                self.target = 1;
+            }
+
+            if door_target == DoorTarget::Release
+                && self.state == DoorState::Open
+                && lichtschranke_frei
+            {
+                self.close_timer += delta();
+                if self.close_timer > self.regular_open_time {
+                    self.target = -1;
+                }
+            } else if door_target == DoorTarget::Close && self.state == DoorState::Open {
+                self.close_timer += delta();
+                if self.close_timer > self.min_open_time {
+                    self.target = -1;
+                }
+            } else {
+                self.close_timer = 0.0;
+            }
+
+            if door_target == DoorTarget::FastClose {
+                self.target = -1;
+            }
+
+            // Reversieren bei Hindernis
+            if self.target < 0 && !self.locked && door_target != DoorTarget::FastClose {
+                if self.speed.abs() < self.stall_speed && self.pos > self.lock_pos {
+                    self.stall_timer += delta();
+                } else {
+                    self.stall_timer = 0.0;
+                }
+
+                if !lichtschranke_frei || self.stall_timer > self.stall_time {
+                    self.reverse();
+                }
+            } else {
+                self.stall_timer = 0.0;
+            }
+        } else {
+            self.target = 0;
+            self.close_timer = 0.0;
+            self.stall_timer = 0.0;
+        }
+
+        //----------------------------------------------
+
+        if target_last != self.target {
+            self.snd_open.stop();
+            self.snd_close.stop();
+            if self.target > 0 {
+                self.snd_open.start();
+            } else if self.target < 0 {
+                self.snd_close.start();
+            }
+        }
+
+        // Endverriegelung
+        if self.locked && ((self.target > 0 && power) || emergency_door_unlock) {
+            self.locked = false;
+            self.snd_unlock.start();
+        }
+
+        if self.locked {
+            self.pos = 0.0;
+            self.speed = 0.0;
+        } else {
+            if grabbing && (emergency_door_unlock || !power) {
+                self.pos =
+                    (self.pos + mouse_move().x * self.mouse_factor * delta()).clamp(0.0, 1.0);
+                self.speed = 0.0;
+            } else if grabbing {
+                // Festhalten des Flügels durch den Fahrgast
+                self.speed = 0.0;
+            }
+
+            let torque = if self.target != 0 && !grabbing {
+                let end_zone = if self.target > 0 {
+                    self.pos > 1.0 - self.end_zone
+                } else {
+                    self.pos < self.end_zone
+                };
+
+                let v_soll = match (self.target > 0, end_zone) {
+                    (true, false) => self.open_speed,
+                    (false, false) => -self.close_speed,
+                    (true, true) => self.end_speed,
+                    (false, true) => -self.end_speed,
+                };
+
+                ((v_soll * self.drive_factor * self.cycles.speed_factor - self.speed)
+                    * self.stiffness)
+                    .clamp(-self.max_torque, self.max_torque)
+            } else {
+                0.0
+            };
+
+            if !grabbing {
+                self.move_door(torque);
+            }
+
+            if self.pos <= self.lock_pos && self.target < 0 && power && !emergency_door_unlock {
+                self.locked = true;
+                self.pos = 0.0;
+                self.speed = 0.0;
+                self.target = 0;
+                self.snd_close.stop();
+                match self
+                    .cycles
+                    .sample(self.snd_lock_alt.len() + 1)
+                    .checked_sub(1)
+                {
+                    Some(i) => self.snd_lock_alt.start_sample(i),
+                    None => self.snd_lock.start(),
+                }
+            }
+        }
+
+        if self.target > 0 && self.pos >= 1.0 {
+            self.target = 0;
+            self.speed = 0.0;
+        }
+
+        if self.locked {
+            self.state = DoorState::Closed;
+        } else if self.pos >= 1.0 {
+            self.state = DoorState::Open;
+        } else {
+            self.state = DoorState::Other;
+        }
+
+        self.update_animations();
+
+        self.pass_door.update_open(self.pos > 0.75);
+        self.pass_door
+            .update_released(door_target >= DoorTarget::Release);
+        self.cycles.update(self.state);
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> SensitiveEdgeDoor for SwingDoor<A, S, L> {
+    fn opening_pos(&self) -> f32 {
+        self.pos
+    }
+
+    fn is_closing(&self) -> bool {
+        self.target < 0
+    }
+
+    fn hold_at(&mut self, pos: f32) {
+        if self.pos < pos && !self.locked {
+            self.pos = pos;
+            self.speed = self.speed.max(0.0);
+            self.update_animations();
+        }
+    }
+
+    fn reopen(&mut self) {
+        self.reverse();
+    }
+
+    fn stop_drive(&mut self) {
+        self.target = 0;
+        self.speed = 0.0;
+        self.stall_timer = 0.0;
+        self.snd_close.stop();
+    }
+}
+
+impl<A: AnimationOutput, S: SoundOutput, L: LightOutput> Door for SwingDoor<A, S, L> {
+    fn set_target(&mut self, target: DoorTarget) {
+        self.command = target;
+    }
+
+    fn tick(&mut self, power: bool, emergency_door_unlock: bool, request: bool) {
+        SwingDoor::tick(self, power, self.command, emergency_door_unlock, request);
+    }
+
+    fn warn_tick(&mut self, power: bool, target: bool, voltage: f32) {
+        SwingDoor::warn_tick(self, power, target, voltage);
+    }
+
+    fn state(&self) -> DoorState {
+        self.state
+    }
+
+    fn pos(&self) -> f32 {
+        self.pos
+    }
+
+    fn occupied(&self) -> bool {
+        self.pass_door.occupied()
+    }
+
+    fn cycles(&self) -> &DoorCycles {
+        &self.cycles
+    }
+}
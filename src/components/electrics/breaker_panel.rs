@@ -0,0 +1,230 @@
+//! Panel of circuit breakers ("Sicherungstafel").
+//!
+//! The [`BreakerPanel`] holds the circuit breakers of a vehicle by name. Each
+//! breaker has a lever which can be operated with a key event and is shown by
+//! an animation. A breaker trips on overload or when its fault is injected in
+//! the [`FaultManager`](crate::management::fault_manager::FaultManager), and
+//! feeds its circuit through a [`CircuitBreaker`] only while it is closed.
+//!
+//! Components query their breaker by name, e.g. `panel.is_closed("Wischer")`,
+//! so the wiring of the vehicle is declared in one place.
+
+use std::collections::HashMap;
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::{
+    api::{animation::Animation, key_event::KeyEvent, sound::Sound},
+    components::electrics::mainswitch::CircuitBreaker,
+    management::fault_manager::fault_active,
+};
+
+struct PanelBreaker {
+    breaker: CircuitBreaker,
+    closed: bool,
+    tripped: bool,
+
+    overload: Option<f32>,
+    load: f32,
+    fault: Option<String>,
+
+    key_toggle: KeyEvent,
+    anim: Animation,
+}
+
+pub struct BreakerPanelBuilder {
+    cab_side: Option<CockpitSide>,
+    breakers: HashMap<String, PanelBreaker>,
+
+    snd_switch: Sound,
+    snd_trip: Sound,
+}
+
+impl BreakerPanelBuilder {
+    /// Adds a closed breaker.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name under which the breaker is queried
+    /// * `animation_name` - Animation of the lever, 1.0 is closed
+    /// * `event_name` - Key event toggling the lever
+    pub fn breaker(
+        mut self,
+        name: impl Into<String>,
+        animation_name: impl Into<String>,
+        event_name: impl Into<String>,
+    ) -> Self {
+        self.breakers.insert(
+            name.into(),
+            PanelBreaker {
+                breaker: CircuitBreaker::new(),
+                closed: true,
+                tripped: false,
+                overload: None,
+                load: 0.0,
+                fault: None,
+                key_toggle: KeyEvent::new(Some(&event_name.into()), self.cab_side),
+                anim: Animation::new(Some(&animation_name.into())),
+            },
+        );
+        self
+    }
+
+    /// Trips the breaker if its load set with `set_load` exceeds the limit.
+    pub fn overload(mut self, name: &str, limit: f32) -> Self {
+        if let Some(b) = self.breakers.get_mut(name) {
+            b.overload = Some(limit);
+        }
+        self
+    }
+
+    /// Trips the breaker while the fault with the given id is active.
+    pub fn fault(mut self, name: &str, fault_id: impl Into<String>) -> Self {
+        if let Some(b) = self.breakers.get_mut(name) {
+            b.fault = Some(fault_id.into());
+        }
+        self
+    }
+
+    /// Starts the breaker with the lever open.
+    pub fn init_open(mut self, name: &str) -> Self {
+        if let Some(b) = self.breakers.get_mut(name) {
+            b.closed = false;
+        }
+        self
+    }
+
+    /// Sounds of operating the lever and of a trip.
+    pub fn sounds(mut self, switch_name: impl Into<String>, trip_name: impl Into<String>) -> Self {
+        self.snd_switch = Sound::new_simple(Some(&switch_name.into()));
+        self.snd_trip = Sound::new_simple(Some(&trip_name.into()));
+        self
+    }
+
+    pub fn build(mut self) -> BreakerPanel {
+        for b in self.breakers.values_mut() {
+            b.anim.set(b.closed as u8 as f32);
+        }
+
+        BreakerPanel {
+            breakers: self.breakers,
+            snd_switch: self.snd_switch,
+            snd_trip: self.snd_trip,
+        }
+    }
+}
+
+/// Named circuit breakers with levers, trips and lookup.
+///
+/// Unknown names count as open, so a misspelled breaker name shows up as a
+/// dead circuit instead of a circuit without protection.
+///
+/// # Example
+///
+/// ```rust
+/// let mut panel = BreakerPanel::builder(Some(CockpitSide::A))
+///     .breaker("Wischer", "F12_Lever", "F12_Toggle")
+///     .breaker("Heizung", "F20_Lever", "F20_Toggle")
+///     .overload("Heizung", 1.1)
+///     .fault("Heizung", "Heater_Short")
+///     .sounds("Snd_Breaker", "Snd_Breaker_Trip")
+///     .build();
+///
+/// // In the main loop:
+/// panel.set_load("Heizung", heater.load);
+/// panel.tick(battery_voltage);
+/// wiper.tick(panel.output("Wischer"));
+/// if !panel.is_closed("Heizung") { /* ... */ }
+/// ```
+pub struct BreakerPanel {
+    breakers: HashMap<String, PanelBreaker>,
+
+    snd_switch: Sound,
+    snd_trip: Sound,
+}
+
+impl BreakerPanel {
+    /// Creates a new breaker panel builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `cab_side` - Optional cab side for key event handling
+    pub fn builder(cab_side: Option<CockpitSide>) -> BreakerPanelBuilder {
+        BreakerPanelBuilder {
+            cab_side,
+            breakers: HashMap::new(),
+            snd_switch: Sound::new_simple(None),
+            snd_trip: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the levers, the trips and the outputs of all breakers.
+    ///
+    /// # Arguments
+    ///
+    /// * `voltage` - Supply voltage of the panel from 0.0 to 1.0
+    pub fn tick(&mut self, voltage: f32) {
+        for b in self.breakers.values_mut() {
+            if b.key_toggle.is_just_pressed() {
+                b.closed = !b.closed;
+                b.tripped = false;
+                self.snd_switch.start();
+            }
+
+            let overloaded = b.overload.is_some_and(|limit| b.load > limit);
+            let faulty = b.fault.as_deref().is_some_and(fault_active);
+
+            // Ausgelöster Schalter lässt sich erst nach Beheben wieder einlegen
+            if b.closed && (overloaded || faulty) {
+                b.closed = false;
+                b.tripped = true;
+                self.snd_trip.start();
+            }
+
+            b.breaker.tick(voltage * b.closed as u8 as f32);
+            b.anim.set(b.closed as u8 as f32);
+        }
+    }
+
+    /// Sets the load of the breaker checked against its overload limit.
+    pub fn set_load(&mut self, name: &str, load: f32) {
+        if let Some(b) = self.breakers.get_mut(name) {
+            b.load = load;
+        }
+    }
+
+    /// Opens or closes the lever of the breaker, e.g. from a scenario.
+    pub fn set_closed(&mut self, name: &str, closed: bool) {
+        if let Some(b) = self.breakers.get_mut(name) {
+            b.closed = closed;
+            b.tripped = false;
+        }
+    }
+
+    /// Whether the breaker is closed, unknown names count as open.
+    pub fn is_closed(&self, name: &str) -> bool {
+        self.breakers.get(name).is_some_and(|b| b.closed)
+    }
+
+    /// Whether the breaker has tripped and was not operated since.
+    pub fn is_tripped(&self, name: &str) -> bool {
+        self.breakers.get(name).is_some_and(|b| b.tripped)
+    }
+
+    /// Voltage behind the breaker, 0.0 for unknown names.
+    pub fn output(&self, name: &str) -> f32 {
+        self.breakers.get(name).map_or(0.0, |b| b.breaker.output)
+    }
+
+    /// Names of all tripped breakers, e.g. for a diagnostic display.
+    pub fn tripped(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .breakers
+            .iter()
+            .filter(|(_, b)| b.tripped)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+}
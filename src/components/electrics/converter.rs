@@ -3,9 +3,7 @@
 //! This module provides a `Converter` struct that simulates a voltage converter
 //! with audio feedback during startup and shutdown operations.
 
-use lotus_script::time::delta;
-
-use crate::api::sound::Sound;
+use crate::api::{general::delta, sound::Sound};
 
 /// A voltage converter with sound feedback that handles startup and shutdown sequences.
 ///
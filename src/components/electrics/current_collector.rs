@@ -1,16 +1,50 @@
-use lotus_extra::rand::gen_f32;
-use lotus_script::time::delta;
+//! Third rail collector.
+//!
+//! The [`ThirdRailCollector`] lowers and raises its shoes with a motor. It can
+//! carry shoes on both vehicle sides, the collector has voltage as soon as one
+//! shoe touches a live rail. Running on and off the rail ends and passing gaps
+//! in the rail under load draws an arc with light and sound.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut collector = ThirdRailCollector::builder("A_Stromabnehmer", 0, Side::Left)
+//!     .add_shoe(0, Side::Right)
+//!     .add_saprk(Some("L_Spark"), 0.2, 0.1, true, true)
+//!     .snd_spark("Snd_Spark")
+//!     .build();
+//!
+//! // In your main loop
+//! collector.tick(traction_current > 0.0, battery, fuse);
+//! let voltage = collector.voltage_norm;
+//! ```
 
 use crate::{
     api::{
-        animation::Animation, electrical_supply::ApiThirdRailCollector, light::Light,
-        mock_enums::ThirdRailState, simulation_settings::realisitc_electric_supply, sound::Sound,
+        animation::Animation, electrical_supply::ApiThirdRailCollector, general::delta,
+        light::Light, mock_enums::ThirdRailState, random::gen_f32,
+        simulation_settings::realisitc_electric_supply, sound::Sound,
     },
     management::enums::{
         general_enums::Side, state_enums::SwitchingState, target_enums::SwitchingTarget,
     },
 };
 
+#[derive(Debug)]
+struct CollectorShoe {
+    api: ApiThirdRailCollector,
+    state_last: ThirdRailState,
+}
+
+impl CollectorShoe {
+    fn new(id: usize, side: Side) -> Self {
+        Self {
+            api: ApiThirdRailCollector::new(id, side),
+            state_last: ThirdRailState::Disconnnected,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ThirdRailCollectorBuilder {
     id: usize,
@@ -28,7 +62,6 @@ pub struct ThirdRailCollectorBuilder {
     motor_relais: SwitchingState,
     motor_swiching_timer: f32,
     spark_timer: f32,
-    third_rail_state_last: ThirdRailState,
 
     pos: f32,
 
@@ -40,12 +73,13 @@ pub struct ThirdRailCollectorBuilder {
 
     state: SwitchingState,
 
-    api_thirdrailcollector: ApiThirdRailCollector,
+    shoes: Vec<CollectorShoe>,
 
     snd_on: Sound,
     snd_off: Sound,
     snd_anlauf: Sound,
     snd_ablauf: Sound,
+    snd_spark: Sound,
 
     spark: Light,
 }
@@ -97,6 +131,19 @@ impl ThirdRailCollectorBuilder {
         self
     }
 
+    /// Sound of an arc, played with every spark burst.
+    pub fn snd_spark(mut self, name: impl Into<String>) -> Self {
+        self.snd_spark = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Adds a further shoe moved together with the first one, e.g. the shoe
+    /// of the other vehicle side.
+    pub fn add_shoe(mut self, id: usize, side: Side) -> Self {
+        self.shoes.push(CollectorShoe::new(id, side));
+        self
+    }
+
     pub fn init(mut self, applied: bool) -> Self {
         if applied {
             self.state = SwitchingState::On;
@@ -120,17 +167,18 @@ impl ThirdRailCollectorBuilder {
             motor_relais: self.motor_relais,
             motor_swiching_timer: self.motor_swiching_timer,
             spark_timer: self.spark_timer,
-            third_rail_state_last: self.third_rail_state_last,
             pos: self.pos,
             anim: self.anim,
             motor_target: self.motor_target,
             voltage_norm: self.voltage_norm,
             state: self.state,
-            api_thirdrailcollector: self.api_thirdrailcollector,
+            in_gap: false,
+            shoes: self.shoes,
             snd_on: self.snd_on,
             snd_off: self.snd_off,
             snd_anlauf: self.snd_anlauf,
             snd_ablauf: self.snd_ablauf,
+            snd_spark: self.snd_spark,
             spark: self.spark,
         }
     }
@@ -155,7 +203,6 @@ pub struct ThirdRailCollector {
     motor_relais: SwitchingState,
     motor_swiching_timer: f32,
     spark_timer: f32,
-    third_rail_state_last: ThirdRailState,
 
     pos: f32,
 
@@ -166,13 +213,16 @@ pub struct ThirdRailCollector {
     pub voltage_norm: f32,
 
     state: SwitchingState,
+    /// Whether the collector is lowered but no shoe touches the rail
+    pub in_gap: bool,
 
-    api_thirdrailcollector: ApiThirdRailCollector,
+    shoes: Vec<CollectorShoe>,
 
     snd_on: Sound,
     snd_off: Sound,
     snd_anlauf: Sound,
     snd_ablauf: Sound,
+    snd_spark: Sound,
 
     spark: Light,
 }
@@ -195,17 +245,17 @@ impl ThirdRailCollector {
             motor_relais: SwitchingState::Off,
             motor_swiching_timer: 0.0,
             spark_timer: 0.0,
-            third_rail_state_last: ThirdRailState::Disconnnected,
             pos: 0.0,
             anim: Animation::new(Some(&animation_name.into())),
             motor_target: SwitchingTarget::Neutral,
             voltage_norm: 0.0,
             state: SwitchingState::Off,
-            api_thirdrailcollector: ApiThirdRailCollector::new(id, side),
+            shoes: vec![CollectorShoe::new(id, side)],
             snd_on: Sound::new_simple(None),
             snd_off: Sound::new_simple(None),
             snd_anlauf: Sound::new_simple(None),
             snd_ablauf: Sound::new_simple(None),
+            snd_spark: Sound::new_simple(None),
             spark: Light::new(None),
         }
     }
@@ -281,36 +331,51 @@ impl ThirdRailCollector {
             }
         }
 
-        // Play sound & start spark gap
-        if self.state == SwitchingState::On
-            && self.api_thirdrailcollector.value() == ThirdRailState::PartwiseConnected
-            && (self.third_rail_state_last == ThirdRailState::Connected)
-        {
-            self.snd_anlauf.start();
-
-            if self.spark_on_connect && power_usage {
-                self.spark_timer = gen_f32(
-                    (self.spark_time - self.spark_variance)
-                        ..=(self.spark_time + self.spark_variance),
-                );
+        let spark_duration = gen_f32(
+            (self.spark_time - self.spark_variance)..=(self.spark_time + self.spark_variance),
+        );
+        let mut spark = false;
+        let mut contact = false;
+        let mut voltage = false;
+
+        for shoe in &mut self.shoes {
+            let value = shoe.api.value();
+
+            if self.state == SwitchingState::On {
+                match (&shoe.state_last, &value) {
+                    // Play sound & start spark gap
+                    (ThirdRailState::Connected, ThirdRailState::PartwiseConnected) => {
+                        self.snd_anlauf.start();
+                        spark = spark || self.spark_on_connect;
+                    }
+                    (ThirdRailState::PartwiseConnected, ThirdRailState::Connected) => {
+                        self.snd_ablauf.start();
+                        spark = spark || self.spark_on_disconnect;
+                    }
+                    // Schleifschuh verlässt die Stromschiene (Lücke)
+                    (ThirdRailState::Connected, ThirdRailState::Disconnnected)
+                    | (ThirdRailState::PartwiseConnected, ThirdRailState::Disconnnected) => {
+                        spark = spark || self.spark_on_disconnect;
+                    }
+                    // Schleifschuh läuft wieder auf
+                    (ThirdRailState::Disconnnected, ThirdRailState::Connected)
+                    | (ThirdRailState::Disconnnected, ThirdRailState::PartwiseConnected) => {
+                        spark = spark || self.spark_on_connect;
+                    }
+                    _ => {}
+                }
             }
+
+            contact = contact || value != ThirdRailState::Disconnnected;
+            voltage = voltage || shoe.api.voltage();
+            shoe.state_last = value;
         }
 
-        // Play sound & start spark gap
-        if self.state == SwitchingState::On
-            && self.api_thirdrailcollector.value() == ThirdRailState::Connected
-            && (self.third_rail_state_last == ThirdRailState::PartwiseConnected)
-        {
-            self.snd_ablauf.start();
-
-            if self.spark_on_disconnect && power_usage {
-                self.spark_timer = gen_f32(
-                    (self.spark_time - self.spark_variance)
-                        ..=(self.spark_time + self.spark_variance),
-                );
-            }
+        // Lichtbogen nur unter Last
+        if spark && power_usage {
+            self.spark_timer = spark_duration;
+            self.snd_spark.start();
         }
-        self.third_rail_state_last = self.api_thirdrailcollector.value();
 
         // State setzen
         if self.pos > 0.95 {
@@ -321,10 +386,11 @@ impl ThirdRailCollector {
             self.state = SwitchingState::Neutral;
         }
 
+        self.in_gap = self.state == SwitchingState::On && !contact;
+
         // Voltage aktualisieren
-        self.voltage_norm = (self.state == SwitchingState::On
-            && (self.api_thirdrailcollector.voltage() || !realisitc_electric_supply()))
-        .into();
+        self.voltage_norm =
+            (self.state == SwitchingState::On && (voltage || !realisitc_electric_supply())).into();
 
         // Spark setzen
         if self.spark_timer > 0.0 {
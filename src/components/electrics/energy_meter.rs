@@ -0,0 +1,214 @@
+//! Energy meter ("Energiezähler") of a car.
+//!
+//! The [`EnergyMeter`] integrates the power drawn from and fed back into the
+//! overhead line. The totals are counted like on a real meter and kept in
+//! variables between sessions; the drawn energy can be shown on a
+//! [`RollingCounter`] and as text in a display variable.
+//!
+//! Besides the totals the meter counts the energy and distance of the current
+//! trip, which can be reset e.g. at the terminus. The trip values are sent
+//! periodically as [`EnergyReport`] to the IBIS for the eco-driving feedback.
+
+use lotus_script::message::{send_message, MessageTarget};
+
+use crate::{
+    api::{
+        general::delta,
+        variable::{load_persistent, set_var, store_persistent},
+    },
+    elements::tech::dekaden::RollingCounter,
+    messages::ibis_messages::EnergyReport,
+};
+
+pub struct EnergyMeterBuilder {
+    counter: Option<RollingCounter>,
+    display_var: Option<String>,
+    persist_var: Option<String>,
+    report_interval: f32,
+}
+
+impl EnergyMeterBuilder {
+    /// Rolling counter showing the drawn energy in kWh.
+    pub fn counter(mut self, counter: RollingCounter) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// Text variable showing the drawn and regenerated energy of the trip.
+    pub fn display(mut self, var_name: impl Into<String>) -> Self {
+        self.display_var = Some(var_name.into());
+        self
+    }
+
+    /// Prefix of the variables in which the totals and the trip are stored.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// Interval in seconds in which the trip is reported to the IBIS.
+    pub fn report_interval(mut self, interval: f32) -> Self {
+        self.report_interval = interval;
+        self
+    }
+
+    pub fn build(self) -> EnergyMeter {
+        let load = |suffix: &str| {
+            self.persist_var
+                .as_ref()
+                .and_then(|var_name| load_persistent::<f64>(&format!("{var_name}_{suffix}")))
+                .unwrap_or_default()
+        };
+
+        EnergyMeter {
+            consumed_kwh: load("Consumed"),
+            regenerated_kwh: load("Regenerated"),
+            trip: EnergyReport {
+                consumed_kwh: load("TripConsumed"),
+                regenerated_kwh: load("TripRegenerated"),
+                distance_km: load("TripDistance"),
+            },
+            counter: self.counter,
+            display_var: self.display_var,
+            display_last: String::new(),
+            persist_var: self.persist_var,
+            report_interval: self.report_interval,
+            report_timer: 0.0,
+            power: 0.0,
+        }
+    }
+}
+
+/// Meter for the energy drawn from and fed back into the overhead line.
+///
+/// # Example
+///
+/// ```rust
+/// let mut energy = EnergyMeter::builder()
+///     .counter(
+///         RollingCounter::builder(None)
+///             .digit("kWh_Digit_0")
+///             .digit("kWh_Digit_1")
+///             .digit("kWh_Digit_2")
+///             .digit("kWh_Digit_3")
+///             .digit("kWh_Digit_4")
+///             .backstop()
+///             .build(),
+///     )
+///     .display("Energy_Text")
+///     .persist("Energy")
+///     .build();
+///
+/// // In the main loop:
+/// energy.tick(traction.line_power - ed_brake.regen_power, v_ground());
+/// if ibis.terminus_reached() {
+///     energy.reset_trip();
+/// }
+/// ```
+pub struct EnergyMeter {
+    counter: Option<RollingCounter>,
+    display_var: Option<String>,
+    display_last: String,
+    persist_var: Option<String>,
+
+    report_interval: f32,
+    report_timer: f32,
+
+    /// Current line power in kW, positive when drawn
+    pub power: f32,
+    /// Total energy drawn from the overhead line in kWh
+    pub consumed_kwh: f64,
+    /// Total energy fed back into the overhead line in kWh
+    pub regenerated_kwh: f64,
+    /// Energy and distance of the current trip
+    pub trip: EnergyReport,
+}
+
+impl EnergyMeter {
+    /// Creates a new energy meter builder.
+    pub fn builder() -> EnergyMeterBuilder {
+        EnergyMeterBuilder {
+            counter: None,
+            display_var: None,
+            persist_var: None,
+            report_interval: 5.0,
+        }
+    }
+
+    /// Integrates the line power and the distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Line power in kW, positive when drawn and negative when fed back
+    /// * `speed` - Current speed in m/s
+    pub fn tick(&mut self, power: f32, speed: f32) {
+        self.power = power;
+
+        let energy = (power * delta()) as f64 / 3600.0;
+        if energy > 0.0 {
+            self.consumed_kwh += energy;
+            self.trip.consumed_kwh += energy;
+        } else {
+            self.regenerated_kwh -= energy;
+            self.trip.regenerated_kwh -= energy;
+        }
+        self.trip.distance_km += (speed.abs() * delta()) as f64 / 1000.0;
+
+        if let Some(counter) = &mut self.counter {
+            counter.set(self.consumed_kwh);
+            counter.tick();
+        }
+
+        self.report_timer += delta();
+        if self.report_timer >= self.report_interval {
+            self.report_timer = 0.0;
+            self.report();
+        }
+
+        self.update_display();
+        self.store();
+    }
+
+    /// Starts a new trip and reports it to the IBIS.
+    pub fn reset_trip(&mut self) {
+        self.trip = EnergyReport::default();
+        self.report_timer = 0.0;
+        self.report();
+    }
+
+    fn report(&self) {
+        send_message(
+            &self.trip,
+            [MessageTarget::Broadcast {
+                across_couplings: true,
+                include_self: true,
+            }],
+        );
+    }
+
+    fn update_display(&mut self) {
+        if let Some(var_name) = &self.display_var {
+            let text = format!(
+                "{:.1} kWh / {:.1} kWh",
+                self.trip.consumed_kwh, self.trip.regenerated_kwh
+            );
+            if text != self.display_last {
+                set_var(var_name, text.clone());
+                self.display_last = text;
+            }
+        }
+    }
+
+    fn store(&self) {
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(&format!("{var_name}_Consumed"), self.consumed_kwh);
+            store_persistent(&format!("{var_name}_Regenerated"), self.regenerated_kwh);
+            store_persistent(&format!("{var_name}_TripConsumed"), self.trip.consumed_kwh);
+            store_persistent(
+                &format!("{var_name}_TripRegenerated"),
+                self.trip.regenerated_kwh,
+            );
+            store_persistent(&format!("{var_name}_TripDistance"), self.trip.distance_km);
+        }
+    }
+}
@@ -5,9 +5,10 @@
 //! It supports configurable voltage thresholds, delayed switching operations, and
 //! automatic protection against undervoltage conditions.
 
-use lotus_script::time::delta;
-
-use crate::{api::sound::Sound, management::enums::target_enums::SwitchingTarget};
+use crate::{
+    api::{general::delta, sound::Sound},
+    management::enums::target_enums::SwitchingTarget,
+};
 
 /// Builder for creating and configuring a `LowVoltageLevel` instance.
 ///
@@ -11,6 +11,11 @@
 //! - **Animation Support**: Integration with animation system for visual feedback
 //! - **Sound Effects**: Configurable audio feedback for switch operations
 //! - **Mouse Control**: Interactive mouse-based switch control
+//! - **Switching Sequence**: Automatic closing and opening as a [`StateMachine`],
+//!   extendable with own transitions
+//! - **Protection**: Configurable trips on undervoltage, overcurrent and train protection
+//!   command, with trip counter and off-before-reclose interlock
+//! - **Remote Control**: Switching target via [`MainSwitchRemote`] messages
 //!
 //! ## Example
 //!
@@ -25,21 +30,69 @@
 //!     .state_indicator("state_led")
 //!     .snd_turn_on("switch_on.wav")
 //!     .snd_turn_off("switch_off.wav")
+//!     .trip_undervoltage(0.6, 0.5)
+//!     .trip_overcurrent(1.2)
+//!     .remote(0)
 //!     .build();
 //!
+//! // In on_message:
+//! switch.on_message(msg);
+//!
 //! // Update the switch state
+//! switch.set_line_current(traction.current);
 //! switch.tick(1.0); // 1.0V input
 //! println!("Switch output: {}V", switch.output);
+//! if let Some(cause) = switch.trip_reason {
+//!     println!("Tripped by {:?}", cause);
+//! }
 //! ```
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
+use lotus_script::prelude::Message;
 
 use crate::{
     api::{animation::Animation, general::mouse_move, key_event::KeyEvent, sound::Sound},
+    elements::std::{relays::OnDelayRelay, state_machine::StateMachine},
     management::enums::target_enums::SwitchingTarget,
+    messages::pandemist_messages::MainSwitchRemote,
 };
 
+/// Cause of the last trip of a [`MainSwitch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TripCause {
+    /// Line voltage below the threshold
+    Undervoltage,
+    /// Line current above the limit
+    Overcurrent,
+    /// Opening command of the train protection
+    TrainProtection,
+}
+
+/// Step of the switching sequence of a [`MainSwitch`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MainSwitchPhase {
+    /// Switch open
+    #[default]
+    Open,
+    /// Closing command received, waiting for the closing delay
+    Closing,
+    /// Switch closed
+    Closed,
+    /// Opening command received, waiting for the opening delay
+    Opening,
+}
+
+/// Inputs of the switching sequence of a [`MainSwitch`].
+#[derive(Debug, Clone, Copy)]
+pub struct MainSwitchInputs {
+    /// Current switching target
+    pub target: SwitchingTarget,
+    /// Whether automatic switching is permitted
+    pub switching_allowed: bool,
+    /// Whether reclosing is blocked until the switch was commanded off after a trip
+    pub reclose_locked: bool,
+}
+
 /// Builder for creating and configuring a `MainSwitch`.
 ///
 /// The `MainSwitchBuilder` provides a fluent interface for setting up a main switch
@@ -59,7 +112,6 @@ use crate::{
 pub struct MainSwitchBuilder {
     cab_side: Option<CockpitSide>,
     state: bool,
-    switching_timer: f32,
     switching_allowed: bool,
 
     output: f32,
@@ -73,7 +125,12 @@ pub struct MainSwitchBuilder {
     state_anim: Animation,
 
     target: SwitchingTarget,
-    target_last: SwitchingTarget,
+    sequence: StateMachine<MainSwitchPhase, MainSwitchInputs>,
+
+    undervoltage: Option<(f32, f32)>,
+    overcurrent_limit: Option<f32>,
+    trip_train_protection: bool,
+    remote_id: Option<usize>,
 
     snd_turn_on_start: Sound,
     snd_turn_on: Sound,
@@ -244,6 +301,68 @@ impl MainSwitchBuilder {
         self
     }
 
+    /// Trips the switch if the input voltage stays below the threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Input voltage below which the switch trips
+    /// * `delay` - Time in seconds the voltage has to stay below the threshold
+    pub fn trip_undervoltage(mut self, threshold: f32, delay: f32) -> Self {
+        self.undervoltage = Some((threshold, delay));
+        self
+    }
+
+    /// Trips the switch if the line current set with `set_line_current`
+    /// exceeds the limit.
+    pub fn trip_overcurrent(mut self, limit: f32) -> Self {
+        self.overcurrent_limit = Some(limit);
+        self
+    }
+
+    /// Trips the switch on the opening command of the train protection.
+    pub fn trip_train_protection(mut self) -> Self {
+        self.trip_train_protection = true;
+        self
+    }
+
+    /// Takes over switching targets from [`MainSwitchRemote`] messages with
+    /// the given id.
+    pub fn remote(mut self, id: usize) -> Self {
+        self.remote_id = Some(id);
+        self
+    }
+
+    /// Adds a transition to the switching sequence.
+    ///
+    /// Own transitions are checked before the built-in ones, so they can
+    /// extend or override the sequence, e.g. to drop out of `Closing` when
+    /// the pantograph loses contact.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Phase in which the transition is checked
+    /// * `to` - Phase entered when the guard holds
+    /// * `guard` - Condition on the inputs and the time in seconds spent in `from`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let builder = MainSwitch::builder(None).transition(
+    ///     MainSwitchPhase::Closing,
+    ///     MainSwitchPhase::Open,
+    ///     |inputs, _| !inputs.switching_allowed,
+    /// );
+    /// ```
+    pub fn transition(
+        mut self,
+        from: MainSwitchPhase,
+        to: MainSwitchPhase,
+        guard: impl Fn(&MainSwitchInputs, f32) -> bool + 'static,
+    ) -> Self {
+        self.sequence.add_transition(from, to, guard);
+        self
+    }
+
     /// Builds and returns the configured `MainSwitch`.
     ///
     /// Consumes the builder and creates a `MainSwitch` instance with all
@@ -262,11 +381,36 @@ impl MainSwitchBuilder {
     ///     .build();
     /// ```
     pub fn build(self) -> MainSwitch {
+        let mut sequence = self
+            .sequence
+            .transition(MainSwitchPhase::Open, MainSwitchPhase::Closing, |i, _| {
+                matches!(i.target, SwitchingTarget::TurnOn(_)) && !i.reclose_locked
+            })
+            .transition(MainSwitchPhase::Closing, MainSwitchPhase::Closed, |i, t| {
+                matches!(i.target, SwitchingTarget::TurnOn(delay) if t > delay)
+                    && i.switching_allowed
+            })
+            .transition(MainSwitchPhase::Closing, MainSwitchPhase::Open, |i, _| {
+                !matches!(i.target, SwitchingTarget::TurnOn(_))
+            })
+            .transition(MainSwitchPhase::Closed, MainSwitchPhase::Opening, |i, _| {
+                matches!(i.target, SwitchingTarget::TurnOff(_))
+            })
+            .transition(MainSwitchPhase::Opening, MainSwitchPhase::Open, |i, t| {
+                matches!(i.target, SwitchingTarget::TurnOff(delay) if t > delay)
+                    && i.switching_allowed
+            })
+            .transition(MainSwitchPhase::Opening, MainSwitchPhase::Closed, |i, _| {
+                !matches!(i.target, SwitchingTarget::TurnOff(_))
+            });
+        if self.state {
+            sequence.set_state(MainSwitchPhase::Closed);
+        }
+
         MainSwitch {
             cab_side: self.cab_side,
 
             state: self.state,
-            switching_timer: self.switching_timer,
             switching_allowed: self.switching_allowed,
 
             output: self.output,
@@ -280,7 +424,17 @@ impl MainSwitchBuilder {
             state_anim: self.state_anim,
 
             target: self.target,
-            target_last: self.target,
+            sequence,
+
+            undervoltage_limit: self.undervoltage.map(|(threshold, _)| threshold),
+            undervoltage_relay: OnDelayRelay::new(self.undervoltage.map_or(0.0, |(_, d)| d)),
+            overcurrent_limit: self.overcurrent_limit,
+            trip_train_protection: self.trip_train_protection,
+            remote_id: self.remote_id,
+            line_current: 0.0,
+            reclose_locked: false,
+            trip_reason: None,
+            trip_count: 0,
 
             snd_turn_on_start: self.snd_turn_on_start,
             snd_turn_on: self.snd_turn_on,
@@ -345,7 +499,6 @@ pub struct MainSwitch {
     pub state: bool,
     /// Whether automatic switching operations are allowed
     pub switching_allowed: bool,
-    switching_timer: f32,
 
     /// Current output voltage (input_voltage * state)
     pub output: f32,
@@ -360,7 +513,20 @@ pub struct MainSwitch {
 
     /// Current switching target (what the switch should do)
     pub target: SwitchingTarget,
-    target_last: SwitchingTarget,
+    sequence: StateMachine<MainSwitchPhase, MainSwitchInputs>,
+
+    undervoltage_limit: Option<f32>,
+    undervoltage_relay: OnDelayRelay,
+    overcurrent_limit: Option<f32>,
+    trip_train_protection: bool,
+    remote_id: Option<usize>,
+    line_current: f32,
+    reclose_locked: bool,
+
+    /// Cause of the last trip, cleared when the switch closes again
+    pub trip_reason: Option<TripCause>,
+    /// Number of trips since the start
+    pub trip_count: u32,
 
     snd_turn_on_start: Sound,
     snd_turn_on: Sound,
@@ -398,10 +564,13 @@ impl MainSwitch {
             snd_turn_on: Sound::new_simple(None),
             snd_turn_off: Sound::new_simple(None),
             snd_trigger: Sound::new_simple(None),
-            switching_timer: 0.0,
             output: 0.0,
             target: SwitchingTarget::Neutral,
-            target_last: SwitchingTarget::Neutral,
+            sequence: StateMachine::new(MainSwitchPhase::Open),
+            undervoltage: None,
+            overcurrent_limit: None,
+            trip_train_protection: false,
+            remote_id: None,
             slider: 1.0,
             mouse_factor: 0.0,
             key_grab: KeyEvent::new(None, None),
@@ -449,44 +618,66 @@ impl MainSwitch {
         // Manual switch engagement
         if (self.slider <= 0.1 && slider_last > 0.1) && !self.state {
             self.snd_turn_on.start();
-            self.state = true;
+            self.sequence.set_state(MainSwitchPhase::Closed);
         }
 
         // Manual switch disengagement
         if self.slider > 0.1 && slider_last <= 0.1 {
             self.snd_turn_off.start();
-            self.state = false;
+            self.sequence.set_state(MainSwitchPhase::Open);
+        }
+
+        // Nach Auslösung erst wieder zuschalten, wenn einmal ausgeschaltet wurde
+        if matches!(self.target, SwitchingTarget::TurnOff(_))
+            || (self.slider > 0.1 && slider_last <= 0.1)
+        {
+            self.reclose_locked = false;
         }
 
         // Automatic switching
-        match (self.target, self.state) {
-            (SwitchingTarget::TurnOn(delay), false) => {
-                if self.target_last != self.target {
+        let inputs = MainSwitchInputs {
+            target: self.target,
+            switching_allowed: self.switching_allowed,
+            reclose_locked: self.reclose_locked,
+        };
+        if self.sequence.tick(&inputs) {
+            match (self.sequence.state_last(), self.sequence.state()) {
+                (MainSwitchPhase::Open, MainSwitchPhase::Closing) => {
                     self.snd_turn_on_start.start();
                 }
-                self.switching_timer += delta();
-                if self.switching_timer > delay && self.switching_allowed {
-                    self.snd_turn_on.start();
-                    self.state = true;
-                }
+                (MainSwitchPhase::Closing, MainSwitchPhase::Closed) => self.snd_turn_on.start(),
+                (MainSwitchPhase::Opening, MainSwitchPhase::Open) => self.snd_turn_off.start(),
+                _ => {}
             }
-            (SwitchingTarget::TurnOff(delay), true) => {
-                self.switching_timer += delta();
-                if self.switching_timer > delay && self.switching_allowed {
-                    self.snd_turn_off.start();
-                    self.state = false;
-                }
-            }
-            (_, _) => {
-                self.switching_timer = 0.0;
+        }
+
+        self.state = matches!(
+            self.sequence.state(),
+            MainSwitchPhase::Closed | MainSwitchPhase::Opening
+        );
+        if self.state {
+            self.trip_reason = None;
+        }
+
+        // Protective trips
+        if let Some(limit) = self.undervoltage_limit {
+            if self
+                .undervoltage_relay
+                .tick(self.state && input_voltage < limit)
+            {
+                self.trip(TripCause::Undervoltage);
             }
         }
+        if self
+            .overcurrent_limit
+            .is_some_and(|limit| self.line_current > limit)
+        {
+            self.trip(TripCause::Overcurrent);
+        }
 
         // Output voltage calculation
         self.output = input_voltage * self.state as u8 as f32;
 
-        self.target_last = self.target;
-
         // State indicator animation
         let state_anim_target = 1.0 * (!self.state) as u8 as f32;
         self.state_anim.set(state_anim_target);
@@ -513,10 +704,66 @@ impl MainSwitch {
         if self.state {
             self.snd_trigger.start();
             self.state = false;
+            self.sequence.set_state(MainSwitchPhase::Open);
             self.target = SwitchingTarget::Neutral;
-            self.target_last = SwitchingTarget::Neutral;
         }
     }
+
+    /// Trips the switch open and blocks reclosing until it has been switched
+    /// off.
+    ///
+    /// # Arguments
+    ///
+    /// * `cause` - Cause shown as `trip_reason`
+    pub fn trip(&mut self, cause: TripCause) {
+        if self.state {
+            self.snd_trigger.start();
+            self.state = false;
+            self.sequence.set_state(MainSwitchPhase::Open);
+            self.undervoltage_relay.reset();
+            self.reclose_locked = true;
+            self.trip_reason = Some(cause);
+            self.trip_count += 1;
+        }
+    }
+
+    /// Sets the line current checked against the overcurrent limit.
+    pub fn set_line_current(&mut self, current: f32) {
+        self.line_current = current;
+    }
+
+    /// Opening command of the train protection, trips the switch if
+    /// configured with `trip_train_protection`.
+    pub fn train_protection_command(&mut self, open: bool) {
+        if open && self.trip_train_protection {
+            self.trip(TripCause::TrainProtection);
+        }
+    }
+
+    /// Takes over the switching target of [`MainSwitchRemote`] messages if
+    /// configured with `remote`.
+    pub fn on_message(&mut self, msg: Message) {
+        let Some(id) = self.remote_id else {
+            return;
+        };
+        msg.handle::<MainSwitchRemote>(|m| {
+            if m.id == id {
+                self.target = m.target;
+            }
+            Ok(())
+        })
+        .expect("MainSwitchRemote: message handle failed");
+    }
+
+    /// Current step of the switching sequence, e.g. for a display.
+    pub fn phase(&self) -> MainSwitchPhase {
+        self.sequence.state()
+    }
+
+    /// Time in seconds spent in the current step of the switching sequence.
+    pub fn phase_time(&self) -> f32 {
+        self.sequence.time_in_state()
+    }
 }
 
 //=================================================================
@@ -624,3 +871,27 @@ impl Default for CircuitBreaker {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::replay::run_frames;
+
+    #[test]
+    fn test_trip_blocks_reclosing() {
+        let mut switch = MainSwitch::builder(None).init(true).build();
+        switch.target = SwitchingTarget::TurnOn(0.1);
+
+        switch.trip(TripCause::Overcurrent);
+        run_frames(20, 0.05, || switch.tick(1.0));
+        assert!(!switch.state);
+        assert_eq!(switch.phase(), MainSwitchPhase::Open);
+
+        // Erst nach dem Ausschaltbefehl darf wieder zugeschaltet werden
+        switch.target = SwitchingTarget::TurnOff(0.1);
+        run_frames(2, 0.05, || switch.tick(1.0));
+        switch.target = SwitchingTarget::TurnOn(0.1);
+        run_frames(20, 0.05, || switch.tick(1.0));
+        assert!(switch.state);
+    }
+}
@@ -1,5 +1,9 @@
+pub mod breaker_panel;
 pub mod converter;
 pub mod current_collector;
+pub mod energy_meter;
 pub mod low_voltage_level;
 pub mod mainswitch;
 pub mod pantograph;
+pub mod powerline;
+pub mod trolley_pole;
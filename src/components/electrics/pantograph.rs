@@ -7,10 +7,15 @@
 //! ## Features
 //!
 //! - **Electric Pantograph**: Automatic pantograph with motor control, configurable speeds,
-//!   and realistic electrical supply simulation
+//!   and realistic electrical supply simulation; the motor sequence is a [`StateMachine`]
+//!   which can be extended with own transitions
+//! - **Auto-Drop**: Optional protection lowering the pantograph on a too low
+//!   wire or on contact loss at speed, latched until reset from the cab
+//! - **Arcing**: Brief contact losses from wire height jitter or in ice mode
+//!   draw arcs with light flashes and crackle, scaled by the current draw
 //! - **Manual Pantograph**: Manual rope-operated pantograph with user interaction
 //! - **Third Rail Collector**: Third rail power collection system with sparking effects
-//!   and realistic state management
+//!   and realistic state management, see [`current_collector`](super::current_collector)
 //!
 //! ## Example
 //!
@@ -28,17 +33,129 @@
 //! ```
 
 use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
-use lotus_script::time::delta;
 
 use crate::{
     api::{
-        animation::Animation, electrical_supply::ApiPantograph,
-        simulation_settings::realisitc_electric_supply, sound::Sound, visible_flag::Visiblility,
+        animation::Animation,
+        electrical_supply::ApiPantograph,
+        general::delta,
+        light::Light,
+        random::gen_f32,
+        simulation_settings::realisitc_electric_supply,
+        sound::{Sound, SoundWithRamp},
+        visible_flag::Visiblility,
+    },
+    elements::{std::state_machine::StateMachine, tech::slider::Slider},
+    management::{
+        enums::{state_enums::SwitchingState, target_enums::SwitchingTarget},
+        fault_manager::fault_active,
+        trainbus::{PeripheryFault, PeripheryKind, TrainBusPeriferie},
     },
-    elements::tech::slider::Slider,
-    management::enums::{state_enums::SwitchingState, target_enums::SwitchingTarget},
 };
 
+/// Fall of the crackle volume per second after an arc has gone out
+const ARC_SOUND_RELEASE: f32 = 8.0;
+
+/// Cause of an auto-drop of an [`ElectricPantograph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoDropCause {
+    /// Wire height below the configured limit
+    WireHeight,
+    /// No voltage at the raised pantograph above the speed threshold
+    ContactLoss,
+}
+
+impl AutoDropCause {
+    fn fault(self) -> PeripheryFault {
+        let long_text = match self {
+            AutoDropCause::WireHeight => "Stromabnehmer abgesenkt: Fahrdraht zu niedrig",
+            AutoDropCause::ContactLoss => "Stromabnehmer abgesenkt: Kontaktverlust",
+        };
+        PeripheryFault::Undefined {
+            short_text: "ADD".to_string(),
+            long_text: long_text.to_string(),
+        }
+    }
+}
+
+/// Arcing at the contact strip, shared by both pantograph types.
+struct PantographArc {
+    light: Light,
+    snd: SoundWithRamp,
+
+    jitter_limit: f32,
+    ice_rate: f32,
+    duration: (f32, f32),
+
+    ice: bool,
+    current: f32,
+    speed: f32,
+    height_last: Option<f32>,
+    timer: f32,
+}
+
+impl PantographArc {
+    fn new() -> Self {
+        Self {
+            light: Light::new(None),
+            snd: SoundWithRamp::default(),
+            jitter_limit: 0.5,
+            ice_rate: 2.0,
+            duration: (0.08, 0.05),
+            ice: false,
+            current: 0.0,
+            speed: 0.0,
+            height_last: None,
+            timer: 0.0,
+        }
+    }
+
+    /// Updates the arc and returns its intensity from 0.0 to 1.0.
+    fn update(&mut self, raised: bool, height: Option<f32>) -> f32 {
+        let dt = delta();
+
+        // Sprünge der Fahrdrahthöhe heben das Schleifstück kurz ab
+        let jump = match (self.height_last, height) {
+            (Some(last), Some(height)) if dt > 0.0 => {
+                (height - last).abs() / dt > self.jitter_limit
+            }
+            _ => false,
+        };
+        self.height_last = height.filter(|_| raised);
+
+        // Bei Raureif reißt der Kontakt mit der Geschwindigkeit häufiger ab
+        let ice = self.ice && gen_f32(0.0..1.0) < self.ice_rate * (self.speed.abs() / 10.0) * dt;
+
+        if raised && (jump || ice) && self.timer <= 0.0 {
+            let (time, variance) = self.duration;
+            self.timer = (time + gen_f32(-1.0..=1.0) * variance).max(0.01);
+        }
+        self.timer = (self.timer - dt).max(0.0);
+
+        let intensity = if raised && self.timer > 0.0 {
+            self.current.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        self.light.set_brightness(intensity * gen_f32(0.5..=1.0));
+        self.snd.tick(intensity, 1.0);
+
+        intensity
+    }
+}
+
+/// Inputs of the motor sequence of an [`ElectricPantograph`].
+#[derive(Debug, Clone, Copy)]
+pub struct PantographInputs {
+    /// Motor command after its delay, `On` raises and `Off` lowers
+    pub command: SwitchingState,
+    /// Whether the motor may run (battery, safeguard and no fault)
+    pub enabled: bool,
+    /// Current position of the pantograph from 0.0 to 1.0
+    pub pos: f32,
+}
+
 /// Builder for creating an `ElectricPantograph` with customizable parameters.
 ///
 /// This builder allows you to configure various aspects of an electric pantograph
@@ -69,7 +186,7 @@ pub struct ElectricPantographBuilder {
     current_wire_max_anim: f32,
 
     motor_target: SwitchingTarget,
-    motor_relais: SwitchingState,
+    motor: StateMachine<SwitchingState, PantographInputs>,
     motor_pos: f32,
 
     cranc_target: SwitchingTarget,
@@ -87,6 +204,14 @@ pub struct ElectricPantographBuilder {
 
     api_panto: ApiPantograph,
 
+    fault: Option<String>,
+
+    min_wire_height: Option<f32>,
+    contact_loss: Option<(f32, f32)>,
+    trainbus_slot: Option<i32>,
+
+    arc: PantographArc,
+
     snd_up: Sound,
     snd_down: Sound,
 }
@@ -171,6 +296,120 @@ impl ElectricPantographBuilder {
         self
     }
 
+    /// Sets the id of the fault in the fault manager with which the
+    /// pantograph gets stuck in its current position.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Id of the fault
+    pub fn fault(mut self, id: impl Into<String>) -> Self {
+        self.fault = Some(id.into());
+        self
+    }
+
+    /// Drops the pantograph when the wire height falls below the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `height` - Lowest permitted wire height, in the unit of the height curve
+    pub fn auto_drop_height(mut self, height: f32) -> Self {
+        self.min_wire_height = Some(height);
+        self
+    }
+
+    /// Drops the pantograph when the raised pantograph has no voltage for
+    /// longer than the detection time above the speed threshold.
+    ///
+    /// Use [`set_speed`](ElectricPantograph::set_speed) to pass the speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Speed threshold in m/s
+    /// * `time` - Detection time in seconds, bridges section insulators
+    pub fn auto_drop_contact_loss(mut self, speed: f32, time: f32) -> Self {
+        self.contact_loss = Some((speed, time));
+        self
+    }
+
+    /// Registers the pantograph on the TrainBus with the given slot index to
+    /// report auto-drops.
+    pub fn trainbus(mut self, slot_index: i32) -> Self {
+        self.trainbus_slot = Some(slot_index);
+        self
+    }
+
+    /// Adds arcing at the contact strip.
+    ///
+    /// Brief contact losses draw an arc whose light and crackle volume scale
+    /// with the current draw set with `set_current`.
+    ///
+    /// # Arguments
+    ///
+    /// * `light_name` - Light of the arc flashes
+    /// * `snd_name` - Crackle sound
+    /// * `snd_vol_name` - Volume of the crackle sound, follows the intensity
+    pub fn arcing(
+        mut self,
+        light_name: impl Into<String>,
+        snd_name: impl Into<String>,
+        snd_vol_name: impl Into<String>,
+    ) -> Self {
+        self.arc.light = Light::new(Some(&light_name.into()));
+        self.arc.snd = SoundWithRamp::new(Some(&snd_name.into()), Some(&snd_vol_name.into()), None)
+            .volume_ramp(f32::INFINITY, ARC_SOUND_RELEASE);
+        self
+    }
+
+    /// Rate of change of the wire height above which the contact is lost
+    /// briefly, in height units per second.
+    pub fn arc_jitter_limit(mut self, limit: f32) -> Self {
+        self.arc.jitter_limit = limit;
+        self
+    }
+
+    /// Contact losses per second at 10 m/s in ice mode.
+    pub fn arc_ice_rate(mut self, rate: f32) -> Self {
+        self.arc.ice_rate = rate;
+        self
+    }
+
+    /// Duration of an arc in seconds with its random variance.
+    pub fn arc_duration(mut self, time: f32, variance: f32) -> Self {
+        self.arc.duration = (time, variance);
+        self
+    }
+
+    /// Adds a transition to the motor sequence.
+    ///
+    /// The motor states are `On` (raising), `Off` (lowering) and `Neutral`
+    /// (stopped). Own transitions are checked before the built-in ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Motor state in which the transition is checked
+    /// * `to` - Motor state entered when the guard holds
+    /// * `guard` - Condition on the inputs and the time in seconds spent in `from`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// // Motor protection: switch off after 8 seconds running
+    /// let builder = builder.motor_transition(
+    ///     SwitchingState::On,
+    ///     SwitchingState::Neutral,
+    ///     |_, time| time > 8.0,
+    /// );
+    /// ```
+    pub fn motor_transition(
+        mut self,
+        from: SwitchingState,
+        to: SwitchingState,
+        guard: impl Fn(&PantographInputs, f32) -> bool + 'static,
+    ) -> Self {
+        self.motor.add_transition(from, to, guard);
+        self
+    }
+
     /// Initializes the pantograph in the raised position.
     ///
     /// When set to `true`, the pantograph starts in the fully raised position
@@ -198,12 +437,33 @@ impl ElectricPantographBuilder {
     ///
     /// A configured `ElectricPantograph` ready for use in simulation.
     pub fn build(self) -> ElectricPantograph {
+        let motor = self
+            .motor
+            .transition(SwitchingState::On, SwitchingState::Neutral, |i, _| {
+                !i.enabled || i.pos >= 1.0
+            })
+            .transition(SwitchingState::Off, SwitchingState::Neutral, |i, _| {
+                !i.enabled || i.pos <= 0.0
+            })
+            .transition(SwitchingState::Neutral, SwitchingState::On, |i, _| {
+                i.enabled && i.command == SwitchingState::On && i.pos < 1.0
+            })
+            .transition(SwitchingState::Neutral, SwitchingState::Off, |i, _| {
+                i.enabled && i.command == SwitchingState::Off && i.pos > 0.0
+            })
+            .transition(SwitchingState::On, SwitchingState::Off, |i, _| {
+                i.command == SwitchingState::Off
+            })
+            .transition(SwitchingState::Off, SwitchingState::On, |i, _| {
+                i.command == SwitchingState::On
+            });
+
         ElectricPantograph {
             move_up_speed: self.move_up_speed,
             move_down_speed: self.move_down_speed,
             height_curve: self.height_curve,
             sub_animations: self.sub_animations,
-            motor_relais: self.motor_relais,
+            motor,
             motor_swiching_timer: self.motor_swiching_timer,
             current_wire_height: self.current_wire_height,
             current_wire_max_anim: self.current_wire_max_anim,
@@ -216,6 +476,23 @@ impl ElectricPantographBuilder {
             voltage_norm: self.voltage_norm,
             state: self.state,
             api_panto: self.api_panto,
+            fault: self.fault,
+            min_wire_height: self.min_wire_height,
+            contact_loss: self.contact_loss,
+            contact_loss_timer: 0.0,
+            speed: 0.0,
+            trainbus: self.trainbus_slot.map(|slot| {
+                TrainBusPeriferie::new(
+                    PeripheryKind::Other {
+                        short_name: "PANTO".to_string(),
+                        full_name: "Stromabnehmer".to_string(),
+                    },
+                    slot,
+                )
+            }),
+            auto_drop: None,
+            arc: self.arc,
+            arcing: 0.0,
             snd_up: self.snd_up,
             snd_down: self.snd_down,
         }
@@ -242,6 +519,8 @@ impl ElectricPantographBuilder {
 /// - Requires battery power and safety systems to be active
 /// - Automatic shutdown when safety conditions are not met
 /// - Prevents operation beyond safe limits
+/// - Optional auto-drop on a too low wire or on contact loss, latched until
+///   [`reset_auto_drop`](Self::reset_auto_drop)
 pub struct ElectricPantograph {
     move_up_speed: f32,
     move_down_speed: f32,
@@ -257,7 +536,7 @@ pub struct ElectricPantograph {
 
     /// Current motor target state
     pub motor_target: SwitchingTarget,
-    motor_relais: SwitchingState,
+    motor: StateMachine<SwitchingState, PantographInputs>,
     motor_pos: f32,
 
     /// Current crank target state for manual operation
@@ -271,6 +550,21 @@ pub struct ElectricPantograph {
 
     api_panto: ApiPantograph,
 
+    fault: Option<String>,
+
+    min_wire_height: Option<f32>,
+    contact_loss: Option<(f32, f32)>,
+    contact_loss_timer: f32,
+    speed: f32,
+    trainbus: Option<TrainBusPeriferie>,
+
+    /// Latched cause of the last auto-drop, `None` if not dropped
+    pub auto_drop: Option<AutoDropCause>,
+
+    arc: PantographArc,
+    /// Intensity of the arc at the contact strip from 0.0 to 1.0
+    pub arcing: f32,
+
     snd_up: Sound,
     snd_down: Sound,
 }
@@ -308,13 +602,18 @@ impl ElectricPantograph {
             height_curve: curve,
             current_wire_height: 10.0,
             api_panto: ApiPantograph::new(id),
+            fault: None,
+            min_wire_height: None,
+            contact_loss: None,
+            trainbus_slot: None,
+            arc: PantographArc::new(),
             cranc_target: SwitchingTarget::Neutral,
             cranc_transmission: 0.0,
             animation: Animation::new(Some(&animation_name.into())),
             snd_up: Sound::new_simple(None),
             snd_down: Sound::new_simple(None),
             motor_target: SwitchingTarget::Neutral,
-            motor_relais: SwitchingState::Neutral,
+            motor: StateMachine::new(SwitchingState::Neutral),
             motor_swiching_timer: 0.0,
             current_wire_max_anim: 0.0,
             motor_pos: 0.0,
@@ -371,45 +670,41 @@ impl ElectricPantograph {
             .height_curve
             .get_value_or_default(self.current_wire_height);
 
-        let target_last = self.motor_relais;
-
-        match self.motor_target {
-            SwitchingTarget::TurnOn(delay) => {
-                self.motor_swiching_timer += delta();
-                if self.motor_swiching_timer > delay {
-                    self.motor_relais = SwitchingState::On;
-                }
-            }
-            SwitchingTarget::TurnOff(delay) => {
+        // Befehl wird erst nach seiner Verzögerung wirksam
+        let command = match self.motor_target {
+            SwitchingTarget::TurnOn(delay) | SwitchingTarget::TurnOff(delay) => {
                 self.motor_swiching_timer += delta();
                 if self.motor_swiching_timer > delay {
-                    self.motor_relais = SwitchingState::Off;
+                    SwitchingState::from(self.motor_target)
+                } else {
+                    SwitchingState::Neutral
                 }
             }
             SwitchingTarget::Neutral => {
                 self.motor_swiching_timer = 0.0;
+                SwitchingState::Neutral
             }
-        }
-
-        if !battery || !safeguard {
-            self.motor_relais = SwitchingState::Neutral;
-        }
-
-        match self.motor_relais {
-            SwitchingState::On => {
-                if self.panto_pos >= 1.0 {
-                    self.motor_relais = SwitchingState::Neutral;
-                }
-            }
-            SwitchingState::Off => {
-                if self.panto_pos <= 0.0 {
-                    self.motor_relais = SwitchingState::Neutral;
-                }
-            }
-            SwitchingState::Neutral => {}
-        }
+        };
 
-        if self.motor_relais == SwitchingState::Neutral {
+        self.check_auto_drop();
+
+        // Festsitzender Stromabnehmer bewegt sich weder mit Motor noch mit Kurbel
+        let stuck = self.fault.as_deref().is_some_and(fault_active);
+
+        // Schnellabsenkung senkt unabhängig von Batterie und Sicherheitskreis
+        let dropped = self.auto_drop.is_some();
+        let motor_changed = self.motor.tick(&PantographInputs {
+            command: if dropped {
+                SwitchingState::Off
+            } else {
+                command
+            },
+            enabled: (dropped || battery && safeguard) && !stuck,
+            pos: self.panto_pos,
+        });
+        let motor_relais = self.motor.state();
+
+        if motor_relais == SwitchingState::Neutral && !stuck && !dropped {
             match self.cranc_target {
                 SwitchingTarget::TurnOn(_) => {
                     self.motor_pos = (self.motor_pos + self.cranc_transmission * delta()).min(1.0);
@@ -421,7 +716,7 @@ impl ElectricPantograph {
             }
         }
 
-        match self.motor_relais {
+        match motor_relais {
             SwitchingState::On => {
                 self.motor_pos = (self.motor_pos + self.move_up_speed * delta()).min(1.0);
             }
@@ -431,8 +726,8 @@ impl ElectricPantograph {
             SwitchingState::Neutral => {}
         }
 
-        if self.motor_relais != target_last {
-            match self.motor_relais {
+        if motor_changed {
+            match motor_relais {
                 SwitchingState::On => {
                     self.snd_up.start();
                     self.snd_down.stop();
@@ -466,6 +761,77 @@ impl ElectricPantograph {
         self.panto_pos = self.motor_pos;
         self.panto_pos = self.panto_pos.min(self.current_wire_height);
         self.update_animation(self.panto_pos);
+
+        self.arc.speed = self.speed;
+        self.arcing = self
+            .arc
+            .update(self.state == SwitchingState::On, self.api_panto.height());
+
+        if let Some(trainbus) = &mut self.trainbus {
+            if battery {
+                trainbus.tick();
+                trainbus.set_defect(
+                    self.auto_drop
+                        .map_or(PeripheryFault::Ok, AutoDropCause::fault),
+                );
+            }
+        }
+    }
+
+    fn check_auto_drop(&mut self) {
+        if self.auto_drop.is_some() || self.state != SwitchingState::On {
+            self.contact_loss_timer = 0.0;
+            return;
+        }
+
+        let too_low = self
+            .min_wire_height
+            .is_some_and(|min| self.api_panto.height().is_some_and(|height| height < min));
+
+        // Spannungslücken an Streckentrennern werden überbrückt
+        if let Some((speed, time)) = self.contact_loss {
+            if self.speed.abs() > speed && self.api_panto.voltage() <= 0.0 {
+                self.contact_loss_timer += delta();
+            } else {
+                self.contact_loss_timer = 0.0;
+            }
+            if self.contact_loss_timer > time {
+                self.auto_drop = Some(AutoDropCause::ContactLoss);
+            }
+        }
+
+        if too_low {
+            self.auto_drop = Some(AutoDropCause::WireHeight);
+        }
+    }
+
+    /// Sets the speed in m/s for the contact loss detection and the arcing.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Sets the current draw from 0.0 to 1.0 scaling the arcing.
+    pub fn set_current(&mut self, current: f32) {
+        self.arc.current = current;
+    }
+
+    /// Enables the ice mode with random contact losses.
+    pub fn set_ice(&mut self, ice: bool) {
+        self.arc.ice = ice;
+    }
+
+    /// Releases a latched auto-drop, e.g. by the reset button in the cab.
+    ///
+    /// The pantograph stays lowered until it is raised again.
+    pub fn reset_auto_drop(&mut self) {
+        self.auto_drop = None;
+        self.contact_loss_timer = 0.0;
+        self.motor_target = SwitchingTarget::Neutral;
+    }
+
+    /// Current state of the motor: `On` while raising, `Off` while lowering.
+    pub fn motor_state(&self) -> SwitchingState {
+        self.motor.state()
     }
 }
 
@@ -494,6 +860,8 @@ pub struct ManualPantographBuilder {
     vis_rope_knoted: Visiblility,
 
     api_panto: ApiPantograph,
+
+    arc: PantographArc,
 }
 
 impl ManualPantographBuilder {
@@ -514,6 +882,47 @@ impl ManualPantographBuilder {
         self
     }
 
+    /// Adds arcing at the contact strip.
+    ///
+    /// Brief contact losses draw an arc whose light and crackle volume scale
+    /// with the current draw set with `set_current`.
+    ///
+    /// # Arguments
+    ///
+    /// * `light_name` - Light of the arc flashes
+    /// * `snd_name` - Crackle sound
+    /// * `snd_vol_name` - Volume of the crackle sound, follows the intensity
+    pub fn arcing(
+        mut self,
+        light_name: impl Into<String>,
+        snd_name: impl Into<String>,
+        snd_vol_name: impl Into<String>,
+    ) -> Self {
+        self.arc.light = Light::new(Some(&light_name.into()));
+        self.arc.snd = SoundWithRamp::new(Some(&snd_name.into()), Some(&snd_vol_name.into()), None)
+            .volume_ramp(f32::INFINITY, ARC_SOUND_RELEASE);
+        self
+    }
+
+    /// Rate of change of the wire height above which the contact is lost
+    /// briefly, in height units per second.
+    pub fn arc_jitter_limit(mut self, limit: f32) -> Self {
+        self.arc.jitter_limit = limit;
+        self
+    }
+
+    /// Contact losses per second at 10 m/s in ice mode.
+    pub fn arc_ice_rate(mut self, rate: f32) -> Self {
+        self.arc.ice_rate = rate;
+        self
+    }
+
+    /// Duration of an arc in seconds with its random variance.
+    pub fn arc_duration(mut self, time: f32, variance: f32) -> Self {
+        self.arc.duration = (time, variance);
+        self
+    }
+
     /// Initializes the manual pantograph in the raised position.
     ///
     /// # Arguments
@@ -548,6 +957,8 @@ impl ManualPantographBuilder {
             vis_rope_loss: self.vis_rope_loss,
             vis_rope_knoted: self.vis_rope_knoted,
             api_panto: self.api_panto,
+            arc: self.arc,
+            arcing: 0.0,
         }
     }
 }
@@ -584,6 +995,10 @@ pub struct ManualPantograph {
     vis_rope_knoted: Visiblility,
 
     api_panto: ApiPantograph,
+
+    arc: PantographArc,
+    /// Intensity of the arc at the contact strip from 0.0 to 1.0
+    pub arcing: f32,
 }
 
 impl ManualPantograph {
@@ -634,6 +1049,7 @@ impl ManualPantograph {
             vis_rope_loss: Visiblility::new(vis_rope_loss_name),
             vis_rope_knoted: Visiblility::new(vis_rope_knoted_name),
             api_panto: ApiPantograph::new(id),
+            arc: PantographArc::new(),
         }
     }
 
@@ -691,5 +1107,22 @@ impl ManualPantograph {
         };
 
         self.update_animation(self.panto.pos);
+
+        self.arcing = self.arc.update(self.state, self.api_panto.height());
+    }
+
+    /// Sets the speed in m/s, used for the contact losses in ice mode.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.arc.speed = speed;
+    }
+
+    /// Sets the current draw from 0.0 to 1.0 scaling the arcing.
+    pub fn set_current(&mut self, current: f32) {
+        self.arc.current = current;
+    }
+
+    /// Enables the ice mode with random contact losses.
+    pub fn set_ice(&mut self, ice: bool) {
+        self.arc.ice = ice;
     }
 }
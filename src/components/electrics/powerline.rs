@@ -0,0 +1,242 @@
+//! Power balancing on the powerline of a consist.
+//!
+//! The [`PowerlineManager`] of each car reports the power fed in by its
+//! raised pantographs and the demand of its loads over the
+//! `CouplerPowerlineBalance` coupling line. From the sum over the consist every
+//! car computes the same balance: if the generation does not cover the demand,
+//! the loads which may be shed are switched off, starting with the lowest
+//! priority, and if even the essential loads are not covered, the voltage of
+//! the powerline drops.
+//!
+//! The voltage is computed per car: the pantographs of a car cover its own
+//! essential loads first, only the rest is shared over the powerline. A car
+//! with its own raised pantograph keeps its voltage while an unpowered car
+//! behind it sags.
+//!
+//! Since every car reports its requested demand and not the demand after
+//! shedding, the balance does not oscillate when loads are shed.
+
+use lotus_script::prelude::Message;
+
+use crate::messages::{
+    coupling_handler::UniversalCouplingLine,
+    gt6n_coupling_messages::{CouplerPowerlineBalance, PowerlineBalance},
+};
+
+struct Load {
+    name: String,
+    power: f32,
+    essential: bool,
+    demand: f32,
+    supplied: bool,
+}
+
+pub struct PowerlineManagerBuilder {
+    rated_power: f32,
+    loads: Vec<Load>,
+}
+
+impl PowerlineManagerBuilder {
+    /// Power in kW fed in by the pantographs of this car at full voltage.
+    pub fn rated_power(mut self, rated_power: f32) -> Self {
+        self.rated_power = rated_power;
+        self
+    }
+
+    /// Adds a load, loads added later are shed first.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name under which the load is requested and queried
+    /// * `power` - Power in kW drawn when the load is fully requested
+    /// * `essential` - Whether the load must never be shed, e.g. the control
+    ///   supply or the compressor
+    pub fn load(mut self, name: impl Into<String>, power: f32, essential: bool) -> Self {
+        self.loads.push(Load {
+            name: name.into(),
+            power,
+            essential,
+            demand: 0.0,
+            supplied: true,
+        });
+        self
+    }
+
+    pub fn build(self) -> PowerlineManager {
+        PowerlineManager {
+            rated_power: self.rated_power,
+            loads: self.loads,
+            powerline: UniversalCouplingLine::new(CouplerPowerlineBalance, (true, true)),
+            local: PowerlineBalance::default(),
+            consist: PowerlineBalance::default(),
+            available: 0.0,
+            deficit: false,
+            voltage: 0.0,
+        }
+    }
+}
+
+/// Power balance with load shedding over the consist.
+///
+/// # Example
+///
+/// ```rust
+/// let mut powerline = PowerlineManager::builder()
+///     .rated_power(400.0)
+///     .load("Steuerstrom", 5.0, true)
+///     .load("Traktion", 300.0, true)
+///     .load("Heizung", 40.0, false)
+///     .load("Klima", 25.0, false)
+///     .build();
+///
+/// // In on_message:
+/// powerline.on_message(msg);
+///
+/// // In the main loop:
+/// powerline.request("Traktion", traction.power_demand);
+/// powerline.request_kw("Heizung", heating.demand_kw);
+/// powerline.tick(pantograph.voltage_norm);
+/// heating.tick(powerline.is_supplied("Heizung"), saloon.temperature);
+/// converter.tick(powerline.voltage, true);
+/// ```
+pub struct PowerlineManager {
+    rated_power: f32,
+    loads: Vec<Load>,
+
+    powerline: UniversalCouplingLine<PowerlineBalance, CouplerPowerlineBalance>,
+
+    /// Power balance of this car
+    pub local: PowerlineBalance,
+    /// Power balance of the whole consist
+    pub consist: PowerlineBalance,
+    /// Power in kW available to the loads of this car
+    pub available: f32,
+    /// Whether the generation does not cover the demand of the consist
+    pub deficit: bool,
+    /// Voltage of the powerline at this car from 0.0 to 1.0
+    pub voltage: f32,
+}
+
+impl PowerlineManager {
+    /// Creates a new powerline manager builder.
+    pub fn builder() -> PowerlineManagerBuilder {
+        PowerlineManagerBuilder {
+            rated_power: 0.0,
+            loads: Vec::new(),
+        }
+    }
+
+    /// Passes messages to the `CouplerPowerlineBalance` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.powerline.on_message(msg);
+    }
+
+    /// Sets the demand of a load from 0.0 (off) to 1.0 (full power).
+    pub fn request(&mut self, name: &str, demand: f32) {
+        if let Some(load) = self.loads.iter_mut().find(|l| l.name == name) {
+            load.demand = demand.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Sets the demand of a load as power in kW, e.g. the `demand_kw` of a
+    /// heater, limited to the power of the load.
+    pub fn request_kw(&mut self, name: &str, power_kw: f32) {
+        if let Some(load) = self.loads.iter_mut().find(|l| l.name == name) {
+            load.demand = if load.power > 0.0 {
+                (power_kw / load.power).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+        }
+    }
+
+    /// Whether the load is supplied or has been shed, unknown names count as
+    /// supplied.
+    pub fn is_supplied(&self, name: &str) -> bool {
+        self.loads
+            .iter()
+            .find(|l| l.name == name)
+            .is_none_or(|l| l.supplied)
+    }
+
+    /// Updates the balance and sheds the loads of this car.
+    ///
+    /// # Arguments
+    ///
+    /// * `panto_voltage` - Voltage at the pantographs of this car from 0.0 to 1.0,
+    ///   0.0 for cars without pantograph
+    pub fn tick(&mut self, panto_voltage: f32) {
+        let mut local = PowerlineBalance {
+            generation: panto_voltage * self.rated_power,
+            ..Default::default()
+        };
+        for load in &self.loads {
+            let power = load.power * load.demand;
+            if load.essential {
+                local.essential += power;
+            } else {
+                local.non_essential += power;
+            }
+        }
+        local.self_supplied = local.generation.min(local.essential);
+        self.local = local;
+
+        self.powerline.update_local(local);
+        self.consist = self.powerline.get_value();
+
+        let generation = self.consist.generation;
+        let essential = self.consist.essential;
+        let non_essential = self.consist.non_essential;
+
+        self.deficit = generation < essential + non_essential;
+
+        // Anteil der abschaltbaren Leistung, der noch gedeckt ist
+        let share = if non_essential > 0.0 {
+            ((generation - essential) / non_essential).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        // Abschalten von hinten, bis der Rest in den Anteil dieses Wagens passt
+        let mut budget = share * local.non_essential;
+        let mut shedding = false;
+        for load in &mut self.loads {
+            if load.essential {
+                load.supplied = true;
+                continue;
+            }
+            let power = load.power * load.demand;
+            shedding |= power > budget + 1e-3;
+            load.supplied = !shedding;
+            budget -= power;
+        }
+
+        // Eigene Stromabnehmer decken zuerst den eigenen Bedarf, nur der Rest
+        // wird über die Leitung geteilt und bricht bei Unterdeckung ein
+        let line_supply = (generation - self.consist.self_supplied).max(0.0);
+        let line_demand = essential - self.consist.self_supplied;
+        let line_ratio = if line_demand > 1e-3 {
+            (line_supply / line_demand).min(1.0)
+        } else {
+            1.0
+        };
+        self.voltage = if generation <= 0.0 {
+            0.0
+        } else if local.essential > 0.0 {
+            let fed = local.essential - local.self_supplied;
+            (local.self_supplied + fed * line_ratio) / local.essential
+        } else if local.generation > 0.0 {
+            1.0
+        } else {
+            line_ratio
+        };
+
+        let kept: f32 = self
+            .loads
+            .iter()
+            .filter(|l| !l.essential && l.supplied)
+            .map(|l| l.power * l.demand)
+            .sum();
+        self.available = local.essential * self.voltage + kept;
+    }
+}
@@ -0,0 +1,311 @@
+//! Trolley pole ("Stangenstromabnehmer") for historic vehicles.
+//!
+//! The [`TrolleyPole`] is pressed against the wire by its spring and is held
+//! and guided with the retriever rope, which is operated like a [`Slider`].
+//! At speed and in curves the trolley wheel may jump off the wire; the pole
+//! then springs up and the vehicle loses its supply. To rewire, the pole is
+//! pulled down below the wire and let up slowly again; at too high a speed the
+//! wheel misses the wire and the pole dewires again.
+//!
+//! ## Frog navigation
+//!
+//! At an overhead switch the trolley wheel is guided into the branch the frog
+//! is set for. The frog is set by the contactor ahead of it: passing the
+//! contactor under power sets it for the diverging branch, coasting through
+//! sets it straight. If the frog does not match the track the vehicle takes,
+//! or the frog is passed too fast, the wheel runs off and the pole dewires.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut pole = TrolleyPole::builder("A_Stange", "Stange_Seil", 0, height_curve, None)
+//!     .dewire_rate(0.002, 0.5)
+//!     .rewire_speed(2.0)
+//!     .snd_dewire("Snd_Stange_Ab")
+//!     .snd_rewire("Snd_Stange_An")
+//!     .init(true)
+//!     .build();
+//!
+//! // In the main loop:
+//! pole.tick(v_ground(), inv_radius);
+//! let voltage = pole.voltage_norm;
+//!
+//! // At the contactor and the frog of an overhead switch:
+//! pole.pass_contactor(traction.current > 0.0);
+//! pole.pass_frog(track_diverging, v_ground());
+//! ```
+
+use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
+
+use crate::{
+    api::{
+        animation::Animation, electrical_supply::ApiPantograph, general::delta, random::gen_f32,
+        simulation_settings::realisitc_electric_supply, sound::Sound,
+    },
+    elements::tech::slider::Slider,
+};
+
+/// Position of the trolley wheel relative to the wire.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TrolleyPoleState {
+    /// Wheel on the wire, supply available
+    Wired,
+    /// Pole below the wire, e.g. pulled down or being rewired
+    #[default]
+    Lowered,
+    /// Wheel jumped off, pole sprung up beside the wire
+    Dewired,
+}
+
+pub struct TrolleyPoleBuilder {
+    height_curve: PiecewiseLinearFunction,
+    animation: Animation,
+
+    rope: Slider,
+    api_panto: ApiPantograph,
+
+    dewire_rate: f32,
+    curve_factor: f32,
+    rewire_speed: f32,
+    frog_speed: f32,
+
+    state: TrolleyPoleState,
+
+    snd_dewire: Sound,
+    snd_rewire: Sound,
+}
+
+impl TrolleyPoleBuilder {
+    /// Probability of a dewirement.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - Dewirements per second at 10 m/s on straight track
+    /// * `curve_factor` - Additional dewirements per second per m/s and 1/m
+    ///   inverse curve radius
+    pub fn dewire_rate(mut self, rate: f32, curve_factor: f32) -> Self {
+        self.dewire_rate = rate;
+        self.curve_factor = curve_factor;
+        self
+    }
+
+    /// Highest speed in m/s at which the wheel can be put back on the wire.
+    pub fn rewire_speed(mut self, speed: f32) -> Self {
+        self.rewire_speed = speed;
+        self
+    }
+
+    /// Highest speed in m/s at which a frog is passed safely, above it the
+    /// wheel increasingly runs off.
+    pub fn frog_speed(mut self, speed: f32) -> Self {
+        self.frog_speed = speed;
+        self
+    }
+
+    /// Sound of the pole springing up after a dewirement.
+    pub fn snd_dewire(mut self, name: impl Into<String>) -> Self {
+        self.snd_dewire = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the wheel touching the wire.
+    pub fn snd_rewire(mut self, name: impl Into<String>) -> Self {
+        self.snd_rewire = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Initializes the pole on the wire.
+    pub fn init(mut self, init: bool) -> Self {
+        if init {
+            self.rope.pos = 1.0;
+            self.state = TrolleyPoleState::Wired;
+        }
+        self
+    }
+
+    pub fn build(self) -> TrolleyPole {
+        TrolleyPole {
+            height_curve: self.height_curve,
+            animation: self.animation,
+            rope: self.rope,
+            api_panto: self.api_panto,
+            dewire_rate: self.dewire_rate,
+            curve_factor: self.curve_factor,
+            rewire_speed: self.rewire_speed,
+            frog_speed: self.frog_speed,
+            wire_pos: 1.0,
+            snd_dewire: self.snd_dewire,
+            snd_rewire: self.snd_rewire,
+            pos: 0.0,
+            state: self.state,
+            frog_diverging: false,
+            voltage_norm: 0.0,
+        }
+    }
+}
+
+/// Trolley pole with rope retrieval, dewirement and rewiring.
+pub struct TrolleyPole {
+    height_curve: PiecewiseLinearFunction,
+    animation: Animation,
+
+    rope: Slider,
+    api_panto: ApiPantograph,
+
+    dewire_rate: f32,
+    curve_factor: f32,
+    rewire_speed: f32,
+    frog_speed: f32,
+
+    wire_pos: f32,
+
+    snd_dewire: Sound,
+    snd_rewire: Sound,
+
+    /// Position of the pole from 0.0 (hooked down) to 1.0 (fully sprung up)
+    pub pos: f32,
+    /// Position of the wheel relative to the wire
+    pub state: TrolleyPoleState,
+    /// Whether the next frog is set for the diverging branch
+    pub frog_diverging: bool,
+    /// Normalized voltage output (0.0 to 1.0)
+    pub voltage_norm: f32,
+}
+
+impl TrolleyPole {
+    /// Creates a new trolley pole builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Animation of the pole
+    /// * `key_event_name` - Key event for grabbing the retriever rope
+    /// * `id` - Id of the current collector in the electrical supply
+    /// * `curve` - Height curve mapping the wire height to the animation position
+    /// * `cab_side` - Optional cab side for key event handling
+    pub fn builder(
+        animation_name: impl Into<String>,
+        key_event_name: impl Into<String>,
+        id: usize,
+        curve: PiecewiseLinearFunction,
+        cab_side: Option<CockpitSide>,
+    ) -> TrolleyPoleBuilder {
+        TrolleyPoleBuilder {
+            height_curve: curve,
+            animation: Animation::new(Some(&animation_name.into())),
+            rope: Slider::builder()
+                .key_event(key_event_name.into(), cab_side)
+                .axis_y()
+                .mouse_factor(-0.1 / 400.0)
+                .friction(0.1)
+                .force(1.5)
+                .build(),
+            api_panto: ApiPantograph::new(id),
+            dewire_rate: 0.001,
+            curve_factor: 0.3,
+            rewire_speed: 1.5,
+            frog_speed: 4.0,
+            state: TrolleyPoleState::Lowered,
+            snd_dewire: Sound::new_simple(None),
+            snd_rewire: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the rope, the pole and the contact with the wire.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Current speed in m/s
+    /// * `inv_radius` - Inverse curve radius at the pole in 1/m
+    pub fn tick(&mut self, speed: f32, inv_radius: f32) {
+        if let Some(height) = self.api_panto.height() {
+            self.wire_pos = self.height_curve.get_value_or_default(height).min(1.0);
+        }
+
+        self.rope.tick();
+
+        let v = speed.abs();
+        let touching = self.rope.pos >= self.wire_pos;
+        let below = self.rope.pos < self.wire_pos - 0.05;
+
+        match self.state {
+            TrolleyPoleState::Wired => {
+                // Rollenstromabnehmer springt mit Tempo und in Bögen häufiger ab
+                let rate = self.dewire_rate * v / 10.0 + self.curve_factor * inv_radius.abs() * v;
+                if gen_f32(0.0..1.0) < rate * delta() {
+                    self.dewire();
+                } else if below {
+                    self.state = TrolleyPoleState::Lowered;
+                }
+            }
+            TrolleyPoleState::Lowered => {
+                if touching {
+                    if v <= self.rewire_speed {
+                        self.state = TrolleyPoleState::Wired;
+                        self.snd_rewire.start();
+                    } else {
+                        self.dewire();
+                    }
+                }
+            }
+            TrolleyPoleState::Dewired => {
+                if below {
+                    self.state = TrolleyPoleState::Lowered;
+                }
+            }
+        }
+
+        self.pos = match self.state {
+            TrolleyPoleState::Dewired => self.rope.pos,
+            _ => self.rope.pos.min(self.wire_pos),
+        };
+
+        let wired = self.state == TrolleyPoleState::Wired;
+        self.voltage_norm = if realisitc_electric_supply() {
+            (wired as u8 as f32) * self.api_panto.voltage()
+        } else {
+            wired as u8 as f32
+        };
+
+        self.animation.set(self.pos);
+    }
+
+    /// Sets the next frog when passing its contactor.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the vehicle draws current while the wheel passes
+    ///   the contactor, which sets the frog for the diverging branch
+    pub fn pass_contactor(&mut self, power: bool) {
+        if self.state == TrolleyPoleState::Wired {
+            self.frog_diverging = power;
+        }
+    }
+
+    /// Passes the wheel through a frog.
+    ///
+    /// # Arguments
+    ///
+    /// * `track_diverging` - Whether the vehicle takes the diverging track
+    /// * `speed` - Current speed in m/s
+    pub fn pass_frog(&mut self, track_diverging: bool, speed: f32) {
+        if self.state != TrolleyPoleState::Wired {
+            return;
+        }
+
+        // Falsch gestelltes Luftweichenherz führt die Rolle vom Fahrdraht
+        let overspeed = (speed.abs() / self.frog_speed.max(0.1) - 1.0).clamp(0.0, 1.0);
+        if track_diverging != self.frog_diverging || gen_f32(0.0..1.0) < overspeed {
+            self.dewire();
+        }
+        // Das Herz fällt nach der Durchfahrt in die Grundstellung zurück
+        self.frog_diverging = false;
+    }
+
+    /// Lets the wheel jump off the wire, e.g. from a scenario.
+    pub fn dewire(&mut self) {
+        if self.state != TrolleyPoleState::Dewired {
+            self.state = TrolleyPoleState::Dewired;
+            self.snd_dewire.start();
+        }
+    }
+}
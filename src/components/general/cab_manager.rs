@@ -0,0 +1,148 @@
+//! Cab activation and console arbitration.
+//!
+//! The [`CabManager`] decides which of the two cabs of a car is in charge.
+//! A cab is occupied as soon as its key switch is turned, the first occupied
+//! cab becomes the active one. A second cab switched on in the meantime, in
+//! this car or anywhere else in the consist, is reported as conflict and its
+//! inputs are ignored. The activation is shared with the consist over the
+//! `CouplerCarActiv` coupling line.
+
+use lotus_extra::vehicle::CockpitSide;
+use lotus_script::prelude::Message;
+
+use crate::{
+    management::{
+        enums::general_enums::CabActivState,
+        structs::general_structs::{ActivStatePos, TrainActivState},
+    },
+    messages::{coupling_handler::UniversalCouplingLine, gt6n_coupling_messages::CouplerCarActiv},
+};
+
+/// Tracks the active cab of a car with two cabs.
+///
+/// # Example
+///
+/// ```rust
+/// let mut cabs = CabManager::new();
+///
+/// // In on_message:
+/// cabs.on_message(msg);
+///
+/// // In the main loop:
+/// cabs.tick(key_a.state(), key_b.state());
+/// throttle_line.update_local(cabs.arbitrate(throttle_a.value, 0.0));
+/// throttle_rear_line.update_local(cabs.arbitrate(0.0, throttle_b.value));
+/// ```
+pub struct CabManager {
+    car_activ_line: UniversalCouplingLine<bool, CouplerCarActiv>,
+
+    /// Activation states of both cabs and the resulting state of the car
+    pub state: TrainActivState,
+    /// Cab in charge of the car
+    pub active_cab: Option<CockpitSide>,
+    /// Whether a cab is switched on while another one is in charge
+    pub conflict: bool,
+    /// Whether a cab is active somewhere in the consist
+    pub consist_active: bool,
+}
+
+impl CabManager {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            car_activ_line: UniversalCouplingLine::new(CouplerCarActiv, (true, true)),
+            state: TrainActivState::new(),
+            active_cab: None,
+            conflict: false,
+            consist_active: false,
+        }
+    }
+
+    /// Passes messages to the `CouplerCarActiv` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.car_activ_line.on_message(msg);
+    }
+
+    /// Whether the given cab is in charge.
+    pub fn is_active(&self, cab: CockpitSide) -> bool {
+        self.active_cab == Some(cab)
+    }
+
+    /// Returns the input of the cab in charge, the default value if no cab
+    /// of this car is active.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_a` - Input of the console in cab A
+    /// * `input_b` - Input of the console in cab B
+    pub fn arbitrate<T: Default>(&self, input_a: T, input_b: T) -> T {
+        match self.active_cab {
+            Some(CockpitSide::A) => input_a,
+            Some(CockpitSide::B) => input_b,
+            None => T::default(),
+        }
+    }
+
+    /// Updates the activation.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_a` - Position of the key switch in cab A
+    /// * `key_b` - Position of the key switch in cab B
+    pub fn tick(&mut self, key_a: CabActivState, key_b: CabActivState) {
+        let occupied_a = key_a > CabActivState::Off;
+        let occupied_b = key_b > CabActivState::Off;
+
+        // Aktiver Führerstand bleibt, bis er abgerüstet wird
+        match self.active_cab {
+            Some(CockpitSide::A) if !occupied_a => self.active_cab = None,
+            Some(CockpitSide::B) if !occupied_b => self.active_cab = None,
+            _ => {}
+        }
+
+        let remote_active = self.car_activ_line.get_front() || self.car_activ_line.get_rear();
+
+        if self.active_cab.is_none() && !remote_active {
+            if occupied_a {
+                self.active_cab = Some(CockpitSide::A);
+            } else if occupied_b {
+                self.active_cab = Some(CockpitSide::B);
+            }
+        }
+
+        self.conflict = match self.active_cab {
+            Some(CockpitSide::A) => occupied_b,
+            Some(CockpitSide::B) => occupied_a,
+            None => occupied_a || occupied_b,
+        };
+
+        self.state.update(
+            ActivStatePos::CabA,
+            if self.is_active(CockpitSide::A) {
+                key_a
+            } else {
+                CabActivState::Off
+            },
+        );
+        self.state.update(
+            ActivStatePos::CabB,
+            if self.is_active(CockpitSide::B) {
+                key_b
+            } else {
+                CabActivState::Off
+            },
+        );
+        self.state
+            .update(ActivStatePos::Train, self.state.cab_a.max(self.state.cab_b));
+
+        self.car_activ_line
+            .update_local(self.state.active(ActivStatePos::Train));
+        self.consist_active = self.car_activ_line.get_value();
+    }
+}
+
+impl Default for CabManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,6 +3,8 @@
 //! This module provides interactive door systems for train cabin simulations.
 //! It includes two main door types:
 //! - `HandDoorWithLever`: A more complex door with handle and bolt mechanism
+//! - `CabDoor`: A `HandDoorWithLever` with lock cylinder, interior handle override,
+//!   open door warning and an optional window section
 //!
 //! Both door types support physics-based movement, mouse interaction, sound effects,
 //! and animations.
@@ -10,9 +12,18 @@
 use std::rc::Rc;
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
-use crate::api::{animation::Animation, general::mouse_move, key_event::KeyEvent, sound::Sound};
+use crate::{
+    api::{
+        animation::Animation,
+        general::{delta, mouse_move},
+        key_event::KeyEvent,
+        light::Light,
+        sound::Sound,
+    },
+    components::general::windows::SlidingWindow,
+    elements::tech::key_switch::KeySwitch,
+};
 
 #[derive(PartialEq, Eq)]
 pub enum HandDoorLockingMode {
@@ -421,3 +432,169 @@ impl HandDoorWithLever {
         self.handle_anim.set(handle.into());
     }
 }
+
+//===========================================================
+
+pub struct CabDoorBuilder {
+    door: HandDoorWithLever,
+
+    lock: Option<KeySwitch>,
+    locked: bool,
+
+    warning_speed: f32,
+    lm_open_warning: Light,
+    snd_open_warning: Sound,
+
+    window: Option<SlidingWindow>,
+}
+
+impl CabDoorBuilder {
+    /// Lock cylinder operated with a key switch. Turning the key to -1 locks
+    /// the closed door, turning it to +1 unlocks it.
+    pub fn lock(mut self, lock: KeySwitch) -> Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    /// Door is locked at the start.
+    pub fn init_locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Speed in m/s above which an opened door is warned about.
+    pub fn warning_speed(mut self, warning_speed: f32) -> Self {
+        self.warning_speed = warning_speed;
+        self
+    }
+
+    /// Dashboard lamp and buzzer of the open door warning.
+    pub fn open_warning(
+        mut self,
+        light_name: impl Into<String>,
+        sound_name: impl Into<String>,
+    ) -> Self {
+        self.lm_open_warning = Light::new(Some(&light_name.into()));
+        self.snd_open_warning = Sound::new_simple(Some(&sound_name.into()));
+        self
+    }
+
+    /// Window section in the door (first-series vehicles).
+    pub fn window(mut self, window: SlidingWindow) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    pub fn build(self) -> CabDoor {
+        CabDoor {
+            door: self.door,
+            lock: self.lock,
+            warning_speed: self.warning_speed,
+            lm_open_warning: self.lm_open_warning,
+            snd_open_warning: self.snd_open_warning,
+            window: self.window,
+            locked: self.locked,
+            open_warning: false,
+        }
+    }
+}
+
+/// Cab door with lock cylinder, interior handle and open door warning.
+///
+/// The door movement is done by a [`HandDoorWithLever`]. Handle A is the outer
+/// handle, handle B the interior handle. The locked door can only be opened
+/// with the interior handle.
+///
+/// # Example
+///
+/// ```rust
+/// let mut cab_door = CabDoor::builder(
+///     HandDoorWithLever::builder("CabDoor", "CabDoor_Bolt", "CabDoor_Handle", "CabDoor_GrabA", "CabDoor_GrabB", "CabDoor_HandleA", "CabDoor_HandleB", None)
+///         .set_bolt_mode()
+///         .friction(0.5)
+///         .mouse_factor(0.01)
+///         .build(),
+/// )
+/// .lock(KeySwitch::builder(KeyDepot::new("Key_CabDoor"), "CabDoor_Lock", "CabDoor_Key_Vis", None).min(-1).min_spring().max_spring().build())
+/// .open_warning("L_CabDoor_Open", "Snd_CabDoor_Warning")
+/// .build();
+///
+/// // In the main loop:
+/// cab_door.tick(0.0, v_ground(), voltage > 0.5);
+/// ```
+pub struct CabDoor {
+    /// The door
+    pub door: HandDoorWithLever,
+    /// Lock cylinder
+    pub lock: Option<KeySwitch>,
+
+    warning_speed: f32,
+    lm_open_warning: Light,
+    snd_open_warning: Sound,
+
+    /// Window section of the door
+    pub window: Option<SlidingWindow>,
+
+    /// Whether the door is locked
+    pub locked: bool,
+    /// Whether the door is open while moving
+    pub open_warning: bool,
+}
+
+impl CabDoor {
+    pub fn builder(door: HandDoorWithLever) -> CabDoorBuilder {
+        CabDoorBuilder {
+            door,
+            lock: None,
+            locked: false,
+            warning_speed: 1.0,
+            lm_open_warning: Light::new(None),
+            snd_open_warning: Sound::new_simple(None),
+            window: None,
+        }
+    }
+
+    /// Updates the door.
+    ///
+    /// # Arguments
+    ///
+    /// * `physic_force` - Force acting on the door, see [`HandDoorWithLever::tick`]
+    /// * `speed` - Speed of the vehicle in m/s
+    /// * `power` - Whether the open door warning is supplied
+    pub fn tick(&mut self, physic_force: f32, speed: f32, power: bool) {
+        let closed = self.door.pos <= 0.0;
+
+        // Schließzylinder, nur bei geschlossener Tür wirksam
+        if let Some(lock) = &mut self.lock {
+            lock.tick();
+            match lock.value(true) {
+                -1 if closed => self.locked = true,
+                1 => self.locked = false,
+                _ => {}
+            }
+        }
+
+        // Innengriff öffnet auch die verschlossene Tür
+        let interior_handle = self.door.key_handle_b.is_pressed();
+
+        self.door.tick(physic_force);
+
+        if self.locked && closed && !interior_handle {
+            self.door.pos = 0.0;
+            self.door.speed = 0.0;
+        }
+
+        if self.door.pos > 0.0 {
+            self.locked = false;
+        }
+
+        if let Some(window) = &mut self.window {
+            window.tick();
+        }
+
+        self.open_warning = power && speed.abs() > self.warning_speed && self.door.pos > 0.0;
+        self.lm_open_warning
+            .set_brightness(self.open_warning as u8 as f32);
+        self.snd_open_warning.start_stop(self.open_warning);
+    }
+}
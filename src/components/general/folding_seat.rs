@@ -6,9 +6,13 @@
 
 use lotus_extra::vehicle::CockpitSide;
 use lotus_script::rand::gen_f64;
-use lotus_script::time::delta;
 
-use crate::api::{animation::Animation, general::mouse_move, key_event::KeyEvent, sound::Sound};
+use crate::api::{
+    animation::Animation,
+    general::{delta, mouse_move},
+    key_event::KeyEvent,
+    sound::Sound,
+};
 
 /// Builder for creating a `FoldingSeat` with customizable parameters.
 ///
@@ -5,10 +5,15 @@
 //! and arm movement.
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::{math::Vec2, time::delta};
+use lotus_script::math::Vec2;
 
 use crate::{
-    api::{animation::Animation, general::mouse_move, key_event::KeyEvent, sound::Sound},
+    api::{
+        animation::Animation,
+        general::{delta, mouse_move},
+        key_event::KeyEvent,
+        sound::Sound,
+    },
     management::structs::general_structs::FourDirections,
 };
 
@@ -1,7 +1,10 @@
+pub mod cab_manager;
 pub mod cabin_door;
 pub mod coupler;
 pub mod folding_seat;
 pub mod mirror;
+pub mod passenger_load;
+pub mod point_request;
 pub mod switch_control_unit;
 pub mod windows;
 pub mod wiper;
@@ -0,0 +1,267 @@
+//! Passenger load of a car.
+//!
+//! The [`PassengerLoad`] models the passengers of one car. At every stop a
+//! random share of the passengers alights and new passengers board through
+//! the open doors with a fixed rate per door. As long as passengers are still
+//! waiting, they request the released doors, which keeps them open and
+//! extends the dwell time. The resulting total mass of the car is passed to
+//! the physics.
+//!
+//! On departure the model decides how many passengers want to alight at the
+//! next stop. If anyone does, a stop request is raised after a random time,
+//! which the vehicle passes on to its
+//! [`StopRequestSystem`](crate::components::passenger_info::stop_request::StopRequestSystem).
+
+use crate::api::{general::delta, random::gen_f32};
+
+pub struct PassengerLoadBuilder {
+    empty_mass: f32,
+    passenger_mass: f32,
+    seats: u32,
+    capacity: u32,
+    flow_rate: f32,
+    alighting_share: (f32, f32),
+    boarding: (f32, f32),
+    request_delay: (f32, f32),
+    init: u32,
+}
+
+impl PassengerLoadBuilder {
+    /// Mass of a passenger in kg.
+    pub fn passenger_mass(mut self, mass: f32) -> Self {
+        self.passenger_mass = mass;
+        self
+    }
+
+    /// Passengers per second and open door boarding or alighting.
+    pub fn flow_rate(mut self, rate: f32) -> Self {
+        self.flow_rate = rate;
+        self
+    }
+
+    /// Range of the share of passengers alighting at a stop from 0.0 to 1.0.
+    pub fn alighting_share(mut self, min: f32, max: f32) -> Self {
+        self.alighting_share = (min, max);
+        self
+    }
+
+    /// Range of the number of passengers waiting to board at a stop.
+    pub fn boarding(mut self, min: f32, max: f32) -> Self {
+        self.boarding = (min, max);
+        self
+    }
+
+    /// Range of the time in seconds after departure until a stop is requested.
+    pub fn request_delay(mut self, min: f32, max: f32) -> Self {
+        self.request_delay = (min, max);
+        self
+    }
+
+    /// Passengers on board at the start.
+    pub fn init(mut self, passengers: u32) -> Self {
+        self.init = passengers;
+        self
+    }
+
+    pub fn build(self) -> PassengerLoad {
+        let mut load = PassengerLoad {
+            empty_mass: self.empty_mass,
+            passenger_mass: self.passenger_mass,
+            seats: self.seats,
+            capacity: self.capacity,
+            flow_rate: self.flow_rate,
+            alighting_share: self.alighting_share,
+            boarding_range: self.boarding,
+            request_delay: self.request_delay,
+            released_last: false,
+            departed: true,
+            flow_progress: 0.0,
+            request_timer: None,
+            stop_request: false,
+            alighting: 0,
+            boarding: 0,
+            passengers: self.init.min(self.capacity),
+            standing: 0,
+            exchanging: false,
+            dwell_remaining: 0.0,
+            mass: 0.0,
+        };
+        load.update_outputs(false, 0);
+        load
+    }
+}
+
+/// Passengers of a car with boarding, alighting and stop requests.
+///
+/// # Example
+///
+/// ```rust
+/// let mut load = PassengerLoad::builder(24_000.0, 52, 120)
+///     .flow_rate(0.8)
+///     .boarding(0.0, 20.0)
+///     .init(30)
+///     .build();
+///
+/// // In the main loop:
+/// let open_doors = doors.doors().iter().filter(|(_, d)| d.state() == DoorState::Open).count();
+/// load.tick(v_ground(), doors.released, open_doors);
+/// if load.take_stop_request() {
+///     stop_request.request();
+/// }
+/// doors.tick(power, v_ground(), door_target, side_target, false, &vec![load.exchanging; doors.len()]);
+/// set_mass(load.mass);
+/// ```
+pub struct PassengerLoad {
+    empty_mass: f32,
+    passenger_mass: f32,
+    seats: u32,
+    capacity: u32,
+    flow_rate: f32,
+    alighting_share: (f32, f32),
+    boarding_range: (f32, f32),
+    request_delay: (f32, f32),
+
+    released_last: bool,
+    departed: bool,
+    flow_progress: f32,
+    request_timer: Option<f32>,
+    stop_request: bool,
+
+    /// Passengers who want to alight at the next stop
+    pub alighting: u32,
+    /// Passengers waiting to board at the current stop
+    pub boarding: u32,
+    /// Passengers on board
+    pub passengers: u32,
+    /// Passengers without seat
+    pub standing: u32,
+    /// Whether passengers are waiting at the released doors
+    pub exchanging: bool,
+    /// Time in seconds the passenger exchange still needs with the open doors
+    pub dwell_remaining: f32,
+    /// Total mass of the car in kg
+    pub mass: f32,
+}
+
+impl PassengerLoad {
+    /// Creates a new passenger load builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `empty_mass` - Mass of the empty car in kg
+    /// * `seats` - Number of seats
+    /// * `capacity` - Total number of passengers including standing ones
+    pub fn builder(empty_mass: f32, seats: u32, capacity: u32) -> PassengerLoadBuilder {
+        PassengerLoadBuilder {
+            empty_mass,
+            passenger_mass: 75.0,
+            seats,
+            capacity: capacity.max(seats),
+            flow_rate: 1.0,
+            alighting_share: (0.1, 0.4),
+            boarding: (0.0, 15.0),
+            request_delay: (5.0, 30.0),
+            init: 0,
+        }
+    }
+
+    /// Updates the passenger exchange and the stop requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Current speed in m/s
+    /// * `released` - Whether the doors of the car are released
+    /// * `open_doors` - Number of open doors of the car
+    pub fn tick(&mut self, speed: f32, released: bool, open_doors: usize) {
+        let doors_open = open_doors > 0;
+
+        // Mit der Freigabe an der Haltestelle stehen die Fahrgäste bereit
+        if released && !self.released_last && self.departed {
+            self.departed = false;
+            self.request_timer = None;
+            let (min, max) = self.boarding_range;
+            self.boarding = random(min, max).round() as u32;
+        }
+        self.released_last = released;
+
+        if doors_open {
+            self.flow_progress += self.flow_rate * open_doors as f32 * delta();
+            while self.flow_progress >= 1.0 {
+                self.flow_progress -= 1.0;
+                if self.alighting > 0 {
+                    self.alighting -= 1;
+                    self.passengers = self.passengers.saturating_sub(1);
+                } else if self.boarding > 0 && self.passengers < self.capacity {
+                    self.boarding -= 1;
+                    self.passengers += 1;
+                } else {
+                    self.flow_progress = 0.0;
+                    break;
+                }
+            }
+        }
+
+        if !released && !doors_open && !self.departed && speed.abs() > 1.0 {
+            self.depart();
+        }
+
+        if let Some(timer) = &mut self.request_timer {
+            *timer -= delta();
+            if *timer <= 0.0 {
+                self.stop_request = true;
+                self.request_timer = None;
+            }
+        }
+
+        self.update_outputs(released, open_doors);
+    }
+
+    /// Returns whether a passenger has requested a stop since the last call.
+    pub fn take_stop_request(&mut self) -> bool {
+        std::mem::take(&mut self.stop_request)
+    }
+
+    /// Sets the passengers on board, e.g. from a scenario.
+    pub fn set_passengers(&mut self, passengers: u32) {
+        self.passengers = passengers.min(self.capacity);
+        self.alighting = self.alighting.min(self.passengers);
+    }
+
+    fn depart(&mut self) {
+        self.departed = true;
+        self.boarding = 0;
+
+        let (min, max) = self.alighting_share;
+        self.alighting = (self.passengers as f32 * random(min, max)).round() as u32;
+        if self.alighting > 0 {
+            let (min, max) = self.request_delay;
+            self.request_timer = Some(random(min, max));
+        }
+    }
+
+    fn update_outputs(&mut self, released: bool, open_doors: usize) {
+        self.standing = self.passengers.saturating_sub(self.seats);
+        self.mass = self.empty_mass + self.passengers as f32 * self.passenger_mass;
+
+        let free = self.capacity - self.passengers + self.alighting;
+        let exchange = if self.departed {
+            0
+        } else {
+            self.alighting + self.boarding.min(free)
+        };
+        self.exchanging = released && exchange > 0;
+        self.dwell_remaining = if exchange > 0 {
+            exchange as f32 / (self.flow_rate * open_doors.max(1) as f32).max(0.01)
+        } else {
+            0.0
+        };
+    }
+}
+
+fn random(min: f32, max: f32) -> f32 {
+    if max > min {
+        gen_f32(min..max)
+    } else {
+        min
+    }
+}
@@ -0,0 +1,264 @@
+//! Point request controller ("Weichensteuerung").
+//!
+//! The [`PointRequest`] requests the direction of the next electrically
+//! operated point. Two methods are supported:
+//!
+//! - Overhead contact ("Fahrleitungskontakt"): the vehicle passes a contact
+//!   in the overhead line either with traction current or coasting, which
+//!   selects one of two directions.
+//! - Radio ("Funk-Weichensteuerung"): the driver preselects the direction with
+//!   a step switch, it is transmitted when the vehicle passes the radio point.
+//!
+//! The requested direction is sent as [`RoutingDirection`] message and shown
+//! on the aim indicator lamps once the point has been set. Passing the
+//! passage sensor behind the point cancels the request.
+
+use std::collections::HashMap;
+
+use lotus_extra::messages::pis::RoutingDirection;
+use lotus_script::prelude::{send_message, MessageTarget};
+
+use crate::{
+    api::{general::delta, light::Light},
+    elements::tech::switches::StepSwitch,
+};
+
+/// Kind of a point request sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointSensor {
+    /// Overhead contact with the directions when passed with traction current
+    /// and when coasting
+    Contact {
+        powered: RoutingDirection,
+        coasting: RoutingDirection,
+    },
+    /// Radio point transmitting the direction preselected by the driver
+    Radio,
+    /// Sensor behind the point cancelling the request
+    Passage,
+}
+
+pub struct PointRequestBuilder {
+    sensors: HashMap<u32, PointSensor>,
+
+    direction_selector: Option<StepSwitch>,
+
+    set_time: f32,
+    cancel_time: f32,
+
+    lm_left: Light,
+    lm_straight: Light,
+    lm_right: Light,
+}
+
+impl PointRequestBuilder {
+    /// Maps a sensor ID to a point request sensor.
+    pub fn add_sensor(mut self, sensor: u32, kind: PointSensor) -> Self {
+        self.sensors.insert(sensor, kind);
+        self
+    }
+
+    /// Step switch preselecting the direction for radio points:
+    /// -1 left, 0 straight, +1 right.
+    pub fn direction_selector(mut self, switch: StepSwitch) -> Self {
+        self.direction_selector = Some(switch);
+        self
+    }
+
+    /// Time in seconds until the point is set after the request.
+    pub fn set_time(mut self, set_time: f32) -> Self {
+        self.set_time = set_time;
+        self
+    }
+
+    /// Time in seconds after which a request is cancelled without passage.
+    pub fn cancel_time(mut self, cancel_time: f32) -> Self {
+        self.cancel_time = cancel_time;
+        self
+    }
+
+    /// Aim indicator lamps showing the set direction.
+    pub fn aim_lights(
+        mut self,
+        left: impl Into<String>,
+        straight: impl Into<String>,
+        right: impl Into<String>,
+    ) -> Self {
+        self.lm_left = Light::new(Some(&left.into()));
+        self.lm_straight = Light::new(Some(&straight.into()));
+        self.lm_right = Light::new(Some(&right.into()));
+        self
+    }
+
+    pub fn build(self) -> PointRequest {
+        PointRequest {
+            sensors: self.sensors,
+            direction_selector: self.direction_selector,
+            set_time: self.set_time,
+            cancel_time: self.cancel_time,
+            lm_left: self.lm_left,
+            lm_straight: self.lm_straight,
+            lm_right: self.lm_right,
+            pending: Vec::new(),
+            set_timer: 0.0,
+            cancel_timer: 0.0,
+            requested: RoutingDirection::Off,
+            point_set: false,
+        }
+    }
+}
+
+/// Requests the direction of the next point by overhead contact or radio.
+///
+/// # Example
+///
+/// ```rust
+/// let mut points = PointRequest::builder()
+///     .add_sensor(4001, PointSensor::Contact { powered: RoutingDirection::Left, coasting: RoutingDirection::Straight })
+///     .add_sensor(4002, PointSensor::Radio)
+///     .add_sensor(4003, PointSensor::Passage)
+///     .direction_selector(StepSwitch::builder("Point_Selector", Some(CockpitSide::A)).build())
+///     .aim_lights("L_Point_Left", "L_Point_Straight", "L_Point_Right")
+///     .build();
+///
+/// // In on_trigger:
+/// points.on_trigger(sensor, entering);
+///
+/// // In the main loop:
+/// points.tick(voltage > 0.5, traction_current > 10.0);
+/// ```
+pub struct PointRequest {
+    sensors: HashMap<u32, PointSensor>,
+
+    direction_selector: Option<StepSwitch>,
+
+    set_time: f32,
+    cancel_time: f32,
+
+    lm_left: Light,
+    lm_straight: Light,
+    lm_right: Light,
+
+    pending: Vec<PointSensor>,
+    set_timer: f32,
+    cancel_timer: f32,
+
+    /// Currently requested direction
+    pub requested: RoutingDirection,
+    /// Whether the point has been set to the requested direction
+    pub point_set: bool,
+}
+
+impl PointRequest {
+    /// Creates a new point request builder.
+    pub fn builder() -> PointRequestBuilder {
+        PointRequestBuilder {
+            sensors: HashMap::new(),
+            direction_selector: None,
+            set_time: 1.5,
+            cancel_time: 60.0,
+            lm_left: Light::new(None),
+            lm_straight: Light::new(None),
+            lm_right: Light::new(None),
+        }
+    }
+
+    /// Handles sensor trigger events, unknown sensor IDs are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor` - The ID of the sensor that triggered
+    /// * `entering` - `true` if the vehicle enters the zone, `false` if leaving
+    pub fn on_trigger(&mut self, sensor: u32, entering: bool) {
+        if entering {
+            if let Some(kind) = self.sensors.get(&sensor) {
+                self.pending.push(*kind);
+            }
+        }
+    }
+
+    /// Updates the request and the aim indicator.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the radio transmitter and the aim indicator are supplied
+    /// * `traction_current` - Whether the vehicle draws traction current at the
+    ///   overhead contact
+    pub fn tick(&mut self, power: bool, traction_current: bool) {
+        if let Some(selector) = &mut self.direction_selector {
+            selector.tick();
+        }
+
+        for kind in std::mem::take(&mut self.pending) {
+            match kind {
+                PointSensor::Contact { powered, coasting } => {
+                    self.request(if traction_current { powered } else { coasting });
+                }
+                PointSensor::Radio if power => {
+                    let direction = match &self.direction_selector {
+                        Some(selector) => match selector.value(true) {
+                            i32::MIN..=-1 => RoutingDirection::Left,
+                            0 => RoutingDirection::Straight,
+                            _ => RoutingDirection::Right,
+                        },
+                        None => RoutingDirection::Straight,
+                    };
+                    self.request(direction);
+                }
+                PointSensor::Radio => {}
+                PointSensor::Passage => self.cancel(),
+            }
+        }
+
+        if self.requested != RoutingDirection::Off {
+            self.set_timer -= delta();
+            self.point_set = self.set_timer <= 0.0;
+
+            // Ohne Überfahrt nach Ablauf der Zeit zurücknehmen
+            self.cancel_timer -= delta();
+            if self.cancel_timer <= 0.0 {
+                self.cancel();
+            }
+        }
+
+        let shown = if power && self.point_set {
+            self.requested
+        } else {
+            RoutingDirection::Off
+        };
+        self.lm_left
+            .set_brightness((shown == RoutingDirection::Left) as u8 as f32);
+        self.lm_straight
+            .set_brightness((shown == RoutingDirection::Straight) as u8 as f32);
+        self.lm_right
+            .set_brightness((shown == RoutingDirection::Right) as u8 as f32);
+    }
+
+    /// Requests a direction, e.g. from a scenario.
+    pub fn request(&mut self, direction: RoutingDirection) {
+        self.requested = direction;
+        self.point_set = false;
+        self.set_timer = self.set_time;
+        self.cancel_timer = self.cancel_time;
+        Self::send(direction);
+    }
+
+    /// Cancels the active request.
+    pub fn cancel(&mut self) {
+        if self.requested != RoutingDirection::Off {
+            self.requested = RoutingDirection::Off;
+            self.point_set = false;
+            Self::send(RoutingDirection::Off);
+        }
+    }
+
+    fn send(direction: RoutingDirection) {
+        send_message(
+            &direction,
+            [MessageTarget::Broadcast {
+                across_couplings: false,
+                include_self: true,
+            }],
+        );
+    }
+}
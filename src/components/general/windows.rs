@@ -5,6 +5,9 @@
 //! - [`SlidingWindow`]: A window that can be dragged along an axis using mouse input
 //!
 //! Both window types support animations, sound effects, and customizable input handling.
+//! Sliding windows can additionally hold in latch positions, produce wind noise
+//! depending on the opening and the speed, and report rain coming in for the
+//! [`GlassFogging`](crate::components::hvac::window_heater::GlassFogging) model.
 //!
 //! # Examples
 //!
@@ -230,10 +233,79 @@ pub struct SlidingWindowBuilder {
     snd_slide_lower_end: Sound,
     snd_slide_lower_end_vol_curve: Rc<dyn Fn(f32) -> f32>,
 
+    latches: Vec<f32>,
+    latch_range: f32,
+    snd_latch: Sound,
+
+    snd_wind: Sound,
+    wind_full_speed: f32,
+    rain_opening: f32,
+
     mouse_factor: f32,
 }
 
 impl SlidingWindowBuilder {
+    /// Adds a latch position in which the released window holds.
+    ///
+    /// When the handle is released within the latch range of a latch position,
+    /// the window snaps into it.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - Latch position between 0.0 and 1.0
+    pub fn latch(mut self, pos: f32) -> Self {
+        self.latches.push(pos.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Sets the distance to a latch position within which the window snaps into it.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - Capture range (default: 0.05)
+    pub fn latch_range(mut self, range: f32) -> Self {
+        self.latch_range = range;
+        self
+    }
+
+    /// Sets the sound effect to play when the window snaps into a latch position.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name/path of the sound file to play
+    pub fn snd_latch(mut self, name: impl Into<String>) -> Self {
+        self.snd_latch = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sets the wind noise, its volume scales with the opening and the speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name/path of the sound file to play
+    /// * `volume_name` - Optional name of the volume variable
+    /// * `full_speed` - Speed in m/s at which the fully opened window reaches full volume
+    pub fn snd_wind(
+        mut self,
+        name: impl Into<String>,
+        volume_name: Option<&str>,
+        full_speed: f32,
+    ) -> Self {
+        self.snd_wind = Sound::new(Some(&name.into()), volume_name, None);
+        self.wind_full_speed = full_speed;
+        self
+    }
+
+    /// Sets the opening from which rain comes in.
+    ///
+    /// # Arguments
+    ///
+    /// * `opening` - Window position between 0.0 and 1.0 (default: 0.1)
+    pub fn rain_opening(mut self, opening: f32) -> Self {
+        self.rain_opening = opening;
+        self
+    }
+
     /// Sets the mouse sensitivity factor for sliding.
     ///
     /// Higher values make the window more responsive to mouse movement.
@@ -386,8 +458,15 @@ impl SlidingWindowBuilder {
             snd_slide_upper_end_vol_curve: self.snd_slide_upper_end_vol_curve,
             snd_slide_lower_end: self.snd_slide_lower_end,
             snd_slide_lower_end_vol_curve: self.snd_slide_lower_end_vol_curve,
+            latches: self.latches,
+            latch_range: self.latch_range,
+            snd_latch: self.snd_latch,
+            snd_wind: self.snd_wind,
+            wind_full_speed: self.wind_full_speed,
+            rain_opening: self.rain_opening,
             mouse_factor: self.mouse_factor,
             end_snd_played: false,
+            rain_ingress: false,
         }
     }
 }
@@ -422,6 +501,7 @@ impl SlidingWindowBuilder {
 ///
 /// // In your game loop:
 /// window.tick(); // Call this each frame to handle input and update state
+/// window.tick_weather(v_ground(), raining);
 /// ```
 pub struct SlidingWindow {
     pos: f32,
@@ -441,9 +521,20 @@ pub struct SlidingWindow {
     snd_slide_lower_end: Sound,
     snd_slide_lower_end_vol_curve: Rc<dyn Fn(f32) -> f32>,
 
+    latches: Vec<f32>,
+    latch_range: f32,
+    snd_latch: Sound,
+
+    snd_wind: Sound,
+    wind_full_speed: f32,
+    rain_opening: f32,
+
     mouse_factor: f32,
 
     end_snd_played: bool,
+
+    /// Whether rain comes in through the opened window
+    pub rain_ingress: bool,
 }
 
 impl SlidingWindow {
@@ -485,6 +576,12 @@ impl SlidingWindow {
             snd_slide_upper_end_vol_curve: Rc::new(|x| x),
             snd_slide_lower_end: Sound::new_simple(None),
             snd_slide_lower_end_vol_curve: Rc::new(|x| x),
+            latches: Vec::new(),
+            latch_range: 0.05,
+            snd_latch: Sound::new_simple(None),
+            snd_wind: Sound::new_simple(None),
+            wind_full_speed: 20.0,
+            rain_opening: 0.1,
             mouse_factor: 0.0,
         }
     }
@@ -512,6 +609,20 @@ impl SlidingWindow {
         if self.key_grabbing.is_just_released() {
             self.switch_anim.set(0.0);
             self.snd_handle_release.start();
+
+            // In nächstgelegene Raststellung einrasten
+            let pos = self.pos;
+            let latch = self
+                .latches
+                .iter()
+                .copied()
+                .filter(|l| (l - pos).abs() < self.latch_range)
+                .min_by(|a, b| (a - pos).abs().total_cmp(&(b - pos).abs()));
+
+            if let Some(latch) = latch {
+                self.pos = latch;
+                self.snd_latch.start();
+            }
         }
 
         let vec_mouse = mouse_move() * self.axis;
@@ -553,4 +664,24 @@ impl SlidingWindow {
 
         self.window_anim.set(self.pos);
     }
+
+    /// Updates the wind noise and the rain ingress.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Speed of the vehicle in m/s
+    /// * `raining` - Whether it is raining outside
+    pub fn tick_weather(&mut self, speed: f32, raining: bool) {
+        let wind = self.pos * (speed.abs() / self.wind_full_speed.max(0.01)).min(1.0);
+        self.snd_wind.update_volume(wind);
+        self.snd_wind.start_stop(wind > 0.01);
+
+        self.rain_ingress = raining && self.pos >= self.rain_opening;
+    }
+
+    /// Returns the current opening from 0.0 (closed) to 1.0 (fully open).
+    #[must_use]
+    pub fn opening(&self) -> f32 {
+        self.pos
+    }
 }
@@ -44,10 +44,9 @@
 
 use std::{collections::HashMap, rc::Rc};
 
-use lotus_script::time::delta;
 use std::hash::Hash;
 
-use crate::api::{animation::Animation, sound::Sound};
+use crate::api::{animation::Animation, general::delta, sound::Sound};
 
 /// Configuration for a single wiper speed level.
 ///
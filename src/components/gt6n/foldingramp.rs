@@ -1,16 +1,18 @@
 use std::f32::consts::PI;
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
 use crate::{
     api::{
         animation::Animation,
-        general::mouse_move,
+        general::{delta, mouse_move},
         key_event::KeyEvent,
-        light::{BlinkRelais, Light},
+        light::Light,
+    },
+    elements::{
+        std::relays::FlasherRelay,
+        tech::{slider::Slider, switches::Switch},
     },
-    elements::tech::{slider::Slider, switches::Switch},
 };
 
 const RAMP_DOORWARN_INTERVAL: f32 = 1.0;
@@ -32,7 +34,7 @@ pub struct Foldingramp {
 
     ramp_platform: Slider,
 
-    pub warnrelais: BlinkRelais,
+    pub warnrelais: FlasherRelay,
     l_warning: Light,
 
     pub in_use: bool,
@@ -88,7 +90,7 @@ impl Foldingramp {
                 .max(283.7)
                 .build(),
 
-            warnrelais: BlinkRelais::new(RAMP_DOORWARN_INTERVAL, RAMP_DOORWARN_INTERVAL_HALF, 0.1),
+            warnrelais: FlasherRelay::new(RAMP_DOORWARN_INTERVAL, RAMP_DOORWARN_INTERVAL_HALF, 0.1),
             l_warning: Light::new(Some(&format!(
                 "L_{}_Hubliftwarnung",
                 String::from(cab_side)
@@ -117,11 +119,7 @@ impl Foldingramp {
 
         let can_use = allowed && self.lockpin.value(true);
 
-        if can_use {
-            self.warnrelais.tick();
-        } else {
-            self.warnrelais.reset();
-        }
+        self.warnrelais.run(can_use);
         self.l_warning
             .set_brightness(self.warnrelais.is_on as u8 as f32);
 
@@ -1,6 +1,4 @@
-use lotus_script::time::delta;
-
-use crate::api::{key_event::KeyEvent, sound::Sound, visible_flag::Visiblility};
+use crate::api::{general::delta, key_event::KeyEvent, sound::Sound, visible_flag::Visiblility};
 
 const INTERCOM_TIME: f32 = 1.0;
 const INTERCOM_TIME_HALF: f32 = INTERCOM_TIME / 2.0;
@@ -1,11 +1,12 @@
 use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
-use lotus_script::time::delta;
 
 use crate::api::{
     animation::{Animation, MappedAnimation},
-    light::{BlinkRelais, Light},
+    general::delta,
+    light::Light,
     sound::{Sound, SoundTarget},
 };
+use crate::elements::std::relays::FlasherRelay;
 
 const LIFT_HIGHT_SO: f32 = 1.0;
 const LIFT_HIGHT_PLATFORM: f32 = 0.3;
@@ -50,7 +51,7 @@ pub struct Hublift {
     snd_barrier_down: Sound,
     snd_barrier_up: Sound,
 
-    pub warnrelais: BlinkRelais,
+    pub warnrelais: FlasherRelay,
     l_lift_warning: Light,
 
     pub in_use: bool,
@@ -188,7 +189,7 @@ impl Hublift {
                 None,
             ),
 
-            warnrelais: BlinkRelais::new(LIFT_DOORWARN_INTERVAL, LIFT_DOORWARN_INTERVAL_HALF, 0.1),
+            warnrelais: FlasherRelay::new(LIFT_DOORWARN_INTERVAL, LIFT_DOORWARN_INTERVAL_HALF, 0.1),
 
             l_lift_warning: Light::new(Some(&format!(
                 "L_{}_Hubliftwarnung",
@@ -410,11 +411,7 @@ impl Hublift {
 
         self.in_use = self.locking > 0.0;
 
-        if self.in_use {
-            self.warnrelais.tick();
-        } else {
-            self.warnrelais.reset();
-        }
+        self.warnrelais.run(self.in_use);
         self.l_lift_warning
             .set_brightness(self.warnrelais.is_on as u8 as f32);
     }
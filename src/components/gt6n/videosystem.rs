@@ -1,6 +1,4 @@
-use lotus_script::time::delta;
-
-use crate::api::visible_flag::Visiblility;
+use crate::api::{general::delta, visible_flag::Visiblility};
 
 const VIDEOSYSTEM_BLINK: f32 = 1.5;
 const VIDEOSYSTEM_BLINK_HALF: f32 = VIDEOSYSTEM_BLINK / 2.0;
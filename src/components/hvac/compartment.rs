@@ -0,0 +1,84 @@
+//! Temperature model of a cab or passenger compartment.
+//!
+//! The [`Compartment`] is a single heat capacity which exchanges heat with the
+//! outside through the body shell, open doors and the ventilation. Heaters
+//! feed their heat output into it.
+//!
+//! Temperatures are given in °C, heat flows in kW.
+
+use crate::api::general::delta;
+
+/// Heat capacity of a compartment with losses to the outside.
+///
+/// # Example
+///
+/// ```rust
+/// let mut saloon = Compartment::new(400.0, 0.8, 1.5).init_temperature(18.0);
+///
+/// // In the main loop:
+/// saloon.tick(outside_temp, heating.heat_kw, doors_open, fan.speed);
+/// ```
+#[derive(Debug)]
+pub struct Compartment {
+    heat_capacity: f32,
+    insulation_loss: f32,
+    door_loss: f32,
+    ventilation_loss: f32,
+
+    /// Current temperature in °C
+    pub temperature: f32,
+}
+
+impl Compartment {
+    /// Creates a compartment at 20 °C.
+    ///
+    /// # Arguments
+    ///
+    /// * `heat_capacity` - Heat capacity in kJ/K
+    /// * `insulation_loss` - Heat loss through the body shell in kW/K
+    /// * `door_loss` - Additional heat loss per open door in kW/K
+    #[must_use]
+    pub fn new(heat_capacity: f32, insulation_loss: f32, door_loss: f32) -> Self {
+        Self {
+            heat_capacity,
+            insulation_loss,
+            door_loss,
+            ventilation_loss: 0.5,
+            temperature: 20.0,
+        }
+    }
+
+    pub fn init_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Additional heat loss in kW/K with the ventilation at full speed.
+    pub fn ventilation_loss(mut self, ventilation_loss: f32) -> Self {
+        self.ventilation_loss = ventilation_loss;
+        self
+    }
+
+    /// Updates the temperature.
+    ///
+    /// # Arguments
+    ///
+    /// * `outside_temperature` - Outside temperature in °C
+    /// * `heat` - Heat fed into the compartment in kW
+    /// * `doors_open` - Number of open doors
+    /// * `ventilation` - Speed of the ventilation from 0.0 to 1.0
+    pub fn tick(
+        &mut self,
+        outside_temperature: f32,
+        heat: f32,
+        doors_open: usize,
+        ventilation: f32,
+    ) {
+        let loss_factor = self.insulation_loss
+            + self.door_loss * doors_open as f32
+            + self.ventilation_loss * ventilation.clamp(0.0, 1.0);
+        let loss = loss_factor * (self.temperature - outside_temperature);
+
+        self.temperature += (heat - loss) / self.heat_capacity * delta();
+    }
+}
@@ -0,0 +1,126 @@
+//! Ventilation fan.
+//!
+//! The [`VentilationFan`] spins up and down with the configured times and
+//! drives its running sound with the fan speed. Its electrical load follows
+//! the cube of the speed.
+
+use crate::api::{general::delta, sound::Sound};
+
+pub struct VentilationFanBuilder {
+    spin_up_time: f32,
+    spin_down_time: f32,
+    rated_power: f32,
+
+    snd_run: Sound,
+}
+
+impl VentilationFanBuilder {
+    /// Times in seconds to reach full speed and to come to a halt.
+    pub fn spin_times(mut self, spin_up_time: f32, spin_down_time: f32) -> Self {
+        self.spin_up_time = spin_up_time;
+        self.spin_down_time = spin_down_time;
+        self
+    }
+
+    /// Electrical power in kW at full speed.
+    pub fn rated_power(mut self, rated_power: f32) -> Self {
+        self.rated_power = rated_power;
+        self
+    }
+
+    /// Running sound, volume and pitch follow the fan speed.
+    pub fn snd_run(
+        mut self,
+        sound_name: impl Into<String>,
+        sound_vol_name: impl Into<String>,
+        sound_pitch_name: impl Into<String>,
+    ) -> Self {
+        self.snd_run = Sound::new(
+            Some(&sound_name.into()),
+            Some(&sound_vol_name.into()),
+            Some(&sound_pitch_name.into()),
+        );
+        self
+    }
+
+    pub fn build(self) -> VentilationFan {
+        VentilationFan {
+            spin_up_time: self.spin_up_time,
+            spin_down_time: self.spin_down_time,
+            rated_power: self.rated_power,
+            speed: 0.0,
+            load_kw: 0.0,
+            snd_run: self.snd_run,
+        }
+    }
+}
+
+/// Ventilation fan with spin-up and spin-down.
+///
+/// # Example
+///
+/// ```rust
+/// let mut fan = VentilationFan::builder()
+///     .spin_times(4.0, 10.0)
+///     .snd_run("Snd_Fan", "Snd_Fan_Vol", "Snd_Fan_Pitch")
+///     .build();
+///
+/// // In the main loop:
+/// fan.tick(voltage > 0.5, 0.5);
+/// ```
+#[derive(Debug)]
+pub struct VentilationFan {
+    spin_up_time: f32,
+    spin_down_time: f32,
+    rated_power: f32,
+
+    /// Fan speed from 0.0 to 1.0
+    pub speed: f32,
+    /// Electrical load in kW
+    pub load_kw: f32,
+
+    snd_run: Sound,
+}
+
+impl VentilationFan {
+    pub fn builder() -> VentilationFanBuilder {
+        VentilationFanBuilder {
+            spin_up_time: 3.0,
+            spin_down_time: 8.0,
+            rated_power: 0.5,
+            snd_run: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the fan.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the fan is supplied
+    /// * `level` - Requested fan level from 0.0 (off) to 1.0 (full speed)
+    pub fn tick(&mut self, power: bool, level: f32) {
+        let target = if power { level.clamp(0.0, 1.0) } else { 0.0 };
+        let speed_last = self.speed;
+
+        if self.speed < target {
+            self.speed = (self.speed + delta() / self.spin_up_time).min(target);
+        } else if self.speed > target {
+            self.speed = (self.speed - delta() / self.spin_down_time).max(target);
+        }
+
+        if self.speed > 0.0 && speed_last <= 0.0 {
+            self.snd_run.start();
+        } else if self.speed <= 0.0 && speed_last > 0.0 {
+            self.snd_run.stop();
+        }
+
+        self.snd_run.update_volume(self.speed);
+        self.snd_run.update_pitch(self.speed);
+
+        self.load_kw = if power {
+            self.rated_power * self.speed.powi(3)
+        } else {
+            0.0
+        };
+    }
+}
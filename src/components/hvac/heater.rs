@@ -0,0 +1,218 @@
+//! Cab heater and saloon heating.
+//!
+//! Both heaters are resistance heaters with a thermostat. The heat output
+//! follows the electrical power with the thermal inertia of the heating
+//! elements. The electrical load is given in `load_kw`. The requested power
+//! `demand_kw` is reported to the supply with
+//! [`PowerlineManager::request_kw`], a shed load is passed back as
+//! `power = false`.
+//!
+//! [`PowerlineManager::request_kw`]: crate::components::electrics::powerline::PowerlineManager::request_kw
+
+use crate::api::{general::delta, light::Light};
+
+/// Cab heater with thermostat.
+///
+/// # Example
+///
+/// ```rust
+/// let mut heater = CabHeater::new(3.0).thermostat(22.0, 1.0);
+///
+/// // In the main loop:
+/// powerline.request_kw("Kabinenheizung", heater.demand_kw);
+/// heater.tick(powerline.is_supplied("Kabinenheizung"), heater_switch, cab.temperature);
+/// cab.tick(outside_temp, heater.heat_kw, cab_door_open as usize, 0.0);
+/// ```
+#[derive(Debug)]
+pub struct CabHeater {
+    rated_power: f32,
+    target_temperature: f32,
+    hysteresis: f32,
+    time_constant: f32,
+
+    /// Whether the thermostat switches the heater on
+    pub heating: bool,
+    /// Heat output in kW
+    pub heat_kw: f32,
+    /// Electrical load in kW
+    pub load_kw: f32,
+    /// Power in kW while the thermostat calls for heat, also when shed
+    pub demand_kw: f32,
+
+    lm_on: Light,
+}
+
+impl CabHeater {
+    /// Creates a cab heater.
+    ///
+    /// # Arguments
+    ///
+    /// * `rated_power` - Electrical power of the heater in kW
+    #[must_use]
+    pub fn new(rated_power: f32) -> Self {
+        Self {
+            rated_power,
+            target_temperature: 22.0,
+            hysteresis: 1.0,
+            time_constant: 20.0,
+            heating: false,
+            heat_kw: 0.0,
+            load_kw: 0.0,
+            demand_kw: 0.0,
+            lm_on: Light::new(None),
+        }
+    }
+
+    /// Target temperature in °C and the switching hysteresis in K.
+    pub fn thermostat(mut self, target_temperature: f32, hysteresis: f32) -> Self {
+        self.target_temperature = target_temperature;
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Time constant in seconds of the heating elements.
+    pub fn time_constant(mut self, time_constant: f32) -> Self {
+        self.time_constant = time_constant;
+        self
+    }
+
+    /// Indicator light which is lit while the heater is switched on.
+    pub fn light(mut self, name: impl Into<String>) -> Self {
+        self.lm_on = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Updates the heater.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the heater is supplied
+    /// * `switch_on` - Heater switch in the cab
+    /// * `temperature` - Current cab temperature in °C
+    pub fn tick(&mut self, power: bool, switch_on: bool, temperature: f32) {
+        if temperature < self.target_temperature - self.hysteresis / 2.0 {
+            self.heating = true;
+        } else if temperature > self.target_temperature + self.hysteresis / 2.0 {
+            self.heating = false;
+        }
+
+        self.demand_kw = (switch_on && self.heating) as u8 as f32 * self.rated_power;
+        self.load_kw = power as u8 as f32 * self.demand_kw;
+        self.heat_kw += (self.load_kw - self.heat_kw) / self.time_constant * delta();
+
+        self.lm_on.set_brightness((power && switch_on) as u8 as f32);
+    }
+}
+
+//=========================================================================
+
+/// Saloon heating with several stages.
+///
+/// The stages are switched on one after another the further the temperature
+/// is below the target.
+///
+/// # Example
+///
+/// ```rust
+/// let mut heating = SaloonHeating::new(vec![4.0, 4.0, 8.0]).thermostat(18.0, 1.5);
+///
+/// // In the main loop:
+/// powerline.request_kw("Heizung", heating.demand_kw);
+/// heating.tick(powerline.is_supplied("Heizung"), saloon.temperature);
+/// saloon.tick(outside_temp, heating.heat_kw, doors_open, fan.speed);
+/// ```
+#[derive(Debug)]
+pub struct SaloonHeating {
+    stage_power: Vec<f32>,
+    target_temperature: f32,
+    stage_step: f32,
+    time_constant: f32,
+    switching_delay: f32,
+
+    switching_timer: f32,
+
+    /// Number of active heating stages
+    pub stage: usize,
+    /// Heat output in kW
+    pub heat_kw: f32,
+    /// Electrical load in kW
+    pub load_kw: f32,
+    /// Power in kW of the stages called for by the thermostat
+    pub demand_kw: f32,
+}
+
+impl SaloonHeating {
+    /// Creates a saloon heating.
+    ///
+    /// # Arguments
+    ///
+    /// * `stage_power` - Electrical power of each stage in kW
+    #[must_use]
+    pub fn new(stage_power: Vec<f32>) -> Self {
+        Self {
+            stage_power,
+            target_temperature: 18.0,
+            stage_step: 1.0,
+            time_constant: 60.0,
+            switching_delay: 5.0,
+            switching_timer: 0.0,
+            stage: 0,
+            heat_kw: 0.0,
+            load_kw: 0.0,
+            demand_kw: 0.0,
+        }
+    }
+
+    /// Target temperature in °C and the temperature difference in K per
+    /// additional stage.
+    pub fn thermostat(mut self, target_temperature: f32, stage_step: f32) -> Self {
+        self.target_temperature = target_temperature;
+        self.stage_step = stage_step;
+        self
+    }
+
+    /// Time constant in seconds of the heating elements.
+    pub fn time_constant(mut self, time_constant: f32) -> Self {
+        self.time_constant = time_constant;
+        self
+    }
+
+    /// Minimum time in seconds between two stage changes.
+    pub fn switching_delay(mut self, switching_delay: f32) -> Self {
+        self.switching_delay = switching_delay;
+        self
+    }
+
+    /// Updates the heating.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the heating is supplied
+    /// * `temperature` - Current saloon temperature in °C
+    pub fn tick(&mut self, power: bool, temperature: f32) {
+        let deficit = self.target_temperature - temperature;
+        let demand_stage = if deficit > 0.0 {
+            ((deficit / self.stage_step).ceil() as usize).min(self.stage_power.len())
+        } else {
+            0
+        };
+        self.demand_kw = self.stage_power.iter().take(demand_stage).sum();
+        let target_stage = if power { demand_stage } else { 0 };
+
+        // Stufen nacheinander schalten
+        self.switching_timer += delta();
+        if !power {
+            self.stage = 0;
+        } else if target_stage != self.stage && self.switching_timer > self.switching_delay {
+            if target_stage > self.stage {
+                self.stage += 1;
+            } else {
+                self.stage -= 1;
+            }
+            self.switching_timer = 0.0;
+        }
+
+        self.load_kw = self.stage_power.iter().take(self.stage).sum();
+        self.heat_kw += (self.load_kw - self.heat_kw) / self.time_constant * delta();
+    }
+}
@@ -0,0 +1,4 @@
+pub mod compartment;
+pub mod fan;
+pub mod heater;
+pub mod window_heater;
@@ -0,0 +1,259 @@
+//! Window heating and glass fogging.
+//!
+//! The [`WindowHeater`] warms a pane of glass, the [`GlassFogging`] model
+//! lets condensation build up on the inside when the glass is colder than
+//! the dew point of the air in the compartment, and ice on the outside in
+//! frost. Both layers are shown with an animation value, e.g. to drive the
+//! opacity of a material. Condensation evaporates once the glass is warm
+//! enough, ice melts above 0 °C, and the wipers scrape the outside clear.
+//!
+//! Temperatures are given in °C, humidities as relative humidity from 0.0 to
+//! 1.0. The power requested by the heater is reported to the supply with
+//! [`PowerlineManager::request_kw`].
+//!
+//! [`PowerlineManager::request_kw`]: crate::components::electrics::powerline::PowerlineManager::request_kw
+
+use crate::api::{animation::Animation, general::delta, light::Light};
+
+/// Calculates the dew point in °C with the Magnus formula.
+fn dew_point(temperature: f32, humidity: f32) -> f32 {
+    const A: f32 = 17.62;
+    const B: f32 = 243.12;
+
+    let gamma = (humidity.clamp(0.01, 1.0)).ln() + A * temperature / (B + temperature);
+    B * gamma / (A - gamma)
+}
+
+/// Electric heating of a window pane.
+///
+/// The glass temperature settles between the inside and outside temperature
+/// and is raised by the heating power.
+///
+/// # Example
+///
+/// ```rust
+/// let mut heater = WindowHeater::new(0.8).light("L_Window_Heating");
+///
+/// // In the main loop:
+/// powerline.request_kw("Scheibenheizung", heater.demand_kw);
+/// heater.tick(
+///     powerline.is_supplied("Scheibenheizung"),
+///     heating_switch,
+///     cab.temperature,
+///     outside_temp,
+///     v_ground(),
+/// );
+/// ```
+#[derive(Debug)]
+pub struct WindowHeater {
+    rated_power: f32,
+    heat_capacity: f32,
+    heat_transfer: f32,
+
+    /// Temperature of the glass in °C
+    pub glass_temperature: f32,
+    /// Electrical load in kW
+    pub load_kw: f32,
+    /// Power in kW asked for while switched on, also when the supply is shed
+    pub demand_kw: f32,
+
+    lm_on: Light,
+}
+
+impl WindowHeater {
+    /// Creates a window heater.
+    ///
+    /// # Arguments
+    ///
+    /// * `rated_power` - Electrical power of the heating in kW
+    #[must_use]
+    pub fn new(rated_power: f32) -> Self {
+        Self {
+            rated_power,
+            heat_capacity: 20.0,
+            heat_transfer: 0.1,
+            glass_temperature: 10.0,
+            load_kw: 0.0,
+            demand_kw: 0.0,
+            lm_on: Light::new(None),
+        }
+    }
+
+    /// Heat capacity of the pane in kJ/K and the heat transfer to the air on
+    /// each side in kW/K.
+    pub fn glass(mut self, heat_capacity: f32, heat_transfer: f32) -> Self {
+        self.heat_capacity = heat_capacity;
+        self.heat_transfer = heat_transfer;
+        self
+    }
+
+    pub fn init_temperature(mut self, glass_temperature: f32) -> Self {
+        self.glass_temperature = glass_temperature;
+        self
+    }
+
+    /// Indicator light which is lit while the heating is switched on.
+    pub fn light(mut self, name: impl Into<String>) -> Self {
+        self.lm_on = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Updates the glass temperature.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the heating is supplied
+    /// * `switch_on` - Heating switch in the cab
+    /// * `inside_temperature` - Temperature of the compartment in °C
+    /// * `outside_temperature` - Outside temperature in °C
+    /// * `speed` - Vehicle speed in m/s, the airstream cools the outside
+    pub fn tick(
+        &mut self,
+        power: bool,
+        switch_on: bool,
+        inside_temperature: f32,
+        outside_temperature: f32,
+        speed: f32,
+    ) {
+        let on = power && switch_on;
+        self.demand_kw = switch_on as u8 as f32 * self.rated_power;
+        self.load_kw = on as u8 as f32 * self.rated_power;
+
+        // Fahrtwind verstärkt die Kühlung von außen
+        let outside_transfer = self.heat_transfer * (1.0 + speed.abs() / 10.0);
+
+        let heat_flow = self.load_kw
+            + self.heat_transfer * (inside_temperature - self.glass_temperature)
+            + outside_transfer * (outside_temperature - self.glass_temperature);
+        self.glass_temperature += heat_flow / self.heat_capacity * delta();
+
+        self.lm_on.set_brightness(on as u8 as f32);
+    }
+}
+
+//=========================================================================
+
+/// Condensation and icing of a window pane.
+///
+/// # Example
+///
+/// ```rust
+/// let mut fogging = GlassFogging::new("Windshield_Fog", "Windshield_Ice");
+///
+/// // In the main loop:
+/// fogging.tick(heater.glass_temperature, cab.temperature, 0.6, outside_temp, wiper.motor_pos);
+/// ```
+#[derive(Debug)]
+pub struct GlassFogging {
+    condensation_rate: f32,
+    evaporation_rate: f32,
+    icing_rate: f32,
+    melting_rate: f32,
+    wiper_scrape: f32,
+
+    wiper_pos_last: f32,
+
+    /// Condensation on the inside from 0.0 (clear) to 1.0 (opaque)
+    pub fog: f32,
+    /// Ice on the outside from 0.0 (clear) to 1.0 (opaque)
+    pub ice: f32,
+
+    fog_anim: Animation,
+    ice_anim: Animation,
+}
+
+impl GlassFogging {
+    /// Creates a clear pane.
+    ///
+    /// # Arguments
+    ///
+    /// * `fog_anim_name` - Animation showing the condensation on the inside
+    /// * `ice_anim_name` - Animation showing the ice on the outside
+    pub fn new(fog_anim_name: impl Into<String>, ice_anim_name: impl Into<String>) -> Self {
+        Self {
+            condensation_rate: 0.02,
+            evaporation_rate: 0.05,
+            icing_rate: 0.005,
+            melting_rate: 0.02,
+            wiper_scrape: 0.3,
+            wiper_pos_last: 0.0,
+            fog: 0.0,
+            ice: 0.0,
+            fog_anim: Animation::new(Some(&fog_anim_name.into())),
+            ice_anim: Animation::new(Some(&ice_anim_name.into())),
+        }
+    }
+
+    /// Initial condensation and ice, e.g. for a vehicle parked overnight.
+    pub fn init(mut self, fog: f32, ice: f32) -> Self {
+        self.fog = fog.clamp(0.0, 1.0);
+        self.ice = ice.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Rates per second and per kelvin of difference to the dew point or
+    /// freezing point.
+    pub fn rates(
+        mut self,
+        condensation_rate: f32,
+        evaporation_rate: f32,
+        icing_rate: f32,
+        melting_rate: f32,
+    ) -> Self {
+        self.condensation_rate = condensation_rate;
+        self.evaporation_rate = evaporation_rate;
+        self.icing_rate = icing_rate;
+        self.melting_rate = melting_rate;
+        self
+    }
+
+    /// Share of the ice removed by a full wiper stroke.
+    pub fn wiper_scrape(mut self, wiper_scrape: f32) -> Self {
+        self.wiper_scrape = wiper_scrape;
+        self
+    }
+
+    /// Updates condensation and ice.
+    ///
+    /// # Arguments
+    ///
+    /// * `glass_temperature` - Temperature of the pane in °C
+    /// * `inside_temperature` - Temperature of the compartment in °C
+    /// * `inside_humidity` - Relative humidity in the compartment
+    /// * `outside_temperature` - Outside temperature in °C
+    /// * `wiper_pos` - Position of the wiper from 0.0 to 1.0, if the pane has one
+    pub fn tick(
+        &mut self,
+        glass_temperature: f32,
+        inside_temperature: f32,
+        inside_humidity: f32,
+        outside_temperature: f32,
+        wiper_pos: f32,
+    ) {
+        // Beschlag innen
+        let dew_gap = dew_point(inside_temperature, inside_humidity) - glass_temperature;
+        if dew_gap > 0.0 {
+            self.fog += self.condensation_rate * dew_gap * delta();
+        } else {
+            self.fog += self.evaporation_rate * dew_gap * delta();
+        }
+
+        // Vereisung außen
+        if glass_temperature < 0.0 && outside_temperature < 0.0 {
+            self.ice -= self.icing_rate * glass_temperature * delta();
+        } else if glass_temperature > 0.0 {
+            self.ice -= self.melting_rate * glass_temperature * delta();
+        }
+
+        // Wischer kratzt das Eis ab
+        let wiped = (wiper_pos - self.wiper_pos_last).abs();
+        self.wiper_pos_last = wiper_pos;
+        self.ice -= self.ice * self.wiper_scrape * wiped;
+
+        self.fog = self.fog.clamp(0.0, 1.0);
+        self.ice = self.ice.clamp(0.0, 1.0);
+
+        self.fog_anim.set(self.fog);
+        self.ice_anim.set(self.ice);
+    }
+}
@@ -0,0 +1,253 @@
+//! Turn indicators with flasher relay.
+//!
+//! The [`BlinkerUnit`] latches the indicator lever, adds the hazard warning
+//! and shares the result with the whole consist over the `CouplerIndicator`
+//! coupling line. Like a real flasher relay it blinks faster if a bulb of an
+//! active side has failed. The lever is reset automatically after the vehicle
+//! has passed through a curve in the indicated direction.
+
+use lotus_extra::vehicle::CockpitSide;
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{light::Light, sound::Sound},
+    elements::std::relays::FlasherRelay,
+    messages::{
+        coupling_handler::UniversalCouplingLine,
+        gt6n_coupling_messages::{CouplerIndicator, Indicator},
+    },
+};
+
+pub struct BlinkerUnitBuilder {
+    interval: f32,
+    fast_factor: f32,
+    reset_curvature: f32,
+
+    lm_left: Vec<Light>,
+    lm_right: Vec<Light>,
+    telltales: Vec<(CockpitSide, Light, Light)>,
+
+    snd_relais_on: Sound,
+    snd_relais_off: Sound,
+}
+
+impl BlinkerUnitBuilder {
+    /// Blink interval in seconds and the factor by which the interval is
+    /// shortened on a bulb failure.
+    pub fn interval(mut self, interval: f32, fast_factor: f32) -> Self {
+        self.interval = interval;
+        self.fast_factor = fast_factor;
+        self
+    }
+
+    /// Curvature in 1/m which counts as a turn for the automatic reset of the
+    /// lever. The lever is reset once the curvature has dropped below half of
+    /// this value again.
+    pub fn reset_curvature(mut self, reset_curvature: f32) -> Self {
+        self.reset_curvature = reset_curvature;
+        self
+    }
+
+    pub fn add_left(mut self, name: impl Into<String>) -> Self {
+        self.lm_left.push(Light::new(Some(&name.into())));
+        self
+    }
+
+    pub fn add_right(mut self, name: impl Into<String>) -> Self {
+        self.lm_right.push(Light::new(Some(&name.into())));
+        self
+    }
+
+    /// Adds the telltales of a cab. Left and right refer to the view out of
+    /// the cab, so they are swapped for cab B.
+    pub fn telltale(
+        mut self,
+        cab_side: CockpitSide,
+        left_name: impl Into<String>,
+        right_name: impl Into<String>,
+    ) -> Self {
+        self.telltales.push((
+            cab_side,
+            Light::new(Some(&left_name.into())),
+            Light::new(Some(&right_name.into())),
+        ));
+        self
+    }
+
+    pub fn relais_sounds(
+        mut self,
+        sound_on_name: impl Into<String>,
+        sound_off_name: impl Into<String>,
+    ) -> Self {
+        self.snd_relais_on = Sound::new_simple(Some(&sound_on_name.into()));
+        self.snd_relais_off = Sound::new_simple(Some(&sound_off_name.into()));
+        self
+    }
+
+    pub fn build(self) -> BlinkerUnit {
+        let fast_interval = self.interval * self.fast_factor;
+
+        BlinkerUnit {
+            reset_curvature: self.reset_curvature,
+            relais: FlasherRelay::new(self.interval, self.interval / 2.0, 0.0),
+            relais_fast: FlasherRelay::new(fast_interval, fast_interval / 2.0, 0.0),
+            lm_left: self.lm_left,
+            lm_right: self.lm_right,
+            telltales: self.telltales,
+            snd_relais_on: self.snd_relais_on,
+            snd_relais_off: self.snd_relais_off,
+            indicator_line: UniversalCouplingLine::new(CouplerIndicator, (true, true)),
+            lever: Indicator::default(),
+            turning: false,
+            active: Indicator::default(),
+            lighted: false,
+            bulb_failure_left: false,
+            bulb_failure_right: false,
+        }
+    }
+}
+
+/// Flasher relay for turn indicators and hazard warning.
+///
+/// # Example
+///
+/// ```rust
+/// let mut blinker = BlinkerUnit::builder()
+///     .add_left("L_Blinker_L")
+///     .add_right("L_Blinker_R")
+///     .telltale(CockpitSide::A, "L_Cab_A_Blinker_L", "L_Cab_A_Blinker_R")
+///     .relais_sounds("Snd_Blinker_On", "Snd_Blinker_Off")
+///     .build();
+///
+/// // In on_message:
+/// blinker.on_message(msg);
+///
+/// // In the main loop:
+/// if lever_left.is_just_pressed() {
+///     blinker.set_lever(true, false);
+/// }
+/// blinker.tick(power, hazard_switch, curvature);
+/// ```
+pub struct BlinkerUnit {
+    reset_curvature: f32,
+
+    relais: FlasherRelay,
+    relais_fast: FlasherRelay,
+
+    lm_left: Vec<Light>,
+    lm_right: Vec<Light>,
+    telltales: Vec<(CockpitSide, Light, Light)>,
+
+    snd_relais_on: Sound,
+    snd_relais_off: Sound,
+
+    indicator_line: UniversalCouplingLine<Indicator, CouplerIndicator>,
+
+    lever: Indicator,
+    turning: bool,
+
+    /// Indicators active in the consist
+    pub active: Indicator,
+    /// Whether the indicator lamps are lit at the moment
+    pub lighted: bool,
+
+    /// Failed bulb on the left side, makes the relay blink faster
+    pub bulb_failure_left: bool,
+    /// Failed bulb on the right side, makes the relay blink faster
+    pub bulb_failure_right: bool,
+}
+
+impl BlinkerUnit {
+    pub fn builder() -> BlinkerUnitBuilder {
+        BlinkerUnitBuilder {
+            interval: 0.7,
+            fast_factor: 0.5,
+            reset_curvature: 1.0 / 50.0,
+            lm_left: Vec::new(),
+            lm_right: Vec::new(),
+            telltales: Vec::new(),
+            snd_relais_on: Sound::new_simple(None),
+            snd_relais_off: Sound::new_simple(None),
+        }
+    }
+
+    /// Passes messages to the `CouplerIndicator` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.indicator_line.on_message(msg);
+    }
+
+    /// Sets the indicator lever, left and right refer to the vehicle.
+    pub fn set_lever(&mut self, left: bool, right: bool) {
+        self.lever.left = left;
+        self.lever.right = right && !left;
+        self.turning = false;
+    }
+
+    /// Updates the relay and all indicator lamps.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the indicators are supplied
+    /// * `hazard` - Hazard warning switch, overrides the lever
+    /// * `curvature` - Curvature of the track or steering in 1/m, positive
+    ///   values are right-hand curves
+    pub fn tick(&mut self, power: bool, hazard: bool, curvature: f32) {
+        // Rückstellung nach dem Abbiegen
+        let curve_in_direction = (self.lever.left && curvature < -self.reset_curvature)
+            || (self.lever.right && curvature > self.reset_curvature);
+        if curve_in_direction {
+            self.turning = true;
+        } else if self.turning && curvature.abs() < self.reset_curvature / 2.0 {
+            self.set_lever(false, false);
+        }
+
+        self.lever.warn = hazard;
+        self.indicator_line.update_local(if power {
+            self.lever
+        } else {
+            Indicator::default()
+        });
+        self.active = self.indicator_line.get_value();
+
+        let left = power && (self.active.left || self.active.warn);
+        let right = power && (self.active.right || self.active.warn);
+
+        let fast = (left && self.bulb_failure_left) || (right && self.bulb_failure_right);
+
+        let (relais, other) = if fast {
+            (&mut self.relais_fast, &mut self.relais)
+        } else {
+            (&mut self.relais, &mut self.relais_fast)
+        };
+        other.reset();
+
+        relais.run(left || right);
+
+        // Klicken am Lampenzustand, auch beim Wechsel zwischen den Relais
+        if relais.is_on && !self.lighted {
+            self.snd_relais_on.start();
+        } else if !relais.is_on && self.lighted {
+            self.snd_relais_off.start();
+        }
+        self.lighted = relais.is_on;
+
+        let left_on = (self.lighted && left) as u8 as f32;
+        let right_on = (self.lighted && right) as u8 as f32;
+
+        for lm in &self.lm_left {
+            lm.set_brightness(left_on);
+        }
+        for lm in &self.lm_right {
+            lm.set_brightness(right_on);
+        }
+
+        for (cab_side, lm_left, lm_right) in &self.telltales {
+            let (cab_left, cab_right) = match cab_side {
+                CockpitSide::A => (left_on, right_on),
+                CockpitSide::B => (right_on, left_on),
+            };
+            lm_left.set_brightness(cab_left);
+            lm_right.set_brightness(cab_right);
+        }
+    }
+}
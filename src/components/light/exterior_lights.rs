@@ -0,0 +1,191 @@
+//! Exterior lights of a vehicle end.
+//!
+//! The [`LightController`] switches headlights, taillights and brake lights at
+//! both ends of the car. The white and red lights follow the direction of
+//! driving, so the leading end shows white and the trailing end shows red.
+//! Cars announce themselves to their neighbours with the `CouplerCarPresent`
+//! coupling line, so only the outer ends of a consist are lit.
+
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::light::Light,
+    management::enums::{general_enums::HeadlightMode, traction_enums::DirectionOfDriving},
+    messages::{
+        coupling_handler::UniversalCouplingLine, gt6n_coupling_messages::CouplerCarPresent,
+    },
+};
+
+#[derive(Debug)]
+struct EndLights {
+    headlight_dim: Light,
+    headlight_full: Light,
+    taillight: Light,
+    brakelight: Light,
+}
+
+impl EndLights {
+    fn new() -> Self {
+        Self {
+            headlight_dim: Light::new(None),
+            headlight_full: Light::new(None),
+            taillight: Light::new(None),
+            brakelight: Light::new(None),
+        }
+    }
+
+    fn set(&self, voltage: f32, white: bool, red: bool, mode: HeadlightMode, braking: bool) {
+        self.headlight_dim
+            .set_brightness((white && mode >= HeadlightMode::Dim) as u8 as f32 * voltage);
+        self.headlight_full
+            .set_brightness((white && mode == HeadlightMode::Full) as u8 as f32 * voltage);
+        self.taillight.set_brightness(red as u8 as f32 * voltage);
+        self.brakelight
+            .set_brightness((red && braking) as u8 as f32 * voltage);
+    }
+}
+
+pub struct LightControllerBuilder {
+    front: EndLights,
+    rear: EndLights,
+}
+
+impl LightControllerBuilder {
+    /// Dimmed headlights at the front and rear end.
+    pub fn headlights(
+        mut self,
+        front_name: impl Into<String>,
+        rear_name: impl Into<String>,
+    ) -> Self {
+        self.front.headlight_dim = Light::new(Some(&front_name.into()));
+        self.rear.headlight_dim = Light::new(Some(&rear_name.into()));
+        self
+    }
+
+    /// Full beam headlights at the front and rear end.
+    pub fn full_beam(
+        mut self,
+        front_name: impl Into<String>,
+        rear_name: impl Into<String>,
+    ) -> Self {
+        self.front.headlight_full = Light::new(Some(&front_name.into()));
+        self.rear.headlight_full = Light::new(Some(&rear_name.into()));
+        self
+    }
+
+    pub fn taillights(
+        mut self,
+        front_name: impl Into<String>,
+        rear_name: impl Into<String>,
+    ) -> Self {
+        self.front.taillight = Light::new(Some(&front_name.into()));
+        self.rear.taillight = Light::new(Some(&rear_name.into()));
+        self
+    }
+
+    pub fn brakelights(
+        mut self,
+        front_name: impl Into<String>,
+        rear_name: impl Into<String>,
+    ) -> Self {
+        self.front.brakelight = Light::new(Some(&front_name.into()));
+        self.rear.brakelight = Light::new(Some(&rear_name.into()));
+        self
+    }
+
+    pub fn build(self) -> LightController {
+        let mut presence_line = UniversalCouplingLine::new(CouplerCarPresent, (true, true));
+        presence_line.update_local(true);
+
+        LightController {
+            front: self.front,
+            rear: self.rear,
+            presence_line,
+            front_outer: true,
+            rear_outer: true,
+        }
+    }
+}
+
+/// Head-, tail- and brake lights at both ends of a car.
+///
+/// # Example
+///
+/// ```rust
+/// let mut lights = LightController::builder()
+///     .headlights("L_Head_A", "L_Head_B")
+///     .full_beam("L_FullBeam_A", "L_FullBeam_B")
+///     .taillights("L_Tail_A", "L_Tail_B")
+///     .brakelights("L_Brake_A", "L_Brake_B")
+///     .build();
+///
+/// // In on_message:
+/// lights.on_message(msg);
+///
+/// // In the main loop:
+/// lights.tick(voltage, direction, headlight_switch, brake_force > 0.0);
+/// ```
+pub struct LightController {
+    front: EndLights,
+    rear: EndLights,
+
+    presence_line: UniversalCouplingLine<bool, CouplerCarPresent>,
+
+    /// Whether no car is coupled at the front end
+    pub front_outer: bool,
+    /// Whether no car is coupled at the rear end
+    pub rear_outer: bool,
+}
+
+impl LightController {
+    pub fn builder() -> LightControllerBuilder {
+        LightControllerBuilder {
+            front: EndLights::new(),
+            rear: EndLights::new(),
+        }
+    }
+
+    /// Passes messages to the `CouplerCarPresent` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.presence_line.on_message(msg);
+    }
+
+    /// Updates all exterior lights.
+    ///
+    /// Without a clear direction of driving both outer ends show red.
+    ///
+    /// # Arguments
+    ///
+    /// * `voltage` - Supply voltage of the lights from 0.0 to 1.0
+    /// * `direction` - Direction of driving, forward means the front end is leading
+    /// * `headlight` - Position of the headlight switch
+    /// * `braking` - Whether the brake lights are to be lit
+    pub fn tick(
+        &mut self,
+        voltage: f32,
+        direction: DirectionOfDriving,
+        headlight: HeadlightMode,
+        braking: bool,
+    ) {
+        self.front_outer = !self.presence_line.get_front();
+        self.rear_outer = !self.presence_line.get_rear();
+
+        let front_leading = direction.is_one() && direction.forward;
+        let rear_leading = direction.is_one() && direction.backward;
+
+        self.front.set(
+            voltage,
+            self.front_outer && front_leading,
+            self.front_outer && !front_leading,
+            headlight,
+            braking,
+        );
+        self.rear.set(
+            voltage,
+            self.rear_outer && rear_leading,
+            self.rear_outer && !rear_leading,
+            headlight,
+            braking,
+        );
+    }
+}
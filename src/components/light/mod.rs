@@ -0,0 +1,2 @@
+pub mod blinker;
+pub mod exterior_lights;
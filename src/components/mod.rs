@@ -1,5 +1,16 @@
+pub mod brakes;
+pub mod communication;
+pub mod coupler;
 pub mod doors;
 pub mod electrics;
 pub mod general;
 pub mod gt6n;
+pub mod hvac;
+pub mod light;
+pub mod passenger_info;
+pub mod pneumatics;
+pub mod running_gear;
+pub mod safety;
+pub mod sound;
 pub mod traction;
+pub mod train_protection;
@@ -0,0 +1,281 @@
+//! Synchronous announcements in coupled cars.
+//!
+//! The [`AnnouncementSynchronizer`] of the car with the IBIS master triggers an
+//! announcement and the matching page of the interior displays in all coupled
+//! cars at the same time. The trigger is passed from car to car over the
+//! couplings; every coupling delays the message, so the master starts the
+//! announcement only after a lead time and each car subtracts the
+//! transmission time of the couplings passed so far from the remaining delay.
+//!
+//! If the master is lost while an announcement is running, e.g. because the
+//! cars were uncoupled, the cars either finish the announcement on their own
+//! or abort it and return to the normal display cycle, see [`SyncFallback`].
+
+use lotus_script::{
+    message::{send_message, Coupling, MessageTarget},
+    prelude::Message,
+};
+
+use crate::{
+    api::general::delta,
+    management::trainbus::TrainBusMaster,
+    messages::ibis_messages::{AnnouncementSync, DisplayPage},
+};
+
+/// Behaviour of the cars when the master is lost during an announcement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncFallback {
+    /// Finish the running announcement, waiting triggers still start
+    #[default]
+    Finish,
+    /// Abort the running announcement and drop waiting triggers
+    Abort,
+}
+
+/// Event to be carried out by the announcement module and the displays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyncEvent {
+    /// Start the announcement and show the page for its duration
+    Start {
+        /// Number of the announcement
+        announcement: u32,
+        /// Duration of the announcement in seconds
+        duration: f32,
+        /// Page of the interior displays
+        page: Option<DisplayPage>,
+    },
+    /// The announcement has ended
+    End,
+    /// Stop the announcement at once and return to the normal display cycle
+    Abort,
+}
+
+pub struct AnnouncementSynchronizerBuilder {
+    lead_time: f32,
+    hop_latency: f32,
+    fallback: SyncFallback,
+}
+
+impl AnnouncementSynchronizerBuilder {
+    /// Time in seconds the master waits before starting, so the trigger can
+    /// reach the last car in time.
+    pub fn lead_time(mut self, lead_time: f32) -> Self {
+        self.lead_time = lead_time;
+        self
+    }
+
+    /// Transmission time in seconds of one coupling.
+    pub fn hop_latency(mut self, hop_latency: f32) -> Self {
+        self.hop_latency = hop_latency;
+        self
+    }
+
+    /// Behaviour when the master is lost during an announcement.
+    pub fn fallback(mut self, fallback: SyncFallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn build(self) -> AnnouncementSynchronizer {
+        AnnouncementSynchronizer {
+            lead_time: self.lead_time,
+            hop_latency: self.hop_latency,
+            fallback: self.fallback,
+            events: Vec::new(),
+            id: 0,
+            id_last: None,
+            pending: None,
+            remaining: 0.0,
+            is_master: false,
+            playing: None,
+            hops: 0,
+            master_present: false,
+        }
+    }
+}
+
+/// Trigger of announcements and display pages in all coupled cars.
+///
+/// # Example
+///
+/// ```rust
+/// let mut sync = AnnouncementSynchronizer::builder()
+///     .lead_time(0.3)
+///     .hop_latency(0.05)
+///     .fallback(SyncFallback::Finish)
+///     .build();
+///
+/// // In on_message:
+/// sync.on_message(msg.clone());
+///
+/// // In the main loop:
+/// if next_stop_changed {
+///     sync.trigger(12, 4.5, Some(DisplayPage::NextStop));
+/// }
+/// sync.tick(ibis.is_master);
+/// for event in sync.take_events() {
+///     match event {
+///         SyncEvent::Start { announcement, duration, page } => {
+///             announcements.play(announcement);
+///             if let Some(page) = page {
+///                 display.show_page(page, duration);
+///             }
+///         }
+///         SyncEvent::Abort => {
+///             announcements.stop();
+///             display.show_page(DisplayPage::NextStop, 0.0);
+///         }
+///         SyncEvent::End => {}
+///     }
+/// }
+/// ```
+pub struct AnnouncementSynchronizer {
+    lead_time: f32,
+    hop_latency: f32,
+    fallback: SyncFallback,
+
+    events: Vec<SyncEvent>,
+    id: u32,
+    id_last: Option<u32>,
+    pending: Option<AnnouncementSync>,
+    remaining: f32,
+    is_master: bool,
+
+    /// Number of the announcement currently running
+    pub playing: Option<u32>,
+    /// Couplings between this car and the master at the last trigger
+    pub hops: u32,
+    /// Whether a master is active in the train
+    pub master_present: bool,
+}
+
+impl AnnouncementSynchronizer {
+    /// Creates a new announcement synchronizer builder.
+    pub fn builder() -> AnnouncementSynchronizerBuilder {
+        AnnouncementSynchronizerBuilder {
+            lead_time: 0.3,
+            hop_latency: 0.05,
+            fallback: SyncFallback::Finish,
+        }
+    }
+
+    /// Receives and passes on the triggers of the master and watches the
+    /// master.
+    pub fn on_message(&mut self, msg: Message) {
+        if let Some(side) = msg.source().coupling {
+            msg.handle::<AnnouncementSync>(|m| {
+                if !self.is_master && self.id_last != Some(m.id) {
+                    self.id_last = Some(m.id);
+                    self.hops = m.hops;
+                    self.pending = Some(m);
+
+                    // Weiter zur anderen Kupplung, abzüglich der Laufzeit über diese Kupplung
+                    let next = match side {
+                        Coupling::Front => Coupling::Rear,
+                        Coupling::Rear => Coupling::Front,
+                    };
+                    self.forward(m, next);
+                }
+                Ok(())
+            })
+            .expect("AnnouncementSync: message handle failed");
+        }
+
+        msg.handle::<TrainBusMaster>(|m| {
+            self.master_present = m.value;
+            Ok(())
+        })
+        .expect("TrainBusMaster: message handle failed");
+    }
+
+    /// Starts an announcement in all cars, only possible on the master.
+    ///
+    /// # Arguments
+    ///
+    /// * `announcement` - Number of the announcement
+    /// * `duration` - Duration of the announcement in seconds
+    /// * `page` - Page the interior displays show during the announcement
+    pub fn trigger(&mut self, announcement: u32, duration: f32, page: Option<DisplayPage>) {
+        if !self.is_master {
+            return;
+        }
+
+        self.id = self.id.wrapping_add(1);
+        let sync = AnnouncementSync {
+            id: self.id,
+            announcement,
+            duration,
+            page,
+            delay: self.lead_time,
+            hops: 0,
+        };
+
+        self.hops = 0;
+        self.pending = Some(sync);
+        self.forward(sync, Coupling::Front);
+        self.forward(sync, Coupling::Rear);
+    }
+
+    /// Returns the events since the last call.
+    pub fn take_events(&mut self) -> Vec<SyncEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Starts and ends the announcements and handles the loss of the master.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_master` - Whether the IBIS of this car is the acting master
+    pub fn tick(&mut self, is_master: bool) {
+        self.is_master = is_master;
+
+        let master_lost = !is_master && !self.master_present;
+        if master_lost {
+            // Ein neuer Master zählt seine Nummern von vorn
+            self.id_last = None;
+        }
+        if master_lost && self.fallback == SyncFallback::Abort {
+            self.pending = None;
+            if self.playing.take().is_some() {
+                self.events.push(SyncEvent::Abort);
+            }
+        }
+
+        if self.playing.is_some() {
+            self.remaining -= delta();
+            if self.remaining <= 0.0 {
+                self.playing = None;
+                self.events.push(SyncEvent::End);
+            }
+        }
+
+        if let Some(sync) = &mut self.pending {
+            sync.delay -= delta();
+            if sync.delay <= 0.0 {
+                // Eine laufende Ansage wird von der neuen abgelöst
+                self.playing = Some(sync.announcement);
+                self.remaining = sync.duration + sync.delay;
+                self.events.push(SyncEvent::Start {
+                    announcement: sync.announcement,
+                    duration: sync.duration,
+                    page: sync.page,
+                });
+                self.pending = None;
+            }
+        }
+    }
+
+    fn forward(&self, sync: AnnouncementSync, side: Coupling) {
+        send_message(
+            &AnnouncementSync {
+                delay: sync.delay - self.hop_latency,
+                hops: sync.hops + 1,
+                ..sync
+            },
+            [MessageTarget::AcrossCoupling {
+                coupling: side,
+                cascade: false,
+            }],
+        );
+    }
+}
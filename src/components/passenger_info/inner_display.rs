@@ -0,0 +1,246 @@
+//! Interior passenger information display.
+//!
+//! The [`InnerDisplay`] is registered on the TrainBus as
+//! [`PeripheryKind::DisplayInner`] and shows the journey data received from
+//! the IBIS master with the [`PassengerInfo`] message. It cycles through the
+//! next stop, the connections and the free text; empty pages are skipped.
+//! Each row of the display is a text variable. Texts longer than the row
+//! scroll through the row.
+
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{general::delta, variable::set_var},
+    management::trainbus::{PeripheryKind, TrainBusPeriferie},
+    messages::ibis_messages::PassengerInfo,
+};
+
+pub use crate::messages::ibis_messages::DisplayPage;
+
+/// Gap in characters between the end and the restart of a scrolling text
+const SCROLL_GAP: usize = 4;
+
+pub struct InnerDisplayBuilder {
+    slot_index: i32,
+
+    row_vars: Vec<String>,
+    width: usize,
+
+    page_times: (f32, f32, f32),
+    scroll_speed: f32,
+}
+
+impl InnerDisplayBuilder {
+    /// Adds a row of the display, the row text is written into the variable.
+    pub fn row(mut self, var_name: impl Into<String>) -> Self {
+        self.row_vars.push(var_name.into());
+        self
+    }
+
+    /// Number of characters per row, longer texts scroll.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    /// Time in seconds each page is shown.
+    pub fn page_times(mut self, next_stop: f32, connections: f32, free_text: f32) -> Self {
+        self.page_times = (next_stop, connections, free_text);
+        self
+    }
+
+    /// Scroll speed of long texts in characters per second.
+    pub fn scroll_speed(mut self, scroll_speed: f32) -> Self {
+        self.scroll_speed = scroll_speed;
+        self
+    }
+
+    pub fn build(self) -> InnerDisplay {
+        InnerDisplay {
+            trainbus: TrainBusPeriferie::new(PeripheryKind::DisplayInner, self.slot_index),
+            row_vars: self.row_vars,
+            width: self.width,
+            page_times: self.page_times,
+            scroll_speed: self.scroll_speed,
+            page_timer: 0.0,
+            page_hold: 0.0,
+            scroll_timer: 0.0,
+            rows_last: Vec::new(),
+            info: PassengerInfo::default(),
+            page: DisplayPage::NextStop,
+        }
+    }
+}
+
+/// Interior display cycling next stop, connections and free text.
+///
+/// # Example
+///
+/// ```rust
+/// let mut display = InnerDisplay::builder(20)
+///     .row("Display_Inner_Row1")
+///     .row("Display_Inner_Row2")
+///     .width(24)
+///     .page_times(8.0, 5.0, 6.0)
+///     .build();
+///
+/// // In on_message:
+/// display.on_message(msg);
+///
+/// // In the main loop:
+/// display.tick(voltage > 0.5);
+/// ```
+pub struct InnerDisplay {
+    trainbus: TrainBusPeriferie,
+
+    row_vars: Vec<String>,
+    width: usize,
+
+    page_times: (f32, f32, f32),
+    scroll_speed: f32,
+
+    page_timer: f32,
+    page_hold: f32,
+    scroll_timer: f32,
+    rows_last: Vec<String>,
+
+    /// Journey data last received from the IBIS master
+    pub info: PassengerInfo,
+    /// Page currently shown
+    pub page: DisplayPage,
+}
+
+impl InnerDisplay {
+    /// Creates a new interior display builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - Slot index with which the display registers on the TrainBus
+    pub fn builder(slot_index: i32) -> InnerDisplayBuilder {
+        InnerDisplayBuilder {
+            slot_index,
+            row_vars: Vec::new(),
+            width: 20,
+            page_times: (8.0, 5.0, 6.0),
+            scroll_speed: 4.0,
+        }
+    }
+
+    /// Receives the journey data of the IBIS master.
+    pub fn on_message(&mut self, msg: Message) {
+        self.trainbus.on_message(msg.clone());
+
+        msg.handle::<PassengerInfo>(|m| {
+            self.info = m;
+            Ok(())
+        })
+        .expect("PassengerInfo: message handle failed");
+    }
+
+    /// Shows a page right away, e.g. during an announcement.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - Page to show
+    /// * `hold` - Time in seconds the page is shown at least
+    pub fn show_page(&mut self, page: DisplayPage, hold: f32) {
+        self.page = page;
+        self.page_hold = hold;
+        self.page_timer = 0.0;
+        self.scroll_timer = 0.0;
+    }
+
+    /// Updates the page cycle and writes the rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the display is supplied, the rows stay empty without
+    pub fn tick(&mut self, power: bool) {
+        if !power {
+            self.page = DisplayPage::NextStop;
+            self.page_timer = 0.0;
+            self.page_hold = 0.0;
+            self.scroll_timer = 0.0;
+            self.write_rows(Vec::new());
+            return;
+        }
+
+        self.trainbus.tick();
+
+        self.page_timer += delta();
+        self.scroll_timer += delta();
+
+        let page_time = match self.page {
+            DisplayPage::NextStop => self.page_times.0,
+            DisplayPage::Connections => self.page_times.1,
+            DisplayPage::FreeText => self.page_times.2,
+        };
+
+        if self.page_timer >= page_time.max(self.page_hold) || !self.has_content(self.page) {
+            self.page = self.next_page();
+            self.page_timer = 0.0;
+            self.page_hold = 0.0;
+            self.scroll_timer = 0.0;
+        }
+
+        let texts = match self.page {
+            DisplayPage::NextStop => vec![
+                format!("{} {}", self.info.line, self.info.destination),
+                self.info.next_stop.clone(),
+            ],
+            DisplayPage::Connections => self.info.connections.clone(),
+            DisplayPage::FreeText => self.info.free_text.iter().cloned().collect(),
+        };
+
+        let rows = texts.iter().map(|text| self.render(text)).collect();
+        self.write_rows(rows);
+    }
+
+    fn has_content(&self, page: DisplayPage) -> bool {
+        match page {
+            DisplayPage::NextStop => true,
+            DisplayPage::Connections => !self.info.connections.is_empty(),
+            DisplayPage::FreeText => self.info.free_text.is_some(),
+        }
+    }
+
+    fn next_page(&self) -> DisplayPage {
+        let mut page = self.page;
+        loop {
+            page = match page {
+                DisplayPage::NextStop => DisplayPage::Connections,
+                DisplayPage::Connections => DisplayPage::FreeText,
+                DisplayPage::FreeText => DisplayPage::NextStop,
+            };
+            if self.has_content(page) {
+                return page;
+            }
+        }
+    }
+
+    /// Cuts the text to the row width, long texts scroll as a marquee
+    fn render(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        if chars.len() <= self.width {
+            return text.to_string();
+        }
+
+        let cycle = chars.len() + SCROLL_GAP;
+        let offset = (self.scroll_timer * self.scroll_speed) as usize % cycle;
+
+        (0..self.width)
+            .map(|i| chars.get((offset + i) % cycle).copied().unwrap_or(' '))
+            .collect()
+    }
+
+    fn write_rows(&mut self, rows: Vec<String>) {
+        for (idx, var) in self.row_vars.iter().enumerate() {
+            let row = rows.get(idx).cloned().unwrap_or_default();
+            if self.rows_last.get(idx) != Some(&row) {
+                set_var(var, row);
+            }
+        }
+        self.rows_last = rows;
+        self.rows_last.resize(self.row_vars.len(), String::new());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod announcement_sync;
+pub mod inner_display;
+pub mod roller_blind;
+pub mod stop_request;
+pub mod ticket_machine;
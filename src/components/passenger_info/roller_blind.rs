@@ -0,0 +1,260 @@
+//! Mechanical roller blind destination display ("Rollband").
+//!
+//! The [`RollerBlindDisplay`] winds a film between two rollers. Each
+//! destination is printed at an indexed film position; when the IBIS master
+//! sends a new [`DestinationCode`], the motor winds the film to the position
+//! assigned to the code. The position is counted from the motor travel, so a
+//! slipping film drifts away over time. The drift is removed by a calibration
+//! run to the reference mark at the start of the film, which the device does
+//! after every power-up and whenever the film was moved by hand.
+//!
+//! Without supply the film can be wound with the manual crank. The film is
+//! shown with a [`Rollerblind`] and lit by a backlight.
+
+use std::collections::HashMap;
+
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{general::delta, light::Light, sound::Sound},
+    elements::tech::{cranc::Crank, rollerblind::Rollerblind},
+    management::trainbus::{PeripheryKind, TrainBusPeriferie},
+    messages::ibis_messages::DestinationCode,
+};
+
+pub struct RollerBlindDisplayBuilder {
+    blind: Rollerblind,
+    slot_index: i32,
+
+    positions: HashMap<u32, f32>,
+    film_length: f32,
+    motor_speed: f32,
+    slip: f32,
+    init_pos: f32,
+
+    crank: Option<(Crank, f32)>,
+    backlight: Light,
+    snd_motor: Sound,
+}
+
+impl RollerBlindDisplayBuilder {
+    /// Assigns a film position to a destination code.
+    pub fn destination(mut self, code: u32, position: f32) -> Self {
+        self.positions.insert(code, position);
+        self.film_length = self.film_length.max(position);
+        self
+    }
+
+    /// Winding speed of the motor in film positions per second.
+    pub fn motor_speed(mut self, speed: f32) -> Self {
+        self.motor_speed = speed;
+        self
+    }
+
+    /// Share of the motor travel lost by the slipping film from 0.0 to 1.0.
+    pub fn slip(mut self, slip: f32) -> Self {
+        self.slip = slip.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Manual crank and the film positions wound per crank unit.
+    pub fn crank(mut self, crank: Crank, positions_per_unit: f32) -> Self {
+        self.crank = Some((crank, positions_per_unit));
+        self
+    }
+
+    /// Backlight of the film.
+    pub fn backlight(mut self, name: impl Into<String>) -> Self {
+        self.backlight = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Sound of the winding motor.
+    pub fn snd_motor(mut self, name: impl Into<String>) -> Self {
+        self.snd_motor = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Film position at the start.
+    pub fn init_position(mut self, position: f32) -> Self {
+        self.init_pos = position;
+        self
+    }
+
+    pub fn build(self) -> RollerBlindDisplay {
+        let crank_pos_last = self.crank.as_ref().map_or(0.0, |(crank, _)| crank.pos);
+
+        RollerBlindDisplay {
+            blind: self.blind,
+            trainbus: TrainBusPeriferie::new(PeripheryKind::DisplayOuter, self.slot_index),
+            positions: self.positions,
+            film_length: self.film_length,
+            motor_speed: self.motor_speed,
+            slip: self.slip,
+            crank: self.crank,
+            crank_pos_last,
+            power_last: false,
+            backlight: self.backlight,
+            snd_motor: self.snd_motor,
+            counted: self.init_pos,
+            code: 0,
+            film_pos: self.init_pos,
+            target: None,
+            calibrated: false,
+        }
+    }
+}
+
+/// Roller blind destination display with motor, calibration and manual crank.
+///
+/// # Example
+///
+/// ```rust
+/// let mut blind = RollerBlindDisplay::builder(
+///     Rollerblind::new("Rollband_Oben", "Rollband_Unten", 1000, 100),
+///     30,
+/// )
+/// .destination(0, 0.0)
+/// .destination(4, 3.0)
+/// .destination(14, 7.0)
+/// .motor_speed(2.0)
+/// .slip(0.005)
+/// .crank(Crank::builder("A_Rollband_Kurbel", None).event_plus("Kurbel").build(), 1.0)
+/// .backlight("L_Rollband")
+/// .build();
+///
+/// // In on_message:
+/// blind.on_message(msg);
+///
+/// // In the main loop:
+/// blind.tick(voltage > 0.5, lights_on);
+/// ```
+pub struct RollerBlindDisplay {
+    blind: Rollerblind,
+    trainbus: TrainBusPeriferie,
+
+    positions: HashMap<u32, f32>,
+    film_length: f32,
+    motor_speed: f32,
+    slip: f32,
+
+    crank: Option<(Crank, f32)>,
+    crank_pos_last: f32,
+    power_last: bool,
+
+    backlight: Light,
+    snd_motor: Sound,
+
+    counted: f32,
+
+    /// Destination code last received from the IBIS master
+    pub code: u32,
+    /// Actual film position
+    pub film_pos: f32,
+    /// Film position the motor winds to
+    pub target: Option<f32>,
+    /// Whether the counted position matches the film
+    pub calibrated: bool,
+}
+
+impl RollerBlindDisplay {
+    /// Creates a new roller blind display builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `blind` - Film textures of the display
+    /// * `slot_index` - Slot index with which the display registers on the TrainBus
+    pub fn builder(blind: Rollerblind, slot_index: i32) -> RollerBlindDisplayBuilder {
+        RollerBlindDisplayBuilder {
+            blind,
+            slot_index,
+            positions: HashMap::new(),
+            film_length: 0.0,
+            motor_speed: 1.5,
+            slip: 0.0,
+            init_pos: 0.0,
+            crank: None,
+            backlight: Light::new(None),
+            snd_motor: Sound::new_simple(None),
+        }
+    }
+
+    /// Receives the destination code of the IBIS master.
+    pub fn on_message(&mut self, msg: Message) {
+        self.trainbus.on_message(msg.clone());
+
+        msg.handle::<DestinationCode>(|m| {
+            self.set_code(m.code);
+            Ok(())
+        })
+        .expect("DestinationCode: message handle failed");
+    }
+
+    /// Selects the destination, unknown codes keep the film where it is.
+    pub fn set_code(&mut self, code: u32) {
+        self.code = code;
+        if let Some(position) = self.positions.get(&code) {
+            self.target = Some(*position);
+        }
+    }
+
+    /// Starts a calibration run to the reference mark.
+    pub fn calibrate(&mut self) {
+        self.calibrated = false;
+    }
+
+    /// Updates the motor, the crank and the backlight.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the display is supplied
+    /// * `lights` - Whether the backlight is switched on
+    pub fn tick(&mut self, power: bool, lights: bool) {
+        if let Some((crank, factor)) = &mut self.crank {
+            crank.tick();
+            let turned = (crank.pos - self.crank_pos_last) * *factor;
+            self.crank_pos_last = crank.pos;
+
+            // Von Hand verstellter Film muss neu kalibriert werden
+            if turned != 0.0 && !power {
+                self.film_pos += turned;
+                self.calibrated = false;
+            }
+        }
+
+        // Nach dem Einschalten fährt das Gerät zuerst die Referenzmarke an
+        if power && !self.power_last {
+            self.calibrated = false;
+        }
+        self.power_last = power;
+
+        let mut moving = false;
+        if power {
+            self.trainbus.tick();
+
+            let step = self.motor_speed * delta();
+            if !self.calibrated {
+                // Referenzfahrt zum Filmanfang
+                self.film_pos -= step;
+                moving = true;
+                if self.film_pos <= 0.0 {
+                    self.film_pos = 0.0;
+                    self.counted = 0.0;
+                    self.calibrated = true;
+                }
+            } else if let Some(target) = self.target {
+                let travel = (target - self.counted).clamp(-step, step);
+                self.counted += travel;
+                self.film_pos += travel * (1.0 - self.slip);
+                moving = travel != 0.0;
+            }
+        }
+
+        self.film_pos = self.film_pos.clamp(0.0, self.film_length);
+        self.snd_motor.start_stop(moving);
+
+        self.backlight
+            .set_brightness((power && lights) as u8 as f32);
+        self.blind.tick(self.film_pos);
+    }
+}
@@ -0,0 +1,160 @@
+//! Stop request system ("Haltewunsch").
+//!
+//! The [`StopRequestSystem`] latches a stop request from the passenger
+//! buttons of the car until the doors are released at the next stop. The
+//! request is shared with the whole consist over the `CouplerStopRequest`
+//! coupling line, so the "Wagen hält" signs in every car and the lamp on the
+//! dashboard light up, no matter in which car the button was pressed. Since
+//! the door release reaches every car, each car resets its own latch.
+
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{light::Light, sound::Sound},
+    elements::tech::buttons::PushButton,
+    management::enums::door_enums::DoorTarget,
+    messages::{
+        coupling_handler::UniversalCouplingLine, gt6n_coupling_messages::CouplerStopRequest,
+    },
+};
+
+pub struct StopRequestSystemBuilder {
+    buttons: Vec<PushButton>,
+    signs: Vec<Light>,
+    lm_dashboard: Light,
+    snd_chime: Sound,
+}
+
+impl StopRequestSystemBuilder {
+    /// Adds a passenger stop request button.
+    pub fn button(mut self, button: PushButton) -> Self {
+        self.buttons.push(button);
+        self
+    }
+
+    /// Adds a "Wagen hält" sign in the passenger compartment.
+    pub fn sign(mut self, name: impl Into<String>) -> Self {
+        self.signs.push(Light::new(Some(&name.into())));
+        self
+    }
+
+    /// Stop request lamp on the dashboard.
+    pub fn lm_dashboard(mut self, name: impl Into<String>) -> Self {
+        self.lm_dashboard = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Chime played once when the consist receives a new request.
+    pub fn snd_chime(mut self, name: impl Into<String>) -> Self {
+        self.snd_chime = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> StopRequestSystem {
+        StopRequestSystem {
+            buttons: self.buttons,
+            signs: self.signs,
+            lm_dashboard: self.lm_dashboard,
+            snd_chime: self.snd_chime,
+            request_line: UniversalCouplingLine::new(CouplerStopRequest, (true, true)),
+            pending: false,
+            latched: false,
+            requested: false,
+        }
+    }
+}
+
+/// Latched stop request with signs, dashboard lamp and chime over the consist.
+///
+/// # Example
+///
+/// ```rust
+/// let mut stop_request = StopRequestSystem::builder()
+///     .button(PushButton::builder("A_Halt_1", "Halt_1", None).build())
+///     .button(PushButton::builder("A_Halt_2", "Halt_2", None).build())
+///     .sign("L_Wagen_haelt_1")
+///     .sign("L_Wagen_haelt_2")
+///     .lm_dashboard("L_Haltewunsch")
+///     .snd_chime("Snd_Haltewunsch")
+///     .build();
+///
+/// // In on_message:
+/// stop_request.on_message(msg);
+///
+/// // In the main loop:
+/// stop_request.tick(voltage > 0.7, door_target);
+/// ```
+pub struct StopRequestSystem {
+    buttons: Vec<PushButton>,
+    signs: Vec<Light>,
+    lm_dashboard: Light,
+    snd_chime: Sound,
+
+    request_line: UniversalCouplingLine<bool, CouplerStopRequest>,
+    pending: bool,
+
+    /// Whether a request of this car is latched
+    pub latched: bool,
+    /// Whether a stop is requested anywhere in the consist
+    pub requested: bool,
+}
+
+impl StopRequestSystem {
+    /// Creates a new stop request system builder.
+    pub fn builder() -> StopRequestSystemBuilder {
+        StopRequestSystemBuilder {
+            buttons: Vec::new(),
+            signs: Vec::new(),
+            lm_dashboard: Light::new(None),
+            snd_chime: Sound::new_simple(None),
+        }
+    }
+
+    /// Passes messages to the `CouplerStopRequest` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.request_line.on_message(msg);
+    }
+
+    /// Requests a stop, e.g. by an AI passenger.
+    pub fn request(&mut self) {
+        self.pending = true;
+    }
+
+    /// Updates the buttons, the latch and the indicators.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the passenger information is supplied
+    /// * `door_target` - Door command of the driver, a release resets the request
+    pub fn tick(&mut self, power: bool, door_target: DoorTarget) {
+        for button in &mut self.buttons {
+            button.tick();
+            if button.is_just_pressed() {
+                self.pending = true;
+            }
+        }
+
+        let released = door_target >= DoorTarget::Release;
+
+        // Bei freigegebenen Türen werden keine neuen Wünsche angenommen
+        if !power || released {
+            self.latched = false;
+        } else if self.pending {
+            self.latched = true;
+        }
+        self.pending = false;
+
+        self.request_line.update_local(self.latched);
+        let requested = power && self.request_line.get_value();
+        if requested && !self.requested {
+            self.snd_chime.start();
+        }
+        self.requested = requested;
+
+        let brightness = requested as u8 as f32;
+        for sign in &mut self.signs {
+            sign.set_brightness(brightness);
+        }
+        self.lm_dashboard.set_brightness(brightness);
+    }
+}
@@ -0,0 +1,524 @@
+//! Ticket machine in the passenger compartment ("Fahrscheinautomat", VVG).
+//!
+//! The [`TicketMachine`] is registered on the TrainBus as
+//! [`PeripheryKind::TicketMachine`]. The passenger selects a [`Tariff`] and
+//! pays with coins or by card. Coins are added up until the price is reached,
+//! the overpaid amount is returned as change in the accepted coins. Card
+//! payments are authorized after a short delay and may be declined.
+//!
+//! The ticket is printed by the printer of the car: the machine sends a
+//! [`PrintJob`] and waits for the [`PrintResult`]. If the ticket cannot be
+//! printed, the cash paid is refunded and the machine goes out of service
+//! until it is reset. The state of the machine is reported to the TrainBus
+//! with each heartbeat.
+
+use lotus_script::{
+    message::{send_message, MessageTarget},
+    prelude::Message,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{general::delta, random::gen_f32, sound::Sound, variable::set_var},
+    elements::tech::buttons::PushButton,
+    management::trainbus::{PeripheryFault, PeripheryKind, TrainBusPeriferie},
+    messages::ibis_messages::{PrintJob, PrintResult},
+};
+
+/// Ticket offered by the machine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tariff {
+    /// Name printed on the ticket, e.g. "Einzelfahrt"
+    pub name: String,
+    /// Price in cents
+    pub price: u32,
+}
+
+/// State of the ticket machine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TicketMachineState {
+    /// Waiting for a tariff to be selected
+    #[default]
+    Idle,
+    /// Tariff selected, waiting for the payment
+    Payment,
+    /// Card payment being authorized
+    Authorizing,
+    /// Ticket being printed
+    Printing,
+    /// Out of service until reset
+    OutOfService,
+}
+
+/// Event of the ticket machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TicketEvent {
+    /// Ticket of the tariff issued
+    TicketIssued(usize),
+    /// Change returned in coins
+    Change(Vec<u32>),
+    /// Cash refunded in coins after a cancellation or a fault
+    Refund(Vec<u32>),
+    /// Coin not accepted and returned
+    CoinRejected(u32),
+    /// Card payment declined
+    CardDeclined,
+}
+
+pub struct TicketMachineBuilder {
+    slot_index: i32,
+    tariffs: Vec<Tariff>,
+    coins: Vec<u32>,
+
+    tariff_buttons: Vec<(usize, PushButton)>,
+    cancel_button: Option<PushButton>,
+
+    authorize_time: f32,
+    decline_probability: f32,
+    print_timeout: f32,
+    display_var: Option<String>,
+
+    snd_coin: Sound,
+    snd_change: Sound,
+}
+
+impl TicketMachineBuilder {
+    /// Accepted coins in cents, also used for the change.
+    pub fn coins(mut self, coins: &[u32]) -> Self {
+        self.coins = coins.to_vec();
+        self.coins.sort_unstable_by(|a, b| b.cmp(a));
+        self
+    }
+
+    /// Button selecting a tariff.
+    pub fn tariff_button(mut self, tariff: usize, button: PushButton) -> Self {
+        self.tariff_buttons.push((tariff, button));
+        self
+    }
+
+    /// Button cancelling the purchase.
+    pub fn cancel_button(mut self, button: PushButton) -> Self {
+        self.cancel_button = Some(button);
+        self
+    }
+
+    /// Card payment.
+    ///
+    /// # Arguments
+    ///
+    /// * `authorize_time` - Time in seconds until the payment is authorized
+    /// * `decline_probability` - Probability from 0.0 to 1.0 that the card is declined
+    pub fn cashless(mut self, authorize_time: f32, decline_probability: f32) -> Self {
+        self.authorize_time = authorize_time;
+        self.decline_probability = decline_probability;
+        self
+    }
+
+    /// Time in seconds after which a ticket without print result is
+    /// considered lost.
+    pub fn print_timeout(mut self, timeout: f32) -> Self {
+        self.print_timeout = timeout;
+        self
+    }
+
+    /// String variable showing the text of the machine display.
+    pub fn display_var(mut self, name: impl Into<String>) -> Self {
+        self.display_var = Some(name.into());
+        self
+    }
+
+    /// Sound of an inserted coin.
+    pub fn snd_coin(mut self, name: impl Into<String>) -> Self {
+        self.snd_coin = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound of coins dropping into the return tray.
+    pub fn snd_change(mut self, name: impl Into<String>) -> Self {
+        self.snd_change = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> TicketMachine {
+        TicketMachine {
+            trainbus: TrainBusPeriferie::new(PeripheryKind::TicketMachine, self.slot_index),
+            tariffs: self.tariffs,
+            coins: self.coins,
+            tariff_buttons: self.tariff_buttons,
+            cancel_button: self.cancel_button,
+            authorize_time: self.authorize_time,
+            decline_probability: self.decline_probability,
+            print_timeout: self.print_timeout,
+            display_var: self.display_var,
+            snd_coin: self.snd_coin,
+            snd_change: self.snd_change,
+            events: Vec::new(),
+            timer: 0.0,
+            // Auftragsnummern nach Slot getrennt, damit Ergebnisse anderer Auftraggeber nicht passen
+            job_id: (self.slot_index as u32) << 16,
+            cash: false,
+            display_last: None,
+            state: TicketMachineState::Idle,
+            selected: None,
+            paid: 0,
+        }
+    }
+}
+
+/// Ticket machine with tariff selection, coin and card payment and printing.
+///
+/// # Example
+///
+/// ```rust
+/// let mut machine = TicketMachine::builder(
+///     vec![
+///         Tariff { name: "Einzelfahrt".into(), price: 290 },
+///         Tariff { name: "Kurzstrecke".into(), price: 180 },
+///     ],
+///     50,
+/// )
+/// .coins(&[10, 20, 50, 100, 200])
+/// .tariff_button(0, PushButton::builder("VVG_T1", "VVG_T1", None).build())
+/// .tariff_button(1, PushButton::builder("VVG_T2", "VVG_T2", None).build())
+/// .cancel_button(PushButton::builder("VVG_Abbruch", "VVG_Abbruch", None).build())
+/// .display_var("VVG_Display")
+/// .snd_coin("Snd_VVG_Muenze")
+/// .build();
+///
+/// // In on_message:
+/// machine.on_message(msg);
+///
+/// // In the main loop:
+/// machine.tick(voltage > 0.5);
+/// for event in machine.take_events() {
+///     // ...
+/// }
+/// ```
+pub struct TicketMachine {
+    trainbus: TrainBusPeriferie,
+    tariffs: Vec<Tariff>,
+    coins: Vec<u32>,
+
+    tariff_buttons: Vec<(usize, PushButton)>,
+    cancel_button: Option<PushButton>,
+
+    authorize_time: f32,
+    decline_probability: f32,
+    print_timeout: f32,
+    display_var: Option<String>,
+
+    snd_coin: Sound,
+    snd_change: Sound,
+
+    events: Vec<TicketEvent>,
+    timer: f32,
+    job_id: u32,
+    cash: bool,
+    display_last: Option<String>,
+
+    /// Current state
+    pub state: TicketMachineState,
+    /// Selected tariff
+    pub selected: Option<usize>,
+    /// Amount paid in cash for the selected tariff in cents
+    pub paid: u32,
+}
+
+impl TicketMachine {
+    /// Creates a new ticket machine builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `tariffs` - Tickets offered by the machine
+    /// * `slot_index` - Slot index with which the machine registers on the TrainBus
+    pub fn builder(tariffs: Vec<Tariff>, slot_index: i32) -> TicketMachineBuilder {
+        TicketMachineBuilder {
+            slot_index,
+            tariffs,
+            coins: vec![200, 100, 50, 20, 10],
+            tariff_buttons: Vec::new(),
+            cancel_button: None,
+            authorize_time: 3.0,
+            decline_probability: 0.05,
+            print_timeout: 10.0,
+            display_var: None,
+            snd_coin: Sound::new_simple(None),
+            snd_change: Sound::new_simple(None),
+        }
+    }
+
+    /// Creates a ticket machine builder from a JSON array of tariffs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is not a valid list of tariffs.
+    pub fn from_json(
+        json: &str,
+        slot_index: i32,
+    ) -> Result<TicketMachineBuilder, serde_json::Error> {
+        Ok(Self::builder(serde_json::from_str(json)?, slot_index))
+    }
+
+    /// Receives the result of the print job.
+    pub fn on_message(&mut self, msg: Message) {
+        self.trainbus.on_message(msg.clone());
+
+        msg.handle::<PrintResult>(|m| {
+            if self.state == TicketMachineState::Printing && m.id == self.job_id {
+                if m.printed {
+                    self.issue();
+                } else {
+                    self.fail();
+                }
+            }
+            Ok(())
+        })
+        .expect("PrintResult: message handle failed");
+    }
+
+    /// Selects a tariff, only possible while no payment is running.
+    pub fn select(&mut self, tariff: usize) {
+        if tariff < self.tariffs.len()
+            && matches!(
+                self.state,
+                TicketMachineState::Idle | TicketMachineState::Payment
+            )
+            && self.paid == 0
+        {
+            self.selected = Some(tariff);
+            self.state = TicketMachineState::Payment;
+        }
+    }
+
+    /// Inserts a coin of the given value in cents. Coins which are not
+    /// accepted or inserted without a selected tariff are returned.
+    pub fn insert_coin(&mut self, value: u32) {
+        if self.state != TicketMachineState::Payment || !self.coins.contains(&value) {
+            self.events.push(TicketEvent::CoinRejected(value));
+            return;
+        }
+
+        self.snd_coin.start();
+        self.paid += value;
+        self.cash = true;
+
+        if self.paid >= self.price() {
+            self.print();
+        }
+    }
+
+    /// Starts a card payment for the selected tariff.
+    pub fn pay_cashless(&mut self) {
+        if self.state == TicketMachineState::Payment && self.paid == 0 {
+            self.cash = false;
+            self.timer = self.authorize_time;
+            self.state = TicketMachineState::Authorizing;
+        }
+    }
+
+    /// Cancels the purchase and refunds the cash paid.
+    pub fn cancel(&mut self) {
+        if matches!(
+            self.state,
+            TicketMachineState::Payment | TicketMachineState::Authorizing
+        ) {
+            self.refund();
+            self.state = TicketMachineState::Idle;
+        }
+    }
+
+    /// Puts the machine out of service, e.g. from a scenario.
+    pub fn set_out_of_service(&mut self) {
+        self.refund();
+        self.state = TicketMachineState::OutOfService;
+    }
+
+    /// Puts the machine back into service after a fault.
+    pub fn reset(&mut self) {
+        if self.state == TicketMachineState::OutOfService {
+            self.state = TicketMachineState::Idle;
+        }
+    }
+
+    /// Returns the events since the last call.
+    pub fn take_events(&mut self) -> Vec<TicketEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Updates the buttons, the payment and the display.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the machine is supplied
+    pub fn tick(&mut self, power: bool) {
+        let mut selected = None;
+        for (tariff, button) in &mut self.tariff_buttons {
+            button.tick();
+            if button.is_just_pressed() {
+                selected = Some(*tariff);
+            }
+        }
+        let cancel = match &mut self.cancel_button {
+            Some(button) => {
+                button.tick();
+                button.is_just_pressed()
+            }
+            None => false,
+        };
+
+        if !power {
+            // Ohne Versorgung wird ein laufender Kauf abgebrochen
+            if self.state != TicketMachineState::OutOfService {
+                self.refund();
+                self.state = TicketMachineState::Idle;
+            }
+            self.update_display(String::new());
+            return;
+        }
+
+        if let Some(tariff) = selected {
+            self.select(tariff);
+        }
+        if cancel {
+            self.cancel();
+        }
+
+        match self.state {
+            TicketMachineState::Authorizing => {
+                self.timer -= delta();
+                if self.timer <= 0.0 {
+                    if gen_f32(0.0..1.0) < self.decline_probability {
+                        self.events.push(TicketEvent::CardDeclined);
+                        self.state = TicketMachineState::Payment;
+                    } else {
+                        self.print();
+                    }
+                }
+            }
+            TicketMachineState::Printing => {
+                self.timer -= delta();
+                if self.timer <= 0.0 {
+                    self.fail();
+                }
+            }
+            _ => {}
+        }
+
+        self.trainbus.tick();
+        self.trainbus
+            .set_defect(if self.state == TicketMachineState::OutOfService {
+                PeripheryFault::Disrupted
+            } else {
+                PeripheryFault::Ok
+            });
+
+        let text = self.display_text();
+        self.update_display(text);
+    }
+
+    //===================================================================
+    // Internal
+    //===================================================================
+
+    fn price(&self) -> u32 {
+        self.selected.map(|i| self.tariffs[i].price).unwrap_or(0)
+    }
+
+    fn print(&mut self) {
+        let Some(tariff) = self.selected.map(|i| &self.tariffs[i]) else {
+            return;
+        };
+
+        self.job_id = self.job_id.wrapping_add(1);
+        send_message(
+            &PrintJob {
+                id: self.job_id,
+                lines: vec![tariff.name.clone(), format_price(tariff.price)],
+            },
+            [MessageTarget::Broadcast {
+                across_couplings: false,
+                include_self: true,
+            }],
+        );
+
+        self.timer = self.print_timeout;
+        self.state = TicketMachineState::Printing;
+    }
+
+    fn issue(&mut self) {
+        if let Some(tariff) = self.selected {
+            self.events.push(TicketEvent::TicketIssued(tariff));
+        }
+
+        if self.cash {
+            let change = self.change(self.paid.saturating_sub(self.price()));
+            if !change.is_empty() {
+                self.snd_change.start();
+                self.events.push(TicketEvent::Change(change));
+            }
+        }
+
+        self.paid = 0;
+        self.selected = None;
+        self.state = TicketMachineState::Idle;
+    }
+
+    fn fail(&mut self) {
+        self.refund();
+        self.state = TicketMachineState::OutOfService;
+    }
+
+    fn refund(&mut self) {
+        if self.paid > 0 {
+            let coins = self.change(self.paid);
+            self.snd_change.start();
+            self.events.push(TicketEvent::Refund(coins));
+        }
+        self.paid = 0;
+        self.selected = None;
+    }
+
+    /// Splits an amount into the accepted coins, largest first.
+    fn change(&self, mut amount: u32) -> Vec<u32> {
+        let mut coins = Vec::new();
+        for &coin in &self.coins {
+            while coin > 0 && amount >= coin {
+                coins.push(coin);
+                amount -= coin;
+            }
+        }
+        coins
+    }
+
+    fn display_text(&self) -> String {
+        match self.state {
+            TicketMachineState::Idle => "Bitte Fahrschein wählen".to_string(),
+            TicketMachineState::Payment => {
+                let name = self
+                    .selected
+                    .map(|i| self.tariffs[i].name.as_str())
+                    .unwrap_or_default();
+                format!(
+                    "{name}: noch {}",
+                    format_price(self.price().saturating_sub(self.paid))
+                )
+            }
+            TicketMachineState::Authorizing => "Karte wird geprüft".to_string(),
+            TicketMachineState::Printing => "Fahrschein wird gedruckt".to_string(),
+            TicketMachineState::OutOfService => "Außer Betrieb".to_string(),
+        }
+    }
+
+    fn update_display(&mut self, text: String) {
+        if self.display_last.as_ref() != Some(&text) {
+            if let Some(var_name) = &self.display_var {
+                set_var(var_name, text.clone());
+            }
+            self.display_last = Some(text);
+        }
+    }
+}
+
+fn format_price(cents: u32) -> String {
+    format!("{},{:02} €", cents / 100, cents % 100)
+}
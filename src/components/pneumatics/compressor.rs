@@ -0,0 +1,416 @@
+//! Air compressor and main reservoir.
+//!
+//! The [`Compressor`] fills the [`MainReservoir`] under control of a pressure
+//! governor. It heats up while running and is switched off by a thermal
+//! protection if it runs for too long. Consumers like doors, brakes and the
+//! horn draw their air from the main reservoir.
+//!
+//! All pressures are gauge pressures in bar, air quantities are given in
+//! bar·litre.
+
+use crate::{
+    api::{
+        general::delta,
+        mock::SoundOutput,
+        sound::{Sound, SoundWithRamp},
+    },
+    management::fault_manager::fault_active,
+};
+
+const AMBIENT_TEMPERATURE: f32 = 20.0;
+
+/// Rise and fall per second of the volume and pitch of the running sound
+const RUN_SOUND_ATTACK: f32 = 2.0;
+const RUN_SOUND_RELEASE: f32 = 1.0;
+
+/// Main air reservoir with safety valve.
+///
+/// # Example
+///
+/// ```rust
+/// let mut reservoir = MainReservoir::new(400.0, 10.5);
+///
+/// // Consumers draw their air:
+/// reservoir.take(2.5);
+/// let door_pressure = reservoir.pressure;
+/// ```
+#[derive(Debug)]
+pub struct MainReservoir {
+    volume: f32,
+    safety_valve_pressure: f32,
+    safety_valve_open: bool,
+
+    /// Current pressure in bar
+    pub pressure: f32,
+
+    snd_safety_valve: Sound,
+}
+
+impl MainReservoir {
+    /// Creates an empty reservoir.
+    ///
+    /// # Arguments
+    ///
+    /// * `volume` - Volume of the reservoir in litre
+    /// * `safety_valve_pressure` - Pressure in bar at which the safety valve blows off
+    #[must_use]
+    pub fn new(volume: f32, safety_valve_pressure: f32) -> Self {
+        Self {
+            volume,
+            safety_valve_pressure,
+            safety_valve_open: false,
+            pressure: 0.0,
+            snd_safety_valve: Sound::new_simple(None),
+        }
+    }
+
+    /// Sets the initial pressure, e.g. for a vehicle placed ready for service.
+    pub fn init_pressure(mut self, pressure: f32) -> Self {
+        self.pressure = pressure;
+        self
+    }
+
+    pub fn snd_safety_valve(mut self, name: impl Into<String>) -> Self {
+        self.snd_safety_valve = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Feeds the given amount of air into the reservoir.
+    pub fn feed(&mut self, air: f32) {
+        self.pressure += air.max(0.0) / self.volume;
+    }
+
+    /// Draws up to the given amount of air and returns the amount actually taken.
+    pub fn take(&mut self, air: f32) -> f32 {
+        let taken = air.max(0.0).min(self.pressure * self.volume);
+        self.pressure -= taken / self.volume;
+        taken
+    }
+
+    /// Updates the safety valve.
+    pub fn tick(&mut self) {
+        if self.pressure > self.safety_valve_pressure {
+            if !self.safety_valve_open {
+                self.snd_safety_valve.start();
+            }
+            self.safety_valve_open = true;
+        } else if self.pressure < self.safety_valve_pressure - 0.3 {
+            if self.safety_valve_open {
+                self.snd_safety_valve.stop();
+            }
+            self.safety_valve_open = false;
+        }
+
+        if self.safety_valve_open {
+            self.take(self.volume * 0.5 * delta());
+        }
+    }
+}
+
+//=========================================================================
+
+pub struct CompressorBuilder<S = Sound> {
+    cut_in_pressure: f32,
+    cut_out_pressure: f32,
+    delivery: f32,
+    max_pressure: f32,
+    heating_rate: f32,
+    cooling_rate: f32,
+    max_temperature: f32,
+    restart_temperature: f32,
+    run_up_time: f32,
+
+    fault: Option<String>,
+
+    snd_start: S,
+    snd_run: SoundWithRamp<S>,
+    snd_stop: S,
+    snd_unloader: S,
+}
+
+impl CompressorBuilder {
+    /// Sets the motor sounds. The running sound gets its volume and pitch
+    /// from the speed and the load of the compressor.
+    pub fn sounds(
+        mut self,
+        sound_start_name: impl Into<String>,
+        sound_run_name: impl Into<String>,
+        sound_run_vol_name: impl Into<String>,
+        sound_run_pitch_name: impl Into<String>,
+        sound_stop_name: impl Into<String>,
+    ) -> Self {
+        self.snd_start = Sound::new_simple(Some(&sound_start_name.into()));
+        self.snd_run = SoundWithRamp::new(
+            Some(&sound_run_name.into()),
+            Some(&sound_run_vol_name.into()),
+            Some(&sound_run_pitch_name.into()),
+        );
+        self.snd_stop = Sound::new_simple(Some(&sound_stop_name.into()));
+        self
+    }
+
+    pub fn snd_unloader(mut self, name: impl Into<String>) -> Self {
+        self.snd_unloader = Sound::new_simple(Some(&name.into()));
+        self
+    }
+}
+
+impl<S: SoundOutput> CompressorBuilder<S> {
+    /// Pressures in bar at which the governor switches the compressor on and off.
+    pub fn governor(mut self, cut_in_pressure: f32, cut_out_pressure: f32) -> Self {
+        self.cut_in_pressure = cut_in_pressure;
+        self.cut_out_pressure = cut_out_pressure;
+        self
+    }
+
+    /// Delivery in bar·litre per second against an empty reservoir and the
+    /// pressure at which the delivery drops to zero.
+    pub fn delivery(mut self, delivery: f32, max_pressure: f32) -> Self {
+        self.delivery = delivery;
+        self.max_pressure = max_pressure;
+        self
+    }
+
+    /// Heating and cooling rates in K/s and the temperatures at which the
+    /// thermal protection switches off and allows a restart.
+    pub fn thermal(
+        mut self,
+        heating_rate: f32,
+        cooling_rate: f32,
+        max_temperature: f32,
+        restart_temperature: f32,
+    ) -> Self {
+        self.heating_rate = heating_rate;
+        self.cooling_rate = cooling_rate;
+        self.max_temperature = max_temperature;
+        self.restart_temperature = restart_temperature;
+        self
+    }
+
+    pub fn run_up_time(mut self, run_up_time: f32) -> Self {
+        self.run_up_time = run_up_time;
+        self
+    }
+
+    /// Id of the fault in the fault manager which stops the compressor motor.
+    pub fn fault(mut self, id: impl Into<String>) -> Self {
+        self.fault = Some(id.into());
+        self
+    }
+
+    pub fn build(self) -> Compressor<S> {
+        Compressor {
+            cut_in_pressure: self.cut_in_pressure,
+            cut_out_pressure: self.cut_out_pressure,
+            delivery: self.delivery,
+            max_pressure: self.max_pressure,
+            heating_rate: self.heating_rate,
+            cooling_rate: self.cooling_rate,
+            max_temperature: self.max_temperature,
+            restart_temperature: self.restart_temperature,
+            run_up_time: self.run_up_time,
+            fault: self.fault,
+            governor_on: false,
+            thermal_trip: false,
+            running: false,
+            speed: 0.0,
+            temperature: AMBIENT_TEMPERATURE,
+            duty_time: 0.0,
+            snd_start: self.snd_start,
+            snd_run: self
+                .snd_run
+                .volume_ramp(RUN_SOUND_ATTACK, RUN_SOUND_RELEASE)
+                .pitch_ramp(RUN_SOUND_ATTACK, RUN_SOUND_RELEASE),
+            snd_stop: self.snd_stop,
+            snd_unloader: self.snd_unloader,
+        }
+    }
+}
+
+/// Governor controlled air compressor with thermal protection.
+///
+/// # Example
+///
+/// ```rust
+/// let mut compressor = Compressor::builder()
+///     .governor(8.0, 10.0)
+///     .snd_unloader("Snd_Compressor_Unloader")
+///     .build();
+///
+/// // In the main loop:
+/// compressor.tick(power, &mut reservoir);
+/// reservoir.tick();
+/// ```
+#[derive(Debug)]
+pub struct Compressor<S = Sound> {
+    cut_in_pressure: f32,
+    cut_out_pressure: f32,
+    delivery: f32,
+    max_pressure: f32,
+    heating_rate: f32,
+    cooling_rate: f32,
+    max_temperature: f32,
+    restart_temperature: f32,
+    run_up_time: f32,
+
+    fault: Option<String>,
+
+    /// Whether the governor requests the compressor
+    pub governor_on: bool,
+    /// Whether the thermal protection has switched off the compressor
+    pub thermal_trip: bool,
+    /// Whether the motor is switched on
+    pub running: bool,
+    /// Motor speed from 0.0 to 1.0
+    pub speed: f32,
+    /// Temperature of the compressor in °C
+    pub temperature: f32,
+    /// Running time since the last start in seconds
+    pub duty_time: f32,
+
+    snd_start: S,
+    snd_run: SoundWithRamp<S>,
+    snd_stop: S,
+    snd_unloader: S,
+}
+
+impl Compressor {
+    pub fn builder() -> CompressorBuilder {
+        Self::builder_with_outputs()
+    }
+}
+
+impl<S: SoundOutput> Compressor<S> {
+    /// Creates a builder on any sound outputs, e.g. a
+    /// [`MockSound`](crate::api::mock::MockSound) in tests.
+    pub fn builder_with_outputs() -> CompressorBuilder<S>
+    where
+        S: Default,
+    {
+        CompressorBuilder {
+            cut_in_pressure: 8.0,
+            cut_out_pressure: 10.0,
+            delivery: 15.0,
+            max_pressure: 12.0,
+            heating_rate: 0.5,
+            cooling_rate: 0.2,
+            max_temperature: 120.0,
+            restart_temperature: 80.0,
+            run_up_time: 1.5,
+            fault: None,
+            snd_start: S::default(),
+            snd_run: SoundWithRamp::with_output(S::default()),
+            snd_stop: S::default(),
+            snd_unloader: S::default(),
+        }
+    }
+
+    /// Updates the compressor and feeds the reservoir.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the compressor motor is supplied
+    /// * `reservoir` - Main reservoir fed by the compressor
+    pub fn tick(&mut self, power: bool, reservoir: &mut MainReservoir) {
+        // Druckregler
+        if reservoir.pressure < self.cut_in_pressure {
+            self.governor_on = true;
+        } else if reservoir.pressure >= self.cut_out_pressure {
+            self.governor_on = false;
+        }
+
+        // Thermoschutz
+        if self.temperature >= self.max_temperature {
+            self.thermal_trip = true;
+        } else if self.temperature <= self.restart_temperature {
+            self.thermal_trip = false;
+        }
+
+        let running_last = self.running;
+        let failed = self.fault.as_deref().is_some_and(fault_active);
+        self.running = power && self.governor_on && !self.thermal_trip && !failed;
+
+        if self.running != running_last {
+            if self.running {
+                self.snd_stop.stop();
+                self.snd_start.start();
+                self.duty_time = 0.0;
+            } else {
+                self.snd_start.stop();
+                self.snd_stop.start();
+                // Entlastungsventil entlüftet den Zylinderkopf
+                self.snd_unloader.start();
+            }
+        }
+
+        if self.running {
+            self.speed = (self.speed + delta() / self.run_up_time).min(1.0);
+            self.duty_time += delta();
+        } else {
+            self.speed = (self.speed - 2.0 * delta() / self.run_up_time).max(0.0);
+        }
+
+        let load = (reservoir.pressure / self.max_pressure).clamp(0.0, 1.0);
+
+        if self.running {
+            reservoir.feed(self.delivery * self.speed * (1.0 - load) * delta());
+            self.temperature += self.heating_rate * (0.5 + load) * delta();
+        } else {
+            self.temperature =
+                (self.temperature - self.cooling_rate * delta()).max(AMBIENT_TEMPERATURE);
+        }
+
+        // Lauf-Geräusch startet und endet mit der Drehzahl
+        self.snd_run
+            .tick(self.speed, self.speed * (1.0 - 0.1 * load));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{mock::MockSound, replay::run_frames};
+
+    #[test]
+    fn test_compressor_cycle() {
+        let mut compressor = Compressor::<MockSound>::builder_with_outputs()
+            .governor(8.0, 10.0)
+            .delivery(100.0, 12.0)
+            .build();
+        let mut reservoir = MainReservoir::new(100.0, 10.5).init_pressure(7.0);
+
+        run_frames(10, 0.1, || compressor.tick(true, &mut reservoir));
+        assert!(compressor.running);
+        assert_eq!(compressor.snd_start.start_count, 1);
+        assert!(compressor.snd_run.is_running());
+
+        // Bis zum Abschaltdruck füllen, das Lauf-Geräusch klingt langsamer aus als der Motor
+        let (mut volume_last, mut speed_last) = (compressor.snd_run.volume(), compressor.speed);
+        let (mut volume_drop, mut speed_drop): (f32, f32) = (0.0, 0.0);
+        run_frames(300, 0.1, || {
+            compressor.tick(true, &mut reservoir);
+            volume_drop = volume_drop.max(volume_last - compressor.snd_run.volume());
+            speed_drop = speed_drop.max(speed_last - compressor.speed);
+            (volume_last, speed_last) = (compressor.snd_run.volume(), compressor.speed);
+        });
+        assert!(!compressor.running);
+        assert!(volume_drop > 0.0 && volume_drop < speed_drop);
+        assert!(reservoir.pressure >= 10.0);
+        assert_eq!(compressor.snd_start.start_count, 1);
+        assert_eq!(compressor.snd_stop.start_count, 1);
+        assert_eq!(compressor.snd_unloader.start_count, 1);
+        assert!(!compressor.snd_run.is_running());
+    }
+
+    #[test]
+    fn test_compressor_without_power() {
+        let mut compressor = Compressor::<MockSound>::builder_with_outputs().build();
+        let mut reservoir = MainReservoir::new(100.0, 10.5);
+
+        run_frames(10, 0.1, || compressor.tick(false, &mut reservoir));
+        assert!(compressor.governor_on);
+        assert!(!compressor.running);
+        assert_eq!(compressor.snd_start.start_count, 0);
+        assert_eq!(reservoir.pressure, 0.0);
+    }
+}
@@ -0,0 +1,326 @@
+//! Bogie and suspension kinematics.
+//!
+//! The [`BogieKinematics`] derives the animations of the running gear from the
+//! track under the vehicle: the yaw angles of the bogies relative to the
+//! carbody from the curvature, and the roll and pitch of the carbody on its
+//! suspension from the cant, the unbalanced lateral acceleration in curves and
+//! the longitudinal acceleration.
+//!
+//! The [`GangwayBellows`] deforms the gangway bellows ("Faltenbalg") between
+//! two car sections according to the bend and the twist between them.
+
+use lotus_script::math::exponential_approach;
+
+use crate::api::animation::Animation;
+
+/// Acceleration of gravity in m/s²
+const GRAVITY: f32 = 9.81;
+
+pub struct BogieKinematicsBuilder {
+    pivot_distance: f32,
+    gauge: f32,
+
+    roll_factor: f32,
+    pitch_factor: f32,
+    damping: f32,
+
+    anim_bogie_front: Animation,
+    anim_bogie_rear: Animation,
+    anim_roll: Animation,
+    anim_pitch: Animation,
+}
+
+impl BogieKinematicsBuilder {
+    /// Yaw animations of the front and the rear bogie in degrees.
+    pub fn bogie_anims(mut self, front: impl Into<String>, rear: impl Into<String>) -> Self {
+        self.anim_bogie_front = Animation::new(Some(&front.into()));
+        self.anim_bogie_rear = Animation::new(Some(&rear.into()));
+        self
+    }
+
+    /// Roll animation of the carbody in degrees, positive to the right.
+    pub fn roll_anim(mut self, name: impl Into<String>) -> Self {
+        self.anim_roll = Animation::new(Some(&name.into()));
+        self
+    }
+
+    /// Pitch animation of the carbody in degrees, positive nose up.
+    pub fn pitch_anim(mut self, name: impl Into<String>) -> Self {
+        self.anim_pitch = Animation::new(Some(&name.into()));
+        self
+    }
+
+    /// Distance between the rail centres in m, used to convert the cant.
+    pub fn gauge(mut self, gauge: f32) -> Self {
+        self.gauge = gauge;
+        self
+    }
+
+    /// Softness of the suspension in degrees per m/s² of lateral and
+    /// longitudinal acceleration and the damping exponent of the carbody
+    /// movement.
+    pub fn suspension(mut self, roll_factor: f32, pitch_factor: f32, damping: f32) -> Self {
+        self.roll_factor = roll_factor;
+        self.pitch_factor = pitch_factor;
+        self.damping = damping;
+        self
+    }
+
+    pub fn build(self) -> BogieKinematics {
+        BogieKinematics {
+            pivot_distance: self.pivot_distance,
+            gauge: self.gauge,
+            roll_factor: self.roll_factor,
+            pitch_factor: self.pitch_factor,
+            damping: self.damping,
+            anim_bogie_front: self.anim_bogie_front,
+            anim_bogie_rear: self.anim_bogie_rear,
+            anim_roll: self.anim_roll,
+            anim_pitch: self.anim_pitch,
+            yaw_front: 0.0,
+            yaw_rear: 0.0,
+            lateral_acceleration: 0.0,
+            roll: 0.0,
+            pitch: 0.0,
+        }
+    }
+}
+
+/// Bogie yaw and carbody roll and pitch of a car section.
+///
+/// # Example
+///
+/// ```rust
+/// let mut kinematics = BogieKinematics::builder(10.0)
+///     .bogie_anims("Bogie_Front_Yaw", "Bogie_Rear_Yaw")
+///     .roll_anim("Body_Roll")
+///     .pitch_anim("Body_Pitch")
+///     .suspension(1.2, 0.4, 3.0)
+///     .build();
+///
+/// // In the main loop:
+/// kinematics.tick(axle_front.invradius(), axle_rear.invradius(), cant, v_ground(), acceleration);
+/// ```
+pub struct BogieKinematics {
+    pivot_distance: f32,
+    gauge: f32,
+
+    roll_factor: f32,
+    pitch_factor: f32,
+    damping: f32,
+
+    anim_bogie_front: Animation,
+    anim_bogie_rear: Animation,
+    anim_roll: Animation,
+    anim_pitch: Animation,
+
+    /// Yaw angle of the front bogie relative to the carbody in degrees
+    pub yaw_front: f32,
+    /// Yaw angle of the rear bogie relative to the carbody in degrees
+    pub yaw_rear: f32,
+    /// Lateral acceleration not compensated by the cant in m/s², positive
+    /// to the outside of a right curve
+    pub lateral_acceleration: f32,
+    /// Roll of the carbody in degrees, positive to the right
+    pub roll: f32,
+    /// Pitch of the carbody in degrees, positive nose up
+    pub pitch: f32,
+}
+
+impl BogieKinematics {
+    /// Creates a new bogie kinematics builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `pivot_distance` - Distance between the bogie pivots in m
+    pub fn builder(pivot_distance: f32) -> BogieKinematicsBuilder {
+        BogieKinematicsBuilder {
+            pivot_distance,
+            gauge: 1.5,
+            roll_factor: 1.0,
+            pitch_factor: 0.3,
+            damping: 3.0,
+            anim_bogie_front: Animation::new(None),
+            anim_bogie_rear: Animation::new(None),
+            anim_roll: Animation::new(None),
+            anim_pitch: Animation::new(None),
+        }
+    }
+
+    /// Updates the angles and the animations.
+    ///
+    /// # Arguments
+    ///
+    /// * `inv_radius_front` - Curvature under the front bogie in 1/m, positive to the right
+    /// * `inv_radius_rear` - Curvature under the rear bogie in 1/m, positive to the right
+    /// * `cant` - Cant of the track in m, positive with the right rail lower
+    /// * `speed` - Speed of the vehicle in m/s
+    /// * `acceleration` - Longitudinal acceleration in m/s²
+    pub fn tick(
+        &mut self,
+        inv_radius_front: f32,
+        inv_radius_rear: f32,
+        cant: f32,
+        speed: f32,
+        acceleration: f32,
+    ) {
+        // Drehgestell steht tangential, der Wagenkasten als Sehne zwischen den Drehzapfen
+        let half = self.pivot_distance * 0.5;
+        self.yaw_front = (half * inv_radius_front)
+            .clamp(-1.0, 1.0)
+            .asin()
+            .to_degrees();
+        self.yaw_rear = -(half * inv_radius_rear)
+            .clamp(-1.0, 1.0)
+            .asin()
+            .to_degrees();
+
+        let inv_radius = (inv_radius_front + inv_radius_rear) * 0.5;
+        let cant_angle = (cant / self.gauge).clamp(-1.0, 1.0).asin();
+        self.lateral_acceleration = speed * speed * inv_radius - GRAVITY * cant_angle.sin();
+
+        // Wagenkasten neigt sich zur Kurvenaußenseite
+        let roll_target = cant_angle.to_degrees() - self.roll_factor * self.lateral_acceleration;
+        let pitch_target = self.pitch_factor * acceleration;
+        self.roll = exponential_approach(self.roll, self.damping, roll_target);
+        self.pitch = exponential_approach(self.pitch, self.damping, pitch_target);
+
+        self.anim_bogie_front.set(self.yaw_front);
+        self.anim_bogie_rear.set(self.yaw_rear);
+        self.anim_roll.set(self.roll);
+        self.anim_pitch.set(self.pitch);
+    }
+}
+
+//=========================================================================
+
+pub struct GangwayBellowsBuilder {
+    length_a: f32,
+    length_b: f32,
+    half_width: f32,
+
+    anim_bend: Animation,
+    anim_twist: Animation,
+    anim_left: Animation,
+    anim_right: Animation,
+}
+
+impl GangwayBellowsBuilder {
+    /// Half width of the bellows in m, used for the stretch of the sides.
+    pub fn half_width(mut self, half_width: f32) -> Self {
+        self.half_width = half_width;
+        self
+    }
+
+    /// Bend animation of the bellows in degrees.
+    pub fn bend_anim(mut self, name: impl Into<String>) -> Self {
+        self.anim_bend = Animation::new(Some(&name.into()));
+        self
+    }
+
+    /// Twist animation of the bellows in degrees.
+    pub fn twist_anim(mut self, name: impl Into<String>) -> Self {
+        self.anim_twist = Animation::new(Some(&name.into()));
+        self
+    }
+
+    /// Animations of the stretch of the left and the right side in m.
+    pub fn side_anims(mut self, left: impl Into<String>, right: impl Into<String>) -> Self {
+        self.anim_left = Animation::new(Some(&left.into()));
+        self.anim_right = Animation::new(Some(&right.into()));
+        self
+    }
+
+    pub fn build(self) -> GangwayBellows {
+        GangwayBellows {
+            length_a: self.length_a,
+            length_b: self.length_b,
+            half_width: self.half_width,
+            anim_bend: self.anim_bend,
+            anim_twist: self.anim_twist,
+            anim_left: self.anim_left,
+            anim_right: self.anim_right,
+            bend: 0.0,
+            twist: 0.0,
+        }
+    }
+}
+
+/// Gangway bellows between two car sections.
+///
+/// # Example
+///
+/// ```rust
+/// let mut bellows = GangwayBellows::builder(5.5, 5.5)
+///     .half_width(1.1)
+///     .bend_anim("Gangway_Bend")
+///     .side_anims("Gangway_Left", "Gangway_Right")
+///     .build();
+///
+/// // In the main loop:
+/// bellows.tick(axle.invradius(), kinematics_a.roll, kinematics_b.roll);
+/// ```
+pub struct GangwayBellows {
+    length_a: f32,
+    length_b: f32,
+    half_width: f32,
+
+    anim_bend: Animation,
+    anim_twist: Animation,
+    anim_left: Animation,
+    anim_right: Animation,
+
+    /// Bend angle between the sections in degrees, positive to the right
+    pub bend: f32,
+    /// Twist between the sections in degrees
+    pub twist: f32,
+}
+
+impl GangwayBellows {
+    /// Creates a new gangway bellows builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `length_a` - Distance from the gangway to the next pivot of the front section in m
+    /// * `length_b` - Distance from the gangway to the next pivot of the rear section in m
+    pub fn builder(length_a: f32, length_b: f32) -> GangwayBellowsBuilder {
+        GangwayBellowsBuilder {
+            length_a,
+            length_b,
+            half_width: 1.1,
+            anim_bend: Animation::new(None),
+            anim_twist: Animation::new(None),
+            anim_left: Animation::new(None),
+            anim_right: Animation::new(None),
+        }
+    }
+
+    /// Updates the deformation of the bellows.
+    ///
+    /// # Arguments
+    ///
+    /// * `inv_radius` - Curvature at the gangway in 1/m, positive to the right
+    /// * `roll_a` - Roll of the front section in degrees
+    /// * `roll_b` - Roll of the rear section in degrees
+    pub fn tick(&mut self, inv_radius: f32, roll_a: f32, roll_b: f32) {
+        self.bend = bend_angle(inv_radius, self.length_a, self.length_b).to_degrees();
+        self.twist = roll_a - roll_b;
+
+        // Kurveninnenseite wird gestaucht, Außenseite gestreckt
+        let stretch = self.bend.to_radians() * self.half_width;
+
+        self.anim_bend.set(self.bend);
+        self.anim_twist.set(self.twist);
+        self.anim_left.set(stretch);
+        self.anim_right.set(-stretch);
+    }
+}
+
+/// Bend angle in rad between two car sections whose carbodies are chords of
+/// the track from the joint to their next pivots.
+#[must_use]
+pub fn bend_angle(inv_radius: f32, length_a: f32, length_b: f32) -> f32 {
+    let half_a = (length_a * inv_radius * 0.5).clamp(-1.0, 1.0).asin();
+    let half_b = (length_b * inv_radius * 0.5).clamp(-1.0, 1.0).asin();
+    half_a + half_b
+}
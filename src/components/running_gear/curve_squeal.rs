@@ -0,0 +1,340 @@
+//! Curve squeal and flange lubrication.
+//!
+//! The [`CurveSqueal`] lets the wheels squeal in tight curves. Whether a curve
+//! squeals is decided by chance, the probability rises with the tightness of
+//! the curve and drops on wet rails and with lubricated flanges. The
+//! [`FlangeLubricator`] sprays grease on the flanges when entering a curve or
+//! on demand of the driver. The grease lasts for a certain distance and is
+//! taken from a reservoir which has to be refilled in the depot.
+
+use crate::api::{
+    general::delta, key_event::KeyEvent, light::Light, random::gen_f32, sound::Sound,
+};
+
+pub struct CurveSquealBuilder {
+    max_radius: f32,
+    min_speed: f32,
+    probability: f32,
+    check_interval: f32,
+    duration: (f32, f32),
+
+    snd_squeal: Sound,
+}
+
+impl CurveSquealBuilder {
+    /// Largest curve radius in m in which the wheels squeal and the minimum
+    /// speed in m/s.
+    pub fn limits(mut self, max_radius: f32, min_speed: f32) -> Self {
+        self.max_radius = max_radius;
+        self.min_speed = min_speed;
+        self
+    }
+
+    /// Probability of squealing per check in the tightest curve on dry rails
+    /// and the interval of the checks in seconds.
+    pub fn probability(mut self, probability: f32, check_interval: f32) -> Self {
+        self.probability = probability;
+        self.check_interval = check_interval;
+        self
+    }
+
+    /// Minimum and maximum duration of a squeal in seconds, the values may be
+    /// given in any order.
+    pub fn duration(mut self, min: f32, max: f32) -> Self {
+        self.duration = (min.min(max), min.max(max));
+        self
+    }
+
+    pub fn build(self) -> CurveSqueal {
+        CurveSqueal {
+            max_radius: self.max_radius,
+            min_speed: self.min_speed,
+            probability: self.probability,
+            check_interval: self.check_interval,
+            duration: self.duration,
+            snd_squeal: self.snd_squeal,
+            check_timer: 0.0,
+            squeal_timer: 0.0,
+            squealing: false,
+            intensity: 0.0,
+        }
+    }
+}
+
+/// Squealing of the wheels in tight curves.
+///
+/// # Example
+///
+/// ```rust
+/// let mut squeal = CurveSqueal::builder("Snd_Squeal", "Snd_Squeal_Vol", "Snd_Squeal_Pitch")
+///     .limits(150.0, 2.0)
+///     .build();
+///
+/// // In the main loop:
+/// lubricator.tick(power, axle.invradius(), v_ground());
+/// squeal.tick(axle.invradius(), v_ground(), rain, lubricator.effect);
+/// ```
+pub struct CurveSqueal {
+    max_radius: f32,
+    min_speed: f32,
+    probability: f32,
+    check_interval: f32,
+    duration: (f32, f32),
+
+    snd_squeal: Sound,
+
+    check_timer: f32,
+    squeal_timer: f32,
+
+    /// Whether the wheels squeal
+    pub squealing: bool,
+    /// Intensity of the squeal from 0.0 to 1.0
+    pub intensity: f32,
+}
+
+impl CurveSqueal {
+    /// Creates a new curve squeal builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `sound_name` - Name of the squealing sound
+    /// * `sound_vol_name` - Volume variable of the squealing sound
+    /// * `sound_pitch_name` - Pitch variable of the squealing sound
+    pub fn builder(
+        sound_name: impl Into<String>,
+        sound_vol_name: impl Into<String>,
+        sound_pitch_name: impl Into<String>,
+    ) -> CurveSquealBuilder {
+        CurveSquealBuilder {
+            max_radius: 150.0,
+            min_speed: 1.5,
+            probability: 0.6,
+            check_interval: 1.0,
+            duration: (2.0, 8.0),
+            snd_squeal: Sound::new(
+                Some(&sound_name.into()),
+                Some(&sound_vol_name.into()),
+                Some(&sound_pitch_name.into()),
+            ),
+        }
+    }
+
+    /// Updates the squeal.
+    ///
+    /// # Arguments
+    ///
+    /// * `inv_radius` - Curvature of the track in 1/m
+    /// * `speed` - Speed of the vehicle in m/s
+    /// * `wetness` - Wetness of the rails from 0.0 (dry) to 1.0 (wet)
+    /// * `lubrication` - Effect of the flange lubrication from 0.0 to 1.0
+    pub fn tick(&mut self, inv_radius: f32, speed: f32, wetness: f32, lubrication: f32) {
+        let radius = 1.0 / inv_radius.abs().max(f32::EPSILON);
+        let tightness = (1.0 - radius / self.max_radius).clamp(0.0, 1.0);
+        let possible = tightness > 0.0 && speed.abs() >= self.min_speed;
+
+        if !possible {
+            self.squeal_timer = 0.0;
+            self.check_timer = 0.0;
+        } else if self.squeal_timer > 0.0 {
+            self.squeal_timer -= delta();
+        } else {
+            self.check_timer -= delta();
+            if self.check_timer <= 0.0 {
+                self.check_timer = self.check_interval;
+
+                // Nasse Schienen und geschmierte Spurkränze dämpfen das Quietschen
+                let chance = self.probability
+                    * tightness
+                    * (1.0 - 0.8 * wetness.clamp(0.0, 1.0))
+                    * (1.0 - lubrication.clamp(0.0, 1.0));
+                if gen_f32(0.0..1.0) < chance {
+                    self.squeal_timer = gen_f32(self.duration.0..=self.duration.1);
+                }
+            }
+        }
+
+        let squealing = self.squeal_timer > 0.0;
+        if squealing != self.squealing {
+            self.squealing = squealing;
+            self.snd_squeal.start_stop(squealing);
+        }
+
+        self.intensity = if squealing {
+            tightness * (speed.abs() / 10.0).min(1.0)
+        } else {
+            0.0
+        };
+        self.snd_squeal.update_volume(self.intensity);
+        self.snd_squeal
+            .update_pitch(0.9 + 0.2 * (speed.abs() / 10.0).min(1.0));
+    }
+}
+
+//=========================================================================
+
+pub struct FlangeLubricatorBuilder {
+    capacity: f32,
+    dose: f32,
+    effect_distance: f32,
+    auto_radius: f32,
+    low_level: f32,
+
+    key_manual: KeyEvent,
+
+    lm_low_level: Light,
+    snd_spray: Sound,
+}
+
+impl FlangeLubricatorBuilder {
+    /// Grease per activation in kg and the distance in m the grease lasts.
+    pub fn dose(mut self, dose: f32, effect_distance: f32) -> Self {
+        self.dose = dose;
+        self.effect_distance = effect_distance;
+        self
+    }
+
+    /// Curve radius in m below which the lubricator activates automatically.
+    pub fn auto_radius(mut self, auto_radius: f32) -> Self {
+        self.auto_radius = auto_radius;
+        self
+    }
+
+    /// Key event activating the lubricator manually.
+    pub fn manual_event(mut self, name: impl Into<String>) -> Self {
+        self.key_manual = KeyEvent::new(Some(&name.into()), None);
+        self
+    }
+
+    /// Warning lamp for a low grease level and the level relative to the
+    /// capacity at which it is lit.
+    pub fn low_level(mut self, light_name: impl Into<String>, low_level: f32) -> Self {
+        self.lm_low_level = Light::new(Some(&light_name.into()));
+        self.low_level = low_level;
+        self
+    }
+
+    pub fn snd_spray(mut self, name: impl Into<String>) -> Self {
+        self.snd_spray = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> FlangeLubricator {
+        FlangeLubricator {
+            capacity: self.capacity,
+            dose: self.dose,
+            effect_distance: self.effect_distance,
+            auto_radius: self.auto_radius,
+            low_level: self.low_level,
+            key_manual: self.key_manual,
+            lm_low_level: self.lm_low_level,
+            snd_spray: self.snd_spray,
+            in_curve: false,
+            remaining_distance: 0.0,
+            level: self.capacity,
+            effect: 0.0,
+        }
+    }
+}
+
+/// Flange lubricator with grease reservoir.
+///
+/// # Example
+///
+/// ```rust
+/// let mut lubricator = FlangeLubricator::builder(5.0)
+///     .auto_radius(200.0)
+///     .manual_event("Flange_Lubrication")
+///     .build();
+///
+/// // In the main loop:
+/// lubricator.tick(power, axle.invradius(), v_ground());
+/// ```
+pub struct FlangeLubricator {
+    capacity: f32,
+    dose: f32,
+    effect_distance: f32,
+    auto_radius: f32,
+    low_level: f32,
+
+    key_manual: KeyEvent,
+
+    lm_low_level: Light,
+    snd_spray: Sound,
+
+    in_curve: bool,
+    remaining_distance: f32,
+
+    /// Grease in the reservoir in kg
+    pub level: f32,
+    /// Effect of the lubrication from 0.0 to 1.0
+    pub effect: f32,
+}
+
+impl FlangeLubricator {
+    /// Creates a new flange lubricator builder, the reservoir starts full.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Capacity of the grease reservoir in kg
+    pub fn builder(capacity: f32) -> FlangeLubricatorBuilder {
+        FlangeLubricatorBuilder {
+            capacity,
+            dose: 0.01,
+            effect_distance: 500.0,
+            auto_radius: 200.0,
+            low_level: 0.1,
+            key_manual: KeyEvent::new(None, None),
+            lm_low_level: Light::new(None),
+            snd_spray: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the lubricator.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the lubricator is supplied
+    /// * `inv_radius` - Curvature of the track in 1/m
+    /// * `speed` - Speed of the vehicle in m/s
+    pub fn tick(&mut self, power: bool, inv_radius: f32, speed: f32) {
+        // Automatisch einmal beim Einfahren in den Bogen
+        let curve = inv_radius.abs() * self.auto_radius > 1.0;
+        let entering = curve && !self.in_curve;
+        self.in_curve = curve;
+
+        let manual = self.key_manual.is_just_pressed();
+        if power && (entering || manual) && speed.abs() > 0.1 {
+            self.spray();
+        }
+
+        self.remaining_distance = (self.remaining_distance - speed.abs() * delta()).max(0.0);
+        self.effect = if self.effect_distance > 0.0 {
+            self.remaining_distance / self.effect_distance
+        } else {
+            0.0
+        };
+
+        self.lm_low_level
+            .set_brightness((power && self.level_rel() < self.low_level) as u8 as f32);
+    }
+
+    /// Sprays a dose of grease if the reservoir is not empty.
+    pub fn spray(&mut self) {
+        if self.level <= 0.0 {
+            return;
+        }
+        self.level = (self.level - self.dose).max(0.0);
+        self.remaining_distance = self.effect_distance;
+        self.snd_spray.start();
+    }
+
+    /// Refills the grease reservoir.
+    pub fn refill(&mut self) {
+        self.level = self.capacity;
+    }
+
+    /// Level relative to the capacity from 0.0 to 1.0.
+    pub fn level_rel(&self) -> f32 {
+        self.level / self.capacity
+    }
+}
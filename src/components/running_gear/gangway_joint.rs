@@ -0,0 +1,185 @@
+//! Articulation joint between car sections.
+//!
+//! The [`GangwayJoint`] connects two sections of an articulated tram like the
+//! GT6N. It derives the bend angle between the sections from the curvature
+//! and the roll angle from the roll of both carbodies, drives the joint and
+//! bellows animations and lets the joint creak while it moves. The creaking
+//! volume follows the angular velocity of the joint.
+
+use crate::{
+    api::{animation::Animation, general::delta, sound::Sound},
+    components::running_gear::bogie::{bend_angle, GangwayBellows},
+};
+
+pub struct GangwayJointBuilder {
+    length_a: f32,
+    length_b: f32,
+
+    bellows: Option<GangwayBellows>,
+
+    anim_bend: Animation,
+    anim_roll: Animation,
+
+    creak_factor: f32,
+    creak_threshold: f32,
+
+    snd_creak: Sound,
+}
+
+impl GangwayJointBuilder {
+    /// Bellows deformed together with the joint.
+    pub fn bellows(mut self, bellows: GangwayBellows) -> Self {
+        self.bellows = Some(bellows);
+        self
+    }
+
+    /// Bend and roll animations of the joint in degrees.
+    pub fn joint_anims(mut self, bend: impl Into<String>, roll: impl Into<String>) -> Self {
+        self.anim_bend = Animation::new(Some(&bend.into()));
+        self.anim_roll = Animation::new(Some(&roll.into()));
+        self
+    }
+
+    /// Creaking sound with volume and pitch variables.
+    pub fn snd_creak(
+        mut self,
+        name: impl Into<String>,
+        vol_name: impl Into<String>,
+        pitch_name: impl Into<String>,
+    ) -> Self {
+        self.snd_creak = Sound::new(
+            Some(&name.into()),
+            Some(&vol_name.into()),
+            Some(&pitch_name.into()),
+        );
+        self
+    }
+
+    /// Creaking volume per degree per second of angular velocity and the
+    /// angular velocity in degrees per second below which the joint is silent.
+    pub fn creak(mut self, creak_factor: f32, creak_threshold: f32) -> Self {
+        self.creak_factor = creak_factor;
+        self.creak_threshold = creak_threshold;
+        self
+    }
+
+    pub fn build(self) -> GangwayJoint {
+        GangwayJoint {
+            length_a: self.length_a,
+            length_b: self.length_b,
+            bellows: self.bellows,
+            anim_bend: self.anim_bend,
+            anim_roll: self.anim_roll,
+            creak_factor: self.creak_factor,
+            creak_threshold: self.creak_threshold,
+            snd_creak: self.snd_creak,
+            initialized: false,
+            bend: 0.0,
+            roll: 0.0,
+            angular_velocity: 0.0,
+            creaking: false,
+        }
+    }
+}
+
+/// Articulation joint with bellows and creaking sound.
+///
+/// # Example
+///
+/// ```rust
+/// let mut joint = GangwayJoint::builder(4.2, 4.2)
+///     .bellows(GangwayBellows::builder(4.2, 4.2).side_anims("Bellows_L", "Bellows_R").build())
+///     .joint_anims("Joint_Bend", "Joint_Roll")
+///     .snd_creak("Snd_Joint_Creak", "Snd_Joint_Creak_Vol", "Snd_Joint_Creak_Pitch")
+///     .build();
+///
+/// // In the main loop:
+/// joint.tick(axle.invradius(), section_a.roll, section_b.roll);
+/// ```
+pub struct GangwayJoint {
+    length_a: f32,
+    length_b: f32,
+
+    bellows: Option<GangwayBellows>,
+
+    anim_bend: Animation,
+    anim_roll: Animation,
+
+    creak_factor: f32,
+    creak_threshold: f32,
+
+    snd_creak: Sound,
+
+    initialized: bool,
+
+    /// Bend angle between the sections in degrees, positive to the right
+    pub bend: f32,
+    /// Roll angle between the sections in degrees
+    pub roll: f32,
+    /// Angular velocity of the joint in degrees per second
+    pub angular_velocity: f32,
+    /// Whether the joint creaks
+    pub creaking: bool,
+}
+
+impl GangwayJoint {
+    /// Creates a new articulation joint builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `length_a` - Distance from the joint to the next pivot of the front section in m
+    /// * `length_b` - Distance from the joint to the next pivot of the rear section in m
+    pub fn builder(length_a: f32, length_b: f32) -> GangwayJointBuilder {
+        GangwayJointBuilder {
+            length_a,
+            length_b,
+            bellows: None,
+            anim_bend: Animation::new(None),
+            anim_roll: Animation::new(None),
+            creak_factor: 0.2,
+            creak_threshold: 0.3,
+            snd_creak: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the joint angles, the animations and the creaking.
+    ///
+    /// # Arguments
+    ///
+    /// * `inv_radius` - Curvature at the joint in 1/m, positive to the right
+    /// * `roll_a` - Roll of the front section in degrees
+    /// * `roll_b` - Roll of the rear section in degrees
+    pub fn tick(&mut self, inv_radius: f32, roll_a: f32, roll_b: f32) {
+        let bend = bend_angle(inv_radius, self.length_a, self.length_b).to_degrees();
+        let roll = roll_a - roll_b;
+
+        // Erster Durchlauf ohne Sprung der Winkelgeschwindigkeit
+        let dt = delta();
+        self.angular_velocity = if self.initialized && dt > 0.0 {
+            ((bend - self.bend).abs() + (roll - self.roll).abs()) / dt
+        } else {
+            0.0
+        };
+        self.initialized = true;
+
+        self.bend = bend;
+        self.roll = roll;
+
+        self.anim_bend.set(self.bend);
+        self.anim_roll.set(self.roll);
+
+        if let Some(bellows) = &mut self.bellows {
+            bellows.tick(inv_radius, roll_a, roll_b);
+        }
+
+        let creaking = self.angular_velocity > self.creak_threshold;
+        if creaking != self.creaking {
+            self.creaking = creaking;
+            self.snd_creak.start_stop(creaking);
+        }
+
+        let volume = (self.angular_velocity * self.creak_factor).min(1.0);
+        self.snd_creak.update_volume(volume);
+        self.snd_creak.update_pitch(0.8 + 0.4 * volume);
+    }
+}
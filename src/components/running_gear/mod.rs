@@ -0,0 +1,4 @@
+pub mod bogie;
+pub mod curve_squeal;
+pub mod gangway_joint;
+pub mod rail_condition;
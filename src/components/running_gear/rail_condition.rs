@@ -0,0 +1,189 @@
+//! Rail condition and adhesion.
+//!
+//! The [`RailCondition`] derives the adhesion coefficient between wheel and
+//! rail from the weather: rain, leaves on the track and frost lower it, recent
+//! sanding raises it again. The adhesion required by the traction or brake
+//! force is compared with the available one; if it is exceeded, the wheels
+//! slip or slide. The adhesion is published as [`AdhesionInfo`] message, to
+//! which e.g. the [`Sander`](crate::components::traction::sander::Sander)
+//! reacts, and a slip is reported as [`DiagnosticSlip`](crate::messages::diagnostic_messages::DiagnosticSlip)
+//! for the train protection and the diagnostics.
+
+use crate::{
+    api::general::delta,
+    messages::{
+        diagnostic_messages::DiagnosticSlipSender,
+        pandemist_messages::{AdhesionInfo, AdhesionInfoSender},
+    },
+};
+
+/// Weather at the vehicle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weather {
+    /// Rain from 0.0 (dry) to 1.0 (heavy rain)
+    pub rain: f32,
+    /// Leaves on the track from 0.0 (none) to 1.0 (leaf mush)
+    pub leaves: f32,
+    /// Outside temperature in °C
+    pub temperature: f32,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            rain: 0.0,
+            leaves: 0.0,
+            temperature: 15.0,
+        }
+    }
+}
+
+pub struct RailConditionBuilder {
+    dry_adhesion: f32,
+    rain_factor: f32,
+    leaves_factor: f32,
+    frost_factor: f32,
+    sand_bonus: f32,
+    sand_effect_time: f32,
+}
+
+impl RailConditionBuilder {
+    /// Adhesion coefficient on clean and dry rails.
+    pub fn dry_adhesion(mut self, dry_adhesion: f32) -> Self {
+        self.dry_adhesion = dry_adhesion;
+        self
+    }
+
+    /// Remaining share of the adhesion in heavy rain, on leaf mush and on
+    /// frozen rails.
+    pub fn factors(mut self, rain: f32, leaves: f32, frost: f32) -> Self {
+        self.rain_factor = rain;
+        self.leaves_factor = leaves;
+        self.frost_factor = frost;
+        self
+    }
+
+    /// Adhesion gained by sanding and the time in seconds it lasts after the
+    /// sanding has ended.
+    pub fn sanding(mut self, sand_bonus: f32, sand_effect_time: f32) -> Self {
+        self.sand_bonus = sand_bonus;
+        self.sand_effect_time = sand_effect_time;
+        self
+    }
+
+    pub fn build(self) -> RailCondition {
+        RailCondition {
+            dry_adhesion: self.dry_adhesion,
+            rain_factor: self.rain_factor,
+            leaves_factor: self.leaves_factor,
+            frost_factor: self.frost_factor,
+            sand_bonus: self.sand_bonus,
+            sand_effect_time: self.sand_effect_time,
+            sand_effect: 0.0,
+            info_sender: AdhesionInfoSender::default(),
+            slip_sender: DiagnosticSlipSender::default(),
+            adhesion: self.dry_adhesion,
+            wetness: 0.0,
+            slip: false,
+            slide: false,
+        }
+    }
+}
+
+/// Adhesion between wheel and rail depending on the weather and sanding.
+///
+/// # Example
+///
+/// ```rust
+/// let mut rail = RailCondition::builder().factors(0.6, 0.3, 0.5).build();
+///
+/// // In the main loop:
+/// let weather = Weather { rain: 0.5, leaves: 0.2, temperature: 8.0 };
+/// rail.tick(weather, sander.sanding, traction_force / weight_force, brake_force / weight_force);
+/// ```
+pub struct RailCondition {
+    dry_adhesion: f32,
+    rain_factor: f32,
+    leaves_factor: f32,
+    frost_factor: f32,
+    sand_bonus: f32,
+    sand_effect_time: f32,
+
+    sand_effect: f32,
+
+    info_sender: AdhesionInfoSender,
+    slip_sender: DiagnosticSlipSender,
+
+    /// Available adhesion coefficient
+    pub adhesion: f32,
+    /// Wetness of the rails from 0.0 (dry) to 1.0 (wet)
+    pub wetness: f32,
+    /// Whether the wheels slip under traction
+    pub slip: bool,
+    /// Whether the wheels slide under braking
+    pub slide: bool,
+}
+
+impl RailCondition {
+    /// Creates a new rail condition builder.
+    pub fn builder() -> RailConditionBuilder {
+        RailConditionBuilder {
+            dry_adhesion: 0.33,
+            rain_factor: 0.6,
+            leaves_factor: 0.3,
+            frost_factor: 0.5,
+            sand_bonus: 0.1,
+            sand_effect_time: 5.0,
+        }
+    }
+
+    /// Updates the adhesion and detects slipping and sliding wheels.
+    ///
+    /// # Arguments
+    ///
+    /// * `weather` - Weather at the vehicle
+    /// * `sanding` - Whether the vehicle is sanding
+    /// * `traction_demand` - Adhesion required by the traction force, i.e. the
+    ///   traction force divided by the weight force on the driven axles
+    /// * `brake_demand` - Adhesion required by the brake force
+    pub fn tick(
+        &mut self,
+        weather: Weather,
+        sanding: bool,
+        traction_demand: f32,
+        brake_demand: f32,
+    ) {
+        self.wetness = weather.rain.clamp(0.0, 1.0);
+
+        // Reif und Eis ab etwa 1 °C, voll ausgeprägt bei -3 °C
+        let frost = ((1.0 - weather.temperature) / 4.0).clamp(0.0, 1.0);
+
+        if sanding {
+            self.sand_effect = 1.0;
+        } else if self.sand_effect_time > 0.0 {
+            self.sand_effect = (self.sand_effect - delta() / self.sand_effect_time).max(0.0);
+        } else {
+            self.sand_effect = 0.0;
+        }
+
+        self.adhesion = self.dry_adhesion
+            * Self::reduction(self.wetness, self.rain_factor)
+            * Self::reduction(weather.leaves, self.leaves_factor)
+            * Self::reduction(frost, self.frost_factor)
+            + self.sand_bonus * self.sand_effect;
+
+        self.slip = traction_demand.abs() > self.adhesion;
+        self.slide = brake_demand.abs() > self.adhesion;
+
+        self.info_sender.send(AdhesionInfo {
+            adhesion: self.adhesion,
+            slip: self.slip,
+            slide: self.slide,
+        });
+        self.slip_sender.send(self.slip || self.slide);
+    }
+
+    fn reduction(amount: f32, factor: f32) -> f32 {
+        1.0 - (1.0 - factor) * amount.clamp(0.0, 1.0)
+    }
+}
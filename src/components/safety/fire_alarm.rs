@@ -0,0 +1,569 @@
+//! Fire alarm system ("Brandmeldeanlage") and fire extinguishers.
+//!
+//! The [`FireAlarm`] watches one smoke detector per compartment. A detector is
+//! triggered by a scenario through its trigger variable or with
+//! [`FireAlarm::trigger`]; the smoke density rises and the detector latches as
+//! soon as the density exceeds the threshold. A latched detector switches on
+//! the alarm lamp and the buzzer and reports the fire on the TrainBus. The
+//! reset button first silences the buzzer and, once the smoke has cleared,
+//! resets the detectors.
+//!
+//! The [`Extinguisher`] is the prop in its holder. It is taken out with a
+//! removal animation, can be discharged and reports in an inventory variable
+//! whether it is complete and usable in its holder.
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::{
+    api::{
+        animation::Animation,
+        general::delta,
+        key_event::KeyEvent,
+        light::Light,
+        sound::Sound,
+        variable::{get_var, set_var},
+        visible_flag::Visiblility,
+    },
+    elements::tech::buttons::PushButton,
+    management::trainbus::{PeripheryFault, PeripheryKind, TrainBusPeriferie},
+};
+
+//=================================================================
+// SmokeDetector
+//=================================================================
+
+/// Smoke detector of a single compartment.
+#[derive(Debug)]
+pub struct SmokeDetector {
+    /// Name of the compartment, used in the TrainBus report
+    pub compartment: String,
+    trigger_var: Option<String>,
+    triggered: bool,
+
+    lm_detector: Light,
+
+    /// Smoke density from 0.0 to 1.0
+    pub smoke: f32,
+    /// Whether the detector has latched the alarm
+    pub detected: bool,
+}
+
+impl SmokeDetector {
+    fn update(&mut self, rise_rate: f32, clear_rate: f32, threshold: f32) {
+        let triggered = self.triggered
+            || self
+                .trigger_var
+                .as_ref()
+                .is_some_and(|var| get_var::<bool>(var));
+
+        // Rauch steigt bei Brand an und zieht danach langsam ab
+        self.smoke = if triggered {
+            (self.smoke + rise_rate * delta()).min(1.0)
+        } else {
+            (self.smoke - clear_rate * delta()).max(0.0)
+        };
+
+        if self.smoke >= threshold {
+            self.detected = true;
+        }
+    }
+}
+
+//=================================================================
+// FireAlarm
+//=================================================================
+
+pub struct FireAlarmBuilder {
+    detectors: Vec<SmokeDetector>,
+
+    threshold: f32,
+    rise_rate: f32,
+    clear_rate: f32,
+
+    reset_button: Option<PushButton>,
+    trainbus_slot: Option<i32>,
+
+    lm_alarm: Light,
+    snd_buzzer: Sound,
+}
+
+impl FireAlarmBuilder {
+    /// Adds the smoke detector of a compartment.
+    ///
+    /// * `compartment` - Name of the compartment
+    /// * `trigger_var` - Bool variable with which a scenario starts a fire
+    pub fn detector(mut self, compartment: impl Into<String>, trigger_var: Option<&str>) -> Self {
+        self.detectors.push(SmokeDetector {
+            compartment: compartment.into(),
+            trigger_var: trigger_var.map(str::to_string),
+            triggered: false,
+            lm_detector: Light::new(None),
+            smoke: 0.0,
+            detected: false,
+        });
+        self
+    }
+
+    /// Indicator lamp of the last added detector, lit while it has latched.
+    pub fn detector_light(mut self, name: impl Into<String>) -> Self {
+        if let Some(detector) = self.detectors.last_mut() {
+            detector.lm_detector = Light::new(Some(&name.into()));
+        }
+        self
+    }
+
+    /// Smoke density from 0.0 to 1.0 at which the detectors latch.
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Rates per second at which the smoke rises and clears.
+    pub fn smoke_rates(mut self, rise_rate: f32, clear_rate: f32) -> Self {
+        self.rise_rate = rise_rate;
+        self.clear_rate = clear_rate;
+        self
+    }
+
+    /// Push button silencing the buzzer and resetting the detectors.
+    pub fn reset_button(
+        mut self,
+        animation_name: impl Into<String>,
+        event_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> Self {
+        self.reset_button = Some(PushButton::builder(animation_name, event_name, cab_side).build());
+        self
+    }
+
+    /// Registers the fire alarm on the TrainBus with the given slot index.
+    pub fn trainbus(mut self, slot_index: i32) -> Self {
+        self.trainbus_slot = Some(slot_index);
+        self
+    }
+
+    /// Alarm lamp in the cab.
+    pub fn alarm_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_alarm = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Alarm buzzer in the cab.
+    pub fn snd_buzzer(mut self, name: impl Into<String>) -> Self {
+        self.snd_buzzer = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> FireAlarm {
+        FireAlarm {
+            detectors: self.detectors,
+            threshold: self.threshold,
+            rise_rate: self.rise_rate,
+            clear_rate: self.clear_rate,
+            reset_button: self.reset_button,
+            trainbus: self.trainbus_slot.map(|slot| {
+                TrainBusPeriferie::new(
+                    PeripheryKind::Other {
+                        short_name: "BMA".to_string(),
+                        full_name: "Brandmeldeanlage".to_string(),
+                    },
+                    slot,
+                )
+            }),
+            lm_alarm: self.lm_alarm,
+            snd_buzzer: self.snd_buzzer,
+            alarm: false,
+            silenced: false,
+        }
+    }
+}
+
+/// Fire alarm system with one smoke detector per compartment.
+///
+/// # Example
+///
+/// ```rust
+/// let mut fire_alarm = FireAlarm::builder()
+///     .detector("A", Some("Scenario_Fire_A"))
+///     .detector_light("L_Fire_A")
+///     .detector("B", Some("Scenario_Fire_B"))
+///     .detector_light("L_Fire_B")
+///     .reset_button("Fire_Reset", "Fire_Reset", Some(CockpitSide::A))
+///     .trainbus(12)
+///     .alarm_light("L_Fire")
+///     .snd_buzzer("Snd_Fire_Buzzer")
+///     .build();
+///
+/// // In the main loop:
+/// fire_alarm.tick(voltage > 0.5);
+/// ```
+pub struct FireAlarm {
+    /// Smoke detectors of the compartments
+    pub detectors: Vec<SmokeDetector>,
+
+    threshold: f32,
+    rise_rate: f32,
+    clear_rate: f32,
+
+    reset_button: Option<PushButton>,
+    trainbus: Option<TrainBusPeriferie>,
+
+    lm_alarm: Light,
+    snd_buzzer: Sound,
+
+    /// Whether any detector has latched the alarm
+    pub alarm: bool,
+    /// Whether the buzzer is silenced for the current alarm
+    pub silenced: bool,
+}
+
+impl FireAlarm {
+    /// Creates a new fire alarm builder.
+    pub fn builder() -> FireAlarmBuilder {
+        FireAlarmBuilder {
+            detectors: Vec::new(),
+            threshold: 0.3,
+            rise_rate: 0.05,
+            clear_rate: 0.02,
+            reset_button: None,
+            trainbus_slot: None,
+            lm_alarm: Light::new(None),
+            snd_buzzer: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the detectors and the alarm outputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the fire alarm is supplied, the detectors keep their
+    ///   latched state without supply
+    pub fn tick(&mut self, power: bool) {
+        let reset = match &mut self.reset_button {
+            Some(button) => {
+                button.tick();
+                button.is_just_pressed() && power
+            }
+            None => false,
+        };
+
+        for detector in &mut self.detectors {
+            detector.update(self.rise_rate, self.clear_rate, self.threshold);
+        }
+
+        if reset {
+            if self.alarm && !self.silenced {
+                self.silenced = true;
+            } else {
+                self.reset();
+            }
+        }
+
+        self.alarm = self.detectors.iter().any(|d| d.detected);
+        if !self.alarm {
+            self.silenced = false;
+        }
+
+        for detector in &mut self.detectors {
+            detector
+                .lm_detector
+                .set_brightness((power && detector.detected) as u8 as f32);
+        }
+
+        self.lm_alarm
+            .set_brightness((power && self.alarm) as u8 as f32);
+        self.snd_buzzer
+            .start_stop(power && self.alarm && !self.silenced);
+
+        let fault = self.fault();
+        if let Some(trainbus) = &mut self.trainbus {
+            if power {
+                trainbus.tick();
+                trainbus.set_defect(fault);
+            }
+        }
+    }
+
+    /// Starts or stops a fire in a compartment, e.g. from a scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `compartment` - Name of the compartment
+    /// * `value` - `true` to start the fire, `false` to put it out
+    pub fn trigger(&mut self, compartment: &str, value: bool) {
+        if let Some(detector) = self
+            .detectors
+            .iter_mut()
+            .find(|d| d.compartment == compartment)
+        {
+            detector.triggered = value;
+        }
+    }
+
+    /// Resets all detectors whose smoke has cleared below the threshold.
+    pub fn reset(&mut self) {
+        for detector in &mut self.detectors {
+            if detector.smoke < self.threshold {
+                detector.detected = false;
+            }
+        }
+    }
+
+    fn fault(&self) -> PeripheryFault {
+        let compartments: Vec<&str> = self
+            .detectors
+            .iter()
+            .filter(|d| d.detected)
+            .map(|d| d.compartment.as_str())
+            .collect();
+
+        if compartments.is_empty() {
+            PeripheryFault::Ok
+        } else {
+            PeripheryFault::Undefined {
+                short_text: "BRAND".to_string(),
+                long_text: format!("Brandalarm {}", compartments.join(", ")),
+            }
+        }
+    }
+}
+
+//=================================================================
+// Extinguisher
+//=================================================================
+
+/// State of a fire extinguisher.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExtinguisherState {
+    /// Extinguisher is stowed in its holder
+    #[default]
+    InHolder,
+    /// Extinguisher is taken out of the holder
+    Removed,
+    /// Extinguisher is missing, e.g. not restocked after use
+    Missing,
+}
+
+pub struct ExtinguisherBuilder {
+    cab_side: Option<CockpitSide>,
+
+    anim: Animation,
+    key_take: KeyEvent,
+    key_discharge: KeyEvent,
+
+    visibility: Option<Visiblility>,
+    inventory_var: Option<String>,
+
+    removal_time: f32,
+    discharge_time: f32,
+
+    snd_take: Sound,
+    snd_discharge: Sound,
+}
+
+impl ExtinguisherBuilder {
+    /// Key event discharging the removed extinguisher while pressed.
+    pub fn event_discharge(mut self, name: impl Into<String>) -> Self {
+        self.key_discharge = KeyEvent::new(Some(&name.into()), self.cab_side);
+        self
+    }
+
+    /// Visibility of the extinguisher, hidden while it is missing.
+    pub fn visibility(mut self, name: impl Into<String>) -> Self {
+        self.visibility = Some(Visiblility::new_visible(name));
+        self
+    }
+
+    /// Bool variable reporting whether a usable extinguisher is in the holder.
+    pub fn inventory_var(mut self, name: impl Into<String>) -> Self {
+        self.inventory_var = Some(name.into());
+        self
+    }
+
+    /// Time in seconds of the removal animation.
+    pub fn removal_time(mut self, removal_time: f32) -> Self {
+        self.removal_time = removal_time;
+        self
+    }
+
+    /// Time in seconds until a full extinguisher is empty.
+    pub fn discharge_time(mut self, discharge_time: f32) -> Self {
+        self.discharge_time = discharge_time;
+        self
+    }
+
+    /// Sound of taking out and putting back the extinguisher.
+    pub fn snd_take(mut self, name: impl Into<String>) -> Self {
+        self.snd_take = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sound while the extinguisher is discharged.
+    pub fn snd_discharge(mut self, name: impl Into<String>) -> Self {
+        self.snd_discharge = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> Extinguisher {
+        let mut extinguisher = Extinguisher {
+            anim: self.anim,
+            key_take: self.key_take,
+            key_discharge: self.key_discharge,
+            visibility: self.visibility,
+            inventory_var: self.inventory_var,
+            removal_time: self.removal_time,
+            discharge_time: self.discharge_time,
+            snd_take: self.snd_take,
+            snd_discharge: self.snd_discharge,
+            state: ExtinguisherState::InHolder,
+            pos: 0.0,
+            charge: 1.0,
+        };
+        extinguisher.update_inventory();
+        extinguisher
+    }
+}
+
+/// Fire extinguisher prop with removal animation and inventory tracking.
+///
+/// # Example
+///
+/// ```rust
+/// let mut extinguisher = Extinguisher::builder("Extinguisher_A", "Extinguisher_Take_A", Some(CockpitSide::A))
+///     .event_discharge("Extinguisher_Use")
+///     .visibility("Extinguisher_A_Vis")
+///     .inventory_var("Inventory_Extinguisher_A")
+///     .build();
+///
+/// // In the main loop:
+/// extinguisher.tick();
+/// if extinguisher.discharging() {
+///     fire_alarm.trigger("A", false);
+/// }
+/// ```
+pub struct Extinguisher {
+    anim: Animation,
+    key_take: KeyEvent,
+    key_discharge: KeyEvent,
+
+    visibility: Option<Visiblility>,
+    inventory_var: Option<String>,
+
+    removal_time: f32,
+    discharge_time: f32,
+
+    snd_take: Sound,
+    snd_discharge: Sound,
+
+    /// Current state of the extinguisher
+    pub state: ExtinguisherState,
+    /// Position of the removal animation from 0.0 (in holder) to 1.0 (removed)
+    pub pos: f32,
+    /// Remaining extinguishing agent from 0.0 to 1.0
+    pub charge: f32,
+}
+
+impl Extinguisher {
+    /// Creates a new extinguisher builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Removal animation, 0.0 in holder and 1.0 removed
+    /// * `event_name` - Key event taking out and putting back the extinguisher
+    /// * `cab_side` - Optional cab side for key event handling
+    pub fn builder(
+        animation_name: impl Into<String>,
+        event_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> ExtinguisherBuilder {
+        ExtinguisherBuilder {
+            cab_side,
+            anim: Animation::new(Some(&animation_name.into())),
+            key_take: KeyEvent::new(Some(&event_name.into()), cab_side),
+            key_discharge: KeyEvent::new(None, None),
+            visibility: None,
+            inventory_var: None,
+            removal_time: 1.0,
+            discharge_time: 15.0,
+            snd_take: Sound::new_simple(None),
+            snd_discharge: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the extinguisher.
+    pub fn tick(&mut self) {
+        if self.key_take.is_just_pressed() {
+            match self.state {
+                ExtinguisherState::InHolder => {
+                    self.state = ExtinguisherState::Removed;
+                    self.snd_take.start();
+                }
+                ExtinguisherState::Removed if self.pos >= 1.0 => {
+                    self.state = ExtinguisherState::InHolder;
+                    self.snd_take.start();
+                }
+                _ => {}
+            }
+        }
+
+        let target = (self.state == ExtinguisherState::Removed) as u8 as f32;
+        if self.pos != target {
+            let step = delta() / self.removal_time.max(0.01);
+            self.pos = if target > self.pos {
+                (self.pos + step).min(target)
+            } else {
+                (self.pos - step).max(target)
+            };
+            self.anim.set(self.pos);
+        }
+
+        let discharging = self.discharging();
+        if discharging {
+            self.charge = (self.charge - delta() / self.discharge_time.max(0.01)).max(0.0);
+        }
+        self.snd_discharge.start_stop(discharging);
+
+        self.update_inventory();
+    }
+
+    /// Whether the extinguisher is currently discharged.
+    #[must_use]
+    pub fn discharging(&mut self) -> bool {
+        self.state == ExtinguisherState::Removed
+            && self.pos >= 1.0
+            && self.charge > 0.0
+            && self.key_discharge.is_pressed()
+    }
+
+    /// Whether a usable extinguisher is in the holder.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.state == ExtinguisherState::InHolder && self.charge > 0.0
+    }
+
+    /// Removes the extinguisher from the vehicle, e.g. after it was used up.
+    pub fn take_away(&mut self) {
+        self.state = ExtinguisherState::Missing;
+        self.pos = 0.0;
+        self.anim.set(self.pos);
+        self.update_inventory();
+    }
+
+    /// Puts a new, full extinguisher into the holder.
+    pub fn restock(&mut self) {
+        self.state = ExtinguisherState::InHolder;
+        self.pos = 0.0;
+        self.charge = 1.0;
+        self.anim.set(self.pos);
+        self.update_inventory();
+    }
+
+    fn update_inventory(&mut self) {
+        if let Some(vis) = &mut self.visibility {
+            vis.set_visbility(self.state != ExtinguisherState::Missing);
+        }
+        if let Some(var) = &self.inventory_var {
+            set_var(var, self.is_complete());
+        }
+    }
+}
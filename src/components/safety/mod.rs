@@ -0,0 +1,3 @@
+pub mod fire_alarm;
+pub mod redbox;
+pub mod video_surveillance;
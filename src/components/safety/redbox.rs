@@ -0,0 +1,395 @@
+//! Short-distance recorder ("Kurzwegregistriergerät", Redbox, KWR).
+//!
+//! The [`Redbox`] is registered on the TrainBus as [`PeripheryKind::Redbox`]
+//! and records the operating state of the vehicle as [`RedboxTelegram`]s:
+//! every few metres of travel and additionally whenever the emergency brake
+//! or the doors change. Only the most recent telegrams are kept in a ring
+//! buffer.
+//!
+//! So that an abrupt end of the session cannot destroy the record, each
+//! telegram is stored in its own variable slot instead of rewriting the whole
+//! buffer, and the running number is only advanced after the slot is written.
+//! Slots which cannot be read back after a restart are reported as a fault to
+//! the TrainBus until the memory is cleared.
+//!
+//! In read-out mode the display variable lists the recent telegrams, newest
+//! first, and can be scrolled.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        general::delta,
+        variable::{get_var, set_var},
+    },
+    elements::tech::buttons::PushButton,
+    management::trainbus::{PeripheryFault, PeripheryKind, TrainBusPeriferie},
+};
+
+/// Operating state handed to the recorder.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RedboxInput {
+    /// Speed in m/s
+    pub speed: f32,
+    /// Traction demand from 0.0 to 1.0
+    pub traction: f32,
+    /// Brake demand from 0.0 to 1.0
+    pub brake: f32,
+    /// Whether the emergency brake is applied
+    pub emergency_brake: bool,
+    /// Whether all doors are closed
+    pub doors_closed: bool,
+}
+
+/// Recorded telegram.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedboxTelegram {
+    /// Running number of the telegram
+    pub number: u64,
+    /// Operating time in seconds
+    pub time: f64,
+    /// Travelled distance in m
+    pub distance_m: f64,
+    /// Speed in km/h
+    pub speed_kmh: f32,
+    /// Traction demand from 0.0 to 1.0
+    pub traction: f32,
+    /// Brake demand from 0.0 to 1.0
+    pub brake: f32,
+    /// Whether the emergency brake was applied
+    pub emergency_brake: bool,
+    /// Whether all doors were closed
+    pub doors_closed: bool,
+}
+
+impl RedboxTelegram {
+    /// Line of the read-out list.
+    pub fn text(&self) -> String {
+        format!(
+            "#{} {:.0} m {:.0} km/h F{:.0} B{:.0}{}{}",
+            self.number,
+            self.distance_m,
+            self.speed_kmh,
+            self.traction * 100.0,
+            self.brake * 100.0,
+            if self.emergency_brake { " NB" } else { "" },
+            if self.doors_closed { "" } else { " T" },
+        )
+    }
+}
+
+pub struct RedboxBuilder {
+    slot_index: i32,
+    capacity: usize,
+    interval: f64,
+    persist_var: Option<String>,
+
+    readout_button: Option<PushButton>,
+    scroll_buttons: Option<(PushButton, PushButton)>,
+    readout_lines: usize,
+    display_var: Option<String>,
+}
+
+impl RedboxBuilder {
+    /// Number of telegrams kept, older ones are overwritten.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Distance in m after which a telegram is recorded while moving.
+    pub fn interval(mut self, interval: f64) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Prefix of the variables storing the telegrams. Each slot is stored in
+    /// `<var_name>_<slot>` and the running number in `<var_name>_Number`.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// Toggle button switching the read-out mode.
+    pub fn readout_button(mut self, button: PushButton) -> Self {
+        self.readout_button = Some(button);
+        self
+    }
+
+    /// Push buttons scrolling the read-out list to older and newer telegrams.
+    pub fn scroll_buttons(mut self, older: PushButton, newer: PushButton) -> Self {
+        self.scroll_buttons = Some((older, newer));
+        self
+    }
+
+    /// String variable showing the read-out list.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the variable
+    /// * `lines` - Number of telegrams shown at once
+    pub fn display_var(mut self, name: impl Into<String>, lines: usize) -> Self {
+        self.display_var = Some(name.into());
+        self.readout_lines = lines.max(1);
+        self
+    }
+
+    pub fn build(self) -> Redbox {
+        let mut telegrams = VecDeque::new();
+        let mut next_number = 0;
+        let mut corrupted = false;
+
+        if let Some(var_name) = &self.persist_var {
+            next_number = get_var::<i32>(&format!("{var_name}_Number")).max(0) as u64;
+
+            let mut stored = Vec::new();
+            for slot in 0..self.capacity {
+                let json = get_var::<String>(&format!("{var_name}_{slot}"));
+                if json.is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<RedboxTelegram>(&json) {
+                    Ok(telegram) => stored.push(telegram),
+                    Err(_) => corrupted = true,
+                }
+            }
+
+            // Ein geschriebener Slot zählt auch, wenn die Nummer nicht mehr weitergesetzt wurde
+            stored.sort_by_key(|t| t.number);
+            if let Some(last) = stored.last() {
+                next_number = next_number.max(last.number + 1);
+            }
+            telegrams.extend(stored);
+        }
+        let (time, distance) = telegrams
+            .back()
+            .map_or((0.0, 0.0), |t| (t.time, t.distance_m));
+
+        Redbox {
+            trainbus: TrainBusPeriferie::new(PeripheryKind::Redbox, self.slot_index),
+            capacity: self.capacity,
+            interval: self.interval,
+            persist_var: self.persist_var,
+            readout_button: self.readout_button,
+            scroll_buttons: self.scroll_buttons,
+            readout_lines: self.readout_lines,
+            display_var: self.display_var,
+            telegrams,
+            next_number,
+            time,
+            distance,
+            distance_last: None,
+            input_last: None,
+            display_last: None,
+            readout: false,
+            scroll: 0,
+            corrupted,
+        }
+    }
+}
+
+/// Recorder of the recent operating state with crash-safe storage.
+///
+/// # Example
+///
+/// ```rust
+/// let mut redbox = Redbox::builder(20)
+///     .capacity(200)
+///     .interval(5.0)
+///     .persist("KWR")
+///     .display_var("KWR_Display", 8)
+///     .build();
+///
+/// // In the main loop:
+/// redbox.tick(
+///     voltage > 0.5,
+///     RedboxInput {
+///         speed: v_ground(),
+///         traction: controller.traction(),
+///         brake: controller.brake(),
+///         emergency_brake: emergency_brake.active,
+///         doors_closed: doors.doors_closed,
+///     },
+/// );
+/// ```
+pub struct Redbox {
+    trainbus: TrainBusPeriferie,
+    capacity: usize,
+    interval: f64,
+    persist_var: Option<String>,
+
+    readout_button: Option<PushButton>,
+    scroll_buttons: Option<(PushButton, PushButton)>,
+    readout_lines: usize,
+    display_var: Option<String>,
+
+    telegrams: VecDeque<RedboxTelegram>,
+    next_number: u64,
+    time: f64,
+    distance: f64,
+    distance_last: Option<f64>,
+    input_last: Option<RedboxInput>,
+    display_last: Option<String>,
+
+    /// Whether the read-out mode is active
+    pub readout: bool,
+    /// Telegrams scrolled back from the newest in read-out mode
+    pub scroll: usize,
+    /// Whether stored telegrams were lost at the last start
+    pub corrupted: bool,
+}
+
+impl Redbox {
+    /// Creates a new Redbox builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - Slot index with which the Redbox registers on the TrainBus
+    pub fn builder(slot_index: i32) -> RedboxBuilder {
+        RedboxBuilder {
+            slot_index,
+            capacity: 100,
+            interval: 10.0,
+            persist_var: None,
+            readout_button: None,
+            scroll_buttons: None,
+            readout_lines: 5,
+            display_var: None,
+        }
+    }
+
+    /// Recorded telegrams, newest first.
+    pub fn recent(&self) -> impl Iterator<Item = &RedboxTelegram> {
+        self.telegrams.iter().rev()
+    }
+
+    /// Switches the read-out mode, e.g. from an MFD.
+    pub fn set_readout(&mut self, readout: bool) {
+        self.readout = readout;
+        self.scroll = 0;
+    }
+
+    /// Clears the memory after the read-out and acknowledges lost telegrams.
+    pub fn clear(&mut self) {
+        if let Some(var_name) = &self.persist_var {
+            for slot in 0..self.capacity {
+                set_var(&format!("{var_name}_{slot}"), String::new());
+            }
+        }
+        self.telegrams.clear();
+        self.scroll = 0;
+        self.corrupted = false;
+    }
+
+    /// Records the operating state and updates the read-out.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the Redbox is supplied, nothing is recorded without
+    /// * `input` - Operating state to record
+    pub fn tick(&mut self, power: bool, input: RedboxInput) {
+        if let Some(button) = &mut self.readout_button {
+            button.tick();
+            if button.is_just_pressed() {
+                self.readout = !self.readout;
+                self.scroll = 0;
+            }
+        }
+        if let Some((older, newer)) = &mut self.scroll_buttons {
+            older.tick();
+            newer.tick();
+            if older.is_just_pressed() {
+                self.scroll = (self.scroll + 1).min(self.telegrams.len().saturating_sub(1));
+            }
+            if newer.is_just_pressed() {
+                self.scroll = self.scroll.saturating_sub(1);
+            }
+        }
+
+        if !power {
+            self.input_last = None;
+            self.update_display(String::new());
+            return;
+        }
+
+        self.time += delta() as f64;
+        self.distance += (input.speed.abs() * delta()) as f64;
+
+        let moved = self
+            .distance_last
+            .is_none_or(|last| self.distance - last >= self.interval);
+        let changed = self.input_last.is_none_or(|last| {
+            last.emergency_brake != input.emergency_brake || last.doors_closed != input.doors_closed
+        });
+        if (moved && input.speed.abs() > 0.1) || changed {
+            self.record(input);
+        }
+        self.input_last = Some(input);
+
+        self.trainbus.tick();
+        self.trainbus.set_defect(if self.corrupted {
+            PeripheryFault::Undefined {
+                short_text: "Datenverlust".to_string(),
+                long_text: "Gespeicherte Telegramme unlesbar".to_string(),
+            }
+        } else {
+            PeripheryFault::Ok
+        });
+
+        let text = if self.readout {
+            self.recent()
+                .skip(self.scroll)
+                .take(self.readout_lines)
+                .map(RedboxTelegram::text)
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            String::new()
+        };
+        self.update_display(text);
+    }
+
+    //===================================================================
+    // Internal
+    //===================================================================
+
+    fn record(&mut self, input: RedboxInput) {
+        let telegram = RedboxTelegram {
+            number: self.next_number,
+            time: self.time,
+            distance_m: self.distance,
+            speed_kmh: input.speed.abs() * 3.6,
+            traction: input.traction,
+            brake: input.brake,
+            emergency_brake: input.emergency_brake,
+            doors_closed: input.doors_closed,
+        };
+
+        if let Some(var_name) = &self.persist_var {
+            // Erst den Slot schreiben, dann die Nummer weitersetzen
+            let slot = (self.next_number % self.capacity as u64) as usize;
+            if let Ok(json) = serde_json::to_string(&telegram) {
+                set_var(&format!("{var_name}_{slot}"), json);
+            }
+            set_var(&format!("{var_name}_Number"), (self.next_number + 1) as i32);
+        }
+
+        self.telegrams.push_back(telegram);
+        while self.telegrams.len() > self.capacity {
+            self.telegrams.pop_front();
+        }
+        self.next_number += 1;
+        self.distance_last = Some(self.distance);
+    }
+
+    fn update_display(&mut self, text: String) {
+        if self.display_last.as_ref() != Some(&text) {
+            if let Some(var_name) = &self.display_var {
+                set_var(var_name, text.clone());
+            }
+            self.display_last = Some(text);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! Video surveillance of the passenger compartments ("Videoüberwachung", VIDEO).
+//!
+//! The [`VideoSurveillance`] records the images of one [`Camera`] per saloon
+//! and shows one of them on the monitor in the cab. The driver steps through
+//! the channels with the channel buttons. While the doors are released, the
+//! monitor switches to the cameras watching the door areas and cycles through
+//! them; after the doors are closed it returns to the channel chosen before.
+//!
+//! The monitor writes the index of the shown camera into a variable, which the
+//! model uses to switch the monitor texture. A lamp indicates the running
+//! recording. Failed cameras show no signal and are reported to the TrainBus
+//! as [`PeripheryKind::VideoSystem`].
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::{
+    api::{
+        general::delta,
+        light::Light,
+        variable::{get_var, set_var},
+    },
+    elements::tech::buttons::PushButton,
+    management::trainbus::{PeripheryFault, PeripheryKind, TrainBusPeriferie},
+};
+
+//=================================================================
+// Camera
+//=================================================================
+
+/// Camera of a single saloon.
+#[derive(Debug)]
+pub struct Camera {
+    /// Name of the saloon, shown on the monitor
+    pub saloon: String,
+    /// Whether the camera watches a door area
+    pub door_area: bool,
+    fault_var: Option<String>,
+
+    /// Whether the camera has failed
+    pub fault: bool,
+}
+
+impl Camera {
+    fn update(&mut self) {
+        if let Some(var) = &self.fault_var {
+            self.fault = get_var::<bool>(var);
+        }
+    }
+}
+
+//=================================================================
+// VideoSurveillance
+//=================================================================
+
+pub struct VideoSurveillanceBuilder {
+    slot_index: i32,
+    cameras: Vec<Camera>,
+
+    next_button: Option<PushButton>,
+    prev_button: Option<PushButton>,
+    door_cycle_time: f32,
+
+    channel_var: Option<String>,
+    name_var: Option<String>,
+    lm_recording: Light,
+}
+
+impl VideoSurveillanceBuilder {
+    /// Adds the camera of a saloon.
+    ///
+    /// * `saloon` - Name of the saloon
+    /// * `door_area` - Whether the camera watches a door area
+    /// * `fault_var` - Bool variable with which a scenario lets the camera fail
+    pub fn camera(
+        mut self,
+        saloon: impl Into<String>,
+        door_area: bool,
+        fault_var: Option<&str>,
+    ) -> Self {
+        self.cameras.push(Camera {
+            saloon: saloon.into(),
+            door_area,
+            fault_var: fault_var.map(str::to_string),
+            fault: false,
+        });
+        self
+    }
+
+    /// Push buttons stepping to the next and the previous channel.
+    pub fn channel_buttons(
+        mut self,
+        next: (impl Into<String>, impl Into<String>),
+        prev: (impl Into<String>, impl Into<String>),
+        cab_side: Option<CockpitSide>,
+    ) -> Self {
+        self.next_button = Some(PushButton::builder(next.0, next.1, cab_side).build());
+        self.prev_button = Some(PushButton::builder(prev.0, prev.1, cab_side).build());
+        self
+    }
+
+    /// Time in seconds each door camera is shown while the doors are released.
+    pub fn door_cycle_time(mut self, time: f32) -> Self {
+        self.door_cycle_time = time;
+        self
+    }
+
+    /// Integer variable with the index of the shown camera, -1 without signal.
+    pub fn channel_var(mut self, name: impl Into<String>) -> Self {
+        self.channel_var = Some(name.into());
+        self
+    }
+
+    /// String variable with the caption of the monitor.
+    pub fn name_var(mut self, name: impl Into<String>) -> Self {
+        self.name_var = Some(name.into());
+        self
+    }
+
+    /// Lamp lit while the system is recording.
+    pub fn lm_recording(mut self, name: impl Into<String>) -> Self {
+        self.lm_recording = Light::new(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> VideoSurveillance {
+        VideoSurveillance {
+            trainbus: TrainBusPeriferie::new(PeripheryKind::VideoSystem, self.slot_index),
+            cameras: self.cameras,
+            next_button: self.next_button,
+            prev_button: self.prev_button,
+            door_cycle_time: self.door_cycle_time,
+            channel_var: self.channel_var,
+            name_var: self.name_var,
+            lm_recording: self.lm_recording,
+            cycle_timer: 0.0,
+            released_last: false,
+            shown_last: None,
+            channel: 0,
+            shown: None,
+            door_mode: false,
+            recording: false,
+        }
+    }
+}
+
+/// Video surveillance with saloon cameras, cab monitor and recording.
+///
+/// # Example
+///
+/// ```rust
+/// let mut video = VideoSurveillance::builder(70)
+///     .camera("A vorne", true, Some("Scenario_Cam_1"))
+///     .camera("A hinten", true, None)
+///     .camera("B", false, None)
+///     .channel_buttons(("Video_Plus", "Video_Plus"), ("Video_Minus", "Video_Minus"), Some(CockpitSide::A))
+///     .channel_var("Video_Kanal")
+///     .name_var("Video_Text")
+///     .lm_recording("L_Video_Rec")
+///     .build();
+///
+/// // In the main loop:
+/// video.tick(voltage > 0.5, doors_released);
+/// ```
+pub struct VideoSurveillance {
+    trainbus: TrainBusPeriferie,
+    cameras: Vec<Camera>,
+
+    next_button: Option<PushButton>,
+    prev_button: Option<PushButton>,
+    door_cycle_time: f32,
+
+    channel_var: Option<String>,
+    name_var: Option<String>,
+    lm_recording: Light,
+
+    cycle_timer: f32,
+    released_last: bool,
+    shown_last: Option<Option<usize>>,
+
+    /// Channel selected by the driver
+    pub channel: usize,
+    /// Camera shown on the monitor, `None` when the monitor is dark
+    pub shown: Option<usize>,
+    /// Whether the monitor cycles through the door cameras
+    pub door_mode: bool,
+    /// Whether the system is recording
+    pub recording: bool,
+}
+
+impl VideoSurveillance {
+    /// Creates a new video surveillance builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - Slot index with which the system registers on the TrainBus
+    pub fn builder(slot_index: i32) -> VideoSurveillanceBuilder {
+        VideoSurveillanceBuilder {
+            slot_index,
+            cameras: Vec::new(),
+            next_button: None,
+            prev_button: None,
+            door_cycle_time: 4.0,
+            channel_var: None,
+            name_var: None,
+            lm_recording: Light::new(None),
+        }
+    }
+
+    /// All cameras in the order they were added.
+    pub fn cameras(&self) -> &[Camera] {
+        &self.cameras
+    }
+
+    /// Lets a camera fail or repairs it, e.g. from a scenario.
+    pub fn set_fault(&mut self, saloon: &str, fault: bool) {
+        if let Some(camera) = self.cameras.iter_mut().find(|c| c.saloon == saloon) {
+            camera.fault = fault;
+        }
+    }
+
+    /// Updates the cameras, the monitor and the recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the system is supplied
+    /// * `doors_released` - Whether the doors of the car are released
+    pub fn tick(&mut self, power: bool, doors_released: bool) {
+        let mut step = 0;
+        if let Some(button) = &mut self.next_button {
+            button.tick();
+            if button.is_just_pressed() {
+                step += 1;
+            }
+        }
+        if let Some(button) = &mut self.prev_button {
+            button.tick();
+            if button.is_just_pressed() {
+                step -= 1;
+            }
+        }
+
+        for camera in &mut self.cameras {
+            camera.update();
+        }
+
+        if !power || self.cameras.is_empty() {
+            self.door_mode = false;
+            self.released_last = false;
+            self.recording = false;
+            self.lm_recording.set_brightness(0.0);
+            self.show(None);
+            return;
+        }
+
+        if step != 0 {
+            // Manuelle Wahl beendet die automatische Türumschaltung
+            self.door_mode = false;
+            let count = self.cameras.len() as i32;
+            self.channel = (self.channel as i32 + step).rem_euclid(count) as usize;
+        }
+
+        let door_cameras: Vec<usize> = (0..self.cameras.len())
+            .filter(|&i| self.cameras[i].door_area)
+            .collect();
+
+        // Bei Türfreigabe auf die Türkameras umschalten, bis die Türen wieder zu sind
+        if doors_released && !self.released_last {
+            self.door_mode = !door_cameras.is_empty();
+            self.cycle_timer = 0.0;
+        }
+        if !doors_released {
+            self.door_mode = false;
+        }
+        self.released_last = doors_released;
+
+        let camera = if self.door_mode {
+            self.cycle_timer += delta();
+            let index = (self.cycle_timer / self.door_cycle_time.max(0.1)) as usize;
+            door_cameras[index % door_cameras.len()]
+        } else {
+            self.channel.min(self.cameras.len() - 1)
+        };
+        self.show(Some(camera));
+
+        self.recording = self.cameras.iter().any(|c| !c.fault);
+        self.lm_recording
+            .set_brightness(self.recording as u8 as f32);
+
+        let fault = self.fault();
+        self.trainbus.tick();
+        self.trainbus.set_defect(fault);
+    }
+
+    //===================================================================
+    // Internal
+    //===================================================================
+
+    fn show(&mut self, camera: Option<usize>) {
+        let camera = camera.filter(|&i| !self.cameras[i].fault);
+        self.shown = camera;
+
+        if self.shown_last == Some(camera) {
+            return;
+        }
+        self.shown_last = Some(camera);
+
+        if let Some(var) = &self.channel_var {
+            set_var(var, camera.map(|i| i as i32).unwrap_or(-1));
+        }
+        if let Some(var) = &self.name_var {
+            let text = match camera {
+                Some(i) => format!("Kamera {} - {}", i + 1, self.cameras[i].saloon),
+                None => "Kein Signal".to_string(),
+            };
+            set_var(var, text);
+        }
+    }
+
+    fn fault(&self) -> PeripheryFault {
+        let failed = self.cameras.iter().filter(|c| c.fault).count();
+        if failed == 0 {
+            PeripheryFault::Ok
+        } else if failed == self.cameras.len() {
+            PeripheryFault::Defect
+        } else {
+            PeripheryFault::Disrupted
+        }
+    }
+}
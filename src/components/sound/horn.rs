@@ -0,0 +1,217 @@
+//! Pneumatic horn with low and high tone.
+//!
+//! Each tone of the [`Horn`] has a start, a loop and a release sample. The
+//! tones are blown with key events of the cab and can sound together. The
+//! horn draws its air from the main reservoir and gets quieter and lower
+//! when the pressure drops.
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::{
+    api::{general::delta, key_event::KeyEvent, sound::Sound},
+    components::pneumatics::compressor::MainReservoir,
+};
+
+/// Tone of a multi-tone horn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HornTone {
+    Low,
+    High,
+}
+
+#[derive(Debug)]
+struct HornVoice {
+    keys: Vec<KeyEvent>,
+
+    blowing: bool,
+
+    snd_start: Sound,
+    snd_loop: Sound,
+    snd_release: Sound,
+}
+
+impl HornVoice {
+    fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            blowing: false,
+            snd_start: Sound::new_simple(None),
+            snd_loop: Sound::new_simple(None),
+            snd_release: Sound::new_simple(None),
+        }
+    }
+
+    fn tick(&mut self, request: bool, supply: f32) {
+        let mut pressed = request;
+        for key in &mut self.keys {
+            pressed = key.is_pressed() || pressed;
+        }
+
+        let blowing = pressed && supply > 0.0;
+
+        if blowing != self.blowing {
+            if blowing {
+                self.snd_release.stop();
+                self.snd_start.start();
+                self.snd_loop.start();
+            } else {
+                self.snd_start.stop();
+                self.snd_loop.stop();
+                self.snd_release.start();
+            }
+            self.blowing = blowing;
+        }
+
+        self.snd_loop.update_volume(supply);
+        self.snd_loop.update_pitch(0.9 + 0.1 * supply);
+    }
+}
+
+pub struct HornBuilder {
+    cab_side: Option<CockpitSide>,
+    min_pressure: f32,
+    nominal_pressure: f32,
+    air_consumption: f32,
+
+    low: HornVoice,
+    high: HornVoice,
+}
+
+impl HornBuilder {
+    /// Adds a key event blowing the given tone.
+    pub fn key(mut self, tone: HornTone, name: impl Into<String>) -> Self {
+        let key = KeyEvent::new(Some(&name.into()), self.cab_side);
+        self.voice(tone).keys.push(key);
+        self
+    }
+
+    /// Sets the samples of a tone. The loop sound gets its volume and pitch
+    /// from the air supply.
+    pub fn sounds(
+        mut self,
+        tone: HornTone,
+        sound_start_name: impl Into<String>,
+        sound_loop_name: impl Into<String>,
+        sound_loop_vol_name: impl Into<String>,
+        sound_loop_pitch_name: impl Into<String>,
+        sound_release_name: impl Into<String>,
+    ) -> Self {
+        let voice = self.voice(tone);
+        voice.snd_start = Sound::new_simple(Some(&sound_start_name.into()));
+        voice.snd_loop = Sound::new(
+            Some(&sound_loop_name.into()),
+            Some(&sound_loop_vol_name.into()),
+            Some(&sound_loop_pitch_name.into()),
+        );
+        voice.snd_release = Sound::new_simple(Some(&sound_release_name.into()));
+        self
+    }
+
+    /// Pressure in bar below which the horn is silent and the pressure at
+    /// which it sounds at full volume.
+    pub fn pressure_range(mut self, min_pressure: f32, nominal_pressure: f32) -> Self {
+        self.min_pressure = min_pressure;
+        self.nominal_pressure = nominal_pressure;
+        self
+    }
+
+    /// Air consumption in bar·litre per second and tone.
+    pub fn air_consumption(mut self, air_consumption: f32) -> Self {
+        self.air_consumption = air_consumption;
+        self
+    }
+
+    fn voice(&mut self, tone: HornTone) -> &mut HornVoice {
+        match tone {
+            HornTone::Low => &mut self.low,
+            HornTone::High => &mut self.high,
+        }
+    }
+
+    pub fn build(self) -> Horn {
+        Horn {
+            min_pressure: self.min_pressure,
+            nominal_pressure: self.nominal_pressure,
+            air_consumption: self.air_consumption,
+            low: self.low,
+            high: self.high,
+            request_low: false,
+            request_high: false,
+        }
+    }
+}
+
+/// Two-tone pneumatic horn.
+///
+/// # Example
+///
+/// ```rust
+/// let mut horn = Horn::builder(Some(CockpitSide::A))
+///     .key(HornTone::Low, "Horn_Low")
+///     .key(HornTone::High, "Horn_High")
+///     .sounds(HornTone::Low, "Snd_Horn_Low_Start", "Snd_Horn_Low", "Snd_Horn_Low_Vol", "Snd_Horn_Low_Pitch", "Snd_Horn_Low_End")
+///     .sounds(HornTone::High, "Snd_Horn_High_Start", "Snd_Horn_High", "Snd_Horn_High_Vol", "Snd_Horn_High_Pitch", "Snd_Horn_High_End")
+///     .build();
+///
+/// // In the main loop:
+/// horn.tick(cab_active, &mut reservoir);
+/// ```
+#[derive(Debug)]
+pub struct Horn {
+    min_pressure: f32,
+    nominal_pressure: f32,
+    air_consumption: f32,
+
+    low: HornVoice,
+    high: HornVoice,
+
+    /// Blows the low tone in addition to the key events, e.g. for a warning
+    pub request_low: bool,
+    /// Blows the high tone in addition to the key events
+    pub request_high: bool,
+}
+
+impl Horn {
+    /// Creates a new horn builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `cab_side` - Cab the key events belong to
+    pub fn builder(cab_side: Option<CockpitSide>) -> HornBuilder {
+        HornBuilder {
+            cab_side,
+            min_pressure: 3.0,
+            nominal_pressure: 8.0,
+            air_consumption: 5.0,
+            low: HornVoice::new(),
+            high: HornVoice::new(),
+        }
+    }
+
+    /// Whether the given tone is blowing.
+    pub fn is_blowing(&self, tone: HornTone) -> bool {
+        match tone {
+            HornTone::Low => self.low.blowing,
+            HornTone::High => self.high.blowing,
+        }
+    }
+
+    /// Updates the horn and draws its air from the reservoir.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the horn valves can be operated, e.g. cab active
+    /// * `reservoir` - Main reservoir supplying the horn
+    pub fn tick(&mut self, enabled: bool, reservoir: &mut MainReservoir) {
+        let supply = ((reservoir.pressure - self.min_pressure)
+            / (self.nominal_pressure - self.min_pressure))
+            .clamp(0.0, 1.0)
+            * enabled as u8 as f32;
+
+        self.low.tick(self.request_low, supply);
+        self.high.tick(self.request_high, supply);
+
+        let tones = self.low.blowing as u8 + self.high.blowing as u8;
+        reservoir.take(self.air_consumption * tones as f32 * delta());
+    }
+}
@@ -1,7 +1,6 @@
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
-use crate::api::{animation::Animation, key_event::KeyEvent, sound::Sound};
+use crate::api::{animation::Animation, general::delta, key_event::KeyEvent, sound::Sound};
 
 #[derive(Debug, PartialEq)]
 pub enum ThrottleMode {
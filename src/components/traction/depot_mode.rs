@@ -0,0 +1,225 @@
+//! Depot mode ("Betriebshofmodus") with washing program.
+//!
+//! The [`DepotMode`] limits the speed for movements in the depot by reducing
+//! the traction and braking above the limit, keeps the doors closed and
+//! switches on the shunting signal of the whole consist over
+//! the `CouplerShuntingSignal` coupling line. In the washing program the
+//! vehicle passes the washing plant at a constant creep speed: as long as the
+//! driver demands traction, the speed is held automatically.
+
+use lotus_extra::vehicle::CockpitSide;
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{key_event::KeyEvent, light::Light},
+    management::enums::door_enums::DoorTarget,
+    messages::{
+        coupling_handler::UniversalCouplingLine, gt6n_coupling_messages::CouplerShuntingSignal,
+    },
+};
+
+pub struct DepotModeBuilder {
+    speed_limit: f32,
+    wash_speed: f32,
+    hold_gain: f32,
+
+    key_depot: KeyEvent,
+    key_wash: KeyEvent,
+
+    lm_depot: Light,
+    lm_wash: Light,
+}
+
+impl DepotModeBuilder {
+    /// Maximum speed in depot mode in km/h.
+    pub fn speed_limit(mut self, speed_limit: f32) -> Self {
+        self.speed_limit = speed_limit;
+        self
+    }
+
+    /// Creep speed of the washing program in km/h and the gain of the speed
+    /// hold in demand per km/h of deviation. The gain also sets the brake
+    /// demand above the speed limit.
+    pub fn wash_speed(mut self, wash_speed: f32, hold_gain: f32) -> Self {
+        self.wash_speed = wash_speed;
+        self.hold_gain = hold_gain;
+        self
+    }
+
+    /// Key events toggling the depot mode and the washing program.
+    pub fn events(
+        mut self,
+        depot_name: impl Into<String>,
+        wash_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> Self {
+        self.key_depot = KeyEvent::new(Some(&depot_name.into()), cab_side);
+        self.key_wash = KeyEvent::new(Some(&wash_name.into()), cab_side);
+        self
+    }
+
+    /// Indicator lamps of the depot mode and the washing program.
+    pub fn lamps(mut self, depot_name: impl Into<String>, wash_name: impl Into<String>) -> Self {
+        self.lm_depot = Light::new(Some(&depot_name.into()));
+        self.lm_wash = Light::new(Some(&wash_name.into()));
+        self
+    }
+
+    pub fn build(self) -> DepotMode {
+        DepotMode {
+            speed_limit: self.speed_limit,
+            wash_speed: self.wash_speed,
+            hold_gain: self.hold_gain,
+            key_depot: self.key_depot,
+            key_wash: self.key_wash,
+            lm_depot: self.lm_depot,
+            lm_wash: self.lm_wash,
+            shunting_line: UniversalCouplingLine::new(CouplerShuntingSignal, (true, true)),
+            active: false,
+            washing: false,
+            doors_locked: false,
+            door_target: DoorTarget::Close,
+            shunting_signal: false,
+            traction: 0.0,
+            brake: 0.0,
+        }
+    }
+}
+
+/// Depot mode with speed limit, door lock and washing program.
+///
+/// # Example
+///
+/// ```rust
+/// let mut depot = DepotMode::builder()
+///     .speed_limit(15.0)
+///     .wash_speed(3.0, 0.2)
+///     .events("Depot_Mode", "Wash_Program", Some(CockpitSide::A))
+///     .lamps("L_Depot", "L_Wash")
+///     .build();
+///
+/// // In on_message:
+/// depot.on_message(msg);
+///
+/// // In the main loop:
+/// depot.tick(voltage > 0.7, v_ground(), controller.traction(), controller.brake(), door_target);
+/// doors.tick(power, v_ground(), depot.door_target, side_target, false, &requests);
+/// traction.tick(depot.traction);
+/// ```
+pub struct DepotMode {
+    speed_limit: f32,
+    wash_speed: f32,
+    hold_gain: f32,
+
+    key_depot: KeyEvent,
+    key_wash: KeyEvent,
+
+    lm_depot: Light,
+    lm_wash: Light,
+
+    shunting_line: UniversalCouplingLine<bool, CouplerShuntingSignal>,
+
+    /// Whether the depot mode is active
+    pub active: bool,
+    /// Whether the washing program is active
+    pub washing: bool,
+    /// Whether the doors have to be kept closed
+    pub doors_locked: bool,
+    /// Door command after the door lock
+    pub door_target: DoorTarget,
+    /// Whether the shunting signal of the consist is on
+    pub shunting_signal: bool,
+    /// Limited traction demand from 0.0 to 1.0
+    pub traction: f32,
+    /// Brake demand from 0.0 to 1.0 including the speed hold
+    pub brake: f32,
+}
+
+impl DepotMode {
+    /// Creates a new depot mode builder.
+    pub fn builder() -> DepotModeBuilder {
+        DepotModeBuilder {
+            speed_limit: 15.0,
+            wash_speed: 3.0,
+            hold_gain: 0.2,
+            key_depot: KeyEvent::new(None, None),
+            key_wash: KeyEvent::new(None, None),
+            lm_depot: Light::new(None),
+            lm_wash: Light::new(None),
+        }
+    }
+
+    /// Passes messages to the `CouplerShuntingSignal` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.shunting_line.on_message(msg);
+    }
+
+    /// Switches the depot mode on or off, switching it off ends the washing
+    /// program.
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+        if !active {
+            self.washing = false;
+        }
+    }
+
+    /// Starts or ends the washing program, it is only available in depot mode.
+    pub fn set_washing(&mut self, washing: bool) {
+        self.washing = washing && self.active;
+    }
+
+    /// Updates the mode and limits the demands.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the vehicle control is supplied
+    /// * `speed` - Current speed in m/s
+    /// * `traction` - Traction demand of the driver from 0.0 to 1.0
+    /// * `brake` - Brake demand of the driver from 0.0 to 1.0
+    /// * `door_target` - Door command of the driver
+    pub fn tick(
+        &mut self,
+        power: bool,
+        speed: f32,
+        traction: f32,
+        brake: f32,
+        door_target: DoorTarget,
+    ) {
+        if self.key_depot.is_just_pressed() && power {
+            self.set_active(!self.active);
+        }
+        if self.key_wash.is_just_pressed() && power {
+            self.set_washing(!self.washing);
+        }
+
+        let v_kmh = speed.abs() * 3.6;
+
+        (self.traction, self.brake) = if !power || !self.active {
+            (traction, brake)
+        } else if self.washing && traction > 0.0 && brake <= 0.0 {
+            // Geschwindigkeitshaltung in der Waschanlage
+            let demand = (self.wash_speed - v_kmh) * self.hold_gain;
+            (demand.clamp(0.0, 1.0), (-demand).clamp(0.0, 1.0))
+        } else {
+            // Zugkraft zur Höchstgeschwindigkeit hin zurücknehmen, darüber bremsen
+            let limit = (self.speed_limit - v_kmh).clamp(0.0, 1.0);
+            let overspeed = ((v_kmh - self.speed_limit) * self.hold_gain).clamp(0.0, 1.0);
+            (traction.min(limit), brake.max(overspeed))
+        };
+
+        self.doors_locked = power && self.active;
+        self.door_target = if self.doors_locked {
+            door_target.min(DoorTarget::Close)
+        } else {
+            door_target
+        };
+
+        self.shunting_line.update_local(power && self.active);
+        self.shunting_signal = self.shunting_line.get_value();
+
+        self.lm_depot
+            .set_brightness((power && self.active) as u8 as f32);
+        self.lm_wash
+            .set_brightness((power && self.washing) as u8 as f32);
+    }
+}
@@ -1,5 +1,8 @@
 pub mod continous_throttle_lever;
+pub mod depot_mode;
 pub mod odometer;
 pub mod railbrakes;
+pub mod reverser_logic;
+pub mod sander;
 pub mod speedometer;
 pub mod staged_throttle_lever;
@@ -1,6 +1,7 @@
-use lotus_script::time::delta;
-
-use crate::{api::animation::Animation, elements::tech::dekaden::DecadeSwitch};
+use crate::{
+    api::{animation::Animation, general::delta},
+    elements::tech::dekaden::DecadeSwitch,
+};
 
 pub struct Odometer {
     frac: f32,
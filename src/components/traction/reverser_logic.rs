@@ -0,0 +1,146 @@
+//! Interlocking of the reverser.
+//!
+//! The [`ReverserLogic`] takes the positions of the reverser levers of both
+//! cabs and decides which direction is accepted:
+//!
+//! - the lever can only leave neutral with the master key inserted
+//! - the key can only be removed with the lever in neutral
+//! - the direction can only be changed at standstill
+//!
+//! The accepted direction is sent over the `CouplerReverser` coupling line.
+//! If two cabs in the consist select opposite directions, a fault is latched
+//! and the direction is dropped until all levers are back in neutral.
+
+use lotus_extra::vehicle::CockpitSide;
+use lotus_script::prelude::Message;
+
+use crate::{
+    management::enums::traction_enums::DirectionOfDriving,
+    messages::{coupling_handler::UniversalCouplingLine, gt6n_coupling_messages::CouplerReverser},
+};
+
+/// Reverser interlocking of a car with two cabs.
+///
+/// # Example
+///
+/// ```rust
+/// let mut reverser = ReverserLogic::new(0.1);
+///
+/// // In on_message:
+/// reverser.on_message(msg);
+///
+/// // In the main loop:
+/// reverser.tick(lever_a.into(), lever_b.into(), key_a, key_b, v_ground());
+/// lever_a_locked = reverser.lever_locked(CockpitSide::A);
+/// let direction = reverser.direction;
+/// ```
+pub struct ReverserLogic {
+    standstill_speed: f32,
+
+    reverser_line: UniversalCouplingLine<DirectionOfDriving, CouplerReverser>,
+
+    local: DirectionOfDriving,
+    key_inserted: (bool, bool),
+    lever_neutral: (bool, bool),
+
+    /// Direction accepted for the consist, relative to the car
+    pub direction: DirectionOfDriving,
+    /// Latched fault of conflicting directions
+    pub fault: bool,
+}
+
+impl ReverserLogic {
+    /// Creates the reverser logic.
+    ///
+    /// # Arguments
+    ///
+    /// * `standstill_speed` - Speed in m/s below which the direction may be changed
+    #[must_use]
+    pub fn new(standstill_speed: f32) -> Self {
+        Self {
+            standstill_speed,
+            reverser_line: UniversalCouplingLine::new(CouplerReverser, (true, true)),
+            local: DirectionOfDriving::default(),
+            key_inserted: (false, false),
+            lever_neutral: (true, true),
+            direction: DirectionOfDriving::default(),
+            fault: false,
+        }
+    }
+
+    /// Passes messages to the `CouplerReverser` line.
+    pub fn on_message(&mut self, msg: Message) {
+        self.reverser_line.on_message(msg);
+    }
+
+    /// Whether the lever of the given cab is locked in neutral.
+    pub fn lever_locked(&self, cab: CockpitSide) -> bool {
+        match cab {
+            CockpitSide::A => !self.key_inserted.0,
+            CockpitSide::B => !self.key_inserted.1,
+        }
+    }
+
+    /// Whether the master key of the given cab may be removed.
+    pub fn key_removable(&self, cab: CockpitSide) -> bool {
+        match cab {
+            CockpitSide::A => self.lever_neutral.0,
+            CockpitSide::B => self.lever_neutral.1,
+        }
+    }
+
+    /// Updates the interlocking.
+    ///
+    /// # Arguments
+    ///
+    /// * `lever_a` - Reverser lever in cab A, forward is the direction of cab A
+    /// * `lever_b` - Reverser lever in cab B, forward is the direction of cab B
+    /// * `key_a` - Whether the master key is inserted in cab A
+    /// * `key_b` - Whether the master key is inserted in cab B
+    /// * `speed` - Current speed in m/s
+    pub fn tick(
+        &mut self,
+        lever_a: DirectionOfDriving,
+        lever_b: DirectionOfDriving,
+        key_a: bool,
+        key_b: bool,
+        speed: f32,
+    ) {
+        self.key_inserted = (key_a, key_b);
+        self.lever_neutral = (lever_a.is_none(), lever_b.is_none());
+
+        // Ohne Schlüssel bleibt der Hebel in Nullstellung
+        let lever_a = if key_a {
+            lever_a
+        } else {
+            DirectionOfDriving::default()
+        };
+        let lever_b = if key_b {
+            lever_b.flip()
+        } else {
+            DirectionOfDriving::default()
+        };
+        let requested = lever_a.merge(&lever_b);
+
+        // Fahrtrichtungswechsel nur im Stillstand
+        let standstill = speed.abs() < self.standstill_speed;
+        if standstill || requested.is_none() || requested == self.local {
+            self.local = requested;
+        }
+
+        self.reverser_line.update_local(self.local);
+        let consist = self.reverser_line.get_value();
+
+        if consist.is_both() {
+            self.fault = true;
+        } else if consist.is_none() {
+            self.fault = false;
+        }
+
+        self.direction = if self.fault {
+            DirectionOfDriving::default()
+        } else {
+            consist
+        };
+    }
+}
@@ -0,0 +1,275 @@
+//! Sanding equipment.
+//!
+//! The [`Sander`] sands the axles of each bogie from its own hopper. The
+//! request is shared with the consist over the `CouplerSanding` coupling
+//! line, and the sanded axles get a better adhesion in the simulation. In
+//! frost the nozzles freeze up unless their heater is switched on, a
+//! blocked nozzle sands no more until it has thawed.
+
+use lotus_script::prelude::Message;
+
+use crate::{
+    api::{axis::ApiRailAxis, general::delta, light::Light, sound::Sound},
+    messages::{
+        coupling_handler::UniversalCouplingLine, gt6n_coupling_messages::CouplerSanding,
+        pandemist_messages::AdhesionInfo,
+    },
+};
+
+const NOZZLE_FREEZE_TIME: f32 = 120.0;
+
+/// Sand hopper of one bogie.
+pub struct SandHopper {
+    axles: Vec<ApiRailAxis>,
+    capacity: f32,
+
+    freeze_timer: f32,
+
+    /// Sand in the hopper in kg
+    pub level: f32,
+    /// Whether the nozzle is blocked
+    pub blocked: bool,
+}
+
+impl SandHopper {
+    /// Level relative to the capacity from 0.0 to 1.0.
+    pub fn level_rel(&self) -> f32 {
+        self.level / self.capacity
+    }
+}
+
+pub struct SanderBuilder {
+    consumption: f32,
+    low_level: f32,
+    heater_power: f32,
+
+    hoppers: Vec<SandHopper>,
+
+    auto_sanding: bool,
+
+    lm_low_sand: Light,
+    lm_heater: Light,
+    snd_valve_on: Sound,
+    snd_valve_off: Sound,
+}
+
+impl SanderBuilder {
+    /// Adds a hopper sanding the axles of a bogie.
+    ///
+    /// # Arguments
+    ///
+    /// * `bogie_index` - Index of the bogie
+    /// * `axle_count` - Number of sanded axles
+    /// * `capacity` - Capacity of the hopper in kg, the hopper starts full
+    pub fn add_hopper(mut self, bogie_index: usize, axle_count: usize, capacity: f32) -> Self {
+        self.hoppers.push(SandHopper {
+            axles: (0..axle_count)
+                .map(|axle_index| ApiRailAxis::new(axle_index, bogie_index))
+                .collect(),
+            capacity,
+            freeze_timer: 0.0,
+            level: capacity,
+            blocked: false,
+        });
+        self
+    }
+
+    /// Sand consumption per hopper in kg/s.
+    pub fn consumption(mut self, consumption: f32) -> Self {
+        self.consumption = consumption;
+        self
+    }
+
+    /// Relative level below which the low sand warning is lit.
+    pub fn low_level(mut self, low_level: f32) -> Self {
+        self.low_level = low_level;
+        self
+    }
+
+    /// Electrical power of the nozzle heaters in kW.
+    pub fn heater_power(mut self, heater_power: f32) -> Self {
+        self.heater_power = heater_power;
+        self
+    }
+
+    pub fn low_sand_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_low_sand = Light::new(Some(&name.into()));
+        self
+    }
+
+    pub fn heater_light(mut self, name: impl Into<String>) -> Self {
+        self.lm_heater = Light::new(Some(&name.into()));
+        self
+    }
+
+    /// Sands automatically while the rail condition reports slipping or
+    /// sliding wheels.
+    pub fn auto_sanding(mut self, auto_sanding: bool) -> Self {
+        self.auto_sanding = auto_sanding;
+        self
+    }
+
+    /// Sounds of the sanding solenoid valve.
+    pub fn valve_sounds(
+        mut self,
+        sound_on_name: impl Into<String>,
+        sound_off_name: impl Into<String>,
+    ) -> Self {
+        self.snd_valve_on = Sound::new_simple(Some(&sound_on_name.into()));
+        self.snd_valve_off = Sound::new_simple(Some(&sound_off_name.into()));
+        self
+    }
+
+    pub fn build(self) -> Sander {
+        Sander {
+            consumption: self.consumption,
+            low_level: self.low_level,
+            heater_power: self.heater_power,
+            hoppers: self.hoppers,
+            sanding_line: UniversalCouplingLine::new(CouplerSanding, (true, true)),
+            auto_sanding: self.auto_sanding,
+            wheel_slip: false,
+            sanding: false,
+            low_sand: false,
+            load_kw: 0.0,
+            lm_low_sand: self.lm_low_sand,
+            lm_heater: self.lm_heater,
+            snd_valve_on: self.snd_valve_on,
+            snd_valve_off: self.snd_valve_off,
+        }
+    }
+}
+
+/// Sanding equipment with one hopper per bogie.
+///
+/// # Example
+///
+/// ```rust
+/// let mut sander = Sander::builder()
+///     .add_hopper(0, 2, 40.0)
+///     .add_hopper(2, 2, 40.0)
+///     .low_sand_light("L_Sand_Low")
+///     .valve_sounds("Snd_Sand_On", "Snd_Sand_Off")
+///     .build();
+///
+/// // In on_message:
+/// sander.on_message(msg);
+///
+/// // In the main loop:
+/// sander.tick(voltage > 0.7, sand_button.is_pressed(), nozzle_heater_switch, outside_temp);
+/// ```
+pub struct Sander {
+    consumption: f32,
+    low_level: f32,
+    heater_power: f32,
+
+    /// Hoppers in the order they were added
+    pub hoppers: Vec<SandHopper>,
+
+    sanding_line: UniversalCouplingLine<bool, CouplerSanding>,
+
+    auto_sanding: bool,
+    wheel_slip: bool,
+
+    /// Whether the sanding valves are open
+    pub sanding: bool,
+    /// Whether a hopper is below the low level
+    pub low_sand: bool,
+    /// Electrical load of the nozzle heaters in kW
+    pub load_kw: f32,
+
+    lm_low_sand: Light,
+    lm_heater: Light,
+    snd_valve_on: Sound,
+    snd_valve_off: Sound,
+}
+
+impl Sander {
+    pub fn builder() -> SanderBuilder {
+        SanderBuilder {
+            consumption: 0.4,
+            low_level: 0.2,
+            heater_power: 0.2,
+            hoppers: Vec::new(),
+            auto_sanding: false,
+            lm_low_sand: Light::new(None),
+            lm_heater: Light::new(None),
+            snd_valve_on: Sound::new_simple(None),
+            snd_valve_off: Sound::new_simple(None),
+        }
+    }
+
+    /// Passes messages to the `CouplerSanding` line and receives the
+    /// [`AdhesionInfo`] for the automatic sanding.
+    pub fn on_message(&mut self, msg: Message) {
+        msg.handle::<AdhesionInfo>(|m| {
+            self.wheel_slip = m.slip || m.slide;
+            Ok(())
+        })
+        .expect("AdhesionInfo: message handle failed");
+
+        self.sanding_line.on_message(msg);
+    }
+
+    /// Fills all hoppers, e.g. in the depot.
+    pub fn refill(&mut self) {
+        for hopper in &mut self.hoppers {
+            hopper.level = hopper.capacity;
+        }
+    }
+
+    /// Updates the sanding.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the sanding control is supplied
+    /// * `request` - Local sanding request, e.g. the sanding button
+    /// * `heater` - Whether the nozzle heaters are switched on
+    /// * `outside_temperature` - Outside temperature in °C
+    pub fn tick(&mut self, power: bool, request: bool, heater: bool, outside_temperature: f32) {
+        let request = request || (self.auto_sanding && self.wheel_slip);
+        self.sanding_line.update_local(power && request);
+        let sanding = power && self.sanding_line.get_value();
+
+        if sanding != self.sanding {
+            if sanding {
+                self.snd_valve_on.start();
+            } else {
+                self.snd_valve_off.start();
+            }
+            self.sanding = sanding;
+        }
+
+        let heating = power && heater;
+        self.load_kw = heating as u8 as f32 * self.heater_power * self.hoppers.len() as f32;
+
+        self.low_sand = false;
+        for hopper in &mut self.hoppers {
+            // Düsen frieren bei Frost ohne Heizung zu
+            if outside_temperature < 0.0 && !heating {
+                hopper.freeze_timer += delta();
+            } else {
+                hopper.freeze_timer = (hopper.freeze_timer - 2.0 * delta()).max(0.0);
+            }
+            if hopper.freeze_timer > NOZZLE_FREEZE_TIME {
+                hopper.blocked = true;
+            } else if hopper.freeze_timer <= 0.0 {
+                hopper.blocked = false;
+            }
+
+            let flowing = sanding && !hopper.blocked && hopper.level > 0.0;
+            if flowing {
+                hopper.level = (hopper.level - self.consumption * delta()).max(0.0);
+            }
+            for axle in &hopper.axles {
+                axle.set_sanding(flowing);
+            }
+
+            self.low_sand = self.low_sand || hopper.level_rel() < self.low_level;
+        }
+
+        self.lm_low_sand
+            .set_brightness((power && self.low_sand) as u8 as f32);
+        self.lm_heater.set_brightness(heating as u8 as f32);
+    }
+}
@@ -5,9 +5,14 @@
 //! to create realistic movement dynamics.
 
 use lotus_extra::math::PiecewiseLinearFunction;
-use lotus_script::time::delta;
 
-use crate::api::animation::Animation;
+use crate::{
+    api::{animation::Animation, general::delta, random::gen_f32},
+    components::traction::odometer::Odometer,
+};
+
+/// Hysteresis of the overspeed contact in km/h.
+const OVERSPEED_HYSTERESIS: f32 = 2.0;
 
 /// Builder for creating a `Speedometer` with customizable parameters.
 ///
@@ -44,6 +49,11 @@ pub struct SpeedometerBuilder {
 
     needle_pos_anim: Animation,
     arrow_pos_anim: Animation,
+
+    drive_delay: f32,
+    calibration_error: f32,
+    odometer: Option<Odometer>,
+    overspeed_limit: Option<f32>,
 }
 
 impl SpeedometerBuilder {
@@ -169,6 +179,69 @@ impl SpeedometerBuilder {
         self
     }
 
+    /// Sets the lag of the mechanical speedometer drive.
+    ///
+    /// The speed passed to [`Speedometer::tick_speed`] reaches the needle
+    /// delayed by a first order lag with this time constant.
+    ///
+    /// # Parameters
+    ///
+    /// * `delay` - Time constant of the drive in seconds
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn drive_delay(mut self, delay: f32) -> Self {
+        self.drive_delay = delay;
+        self
+    }
+
+    /// Sets the maximum calibration error of the device.
+    ///
+    /// On build a random error within `-max_error..=max_error` is chosen, so
+    /// every speedometer shows slightly different values.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_error` - Maximum relative error, e.g. `0.03` for 3 %
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn calibration_error(mut self, max_error: f32) -> Self {
+        self.calibration_error = max_error;
+        self
+    }
+
+    /// Adds counter rollers driven by the speedometer.
+    ///
+    /// # Parameters
+    ///
+    /// * `init_km` - Initial kilometre reading
+    /// * `animation_names` - Animations of the rollers, see [`Odometer::new`]
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn odometer(mut self, init_km: f64, animation_names: Vec<&str>) -> Self {
+        self.odometer = Some(Odometer::new(init_km, animation_names));
+        self
+    }
+
+    /// Adds an overspeed contact closing when the needle passes the limit.
+    ///
+    /// # Parameters
+    ///
+    /// * `limit` - Speed in km/h at which the contact closes
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn overspeed_contact(mut self, limit: f32) -> Self {
+        self.overspeed_limit = Some(limit);
+        self
+    }
+
     /// Constructs the final `Speedometer` instance with the configured parameters.
     ///
     /// # Returns
@@ -202,6 +275,18 @@ impl SpeedometerBuilder {
 
             needle_pos_anim: self.needle_pos_anim,
             arrow_pos_anim: self.arrow_pos_anim,
+
+            drive_delay: self.drive_delay,
+            calibration_factor: 1.0
+                + if self.calibration_error > 0.0 {
+                    gen_f32(-self.calibration_error..=self.calibration_error)
+                } else {
+                    0.0
+                },
+            driven_speed: 0.0,
+            odometer: self.odometer,
+            overspeed_limit: self.overspeed_limit,
+            overspeed: false,
         }
     }
 }
@@ -251,6 +336,15 @@ pub struct Speedometer {
 
     needle_pos_anim: Animation,
     arrow_pos_anim: Animation,
+
+    drive_delay: f32,
+    calibration_factor: f32,
+    driven_speed: f32,
+    odometer: Option<Odometer>,
+    overspeed_limit: Option<f32>,
+
+    /// State of the overspeed contact
+    pub overspeed: bool,
 }
 
 impl Speedometer {
@@ -289,9 +383,67 @@ impl Speedometer {
             arrow_pos_anim: Animation::new(None),
             needle_path: None,
             arrow_path: None,
+            drive_delay: 0.0,
+            calibration_error: 0.0,
+            odometer: None,
+            overspeed_limit: None,
         }
     }
 
+    /// Updates the speedometer from the vehicle speed.
+    ///
+    /// The speed is converted to km/h, falsified by the calibration error and
+    /// delayed by the drive before it is passed to [`Speedometer::tick`]. The
+    /// counter rollers and the overspeed contact are updated as well.
+    ///
+    /// # Parameters
+    ///
+    /// * `speed` - Vehicle speed in m/s
+    /// * `target_arrow` - The target position for the arrow
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut speedometer = Speedometer::builder("needle")
+    ///     .force(40.0)
+    ///     .friction(12.0)
+    ///     .drive_delay(0.4)
+    ///     .calibration_error(0.03)
+    ///     .overspeed_contact(72.0)
+    ///     .build();
+    ///
+    /// speedometer.tick_speed(v_ground(), 0.0);
+    /// ```
+    pub fn tick_speed(&mut self, speed: f32, target_arrow: f32) {
+        let shown_speed = speed.abs() * self.calibration_factor;
+
+        if self.drive_delay > 0.0 {
+            self.driven_speed +=
+                (shown_speed - self.driven_speed) * (delta() / self.drive_delay).min(1.0);
+        } else {
+            self.driven_speed = shown_speed;
+        }
+
+        if let Some(odometer) = &mut self.odometer {
+            odometer.tick(shown_speed);
+        }
+
+        self.tick(self.driven_speed * 3.6, target_arrow);
+
+        if let Some(limit) = self.overspeed_limit {
+            if self.needle_pos > limit {
+                self.overspeed = true;
+            } else if self.needle_pos < limit - OVERSPEED_HYSTERESIS {
+                self.overspeed = false;
+            }
+        }
+    }
+
+    /// Returns the kilometre reading of the counter rollers, if present.
+    pub fn km(&mut self) -> Option<f64> {
+        self.odometer.as_mut().map(|odometer| odometer.get_km_h())
+    }
+
     /// Updates the speedometer physics simulation for one frame.
     ///
     /// This method should be called once per frame to update the positions of both
@@ -1,9 +1,8 @@
 use std::collections::HashMap;
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
-use crate::api::{animation::Animation, key_event::KeyEvent, sound::Sound};
+use crate::api::{animation::Animation, general::delta, key_event::KeyEvent, sound::Sound};
 
 #[derive(Debug, PartialEq)]
 enum MovementMode {
@@ -0,0 +1,3 @@
+pub mod pzb;
+pub mod speed_supervisor;
+pub mod track_magnets;
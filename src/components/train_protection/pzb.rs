@@ -0,0 +1,387 @@
+//! Intermittent train protection ("PZB").
+//!
+//! The [`Pzb`] evaluates the track magnets passed by the vehicle and
+//! supervises the speed afterwards:
+//!
+//! - 1000 Hz: the driver has to acknowledge within the acknowledgment window,
+//!   afterwards the speed is supervised with a curve over time for 1250 m
+//! - 500 Hz: the speed is supervised with a curve over distance for 250 m
+//! - 2000 Hz: immediate forced braking, unless the command button is held
+//!
+//! If the vehicle stands nearly still during a supervision, the unit switches
+//! to the restrictive mode with lower limits. A forced braking is released with
+//! the release button at standstill. Distances are taken from the
+//! [`Odometry`](crate::management::odometry::Odometry).
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::{
+    api::{general::delta, key_event::KeyEvent, light::Light, sound::Sound},
+    components::train_protection::track_magnets::TrackMagnetListener,
+    elements::std::relays::FlasherRelay,
+    management::enums::train_protection_enums::{PzbTrainType, TrackMagnet},
+};
+
+const ACK_WINDOW: f32 = 4.0;
+const MONITOR_1000_LENGTH: f64 = 1250.0;
+const FREE_1000_DISTANCE: f64 = 700.0;
+const MONITOR_500_LENGTH: f64 = 250.0;
+const CURVE_500_LENGTH: f64 = 153.0;
+const COMMAND_SPEED: f32 = 40.0;
+const STANDSTILL_SPEED: f32 = 10.0;
+const RESTRICTIVE_TIME: f32 = 15.0;
+const RELEASE_SPEED: f32 = 0.5;
+
+struct Limits {
+    v_max: f32,
+    v_1000_start: f32,
+    v_1000_end: f32,
+    t_1000: f32,
+    v_500_start: f32,
+    v_500_end: f32,
+    v_1000_restrictive: f32,
+    v_500_restrictive: f32,
+}
+
+impl From<PzbTrainType> for Limits {
+    fn from(train_type: PzbTrainType) -> Self {
+        let (v_max, v_1000_end, t_1000, v_500_start, v_500_end) = match train_type {
+            PzbTrainType::O => (165.0, 85.0, 23.0, 65.0, 45.0),
+            PzbTrainType::M => (125.0, 70.0, 29.0, 50.0, 35.0),
+            PzbTrainType::U => (105.0, 55.0, 38.0, 40.0, 25.0),
+        };
+        Self {
+            v_max,
+            v_1000_start: v_max,
+            v_1000_end,
+            t_1000,
+            v_500_start,
+            v_500_end,
+            v_1000_restrictive: 45.0,
+            v_500_restrictive: 25.0,
+        }
+    }
+}
+
+struct Monitor1000 {
+    mark: f64,
+    timer: f32,
+}
+
+pub struct PzbBuilder {
+    limits: Limits,
+
+    key_ack: KeyEvent,
+    key_release: KeyEvent,
+    key_free: KeyEvent,
+    key_command: KeyEvent,
+
+    lm_train_type: Light,
+    lm_1000: Light,
+    lm_500: Light,
+    lm_command: Light,
+
+    snd_horn: Sound,
+    snd_forced_brake: Sound,
+}
+
+impl PzbBuilder {
+    /// Key events of the acknowledgment ("Wachsam"), release ("Befreiung"),
+    /// free ("Frei") and command ("Befehl") buttons.
+    pub fn keys(
+        mut self,
+        ack_name: impl Into<String>,
+        release_name: impl Into<String>,
+        free_name: impl Into<String>,
+        command_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> Self {
+        self.key_ack = KeyEvent::new(Some(&ack_name.into()), cab_side);
+        self.key_release = KeyEvent::new(Some(&release_name.into()), cab_side);
+        self.key_free = KeyEvent::new(Some(&free_name.into()), cab_side);
+        self.key_command = KeyEvent::new(Some(&command_name.into()), cab_side);
+        self
+    }
+
+    /// Cab signal lamps. The train type lamp blinks in restrictive mode.
+    pub fn lamps(
+        mut self,
+        train_type_name: impl Into<String>,
+        hz1000_name: impl Into<String>,
+        hz500_name: impl Into<String>,
+        command_name: impl Into<String>,
+    ) -> Self {
+        self.lm_train_type = Light::new(Some(&train_type_name.into()));
+        self.lm_1000 = Light::new(Some(&hz1000_name.into()));
+        self.lm_500 = Light::new(Some(&hz500_name.into()));
+        self.lm_command = Light::new(Some(&command_name.into()));
+        self
+    }
+
+    /// Horn sounding until a 1000 Hz magnet is acknowledged and the sound of
+    /// the forced braking.
+    pub fn sounds(
+        mut self,
+        sound_horn_name: impl Into<String>,
+        sound_forced_brake_name: impl Into<String>,
+    ) -> Self {
+        self.snd_horn = Sound::new_simple(Some(&sound_horn_name.into()));
+        self.snd_forced_brake = Sound::new_simple(Some(&sound_forced_brake_name.into()));
+        self
+    }
+
+    pub fn build(self) -> Pzb {
+        Pzb {
+            limits: self.limits,
+            key_ack: self.key_ack,
+            key_release: self.key_release,
+            key_free: self.key_free,
+            key_command: self.key_command,
+            ack_timer: None,
+            monitor_1000: None,
+            monitor_500: None,
+            standstill_timer: 0.0,
+            distance: 0.0,
+            restrictive: false,
+            forced_brake: false,
+            speed_limit: 0.0,
+            blink_restrictive: FlasherRelay::new(1.0, 0.5, 0.0),
+            lm_train_type: self.lm_train_type,
+            lm_1000: self.lm_1000,
+            lm_500: self.lm_500,
+            lm_command: self.lm_command,
+            snd_horn: self.snd_horn,
+            snd_forced_brake: self.snd_forced_brake,
+        }
+    }
+}
+
+/// PZB unit with magnet evaluation and speed supervision.
+///
+/// # Example
+///
+/// ```rust
+/// let mut pzb = Pzb::builder(PzbTrainType::M)
+///     .keys("PZB_Wachsam", "PZB_Befreiung", "PZB_Frei", "PZB_Befehl", Some(CockpitSide::A))
+///     .lamps("L_PZB_70", "L_PZB_1000", "L_PZB_500", "L_PZB_Befehl")
+///     .sounds("Snd_PZB_Hupe", "Snd_PZB_Zwangsbremsung")
+///     .build();
+///
+/// // In the main loop:
+/// magnets.tick(&mut [&mut pzb]);
+/// odometry.tick(v_ground());
+/// pzb.tick(voltage > 0.7, v_ground(), odometry.distance);
+/// let emergency = pzb.forced_brake;
+/// ```
+pub struct Pzb {
+    limits: Limits,
+
+    key_ack: KeyEvent,
+    key_release: KeyEvent,
+    key_free: KeyEvent,
+    key_command: KeyEvent,
+
+    ack_timer: Option<f32>,
+    monitor_1000: Option<Monitor1000>,
+    monitor_500: Option<f64>,
+    standstill_timer: f32,
+    distance: f64,
+
+    /// Whether the restrictive mode is active
+    pub restrictive: bool,
+    /// Whether a forced braking is demanded
+    pub forced_brake: bool,
+    /// Currently supervised speed limit in km/h
+    pub speed_limit: f32,
+
+    blink_restrictive: FlasherRelay,
+
+    lm_train_type: Light,
+    lm_1000: Light,
+    lm_500: Light,
+    lm_command: Light,
+
+    snd_horn: Sound,
+    snd_forced_brake: Sound,
+}
+
+impl Pzb {
+    /// Creates a new PZB builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `train_type` - Train type selecting the supervision limits
+    pub fn builder(train_type: PzbTrainType) -> PzbBuilder {
+        PzbBuilder {
+            limits: train_type.into(),
+            key_ack: KeyEvent::new(None, None),
+            key_release: KeyEvent::new(None, None),
+            key_free: KeyEvent::new(None, None),
+            key_command: KeyEvent::new(None, None),
+            lm_train_type: Light::new(None),
+            lm_1000: Light::new(None),
+            lm_500: Light::new(None),
+            lm_command: Light::new(None),
+            snd_horn: Sound::new_simple(None),
+            snd_forced_brake: Sound::new_simple(None),
+        }
+    }
+
+    /// Changes the train type, e.g. with the train type switch.
+    pub fn set_train_type(&mut self, train_type: PzbTrainType) {
+        self.limits = train_type.into();
+    }
+
+    fn trigger_forced_brake(&mut self) {
+        if !self.forced_brake {
+            self.snd_forced_brake.start();
+            self.forced_brake = true;
+        }
+    }
+
+    fn curve(start: f32, end: f32, progress: f32) -> f32 {
+        start - (start - end) * progress.clamp(0.0, 1.0)
+    }
+
+    /// Updates the supervision.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the unit is supplied, the lamps stay dark without
+    /// * `speed` - Current speed in m/s
+    /// * `distance` - Distance from the odometry in m
+    pub fn tick(&mut self, power: bool, speed: f32, distance: f64) {
+        self.distance = distance;
+        let v_kmh = speed.abs() * 3.6;
+
+        // Wachsamkeitstaste: nur innerhalb des Zeitfensters nach dem 1000-Hz-Magneten
+        let ack = self.key_ack.is_just_pressed();
+        if let Some(timer) = &mut self.ack_timer {
+            *timer += delta();
+            if ack {
+                self.ack_timer = None;
+                self.snd_horn.stop();
+            } else if *timer > ACK_WINDOW {
+                self.ack_timer = None;
+                self.snd_horn.stop();
+                self.trigger_forced_brake();
+            }
+        }
+
+        if let Some(monitor) = &mut self.monitor_1000 {
+            monitor.timer += delta();
+            let travelled = distance - monitor.mark;
+            let free = travelled > FREE_1000_DISTANCE && self.key_free.is_just_pressed();
+            if travelled > MONITOR_1000_LENGTH || free {
+                self.monitor_1000 = None;
+            }
+        }
+
+        if let Some(mark) = self.monitor_500 {
+            if distance - mark > MONITOR_500_LENGTH {
+                self.monitor_500 = None;
+            }
+        }
+
+        // Restriktive Überwachung nach Halt
+        let monitoring = self.monitor_1000.is_some() || self.monitor_500.is_some();
+        if monitoring && v_kmh < STANDSTILL_SPEED {
+            self.standstill_timer += delta();
+            if self.standstill_timer > RESTRICTIVE_TIME {
+                self.restrictive = true;
+            }
+        } else {
+            self.standstill_timer = 0.0;
+        }
+        if !monitoring {
+            self.restrictive = false;
+        }
+
+        let limits = &self.limits;
+        let mut limit = limits.v_max;
+        if let Some(monitor) = &self.monitor_1000 {
+            limit = limit.min(if self.restrictive {
+                limits.v_1000_restrictive
+            } else {
+                Self::curve(
+                    limits.v_1000_start,
+                    limits.v_1000_end,
+                    monitor.timer / limits.t_1000,
+                )
+            });
+        }
+        if let Some(mark) = self.monitor_500 {
+            limit = limit.min(if self.restrictive {
+                limits.v_500_restrictive
+            } else {
+                Self::curve(
+                    limits.v_500_start,
+                    limits.v_500_end,
+                    ((distance - mark) / CURVE_500_LENGTH) as f32,
+                )
+            });
+        }
+        let command = self.key_command.is_pressed();
+        if command {
+            limit = limit.min(COMMAND_SPEED);
+        }
+        self.speed_limit = limit;
+
+        if !power {
+            self.forced_brake = true;
+        } else if v_kmh > limit {
+            self.trigger_forced_brake();
+        }
+
+        if self.forced_brake
+            && power
+            && speed.abs() < RELEASE_SPEED
+            && self.key_release.is_just_pressed()
+        {
+            self.forced_brake = false;
+        }
+
+        self.blink_restrictive.run(self.restrictive);
+
+        let lm_train_type = if self.restrictive {
+            self.blink_restrictive.is_on
+        } else {
+            true
+        };
+        let lm_1000 = self
+            .monitor_1000
+            .as_ref()
+            .is_some_and(|m| distance - m.mark <= FREE_1000_DISTANCE);
+
+        self.lm_train_type
+            .set_brightness((power && lm_train_type) as u8 as f32);
+        self.lm_1000.set_brightness((power && lm_1000) as u8 as f32);
+        self.lm_500
+            .set_brightness((power && self.monitor_500.is_some()) as u8 as f32);
+        self.lm_command
+            .set_brightness((power && command) as u8 as f32);
+    }
+}
+
+impl TrackMagnetListener for Pzb {
+    fn on_magnet(&mut self, magnet: TrackMagnet) {
+        match magnet {
+            TrackMagnet::Hz1000 => {
+                self.ack_timer = Some(0.0);
+                self.snd_horn.start();
+                self.monitor_1000 = Some(Monitor1000 {
+                    mark: self.distance,
+                    timer: 0.0,
+                });
+            }
+            TrackMagnet::Hz500 => {
+                self.monitor_500 = Some(self.distance);
+            }
+            TrackMagnet::Hz2000 => {
+                if !self.key_command.is_pressed() {
+                    self.trigger_forced_brake();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Speed limit supervision.
+//!
+//! The [`SpeedSupervisor`] supervises a maximum speed per operating mode.
+//! Exceeding the warning margin sounds the buzzer, exceeding the brake margin
+//! applies the brakes automatically. The brake application is latched: it is
+//! only released at standstill, with the release button if one is configured.
+
+use std::collections::HashMap;
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::{
+    api::{key_event::KeyEvent, light::Light, sound::Sound},
+    management::enums::train_protection_enums::SupervisionMode,
+};
+
+/// Speed in km/h below which the vehicle is at standstill
+const STANDSTILL_SPEED: f32 = 0.5;
+
+pub struct SpeedSupervisorBuilder {
+    limits: HashMap<SupervisionMode, f32>,
+    warn_margin: f32,
+    brake_margin: f32,
+
+    key_release: KeyEvent,
+    release_required: bool,
+
+    lm_warning: Light,
+    lm_brake: Light,
+
+    snd_buzzer: Sound,
+}
+
+impl SpeedSupervisorBuilder {
+    /// Sets the maximum speed of a mode in km/h.
+    pub fn limit(mut self, mode: SupervisionMode, v_max: f32) -> Self {
+        self.limits.insert(mode, v_max);
+        self
+    }
+
+    /// Margins above the maximum speed in km/h at which the buzzer sounds
+    /// and the brakes are applied.
+    pub fn margins(mut self, warn_margin: f32, brake_margin: f32) -> Self {
+        self.warn_margin = warn_margin;
+        self.brake_margin = brake_margin;
+        self
+    }
+
+    /// Key event of the release button. Without it, the brake application is
+    /// released automatically at standstill.
+    pub fn release_event(mut self, name: impl Into<String>, cab_side: Option<CockpitSide>) -> Self {
+        self.key_release = KeyEvent::new(Some(&name.into()), cab_side);
+        self.release_required = true;
+        self
+    }
+
+    /// Lamps of the warning and of the brake application.
+    pub fn lamps(mut self, warning_name: impl Into<String>, brake_name: impl Into<String>) -> Self {
+        self.lm_warning = Light::new(Some(&warning_name.into()));
+        self.lm_brake = Light::new(Some(&brake_name.into()));
+        self
+    }
+
+    pub fn snd_buzzer(mut self, name: impl Into<String>) -> Self {
+        self.snd_buzzer = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> SpeedSupervisor {
+        SpeedSupervisor {
+            limits: self.limits,
+            warn_margin: self.warn_margin,
+            brake_margin: self.brake_margin,
+            key_release: self.key_release,
+            release_required: self.release_required,
+            lm_warning: self.lm_warning,
+            lm_brake: self.lm_brake,
+            snd_buzzer: self.snd_buzzer,
+            mode: SupervisionMode::default(),
+            speed_limit: f32::MAX,
+            warning: false,
+            brake: false,
+        }
+    }
+}
+
+/// Supervision of the maximum speed of the operating mode.
+///
+/// # Example
+///
+/// ```rust
+/// let mut supervisor = SpeedSupervisor::builder()
+///     .limit(SupervisionMode::Shunting, 15.0)
+///     .limit(SupervisionMode::Depot, 10.0)
+///     .limit(SupervisionMode::Line, 70.0)
+///     .margins(2.0, 5.0)
+///     .release_event("Supervisor_Release", Some(CockpitSide::A))
+///     .snd_buzzer("Snd_Overspeed")
+///     .build();
+///
+/// // In the main loop:
+/// supervisor.tick(voltage > 0.7, v_ground());
+/// let emergency = supervisor.brake;
+/// ```
+pub struct SpeedSupervisor {
+    limits: HashMap<SupervisionMode, f32>,
+    warn_margin: f32,
+    brake_margin: f32,
+
+    key_release: KeyEvent,
+    release_required: bool,
+
+    lm_warning: Light,
+    lm_brake: Light,
+
+    snd_buzzer: Sound,
+
+    /// Current operating mode
+    pub mode: SupervisionMode,
+    /// Supervised maximum speed in km/h
+    pub speed_limit: f32,
+    /// Whether the warning margin is exceeded
+    pub warning: bool,
+    /// Whether the brakes are applied, latched until the release at standstill
+    pub brake: bool,
+}
+
+impl SpeedSupervisor {
+    /// Creates a new speed supervisor builder. Modes without a limit are not
+    /// supervised.
+    pub fn builder() -> SpeedSupervisorBuilder {
+        SpeedSupervisorBuilder {
+            limits: HashMap::new(),
+            warn_margin: 0.0,
+            brake_margin: 5.0,
+            key_release: KeyEvent::new(None, None),
+            release_required: false,
+            lm_warning: Light::new(None),
+            lm_brake: Light::new(None),
+            snd_buzzer: Sound::new_simple(None),
+        }
+    }
+
+    /// Changes the operating mode.
+    pub fn set_mode(&mut self, mode: SupervisionMode) {
+        self.mode = mode;
+    }
+
+    /// Updates the supervision.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the supervisor is supplied
+    /// * `speed` - Current speed in m/s
+    pub fn tick(&mut self, power: bool, speed: f32) {
+        let v_kmh = speed.abs() * 3.6;
+        let release = self.key_release.is_just_pressed();
+
+        self.speed_limit = self.limits.get(&self.mode).copied().unwrap_or(f32::MAX);
+
+        let warning = power && v_kmh > self.speed_limit + self.warn_margin;
+        if warning != self.warning {
+            self.warning = warning;
+            self.snd_buzzer.start_stop(warning);
+        }
+
+        if power && v_kmh > self.speed_limit + self.brake_margin {
+            self.brake = true;
+        } else if self.brake && v_kmh < STANDSTILL_SPEED {
+            // Rückstellung nur im Stillstand
+            if release || !self.release_required {
+                self.brake = false;
+            }
+        }
+
+        self.lm_warning
+            .set_brightness((power && self.warning) as u8 as f32);
+        self.lm_brake
+            .set_brightness((power && self.brake) as u8 as f32);
+    }
+}
@@ -0,0 +1,121 @@
+//! Decoding of track magnets from sensor triggers.
+//!
+//! Track magnets are placed on the test track as sensors. The
+//! [`TrackMagnetDecoder`] maps the sensor IDs to magnet types and debounces
+//! the enter and leave events, because every axle of the vehicle passes the
+//! sensor. Train protection devices subscribe to the decoded magnets by
+//! implementing [`TrackMagnetListener`].
+
+use std::collections::HashMap;
+
+use crate::{api::general::delta, management::enums::train_protection_enums::TrackMagnet};
+
+/// Device reacting on the track magnets passed by the vehicle.
+pub trait TrackMagnetListener {
+    /// Called once per passage of an active track magnet.
+    fn on_magnet(&mut self, magnet: TrackMagnet);
+}
+
+struct MagnetSensor {
+    magnet: TrackMagnet,
+    inside: bool,
+    leave_timer: f32,
+}
+
+/// Maps sensor triggers to track magnet events.
+///
+/// # Example
+///
+/// ```rust
+/// let mut magnets = TrackMagnetDecoder::new(1.0)
+///     .add_sensor(1000, TrackMagnet::Hz1000)
+///     .add_sensor(500, TrackMagnet::Hz500)
+///     .add_sensor(2000, TrackMagnet::Hz2000);
+///
+/// // In on_trigger:
+/// magnets.on_trigger(sensor, entering);
+///
+/// // In the main loop:
+/// magnets.tick(&mut [&mut pzb]);
+/// ```
+pub struct TrackMagnetDecoder {
+    debounce_time: f32,
+
+    sensors: HashMap<u32, MagnetSensor>,
+    pending: Vec<TrackMagnet>,
+}
+
+impl TrackMagnetDecoder {
+    /// Creates a new decoder.
+    ///
+    /// # Arguments
+    ///
+    /// * `debounce_time` - Time in seconds the sensor has to be left before the
+    ///   magnet is reported again
+    #[must_use]
+    pub fn new(debounce_time: f32) -> Self {
+        Self {
+            debounce_time,
+            sensors: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Maps a sensor ID to a magnet type.
+    #[must_use]
+    pub fn add_sensor(mut self, sensor: u32, magnet: TrackMagnet) -> Self {
+        self.sensors.insert(
+            sensor,
+            MagnetSensor {
+                magnet,
+                inside: false,
+                leave_timer: 0.0,
+            },
+        );
+        self
+    }
+
+    /// Handles sensor trigger events.
+    ///
+    /// Unknown sensor IDs are ignored. A magnet is reported when its sensor is
+    /// entered after having been left for at least the debounce time.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor` - The ID of the sensor that triggered
+    /// * `entering` - `true` if the vehicle enters the zone, `false` if leaving
+    pub fn on_trigger(&mut self, sensor: u32, entering: bool) {
+        let Some(magnet_sensor) = self.sensors.get_mut(&sensor) else {
+            return;
+        };
+
+        if entering {
+            if !magnet_sensor.inside && magnet_sensor.leave_timer <= 0.0 {
+                self.pending.push(magnet_sensor.magnet);
+            }
+            magnet_sensor.inside = true;
+        } else if magnet_sensor.inside {
+            magnet_sensor.inside = false;
+            magnet_sensor.leave_timer = self.debounce_time;
+        }
+    }
+
+    /// Updates the debounce timers and passes the decoded magnets to the listeners.
+    ///
+    /// # Arguments
+    ///
+    /// * `listeners` - Devices subscribed to the track magnets
+    pub fn tick(&mut self, listeners: &mut [&mut dyn TrackMagnetListener]) {
+        for magnet_sensor in self.sensors.values_mut() {
+            if !magnet_sensor.inside {
+                magnet_sensor.leave_timer = (magnet_sensor.leave_timer - delta()).max(0.0);
+            }
+        }
+
+        for magnet in self.pending.drain(..) {
+            for listener in listeners.iter_mut() {
+                listener.on_magnet(magnet);
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use lotus_script::time::delta;
+use crate::api::general::delta;
 
 /// A time-based delay component that outputs a value only after it has been stable for a specified duration.
 ///
@@ -79,7 +79,7 @@ impl<T: PartialEq + Clone> Delay<T> {
     /// timer. If the timer expires (reaches zero or below), the output is updated
     /// to match the input.
     ///
-    /// The timer is decremented by the frame delta time obtained from `lotus_script::time::delta()`.
+    /// The timer is decremented by the frame delta time obtained from `crate::api::general::delta()`.
     ///
     /// # Parameters
     ///
@@ -0,0 +1,558 @@
+//! Dot-matrix rendering engine.
+//!
+//! This module renders text onto a matrix of dots, as used by the interior
+//! and exterior displays and the IBIS terminal:
+//!
+//! - [`PixelFont`]: Configurable pixel font with proportional or fixed spacing,
+//!   defined in code or loaded from JSON
+//! - [`DotMatrix`]: Frame buffer of dots
+//! - [`TextArea`]: Region of the display with alignment, scrolling and
+//!   blinking
+//! - [`DotMatrixDisplay`]: Display geometry of several text areas, drawn onto
+//!   a [`ScriptTexture`]
+//!
+//! The font and the frame buffer do not depend on the simulator, so they can
+//! also be used to measure texts or to lay out pages in advance.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let font = PixelFont::from_json(FONT_7PX).expect("invalid font");
+//!
+//! let mut display = DotMatrixDisplay::builder(112, 16)
+//!     .texture("Matrix_Front")
+//!     .colors(Color::rgb(255, 160, 0), Color::rgb(20, 12, 0))
+//!     .area(TextArea::new(0, 0, 20, 16).align(Align::Center), font.clone())
+//!     .area(TextArea::new(22, 0, 90, 8).scroll(15.0), font.clone())
+//!     .area(TextArea::new(22, 8, 90, 8), font)
+//!     .build();
+//!
+//! display.set_text(0, "4");
+//! display.set_text(1, "Hauptbahnhof über Marktplatz");
+//!
+//! // In the main loop:
+//! display.tick(voltage > 0.5);
+//! ```
+
+use std::collections::HashMap;
+
+use lotus_script::graphics::Color;
+use serde::Deserialize;
+
+use crate::api::{general::delta, texture::ScriptTexture};
+
+//=================================================================
+// Font
+//=================================================================
+
+#[derive(Deserialize)]
+struct FontConfig {
+    height: u32,
+    #[serde(default = "default_spacing")]
+    spacing: u32,
+    #[serde(default)]
+    monospace: Option<u32>,
+    glyphs: HashMap<char, Vec<String>>,
+}
+
+fn default_spacing() -> u32 {
+    1
+}
+
+/// Pixel font with one bitmap per character.
+///
+/// Each glyph is stored as columns, bit 0 being the top row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelFont {
+    height: u32,
+    spacing: u32,
+    monospace: Option<u32>,
+    glyphs: HashMap<char, Vec<u32>>,
+}
+
+impl PixelFont {
+    /// Creates an empty font.
+    ///
+    /// # Arguments
+    ///
+    /// * `height` - Height of the glyphs in dots, at most 32
+    pub fn new(height: u32) -> Self {
+        Self {
+            height: height.min(32),
+            spacing: 1,
+            monospace: None,
+            glyphs: HashMap::new(),
+        }
+    }
+
+    /// Loads a font from JSON.
+    ///
+    /// The glyphs are given as rows from top to bottom, `#` marks a dot:
+    ///
+    /// ```json
+    /// { "height": 3, "spacing": 1, "glyphs": { "I": ["#", "#", "#"], "-": ["...", "###", "..."] } }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the parser error if the string is no valid font.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let config: FontConfig = serde_json::from_str(json)?;
+
+        let mut font = Self::new(config.height).spacing(config.spacing);
+        font.monospace = config.monospace;
+        for (c, rows) in &config.glyphs {
+            let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+            font = font.glyph(*c, &rows);
+        }
+        Ok(font)
+    }
+
+    /// Adds a glyph given as rows from top to bottom, `#` marks a dot.
+    pub fn glyph(mut self, c: char, rows: &[&str]) -> Self {
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut columns = vec![0; width];
+
+        for (y, row) in rows.iter().take(self.height as usize).enumerate() {
+            for (x, dot) in row.chars().enumerate() {
+                if dot == '#' {
+                    columns[x] |= 1 << y;
+                }
+            }
+        }
+
+        self.glyphs.insert(c, columns);
+        self
+    }
+
+    /// Sets the gap between two characters in dots.
+    pub fn spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Gives every character the same width in dots.
+    pub fn monospace(mut self, width: u32) -> Self {
+        self.monospace = Some(width);
+        self
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Columns of a character, missing characters are blank.
+    fn columns(&self, c: char) -> Vec<u32> {
+        let mut columns = self
+            .glyphs
+            .get(&c)
+            .cloned()
+            .unwrap_or_else(|| vec![0; (self.height / 2).max(1) as usize]);
+
+        if let Some(width) = self.monospace {
+            // Schmale Zeichen mittig in die feste Breite setzen
+            let width = width as usize;
+            let pad = width.saturating_sub(columns.len()) / 2;
+            columns.splice(0..0, std::iter::repeat_n(0, pad));
+            columns.resize(width, 0);
+        }
+        columns
+    }
+
+    /// Renders a text into columns, bit 0 being the top row.
+    pub fn render(&self, text: &str) -> Vec<u32> {
+        let mut columns = Vec::new();
+        for (idx, c) in text.chars().enumerate() {
+            if idx > 0 {
+                columns.extend(std::iter::repeat_n(0, self.spacing as usize));
+            }
+            columns.extend(self.columns(c));
+        }
+        columns
+    }
+
+    /// Width of a text in dots.
+    pub fn text_width(&self, text: &str) -> u32 {
+        self.render(text).len() as u32
+    }
+}
+
+//=================================================================
+// Frame buffer
+//=================================================================
+
+/// Frame buffer of a dot-matrix display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotMatrix {
+    width: u32,
+    height: u32,
+    dots: Vec<bool>,
+}
+
+impl DotMatrix {
+    /// Creates a dark frame buffer.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            dots: vec![false; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Switches all dots off.
+    pub fn clear(&mut self) {
+        self.dots.fill(false);
+    }
+
+    /// Whether the dot is lit, dots outside the matrix are dark.
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.dots[(y * self.width + x) as usize]
+    }
+
+    /// Sets a dot, dots outside the matrix are ignored.
+    pub fn set(&mut self, x: u32, y: u32, value: bool) {
+        if x < self.width && y < self.height {
+            self.dots[(y * self.width + x) as usize] = value;
+        }
+    }
+
+    /// Positions of all lit dots.
+    pub fn lit(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.dots
+            .iter()
+            .enumerate()
+            .filter(|(_, dot)| **dot)
+            .map(|(idx, _)| (idx as u32 % self.width, idx as u32 / self.width))
+    }
+
+    /// Draws text columns into an area, clipped to the area.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - Target area
+    /// * `columns` - Rendered text, see [`PixelFont::render`]
+    /// * `offset` - Horizontal position of the first column relative to the area
+    pub fn draw_columns(&mut self, area: &TextArea, columns: &[u32], offset: i32) {
+        for (idx, column) in columns.iter().enumerate() {
+            let x = offset + idx as i32;
+            if x < 0 || x >= area.width as i32 {
+                continue;
+            }
+            for y in 0..area.height.min(32) {
+                if column & (1 << y) != 0 {
+                    self.set(area.x + x as u32, area.y + y, true);
+                }
+            }
+        }
+    }
+
+    /// Renders a text into an area with its alignment, scrolling and
+    /// blinking.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - Target area
+    /// * `font` - Font of the text
+    /// * `text` - Text to render
+    /// * `time` - Time in seconds since the text was set
+    pub fn draw_text(&mut self, area: &TextArea, font: &PixelFont, text: &str, time: f32) {
+        if area.blink > 0.0 && time.rem_euclid(area.blink) >= area.blink / 2.0 {
+            return;
+        }
+
+        let columns = font.render(text);
+        let width = columns.len() as i32;
+        let free = area.width as i32 - width;
+
+        let offset = if area.scroll > 0.0 && free < 0 {
+            // Lauftext: von rechts herein, links hinaus
+            let cycle = width + area.width as i32;
+            area.width as i32 - (time * area.scroll) as i32 % cycle
+        } else {
+            match area.align {
+                Align::Left => 0,
+                Align::Center => free / 2,
+                Align::Right => free,
+            }
+        };
+
+        self.draw_columns(area, &columns, offset);
+    }
+}
+
+//=================================================================
+// Display
+//=================================================================
+
+/// Horizontal alignment of a text in its area.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Region of a dot-matrix display showing one text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextArea {
+    /// Left edge in dots
+    pub x: u32,
+    /// Top edge in dots
+    pub y: u32,
+    /// Width in dots
+    pub width: u32,
+    /// Height in dots
+    pub height: u32,
+    /// Alignment of texts fitting into the area
+    pub align: Align,
+    /// Scroll speed in dots per second for texts wider than the area, 0.0
+    /// cuts them
+    pub scroll: f32,
+    /// Blink period in seconds, 0.0 for steady text
+    pub blink: f32,
+}
+
+impl TextArea {
+    /// Creates a left aligned area without scrolling and blinking.
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            align: Align::Left,
+            scroll: 0.0,
+            blink: 0.0,
+        }
+    }
+
+    /// Sets the alignment.
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Lets texts wider than the area scroll with the given speed in dots
+    /// per second.
+    pub fn scroll(mut self, speed: f32) -> Self {
+        self.scroll = speed;
+        self
+    }
+
+    /// Lets the text blink with the given period in seconds.
+    pub fn blink(mut self, period: f32) -> Self {
+        self.blink = period;
+        self
+    }
+}
+
+struct AreaContent {
+    area: TextArea,
+    font: PixelFont,
+    text: String,
+    time: f32,
+}
+
+/// Builder for creating a [`DotMatrixDisplay`].
+pub struct DotMatrixDisplayBuilder {
+    width: u32,
+    height: u32,
+    texture_name: Option<String>,
+    on_color: Color,
+    off_color: Color,
+    areas: Vec<AreaContent>,
+}
+
+impl DotMatrixDisplayBuilder {
+    /// Texture slot the display is drawn to.
+    pub fn texture(mut self, name: impl Into<String>) -> Self {
+        self.texture_name = Some(name.into());
+        self
+    }
+
+    /// Colors of lit and dark dots.
+    pub fn colors(mut self, on: Color, off: Color) -> Self {
+        self.on_color = on;
+        self.off_color = off;
+        self
+    }
+
+    /// Adds a text area with its font.
+    pub fn area(mut self, area: TextArea, font: PixelFont) -> Self {
+        self.areas.push(AreaContent {
+            area,
+            font,
+            text: String::new(),
+            time: 0.0,
+        });
+        self
+    }
+
+    pub fn build(self) -> DotMatrixDisplay {
+        DotMatrixDisplay {
+            texture: ScriptTexture::new(self.texture_name.as_deref(), self.width, self.height),
+            on_color: self.on_color,
+            off_color: self.off_color,
+            areas: self.areas,
+            frame_last: None,
+            frame: DotMatrix::new(self.width, self.height),
+        }
+    }
+}
+
+/// Dot-matrix display of several text areas drawn onto a script texture.
+pub struct DotMatrixDisplay {
+    texture: ScriptTexture,
+    on_color: Color,
+    off_color: Color,
+    areas: Vec<AreaContent>,
+    frame_last: Option<DotMatrix>,
+
+    /// Frame currently shown
+    pub frame: DotMatrix,
+}
+
+impl DotMatrixDisplay {
+    /// Creates a new dot-matrix display builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Width of the display in dots
+    /// * `height` - Height of the display in dots
+    pub fn builder(width: u32, height: u32) -> DotMatrixDisplayBuilder {
+        DotMatrixDisplayBuilder {
+            width,
+            height,
+            texture_name: None,
+            on_color: Color::rgb(255, 160, 0),
+            off_color: Color::BLACK,
+            areas: Vec::new(),
+        }
+    }
+
+    /// Sets the text of an area, a changed text restarts scrolling and
+    /// blinking.
+    pub fn set_text(&mut self, area: usize, text: impl Into<String>) {
+        if let Some(content) = self.areas.get_mut(area) {
+            let text = text.into();
+            if content.text != text {
+                content.text = text;
+                content.time = 0.0;
+            }
+        }
+    }
+
+    /// Changes the blink period of an area, 0.0 for steady text.
+    pub fn set_blink(&mut self, area: usize, period: f32) {
+        if let Some(content) = self.areas.get_mut(area) {
+            content.area.blink = period;
+        }
+    }
+
+    /// Text of an area.
+    pub fn text(&self, area: usize) -> Option<&str> {
+        self.areas.get(area).map(|content| content.text.as_str())
+    }
+
+    /// Renders the areas and draws the frame if it has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the display is supplied, all dots stay dark without
+    pub fn tick(&mut self, power: bool) {
+        self.frame.clear();
+
+        if power {
+            for content in &mut self.areas {
+                content.time += delta();
+                self.frame
+                    .draw_text(&content.area, &content.font, &content.text, content.time);
+            }
+        }
+
+        if self.frame_last.as_ref() != Some(&self.frame) {
+            let on_color = self.on_color;
+            self.texture.clear(self.off_color);
+            self.texture
+                .draw_pixels(self.frame.lit().map(|(x, y)| (x, y, on_color)));
+            self.texture.flush();
+            self.frame_last = Some(self.frame.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font() -> PixelFont {
+        PixelFont::new(3)
+            .glyph('I', &["#", "#", "#"])
+            .glyph('-', &["...", "###", "..."])
+    }
+
+    #[test]
+    fn test_proportional_width() {
+        let font = font();
+        assert_eq!(font.text_width("I"), 1);
+        assert_eq!(font.text_width("I-I"), 1 + 1 + 3 + 1 + 1);
+        assert_eq!(font.render("-"), vec![0b010, 0b010, 0b010]);
+    }
+
+    #[test]
+    fn test_monospace() {
+        let font = font().monospace(3);
+        assert_eq!(font.render("I"), vec![0, 0b111, 0]);
+        assert_eq!(font.text_width("II"), 7);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let font = PixelFont::from_json(r##"{ "height": 3, "glyphs": { "I": ["#", "#", "#"] } }"##)
+            .unwrap();
+        assert_eq!(font.render("I"), vec![0b111]);
+    }
+
+    #[test]
+    fn test_alignment() {
+        let mut frame = DotMatrix::new(5, 3);
+        let area = TextArea::new(0, 0, 5, 3).align(Align::Center);
+        frame.draw_text(&area, &font(), "I", 0.0);
+        assert!(frame.get(2, 0) && frame.get(2, 2));
+        assert_eq!(frame.lit().count(), 3);
+    }
+
+    #[test]
+    fn test_clipping_and_blink() {
+        let mut frame = DotMatrix::new(4, 3);
+        let area = TextArea::new(1, 0, 2, 3).blink(1.0);
+        frame.draw_text(&area, &font(), "---", 0.0);
+        assert_eq!(frame.lit().collect::<Vec<_>>(), vec![(1, 1), (2, 1)]);
+
+        frame.clear();
+        frame.draw_text(&area, &font(), "---", 0.75);
+        assert_eq!(frame.lit().count(), 0);
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut frame = DotMatrix::new(2, 3);
+        let area = TextArea::new(0, 0, 2, 3).scroll(1.0);
+        frame.draw_text(&area, &font(), "I-", 1.0);
+        assert_eq!(frame.lit().count(), 3);
+        assert!(frame.get(1, 0));
+    }
+}
@@ -0,0 +1,258 @@
+//! Time-based signal filters.
+//!
+//! This module provides small building blocks for smoothing and conditioning
+//! signals over time:
+//!
+//! - [`LowPass`]: First-order low-pass, e.g. for damping gauge needles or
+//!   smoothing a voltage
+//! - [`SlewLimiter`]: Limits the rate of change, separately for rising and
+//!   falling values
+//! - [`Debounce`]: Delays switching a boolean on and off, e.g. for the pick-up
+//!   and drop-out delay of a relay
+//!
+//! Every filter has a `tick` method using the frame time from
+//! [`delta()`](crate::api::general::delta) and an `update` method taking the
+//! time step explicitly.
+
+use crate::api::general::delta;
+
+/// First-order low-pass filter.
+///
+/// The output approaches the input exponentially with the given time constant,
+/// after one time constant about 63% of a step is reached.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::elements::std::filter::LowPass;
+///
+/// let mut needle = LowPass::new(0.3, 0.0);
+///
+/// // In the main loop:
+/// let shown = needle.tick(voltage);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LowPass {
+    /// Time constant in seconds, 0.0 passes the input unchanged
+    pub time_constant: f32,
+    /// Current filtered value
+    pub output: f32,
+}
+
+impl LowPass {
+    /// Creates a new low-pass filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_constant` - Time constant in seconds
+    /// * `init` - Initial output value
+    pub fn new(time_constant: f32, init: f32) -> Self {
+        Self {
+            time_constant,
+            output: init,
+        }
+    }
+
+    /// Sets the output directly, e.g. after loading a saved state.
+    pub fn set(&mut self, value: f32) {
+        self.output = value;
+    }
+
+    /// Filters the input with the frame time and returns the output.
+    pub fn tick(&mut self, input: f32) -> f32 {
+        self.update(input, delta())
+    }
+
+    /// Filters the input with the given time step in seconds and returns the
+    /// output.
+    pub fn update(&mut self, input: f32, dt: f32) -> f32 {
+        if self.time_constant <= 0.0 {
+            self.output = input;
+        } else {
+            // Exakte Diskretisierung, stabil auch bei großen Zeitschritten
+            let factor = 1.0 - (-dt / self.time_constant).exp();
+            self.output += (input - self.output) * factor;
+        }
+        self.output
+    }
+}
+
+//=========================================================================
+
+/// Rate limiter following the input with a maximum speed.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::elements::std::filter::SlewLimiter;
+///
+/// // Rises with 0.5 per second, falls with 2.0 per second
+/// let mut demand = SlewLimiter::new(0.5, 2.0, 0.0);
+///
+/// // In the main loop:
+/// let traction = demand.tick(controller.traction());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SlewLimiter {
+    /// Maximum rise per second
+    pub rise_rate: f32,
+    /// Maximum fall per second
+    pub fall_rate: f32,
+    /// Current limited value
+    pub output: f32,
+}
+
+impl SlewLimiter {
+    /// Creates a new rate limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `rise_rate` - Maximum rise per second
+    /// * `fall_rate` - Maximum fall per second
+    /// * `init` - Initial output value
+    pub fn new(rise_rate: f32, fall_rate: f32, init: f32) -> Self {
+        Self {
+            rise_rate,
+            fall_rate,
+            output: init,
+        }
+    }
+
+    /// Creates a rate limiter with the same rate in both directions.
+    pub fn symmetric(rate: f32, init: f32) -> Self {
+        Self::new(rate, rate, init)
+    }
+
+    /// Sets the output directly.
+    pub fn set(&mut self, value: f32) {
+        self.output = value;
+    }
+
+    /// Follows the input with the frame time and returns the output.
+    pub fn tick(&mut self, input: f32) -> f32 {
+        self.update(input, delta())
+    }
+
+    /// Follows the input with the given time step in seconds and returns the
+    /// output.
+    pub fn update(&mut self, input: f32, dt: f32) -> f32 {
+        let diff = input - self.output;
+        self.output += diff.clamp(-self.fall_rate * dt, self.rise_rate * dt);
+        self.output
+    }
+}
+
+//=========================================================================
+
+/// Boolean debounce with separate switch-on and switch-off delays.
+///
+/// The output only follows the input once it has been stable for the
+/// respective delay. Unlike [`Delay`](super::delay::Delay), the delays for
+/// both directions can differ, like the pick-up and drop-out time of a relay.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::elements::std::filter::Debounce;
+///
+/// // Picks up after 0.2 s, drops out after 1.0 s
+/// let mut relay = Debounce::new(0.2, 1.0, false);
+///
+/// // In the main loop:
+/// let closed = relay.tick(coil_voltage > 0.7);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Debounce {
+    /// Delay in seconds until a `true` input is passed
+    pub on_delay: f32,
+    /// Delay in seconds until a `false` input is passed
+    pub off_delay: f32,
+    timer: f32,
+    /// Current debounced state
+    pub output: bool,
+}
+
+impl Debounce {
+    /// Creates a new debounce.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_delay` - Delay in seconds for switching on
+    /// * `off_delay` - Delay in seconds for switching off
+    /// * `init` - Initial output state
+    pub fn new(on_delay: f32, off_delay: f32, init: bool) -> Self {
+        Self {
+            on_delay,
+            off_delay,
+            timer: 0.0,
+            output: init,
+        }
+    }
+
+    /// Sets the output directly and cancels a running delay.
+    pub fn set(&mut self, value: bool) {
+        self.output = value;
+        self.timer = 0.0;
+    }
+
+    /// Debounces the input with the frame time and returns the output.
+    pub fn tick(&mut self, input: bool) -> bool {
+        self.update(input, delta())
+    }
+
+    /// Debounces the input with the given time step in seconds and returns
+    /// the output.
+    pub fn update(&mut self, input: bool, dt: f32) -> bool {
+        if input == self.output {
+            self.timer = 0.0;
+        } else {
+            self.timer += dt;
+            let delay = if input { self.on_delay } else { self.off_delay };
+            if self.timer >= delay {
+                self.output = input;
+                self.timer = 0.0;
+            }
+        }
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_pass() {
+        let mut filter = LowPass::new(1.0, 0.0);
+        let y = filter.update(1.0, 1.0);
+        assert!((y - 0.632).abs() < 0.001);
+
+        // Großer Zeitschritt schwingt nicht über
+        let y = filter.update(1.0, 100.0);
+        assert!(y <= 1.0 && y > 0.999);
+
+        let mut direct = LowPass::new(0.0, 0.0);
+        assert_eq!(direct.update(5.0, 0.01), 5.0);
+    }
+
+    #[test]
+    fn test_slew_limiter() {
+        let mut limiter = SlewLimiter::new(1.0, 2.0, 0.0);
+        assert_eq!(limiter.update(10.0, 0.5), 0.5);
+        assert_eq!(limiter.update(0.6, 0.5), 0.6);
+        assert!((limiter.update(-1.0, 0.25) - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_debounce() {
+        let mut relay = Debounce::new(0.2, 1.0, false);
+        assert!(!relay.update(true, 0.1));
+        assert!(relay.update(true, 0.1));
+
+        // Kurzer Einbruch wird überbrückt
+        assert!(relay.update(false, 0.5));
+        assert!(relay.update(true, 0.1));
+        assert!(relay.update(false, 0.5));
+        assert!(!relay.update(false, 0.5));
+    }
+}
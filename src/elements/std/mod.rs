@@ -1,5 +1,9 @@
 //pub mod ad_ids;
 pub mod delay;
+pub mod dot_matrix;
+pub mod filter;
 //pub mod helper;
-//pub mod piecewise_linear_function;
+pub mod piecewise_linear_function;
+pub mod relays;
 pub mod scroller;
+pub mod state_machine;
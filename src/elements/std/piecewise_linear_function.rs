@@ -10,9 +10,12 @@
 //!
 //! - **Sorted storage**: Points are automatically kept sorted by x-coordinate
 //! - **Linear interpolation**: Values between points are calculated using linear interpolation
-//! - **Edge case handling**: Values outside the defined range return the nearest endpoint value
+//! - **Edge case handling**: Values outside the defined range are held, extrapolated or rejected
 //! - **Duplicate handling**: Points with the same x-coordinate overwrite previous values
 //! - **Error handling**: Comprehensive error handling for invalid inputs
+//! - **Smoothing**: Optional monotone cubic interpolation without overshoot
+//! - **Inverse evaluation**: Finding the x-value for a given y-value
+//! - **Deserialization**: Loading from a configuration with serde
 //!
 //! ## Quick Start
 //!
@@ -47,10 +50,17 @@
 //! // From an iterator
 //! let points = vec![(0.0, 1.0), (1.0, 2.0)];
 //! let function3: PiecewiseLinearFunction = points.into_iter().collect();
+//!
+//! // From a configuration
+//! let function4: PiecewiseLinearFunction = serde_json::from_str(
+//!     r#"{ "points": [[0.0, 0.0], [1.0, 2.0]], "clamp": "Extrapolate" }"#,
+//! ).unwrap();
 //! ```
 
 use std::fmt;
 
+use serde::Deserialize;
+
 /// Errors that can occur when working with piecewise linear functions.
 #[derive(Debug, Clone, PartialEq)]
 pub enum PiecewiseError {
@@ -58,6 +68,8 @@ pub enum PiecewiseError {
     EmptyFunction,
     /// A point contains invalid coordinates (NaN or infinite values).
     InvalidPoint,
+    /// The value lies outside the defined range and the clamp mode rejects it.
+    OutOfRange,
 }
 
 impl fmt::Display for PiecewiseError {
@@ -65,12 +77,65 @@ impl fmt::Display for PiecewiseError {
         match self {
             PiecewiseError::EmptyFunction => write!(f, "Function has no points defined"),
             PiecewiseError::InvalidPoint => write!(f, "Invalid point coordinates"),
+            PiecewiseError::OutOfRange => write!(f, "Value outside the defined range"),
         }
     }
 }
 
 impl std::error::Error for PiecewiseError {}
 
+/// Behavior of the function for x-values outside the defined range.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ClampMode {
+    /// Returns the y-value of the nearest endpoint
+    #[default]
+    Hold,
+    /// Continues the first or last segment linearly
+    Extrapolate,
+    /// Returns [`PiecewiseError::OutOfRange`]
+    Reject,
+}
+
+/// Interpolation between the defined points.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Interpolation {
+    /// Straight line segments
+    #[default]
+    Linear,
+    /// Monotone cubic Hermite spline (Fritsch-Carlson), smooth without
+    /// overshooting the points
+    MonotoneCubic,
+}
+
+/// Serialized form, either a plain list of points or an object with options.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PiecewiseConfig {
+    Points(Vec<(f32, f32)>),
+    Full {
+        points: Vec<(f32, f32)>,
+        #[serde(default)]
+        clamp: ClampMode,
+        #[serde(default)]
+        interpolation: Interpolation,
+    },
+}
+
+impl From<PiecewiseConfig> for PiecewiseLinearFunction {
+    fn from(config: PiecewiseConfig) -> Self {
+        match config {
+            PiecewiseConfig::Points(points) => Self::new(points),
+            PiecewiseConfig::Full {
+                points,
+                clamp,
+                interpolation,
+            } => Self::new(points)
+                .clamp_mode(clamp)
+                .interpolation(interpolation),
+        }
+    }
+}
+
 /// A piecewise linear function defined by a series of connected line segments.
 ///
 /// The function is represented by a collection of points (x, y), where consecutive
@@ -80,7 +145,8 @@ impl std::error::Error for PiecewiseError {}
 /// # Behavior
 ///
 /// - **Interpolation**: For x-values between defined points, the function uses linear interpolation
-/// - **Extrapolation**: For x-values outside the defined range, the function returns the y-value of the nearest endpoint
+/// - **Extrapolation**: For x-values outside the defined range, the function returns the y-value of the nearest endpoint,
+///   other behaviors can be selected with [`clamp_mode`](Self::clamp_mode)
 /// - **Duplicate x-values**: Adding a point with an existing x-coordinate overwrites the previous y-value
 ///
 /// # Examples
@@ -105,10 +171,17 @@ impl std::error::Error for PiecewiseError {}
 /// assert_eq!(function.get_value(-1.0).unwrap(), 0.0);  // Returns first point's y
 /// assert_eq!(function.get_value(3.0).unwrap(), 1.0);   // Returns last point's y
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "PiecewiseConfig")]
 pub struct PiecewiseLinearFunction {
     /// Internal storage of points, kept sorted by x-coordinate.
     points: Vec<(f32, f32)>,
+    /// Behavior outside the defined range.
+    clamp: ClampMode,
+    /// Interpolation between the points.
+    interpolation: Interpolation,
+    /// Tangents of the monotone cubic interpolation, one per point.
+    tangents: Vec<f32>,
 }
 
 impl PiecewiseLinearFunction {
@@ -135,7 +208,7 @@ impl PiecewiseLinearFunction {
     /// ```
     #[must_use]
     pub fn new(points: Vec<(f32, f32)>) -> Self {
-        let mut fun = Self { points: Vec::new() };
+        let mut fun = Self::empty();
 
         for (x, y) in points {
             fun.add_point_unchecked(x, y);
@@ -161,7 +234,48 @@ impl PiecewiseLinearFunction {
     /// ```
     #[must_use]
     pub fn empty() -> Self {
-        Self { points: Vec::new() }
+        Self {
+            points: Vec::new(),
+            clamp: ClampMode::default(),
+            interpolation: Interpolation::default(),
+            tangents: Vec::new(),
+        }
+    }
+
+    /// Sets the behavior for x-values outside the defined range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use piecewise_linear_function::{ClampMode, PiecewiseLinearFunction};
+    ///
+    /// let function = PiecewiseLinearFunction::new(vec![(0.0, 0.0), (1.0, 2.0)])
+    ///     .clamp_mode(ClampMode::Extrapolate);
+    /// assert_eq!(function.get_value(2.0).unwrap(), 4.0);
+    /// ```
+    #[must_use]
+    pub fn clamp_mode(mut self, clamp: ClampMode) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// Sets the interpolation between the points.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use piecewise_linear_function::{Interpolation, PiecewiseLinearFunction};
+    ///
+    /// let function = PiecewiseLinearFunction::new(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 4.0)])
+    ///     .interpolation(Interpolation::MonotoneCubic);
+    /// let y = function.get_value(1.5).unwrap();
+    /// assert!(y > 1.0 && y < 4.0);
+    /// ```
+    #[must_use]
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self.update_tangents();
+        self
     }
 
     /// Adds a point to the function.
@@ -223,6 +337,72 @@ impl PiecewiseLinearFunction {
                 self.points.insert(pos, (x, y));
             }
         }
+        self.update_tangents();
+    }
+
+    /// Calculates the tangents of the monotone cubic interpolation.
+    fn update_tangents(&mut self) {
+        self.tangents.clear();
+        if self.interpolation != Interpolation::MonotoneCubic || self.points.len() < 2 {
+            return;
+        }
+
+        let n = self.points.len();
+        let slopes: Vec<f32> = self
+            .points
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+            .collect();
+
+        self.tangents.push(slopes[0]);
+        for i in 1..n - 1 {
+            let (d0, d1) = (slopes[i - 1], slopes[i]);
+            // Extremum or plateau: flat tangent, otherwise harmonic mean
+            let m = if d0 * d1 <= 0.0 {
+                0.0
+            } else {
+                2.0 / (1.0 / d0 + 1.0 / d1)
+            };
+            self.tangents.push(m);
+        }
+        self.tangents.push(slopes[n - 2]);
+
+        // Fritsch-Carlson: limit the tangents to avoid overshooting
+        for (i, &d) in slopes.iter().enumerate() {
+            if d == 0.0 {
+                self.tangents[i] = 0.0;
+                self.tangents[i + 1] = 0.0;
+                continue;
+            }
+            let a = self.tangents[i] / d;
+            let b = self.tangents[i + 1] / d;
+            let h = a * a + b * b;
+            if h > 9.0 {
+                let t = 3.0 / h.sqrt();
+                self.tangents[i] = t * a * d;
+                self.tangents[i + 1] = t * b * d;
+            }
+        }
+    }
+
+    /// Evaluates the segment starting at the point with the given index.
+    fn segment_value(&self, index: usize, x: f32) -> f32 {
+        let (x0, y0) = self.points[index];
+        let (x1, y1) = self.points[index + 1];
+        let h = x1 - x0;
+
+        match self.interpolation {
+            Interpolation::Linear => y0 + (x - x0) * (y1 - y0) / h,
+            Interpolation::MonotoneCubic => {
+                let t = (x - x0) / h;
+                let t2 = t * t;
+                let t3 = t2 * t;
+                (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                    + (t3 - 2.0 * t2 + t) * h * self.tangents[index]
+                    + (-2.0 * t3 + 3.0 * t2) * y1
+                    + (t3 - t2) * h * self.tangents[index + 1]
+            }
+        }
     }
 
     /// Returns the number of points in the function.
@@ -381,14 +561,33 @@ impl PiecewiseLinearFunction {
             return Err(PiecewiseError::InvalidPoint);
         }
 
-        // edge case, smaller than the existing values
-        if x <= self.points[0].0 {
-            return Ok(self.points[0].1);
+        let last = self.points.len() - 1;
+
+        // edge case, outside of the existing values
+        if x < self.points[0].0 || x > self.points[last].0 {
+            return match self.clamp {
+                ClampMode::Reject => Err(PiecewiseError::OutOfRange),
+                ClampMode::Extrapolate if last > 0 => {
+                    let (index, (px, py)) = if x < self.points[0].0 {
+                        (0, self.points[0])
+                    } else {
+                        (last - 1, self.points[last])
+                    };
+                    let (x0, y0) = self.points[index];
+                    let (x1, y1) = self.points[index + 1];
+                    Ok(py + (x - px) * (y1 - y0) / (x1 - x0))
+                }
+                _ if x < self.points[0].0 => Ok(self.points[0].1),
+                _ => Ok(self.points[last].1),
+            };
         }
 
-        // edge case, greater than the existing values
-        if x >= self.points[self.points.len() - 1].0 {
-            return Ok(self.points[self.points.len() - 1].1);
+        // edge case, on the endpoints
+        if x == self.points[0].0 {
+            return Ok(self.points[0].1);
+        }
+        if x == self.points[last].0 {
+            return Ok(self.points[last].1);
         }
 
         let pos = self
@@ -400,15 +599,81 @@ impl PiecewiseLinearFunction {
                 // Exact match found
                 Ok(self.points[index].1)
             }
-            Err(index) => {
-                let (x0, y0) = self.points[index - 1];
-                let (x1, y1) = self.points[index];
+            Err(index) => Ok(self.segment_value(index - 1, x)),
+        }
+    }
+
+    /// Finds the x-value at which the function reaches the given y-value.
+    ///
+    /// The segments are searched from the lowest x-value on, so for functions
+    /// which are not monotone the first matching x-value is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - The y-value to look for (must be finite)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(x)` - The x-value with f(x) = y
+    /// * `Err(PiecewiseError::EmptyFunction)` - If the function has no points
+    /// * `Err(PiecewiseError::InvalidPoint)` - If y is NaN or infinite
+    /// * `Err(PiecewiseError::OutOfRange)` - If the function never reaches y
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use piecewise_linear_function::PiecewiseLinearFunction;
+    ///
+    /// let function = PiecewiseLinearFunction::new(vec![(0.0, 0.0), (2.0, 4.0), (4.0, 5.0)]);
+    ///
+    /// assert_eq!(function.get_inverse(2.0).unwrap(), 1.0);
+    /// assert_eq!(function.get_inverse(4.5).unwrap(), 3.0);
+    /// assert!(function.get_inverse(6.0).is_err());
+    /// ```
+    pub fn get_inverse(&self, y: f32) -> Result<f32, PiecewiseError> {
+        if self.points.is_empty() {
+            return Err(PiecewiseError::EmptyFunction);
+        }
+
+        if !y.is_finite() {
+            return Err(PiecewiseError::InvalidPoint);
+        }
+
+        if let Some(&(x, _)) = self.points.iter().find(|&&(_, py)| py == y) {
+            return Ok(x);
+        }
 
-                // Linear interpolation
-                let interpolated_y = y0 + (x - x0) * (y1 - y0) / (x1 - x0);
-                Ok(interpolated_y)
+        for (index, w) in self.points.windows(2).enumerate() {
+            let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+            if (y - y0) * (y - y1) > 0.0 {
+                continue;
             }
+
+            return Ok(match self.interpolation {
+                Interpolation::Linear => x0 + (y - y0) * (x1 - x0) / (y1 - y0),
+                Interpolation::MonotoneCubic => {
+                    // Bisection, the segment is monotone
+                    let rising = y1 > y0;
+                    let (mut lo, mut hi) = (x0, x1);
+                    for _ in 0..32 {
+                        let mid = 0.5 * (lo + hi);
+                        if (self.segment_value(index, mid) < y) == rising {
+                            lo = mid;
+                        } else {
+                            hi = mid;
+                        }
+                    }
+                    0.5 * (lo + hi)
+                }
+            });
         }
+
+        Err(PiecewiseError::OutOfRange)
+    }
+
+    /// Finds the x-value for the given y-value, returning 0.0 if there is none.
+    pub fn get_inverse_or_default(&self, y: f32) -> f32 {
+        self.get_inverse(y).unwrap_or(0.0)
     }
 }
 
@@ -511,6 +776,47 @@ mod tests {
         assert_eq!(piecewise.get_value(1.0).unwrap(), 2.0);
     }
 
+    #[test]
+    fn test_clamp_modes() {
+        let function = PiecewiseLinearFunction::new(vec![(0.0, 0.0), (1.0, 2.0)]);
+        assert_eq!(function.get_value(2.0).unwrap(), 2.0);
+
+        let function = function.clamp_mode(ClampMode::Extrapolate);
+        assert_eq!(function.get_value(2.0).unwrap(), 4.0);
+        assert_eq!(function.get_value(-1.0).unwrap(), -2.0);
+
+        let function = function.clamp_mode(ClampMode::Reject);
+        assert_eq!(function.get_value(2.0), Err(PiecewiseError::OutOfRange));
+        assert_eq!(function.get_value(1.0).unwrap(), 2.0);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let function = PiecewiseLinearFunction::new(vec![(0.0, 0.0), (2.0, 4.0), (4.0, 5.0)]);
+        assert_eq!(function.get_inverse(2.0).unwrap(), 1.0);
+        assert_eq!(function.get_inverse(5.0).unwrap(), 4.0);
+        assert_eq!(function.get_inverse(6.0), Err(PiecewiseError::OutOfRange));
+
+        let falling = PiecewiseLinearFunction::new(vec![(0.0, 10.0), (10.0, 0.0)]);
+        assert_eq!(falling.get_inverse(2.5).unwrap(), 7.5);
+    }
+
+    #[test]
+    fn test_monotone_cubic() {
+        let function = PiecewiseLinearFunction::new(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 1.0)])
+            .interpolation(Interpolation::MonotoneCubic);
+
+        assert_eq!(function.get_value(1.0).unwrap(), 1.0);
+        // No overshoot above the plateau
+        for i in 0..=20 {
+            let y = function.get_value(i as f32 * 0.1).unwrap();
+            assert!((0.0..=1.0).contains(&y));
+        }
+
+        let x = function.get_inverse(0.5).unwrap();
+        assert!((function.get_value(x).unwrap() - 0.5).abs() < 1e-4);
+    }
+
     #[test]
     fn test_get_value_or_default() {
         let function = PiecewiseLinearFunction::new(vec![(0.0, 1.0), (1.0, 2.0)]);
@@ -0,0 +1,267 @@
+//! Timer relays ("Zeitrelais").
+//!
+//! Reusable timing primitives as found in the relay cabinets of vehicles:
+//!
+//! - [`OnDelayRelay`]: Picks up delayed, drops out immediately ("Anzugsverzögert")
+//! - [`OffDelayRelay`]: Picks up immediately, drops out delayed ("Abfallverzögert")
+//! - [`PulseRelay`]: Gives a pulse of fixed length on a rising input ("Wischrelais")
+//! - [`FlasherRelay`]: Switches periodically on and off ("Blinkrelais")
+//!
+//! All relays use the frame time from [`delta()`](crate::api::general::delta).
+
+use crate::{api::general::delta, elements::std::filter::Debounce};
+
+/// Relay which picks up after the input has been on for the delay time.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::elements::std::relays::OnDelayRelay;
+///
+/// let mut contactor = OnDelayRelay::new(0.5);
+///
+/// // In the main loop:
+/// let closed = contactor.tick(voltage > 0.7);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct OnDelayRelay {
+    debounce: Debounce,
+}
+
+impl OnDelayRelay {
+    /// Creates a new on-delay relay.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - Pick-up delay in seconds
+    pub fn new(delay: f32) -> Self {
+        Self {
+            debounce: Debounce::new(delay, 0.0, false),
+        }
+    }
+
+    /// Updates the relay and returns whether it is picked up.
+    pub fn tick(&mut self, input: bool) -> bool {
+        self.debounce.tick(input)
+    }
+
+    /// Whether the relay is picked up.
+    pub fn is_on(&self) -> bool {
+        self.debounce.output
+    }
+
+    /// Drops the relay out and cancels a running delay.
+    pub fn reset(&mut self) {
+        self.debounce.set(false);
+    }
+}
+
+//=========================================================================
+
+/// Relay which drops out after the input has been off for the delay time.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::elements::std::relays::OffDelayRelay;
+///
+/// let mut interior_light = OffDelayRelay::new(30.0);
+///
+/// // In the main loop:
+/// let on = interior_light.tick(doors_open);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct OffDelayRelay {
+    debounce: Debounce,
+}
+
+impl OffDelayRelay {
+    /// Creates a new off-delay relay.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - Drop-out delay in seconds
+    pub fn new(delay: f32) -> Self {
+        Self {
+            debounce: Debounce::new(0.0, delay, false),
+        }
+    }
+
+    /// Updates the relay and returns whether it is picked up.
+    pub fn tick(&mut self, input: bool) -> bool {
+        self.debounce.tick(input)
+    }
+
+    /// Whether the relay is picked up.
+    pub fn is_on(&self) -> bool {
+        self.debounce.output
+    }
+
+    /// Drops the relay out immediately.
+    pub fn reset(&mut self) {
+        self.debounce.set(false);
+    }
+}
+
+//=========================================================================
+
+/// Relay giving a pulse of fixed length on each rising edge of the input.
+///
+/// The pulse runs to its end even if the input falls earlier, a new rising
+/// edge during the pulse does not extend it.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::elements::std::relays::PulseRelay;
+///
+/// let mut horn_pulse = PulseRelay::new(0.3);
+///
+/// // In the main loop:
+/// let horn = horn_pulse.tick(pzb.warning);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PulseRelay {
+    pulse_time: f32,
+    timer: f32,
+    input_last: bool,
+    /// Whether the pulse is running
+    pub is_on: bool,
+}
+
+impl PulseRelay {
+    /// Creates a new pulse relay.
+    ///
+    /// # Arguments
+    ///
+    /// * `pulse_time` - Length of the pulse in seconds
+    pub fn new(pulse_time: f32) -> Self {
+        Self {
+            pulse_time,
+            timer: 0.0,
+            input_last: false,
+            is_on: false,
+        }
+    }
+
+    /// Updates the relay and returns whether the pulse is running.
+    pub fn tick(&mut self, input: bool) -> bool {
+        if input && !self.input_last && !self.is_on {
+            self.timer = self.pulse_time;
+        }
+        self.input_last = input;
+
+        self.is_on = self.timer > 0.0;
+        self.timer = (self.timer - delta()).max(0.0);
+        self.is_on
+    }
+
+    /// Ends a running pulse.
+    pub fn reset(&mut self) {
+        self.timer = 0.0;
+        self.is_on = false;
+    }
+}
+
+//=========================================================================
+
+/// Flasher relay switching periodically on and off with state change detection.
+///
+/// Useful for warning lamps and indicators where the transitions trigger the
+/// relay click or the warning tone.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::elements::std::relays::FlasherRelay;
+///
+/// // Blink every 2 seconds, stay on for 0.5 seconds
+/// let mut relay = FlasherRelay::new(2.0, 0.5, 0.0);
+///
+/// // In the main loop:
+/// if relay.run(warning) == 1 {
+///     snd_warn.start();
+/// }
+/// lamp.set_brightness(relay.is_on as u8 as f32);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FlasherRelay {
+    /// Time interval for one complete blink cycle
+    interval: f32,
+    /// Duration for which the relay stays "on" during each cycle
+    on_time: f32,
+    /// Current timer value
+    timer: f32,
+    /// Current state of the relay
+    pub is_on: bool,
+    /// Timer value to set when reset() is called
+    reset_time: f32,
+}
+
+impl FlasherRelay {
+    /// Creates a new flasher relay.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Total duration of one blink cycle
+    /// * `on_time` - Duration for which the relay stays "on" (must be ≤ interval)
+    /// * `reset_time` - Timer value to use when reset() is called
+    pub fn new(interval: f32, on_time: f32, reset_time: f32) -> Self {
+        Self {
+            interval,
+            on_time,
+            timer: 0.0,
+            is_on: false,
+            reset_time,
+        }
+    }
+
+    /// Updates the relay and returns state change information.
+    ///
+    /// # Returns
+    ///
+    /// * `1` - Relay just turned on
+    /// * `-1` - Relay just turned off
+    /// * `0` - No state change occurred
+    pub fn tick(&mut self) -> i32 {
+        self.timer += delta();
+        if self.timer > self.interval {
+            self.timer -= self.interval;
+        }
+
+        let new_on = self.timer < self.on_time;
+
+        let result = if new_on && !self.is_on {
+            1
+        } else if !new_on && self.is_on {
+            -1
+        } else {
+            0
+        };
+
+        self.is_on = new_on;
+        result
+    }
+
+    /// Resets the relay to its initial state.
+    ///
+    /// Sets the timer to the configured reset time and turns the relay off.
+    pub fn reset(&mut self) {
+        self.timer = self.reset_time;
+        self.is_on = false;
+    }
+
+    /// Blinks while enabled and resets otherwise.
+    ///
+    /// Returns the state change like [`tick`](Self::tick), switching off by
+    /// disabling the relay is reported as `-1`.
+    pub fn run(&mut self, enable: bool) -> i32 {
+        if enable {
+            self.tick()
+        } else {
+            let was_on = self.is_on;
+            self.reset();
+            -(was_on as i32)
+        }
+    }
+}
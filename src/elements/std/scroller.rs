@@ -4,9 +4,7 @@
 //! - [`Scroller`]: Linear interpolation-based scrolling with constant speed
 //! - [`Pointer`]: Physics-based movement with force and friction simulation
 
-use lotus_script::time::delta;
-
-use crate::api::animation::Animation;
+use crate::api::{animation::Animation, general::delta};
 
 /// A linear interpolation-based scroller that smoothly moves towards a target position.
 ///
@@ -0,0 +1,215 @@
+//! Small generic state machine.
+//!
+//! The [`StateMachine`] holds a state of any copyable type and a list of
+//! transitions between states. Each transition has a guard, which gets the
+//! inputs of the current tick and the time already spent in the state, so
+//! timed steps of a switching sequence are expressed by the same mechanism as
+//! conditions. Transitions are checked in the order they were added, the first
+//! one whose guard holds is taken, at most one per tick.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use pandemist_vehicle_elements::elements::std::state_machine::StateMachine;
+//!
+//! #[derive(Clone, Copy, PartialEq, Debug)]
+//! enum Lamp {
+//!     Off,
+//!     Warmup,
+//!     On,
+//! }
+//!
+//! let mut lamp = StateMachine::new(Lamp::Off)
+//!     .transition(Lamp::Off, Lamp::Warmup, |switch: &bool, _| *switch)
+//!     .timed(Lamp::Warmup, Lamp::On, 2.0, |switch, _| *switch)
+//!     .transition(Lamp::Warmup, Lamp::Off, |switch, _| !*switch)
+//!     .transition(Lamp::On, Lamp::Off, |switch, _| !*switch);
+//!
+//! // In the main loop:
+//! if lamp.tick(&switch) && lamp.is(Lamp::On) {
+//!     snd_click.start();
+//! }
+//! ```
+
+use crate::api::general::delta;
+
+type Guard<C> = Box<dyn Fn(&C, f32) -> bool>;
+
+struct Transition<S, C> {
+    from: S,
+    to: S,
+    guard: Guard<C>,
+}
+
+/// State machine with guarded and timed transitions.
+///
+/// # Type Parameters
+///
+/// * `S` - The state type, usually a fieldless enum
+/// * `C` - The inputs passed to the guards on each tick
+pub struct StateMachine<S: Copy + PartialEq, C> {
+    state: S,
+    state_last: S,
+    time_in_state: f32,
+    transitions: Vec<Transition<S, C>>,
+}
+
+impl<S: Copy + PartialEq, C> StateMachine<S, C> {
+    /// Creates a new state machine without transitions.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - Initial state
+    pub fn new(init: S) -> Self {
+        Self {
+            state: init,
+            state_last: init,
+            time_in_state: 0.0,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Adds a transition taken as soon as the guard holds.
+    ///
+    /// The guard gets the inputs and the time in seconds spent in `from`.
+    #[must_use]
+    pub fn transition(mut self, from: S, to: S, guard: impl Fn(&C, f32) -> bool + 'static) -> Self {
+        self.add_transition(from, to, guard);
+        self
+    }
+
+    /// Adds a transition taken once the state has been active for `delay`
+    /// seconds and the guard holds.
+    #[must_use]
+    pub fn timed(
+        mut self,
+        from: S,
+        to: S,
+        delay: f32,
+        guard: impl Fn(&C, f32) -> bool + 'static,
+    ) -> Self {
+        self.add_transition(from, to, move |inputs, time| {
+            time >= delay && guard(inputs, time)
+        });
+        self
+    }
+
+    /// Adds a transition to an existing state machine.
+    pub fn add_transition(&mut self, from: S, to: S, guard: impl Fn(&C, f32) -> bool + 'static) {
+        self.transitions.push(Transition {
+            from,
+            to,
+            guard: Box::new(guard),
+        });
+    }
+
+    /// Sets the state directly, e.g. by manual operation, bypassing the guards.
+    pub fn set_state(&mut self, state: S) {
+        if state != self.state {
+            self.state_last = self.state;
+            self.state = state;
+            self.time_in_state = 0.0;
+        }
+    }
+
+    /// Current state.
+    pub fn state(&self) -> S {
+        self.state
+    }
+
+    /// State before the last change.
+    pub fn state_last(&self) -> S {
+        self.state_last
+    }
+
+    /// Whether the machine is in the given state.
+    pub fn is(&self, state: S) -> bool {
+        self.state == state
+    }
+
+    /// Time in seconds spent in the current state.
+    pub fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+
+    /// Checks the transitions with the frame time, returns whether the state
+    /// changed.
+    pub fn tick(&mut self, inputs: &C) -> bool {
+        self.update(inputs, delta())
+    }
+
+    /// Checks the transitions with the given time step in seconds, returns
+    /// whether the state changed.
+    pub fn update(&mut self, inputs: &C, dt: f32) -> bool {
+        self.time_in_state += dt;
+
+        let next = self
+            .transitions
+            .iter()
+            .find(|t| t.from == self.state && (t.guard)(inputs, self.time_in_state))
+            .map(|t| t.to);
+
+        match next {
+            Some(to) if to != self.state => {
+                self.state_last = self.state;
+                self.state = to;
+                self.time_in_state = 0.0;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum Step {
+        Idle,
+        Charging,
+        Closed,
+    }
+
+    fn machine() -> StateMachine<Step, bool> {
+        StateMachine::new(Step::Idle)
+            .transition(Step::Idle, Step::Charging, |on, _| *on)
+            .timed(Step::Charging, Step::Closed, 1.0, |on, _| *on)
+            .transition(Step::Charging, Step::Idle, |on, _| !*on)
+    }
+
+    #[test]
+    fn test_timed_transition() {
+        let mut sm = machine();
+        assert!(sm.update(&true, 0.1));
+        assert_eq!(sm.state(), Step::Charging);
+
+        assert!(!sm.update(&true, 0.5));
+        assert!(sm.update(&true, 0.5));
+        assert_eq!(sm.state(), Step::Closed);
+        assert_eq!(sm.state_last(), Step::Charging);
+    }
+
+    #[test]
+    fn test_guard_cancels() {
+        let mut sm = machine();
+        sm.update(&true, 0.1);
+        sm.update(&true, 0.9);
+        assert!(sm.update(&false, 0.1));
+        assert!(sm.is(Step::Idle));
+
+        // Zeit beginnt nach dem Abbruch neu
+        sm.update(&true, 0.1);
+        assert!(!sm.update(&true, 0.5));
+        assert!(sm.is(Step::Charging));
+    }
+
+    #[test]
+    fn test_set_state() {
+        let mut sm = machine();
+        sm.set_state(Step::Closed);
+        assert!(!sm.update(&false, 1.0));
+        assert_eq!(sm.time_in_state(), 1.0);
+    }
+}
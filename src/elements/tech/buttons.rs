@@ -3,11 +3,18 @@
 //! This module provides a flexible push button system that supports various interaction modes
 //! including regular buttons, toggle buttons, hold-to-activate buttons, and rotating buttons.
 //! Each button can be configured with animations, sounds, and different behavioral patterns.
+//! The [`IlluminatedButton`] combines a push button with its indicator lamp.
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
-use crate::api::{animation::Animation, key_event::KeyEvent, sound::Sound};
+use crate::api::{
+    animation::Animation,
+    general::delta,
+    key_event::KeyEvent,
+    light::Light,
+    sound::{PoolOrder, SoundPool},
+};
+use crate::elements::std::relays::FlasherRelay;
 
 /// Defines the different operational modes for push buttons
 ///
@@ -50,8 +57,8 @@ pub struct PushButtonBuilder {
     btn_anim: Animation,
     rot_anim: Animation,
 
-    snd_press: Sound,
-    snd_release: Sound,
+    snd_press: SoundPool,
+    snd_release: SoundPool,
 
     mode: PushButtonMode,
 }
@@ -93,7 +100,21 @@ impl PushButtonBuilder {
     ///
     /// Returns the builder instance for method chaining.
     pub fn snd_press(mut self, name: impl Into<String>) -> Self {
-        self.snd_press = Sound::new_simple(Some(&name.into()));
+        self.snd_press = SoundPool::new_simple(&[&name.into()], PoolOrder::Random);
+        self
+    }
+
+    /// Set alternative sound samples for button press events
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - The samples of which one is played per press
+    ///
+    /// # Returns
+    ///
+    /// Returns the builder instance for method chaining.
+    pub fn snd_press_pool(mut self, pool: SoundPool) -> Self {
+        self.snd_press = pool;
         self
     }
 
@@ -107,7 +128,21 @@ impl PushButtonBuilder {
     ///
     /// Returns the builder instance for method chaining.
     pub fn snd_release(mut self, name: impl Into<String>) -> Self {
-        self.snd_release = Sound::new_simple(Some(&name.into()));
+        self.snd_release = SoundPool::new_simple(&[&name.into()], PoolOrder::Random);
+        self
+    }
+
+    /// Set alternative sound samples for button release events
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - The samples of which one is played per release
+    ///
+    /// # Returns
+    ///
+    /// Returns the builder instance for method chaining.
+    pub fn snd_release_pool(mut self, pool: SoundPool) -> Self {
+        self.snd_release = pool;
         self
     }
 
@@ -185,8 +220,8 @@ pub struct PushButton {
     btn_anim: Animation,
     rot_anim: Animation,
 
-    snd_press: Sound,
-    snd_release: Sound,
+    snd_press: SoundPool,
+    snd_release: SoundPool,
 
     mode: PushButtonMode,
 }
@@ -368,8 +403,8 @@ impl PushButton {
             key_toggle: KeyEvent::new(None, cab_side),
             btn_anim: Animation::new(Some(&animation_name.into())),
             rot_anim: Animation::new(None),
-            snd_press: Sound::new_simple(None),
-            snd_release: Sound::new_simple(None),
+            snd_press: SoundPool::default(),
+            snd_release: SoundPool::default(),
 
             mode: PushButtonMode::Regular,
         }
@@ -549,3 +584,149 @@ impl PushButton {
         self.value && allowed
     }
 }
+
+//=================================================================
+// IlluminatedButton
+//=================================================================
+
+/// Display modes of the lamp of an [`IlluminatedButton`]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum IndicatorMode {
+    /// Lamp is off
+    #[default]
+    Off,
+    /// Lamp is lit permanently
+    Steady,
+    /// Lamp blinks slowly, e.g. for a pending request
+    SlowBlink,
+    /// Lamp blinks fast, e.g. for a fault or an acknowledgement request
+    FastBlink,
+}
+
+/// Builder for creating an [`IlluminatedButton`]
+pub struct IlluminatedButtonBuilder {
+    button: PushButton,
+    light: Light,
+    slow: FlasherRelay,
+    fast: FlasherRelay,
+}
+
+impl IlluminatedButtonBuilder {
+    /// Sets the blink intervals in seconds, the lamp is lit for half of each
+    /// interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `slow` - Interval of the slow blinking (default: 1.0)
+    /// * `fast` - Interval of the fast blinking (default: 0.4)
+    pub fn blink_intervals(mut self, slow: f32, fast: f32) -> Self {
+        self.slow = FlasherRelay::new(slow, slow / 2.0, 0.0);
+        self.fast = FlasherRelay::new(fast, fast / 2.0, 0.0);
+        self
+    }
+
+    pub fn build(self) -> IlluminatedButton {
+        IlluminatedButton {
+            button: self.button,
+            light: self.light,
+            slow: self.slow,
+            fast: self.fast,
+            mode: IndicatorMode::Off,
+            lit: false,
+        }
+    }
+}
+
+/// Push button with an indicator lamp
+///
+/// The lamp is driven by a single [`IndicatorMode`], the blink timing is
+/// generated internally, so the lamp does not have to be wired up per project.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut btn = IlluminatedButton::builder(
+///     PushButton::builder("Btn_Door_Anim", "Btn_Door", Some(CockpitSide::A)).build(),
+///     "L_Btn_Door",
+/// )
+/// .build();
+///
+/// // In the main loop:
+/// btn.set_indicator(if door_request {
+///     IndicatorMode::SlowBlink
+/// } else {
+///     IndicatorMode::Off
+/// });
+/// btn.tick(voltage);
+/// if btn.button.is_just_pressed() {
+///     // ...
+/// }
+/// ```
+pub struct IlluminatedButton {
+    /// The push button itself
+    pub button: PushButton,
+    light: Light,
+    slow: FlasherRelay,
+    fast: FlasherRelay,
+
+    mode: IndicatorMode,
+    /// Whether the lamp is currently lit
+    pub lit: bool,
+}
+
+impl IlluminatedButton {
+    /// Create a builder for an illuminated button
+    ///
+    /// # Arguments
+    ///
+    /// * `button` - The push button
+    /// * `light_name` - Name of the indicator lamp
+    pub fn builder(button: PushButton, light_name: impl Into<String>) -> IlluminatedButtonBuilder {
+        IlluminatedButtonBuilder {
+            button,
+            light: Light::new(Some(&light_name.into())),
+            slow: FlasherRelay::new(1.0, 0.5, 0.0),
+            fast: FlasherRelay::new(0.4, 0.2, 0.0),
+        }
+    }
+
+    /// Sets the display mode of the lamp
+    ///
+    /// A new blink mode always starts with the lamp lit.
+    pub fn set_indicator(&mut self, mode: IndicatorMode) {
+        if mode != self.mode {
+            self.mode = mode;
+            self.slow.reset();
+            self.fast.reset();
+        }
+    }
+
+    /// Current display mode of the lamp
+    pub fn indicator(&self) -> IndicatorMode {
+        self.mode
+    }
+
+    /// Updates the button and the lamp
+    ///
+    /// # Arguments
+    ///
+    /// * `voltage` - Supply of the lamp from 0.0 to 1.0
+    pub fn tick(&mut self, voltage: f32) {
+        self.button.tick();
+
+        self.lit = match self.mode {
+            IndicatorMode::Off => false,
+            IndicatorMode::Steady => true,
+            IndicatorMode::SlowBlink => {
+                self.slow.tick();
+                self.slow.is_on
+            }
+            IndicatorMode::FastBlink => {
+                self.fast.tick();
+                self.fast.is_on
+            }
+        };
+
+        self.light.set_brightness(self.lit as u8 as f32 * voltage);
+    }
+}
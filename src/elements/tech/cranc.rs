@@ -5,9 +5,8 @@
 //! The crank position is automatically animated and can be controlled via key events.
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
-use crate::api::{animation::Animation, key_event::KeyEvent};
+use crate::api::{animation::Animation, general::delta, key_event::KeyEvent};
 
 /// Builder for creating a `Crank` instance with customizable parameters.
 ///
@@ -1,9 +1,13 @@
-//! Decade Switch Component
+//! Decade Switch and Rolling Counter Components
 //!
 //! This module provides a `DecadeSwitch` component that implements a rotary switch
 //! with configurable steps (decades). It's commonly used in aviation and industrial
 //! control interfaces where precise value selection is required.
 //!
+//! The `RollingCounter` is a mechanical counter made of several digit rolls,
+//! as used in energy meters, km counters and INDUSI counters. Each roll turns
+//! the next one on by one digit while passing from its highest digit to zero.
+//!
 //! The switch supports:
 //! - Smooth animated transitions between values
 //! - Keyboard input handling for increment/decrement operations
@@ -29,9 +33,13 @@
 //! ```
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
-use crate::api::{animation::Animation, key_event::KeyEvent};
+use crate::api::{
+    animation::Animation,
+    general::delta,
+    key_event::KeyEvent,
+    variable::{load_persistent, store_persistent},
+};
 
 /// Builder for creating a `DecadeSwitch` with custom configuration.
 ///
@@ -343,3 +351,230 @@ impl DecadeSwitch {
         0.0*/
     }
 }
+
+//=================================================================
+// RollingCounter
+//=================================================================
+
+/// Builder for creating a [`RollingCounter`] with custom configuration.
+pub struct RollingCounterBuilder {
+    cab_side: Option<CockpitSide>,
+
+    base: u8,
+    digit_anims: Vec<Animation>,
+
+    roll_speed: f32,
+    backstop: bool,
+
+    value: f64,
+    persist_var: Option<String>,
+
+    key_reset: KeyEvent,
+}
+
+impl RollingCounterBuilder {
+    /// Adds a digit roll, starting with the least significant one.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Name of the roll animation, receives the digit position from 0 to base
+    pub fn digit(mut self, animation_name: impl Into<String>) -> Self {
+        self.digit_anims
+            .push(Animation::new(Some(&animation_name.into())));
+        self
+    }
+
+    /// Sets the number base of the rolls (default: 10).
+    pub fn base(mut self, base: u8) -> Self {
+        self.base = base.max(2);
+        self
+    }
+
+    /// Sets the maximum speed of the least significant roll in digits per
+    /// second. A speed of 0.0 shows every change immediately.
+    pub fn roll_speed(mut self, roll_speed: f32) -> Self {
+        self.roll_speed = roll_speed;
+        self
+    }
+
+    /// Prevents counting backwards, like the backstop of an energy meter.
+    pub fn backstop(mut self) -> Self {
+        self.backstop = true;
+        self
+    }
+
+    /// Sets the initial value, used if no value is persisted.
+    pub fn init_value(mut self, value: f64) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Keeps the counted value in the given variable.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// Sets the key event resetting the counter to zero.
+    pub fn reset_event(mut self, name: impl Into<String>) -> Self {
+        self.key_reset = KeyEvent::new(Some(&name.into()), self.cab_side);
+        self
+    }
+
+    pub fn build(self) -> RollingCounter {
+        let value = self
+            .persist_var
+            .as_ref()
+            .and_then(|var_name| load_persistent::<f64>(var_name))
+            .unwrap_or(self.value);
+
+        let range = (self.base as f64).powi(self.digit_anims.len() as i32);
+        let value = value.rem_euclid(range);
+
+        let mut counter = RollingCounter {
+            base: self.base,
+            digit_anims: self.digit_anims,
+            roll_speed: self.roll_speed,
+            backstop: self.backstop,
+            range,
+            persist_var: self.persist_var,
+            key_reset: self.key_reset,
+            value,
+            shown: value,
+        };
+        counter.update_anims();
+        counter
+    }
+}
+
+/// Mechanical counter with several digit rolls and carry propagation.
+///
+/// The counted `value` wraps around after the highest value of all rolls.
+/// The shown value follows it with the configured roll speed, so large jumps
+/// visibly roll through.
+///
+/// # Example
+///
+/// ```rust
+/// let mut counter = RollingCounter::builder(Some(CockpitSide::A))
+///     .digit("Km_Digit_0")
+///     .digit("Km_Digit_1")
+///     .digit("Km_Digit_2")
+///     .digit("Km_Digit_3")
+///     .roll_speed(5.0)
+///     .reset_event("Km_Reset")
+///     .build();
+///
+/// // In your game loop
+/// counter.add((v_ground() * delta() / 1000.0) as f64);
+/// counter.tick();
+/// ```
+#[derive(Debug)]
+pub struct RollingCounter {
+    base: u8,
+    digit_anims: Vec<Animation>,
+
+    roll_speed: f32,
+    backstop: bool,
+    range: f64,
+
+    persist_var: Option<String>,
+
+    key_reset: KeyEvent,
+
+    /// Counted value
+    pub value: f64,
+    /// Value currently shown by the rolls
+    pub shown: f64,
+}
+
+impl RollingCounter {
+    pub fn builder(cab_side: Option<CockpitSide>) -> RollingCounterBuilder {
+        RollingCounterBuilder {
+            cab_side,
+            base: 10,
+            digit_anims: Vec::new(),
+            roll_speed: 0.0,
+            backstop: false,
+            value: 0.0,
+            persist_var: None,
+            key_reset: KeyEvent::new(None, cab_side),
+        }
+    }
+
+    /// Counts the given amount, negative amounts count backwards unless the
+    /// counter has a backstop.
+    pub fn add(&mut self, amount: f64) {
+        if self.backstop && amount < 0.0 {
+            return;
+        }
+        self.set(self.value + amount);
+    }
+
+    /// Sets the counted value, the rolls turn to it with the roll speed.
+    pub fn set(&mut self, value: f64) {
+        let value = value.rem_euclid(self.range);
+
+        // Über den Überlauf hinweg in Zählrichtung weiterdrehen
+        let diff = value - self.value;
+        if diff.abs() > self.range / 2.0 {
+            self.shown -= diff.signum() * self.range;
+        }
+
+        self.value = value;
+
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(var_name, self.value);
+        }
+    }
+
+    /// Resets the counter to zero immediately.
+    pub fn reset(&mut self) {
+        self.value = 0.0;
+        self.shown = 0.0;
+
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(var_name, self.value);
+        }
+    }
+
+    /// Updates the reset input and turns the rolls.
+    pub fn tick(&mut self) {
+        if self.key_reset.is_just_pressed() {
+            self.reset();
+        }
+
+        if self.roll_speed > 0.0 {
+            let step = (self.roll_speed * delta()) as f64;
+            self.shown += (self.value - self.shown).clamp(-step, step);
+        } else {
+            self.shown = self.value;
+        }
+
+        self.update_anims();
+    }
+
+    /// Digit shown by the roll with the given index, starting with the least
+    /// significant one.
+    pub fn digit(&self, index: usize) -> u8 {
+        let base = self.base as f64;
+        (self.shown.rem_euclid(self.range) / base.powi(index as i32)).floor() as u8 % self.base
+    }
+
+    fn update_anims(&mut self) {
+        let base = self.base as f64;
+        let shown = self.shown.rem_euclid(self.range);
+
+        let mut lower = 1.0;
+        for anim in &mut self.digit_anims {
+            // Übertrag: die Rolle dreht nur mit, während alle niedrigeren
+            // Rollen von der höchsten Ziffer auf Null wechseln
+            let digit = (shown / lower).floor() % base;
+            let rest = shown % lower;
+            let carry = (rest - (lower - 1.0)).max(0.0);
+
+            anim.set((digit + carry) as f32);
+            lower *= base;
+        }
+    }
+}
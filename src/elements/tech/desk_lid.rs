@@ -0,0 +1,273 @@
+//! Folding desk lid of the driver's cab.
+//!
+//! This module provides a [`DeskLid`] for trams with a folding desk cover. The
+//! lid moves with the physics of a [`Slider`], can be locked in the closed
+//! position by a key switch, holds in detents at half-open positions and
+//! blocks the console elements below it while it is closed.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lotus_extra::vehicle::CockpitSide;
+//!
+//! let mut lid = DeskLid::builder(
+//!     Slider::builder()
+//!         .axis_y()
+//!         .force(-2.0)
+//!         .friction(1.0)
+//!         .key_event("DeskLid_Grab", Some(CockpitSide::A))
+//!         .animation("DeskLid_A"),
+//! )
+//! .detent(0.5)
+//! .snd_latch("Snd_DeskLid_Latch")
+//! .build();
+//!
+//! // In your main loop
+//! lid.tick(key_switch.value(true));
+//!
+//! let horn = horn_button.value(lid.console_accessible());
+//! ```
+
+use crate::{
+    api::sound::Sound,
+    elements::tech::slider::{Slider, SliderBuilder},
+};
+
+/// Builder for creating a [`DeskLid`] with customizable configuration.
+pub struct DeskLidBuilder {
+    slider: SliderBuilder,
+
+    detents: Vec<f32>,
+    detent_range: f32,
+    detent_speed: f32,
+
+    closed_range: f32,
+    access_pos: f32,
+
+    snd_detent: Sound,
+    snd_latch: Sound,
+}
+
+impl DeskLidBuilder {
+    /// Adds a detent in which the released lid holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - Position of the detent between the minimum and maximum of the slider
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn detent(mut self, pos: f32) -> Self {
+        self.detents.push(pos);
+        self
+    }
+
+    /// Sets the capture range of the detents.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - Distance to the detent within which the lid is captured
+    /// * `speed` - Maximum speed of the lid at which it is captured
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn detent_range(mut self, range: f32, speed: f32) -> Self {
+        self.detent_range = range;
+        self.detent_speed = speed;
+        self
+    }
+
+    /// Sets the range above the minimum in which the lid counts as closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - Distance to the slider minimum
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn closed_range(mut self, range: f32) -> Self {
+        self.closed_range = range;
+        self
+    }
+
+    /// Sets the position from which the console below the lid can be operated.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - Minimum opening of the lid
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn access_pos(mut self, pos: f32) -> Self {
+        self.access_pos = pos;
+        self
+    }
+
+    /// Sets the sound played when the lid snaps into a detent.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sound
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn snd_detent(mut self, name: impl Into<String>) -> Self {
+        self.snd_detent = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sets the sound played when the closed lid is latched by the lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sound
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn snd_latch(mut self, name: impl Into<String>) -> Self {
+        self.snd_latch = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Constructs the final [`DeskLid`] instance.
+    pub fn build(self) -> DeskLid {
+        let slider = self.slider.build();
+
+        DeskLid {
+            closed_pos: slider.min,
+            slider,
+            detents: self.detents,
+            detent_range: self.detent_range,
+            detent_speed: self.detent_speed,
+            closed_range: self.closed_range,
+            access_pos: self.access_pos,
+            snd_detent: self.snd_detent,
+            snd_latch: self.snd_latch,
+            in_detent: None,
+            latched: false,
+        }
+    }
+}
+
+/// Folding desk lid with lock, detents and console interlock.
+pub struct DeskLid {
+    /// The slider moving the lid
+    pub slider: Slider,
+
+    closed_pos: f32,
+
+    detents: Vec<f32>,
+    detent_range: f32,
+    detent_speed: f32,
+
+    closed_range: f32,
+    access_pos: f32,
+
+    snd_detent: Sound,
+    snd_latch: Sound,
+
+    in_detent: Option<usize>,
+
+    /// Whether the closed lid is held by the lock
+    pub latched: bool,
+}
+
+impl DeskLid {
+    /// Creates a new desk lid builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `slider` - Configured slider for the movement of the lid, the slider
+    ///   minimum is the closed position
+    ///
+    /// # Returns
+    ///
+    /// A [`DeskLidBuilder`] for configuring the desk lid.
+    pub fn builder(slider: SliderBuilder) -> DeskLidBuilder {
+        DeskLidBuilder {
+            slider,
+            detents: Vec::new(),
+            detent_range: 0.05,
+            detent_speed: 0.5,
+            closed_range: 0.01,
+            access_pos: 0.3,
+            snd_detent: Sound::new_simple(None),
+            snd_latch: Sound::new_simple(None),
+        }
+    }
+
+    /// Updates the lid.
+    ///
+    /// # Arguments
+    ///
+    /// * `unlocked` - Whether the lock (e.g. the key switch) releases the lid
+    pub fn tick(&mut self, unlocked: bool) {
+        if self.latched {
+            // Verriegelter Deckel bleibt geschlossen
+            if unlocked {
+                self.latched = false;
+            } else {
+                self.slider.speed = 0.0;
+                self.slider.set_pos(self.closed_pos);
+                return;
+            }
+        }
+
+        self.slider.tick();
+
+        if !unlocked && self.is_closed() {
+            self.latched = true;
+            self.slider.speed = 0.0;
+            self.slider.set_pos(self.closed_pos);
+            self.snd_latch.start();
+            return;
+        }
+
+        // Rasten nur im losgelassenen Zustand
+        if self.slider.key_grab.is_pressed() {
+            self.in_detent = None;
+            return;
+        }
+
+        let pos = self.slider.pos;
+        let detent = self
+            .detents
+            .iter()
+            .position(|d| (pos - d).abs() < self.detent_range);
+
+        match detent {
+            Some(idx) if self.in_detent == Some(idx) => {
+                self.slider.speed = 0.0;
+                self.slider.set_pos(self.detents[idx]);
+            }
+            Some(idx) if self.slider.speed.abs() < self.detent_speed => {
+                self.in_detent = Some(idx);
+                self.slider.speed = 0.0;
+                self.slider.set_pos(self.detents[idx]);
+                self.snd_detent.start();
+            }
+            _ => self.in_detent = None,
+        }
+    }
+
+    /// Returns whether the lid is closed.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.slider.pos <= self.closed_pos + self.closed_range
+    }
+
+    /// Returns whether the console below the lid can be operated.
+    ///
+    /// Pass the value as `allowed` to the console elements, e.g.
+    /// [`PushButton::value`](crate::elements::tech::buttons::PushButton::value).
+    #[must_use]
+    pub fn console_accessible(&self) -> bool {
+        self.slider.pos >= self.access_pos
+    }
+}
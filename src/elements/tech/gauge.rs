@@ -0,0 +1,265 @@
+//! Analog gauge instrument with a damped needle.
+//!
+//! This module provides a [`Gauge`] for pointer instruments like pressure gauges
+//! or speedometers. The needle is modelled as a damped spring-mass system, so it
+//! can overshoot the displayed value depending on the configured damping. The
+//! displayed value is mapped to the needle animation through a scale function,
+//! and the needle can be made to tremble with the vibrations of the vehicle.
+
+use lotus_extra::math::PiecewiseLinearFunction;
+
+use crate::api::{animation::Animation, general::delta, light::Light, random::gen_f32};
+
+/// Builder for creating a [`Gauge`] with customizable needle dynamics.
+///
+/// # Examples
+///
+/// ```rust
+/// use lotus_extra::math::PiecewiseLinearFunction;
+///
+/// let gauge = Gauge::builder("A_Manometer_HL")
+///     .scale(PiecewiseLinearFunction::new(vec![(0.0, 0.0), (10.0, 270.0)]))
+///     .dynamics(6.0, 0.4)
+///     .limits(0.0, 12.0)
+///     .illumination("L_Manometer")
+///     .jitter(0.02)
+///     .build();
+/// ```
+pub struct GaugeBuilder {
+    natural_frequency: f32,
+    damping_ratio: f32,
+    min: f32,
+    max: f32,
+    stop_bounce: f32,
+    jitter: f32,
+
+    scale: Option<PiecewiseLinearFunction>,
+
+    needle_anim: Animation,
+    light: Light,
+}
+
+impl GaugeBuilder {
+    /// Sets the scale mapping from the displayed value to the needle animation.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - Function mapping the displayed value to the animation value,
+    ///   e.g. bar to degrees
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn scale(mut self, scale: PiecewiseLinearFunction) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Sets the dynamics of the needle.
+    ///
+    /// The natural frequency defines how fast the needle follows the value,
+    /// the damping ratio how much it overshoots. A damping ratio of 1.0 or
+    /// more lets the needle creep towards the value without overshooting,
+    /// smaller values make it swing over.
+    ///
+    /// # Arguments
+    ///
+    /// * `natural_frequency` - Natural angular frequency of the needle in 1/s
+    /// * `damping_ratio` - Damping ratio of the needle
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn dynamics(mut self, natural_frequency: f32, damping_ratio: f32) -> Self {
+        self.natural_frequency = natural_frequency;
+        self.damping_ratio = damping_ratio;
+        self
+    }
+
+    /// Sets the end stops of the needle in units of the displayed value.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Lower end stop
+    /// * `max` - Upper end stop
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn limits(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Sets how much speed the needle keeps when bouncing off an end stop.
+    ///
+    /// # Arguments
+    ///
+    /// * `stop_bounce` - Factor between 0.0 (no bounce) and 1.0
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn stop_bounce(mut self, stop_bounce: f32) -> Self {
+        self.stop_bounce = stop_bounce;
+        self
+    }
+
+    /// Sets the trembling of the needle at full vibration.
+    ///
+    /// # Arguments
+    ///
+    /// * `jitter` - Amplitude of the trembling in units of the displayed value
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn jitter(mut self, jitter: f32) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Adds an illumination to the gauge.
+    ///
+    /// # Arguments
+    ///
+    /// * `light_name` - Name of the light variable
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn illumination(mut self, light_name: impl Into<String>) -> Self {
+        self.light = Light::new(Some(&light_name.into()));
+        self
+    }
+
+    /// Constructs the final [`Gauge`] instance.
+    pub fn build(self) -> Gauge {
+        Gauge {
+            natural_frequency: self.natural_frequency,
+            damping_ratio: self.damping_ratio,
+            min: self.min,
+            max: self.max,
+            stop_bounce: self.stop_bounce,
+            jitter: self.jitter,
+            scale: self.scale,
+            pos: self.min,
+            speed: 0.0,
+            needle_anim: self.needle_anim,
+            light: self.light,
+        }
+    }
+}
+
+/// Analog pointer instrument with damped needle, scale and illumination.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut gauge = Gauge::builder("A_Manometer_HLB")
+///     .dynamics(5.0, 0.5)
+///     .limits(0.0, 12.0)
+///     .build();
+///
+/// // In the main loop:
+/// gauge.tick(reservoir.pressure, 0.2);
+/// gauge.set_illumination(cab_light * voltage);
+/// ```
+#[derive(Debug)]
+pub struct Gauge {
+    natural_frequency: f32,
+    damping_ratio: f32,
+    min: f32,
+    max: f32,
+    stop_bounce: f32,
+    jitter: f32,
+
+    scale: Option<PiecewiseLinearFunction>,
+
+    /// Current needle position in units of the displayed value
+    pub pos: f32,
+    speed: f32,
+
+    needle_anim: Animation,
+    light: Light,
+}
+
+impl Gauge {
+    /// Creates a new gauge builder with the specified needle animation name.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Name of the needle animation
+    ///
+    /// # Returns
+    ///
+    /// A new [`GaugeBuilder`] instance for configuration.
+    pub fn builder(animation_name: impl Into<String>) -> GaugeBuilder {
+        GaugeBuilder {
+            natural_frequency: 8.0,
+            damping_ratio: 0.7,
+            min: 0.0,
+            max: f32::MAX,
+            stop_bounce: 0.3,
+            jitter: 0.0,
+            scale: None,
+            needle_anim: Animation::new(Some(&animation_name.into())),
+            light: Light::new(None),
+        }
+    }
+
+    /// Updates the needle for one frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Value the gauge should display
+    /// * `vibration` - Vibration of the vehicle from 0.0 (none) to 1.0 (full)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut gauge = Gauge::builder("A_Manometer").build();
+    /// gauge.tick(5.0, 0.0);
+    /// ```
+    pub fn tick(&mut self, value: f32, vibration: f32) {
+        let omega = self.natural_frequency;
+        let acc =
+            omega * omega * (value - self.pos) - 2.0 * self.damping_ratio * omega * self.speed;
+
+        self.speed += acc * delta();
+        self.pos += self.speed * delta();
+
+        if self.pos < self.min {
+            self.pos = self.min;
+            self.speed *= -self.stop_bounce;
+        } else if self.pos > self.max {
+            self.pos = self.max;
+            self.speed *= -self.stop_bounce;
+        }
+
+        let jitter = if self.jitter > 0.0 && vibration > 0.0 {
+            gen_f32(-1.0..=1.0) * self.jitter * vibration.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let shown = (self.pos + jitter).clamp(self.min, self.max);
+
+        let anim_value = if let Some(scale) = &self.scale {
+            scale.get_value_or_default(shown)
+        } else {
+            shown
+        };
+        self.needle_anim.set(anim_value);
+    }
+
+    /// Sets the brightness of the gauge illumination.
+    ///
+    /// # Arguments
+    ///
+    /// * `brightness` - Brightness of the illumination, e.g. switch state times voltage
+    pub fn set_illumination(&self, brightness: f32) {
+        self.light.set_brightness(brightness);
+    }
+}
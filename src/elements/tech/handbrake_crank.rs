@@ -0,0 +1,358 @@
+//! Handbrake wheel or crank.
+//!
+//! This module provides a [`HandbrakeCrank`] which is turned over several
+//! revolutions with the mouse or with keys. The brake force builds up along a
+//! curve over the turns, a ratchet clicks on every segment of a revolution and
+//! lamps show whether the handbrake is applied or released. On vehicles where
+//! the handbrake acts on the spring brake, [`HandbrakeCrank::applied`] is
+//! passed as `apply` to
+//! [`SpringBrakeActuator::tick`](crate::components::brakes::spring_brake::SpringBrakeActuator::tick),
+//! which reports it on the `CouplerSpringBrake` line.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
+//!
+//! let mut handbrake = HandbrakeCrank::builder("Handbrake_Wheel", Some(CockpitSide::A))
+//!     .turns(6.0)
+//!     .event_grab("Handbrake_Grab")
+//!     .events("Handbrake_Apply", "Handbrake_Release")
+//!     .force(8000.0, Some(PiecewiseLinearFunction::new(vec![(0.0, 0.0), (0.5, 0.1), (1.0, 1.0)])))
+//!     .snd_ratchet("Snd_Handbrake_Ratchet", 8)
+//!     .lights("L_Handbrake_Applied", "L_Handbrake_Released")
+//!     .build();
+//!
+//! // In your main loop
+//! handbrake.tick(voltage > 0.5);
+//! let force = handbrake.brake_force;
+//! spring_brake.tick(voltage > 0.7, handbrake.applied, reservoir.pressure);
+//! ```
+
+use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
+
+use crate::api::{
+    animation::Animation,
+    general::{delta, mouse_move},
+    key_event::KeyEvent,
+    light::Light,
+    sound::Sound,
+};
+
+/// Builder for creating a [`HandbrakeCrank`] with customizable configuration.
+pub struct HandbrakeCrankBuilder {
+    cab_side: Option<CockpitSide>,
+
+    turns: f32,
+    max_turns: f32,
+
+    mouse_factor: f32,
+    key_speed: f32,
+
+    max_force: f32,
+    force_curve: Option<PiecewiseLinearFunction>,
+    applied_threshold: f32,
+
+    segments_per_turn: u32,
+
+    anim: Animation,
+
+    key_grab: KeyEvent,
+    key_apply: KeyEvent,
+    key_release: KeyEvent,
+
+    snd_ratchet: Sound,
+
+    lm_applied: Light,
+    lm_released: Light,
+}
+
+impl HandbrakeCrankBuilder {
+    /// Sets the number of turns from released to fully applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_turns` - Number of revolutions
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn turns(mut self, max_turns: f32) -> Self {
+        self.max_turns = max_turns.max(0.1);
+        self
+    }
+
+    /// Sets the initial position.
+    ///
+    /// # Arguments
+    ///
+    /// * `turns` - Initial number of turns
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn init_turns(mut self, turns: f32) -> Self {
+        self.turns = turns;
+        self
+    }
+
+    /// Sets the key event for grabbing the wheel with the mouse.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the key event
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn event_grab(mut self, name: impl Into<String>) -> Self {
+        self.key_grab = KeyEvent::new(Some(&name.into()), self.cab_side);
+        self
+    }
+
+    /// Sets the key events turning the wheel.
+    ///
+    /// # Arguments
+    ///
+    /// * `apply` - Name of the key event applying the brake
+    /// * `release` - Name of the key event releasing the brake
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn events(mut self, apply: impl Into<String>, release: impl Into<String>) -> Self {
+        self.key_apply = KeyEvent::new(Some(&apply.into()), self.cab_side);
+        self.key_release = KeyEvent::new(Some(&release.into()), self.cab_side);
+        self
+    }
+
+    /// Sets the mouse sensitivity and the turning speed with the keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `mouse_factor` - Turns per mouse unit
+    /// * `key_speed` - Turns per second while a key is pressed
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn handling(mut self, mouse_factor: f32, key_speed: f32) -> Self {
+        self.mouse_factor = mouse_factor;
+        self.key_speed = key_speed;
+        self
+    }
+
+    /// Sets the brake force and its build-up over the turns.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_force` - Brake force in N when fully applied
+    /// * `curve` - Optional curve mapping the turns (0.0 to 1.0 of the maximum turns)
+    ///   to the force (0.0 to 1.0 of the maximum force), linear if `None`
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn force(mut self, max_force: f32, curve: Option<PiecewiseLinearFunction>) -> Self {
+        self.max_force = max_force;
+        self.force_curve = curve;
+        self
+    }
+
+    /// Sets the share of the maximum force above which the brake counts as applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Share of the maximum force from 0.0 to 1.0
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn applied_threshold(mut self, threshold: f32) -> Self {
+        self.applied_threshold = threshold;
+        self
+    }
+
+    /// Sets the ratchet sound.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sound
+    /// * `segments_per_turn` - Number of clicks per revolution
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn snd_ratchet(mut self, name: impl Into<String>, segments_per_turn: u32) -> Self {
+        self.snd_ratchet = Sound::new_simple(Some(&name.into()));
+        self.segments_per_turn = segments_per_turn.max(1);
+        self
+    }
+
+    /// Sets the dashboard lamps.
+    ///
+    /// # Arguments
+    ///
+    /// * `applied` - Name of the lamp lit while the brake is applied
+    /// * `released` - Name of the lamp lit while the brake is released
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn lights(mut self, applied: impl Into<String>, released: impl Into<String>) -> Self {
+        self.lm_applied = Light::new(Some(&applied.into()));
+        self.lm_released = Light::new(Some(&released.into()));
+        self
+    }
+
+    /// Constructs the final [`HandbrakeCrank`] instance.
+    pub fn build(self) -> HandbrakeCrank {
+        let mut handbrake = HandbrakeCrank {
+            max_turns: self.max_turns,
+            mouse_factor: self.mouse_factor,
+            key_speed: self.key_speed,
+            max_force: self.max_force,
+            force_curve: self.force_curve,
+            applied_threshold: self.applied_threshold,
+            segments_per_turn: self.segments_per_turn,
+            anim: self.anim,
+            key_grab: self.key_grab,
+            key_apply: self.key_apply,
+            key_release: self.key_release,
+            snd_ratchet: self.snd_ratchet,
+            lm_applied: self.lm_applied,
+            lm_released: self.lm_released,
+            segment_last: 0,
+            turns: self.turns.clamp(0.0, self.max_turns),
+            brake_force: 0.0,
+            applied: false,
+        };
+        handbrake.segment_last = handbrake.segment();
+        handbrake.update_force();
+        handbrake.anim.set(handbrake.turns);
+        handbrake
+    }
+}
+
+/// Multi-turn handbrake with force build-up and ratchet.
+pub struct HandbrakeCrank {
+    max_turns: f32,
+
+    mouse_factor: f32,
+    key_speed: f32,
+
+    max_force: f32,
+    force_curve: Option<PiecewiseLinearFunction>,
+    applied_threshold: f32,
+
+    segments_per_turn: u32,
+    segment_last: i32,
+
+    anim: Animation,
+
+    key_grab: KeyEvent,
+    key_apply: KeyEvent,
+    key_release: KeyEvent,
+
+    snd_ratchet: Sound,
+
+    lm_applied: Light,
+    lm_released: Light,
+
+    /// Current number of turns from the released position
+    pub turns: f32,
+    /// Current brake force in N
+    pub brake_force: f32,
+    /// Whether the handbrake is applied
+    pub applied: bool,
+}
+
+impl HandbrakeCrank {
+    /// Creates a new handbrake builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Name of the wheel animation, driven with the number of turns
+    /// * `cab_side` - Optional cab side for key event handling
+    ///
+    /// # Returns
+    ///
+    /// A [`HandbrakeCrankBuilder`] for configuring the handbrake.
+    pub fn builder(
+        animation_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> HandbrakeCrankBuilder {
+        HandbrakeCrankBuilder {
+            cab_side,
+            turns: 0.0,
+            max_turns: 5.0,
+            mouse_factor: 0.01,
+            key_speed: 1.0,
+            max_force: 0.0,
+            force_curve: None,
+            applied_threshold: 0.05,
+            segments_per_turn: 8,
+            anim: Animation::new(Some(&animation_name.into())),
+            key_grab: KeyEvent::new(None, None),
+            key_apply: KeyEvent::new(None, None),
+            key_release: KeyEvent::new(None, None),
+            snd_ratchet: Sound::new_simple(None),
+            lm_applied: Light::new(None),
+            lm_released: Light::new(None),
+        }
+    }
+
+    /// Updates the wheel, the force and the lamps.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the dashboard lamps are supplied
+    pub fn tick(&mut self, power: bool) {
+        let mut turns = self.turns;
+        if self.key_grab.is_pressed() {
+            turns += mouse_move().x * self.mouse_factor;
+        }
+        if self.key_apply.is_pressed() {
+            turns += self.key_speed * delta();
+        }
+        if self.key_release.is_pressed() {
+            turns -= self.key_speed * delta();
+        }
+        turns = turns.clamp(0.0, self.max_turns);
+
+        if turns != self.turns {
+            self.turns = turns;
+            self.anim.set(turns);
+        }
+
+        // Klinke pro Segment
+        let segment = self.segment();
+        if segment != self.segment_last {
+            self.snd_ratchet.start();
+            self.segment_last = segment;
+        }
+
+        self.update_force();
+
+        self.lm_applied
+            .set_brightness((power && self.applied) as u8 as f32);
+        self.lm_released
+            .set_brightness((power && !self.applied) as u8 as f32);
+    }
+
+    fn segment(&self) -> i32 {
+        (self.turns * self.segments_per_turn as f32).floor() as i32
+    }
+
+    fn update_force(&mut self) {
+        let share = self.turns / self.max_turns;
+        let force_share = match &self.force_curve {
+            Some(curve) => curve.get_value_or_default(share),
+            None => share,
+        }
+        .clamp(0.0, 1.0);
+
+        self.brake_force = force_share * self.max_force;
+        self.applied = force_share > self.applied_threshold;
+    }
+}
@@ -5,10 +5,13 @@
 //! The system supports both free movement and constrained single-axis movement.
 
 use lotus_extra::vehicle::CockpitSide;
-use lotus_script::time::delta;
 
 use crate::{
-    api::{animation::Animation, general::mouse_move, key_event::KeyEvent},
+    api::{
+        animation::Animation,
+        general::{delta, mouse_move},
+        key_event::KeyEvent,
+    },
     management::structs::general_structs::FourDirections,
 };
 
@@ -0,0 +1,397 @@
+//! Combined traction and brake controller ("Fahrschalter").
+//!
+//! This module provides a [`MasterController`], a single lever for traction
+//! and braking. Positive positions demand traction, negative positions demand
+//! braking and zero is coasting. The lever can be dragged with the mouse or
+//! stepped from detent to detent with key events.
+//!
+//! ## Features
+//!
+//! - Notch detents the lever snaps into, each with its own notch sound
+//! - Spring zones from which the released lever returns to coasting
+//! - Mechanical lock keeping the lever in zero while the reverser is in neutral
+//! - Output curves for the traction and braking demand
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
+//!
+//! let mut controller = MasterController::builder("A_Fahrschalter", Some(CockpitSide::A))
+//!     .grab_event("Fahrschalter_Grab")
+//!     .step_events("Fahrschalter_Plus", "Fahrschalter_Minus")
+//!     .add_detent(0.0, Some("Snd_Fahrschalter_Null"))
+//!     .add_detent(-1.0, Some("Snd_Fahrschalter_Notbremse"))
+//!     .spring_zone(0.0, 0.2)
+//!     .traction_curve(PiecewiseLinearFunction::new(vec![(0.0, 0.0), (0.1, 0.0), (1.0, 1.0)]))
+//!     .build();
+//!
+//! // In your main loop
+//! controller.tick(!reverser.direction.is_none());
+//! let traction = controller.traction();
+//! let brake = controller.brake();
+//! ```
+
+use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
+
+use crate::api::{
+    animation::Animation,
+    general::{delta, mouse_move},
+    key_event::KeyEvent,
+    sound::Sound,
+};
+
+/// Builder for creating a [`MasterController`] with customizable configuration.
+pub struct MasterControllerBuilder {
+    cab_side: Option<CockpitSide>,
+
+    min: f32,
+    max: f32,
+    mouse_factor: f32,
+    snap_range: f32,
+    spring_speed: f32,
+
+    detents: Vec<(f32, Sound)>,
+    spring_zones: Vec<(f32, f32)>,
+
+    traction_curve: Option<PiecewiseLinearFunction>,
+    brake_curve: Option<PiecewiseLinearFunction>,
+
+    key_grab: KeyEvent,
+    key_plus: KeyEvent,
+    key_minus: KeyEvent,
+
+    anim: Animation,
+}
+
+impl MasterControllerBuilder {
+    /// Sets the end positions of the lever.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Position of full braking, usually -1.0
+    /// * `max` - Position of full traction, usually 1.0
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Sets the key event to grab the lever with the mouse.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the key event
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn grab_event(mut self, name: impl Into<String>) -> Self {
+        self.key_grab = KeyEvent::new(Some(&name.into()), self.cab_side);
+        self
+    }
+
+    /// Sets the key events to move the lever to the next detent.
+    ///
+    /// # Arguments
+    ///
+    /// * `plus_name` - Key event towards traction
+    /// * `minus_name` - Key event towards braking
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn step_events(
+        mut self,
+        plus_name: impl Into<String>,
+        minus_name: impl Into<String>,
+    ) -> Self {
+        self.key_plus = KeyEvent::new(Some(&plus_name.into()), self.cab_side);
+        self.key_minus = KeyEvent::new(Some(&minus_name.into()), self.cab_side);
+        self
+    }
+
+    /// Adds a detent the released lever snaps into.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - Position of the detent
+    /// * `sound_name` - Notch sound played when the lever passes the detent
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn add_detent(mut self, pos: f32, sound_name: Option<&str>) -> Self {
+        self.detents.push((pos, Sound::new_simple(sound_name)));
+        self.detents.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self
+    }
+
+    /// Sets the distance within which the released lever snaps into a detent.
+    ///
+    /// # Arguments
+    ///
+    /// * `snap_range` - Maximum distance to the detent
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn snap_range(mut self, snap_range: f32) -> Self {
+        self.snap_range = snap_range;
+        self
+    }
+
+    /// Adds a zone from which the released lever springs back to zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Lower end of the zone
+    /// * `to` - Upper end of the zone
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn spring_zone(mut self, from: f32, to: f32) -> Self {
+        self.spring_zones.push((from.min(to), from.max(to)));
+        self
+    }
+
+    /// Sets the speed of the spring return per second.
+    ///
+    /// # Arguments
+    ///
+    /// * `spring_speed` - Speed in positions per second
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn spring_speed(mut self, spring_speed: f32) -> Self {
+        self.spring_speed = spring_speed;
+        self
+    }
+
+    /// Sets the factor between mouse movement and lever movement.
+    ///
+    /// # Arguments
+    ///
+    /// * `mouse_factor` - Lever movement per mouse unit
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn mouse_factor(mut self, mouse_factor: f32) -> Self {
+        self.mouse_factor = mouse_factor;
+        self
+    }
+
+    /// Sets the curve mapping the traction position (0.0 to max) to the
+    /// traction demand.
+    ///
+    /// # Arguments
+    ///
+    /// * `curve` - Output curve of the traction demand
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn traction_curve(mut self, curve: PiecewiseLinearFunction) -> Self {
+        self.traction_curve = Some(curve);
+        self
+    }
+
+    /// Sets the curve mapping the brake position (0.0 to -min) to the
+    /// braking demand.
+    ///
+    /// # Arguments
+    ///
+    /// * `curve` - Output curve of the braking demand
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn brake_curve(mut self, curve: PiecewiseLinearFunction) -> Self {
+        self.brake_curve = Some(curve);
+        self
+    }
+
+    /// Constructs the final [`MasterController`] instance.
+    pub fn build(self) -> MasterController {
+        MasterController {
+            min: self.min,
+            max: self.max,
+            mouse_factor: self.mouse_factor,
+            snap_range: self.snap_range,
+            spring_speed: self.spring_speed,
+            detents: self.detents,
+            spring_zones: self.spring_zones,
+            traction_curve: self.traction_curve,
+            brake_curve: self.brake_curve,
+            key_grab: self.key_grab,
+            key_plus: self.key_plus,
+            key_minus: self.key_minus,
+            pos: 0.0,
+            locked: false,
+            anim: self.anim,
+        }
+    }
+}
+
+/// Combined traction and brake lever with detents and spring zones.
+#[derive(Debug)]
+pub struct MasterController {
+    min: f32,
+    max: f32,
+    mouse_factor: f32,
+    snap_range: f32,
+    spring_speed: f32,
+
+    detents: Vec<(f32, Sound)>,
+    spring_zones: Vec<(f32, f32)>,
+
+    traction_curve: Option<PiecewiseLinearFunction>,
+    brake_curve: Option<PiecewiseLinearFunction>,
+
+    key_grab: KeyEvent,
+    key_plus: KeyEvent,
+    key_minus: KeyEvent,
+
+    /// Current lever position, positive for traction, negative for braking
+    pub pos: f32,
+    /// Whether the mechanical lock is engaged
+    pub locked: bool,
+
+    anim: Animation,
+}
+
+impl MasterController {
+    /// Creates a new master controller builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Name of the lever animation
+    /// * `cab_side` - Optional cab side for key event handling
+    ///
+    /// # Returns
+    ///
+    /// A [`MasterControllerBuilder`] for configuring the controller.
+    pub fn builder(
+        animation_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> MasterControllerBuilder {
+        MasterControllerBuilder {
+            cab_side,
+            min: -1.0,
+            max: 1.0,
+            mouse_factor: 0.01,
+            snap_range: 0.05,
+            spring_speed: 2.0,
+            detents: Vec::new(),
+            spring_zones: Vec::new(),
+            traction_curve: None,
+            brake_curve: None,
+            key_grab: KeyEvent::new(None, None),
+            key_plus: KeyEvent::new(None, None),
+            key_minus: KeyEvent::new(None, None),
+            anim: Animation::new(Some(&animation_name.into())),
+        }
+    }
+
+    /// Updates the lever position based on mouse and key events.
+    ///
+    /// # Arguments
+    ///
+    /// * `unlocked` - Whether the lever may leave zero, e.g. reverser not in neutral
+    pub fn tick(&mut self, unlocked: bool) {
+        self.locked = !unlocked;
+        let pos_last = self.pos;
+        let mut pos = self.pos;
+
+        if self.key_plus.is_just_pressed() {
+            if let Some((detent, _)) = self.detents.iter().find(|(d, _)| *d > pos) {
+                pos = *detent;
+            }
+        }
+        if self.key_minus.is_just_pressed() {
+            if let Some((detent, _)) = self.detents.iter().rev().find(|(d, _)| *d < pos) {
+                pos = *detent;
+            }
+        }
+
+        if self.key_grab.is_pressed() {
+            pos += mouse_move().y * self.mouse_factor;
+        } else if self
+            .spring_zones
+            .iter()
+            .any(|(from, to)| pos != 0.0 && pos >= *from && pos <= *to)
+        {
+            // Federrückstellung auf Fahrtstufe 0
+            let step = self.spring_speed * delta();
+            pos = if pos > 0.0 {
+                (pos - step).max(0.0)
+            } else {
+                (pos + step).min(0.0)
+            };
+        } else if let Some((detent, _)) = self
+            .detents
+            .iter()
+            .filter(|(d, _)| (*d - pos).abs() <= self.snap_range)
+            .min_by(|a, b| (a.0 - pos).abs().total_cmp(&(b.0 - pos).abs()))
+        {
+            pos = *detent;
+        }
+
+        pos = pos.clamp(self.min, self.max);
+
+        // Mechanische Sperre: nur Richtung Nullstellung
+        if self.locked {
+            if pos * pos_last < 0.0 || pos_last == 0.0 {
+                pos = 0.0;
+            } else if pos.abs() > pos_last.abs() {
+                pos = pos_last;
+            }
+        }
+
+        for (detent, snd) in &mut self.detents {
+            let passed =
+                (pos_last < *detent && pos >= *detent) || (pos_last > *detent && pos <= *detent);
+            if passed {
+                snd.start();
+            }
+        }
+
+        self.pos = pos;
+        self.anim.set(self.pos);
+    }
+
+    /// Returns the traction demand of the lever.
+    ///
+    /// # Returns
+    ///
+    /// The traction position mapped by the traction curve, 0.0 while braking.
+    #[must_use]
+    pub fn traction(&self) -> f32 {
+        let pos = self.pos.max(0.0);
+        match &self.traction_curve {
+            Some(curve) => curve.get_value_or_default(pos),
+            None => pos,
+        }
+    }
+
+    /// Returns the braking demand of the lever.
+    ///
+    /// # Returns
+    ///
+    /// The brake position mapped by the brake curve, 0.0 while in traction.
+    #[must_use]
+    pub fn brake(&self) -> f32 {
+        let pos = (-self.pos).max(0.0);
+        match &self.brake_curve {
+            Some(curve) => curve.get_value_or_default(pos),
+            None => pos,
+        }
+    }
+}
@@ -1,9 +1,16 @@
 pub mod buttons;
 pub mod cranc;
 pub mod dekaden;
+pub mod desk_lid;
+pub mod gauge;
+pub mod handbrake_crank;
 pub mod handpin;
 pub mod key_switch;
+pub mod master_controller;
+pub mod potentiometer;
 pub mod rollerblind;
+pub mod rotary_brake_valve;
 pub mod seals;
 pub mod slider;
+pub mod split_flap;
 pub mod switches;
@@ -0,0 +1,289 @@
+//! Continuous rotary control ("Drehpotentiometer").
+//!
+//! The [`Potentiometer`] is a knob without detents, like the dimmer of the
+//! instrument lighting or the thermostat of a heater. It is turned by
+//! dragging it with the mouse or by holding plus and minus keys. The knob
+//! position from 0.0 to 1.0 is mapped through an optional curve and smoothed
+//! into the output value. Reaching an end stop plays a sound, and the
+//! position can be kept across sessions in a variable.
+
+use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
+use lotus_script::math::Vec2;
+
+use crate::api::{
+    animation::Animation,
+    general::{delta, mouse_move},
+    key_event::KeyEvent,
+    sound::Sound,
+    variable::{load_persistent, store_persistent},
+};
+
+pub struct PotentiometerBuilder {
+    cab_side: Option<CockpitSide>,
+
+    angle: f32,
+    value: f32,
+
+    key_drag: KeyEvent,
+    drag_axis: Vec2,
+    drag_factor: f32,
+
+    key_plus: KeyEvent,
+    key_minus: KeyEvent,
+    ramp_rate: f32,
+
+    curve: Option<PiecewiseLinearFunction>,
+    smoothing: f32,
+
+    persist_var: Option<String>,
+
+    anim: Animation,
+
+    snd_min: Sound,
+    snd_max: Sound,
+}
+
+impl PotentiometerBuilder {
+    /// Turning angle of the knob in degrees between both end stops.
+    pub fn angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// Initial position from 0.0 to 1.0, used if no value is persisted.
+    pub fn init(mut self, value: f32) -> Self {
+        self.value = value.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Turning the knob by dragging it with the mouse.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The key event grabbing the knob
+    /// * `drag_factor` - Change of the position per unit of mouse movement
+    pub fn drag(mut self, name: impl Into<String>, drag_factor: f32) -> Self {
+        self.key_drag = KeyEvent::new(Some(&name.into()), self.cab_side);
+        self.drag_factor = drag_factor;
+        self
+    }
+
+    /// Turns the knob with vertical instead of horizontal mouse movement.
+    pub fn drag_axis_y(mut self) -> Self {
+        self.drag_axis = Vec2 { x: 0.0, y: 1.0 };
+        self
+    }
+
+    /// Turning the knob with keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `plus_name` - The key event turning the knob up
+    /// * `minus_name` - The key event turning the knob down
+    /// * `ramp_rate` - Change of the position per second while a key is held
+    pub fn keys(
+        mut self,
+        plus_name: impl Into<String>,
+        minus_name: impl Into<String>,
+        ramp_rate: f32,
+    ) -> Self {
+        self.key_plus = KeyEvent::new(Some(&plus_name.into()), self.cab_side);
+        self.key_minus = KeyEvent::new(Some(&minus_name.into()), self.cab_side);
+        self.ramp_rate = ramp_rate;
+        self
+    }
+
+    /// Curve mapping the position from 0.0 to 1.0 to the output value.
+    pub fn curve(mut self, curve: PiecewiseLinearFunction) -> Self {
+        self.curve = Some(curve);
+        self
+    }
+
+    /// Time constant in seconds with which the output follows the knob.
+    pub fn smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Keeps the position in the given variable.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// Sounds played when the knob reaches the lower and the upper end stop.
+    pub fn snd_end_stops(
+        mut self,
+        min_name: impl Into<String>,
+        max_name: impl Into<String>,
+    ) -> Self {
+        self.snd_min = Sound::new_simple(Some(&min_name.into()));
+        self.snd_max = Sound::new_simple(Some(&max_name.into()));
+        self
+    }
+
+    pub fn build(self) -> Potentiometer {
+        let value = self
+            .persist_var
+            .as_ref()
+            .and_then(|var_name| load_persistent::<f32>(var_name))
+            .map_or(self.value, |stored| stored.clamp(0.0, 1.0));
+
+        let mut potentiometer = Potentiometer {
+            angle: self.angle,
+            key_drag: self.key_drag,
+            drag_axis: self.drag_axis,
+            drag_factor: self.drag_factor,
+            key_plus: self.key_plus,
+            key_minus: self.key_minus,
+            ramp_rate: self.ramp_rate,
+            curve: self.curve,
+            smoothing: self.smoothing,
+            persist_var: self.persist_var,
+            anim: self.anim,
+            snd_min: self.snd_min,
+            snd_max: self.snd_max,
+            value,
+            output: 0.0,
+        };
+        potentiometer.output = potentiometer.target();
+        potentiometer.anim.set(value * potentiometer.angle);
+        potentiometer
+    }
+}
+
+/// Continuous rotary knob with smoothed output.
+///
+/// # Example
+///
+/// ```rust
+/// let mut dimmer = Potentiometer::builder("Dimmer_Knob", Some(CockpitSide::A))
+///     .angle(270.0)
+///     .drag("Dimmer_Grab", 0.005)
+///     .keys("Dimmer_Plus", "Dimmer_Minus", 0.5)
+///     .curve(PiecewiseLinearFunction::new(vec![(0.0, 0.05), (1.0, 1.0)]))
+///     .persist("Dimmer_Value")
+///     .snd_end_stops("Snd_Knob_Stop", "Snd_Knob_Stop")
+///     .build();
+///
+/// // In the main loop:
+/// dimmer.tick();
+/// instrument_light.set_brightness(dimmer.output * voltage);
+/// ```
+pub struct Potentiometer {
+    angle: f32,
+
+    key_drag: KeyEvent,
+    drag_axis: Vec2,
+    drag_factor: f32,
+
+    key_plus: KeyEvent,
+    key_minus: KeyEvent,
+    ramp_rate: f32,
+
+    curve: Option<PiecewiseLinearFunction>,
+    smoothing: f32,
+
+    persist_var: Option<String>,
+
+    anim: Animation,
+
+    snd_min: Sound,
+    snd_max: Sound,
+
+    /// Position of the knob from 0.0 to 1.0
+    pub value: f32,
+    /// Output value after the curve and the smoothing
+    pub output: f32,
+}
+
+impl Potentiometer {
+    /// Creates a new potentiometer builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Name of the rotation animation in degrees
+    /// * `cab_side` - Optional cab side for key event handling
+    pub fn builder(
+        animation_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> PotentiometerBuilder {
+        PotentiometerBuilder {
+            cab_side,
+            angle: 270.0,
+            value: 0.0,
+            key_drag: KeyEvent::new(None, None),
+            drag_axis: Vec2 { x: 1.0, y: 0.0 },
+            drag_factor: 0.0,
+            key_plus: KeyEvent::new(None, None),
+            key_minus: KeyEvent::new(None, None),
+            ramp_rate: 0.0,
+            curve: None,
+            smoothing: 0.0,
+            persist_var: None,
+            anim: Animation::new(Some(&animation_name.into())),
+            snd_min: Sound::new_simple(None),
+            snd_max: Sound::new_simple(None),
+        }
+    }
+
+    /// Sets the knob to a position from 0.0 to 1.0 without end stop sounds.
+    pub fn set(&mut self, value: f32) {
+        self.value = value.clamp(0.0, 1.0);
+        self.anim.set(self.value * self.angle);
+        self.store();
+    }
+
+    /// Updates the knob from the mouse and key input and the output value.
+    pub fn tick(&mut self) {
+        let mut delta_value = 0.0;
+
+        if self.key_drag.is_pressed() {
+            let vec_mouse = mouse_move() * self.drag_axis;
+            delta_value += (vec_mouse.x + vec_mouse.y) * self.drag_factor;
+        }
+        if self.key_plus.is_pressed() {
+            delta_value += self.ramp_rate * delta();
+        }
+        if self.key_minus.is_pressed() {
+            delta_value -= self.ramp_rate * delta();
+        }
+
+        if delta_value != 0.0 {
+            let value_last = self.value;
+            self.value = (self.value + delta_value).clamp(0.0, 1.0);
+
+            // Anschlag nur beim Erreichen, nicht beim Weiterdrehen
+            if self.value >= 1.0 && value_last < 1.0 {
+                self.snd_max.start();
+            } else if self.value <= 0.0 && value_last > 0.0 {
+                self.snd_min.start();
+            }
+
+            if self.value != value_last {
+                self.anim.set(self.value * self.angle);
+                self.store();
+            }
+        }
+
+        let target = self.target();
+        self.output = if self.smoothing > 0.0 {
+            self.output + (target - self.output) * (delta() / self.smoothing).min(1.0)
+        } else {
+            target
+        };
+    }
+
+    fn target(&self) -> f32 {
+        match &self.curve {
+            Some(curve) => curve.get_value_or_default(self.value),
+            None => self.value,
+        }
+    }
+
+    fn store(&self) {
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(var_name, self.value);
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! Driver's brake valve with rotary handle.
+//!
+//! This module provides a [`RotaryBrakeValve`] whose handle is turned
+//! continuously over the release, lap, apply and emergency sectors. In the
+//! apply sector the brake pipe pressure is reduced proportional to the angle
+//! (self-lapping valve). The valve computes the air flow between the main
+//! reservoir and the brake pipe, which is passed to the brake pipe model of
+//! the vehicle.
+//!
+//! All pressures are gauge pressures in bar, air flows are given in
+//! bar·litre per second.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lotus_extra::vehicle::CockpitSide;
+//!
+//! let mut valve = RotaryBrakeValve::builder("BrakeValve_A", Some(CockpitSide::A))
+//!     .event_grab("BrakeValve_Grab")
+//!     .events("BrakeValve_Plus", "BrakeValve_Minus")
+//!     .snd_vent("Snd_BrakeValve_Vent", Some("Snd_BrakeValve_Vent_Vol"), 100.0)
+//!     .build();
+//!
+//! // In your main loop
+//! valve.tick(reservoir.pressure, brake_pipe.pressure);
+//! if valve.flow > 0.0 {
+//!     brake_pipe.feed(reservoir.take(valve.flow * delta()));
+//! } else {
+//!     brake_pipe.vent(-valve.flow * delta());
+//! }
+//! ```
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::{
+    api::{
+        animation::Animation,
+        general::{delta, mouse_move},
+        key_event::KeyEvent,
+        sound::Sound,
+    },
+    management::enums::brake_enums::BrakeValveSector,
+};
+
+/// Builder for creating a [`RotaryBrakeValve`] with customizable configuration.
+pub struct RotaryBrakeValveBuilder {
+    cab_side: Option<CockpitSide>,
+
+    angle: f32,
+    sectors: [f32; 4],
+
+    mouse_factor: f32,
+    key_speed: f32,
+
+    regulating_pressure: f32,
+    full_service_reduction: f32,
+
+    release_rate: f32,
+    apply_rate: f32,
+    emergency_rate: f32,
+    full_vent_flow: f32,
+
+    handle_anim: Animation,
+
+    key_grab: KeyEvent,
+    key_plus: KeyEvent,
+    key_minus: KeyEvent,
+
+    snd_vent: Sound,
+    snd_notch: Sound,
+}
+
+impl RotaryBrakeValveBuilder {
+    /// Sets the sector limits of the handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `lap` - Angle in degrees at which the lap sector begins
+    /// * `apply` - Angle in degrees at which the apply sector begins
+    /// * `emergency` - Angle in degrees at which the emergency sector begins
+    /// * `max` - Maximum angle of the handle in degrees
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn sectors(mut self, lap: f32, apply: f32, emergency: f32, max: f32) -> Self {
+        self.sectors = [lap, apply, emergency, max];
+        self
+    }
+
+    /// Sets the initial angle of the handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `angle` - Initial angle in degrees
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn init_angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// Sets the key event for grabbing the handle with the mouse.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the key event
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn event_grab(mut self, name: impl Into<String>) -> Self {
+        self.key_grab = KeyEvent::new(Some(&name.into()), self.cab_side);
+        self
+    }
+
+    /// Sets the key events turning the handle towards emergency and release.
+    ///
+    /// # Arguments
+    ///
+    /// * `plus` - Name of the key event turning towards emergency
+    /// * `minus` - Name of the key event turning towards release
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn events(mut self, plus: impl Into<String>, minus: impl Into<String>) -> Self {
+        self.key_plus = KeyEvent::new(Some(&plus.into()), self.cab_side);
+        self.key_minus = KeyEvent::new(Some(&minus.into()), self.cab_side);
+        self
+    }
+
+    /// Sets the mouse sensitivity and the turning speed with the keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `mouse_factor` - Degrees per mouse unit
+    /// * `key_speed` - Degrees per second while a key is pressed
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn handling(mut self, mouse_factor: f32, key_speed: f32) -> Self {
+        self.mouse_factor = mouse_factor;
+        self.key_speed = key_speed;
+        self
+    }
+
+    /// Sets the pressures of the brake pipe.
+    ///
+    /// # Arguments
+    ///
+    /// * `regulating_pressure` - Pressure in bar in the release and running position
+    /// * `full_service_reduction` - Pressure reduction in bar at the end of the apply sector
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn pressures(mut self, regulating_pressure: f32, full_service_reduction: f32) -> Self {
+        self.regulating_pressure = regulating_pressure;
+        self.full_service_reduction = full_service_reduction;
+        self
+    }
+
+    /// Sets the flow rates of the ports.
+    ///
+    /// The release and apply rates are given per bar of pressure difference.
+    ///
+    /// # Arguments
+    ///
+    /// * `release` - Filling rate in bar·litre/s per bar
+    /// * `apply` - Venting rate in bar·litre/s per bar
+    /// * `emergency` - Venting rate of the emergency port in bar·litre/s per bar
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn flow_rates(mut self, release: f32, apply: f32, emergency: f32) -> Self {
+        self.release_rate = release;
+        self.apply_rate = apply;
+        self.emergency_rate = emergency;
+        self
+    }
+
+    /// Sets the venting sound, its volume follows the venting flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sound
+    /// * `volume_name` - Optional name of the volume variable
+    /// * `full_vent_flow` - Venting flow in bar·litre/s at which the sound reaches full volume
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn snd_vent(
+        mut self,
+        name: impl Into<String>,
+        volume_name: Option<&str>,
+        full_vent_flow: f32,
+    ) -> Self {
+        self.snd_vent = Sound::new(Some(&name.into()), volume_name, None);
+        self.full_vent_flow = full_vent_flow;
+        self
+    }
+
+    /// Sets the sound played when the handle passes a sector limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sound
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn snd_notch(mut self, name: impl Into<String>) -> Self {
+        self.snd_notch = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Constructs the final [`RotaryBrakeValve`] instance.
+    pub fn build(self) -> RotaryBrakeValve {
+        let mut valve = RotaryBrakeValve {
+            sectors: self.sectors,
+            mouse_factor: self.mouse_factor,
+            key_speed: self.key_speed,
+            regulating_pressure: self.regulating_pressure,
+            full_service_reduction: self.full_service_reduction,
+            release_rate: self.release_rate,
+            apply_rate: self.apply_rate,
+            emergency_rate: self.emergency_rate,
+            full_vent_flow: self.full_vent_flow,
+            handle_anim: self.handle_anim,
+            key_grab: self.key_grab,
+            key_plus: self.key_plus,
+            key_minus: self.key_minus,
+            snd_vent: self.snd_vent,
+            snd_notch: self.snd_notch,
+            angle: self.angle.clamp(0.0, self.sectors[3]),
+            sector: BrakeValveSector::Release,
+            flow: 0.0,
+        };
+        valve.sector = valve.sector_of(valve.angle);
+        valve.handle_anim.set(valve.angle);
+        valve
+    }
+}
+
+/// Driver's brake valve with continuous handle angle.
+pub struct RotaryBrakeValve {
+    sectors: [f32; 4],
+
+    mouse_factor: f32,
+    key_speed: f32,
+
+    regulating_pressure: f32,
+    full_service_reduction: f32,
+
+    release_rate: f32,
+    apply_rate: f32,
+    emergency_rate: f32,
+    full_vent_flow: f32,
+
+    handle_anim: Animation,
+
+    key_grab: KeyEvent,
+    key_plus: KeyEvent,
+    key_minus: KeyEvent,
+
+    snd_vent: Sound,
+    snd_notch: Sound,
+
+    /// Angle of the handle in degrees
+    pub angle: f32,
+    /// Sector of the current angle
+    pub sector: BrakeValveSector,
+    /// Air flow in bar·litre/s, positive into the brake pipe, negative venting the brake pipe
+    pub flow: f32,
+}
+
+impl RotaryBrakeValve {
+    /// Creates a new brake valve builder.
+    ///
+    /// Default sectors: release 0°–20°, lap 20°–40°, apply 40°–100°,
+    /// emergency 100°–120°.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Name of the handle animation, driven with the angle in degrees
+    /// * `cab_side` - Optional cab side for key event handling
+    ///
+    /// # Returns
+    ///
+    /// A [`RotaryBrakeValveBuilder`] for configuring the brake valve.
+    pub fn builder(
+        animation_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> RotaryBrakeValveBuilder {
+        RotaryBrakeValveBuilder {
+            cab_side,
+            angle: 0.0,
+            sectors: [20.0, 40.0, 100.0, 120.0],
+            mouse_factor: 1.0,
+            key_speed: 60.0,
+            regulating_pressure: 5.0,
+            full_service_reduction: 1.5,
+            release_rate: 40.0,
+            apply_rate: 15.0,
+            emergency_rate: 80.0,
+            full_vent_flow: 100.0,
+            handle_anim: Animation::new(Some(&animation_name.into())),
+            key_grab: KeyEvent::new(None, None),
+            key_plus: KeyEvent::new(None, None),
+            key_minus: KeyEvent::new(None, None),
+            snd_vent: Sound::new_simple(None),
+            snd_notch: Sound::new_simple(None),
+        }
+    }
+
+    /// Returns the sector of an angle.
+    ///
+    /// # Arguments
+    ///
+    /// * `angle` - Angle of the handle in degrees
+    ///
+    /// # Returns
+    ///
+    /// The [`BrakeValveSector`] the angle lies in.
+    #[must_use]
+    pub fn sector_of(&self, angle: f32) -> BrakeValveSector {
+        let [lap, apply, emergency, _] = self.sectors;
+
+        if angle < lap {
+            BrakeValveSector::Release
+        } else if angle < apply {
+            BrakeValveSector::Lap
+        } else if angle < emergency {
+            BrakeValveSector::Apply
+        } else {
+            BrakeValveSector::Emergency
+        }
+    }
+
+    /// Updates the handle and computes the air flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `main_pressure` - Pressure of the main reservoir in bar
+    /// * `pipe_pressure` - Pressure of the brake pipe in bar
+    pub fn tick(&mut self, main_pressure: f32, pipe_pressure: f32) {
+        let [_, apply, emergency, max] = self.sectors;
+
+        // Griff bewegen
+        let mut angle = self.angle;
+        if self.key_grab.is_pressed() {
+            angle += mouse_move().x * self.mouse_factor;
+        }
+        if self.key_plus.is_pressed() {
+            angle += self.key_speed * delta();
+        }
+        if self.key_minus.is_pressed() {
+            angle -= self.key_speed * delta();
+        }
+        angle = angle.clamp(0.0, max);
+
+        if angle != self.angle {
+            self.angle = angle;
+            self.handle_anim.set(angle);
+        }
+
+        let sector = self.sector_of(angle);
+        if sector != self.sector {
+            self.sector = sector;
+            self.snd_notch.start();
+        }
+
+        // Luftstrom der Anschlüsse
+        self.flow = match self.sector {
+            BrakeValveSector::Release => {
+                let target = self.regulating_pressure.min(main_pressure);
+                self.release_rate * (target - pipe_pressure).max(0.0)
+            }
+            BrakeValveSector::Lap => 0.0,
+            BrakeValveSector::Apply => {
+                let frac = (angle - apply) / (emergency - apply).max(0.01);
+                let target = self.regulating_pressure - self.full_service_reduction * frac;
+                -self.apply_rate * (pipe_pressure - target).max(0.0)
+            }
+            BrakeValveSector::Emergency => -self.emergency_rate * pipe_pressure.max(0.0),
+        };
+
+        let vent = (-self.flow).max(0.0);
+        if vent > 0.0 {
+            self.snd_vent
+                .update_volume((vent / self.full_vent_flow.max(0.01)).min(1.0));
+            self.snd_vent.start();
+        } else {
+            self.snd_vent.stop();
+        }
+    }
+}
@@ -7,6 +7,7 @@
 //!
 //! - **Slider**: A draggable slider with customizable bounds, physics simulation, and path following
 //! - **Rollo**: A roll-up component that can be pulled and reset, useful for curtains, blinds, or similar UI elements
+//! - **SunBlind**: A spring loaded blind with several latched positions and slow retraction
 //! - Physics simulation with force, friction, and bouncing
 //! - Mouse and keyboard interaction
 //! - Animation and sound integration
@@ -42,9 +43,14 @@
 use std::rc::Rc;
 
 use lotus_extra::{math::PiecewiseLinearFunction, vehicle::CockpitSide};
-use lotus_script::{math::Vec2, time::delta};
+use lotus_script::math::Vec2;
 
-use crate::api::{animation::Animation, general::mouse_move, key_event::KeyEvent, sound::Sound};
+use crate::api::{
+    animation::Animation,
+    general::{delta, mouse_move},
+    key_event::KeyEvent,
+    sound::Sound,
+};
 
 /// Builder for creating a [`Slider`] component with customizable properties.
 ///
@@ -819,3 +825,271 @@ impl Rollo {
             .set(self.key_reset.is_pressed() as u8 as f32);
     }
 }
+
+//======================================================================
+// SunBlind
+//======================================================================
+
+/// Builder for creating a [`SunBlind`] with customizable properties.
+///
+/// # Example
+///
+/// ```rust
+/// let sun_blind = SunBlind::builder("SunBlind_A", "SunBlind_Pull", Some(CockpitSide::A))
+///     .detents(vec![0.3, 0.6, 1.0])
+///     .snd_latch("Snd_SunBlind_Latch")
+///     .snd_spring("Snd_SunBlind_Spring")
+///     .build();
+/// ```
+pub struct SunBlindBuilder {
+    cab_side: Option<CockpitSide>,
+
+    pos: f32,
+    detents: Vec<f32>,
+    latch_range: f32,
+    retract_speed: f32,
+
+    mouse_factor: f32,
+
+    key_draw: KeyEvent,
+    key_release: KeyEvent,
+
+    anim: Animation,
+
+    snd_latch: Sound,
+    snd_spring: Sound,
+}
+
+impl SunBlindBuilder {
+    /// Sets the latched detent positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `detents` - Positions between 0.0 (rolled up) and 1.0 (pulled down)
+    pub fn detents(mut self, detents: Vec<f32>) -> Self {
+        self.detents = detents.into_iter().map(|d| d.clamp(0.0, 1.0)).collect();
+        self.detents.sort_by(f32::total_cmp);
+        self
+    }
+
+    /// Sets the distance below a detent within which the released blind latches.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - Capture range (default: 0.05)
+    pub fn latch_range(mut self, range: f32) -> Self {
+        self.latch_range = range;
+        self
+    }
+
+    /// Sets the speed of the spring retraction.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Retraction speed in positions per second (default: 0.5)
+    pub fn retract_speed(mut self, speed: f32) -> Self {
+        self.retract_speed = speed;
+        self
+    }
+
+    /// Sets the mouse sensitivity factor.
+    ///
+    /// # Arguments
+    ///
+    /// * `mouse_factor` - Sensitivity multiplier
+    pub fn mouse_factor(mut self, mouse_factor: f32) -> Self {
+        self.mouse_factor = mouse_factor;
+        self
+    }
+
+    /// Sets the key event releasing the latch, the blind retracts completely.
+    ///
+    /// # Arguments
+    ///
+    /// * `event_name` - Name of the key event
+    pub fn event_release(mut self, event_name: impl Into<String>) -> Self {
+        self.key_release = KeyEvent::new(Some(&event_name.into()), self.cab_side);
+        self
+    }
+
+    /// Sets the sound effect for latching into a detent.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sound effect
+    pub fn snd_latch(mut self, name: impl Into<String>) -> Self {
+        self.snd_latch = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Sets the sound effect played while the spring retracts the blind.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the sound effect
+    pub fn snd_spring(mut self, name: impl Into<String>) -> Self {
+        self.snd_spring = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    /// Builds the final [`SunBlind`] instance with the configured properties.
+    pub fn build(self) -> SunBlind {
+        SunBlind {
+            pos: self.pos,
+            detents: self.detents,
+            latch_range: self.latch_range,
+            retract_speed: self.retract_speed,
+            mouse_factor: self.mouse_factor,
+            key_draw: self.key_draw,
+            key_release: self.key_release,
+            anim: self.anim,
+            snd_latch: self.snd_latch,
+            snd_spring: self.snd_spring,
+            retract_target: None,
+        }
+    }
+}
+
+/// A spring loaded sun blind with several latched positions.
+///
+/// The blind is pulled down with the mouse. When released it latches in the
+/// detent just above, otherwise the spring slowly retracts it to the next
+/// detent or completely.
+///
+/// # Example
+///
+/// ```rust
+/// let (mut sun_blind_a, mut sun_blind_b) =
+///     SunBlind::for_both_cab_sides("SunBlind", "SunBlind_Pull", |b| b.detents(vec![0.5, 1.0]));
+///
+/// // In your update loop:
+/// sun_blind_a.tick();
+/// sun_blind_b.tick();
+/// ```
+pub struct SunBlind {
+    /// Current position from 0.0 (rolled up) to 1.0 (pulled down)
+    pub pos: f32,
+
+    detents: Vec<f32>,
+    latch_range: f32,
+    retract_speed: f32,
+
+    mouse_factor: f32,
+
+    key_draw: KeyEvent,
+    key_release: KeyEvent,
+
+    anim: Animation,
+
+    snd_latch: Sound,
+    snd_spring: Sound,
+
+    retract_target: Option<f32>,
+}
+
+impl SunBlind {
+    /// Creates a new [`SunBlindBuilder`].
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Name of the blind animation
+    /// * `event_name` - Name of the key event for pulling the blind
+    /// * `cab_side` - Optional cabinet side specification
+    pub fn builder(
+        animation_name: impl Into<String>,
+        event_name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> SunBlindBuilder {
+        SunBlindBuilder {
+            cab_side,
+            pos: 0.0,
+            detents: Vec::new(),
+            latch_range: 0.05,
+            retract_speed: 0.5,
+            mouse_factor: 1.0,
+            key_draw: KeyEvent::new(Some(&event_name.into()), cab_side),
+            key_release: KeyEvent::new(None, None),
+            anim: Animation::new(Some(&animation_name.into())),
+            snd_latch: Sound::new_simple(None),
+            snd_spring: Sound::new_simple(None),
+        }
+    }
+
+    /// Creates the sun blinds of both cabs with the same configuration.
+    ///
+    /// The animations are named `{animation_prefix}_A` and `{animation_prefix}_B`,
+    /// the key event is the same for both cab sides.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_prefix` - Prefix of the blind animations
+    /// * `event_name` - Name of the key event for pulling the blind
+    /// * `configure` - Configuration applied to both builders
+    ///
+    /// # Returns
+    ///
+    /// The sun blinds of cab A and cab B.
+    pub fn for_both_cab_sides(
+        animation_prefix: &str,
+        event_name: &str,
+        configure: impl Fn(SunBlindBuilder) -> SunBlindBuilder,
+    ) -> (SunBlind, SunBlind) {
+        (
+            configure(Self::builder(
+                format!("{animation_prefix}_A"),
+                event_name,
+                Some(CockpitSide::A),
+            ))
+            .build(),
+            configure(Self::builder(
+                format!("{animation_prefix}_B"),
+                event_name,
+                Some(CockpitSide::B),
+            ))
+            .build(),
+        )
+    }
+
+    /// Updates the sun blind for one frame.
+    pub fn tick(&mut self) {
+        if self.key_release.is_just_pressed() {
+            self.retract_target = Some(0.0);
+        }
+
+        if self.key_draw.is_pressed() {
+            self.retract_target = None;
+            let hand_delta = mouse_move().y * self.mouse_factor;
+            self.pos = (self.pos + hand_delta).clamp(0.0, 1.0);
+        } else if self.key_draw.is_just_released() {
+            // Rastet in die nächste Raste darüber ein oder rollt bis dorthin zurück
+            let pos = self.pos;
+            let target = self
+                .detents
+                .iter()
+                .copied()
+                .rfind(|d| *d <= pos + 0.0001)
+                .unwrap_or(0.0);
+
+            if pos - target < self.latch_range && target > 0.0 {
+                self.pos = target;
+                self.snd_latch.start();
+            } else {
+                self.retract_target = Some(target);
+            }
+        }
+
+        if let Some(target) = self.retract_target {
+            self.pos = (self.pos - self.retract_speed * delta()).max(target);
+            if self.pos <= target {
+                self.retract_target = None;
+                if target > 0.0 {
+                    self.snd_latch.start();
+                }
+            }
+        }
+
+        self.snd_spring.start_stop(self.retract_target.is_some());
+
+        self.anim.set(self.pos);
+    }
+}
@@ -0,0 +1,235 @@
+//! Split-flap display ("Fallblattanzeige").
+//!
+//! The [`SplitFlapDisplay`] consists of one flap module per character. Each
+//! module has a drum of flaps with the characters of the character set and
+//! can only turn forward: to show a new character it flips through all flaps
+//! in between. The modules start one after another with a short delay, which
+//! gives the typical cascading rattle. Every flip can jam with a small
+//! probability; a jammed module stops until the jams are cleared.
+//!
+//! The animation of each module receives the flap index, the fractional part
+//! being the falling flap.
+//!
+//! # Example
+//!
+//! ```rust
+//! let mut display = SplitFlapDisplay::builder()
+//!     .module("Fallblatt_0")
+//!     .module("Fallblatt_1")
+//!     .module("Fallblatt_2")
+//!     .module("Fallblatt_3")
+//!     .flip_rate(12.0)
+//!     .cascade_delay(0.05)
+//!     .snd_flip("Snd_Fallblatt")
+//!     .jam_probability(0.0005)
+//!     .build();
+//!
+//! display.set_text("ZOO");
+//!
+//! // In your game loop
+//! display.tick(voltage > 0.5);
+//! ```
+
+use crate::api::{animation::Animation, general::delta, random::gen_f32, sound::Sound};
+
+/// Default character set, the first flap is blank
+const DEFAULT_CHARSET: &str = " ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÜ0123456789.-/";
+
+#[derive(Debug)]
+struct FlapModule {
+    anim: Animation,
+    pos: f32,
+    target: usize,
+    delay: f32,
+    jammed: bool,
+}
+
+impl FlapModule {
+    fn index(&self, flaps: usize) -> usize {
+        self.pos as usize % flaps
+    }
+}
+
+/// Builder for creating a [`SplitFlapDisplay`].
+pub struct SplitFlapDisplayBuilder {
+    modules: Vec<FlapModule>,
+    charset: Vec<char>,
+    flip_rate: f32,
+    cascade_delay: f32,
+    jam_probability: f32,
+    snd_flip: Sound,
+}
+
+impl SplitFlapDisplayBuilder {
+    /// Adds a character module, from left to right.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation_name` - Animation of the flap drum, receives the flap index
+    pub fn module(mut self, animation_name: impl Into<String>) -> Self {
+        self.modules.push(FlapModule {
+            anim: Animation::new(Some(&animation_name.into())),
+            pos: 0.0,
+            target: 0,
+            delay: 0.0,
+            jammed: false,
+        });
+        self
+    }
+
+    /// Characters of the flaps in drum order, the first one is used for
+    /// characters not in the set.
+    pub fn charset(mut self, charset: &str) -> Self {
+        self.charset = charset.chars().collect();
+        if self.charset.is_empty() {
+            self.charset.push(' ');
+        }
+        self
+    }
+
+    /// Flaps per second of a running module.
+    pub fn flip_rate(mut self, flip_rate: f32) -> Self {
+        self.flip_rate = flip_rate;
+        self
+    }
+
+    /// Delay in seconds between the start of neighbouring modules.
+    pub fn cascade_delay(mut self, cascade_delay: f32) -> Self {
+        self.cascade_delay = cascade_delay;
+        self
+    }
+
+    /// Probability from 0.0 to 1.0 that a flip jams the module.
+    pub fn jam_probability(mut self, probability: f32) -> Self {
+        self.jam_probability = probability;
+        self
+    }
+
+    /// Sound of a single falling flap, restarted with every flip.
+    pub fn snd_flip(mut self, name: impl Into<String>) -> Self {
+        self.snd_flip = Sound::new_simple(Some(&name.into()));
+        self
+    }
+
+    pub fn build(self) -> SplitFlapDisplay {
+        let mut display = SplitFlapDisplay {
+            modules: self.modules,
+            charset: self.charset,
+            flip_rate: self.flip_rate,
+            cascade_delay: self.cascade_delay,
+            jam_probability: self.jam_probability,
+            snd_flip: self.snd_flip,
+        };
+        display.update_anims();
+        display
+    }
+}
+
+/// Split-flap display with one flap drum per character.
+#[derive(Debug)]
+pub struct SplitFlapDisplay {
+    modules: Vec<FlapModule>,
+    charset: Vec<char>,
+    flip_rate: f32,
+    cascade_delay: f32,
+    jam_probability: f32,
+    snd_flip: Sound,
+}
+
+impl SplitFlapDisplay {
+    pub fn builder() -> SplitFlapDisplayBuilder {
+        SplitFlapDisplayBuilder {
+            modules: Vec::new(),
+            charset: DEFAULT_CHARSET.chars().collect(),
+            flip_rate: 10.0,
+            cascade_delay: 0.04,
+            jam_probability: 0.0,
+            snd_flip: Sound::new_simple(None),
+        }
+    }
+
+    /// Sets the text to spell, it is cut or padded with blanks to the number
+    /// of modules. Lower case letters are shown in upper case.
+    pub fn set_text(&mut self, text: &str) {
+        let mut chars = text.chars().flat_map(char::to_uppercase);
+
+        for (idx, module) in self.modules.iter_mut().enumerate() {
+            let c = chars.next().unwrap_or(' ');
+            let target = self.charset.iter().position(|&x| x == c).unwrap_or(0);
+            if target != module.target {
+                module.target = target;
+                module.delay = idx as f32 * self.cascade_delay;
+            }
+        }
+    }
+
+    /// Text currently shown by the flaps.
+    pub fn text(&self) -> String {
+        self.modules
+            .iter()
+            .map(|module| self.charset[module.index(self.charset.len())])
+            .collect()
+    }
+
+    /// Whether all modules show their target character.
+    pub fn settled(&self) -> bool {
+        let flaps = self.charset.len();
+        self.modules
+            .iter()
+            .all(|module| module.index(flaps) == module.target && module.pos.fract() == 0.0)
+    }
+
+    /// Whether any module is jammed.
+    pub fn jammed(&self) -> bool {
+        self.modules.iter().any(|module| module.jammed)
+    }
+
+    /// Clears all jams, e.g. after a workshop visit.
+    pub fn clear_jams(&mut self) {
+        for module in &mut self.modules {
+            module.jammed = false;
+        }
+    }
+
+    /// Turns the running modules.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the display is supplied
+    pub fn tick(&mut self, power: bool) {
+        let flaps = self.charset.len();
+
+        for module in &mut self.modules {
+            let done = module.index(flaps) == module.target && module.pos.fract() == 0.0;
+            if !power || module.jammed || done {
+                continue;
+            }
+
+            if module.delay > 0.0 {
+                module.delay -= delta();
+                continue;
+            }
+
+            let pos_last = module.pos;
+            module.pos += self.flip_rate * delta();
+
+            // Jedes Blatt, das fällt, klackert und kann hängen bleiben
+            if module.pos.floor() > pos_last.floor() {
+                module.pos = module.pos.floor() % flaps as f32;
+                self.snd_flip.start();
+
+                if gen_f32(0.0..1.0) < self.jam_probability {
+                    module.jammed = true;
+                }
+            }
+        }
+
+        self.update_anims();
+    }
+
+    fn update_anims(&mut self) {
+        for module in &mut self.modules {
+            module.anim.set(module.pos);
+        }
+    }
+}
@@ -67,8 +67,15 @@
 use std::collections::HashMap;
 
 use lotus_extra::vehicle::CockpitSide;
+use lotus_script::math::Vec2;
+use serde::Deserialize;
 
-use crate::api::{animation::Animation, key_event::KeyEvent, sound::Sound};
+use crate::api::{
+    animation::Animation,
+    general::{delta, mouse_move},
+    key_event::KeyEvent,
+    sound::{PoolOrder, Sound, SoundPool},
+};
 
 //=================================================================
 // Switch
@@ -91,9 +98,12 @@ pub struct SwitchBuilder {
 
     btn_anim: Animation,
 
-    snd_toggle: Sound,
-    snd_plus: Sound,
-    snd_minus: Sound,
+    repeat: Option<(f32, f32)>,
+    long_press: Option<(f32, bool)>,
+
+    snd_toggle: SoundPool,
+    snd_plus: SoundPool,
+    snd_minus: SoundPool,
 }
 
 impl SwitchBuilder {
@@ -157,13 +167,59 @@ impl SwitchBuilder {
         self
     }
 
+    /// Enables auto repeat of the toggle event while the key is held, e.g.
+    /// for a test key flashing a lamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - Time in seconds the key has to be held before repeating
+    /// * `rate` - Toggles per second while repeating
+    pub fn auto_repeat(mut self, delay: f32, rate: f32) -> Self {
+        self.repeat = Some((delay, rate));
+        self
+    }
+
+    /// Adds a long press function to the toggle event.
+    ///
+    /// If the toggle key is held for `hold_time`, the switch is set to
+    /// `target`, a shorter press toggles the switch on release. Replaces the
+    /// auto repeat of the toggle event.
+    ///
+    /// # Arguments
+    ///
+    /// * `hold_time` - Time in seconds the key has to be held
+    /// * `target` - State of the switch after holding the key
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let switch = Switch::builder("anim", None)
+    ///     .event_toggle("LIGHT")
+    ///     .long_press(1.5, false) // Holding switches off
+    ///     .build();
+    /// ```
+    pub fn long_press(mut self, hold_time: f32, target: bool) -> Self {
+        self.long_press = Some((hold_time, target));
+        self
+    }
+
     /// Sets the sound to play when toggling the switch.
     ///
     /// # Arguments
     ///
     /// * `name` - The sound resource name
     pub fn snd_toggle(mut self, name: impl Into<String>) -> Self {
-        self.snd_toggle = Sound::new_simple(Some(&name.into()));
+        self.snd_toggle = SoundPool::new_simple(&[&name.into()], PoolOrder::Random);
+        self
+    }
+
+    /// Sets alternative samples of which one is played when toggling the switch.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - The alternative samples
+    pub fn snd_toggle_pool(mut self, pool: SoundPool) -> Self {
+        self.snd_toggle = pool;
         self
     }
 
@@ -173,7 +229,17 @@ impl SwitchBuilder {
     ///
     /// * `name` - The sound resource name
     pub fn snd_plus(mut self, name: impl Into<String>) -> Self {
-        self.snd_plus = Sound::new_simple(Some(&name.into()));
+        self.snd_plus = SoundPool::new_simple(&[&name.into()], PoolOrder::Random);
+        self
+    }
+
+    /// Sets alternative samples of which one is played when turning the switch on.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - The alternative samples
+    pub fn snd_plus_pool(mut self, pool: SoundPool) -> Self {
+        self.snd_plus = pool;
         self
     }
 
@@ -183,7 +249,17 @@ impl SwitchBuilder {
     ///
     /// * `name` - The sound resource name
     pub fn snd_minus(mut self, name: impl Into<String>) -> Self {
-        self.snd_minus = Sound::new_simple(Some(&name.into()));
+        self.snd_minus = SoundPool::new_simple(&[&name.into()], PoolOrder::Random);
+        self
+    }
+
+    /// Sets alternative samples of which one is played when turning the switch off.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - The alternative samples
+    pub fn snd_minus_pool(mut self, pool: SoundPool) -> Self {
+        self.snd_minus = pool;
         self
     }
 
@@ -202,6 +278,9 @@ impl SwitchBuilder {
             key_plus: self.key_plus,
             key_minus: self.key_minus,
             btn_anim: self.btn_anim,
+            repeat: self.repeat,
+            long_press: self.long_press,
+            hold_timer: None,
             snd_toggle: self.snd_toggle,
             snd_plus: self.snd_plus,
             snd_minus: self.snd_minus,
@@ -244,9 +323,13 @@ pub struct Switch {
 
     btn_anim: Animation,
 
-    snd_toggle: Sound,
-    snd_plus: Sound,
-    snd_minus: Sound,
+    repeat: Option<(f32, f32)>,
+    long_press: Option<(f32, bool)>,
+    hold_timer: Option<f32>,
+
+    snd_toggle: SoundPool,
+    snd_plus: SoundPool,
+    snd_minus: SoundPool,
 }
 
 impl Switch {
@@ -282,9 +365,11 @@ impl Switch {
             key_plus: KeyEvent::new(None, None),
             key_minus: KeyEvent::new(None, None),
             btn_anim: Animation::new(Some(&animation_name.into())),
-            snd_toggle: Sound::new_simple(None),
-            snd_plus: Sound::new_simple(None),
-            snd_minus: Sound::new_simple(None),
+            repeat: None,
+            long_press: None,
+            snd_toggle: SoundPool::default(),
+            snd_plus: SoundPool::default(),
+            snd_minus: SoundPool::default(),
         }
     }
 
@@ -309,7 +394,8 @@ impl Switch {
     ///
     /// # Behavior
     ///
-    /// - Toggle key: Switches between on/off states
+    /// - Toggle key: Switches between on/off states, with auto repeat or
+    ///   long press function if configured
     /// - Plus key: Turns the switch on (if currently off)
     /// - Minus key: Turns the switch off (if currently on)
     ///
@@ -317,17 +403,7 @@ impl Switch {
     pub fn tick(&mut self) {
         self.value_last = self.value;
 
-        if self.key_toggle.is_just_pressed() {
-            self.pos = 1.0 - self.pos;
-            self.value = self.pos > 0.5;
-            if self.value {
-                self.snd_plus.start();
-            } else {
-                self.snd_minus.start();
-            }
-            self.snd_toggle.start();
-            self.btn_anim.set(self.pos);
-        }
+        self.toggle_tick();
 
         if self.key_plus.is_just_pressed() && !self.value {
             self.pos = 1.0;
@@ -346,6 +422,56 @@ impl Switch {
         }
     }
 
+    /// Handles the toggle key including auto repeat and long press.
+    fn toggle_tick(&mut self) {
+        // Nur einmal abfragen, die Flanken werden über den Haltetimer erkannt
+        let pressed = self.key_toggle.is_pressed();
+
+        match (pressed, self.hold_timer) {
+            (true, None) => {
+                self.hold_timer = Some(0.0);
+                // Mit Langdruckfunktion erst beim Loslassen umschalten
+                if self.long_press.is_none() {
+                    self.set(!self.value);
+                }
+            }
+            (true, Some(last)) => {
+                let now = last + delta();
+                self.hold_timer = Some(now);
+
+                match self.long_press {
+                    Some((hold_time, target)) => {
+                        if last < hold_time && now >= hold_time {
+                            self.set(target);
+                        }
+                    }
+                    None => {
+                        for _ in 0..(self.repeats(now) - self.repeats(last)) {
+                            self.set(!self.value);
+                        }
+                    }
+                }
+            }
+            (false, Some(held)) => {
+                self.hold_timer = None;
+                if let Some((hold_time, _)) = self.long_press {
+                    if held < hold_time {
+                        self.set(!self.value);
+                    }
+                }
+            }
+            (false, None) => {}
+        }
+    }
+
+    /// Number of auto repeats after holding the toggle key for the given time.
+    fn repeats(&self, held: f32) -> u32 {
+        match self.repeat {
+            Some((delay, rate)) if held >= delay => ((held - delay) * rate) as u32 + 1,
+            _ => 0,
+        }
+    }
+
     /// Returns the current switch value, respecting the allowed state.
     ///
     /// # Arguments
@@ -392,14 +518,14 @@ impl Switch {
 // StepSwitch
 //=================================================================
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
 pub enum SwitchSoundDirection {
     Plus,
     Minus,
 }
 
 /// Defines the action to perform when a switch event is triggered.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
 pub enum SwitchEventAction {
     /// Increment the switch position by one step
     Plus,
@@ -407,6 +533,8 @@ pub enum SwitchEventAction {
     Minus,
     /// Set the switch to a specific position
     Set(i32),
+    /// No action, e.g. for keys which only have a long press function
+    None,
 }
 
 /// Builder for creating a [`StepSwitch`] with customizable configuration.
@@ -434,6 +562,13 @@ pub struct StepSwitchBuilder {
 
     events: HashMap<String, SwitchEventAction>,
 
+    key_drag: KeyEvent,
+    drag_axis: Vec2,
+    drag_factor: f32,
+
+    repeat: Option<(f32, f32)>,
+    long_press: HashMap<String, (f32, SwitchEventAction)>,
+
     just_changed: Option<i32>,
 
     snd_default_plus: Sound,
@@ -531,6 +666,82 @@ impl StepSwitchBuilder {
         self
     }
 
+    /// Enables auto repeat of the plus and minus events while the key is held.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - Time in seconds the key has to be held before repeating
+    /// * `rate` - Steps per second while repeating
+    pub fn auto_repeat(mut self, delay: f32, rate: f32) -> Self {
+        self.repeat = Some((delay, rate));
+        self
+    }
+
+    /// Adds a long press action to a key event.
+    ///
+    /// If the key is held for `hold_time`, the long press action is performed
+    /// instead of the regular one, which is then performed on release.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The key event name
+    /// * `hold_time` - Time in seconds the key has to be held
+    /// * `action` - The action to perform after holding the key
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let switch = StepSwitch::builder("anim", None)
+    ///     .event("SET", SwitchEventAction::Set(0))
+    ///     .long_press("SET", 2.0, SwitchEventAction::Set(5))
+    ///     .build();
+    /// ```
+    pub fn long_press(
+        mut self,
+        name: impl Into<String>,
+        hold_time: f32,
+        action: SwitchEventAction,
+    ) -> Self {
+        let name = name.into();
+        // Ohne reguläre Aktion bewirkt kurzes Drücken nichts
+        self.events
+            .entry(name.clone())
+            .or_insert(SwitchEventAction::None);
+        self.long_press.insert(name, (hold_time, action));
+        self
+    }
+
+    /// Enables turning the switch by grabbing and dragging it with the mouse.
+    ///
+    /// While the key event is held, the mouse movement is accumulated; each
+    /// time it reaches half a step, the switch snaps into the next detent.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The key event grabbing the switch
+    /// * `drag_factor` - Steps per unit of mouse movement
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let switch = StepSwitch::builder("anim", None)
+    ///     .min(0)
+    ///     .max(4)
+    ///     .drag("GRAB_SWITCH", 0.01)
+    ///     .build();
+    /// ```
+    pub fn drag(mut self, name: impl Into<String>, drag_factor: f32) -> Self {
+        self.key_drag = KeyEvent::new(Some(&name.into()), self.cab_side);
+        self.drag_factor = drag_factor;
+        self
+    }
+
+    /// Turns the switch with vertical instead of horizontal mouse movement.
+    pub fn drag_axis_y(mut self) -> Self {
+        self.drag_axis = Vec2 { x: 0.0, y: 1.0 };
+        self
+    }
+
     /// Sets a custom mapping between switch positions and animation values.
     ///
     /// # Arguments
@@ -608,6 +819,13 @@ impl StepSwitchBuilder {
             key_plus: self.key_plus,
             key_minus: self.key_minus,
             events: self.events,
+            key_drag: self.key_drag,
+            drag_axis: self.drag_axis,
+            drag_factor: self.drag_factor,
+            drag_accu: 0.0,
+            repeat: self.repeat,
+            long_press: self.long_press,
+            hold_timers: HashMap::new(),
             just_changed: self.just_changed,
             snd_default_plus: self.snd_default_plus,
             snd_default_minus: self.snd_default_minus,
@@ -629,6 +847,7 @@ impl StepSwitchBuilder {
 /// - Wrap-around (inverse turn) functionality
 /// - Custom animation position mapping
 /// - Multiple key event bindings
+/// - Turning by mouse drag with detent snapping
 /// - State change detection
 ///
 /// # Common Use Cases
@@ -663,6 +882,15 @@ pub struct StepSwitch {
 
     events: HashMap<String, SwitchEventAction>,
 
+    key_drag: KeyEvent,
+    drag_axis: Vec2,
+    drag_factor: f32,
+    drag_accu: f32,
+
+    repeat: Option<(f32, f32)>,
+    long_press: HashMap<String, (f32, SwitchEventAction)>,
+    hold_timers: HashMap<String, f32>,
+
     just_changed: Option<i32>,
 
     snd_default_plus: Sound,
@@ -711,6 +939,11 @@ impl StepSwitch {
             key_plus: KeyEvent::new(None, None),
             key_minus: KeyEvent::new(None, None),
             events: HashMap::new(),
+            key_drag: KeyEvent::new(None, None),
+            drag_axis: Vec2 { x: 1.0, y: 0.0 },
+            drag_factor: 0.0,
+            repeat: None,
+            long_press: HashMap::new(),
             just_changed: None,
             snd_default_plus: Sound::new_simple(None),
             snd_default_minus: Sound::new_simple(None),
@@ -822,11 +1055,110 @@ impl StepSwitch {
                 self.value = self.max - 1;
             }
         }
-        self.pos = match self.anim_mapping.get(&self.value) {
-            Some(s) => *s,
-            None => self.value as f32,
+        self.pos = self.anim_pos(self.value);
+
+        // Zwischenstellung beim Ziehen mit der Maus
+        let anim_pos = if self.drag_accu > 0.0 {
+            self.pos + (self.anim_pos(self.value + 1) - self.pos) * self.drag_accu
+        } else if self.drag_accu < 0.0 {
+            self.pos + (self.pos - self.anim_pos(self.value - 1)) * self.drag_accu
+        } else {
+            self.pos
         };
-        self.key_anim.set(self.pos);
+        self.key_anim.set(anim_pos);
+    }
+
+    fn anim_pos(&self, value: i32) -> f32 {
+        match self.anim_mapping.get(&value) {
+            Some(s) => *s,
+            None => value as f32,
+        }
+    }
+
+    /// Applies an action, returns whether the switch was turned up or `None`
+    /// if the position did not change.
+    fn step(&mut self, action: SwitchEventAction) -> Option<bool> {
+        match action {
+            SwitchEventAction::Plus if self.value < self.max => {
+                self.value += 1;
+                Some(true)
+            }
+            SwitchEventAction::Minus if self.value > self.min => {
+                self.value -= 1;
+                Some(false)
+            }
+            SwitchEventAction::Set(new_value)
+                if (self.min..=self.max).contains(&new_value) && self.value != new_value =>
+            {
+                self.value = new_value;
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    /// Number of auto repeats after holding a key for the given time.
+    fn repeats(&self, held: f32) -> u32 {
+        match self.repeat {
+            Some((delay, rate)) if held >= delay => ((held - delay) * rate) as u32 + 1,
+            _ => 0,
+        }
+    }
+
+    /// Turns the switch with the mouse while it is grabbed.
+    ///
+    /// Returns whether the position changed and whether it was turned up.
+    fn drag_tick(&mut self) -> (bool, bool) {
+        let mut has_update = false;
+        let mut plus_minus = false;
+
+        if self.key_drag.is_pressed() {
+            let vec_mouse = mouse_move() * self.drag_axis;
+            self.drag_accu += (vec_mouse.x + vec_mouse.y) * self.drag_factor;
+
+            // Einrasten in die nächste Raststellung
+            while self.drag_accu >= 0.5 && (self.value < self.max || self.inv_turn) {
+                self.value += 1;
+                self.drag_accu -= 1.0;
+                plus_minus = true;
+                has_update = true;
+            }
+            while self.drag_accu <= -0.5 && (self.value > self.min || self.inv_turn) {
+                self.value -= 1;
+                self.drag_accu += 1.0;
+                has_update = true;
+            }
+
+            // Am Anschlag nicht weiter drehen
+            let upper = if self.value < self.max || self.inv_turn {
+                0.5
+            } else {
+                0.0
+            };
+            let lower = if self.value > self.min || self.inv_turn {
+                -0.5
+            } else {
+                0.0
+            };
+            self.drag_accu = self.drag_accu.clamp(lower, upper);
+
+            self.update();
+        } else if self.key_drag.is_just_released() {
+            self.drag_accu = 0.0;
+
+            if self.max_spring && self.value == self.max {
+                self.value -= 1;
+                has_update = true;
+            } else if self.min_spring && self.value == self.min {
+                self.value += 1;
+                plus_minus = true;
+                has_update = true;
+            }
+
+            self.update();
+        }
+
+        (has_update, plus_minus)
     }
 
     /// Updates the switch state based on key events.
@@ -844,33 +1176,61 @@ impl StepSwitch {
 
         let mut has_update = false;
 
-        for (key, value) in &self.events {
+        let events = std::mem::take(&mut self.events);
+        for (key, value) in &events {
             let mut ev = KeyEvent::new(Some(key), self.cab_side);
+            let long_press = self.long_press.get(key).copied();
+
             if ev.is_just_pressed() {
-                match value {
-                    SwitchEventAction::Plus => {
-                        if self.value < self.max {
-                            self.value += 1;
-                            plus_minus = true;
-                            has_update = true;
-                        }
+                self.hold_timers.insert(key.clone(), 0.0);
+                // Mit Langdruckfunktion erst beim Loslassen auslösen
+                if long_press.is_none() {
+                    if let Some(plus) = self.step(*value) {
+                        plus_minus = plus;
+                        has_update = true;
                     }
-                    SwitchEventAction::Minus => {
-                        if self.value > self.min {
-                            self.value -= 1;
-                            has_update = true;
+                }
+            } else if ev.is_pressed() {
+                if let Some(timer) = self.hold_timers.get_mut(key) {
+                    let last = *timer;
+                    *timer += delta();
+                    let now = *timer;
+
+                    match long_press {
+                        Some((hold_time, action)) => {
+                            if last < hold_time && now >= hold_time {
+                                if let Some(plus) = self.step(action) {
+                                    plus_minus = plus;
+                                    has_update = true;
+                                }
+                            }
                         }
-                    }
-                    SwitchEventAction::Set(new_value) => {
-                        if (self.min..=self.max).contains(new_value) && self.value != *new_value {
-                            self.value = *new_value;
-                            plus_minus = true;
-                            has_update = true;
+                        None => {
+                            let repeats = self.repeats(now) - self.repeats(last);
+                            if matches!(value, SwitchEventAction::Plus | SwitchEventAction::Minus) {
+                                for _ in 0..repeats {
+                                    if let Some(plus) = self.step(*value) {
+                                        plus_minus = plus;
+                                        has_update = true;
+                                    }
+                                }
+                            }
                         }
                     }
                 }
             }
+
             if ev.is_just_released() {
+                let held = self.hold_timers.remove(key).unwrap_or_default();
+                if let Some((hold_time, _)) = long_press {
+                    if held < hold_time {
+                        if let Some(plus) = self.step(*value) {
+                            plus_minus = plus;
+                            has_update = true;
+                        }
+                    }
+                }
+
                 match value {
                     SwitchEventAction::Plus => {
                         if self.max_spring && self.value == self.max {
@@ -885,16 +1245,22 @@ impl StepSwitch {
                             has_update = true;
                         }
                     }
-                    SwitchEventAction::Set(_) => {}
+                    SwitchEventAction::Set(_) | SwitchEventAction::None => {}
                 }
             }
         }
+        self.events = events;
 
         if has_update {
             self.play_sound(plus_minus);
             self.update();
         }
 
+        let (drag_update, drag_plus) = self.drag_tick();
+        if drag_update {
+            self.play_sound(drag_plus);
+        }
+
         self.just_changed = if self.value_last != self.value {
             Some(self.value)
         } else {
@@ -0,0 +1,392 @@
+//! Declarative configuration of vehicle elements.
+//!
+//! This module allows to describe switches, step switches, key switches,
+//! lights and sounds in a JSON file instead of writing the builder code for
+//! every single element in the vehicle script. The configuration is read with
+//! [`VehicleConfig::from_json`] and turned into the elements with
+//! [`VehicleConfig::build`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! let config = VehicleConfig::from_json(r#"{
+//!     "switches": {
+//!         "battery": { "animation": "A_Batterie", "cab_side": "A", "event_toggle": "Batterie", "snd_toggle": "Snd_Schalter" }
+//!     },
+//!     "step_switches": {
+//!         "wiper": { "animation": "A_Wischer", "min": 0, "max": 3, "events": { "Wischer_Plus": "Plus", "Wischer_Aus": { "Set": 0 } } }
+//!     },
+//!     "lights": { "cab": "L_Fuehrerstand" },
+//!     "sounds": { "bell": { "name": "Snd_Klingel" } }
+//! }"#).expect("invalid vehicle config");
+//!
+//! let mut elements = config.build();
+//!
+//! // In your main loop
+//! elements.tick();
+//! let battery_on = elements.switches["battery"].value(true);
+//! ```
+
+use std::collections::HashMap;
+
+use lotus_extra::vehicle::CockpitSide;
+use serde::Deserialize;
+
+use crate::{
+    api::{light::Light, sound::Sound},
+    elements::tech::{
+        key_switch::{KeyDepot, KeySwitch},
+        switches::{StepSwitch, Switch, SwitchEventAction, SwitchSoundDirection},
+    },
+};
+
+/// Cab side of an element in the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum CabSideConfig {
+    A,
+    B,
+}
+
+impl From<CabSideConfig> for CockpitSide {
+    fn from(value: CabSideConfig) -> Self {
+        match value {
+            CabSideConfig::A => CockpitSide::A,
+            CabSideConfig::B => CockpitSide::B,
+        }
+    }
+}
+
+/// Configuration of a [`Switch`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct SwitchConfig {
+    pub animation: String,
+    #[serde(default)]
+    pub cab_side: Option<CabSideConfig>,
+    #[serde(default)]
+    pub init: bool,
+    #[serde(default)]
+    pub event_toggle: Option<String>,
+    #[serde(default)]
+    pub event_plus: Option<String>,
+    #[serde(default)]
+    pub event_minus: Option<String>,
+    #[serde(default)]
+    pub snd_toggle: Option<String>,
+    #[serde(default)]
+    pub snd_plus: Option<String>,
+    #[serde(default)]
+    pub snd_minus: Option<String>,
+}
+
+impl SwitchConfig {
+    /// Builds the configured [`Switch`].
+    pub fn build(&self) -> Switch {
+        let mut builder =
+            Switch::builder(&self.animation, self.cab_side.map(Into::into)).init(self.init);
+
+        if let Some(name) = &self.event_toggle {
+            builder = builder.event_toggle(name);
+        }
+        if let Some(name) = &self.event_plus {
+            builder = builder.event_plus(name);
+        }
+        if let Some(name) = &self.event_minus {
+            builder = builder.event_minus(name);
+        }
+        if let Some(name) = &self.snd_toggle {
+            builder = builder.snd_toggle(name);
+        }
+        if let Some(name) = &self.snd_plus {
+            builder = builder.snd_plus(name);
+        }
+        if let Some(name) = &self.snd_minus {
+            builder = builder.snd_minus(name);
+        }
+
+        builder.build()
+    }
+}
+
+/// Alternative sound of a step or key switch position.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AltSoundConfig {
+    pub position: i32,
+    pub sound: String,
+    #[serde(default)]
+    pub direction: Option<SwitchSoundDirection>,
+}
+
+/// Configuration of a [`StepSwitch`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct StepSwitchConfig {
+    pub animation: String,
+    #[serde(default)]
+    pub cab_side: Option<CabSideConfig>,
+    #[serde(default)]
+    pub min: Option<i32>,
+    #[serde(default)]
+    pub max: Option<i32>,
+    #[serde(default)]
+    pub init: Option<i32>,
+    #[serde(default)]
+    pub min_spring: bool,
+    #[serde(default)]
+    pub max_spring: bool,
+    #[serde(default)]
+    pub inv_turn: bool,
+    #[serde(default)]
+    pub events: HashMap<String, SwitchEventAction>,
+    #[serde(default)]
+    pub mapping: HashMap<i32, f32>,
+    #[serde(default)]
+    pub snd_plus: Option<String>,
+    #[serde(default)]
+    pub snd_minus: Option<String>,
+    #[serde(default)]
+    pub alt_sounds: Vec<AltSoundConfig>,
+}
+
+impl StepSwitchConfig {
+    /// Builds the configured [`StepSwitch`].
+    pub fn build(&self) -> StepSwitch {
+        let mut builder = StepSwitch::builder(&self.animation, self.cab_side.map(Into::into));
+
+        if let Some(min) = self.min {
+            builder = builder.min(min);
+        }
+        if let Some(max) = self.max {
+            builder = builder.max(max);
+        }
+        if let Some(init) = self.init {
+            builder = builder.init(init);
+        }
+        if self.min_spring {
+            builder = builder.min_spring();
+        }
+        if self.max_spring {
+            builder = builder.max_spring();
+        }
+        if self.inv_turn {
+            builder = builder.inv_turn();
+        }
+        for (name, action) in &self.events {
+            builder = builder.event(name, *action);
+        }
+        if !self.mapping.is_empty() {
+            builder = builder.mapping(self.mapping.clone());
+        }
+        if let Some(name) = &self.snd_plus {
+            builder = builder.snd_default_plus(name);
+        }
+        if let Some(name) = &self.snd_minus {
+            builder = builder.snd_default_minus(name);
+        }
+        for alt in &self.alt_sounds {
+            builder = builder.add_alt_sound(alt.position, &alt.sound, alt.direction);
+        }
+
+        builder.build()
+    }
+}
+
+/// Configuration of a [`KeySwitch`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeySwitchConfig {
+    pub key_depot: String,
+    pub animation: String,
+    pub visibility: String,
+    #[serde(default)]
+    pub cab_side: Option<CabSideConfig>,
+    #[serde(default)]
+    pub min: Option<i32>,
+    #[serde(default)]
+    pub max: Option<i32>,
+    #[serde(default)]
+    pub pullout_min: bool,
+    #[serde(default)]
+    pub pullout_max: bool,
+    #[serde(default)]
+    pub pullout_states: Vec<i32>,
+    #[serde(default)]
+    pub min_spring: bool,
+    #[serde(default)]
+    pub max_spring: bool,
+    #[serde(default)]
+    pub event_toggle: Option<String>,
+    #[serde(default)]
+    pub event_turn: Option<String>,
+    #[serde(default)]
+    pub event_plus: Option<String>,
+    #[serde(default)]
+    pub event_minus: Option<String>,
+    #[serde(default)]
+    pub mapping: HashMap<i32, f32>,
+    #[serde(default)]
+    pub snd_insert: Option<String>,
+    #[serde(default)]
+    pub snd_takeout: Option<String>,
+    #[serde(default)]
+    pub snd_default: Option<String>,
+    #[serde(default)]
+    pub alt_sounds: Vec<AltSoundConfig>,
+}
+
+impl KeySwitchConfig {
+    /// Builds the configured [`KeySwitch`].
+    pub fn build(&self) -> KeySwitch {
+        let mut builder = KeySwitch::builder(
+            KeyDepot::new(&self.key_depot),
+            &self.animation,
+            &self.visibility,
+            self.cab_side.map(Into::into),
+        );
+
+        if let Some(min) = self.min {
+            builder = builder.min(min);
+        }
+        if let Some(max) = self.max {
+            builder = builder.max(max);
+        }
+        if self.pullout_min {
+            builder = builder.pullout_min();
+        }
+        if self.pullout_max {
+            builder = builder.pullout_max();
+        }
+        for state in &self.pullout_states {
+            builder = builder.add_pullout_state(*state);
+        }
+        if self.min_spring {
+            builder = builder.min_spring();
+        }
+        if self.max_spring {
+            builder = builder.max_spring();
+        }
+        if let Some(name) = &self.event_toggle {
+            builder = builder.event_toggle(name);
+        }
+        if let Some(name) = &self.event_turn {
+            builder = builder.event_turn(name);
+        }
+        if let Some(name) = &self.event_plus {
+            builder = builder.event_plus(name);
+        }
+        if let Some(name) = &self.event_minus {
+            builder = builder.event_minus(name);
+        }
+        if !self.mapping.is_empty() {
+            builder = builder.mapping(self.mapping.clone());
+        }
+        if let Some(name) = &self.snd_insert {
+            builder = builder.snd_insert(name);
+        }
+        if let Some(name) = &self.snd_takeout {
+            builder = builder.snd_takeout(name);
+        }
+        if let Some(name) = &self.snd_default {
+            builder = builder.snd_default(name);
+        }
+        for alt in &self.alt_sounds {
+            builder = builder.add_alt_sound(alt.position, &alt.sound);
+        }
+
+        builder.build()
+    }
+}
+
+/// Configuration of a [`Sound`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct SoundConfig {
+    pub name: String,
+    #[serde(default)]
+    pub volume: Option<String>,
+    #[serde(default)]
+    pub pitch: Option<String>,
+}
+
+impl SoundConfig {
+    /// Builds the configured [`Sound`].
+    pub fn build(&self) -> Sound {
+        Sound::new(
+            Some(&self.name),
+            self.volume.as_deref(),
+            self.pitch.as_deref(),
+        )
+    }
+}
+
+/// Declarative description of the elements of a vehicle.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VehicleConfig {
+    #[serde(default)]
+    pub switches: HashMap<String, SwitchConfig>,
+    #[serde(default)]
+    pub step_switches: HashMap<String, StepSwitchConfig>,
+    #[serde(default)]
+    pub key_switches: HashMap<String, KeySwitchConfig>,
+    /// Light variable names by key
+    #[serde(default)]
+    pub lights: HashMap<String, String>,
+    #[serde(default)]
+    pub sounds: HashMap<String, SoundConfig>,
+}
+
+impl VehicleConfig {
+    /// Reads the configuration from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parser error if the string is no valid configuration.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Builds all configured elements.
+    pub fn build(&self) -> ConfiguredElements {
+        ConfiguredElements {
+            switches: self
+                .switches
+                .iter()
+                .map(|(key, config)| (key.clone(), config.build()))
+                .collect(),
+            step_switches: self
+                .step_switches
+                .iter()
+                .map(|(key, config)| (key.clone(), config.build()))
+                .collect(),
+            key_switches: self
+                .key_switches
+                .iter()
+                .map(|(key, config)| (key.clone(), config.build()))
+                .collect(),
+            lights: self
+                .lights
+                .iter()
+                .map(|(key, name)| (key.clone(), Light::new(Some(name))))
+                .collect(),
+            sounds: self
+                .sounds
+                .iter()
+                .map(|(key, config)| (key.clone(), config.build()))
+                .collect(),
+        }
+    }
+}
+
+/// Elements built from a [`VehicleConfig`], accessible by their keys.
+pub struct ConfiguredElements {
+    pub switches: HashMap<String, Switch>,
+    pub step_switches: HashMap<String, StepSwitch>,
+    pub key_switches: HashMap<String, KeySwitch>,
+    pub lights: HashMap<String, Light>,
+    pub sounds: HashMap<String, Sound>,
+}
+
+impl ConfiguredElements {
+    /// Ticks all switches.
+    pub fn tick(&mut self) {
+        self.switches.values_mut().for_each(Switch::tick);
+        self.step_switches.values_mut().for_each(StepSwitch::tick);
+        self.key_switches.values_mut().for_each(KeySwitch::tick);
+    }
+}
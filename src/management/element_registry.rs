@@ -0,0 +1,196 @@
+//! Registry for ticking elements and dispatching messages in bulk.
+//!
+//! Instead of calling `tick()` and `on_message()` of every switch, door and
+//! reader by hand, the elements are registered once in an
+//! [`ElementRegistry`]. The main loop then only calls
+//! [`ElementRegistry::tick_all`] and the message handler
+//! [`ElementRegistry::dispatch`]. Elements are processed in the order of
+//! registration.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let mut registry = ElementRegistry::new();
+//!
+//! let battery = registry.register(Switch::builder("A_Batterie", Some(CockpitSide::A)).event_toggle("Batterie").build());
+//! let reverser = registry.register(ReverserLogic::new(0.1));
+//!
+//! // In on_message:
+//! registry.dispatch(msg);
+//!
+//! // In the main loop:
+//! registry.tick_all();
+//! let battery_on = registry.get(battery).value(true);
+//! registry.get_mut(reverser).tick(lever_a, lever_b, key_a, key_b, v_ground());
+//! ```
+
+use std::{any::Any, marker::PhantomData};
+
+use lotus_script::prelude::Message;
+
+use crate::{
+    components::{
+        brakes::spring_brake::SpringBrakeActuator,
+        electrics::pantograph::ManualPantograph,
+        general::{
+            cab_manager::CabManager,
+            coupler::SimpleCoupler,
+            folding_seat::FoldingSeat,
+            switch_control_unit::SwtichControlUnit,
+            windows::{FoldingWindow, SlidingWindow},
+        },
+        light::{blinker::BlinkerUnit, exterior_lights::LightController},
+        traction::{
+            continous_throttle_lever::ContinuousThrottleLever, reverser_logic::ReverserLogic,
+            sander::Sander,
+        },
+    },
+    elements::tech::{
+        buttons::PushButton,
+        cranc::Crank,
+        handpin::HandPin,
+        key_switch::KeySwitch,
+        seals::{CoveredKey, SealedStepSwitch, SealedSwitch},
+        slider::{Rollo, Slider, SunBlind},
+        switches::{StepSwitch, Switch},
+    },
+};
+
+/// Element that can be managed by an [`ElementRegistry`].
+///
+/// Both methods do nothing by default, so an element only implements what it
+/// needs. Elements whose `tick` needs arguments only take part in the message
+/// dispatch and are ticked by the vehicle script itself.
+pub trait Element: Any {
+    /// Updates the element once per frame.
+    fn tick(&mut self) {}
+
+    /// Handles an incoming message.
+    fn on_message(&mut self, _msg: Message) {}
+}
+
+/// Typed reference to an element in an [`ElementRegistry`].
+#[derive(Debug)]
+pub struct ElementHandle<T> {
+    index: usize,
+    _element: PhantomData<T>,
+}
+
+impl<T> Clone for ElementHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ElementHandle<T> {}
+
+/// Collection of elements ticked and supplied with messages together.
+#[derive(Default)]
+pub struct ElementRegistry {
+    elements: Vec<Box<dyn Element>>,
+}
+
+impl ElementRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            elements: Vec::new(),
+        }
+    }
+
+    /// Registers an element and returns the handle to access it later.
+    pub fn register<T: Element>(&mut self, element: T) -> ElementHandle<T> {
+        self.elements.push(Box::new(element));
+        ElementHandle {
+            index: self.elements.len() - 1,
+            _element: PhantomData,
+        }
+    }
+
+    /// Returns the registered element.
+    pub fn get<T: Element>(&self, handle: ElementHandle<T>) -> &T {
+        let element: &dyn Any = self.elements[handle.index].as_ref();
+        element
+            .downcast_ref::<T>()
+            .expect("ElementRegistry: handle does not match the element")
+    }
+
+    /// Returns the registered element mutable.
+    pub fn get_mut<T: Element>(&mut self, handle: ElementHandle<T>) -> &mut T {
+        let element: &mut dyn Any = self.elements[handle.index].as_mut();
+        element
+            .downcast_mut::<T>()
+            .expect("ElementRegistry: handle does not match the element")
+    }
+
+    /// Ticks all elements in the order of registration.
+    pub fn tick_all(&mut self) {
+        for element in &mut self.elements {
+            element.tick();
+        }
+    }
+
+    /// Passes a message to all elements in the order of registration.
+    pub fn dispatch(&mut self, msg: Message) {
+        for element in &mut self.elements {
+            element.on_message(msg.clone());
+        }
+    }
+}
+
+//=========================================================================
+
+macro_rules! impl_element_tick {
+    ($($element:ty),* $(,)?) => {
+        $(
+            impl Element for $element {
+                fn tick(&mut self) {
+                    <$element>::tick(self);
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_element_message {
+    ($($element:ty),* $(,)?) => {
+        $(
+            impl Element for $element {
+                fn on_message(&mut self, msg: Message) {
+                    <$element>::on_message(self, msg);
+                }
+            }
+        )*
+    };
+}
+
+impl_element_tick!(
+    Switch,
+    StepSwitch,
+    KeySwitch,
+    SealedSwitch,
+    SealedStepSwitch,
+    CoveredKey,
+    Slider,
+    Rollo,
+    SunBlind,
+    Crank,
+    HandPin,
+    PushButton,
+    ContinuousThrottleLever,
+    ManualPantograph,
+    FoldingSeat,
+    FoldingWindow,
+    SlidingWindow,
+    SimpleCoupler,
+);
+
+impl_element_message!(
+    CabManager,
+    ReverserLogic,
+    Sander,
+    SpringBrakeActuator,
+    LightController,
+    BlinkerUnit,
+    SwtichControlUnit,
+);
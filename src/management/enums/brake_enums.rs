@@ -0,0 +1,25 @@
+/// Represents the sector of a driver's brake valve.
+///
+/// # Examples
+///
+/// ```
+/// use pandemist_vehicle_elements::BrakeValveSector;
+///
+/// let sector = BrakeValveSector::Lap;
+///
+/// if sector == BrakeValveSector::Emergency {
+///     println!("Emergency brake");
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BrakeValveSector {
+    /// Brake pipe is filled to the regulating pressure
+    #[default]
+    Release,
+    /// Brake pipe is closed off
+    Lap,
+    /// Brake pipe pressure is reduced depending on the angle
+    Apply,
+    /// Brake pipe is vented completely
+    Emergency,
+}
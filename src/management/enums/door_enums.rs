@@ -6,6 +6,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::management::enums::general_enums::Side;
+
 /// Specifies which side(s) of a door system to target for operations.
 ///
 /// Used to control which door panels should be affected by door operations
@@ -19,7 +21,7 @@ use serde::{Deserialize, Serialize};
 /// let target = DoorSideTarget::Left;
 /// assert_eq!(target, DoorSideTarget::Left);
 /// ```
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DoorSideTarget {
     /// No specific side targeted (default)
     #[default]
@@ -32,6 +34,46 @@ pub enum DoorSideTarget {
     Both,
 }
 
+impl DoorSideTarget {
+    /// Checks whether the given vehicle side is covered by this target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pandemist_vehicle_elements::{DoorSideTarget, Side};
+    ///
+    /// assert!(DoorSideTarget::Both.includes(Side::Left));
+    /// assert!(!DoorSideTarget::Right.includes(Side::Left));
+    /// assert!(!DoorSideTarget::None.includes(Side::Right));
+    /// ```
+    pub fn includes(&self, side: Side) -> bool {
+        matches!(
+            (self, side),
+            (DoorSideTarget::Both, _)
+                | (DoorSideTarget::Left, Side::Left)
+                | (DoorSideTarget::Right, Side::Right)
+        )
+    }
+
+    /// Combines two side selections, e.g. `Left` and `Right` give `Both`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pandemist_vehicle_elements::DoorSideTarget;
+    ///
+    /// assert_eq!(DoorSideTarget::Left.union(DoorSideTarget::Right), DoorSideTarget::Both);
+    /// assert_eq!(DoorSideTarget::None.union(DoorSideTarget::Left), DoorSideTarget::Left);
+    /// ```
+    pub fn union(self, other: DoorSideTarget) -> Self {
+        match (self, other) {
+            (DoorSideTarget::None, side) | (side, DoorSideTarget::None) => side,
+            (a, b) if a == b => a,
+            _ => DoorSideTarget::Both,
+        }
+    }
+}
+
 //------------------------
 
 /// Specifies the step or platform level to target for door operations.
@@ -173,6 +215,77 @@ impl DoorTarget {
     }
 }
 
+/// Door command of the driver as passed along the consist.
+///
+/// Besides the [`DoorTarget`] it carries the selected platform side and a
+/// mask of the cars it applies to, bit `n` standing for the car with index
+/// `n` in the consist. On the coupling the side is flipped between cars
+/// facing opposite ways, so only the doors on the platform side open.
+///
+/// # Examples
+///
+/// ```
+/// use pandemist_vehicle_elements::{DoorCommand, DoorSideTarget, DoorTarget};
+///
+/// // Release the right doors of the first two cars only
+/// let command = DoorCommand::new(DoorTarget::Release, DoorSideTarget::Right).cars(0b11);
+/// assert!(command.includes_car(1));
+/// assert!(!command.includes_car(2));
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DoorCommand {
+    /// Door command
+    pub target: DoorTarget,
+    /// Platform side
+    pub side: DoorSideTarget,
+    /// Cars the command applies to, bit `n` for the car with index `n`
+    pub car_mask: u32,
+}
+
+impl Default for DoorCommand {
+    fn default() -> Self {
+        Self::new(DoorTarget::Close, DoorSideTarget::None)
+    }
+}
+
+impl DoorCommand {
+    /// Creates a command for all cars.
+    pub fn new(target: DoorTarget, side: DoorSideTarget) -> Self {
+        Self {
+            target,
+            side,
+            car_mask: u32::MAX,
+        }
+    }
+
+    /// Restricts the command to the cars in the mask.
+    pub fn cars(mut self, car_mask: u32) -> Self {
+        self.car_mask = car_mask;
+        self
+    }
+
+    /// Whether the command applies to the car with the given index.
+    pub fn includes_car(&self, index: usize) -> bool {
+        index < 32 && self.car_mask & (1 << index) != 0
+    }
+
+    /// Merges two commands, the targets with [`DoorTarget::merge`], the sides
+    /// and the masks of the commands selecting a side are combined.
+    pub fn merge(&self, other: &DoorCommand) -> Self {
+        let car_mask = match (self.side, other.side) {
+            (DoorSideTarget::None, _) => other.car_mask,
+            (_, DoorSideTarget::None) => self.car_mask,
+            _ => self.car_mask | other.car_mask,
+        };
+
+        Self {
+            target: self.target.merge(&other.target),
+            side: self.side.union(other.side),
+            car_mask,
+        }
+    }
+}
+
 //------------------------
 
 /// Represents the current state of a door.
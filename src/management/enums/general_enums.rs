@@ -135,3 +135,29 @@ pub enum TrainFormationSwitch {
     /// Unit is in a following position
     Following,
 }
+
+//------------------------
+
+/// Represents the position of the headlight switch.
+///
+/// # Examples
+///
+/// ```
+/// use pandemist_vehicle_elements::HeadlightMode;
+///
+/// let mode = HeadlightMode::Full;
+///
+/// if mode >= HeadlightMode::Dim {
+///     println!("Headlights are on");
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HeadlightMode {
+    /// Headlights are turned off (default state)
+    #[default]
+    Off,
+    /// Dimmed headlights
+    Dim,
+    /// Full beam headlights
+    Full,
+}
@@ -1,5 +1,7 @@
+pub mod brake_enums;
 pub mod door_enums;
 pub mod general_enums;
 pub mod state_enums;
 pub mod target_enums;
 pub mod traction_enums;
+pub mod train_protection_enums;
@@ -0,0 +1,72 @@
+/// Represents the frequency of a track magnet of the intermittent train protection.
+///
+/// # Examples
+///
+/// ```
+/// use pandemist_vehicle_elements::TrackMagnet;
+///
+/// let magnet = TrackMagnet::Hz1000;
+///
+/// if magnet == TrackMagnet::Hz2000 {
+///     println!("Stop signal passed");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrackMagnet {
+    /// 500 Hz magnet in front of the signal
+    Hz500,
+    /// 1000 Hz magnet at the distant signal
+    Hz1000,
+    /// 2000 Hz magnet at the main signal
+    Hz2000,
+}
+
+//------------------------
+
+/// Represents the train type set on the PZB unit.
+///
+/// The train type selects the speed limits of the supervision curves.
+///
+/// # Examples
+///
+/// ```
+/// use pandemist_vehicle_elements::PzbTrainType;
+///
+/// let train_type = PzbTrainType::default();
+/// assert_eq!(train_type, PzbTrainType::U);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PzbTrainType {
+    /// Upper train type with the best braking
+    O,
+    /// Middle train type
+    M,
+    /// Lower train type (default state)
+    #[default]
+    U,
+}
+
+//------------------------
+
+/// Represents the operating mode of the speed supervision.
+///
+/// Each mode has its own maximum speed.
+///
+/// # Examples
+///
+/// ```
+/// use pandemist_vehicle_elements::SupervisionMode;
+///
+/// let mode = SupervisionMode::default();
+/// assert_eq!(mode, SupervisionMode::Line);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SupervisionMode {
+    /// Shunting movements
+    Shunting,
+    /// Movements in the depot
+    Depot,
+    /// Line service (default state)
+    #[default]
+    Line,
+}
@@ -0,0 +1,230 @@
+//! # Event Log
+//!
+//! Diagnostic memory ("Ereignisspeicher") of a vehicle. The [`EventLog`]
+//! stores timestamped events like emergency brakes, door faults, train
+//! protection and main switch trips in a ring buffer: once it is full, the
+//! oldest event is overwritten. The buffer and the operating time used as
+//! timestamp are stored in variables, so the memory is kept between sessions
+//! like the mileage of the [`Odometry`](super::odometry::Odometry).
+//!
+//! The MFD queries the entries by category or time, or reads the whole memory
+//! as JSON from the export variable.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut events = EventLog::builder()
+//!     .capacity(200)
+//!     .persist("EventLog")
+//!     .build();
+//!
+//! // In the main loop:
+//! events.set_mileage(odometry.mileage_km);
+//! events.tick();
+//!
+//! if emergency_brake.just_applied() {
+//!     events.log(EventCategory::EmergencyBrake, "Notbremse Wagen A");
+//! }
+//! if let Some(cause) = mainswitch.trip_reason {
+//!     events.log(EventCategory::MainSwitchTrip, format!("{cause:?}"));
+//! }
+//!
+//! let door_faults: Vec<_> = events.by_category(EventCategory::DoorFault).collect();
+//! ```
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::{
+    general::delta,
+    variable::{get_var, set_var},
+};
+
+/// Category of a logged event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventCategory {
+    /// Emergency brake applied
+    EmergencyBrake,
+    /// Door fault or door interlock event
+    DoorFault,
+    /// Train protection intervention
+    TrainProtection,
+    /// Main switch tripped
+    MainSwitchTrip,
+    /// Any other event
+    Other,
+}
+
+/// Entry of the event log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEntry {
+    /// Running number of the event, counted over all sessions
+    pub number: u64,
+    /// Operating time in seconds at which the event occurred
+    pub time: f64,
+    /// Mileage in km at which the event occurred
+    pub mileage_km: f64,
+    /// Category of the event
+    pub category: EventCategory,
+    /// Description of the event
+    pub text: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredLog {
+    next_number: u64,
+    entries: VecDeque<EventEntry>,
+}
+
+pub struct EventLogBuilder {
+    capacity: usize,
+    persist_var: Option<String>,
+}
+
+impl EventLogBuilder {
+    /// Maximum number of entries, older ones are overwritten.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Name of the string variable in which the log is stored as JSON, the
+    /// operating time is stored in `<var_name>_Time`.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    pub fn build(self) -> EventLog {
+        let (stored, time) = match &self.persist_var {
+            Some(var_name) => (
+                serde_json::from_str::<StoredLog>(&get_var::<String>(var_name)).unwrap_or_default(),
+                get_var::<f64>(&format!("{var_name}_Time")),
+            ),
+            None => (StoredLog::default(), 0.0),
+        };
+
+        let mut log = EventLog {
+            capacity: self.capacity,
+            persist_var: self.persist_var,
+            next_number: stored.next_number,
+            entries: stored.entries,
+            time,
+            mileage_km: 0.0,
+        };
+        log.truncate();
+        log
+    }
+}
+
+/// Ring buffer of timestamped events persisted in variables.
+pub struct EventLog {
+    capacity: usize,
+    persist_var: Option<String>,
+
+    next_number: u64,
+    entries: VecDeque<EventEntry>,
+
+    /// Operating time in seconds, used as timestamp
+    pub time: f64,
+    mileage_km: f64,
+}
+
+impl EventLog {
+    /// Creates a new event log builder.
+    pub fn builder() -> EventLogBuilder {
+        EventLogBuilder {
+            capacity: 100,
+            persist_var: None,
+        }
+    }
+
+    /// Advances the operating time.
+    pub fn tick(&mut self) {
+        self.time += delta() as f64;
+
+        if let Some(var_name) = &self.persist_var {
+            set_var(&format!("{var_name}_Time"), self.time);
+        }
+    }
+
+    /// Sets the mileage in km recorded with the next events.
+    pub fn set_mileage(&mut self, mileage_km: f64) {
+        self.mileage_km = mileage_km;
+    }
+
+    /// Logs an event with the current operating time and mileage.
+    pub fn log(&mut self, category: EventCategory, text: impl Into<String>) {
+        self.entries.push_back(EventEntry {
+            number: self.next_number,
+            time: self.time,
+            mileage_km: self.mileage_km,
+            category,
+            text: text.into(),
+        });
+        self.next_number += 1;
+
+        self.truncate();
+        self.store();
+    }
+
+    /// All entries, oldest first.
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &EventEntry> {
+        self.entries.iter()
+    }
+
+    /// The newest entries, newest first.
+    pub fn latest(&self, count: usize) -> impl Iterator<Item = &EventEntry> {
+        self.entries.iter().rev().take(count)
+    }
+
+    /// Entries of a category, oldest first.
+    pub fn by_category(&self, category: EventCategory) -> impl Iterator<Item = &EventEntry> {
+        self.entries.iter().filter(move |e| e.category == category)
+    }
+
+    /// Entries logged at or after the given operating time, oldest first.
+    pub fn since(&self, time: f64) -> impl Iterator<Item = &EventEntry> {
+        self.entries.iter().filter(move |e| e.time >= time)
+    }
+
+    /// Number of stored entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Clears the memory, e.g. after reading it out in the workshop. The
+    /// running number continues.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.store();
+    }
+
+    /// All entries as JSON array, oldest first.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.entries).unwrap_or_default()
+    }
+
+    fn truncate(&mut self) {
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    fn store(&self) {
+        if let Some(var_name) = &self.persist_var {
+            let stored = StoredLog {
+                next_number: self.next_number,
+                entries: self.entries.clone(),
+            };
+            if let Ok(json) = serde_json::to_string(&stored) {
+                set_var(var_name, json);
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Fault injection for training and scenarios.
+//!
+//! The [`FaultManager`] holds a registry of faults which can be injected and
+//! repaired at runtime, either with the [`FaultCommand`] message or with a bool
+//! variable per fault. Components take the id of their fault in the builder,
+//! e.g. [`Compressor`](crate::components::pneumatics::compressor::Compressor)
+//! with `.fault("Compressor")`, and query it every tick, so that a failure is
+//! simulated without any code in the vehicle script:
+//!
+//! - [`FaultKind::DoorMotor`]: the door drive behaves as if unsupplied
+//! - [`FaultKind::PantographStuck`]: the pantograph stays in its position
+//! - [`FaultKind::Compressor`]: the compressor does not start
+//! - [`FaultKind::LampBurnout`]: the [`Light`](crate::api::light::Light) with
+//!   the given name stays dark, no hook in the component is needed
+//!
+//! # Examples
+//!
+//! In the vehicle script:
+//!
+//! ```rust
+//! let mut faults = FaultManager::builder()
+//!     .fault("Door_1", FaultKind::DoorMotor)
+//!     .fault("Panto", FaultKind::PantographStuck)
+//!     .fault("Compressor", FaultKind::Compressor)
+//!     .fault("Headlight_L", FaultKind::LampBurnout { light: "L_Headlight_L".to_string() })
+//!     .var_prefix("Fault_")
+//!     .build();
+//!
+//! let mut compressor = Compressor::builder().fault("Compressor").build();
+//!
+//! // In on_message:
+//! faults.on_message(msg);
+//!
+//! // In the main loop:
+//! faults.tick();
+//! ```
+//!
+//! Injecting a fault from a scenario:
+//!
+//! ```rust
+//! send_message(
+//!     &FaultCommand { id: Some("Panto".to_string()), active: true },
+//!     [MessageTarget::Broadcast { across_couplings: true, include_self: true }],
+//! );
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+use lotus_script::prelude::{message_type, Message};
+use serde::{Deserialize, Serialize};
+
+use crate::api::variable::{get_var, set_var};
+
+/// Kind of an injectable fault.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FaultKind {
+    /// Failure of a door motor or of the door valves
+    DoorMotor,
+    /// Pantograph stuck in its current position
+    PantographStuck,
+    /// Failure of the compressor motor
+    Compressor,
+    /// Burnt out lamp
+    LampBurnout {
+        /// Name of the light variable
+        light: String,
+    },
+}
+
+/// Command injecting or repairing a fault.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FaultCommand {
+    /// Id of the fault, `None` applies the command to all faults
+    pub id: Option<String>,
+    /// Whether the fault is injected or repaired
+    pub active: bool,
+}
+
+message_type!(FaultCommand, "Pan_Fault", "Command");
+
+thread_local! {
+    static ACTIVE_FAULTS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+    static BURNT_LAMPS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+/// Whether the fault with the given id is injected.
+#[must_use]
+pub fn fault_active(id: &str) -> bool {
+    ACTIVE_FAULTS.with(|faults| faults.borrow().contains(id))
+}
+
+/// Whether the light with the given variable name is burnt out.
+pub(crate) fn lamp_burnt_out(light: &str) -> bool {
+    BURNT_LAMPS.with(|lamps| lamps.borrow().contains(light))
+}
+
+#[derive(Debug)]
+struct RegisteredFault {
+    kind: FaultKind,
+    var: Option<String>,
+    var_last: bool,
+}
+
+pub struct FaultManagerBuilder {
+    faults: Vec<(String, FaultKind)>,
+    var_prefix: Option<String>,
+}
+
+impl FaultManagerBuilder {
+    /// Registers an injectable fault.
+    pub fn fault(mut self, id: impl Into<String>, kind: FaultKind) -> Self {
+        self.faults.push((id.into(), kind));
+        self
+    }
+
+    /// Prefix of the bool variables with which the faults are injected, the
+    /// variable of a fault is the prefix followed by the fault id.
+    pub fn var_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.var_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn build(self) -> FaultManager {
+        let faults = self
+            .faults
+            .into_iter()
+            .map(|(id, kind)| {
+                let var = self.var_prefix.as_ref().map(|p| format!("{p}{id}"));
+                let fault = RegisteredFault {
+                    kind,
+                    var,
+                    var_last: false,
+                };
+                (id, fault)
+            })
+            .collect();
+
+        FaultManager { faults }
+    }
+}
+
+/// Registry of the injectable faults of a vehicle.
+pub struct FaultManager {
+    faults: HashMap<String, RegisteredFault>,
+}
+
+impl FaultManager {
+    /// Creates a new fault manager builder, no fault is registered.
+    pub fn builder() -> FaultManagerBuilder {
+        FaultManagerBuilder {
+            faults: Vec::new(),
+            var_prefix: None,
+        }
+    }
+
+    /// Handles the [`FaultCommand`] messages.
+    pub fn on_message(&mut self, msg: Message) {
+        msg.handle::<FaultCommand>(|m| {
+            match m.id {
+                Some(id) => self.set(&id, m.active),
+                None if m.active => self.inject_all(),
+                None => self.repair_all(),
+            }
+            Ok(())
+        })
+        .expect("FaultCommand: message handle failed");
+    }
+
+    /// Reads the fault variables, a changed variable injects or repairs its
+    /// fault.
+    pub fn tick(&mut self) {
+        let changed: Vec<(String, bool)> = self
+            .faults
+            .iter_mut()
+            .filter_map(|(id, fault)| {
+                let value = get_var::<bool>(fault.var.as_ref()?);
+                if value == fault.var_last {
+                    return None;
+                }
+                fault.var_last = value;
+                Some((id.clone(), value))
+            })
+            .collect();
+
+        for (id, active) in changed {
+            self.set(&id, active);
+        }
+    }
+
+    /// Injects a registered fault, unknown ids are ignored.
+    pub fn inject(&mut self, id: &str) {
+        self.set(id, true);
+    }
+
+    /// Repairs a fault.
+    pub fn repair(&mut self, id: &str) {
+        self.set(id, false);
+    }
+
+    /// Injects all registered faults.
+    pub fn inject_all(&mut self) {
+        let ids: Vec<String> = self.faults.keys().cloned().collect();
+        for id in ids {
+            self.set(&id, true);
+        }
+    }
+
+    /// Repairs all faults.
+    pub fn repair_all(&mut self) {
+        let ids: Vec<String> = self.faults.keys().cloned().collect();
+        for id in ids {
+            self.set(&id, false);
+        }
+    }
+
+    /// Ids of the injected faults.
+    #[must_use]
+    pub fn active_faults(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .faults
+            .keys()
+            .filter(|id| fault_active(id))
+            .cloned()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn set(&mut self, id: &str, active: bool) {
+        let Some(fault) = self.faults.get_mut(id) else {
+            return;
+        };
+
+        ACTIVE_FAULTS.with(|faults| {
+            let mut faults = faults.borrow_mut();
+            if active {
+                faults.insert(id.to_string());
+            } else {
+                faults.remove(id);
+            }
+        });
+
+        if let FaultKind::LampBurnout { light } = &fault.kind {
+            BURNT_LAMPS.with(|lamps| {
+                let mut lamps = lamps.borrow_mut();
+                if active {
+                    lamps.insert(light.clone());
+                } else {
+                    lamps.remove(light);
+                }
+            });
+        }
+
+        // Variable mitführen, damit sie den Zustand anzeigt
+        if let Some(var) = &fault.var {
+            fault.var_last = active;
+            set_var(var, active);
+        }
+    }
+}
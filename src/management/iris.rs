@@ -0,0 +1,296 @@
+//! # IRIS
+//!
+//! Operations data recorder ("IRIS"). The [`Iris`] is registered on the
+//! TrainBus as [`PeripheryKind::Iris`] and records the data of each trip:
+//!
+//! - Journey data entered on the IBIS (line, course, destination)
+//! - Arrival, departure and delay at each stop of the [`Timetable`]
+//! - Passengers boarding and alighting at each stop
+//! - Events like faults reported on the TrainBus
+//!
+//! The IRIS hands the delay of each departure to the [`Scorecard`]. When the
+//! vehicle leaves the terminus, the trip is closed together with the
+//! [`ScoreReport`] of the run and exported as [`IrisTrip`] in JSON; the
+//! scorecard then starts over for the next trip.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut iris = Iris::builder(80).export_var("IRIS_Export").build();
+//!
+//! // In on_message:
+//! iris.on_message(msg);
+//!
+//! // In the main loop:
+//! timetable.tick(time, v_ground(), odometry.distance, door_target, doors_closed);
+//! iris.tick(
+//!     voltage > 0.5,
+//!     time,
+//!     odometry.mileage_km,
+//!     load.passengers,
+//!     &timetable,
+//!     &mut scorecard,
+//! );
+//! ```
+
+use lotus_script::prelude::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::variable::set_var,
+    management::{
+        scorecard::{ScoreReport, Scorecard},
+        timetable::Timetable,
+        trainbus::{IbisFaultReport, PeripheryKind, TrainBusPeriferie},
+    },
+    messages::ibis_messages::IbisInput,
+};
+
+/// Data recorded at a stop.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IrisStop {
+    /// Name of the stop
+    pub name: String,
+    /// Actual arrival in seconds since midnight
+    pub arrival: Option<f32>,
+    /// Actual departure in seconds since midnight
+    pub departure: Option<f32>,
+    /// Delay at the departure in seconds, negative when early
+    pub delay: f32,
+    /// Passengers who boarded
+    pub boarded: u32,
+    /// Passengers who alighted
+    pub alighted: u32,
+}
+
+/// Event recorded during a trip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrisEvent {
+    /// Time in seconds since midnight
+    pub time: f32,
+    /// Description of the event
+    pub text: String,
+}
+
+/// Dataset of a trip.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IrisTrip {
+    /// Journey data entered on the IBIS
+    pub input: IbisInput,
+    /// Start of the trip in seconds since midnight
+    pub start: f32,
+    /// End of the trip in seconds since midnight
+    pub end: f32,
+    /// Distance of the trip in km
+    pub distance_km: f64,
+    /// Served stops in driving order
+    pub stops: Vec<IrisStop>,
+    /// Events in chronological order
+    pub events: Vec<IrisEvent>,
+    /// Evaluation of the run
+    pub score: Option<ScoreReport>,
+}
+
+impl IrisTrip {
+    /// Passengers who boarded during the trip.
+    pub fn boarded(&self) -> u32 {
+        self.stops.iter().map(|s| s.boarded).sum()
+    }
+
+    /// Delay at the last departure in seconds.
+    pub fn final_delay(&self) -> f32 {
+        self.stops
+            .iter()
+            .rev()
+            .find(|s| s.departure.is_some())
+            .map(|s| s.delay)
+            .unwrap_or(0.0)
+    }
+
+    /// The trip as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+pub struct IrisBuilder {
+    slot_index: i32,
+    export_var: Option<String>,
+}
+
+impl IrisBuilder {
+    /// String variable into which each closed trip is written as JSON.
+    pub fn export_var(mut self, name: impl Into<String>) -> Self {
+        self.export_var = Some(name.into());
+        self
+    }
+
+    pub fn build(self) -> Iris {
+        Iris {
+            trainbus: TrainBusPeriferie::new(PeripheryKind::Iris, self.slot_index),
+            export_var: self.export_var,
+            time: 0.0,
+            started: false,
+            start_distance: 0.0,
+            at_stop_last: None,
+            passengers_last: 0,
+            trip: IrisTrip::default(),
+            last_trip: None,
+        }
+    }
+}
+
+/// Recorder of the trip data with export at the terminus.
+pub struct Iris {
+    trainbus: TrainBusPeriferie,
+    export_var: Option<String>,
+
+    time: f32,
+    started: bool,
+    start_distance: f64,
+    at_stop_last: Option<usize>,
+    passengers_last: u32,
+
+    /// Trip currently recorded
+    pub trip: IrisTrip,
+    /// Last closed trip
+    pub last_trip: Option<IrisTrip>,
+}
+
+impl Iris {
+    /// Creates a new IRIS builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_index` - Slot index with which the IRIS registers on the TrainBus
+    pub fn builder(slot_index: i32) -> IrisBuilder {
+        IrisBuilder {
+            slot_index,
+            export_var: None,
+        }
+    }
+
+    /// Receives the journey data and the fault reports.
+    pub fn on_message(&mut self, msg: Message) {
+        self.trainbus.on_message(msg.clone());
+
+        msg.handle::<IbisInput>(|m| {
+            self.trip.input = m;
+            Ok(())
+        })
+        .expect("IbisInput: message handle failed");
+
+        msg.handle::<IbisFaultReport>(|m| {
+            let text = format!(
+                "W{} {}{} {}",
+                m.car,
+                m.kind.short_name(),
+                m.counter,
+                m.state.short_text()
+            );
+            self.log_event(self.time, text);
+            Ok(())
+        })
+        .expect("IbisFaultReport: message handle failed");
+    }
+
+    /// Records an event of the current trip.
+    pub fn log_event(&mut self, time: f32, text: impl Into<String>) {
+        self.trip.events.push(IrisEvent {
+            time,
+            text: text.into(),
+        });
+    }
+
+    /// Follows the timetable and records the trip.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - Whether the IRIS is supplied, nothing is recorded without
+    /// * `time` - Current time in seconds since midnight
+    /// * `distance_km` - Travelled distance in km, e.g. the mileage of the odometry
+    /// * `passengers` - Passengers on board
+    /// * `timetable` - Timetable of the course
+    /// * `scorecard` - Scorecard receiving the departures
+    pub fn tick(
+        &mut self,
+        power: bool,
+        time: f32,
+        distance_km: f64,
+        passengers: u32,
+        timetable: &Timetable,
+        scorecard: &mut Scorecard,
+    ) {
+        if !power {
+            return;
+        }
+        self.trainbus.tick();
+
+        if !self.started {
+            self.started = true;
+            self.trip.start = time;
+            self.start_distance = distance_km;
+            self.passengers_last = passengers;
+        }
+        self.time = time;
+        self.trip.distance_km = distance_km - self.start_distance;
+
+        match (self.at_stop_last, timetable.at_stop) {
+            (None, Some(index)) => {
+                self.trip.stops.push(IrisStop {
+                    name: timetable.stops()[index].name.clone(),
+                    arrival: Some(time),
+                    ..Default::default()
+                });
+                self.passengers_last = passengers;
+            }
+            (Some(index), None) => {
+                if let Some(stop) = self.trip.stops.last_mut() {
+                    stop.departure = Some(time);
+                    stop.delay = timetable.delay;
+                }
+                scorecard.departure(timetable.delay);
+
+                // Fahrt endet mit der Abfahrt an der Endhaltestelle
+                if index + 1 >= timetable.stops().len() {
+                    self.close(time, scorecard);
+                }
+            }
+            _ => {}
+        }
+        self.at_stop_last = timetable.at_stop;
+
+        if self.at_stop_last.is_some() {
+            if let Some(stop) = self.trip.stops.last_mut() {
+                if passengers > self.passengers_last {
+                    stop.boarded += passengers - self.passengers_last;
+                } else {
+                    stop.alighted += self.passengers_last - passengers;
+                }
+            }
+        }
+        self.passengers_last = passengers;
+    }
+
+    /// Closes the current trip, e.g. when the course is abandoned.
+    pub fn close(&mut self, time: f32, scorecard: &mut Scorecard) {
+        let input = self.trip.input;
+        let mut trip = std::mem::replace(
+            &mut self.trip,
+            IrisTrip {
+                input,
+                ..Default::default()
+            },
+        );
+        trip.end = time;
+        trip.score = Some(scorecard.report());
+        scorecard.reset();
+
+        if let Some(var_name) = &self.export_var {
+            set_var(var_name, trip.to_json());
+        }
+
+        self.last_trip = Some(trip);
+        self.started = false;
+    }
+}
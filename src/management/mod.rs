@@ -1,5 +1,15 @@
 pub mod communications;
 pub mod communicator;
+pub mod config;
+pub mod element_registry;
 pub mod enums;
+pub mod event_log;
+pub mod fault_manager;
+pub mod iris;
+pub mod odometry;
+pub mod route;
+pub mod scorecard;
 pub mod structs;
+pub mod timetable;
 pub mod trainbus;
+pub mod wear;
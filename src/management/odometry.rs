@@ -0,0 +1,255 @@
+//! Odometry of the vehicle.
+//!
+//! This module provides an [`Odometry`] which integrates the speed of the
+//! vehicle into distances. It is the common distance source for devices that
+//! supervise or count distances, e.g. the distance supervision of the train
+//! protection or the location logic of the IBIS.
+//!
+//! ## Features
+//!
+//! - Distance pulses of a wheel pulse generator with calibrated wheel diameter
+//! - Distance since the start of the session to measure distances from a mark
+//! - Cumulative mileage stored in a script variable to be restored in the next session
+//! - Trip counter which can be reset from the cab
+//!
+//! ## Example
+//!
+//! ```rust
+//! use lotus_extra::vehicle::CockpitSide;
+//!
+//! let mut odometry = Odometry::builder(0.68)
+//!     .pulses_per_revolution(80)
+//!     .persist("Odometry_Mileage")
+//!     .trip_reset_event("Trip_Reset", Some(CockpitSide::A))
+//!     .build();
+//!
+//! // In your main loop
+//! odometry.tick(v_ground());
+//!
+//! let mark = odometry.distance;
+//! // ...
+//! let travelled = odometry.distance_since(mark);
+//! ```
+
+use lotus_extra::vehicle::CockpitSide;
+
+use crate::api::{
+    general::delta,
+    key_event::KeyEvent,
+    variable::{load_persistent, store_persistent},
+};
+
+/// Builder for creating an [`Odometry`] with customizable configuration.
+pub struct OdometryBuilder {
+    wheel_diameter: f32,
+    calibration: f32,
+    pulses_per_revolution: u32,
+
+    persist_var: Option<String>,
+    init_km: f64,
+
+    key_trip_reset: KeyEvent,
+}
+
+impl OdometryBuilder {
+    /// Sets the calibration factor of the wheel diameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `calibration` - Real wheel diameter relative to the nominal one, e.g. 0.98 for worn wheels
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn calibration(mut self, calibration: f32) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Sets the number of pulses of the pulse generator per wheel revolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `pulses_per_revolution` - Pulses per wheel revolution
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn pulses_per_revolution(mut self, pulses_per_revolution: u32) -> Self {
+        self.pulses_per_revolution = pulses_per_revolution.max(1);
+        self
+    }
+
+    /// Sets the initial mileage, used if no stored mileage is available.
+    ///
+    /// # Arguments
+    ///
+    /// * `init_km` - Initial mileage in km
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn init_km(mut self, init_km: f64) -> Self {
+        self.init_km = init_km;
+        self
+    }
+
+    /// Stores the mileage in a script variable.
+    ///
+    /// On build the mileage is read from the variable, in every tick it is
+    /// written back. If the variable is still zero the initial mileage is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_name` - Name of the variable in km
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// Sets the key event resetting the trip counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the key event
+    /// * `cab_side` - Optional cab side for key event handling
+    ///
+    /// # Returns
+    ///
+    /// Returns `self` for method chaining.
+    pub fn trip_reset_event(
+        mut self,
+        name: impl Into<String>,
+        cab_side: Option<CockpitSide>,
+    ) -> Self {
+        self.key_trip_reset = KeyEvent::new(Some(&name.into()), cab_side);
+        self
+    }
+
+    /// Constructs the final [`Odometry`] instance.
+    pub fn build(self) -> Odometry {
+        let mileage_km = self
+            .persist_var
+            .as_ref()
+            .and_then(|var_name| load_persistent::<f64>(var_name))
+            .unwrap_or(self.init_km);
+
+        Odometry {
+            pulse_distance: std::f32::consts::PI * self.wheel_diameter * self.calibration
+                / self.pulses_per_revolution as f32,
+            persist_var: self.persist_var,
+            key_trip_reset: self.key_trip_reset,
+            pulse_frac: 0.0,
+            pulses: 0,
+            pulse_count: 0,
+            distance: 0.0,
+            trip_m: 0.0,
+            mileage_km,
+        }
+    }
+}
+
+/// Distance pulses, mileage and trip counter of the vehicle.
+#[derive(Debug)]
+pub struct Odometry {
+    pulse_distance: f32,
+
+    persist_var: Option<String>,
+
+    key_trip_reset: KeyEvent,
+
+    pulse_frac: f32,
+
+    /// Pulses generated in the last tick
+    pub pulses: u32,
+    /// Pulses generated since the start of the session
+    pub pulse_count: u64,
+    /// Distance in m since the start of the session
+    pub distance: f64,
+    /// Distance in m since the last reset of the trip counter
+    pub trip_m: f64,
+    /// Cumulative mileage in km
+    pub mileage_km: f64,
+}
+
+impl Odometry {
+    /// Creates a new odometry builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `wheel_diameter` - Nominal diameter of the measuring wheel in m
+    ///
+    /// # Returns
+    ///
+    /// An [`OdometryBuilder`] for configuring the odometry.
+    pub fn builder(wheel_diameter: f32) -> OdometryBuilder {
+        OdometryBuilder {
+            wheel_diameter,
+            calibration: 1.0,
+            pulses_per_revolution: 1,
+            persist_var: None,
+            init_km: 0.0,
+            key_trip_reset: KeyEvent::new(None, None),
+        }
+    }
+
+    /// Updates the distances and pulses.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Speed of the measuring wheel in m/s, the sign is ignored
+    pub fn tick(&mut self, speed: f32) {
+        if self.key_trip_reset.is_just_pressed() {
+            self.reset_trip();
+        }
+
+        let step = speed.abs() * delta();
+
+        // Impulsgeber
+        self.pulse_frac += step / self.pulse_distance;
+        self.pulses = self.pulse_frac.trunc() as u32;
+        self.pulse_frac -= self.pulses as f32;
+        self.pulse_count += self.pulses as u64;
+
+        self.distance += step as f64;
+        self.trip_m += step as f64;
+        self.mileage_km += step as f64 / 1000.0;
+
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(var_name, self.mileage_km);
+        }
+    }
+
+    /// Resets the trip counter to zero.
+    pub fn reset_trip(&mut self) {
+        self.trip_m = 0.0;
+    }
+
+    /// Returns the distance travelled since a mark.
+    ///
+    /// # Arguments
+    ///
+    /// * `mark` - Value of [`Odometry::distance`] at the mark
+    ///
+    /// # Returns
+    ///
+    /// The distance in m since the mark.
+    #[must_use]
+    pub fn distance_since(&self, mark: f64) -> f64 {
+        self.distance - mark
+    }
+
+    /// Returns the distance a single pulse stands for.
+    ///
+    /// # Returns
+    ///
+    /// The calibrated distance per pulse in m.
+    #[must_use]
+    pub fn pulse_distance(&self) -> f32 {
+        self.pulse_distance
+    }
+}
@@ -0,0 +1,298 @@
+//! # Route
+//!
+//! The [`RouteFollower`] follows the vehicle along an ordered stop list with
+//! the distances of the stops. The position is taken from the
+//! [`Odometry`](super::odometry::Odometry), the door release confirms the
+//! stop. The follower determines the current and the next stop and emits
+//! [`RouteEvent`]s, on which the announcements, the displays and the automatic
+//! stop brake react:
+//!
+//! - [`RouteEvent::Approaching`] once the next stop is closer than the
+//!   approach distance
+//! - [`RouteEvent::Arrived`] on door release at standstill near a stop
+//! - [`RouteEvent::Departed`] when starting off with closed doors
+//! - [`RouteEvent::Passed`] when a stop is passed without door release
+//!
+//! The follower works on any stop type implementing [`StopPosition`], the
+//! [`Timetable`](super::timetable::Timetable) follows its stop list with it.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut route = RouteFollower::from_json(r#"[
+//!     { "name": "Hauptbahnhof", "position_m": 0.0 },
+//!     { "name": "Marktplatz", "position_m": 850.0 },
+//!     { "name": "Rathaus", "position_m": 1400.0 }
+//! ]"#)
+//! .expect("invalid route")
+//! .approach_distance(200.0)
+//! .build();
+//!
+//! // In the main loop:
+//! route.tick(v_ground(), odometry.distance, door_target, doors.doors_closed);
+//! for event in route.take_events() {
+//!     if let RouteEvent::Approaching(index) = event {
+//!         announcer.announce(&route.stops()[index].name);
+//!     }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::management::enums::door_enums::DoorTarget;
+
+/// Stop which can be followed by the [`RouteFollower`].
+pub trait StopPosition {
+    /// Position in m from the start of the route, without a position every
+    /// door release counts as arrival
+    fn position_m(&self) -> Option<f64>;
+}
+
+/// Stop of the route.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteStop {
+    /// Name of the stop
+    pub name: String,
+    /// Position in m from the start of the route
+    pub position_m: f64,
+}
+
+impl RouteStop {
+    /// Creates a new stop.
+    pub fn new(name: impl Into<String>, position_m: f64) -> Self {
+        Self {
+            name: name.into(),
+            position_m,
+        }
+    }
+}
+
+impl StopPosition for RouteStop {
+    fn position_m(&self) -> Option<f64> {
+        Some(self.position_m)
+    }
+}
+
+/// Event of the route follower, carrying the index of the stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteEvent {
+    /// The stop is closer than the approach distance
+    Approaching(usize),
+    /// The doors were released at the stop
+    Arrived(usize),
+    /// The vehicle left the stop
+    Departed(usize),
+    /// The stop was passed without door release
+    Passed(usize),
+}
+
+pub struct RouteFollowerBuilder<S = RouteStop> {
+    stops: Vec<S>,
+    approach_distance: f64,
+    stop_window: f64,
+    standstill_speed: f32,
+}
+
+impl<S: StopPosition> RouteFollowerBuilder<S> {
+    /// Distance in m before a stop from which it is approached.
+    pub fn approach_distance(mut self, distance: f64) -> Self {
+        self.approach_distance = distance;
+        self
+    }
+
+    /// Distance in m around the stop position within which a door release
+    /// counts as arrival and after which the stop counts as passed.
+    pub fn stop_window(mut self, stop_window: f64) -> Self {
+        self.stop_window = stop_window;
+        self
+    }
+
+    /// Speed in m/s below which the vehicle is considered standing.
+    pub fn standstill_speed(mut self, speed: f32) -> Self {
+        self.standstill_speed = speed;
+        self
+    }
+
+    pub fn build(self) -> RouteFollower<S> {
+        RouteFollower {
+            stops: self.stops,
+            approach_distance: self.approach_distance,
+            stop_window: self.stop_window,
+            standstill_speed: self.standstill_speed,
+            origin: None,
+            release_last: false,
+            approach_sent: false,
+            events: Vec::new(),
+            position: 0.0,
+            current: None,
+            next: 0,
+            distance_to_next: None,
+            approaching: false,
+        }
+    }
+}
+
+/// Follower of a stop list by odometry and door release.
+pub struct RouteFollower<S = RouteStop> {
+    stops: Vec<S>,
+    approach_distance: f64,
+    stop_window: f64,
+    standstill_speed: f32,
+
+    origin: Option<f64>,
+    release_last: bool,
+    approach_sent: bool,
+    events: Vec<RouteEvent>,
+
+    /// Position in m from the start of the route
+    pub position: f64,
+    /// Index of the stop the vehicle is standing at
+    pub current: Option<usize>,
+    /// Index of the next stop
+    pub next: usize,
+    /// Distance in m to the next stop, negative after passing its position and
+    /// `None` for a stop without position
+    pub distance_to_next: Option<f64>,
+    /// Whether the next stop is closer than the approach distance
+    pub approaching: bool,
+}
+
+impl RouteFollower {
+    /// Creates a new route follower builder from a JSON array of stops.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parser error if the string is no valid stop list.
+    pub fn from_json(json: &str) -> Result<RouteFollowerBuilder, serde_json::Error> {
+        serde_json::from_str(json).map(Self::builder)
+    }
+}
+
+impl<S: StopPosition> RouteFollower<S> {
+    /// Creates a new route follower builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `stops` - Stops of the route in driving order
+    pub fn builder(stops: Vec<S>) -> RouteFollowerBuilder<S> {
+        RouteFollowerBuilder {
+            stops,
+            approach_distance: 150.0,
+            stop_window: 25.0,
+            standstill_speed: 0.5,
+        }
+    }
+
+    /// Follows the vehicle along the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Current speed in m/s
+    /// * `distance` - Travelled distance in m, e.g. from the odometry
+    /// * `door_target` - Door command of the driver
+    /// * `doors_closed` - Whether all doors are closed
+    pub fn tick(&mut self, speed: f32, distance: f64, door_target: DoorTarget, doors_closed: bool) {
+        // Positionen zählen ab dem Standort beim ersten Aufruf
+        let origin = *self.origin.get_or_insert(distance);
+        self.position = distance - origin;
+
+        let release = door_target >= DoorTarget::Release;
+        let standing = speed.abs() < self.standstill_speed;
+
+        match self.current {
+            Some(index) if !standing && doors_closed => {
+                self.events.push(RouteEvent::Departed(index));
+                self.current = None;
+            }
+            None if release && !self.release_last && standing => {
+                if let Some(index) = self.find_stop() {
+                    self.arrive(index);
+                }
+            }
+            _ => {}
+        }
+        self.release_last = release;
+
+        // Ohne Türfreigabe durchfahrene Haltestellen überspringen
+        while self.current.is_none()
+            && self
+                .stops
+                .get(self.next)
+                .and_then(|stop| stop.position_m())
+                .is_some_and(|pos| self.position > pos + self.stop_window)
+        {
+            self.events.push(RouteEvent::Passed(self.next));
+            self.next += 1;
+            self.approach_sent = false;
+        }
+
+        self.distance_to_next = self
+            .stops
+            .get(self.next)
+            .and_then(|stop| stop.position_m())
+            .map(|pos| pos - self.position);
+
+        self.approaching = self.current.is_none()
+            && self
+                .distance_to_next
+                .is_some_and(|d| d <= self.approach_distance);
+        if self.approaching && !self.approach_sent {
+            self.events.push(RouteEvent::Approaching(self.next));
+            self.approach_sent = true;
+        }
+    }
+
+    /// Returns the events emitted since the last call.
+    pub fn take_events(&mut self) -> Vec<RouteEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// The stop the vehicle is standing at.
+    pub fn current_stop(&self) -> Option<&S> {
+        self.current.map(|index| &self.stops[index])
+    }
+
+    /// The next stop, while standing at a stop the following one.
+    pub fn next_stop(&self) -> Option<&S> {
+        self.stops.get(self.next)
+    }
+
+    /// All stops of the route.
+    pub fn stops(&self) -> &[S] {
+        &self.stops
+    }
+
+    /// Restarts the route at the first stop from the current position.
+    pub fn restart(&mut self) {
+        self.origin = None;
+        self.release_last = false;
+        self.current = None;
+        self.next = 0;
+        self.approach_sent = false;
+        self.events.clear();
+    }
+
+    /// The first upcoming stop within the window around the position
+    fn find_stop(&self) -> Option<usize> {
+        self.stops
+            .iter()
+            .enumerate()
+            .skip(self.next)
+            .find(|(_, stop)| {
+                stop.position_m()
+                    .is_none_or(|pos| (pos - self.position).abs() <= self.stop_window)
+            })
+            .map(|(index, _)| index)
+    }
+
+    fn arrive(&mut self, index: usize) {
+        for skipped in self.next..index {
+            self.events.push(RouteEvent::Passed(skipped));
+        }
+        self.events.push(RouteEvent::Arrived(index));
+
+        self.current = Some(index);
+        self.next = index + 1;
+        self.approach_sent = false;
+    }
+}
@@ -0,0 +1,264 @@
+//! # Scorecard
+//!
+//! Evaluation of a run ("Fahrtbewertung"). The [`Scorecard`] observes the
+//! driving and rates:
+//!
+//! - Jerk: time during which the change of acceleration exceeds a limit
+//! - Harsh braking: decelerations above a threshold
+//! - Door dwell times: stops at which the doors stay open too long
+//! - Schedule adherence: departures outside the punctuality tolerance
+//!
+//! [`Scorecard::report`] summarizes the run in a [`ScoreReport`], which
+//! scenario scripts or displays can read directly or as JSON.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut scorecard = Scorecard::builder()
+//!     .jerk_limit(1.0)
+//!     .harsh_braking(1.5)
+//!     .max_dwell(30.0)
+//!     .tolerance(-30.0, 120.0)
+//!     .build();
+//!
+//! // In the main loop:
+//! scorecard.tick(v_ground(), doors.doors_closed);
+//! if departed {
+//!     scorecard.departure(timetable.delay());
+//! }
+//!
+//! set_var("Score_Report", scorecard.report().to_json());
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{api::general::delta, elements::std::filter::LowPass};
+
+/// Penalty points per second of excessive jerk
+const PENALTY_JERK: f32 = 1.0;
+/// Penalty points per harsh braking
+const PENALTY_HARSH_BRAKING: f32 = 5.0;
+/// Penalty points per stop with too long dwell time
+const PENALTY_DWELL: f32 = 2.0;
+/// Penalty points per departure outside the tolerance
+const PENALTY_SCHEDULE: f32 = 3.0;
+
+/// Summary of a run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreReport {
+    /// Duration of the run in seconds
+    pub duration: f32,
+    /// Distance of the run in km
+    pub distance_km: f32,
+    /// Highest jerk in m/s³
+    pub max_jerk: f32,
+    /// Time in seconds during which the jerk exceeded the limit
+    pub jerk_time: f32,
+    /// Highest deceleration in m/s²
+    pub max_deceleration: f32,
+    /// Number of harsh brakings
+    pub harsh_brakings: u32,
+    /// Dwell times of all stops in seconds
+    pub dwell_times: Vec<f32>,
+    /// Number of stops with too long dwell time
+    pub long_dwells: u32,
+    /// Delays of all departures in seconds, positive when late
+    pub delays: Vec<f32>,
+    /// Number of departures within the tolerance
+    pub punctual_departures: u32,
+    /// Total score from 0.0 to 100.0
+    pub score: f32,
+}
+
+impl ScoreReport {
+    /// Mean dwell time in seconds, 0.0 without stops.
+    pub fn mean_dwell(&self) -> f32 {
+        mean(&self.dwell_times)
+    }
+
+    /// Mean delay in seconds, 0.0 without departures.
+    pub fn mean_delay(&self) -> f32 {
+        mean(&self.delays)
+    }
+
+    /// The report as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+fn mean(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f32>() / values.len() as f32
+    }
+}
+
+pub struct ScorecardBuilder {
+    jerk_limit: f32,
+    harsh_braking: f32,
+    max_dwell: f32,
+    tolerance: (f32, f32),
+}
+
+impl ScorecardBuilder {
+    /// Jerk in m/s³ above which the ride counts as uncomfortable.
+    pub fn jerk_limit(mut self, jerk_limit: f32) -> Self {
+        self.jerk_limit = jerk_limit;
+        self
+    }
+
+    /// Deceleration in m/s² above which a braking counts as harsh.
+    pub fn harsh_braking(mut self, deceleration: f32) -> Self {
+        self.harsh_braking = deceleration;
+        self
+    }
+
+    /// Dwell time in seconds above which a stop counts as too long.
+    pub fn max_dwell(mut self, max_dwell: f32) -> Self {
+        self.max_dwell = max_dwell;
+        self
+    }
+
+    /// Punctuality tolerance in seconds, e.g. -30.0 (early) and 120.0 (late).
+    pub fn tolerance(mut self, early: f32, late: f32) -> Self {
+        self.tolerance = (early, late);
+        self
+    }
+
+    pub fn build(self) -> Scorecard {
+        Scorecard {
+            jerk_limit: self.jerk_limit,
+            harsh_braking: self.harsh_braking,
+            max_dwell: self.max_dwell,
+            tolerance: self.tolerance,
+            accel_filter: LowPass::new(0.3, 0.0),
+            jerk_filter: LowPass::new(0.3, 0.0),
+            speed_last: None,
+            accel_last: 0.0,
+            braking_harsh: false,
+            dwell: None,
+            report: ScoreReport::default(),
+        }
+    }
+}
+
+/// Evaluation of jerk, braking, dwell times and punctuality during a run.
+pub struct Scorecard {
+    jerk_limit: f32,
+    harsh_braking: f32,
+    max_dwell: f32,
+    tolerance: (f32, f32),
+
+    accel_filter: LowPass,
+    jerk_filter: LowPass,
+    speed_last: Option<f32>,
+    accel_last: f32,
+    braking_harsh: bool,
+    dwell: Option<f32>,
+
+    report: ScoreReport,
+}
+
+impl Scorecard {
+    /// Creates a new scorecard builder.
+    pub fn builder() -> ScorecardBuilder {
+        ScorecardBuilder {
+            jerk_limit: 1.5,
+            harsh_braking: 1.5,
+            max_dwell: 40.0,
+            tolerance: (-60.0, 180.0),
+        }
+    }
+
+    /// Observes the driving.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Current speed in m/s
+    /// * `doors_closed` - Whether all doors of the vehicle are closed
+    pub fn tick(&mut self, speed: f32, doors_closed: bool) {
+        let dt = delta();
+        if dt <= 0.0 {
+            return;
+        }
+
+        self.report.duration += dt;
+        self.report.distance_km += speed.abs() * dt / 1000.0;
+
+        // Beschleunigung und Ruck geglättet, damit Rauschen nicht als Ruck zählt
+        let v = speed.abs();
+        let raw_accel = self.speed_last.map_or(0.0, |last| (v - last) / dt);
+        self.speed_last = Some(v);
+
+        let accel = self.accel_filter.update(raw_accel, dt);
+        let jerk = self
+            .jerk_filter
+            .update((accel - self.accel_last) / dt, dt)
+            .abs();
+        self.accel_last = accel;
+
+        self.report.max_jerk = self.report.max_jerk.max(jerk);
+        if jerk > self.jerk_limit {
+            self.report.jerk_time += dt;
+        }
+
+        let deceleration = -accel;
+        self.report.max_deceleration = self.report.max_deceleration.max(deceleration);
+        let harsh = deceleration > self.harsh_braking;
+        if harsh && !self.braking_harsh {
+            self.report.harsh_brakings += 1;
+        }
+        self.braking_harsh = harsh;
+
+        match (&mut self.dwell, doors_closed) {
+            (None, false) => self.dwell = Some(0.0),
+            (Some(dwell), false) => *dwell += dt,
+            (Some(dwell), true) => {
+                let dwell = *dwell;
+                self.report.dwell_times.push(dwell);
+                if dwell > self.max_dwell {
+                    self.report.long_dwells += 1;
+                }
+                self.dwell = None;
+            }
+            (None, true) => {}
+        }
+    }
+
+    /// Records the departure from a stop.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - Delay against the timetable in seconds, negative when early
+    pub fn departure(&mut self, delay: f32) {
+        self.report.delays.push(delay);
+
+        let (early, late) = self.tolerance;
+        if (early..=late).contains(&delay) {
+            self.report.punctual_departures += 1;
+        }
+    }
+
+    /// Summarizes the run so far.
+    pub fn report(&self) -> ScoreReport {
+        let mut report = self.report.clone();
+
+        let late_departures = report.delays.len() as u32 - report.punctual_departures;
+        let penalty = PENALTY_JERK * report.jerk_time
+            + PENALTY_HARSH_BRAKING * report.harsh_brakings as f32
+            + PENALTY_DWELL * report.long_dwells as f32
+            + PENALTY_SCHEDULE * late_departures as f32;
+        report.score = (100.0 - penalty).max(0.0);
+
+        report
+    }
+
+    /// Starts a new run.
+    pub fn reset(&mut self) {
+        self.report = ScoreReport::default();
+        self.dwell = None;
+        self.braking_harsh = false;
+    }
+}
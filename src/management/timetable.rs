@@ -0,0 +1,311 @@
+//! # Timetable
+//!
+//! The [`Timetable`] holds the stop list of a course with the scheduled
+//! arrival and departure times and follows the vehicle along it:
+//!
+//! - Releasing the doors at standstill near the next stop counts as arrival;
+//!   the position is taken from the [`Odometry`](super::odometry::Odometry)
+//! - Starting off with closed doors counts as departure
+//! - Stops which are passed without door release are marked as skipped
+//!
+//! The stops are followed with a [`RouteFollower`], so the timetable detects
+//! arrivals and departures exactly like the route.
+//!
+//! From the actual and scheduled times the delay is computed. It is sent with
+//! the current and next stop as [`TimetableInfo`] to the IBIS displays and the
+//! announcements.
+//!
+//! Times are given in seconds since midnight, positions in m from the first
+//! stop.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut timetable = Timetable::from_json(r#"[
+//!     { "name": "Hauptbahnhof", "position_m": 0.0, "departure": 30600.0 },
+//!     { "name": "Marktplatz", "position_m": 850.0, "arrival": 30720.0, "departure": 30750.0 },
+//!     { "name": "Rathaus", "position_m": 1400.0, "arrival": 30840.0 }
+//! ]"#)
+//! .expect("invalid timetable")
+//! .stop_window(30.0)
+//! .build();
+//!
+//! // In the main loop:
+//! timetable.tick(day_time, v_ground(), odometry.distance, door_target, doors.doors_closed);
+//! info.next_stop = timetable.next_stop().map(|s| s.name.clone()).unwrap_or_default();
+//! ```
+
+use lotus_script::message::{send_message, MessageTarget};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    management::{
+        enums::door_enums::DoorTarget,
+        route::{RouteEvent, RouteFollower, RouteFollowerBuilder, StopPosition},
+    },
+    messages::ibis_messages::TimetableInfo,
+};
+
+/// Seconds of a day
+const DAY: f32 = 86_400.0;
+
+/// Stop of the timetable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimetableStop {
+    /// Name of the stop
+    pub name: String,
+    /// Position in m from the first stop, without a position every door
+    /// release counts as arrival
+    #[serde(default)]
+    pub position_m: Option<f64>,
+    /// Scheduled arrival in seconds since midnight
+    #[serde(default)]
+    pub arrival: Option<f32>,
+    /// Scheduled departure in seconds since midnight
+    #[serde(default)]
+    pub departure: Option<f32>,
+}
+
+impl TimetableStop {
+    /// Creates a new stop.
+    pub fn new(
+        name: impl Into<String>,
+        position_m: Option<f64>,
+        arrival: Option<f32>,
+        departure: Option<f32>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            position_m,
+            arrival,
+            departure,
+        }
+    }
+}
+
+impl StopPosition for TimetableStop {
+    fn position_m(&self) -> Option<f64> {
+        self.position_m
+    }
+}
+
+/// Actual times at a stop.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StopRecord {
+    /// Actual arrival in seconds since midnight
+    pub arrival: Option<f32>,
+    /// Actual departure in seconds since midnight
+    pub departure: Option<f32>,
+    /// Whether the stop was passed without door release
+    pub skipped: bool,
+}
+
+pub struct TimetableBuilder {
+    route: RouteFollowerBuilder<TimetableStop>,
+}
+
+impl TimetableBuilder {
+    /// Distance in m around the stop position within which a door release
+    /// counts as arrival.
+    pub fn stop_window(mut self, stop_window: f64) -> Self {
+        self.route = self.route.stop_window(stop_window);
+        self
+    }
+
+    /// Speed in m/s above which the vehicle has departed.
+    pub fn departure_speed(mut self, speed: f32) -> Self {
+        self.route = self.route.standstill_speed(speed);
+        self
+    }
+
+    pub fn build(self) -> Timetable {
+        let route = self.route.build();
+        let records = vec![StopRecord::default(); route.stops().len()];
+        Timetable {
+            route,
+            info_last: None,
+            records,
+            next: 0,
+            at_stop: None,
+            delay: 0.0,
+        }
+    }
+}
+
+/// Stop list with tracking of the actual times and the delay.
+pub struct Timetable {
+    route: RouteFollower<TimetableStop>,
+    info_last: Option<TimetableInfo>,
+
+    /// Actual times, one record per stop
+    pub records: Vec<StopRecord>,
+    /// Index of the next stop to be served
+    pub next: usize,
+    /// Index of the stop the vehicle is standing at
+    pub at_stop: Option<usize>,
+    /// Delay in seconds, negative when early
+    pub delay: f32,
+}
+
+impl Timetable {
+    /// Creates a new timetable builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `stops` - Stops of the course in driving order
+    pub fn builder(stops: Vec<TimetableStop>) -> TimetableBuilder {
+        TimetableBuilder {
+            route: RouteFollower::builder(stops).standstill_speed(1.0),
+        }
+    }
+
+    /// Creates a new timetable builder from a JSON array of stops.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parser error if the string is no valid stop list.
+    pub fn from_json(json: &str) -> Result<TimetableBuilder, serde_json::Error> {
+        serde_json::from_str(json).map(Self::builder)
+    }
+
+    /// Follows the vehicle along the stop list.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Current time in seconds since midnight
+    /// * `speed` - Current speed in m/s
+    /// * `distance` - Travelled distance in m, e.g. from the odometry
+    /// * `door_target` - Door command of the driver
+    /// * `doors_closed` - Whether all doors are closed
+    pub fn tick(
+        &mut self,
+        time: f32,
+        speed: f32,
+        distance: f64,
+        door_target: DoorTarget,
+        doors_closed: bool,
+    ) {
+        self.route.tick(speed, distance, door_target, doors_closed);
+        for event in self.route.take_events() {
+            match event {
+                RouteEvent::Arrived(index) => {
+                    self.records[index].arrival = Some(time);
+                    if let Some(arrival) = self.route.stops()[index].arrival {
+                        self.delay = time_diff(time, arrival);
+                    }
+                }
+                RouteEvent::Departed(index) => {
+                    self.records[index].departure = Some(time);
+                    if let Some(departure) = self.route.stops()[index].departure {
+                        self.delay = time_diff(time, departure);
+                    }
+                }
+                RouteEvent::Passed(index) => self.records[index].skipped = true,
+                RouteEvent::Approaching(_) => {}
+            }
+        }
+        self.at_stop = self.route.current;
+        self.next = self.route.next;
+
+        // Verspätung wächst, sobald der nächste Planzeitpunkt überschritten ist
+        let scheduled = match self.route.current_stop() {
+            Some(stop) => stop.departure,
+            None => self
+                .route
+                .next_stop()
+                .and_then(|stop| stop.arrival.or(stop.departure)),
+        };
+        if let Some(scheduled) = scheduled {
+            self.delay = self.delay.max(time_diff(time, scheduled));
+        }
+
+        self.send_info();
+    }
+
+    /// The stop the vehicle is standing at.
+    pub fn current_stop(&self) -> Option<&TimetableStop> {
+        self.route.current_stop()
+    }
+
+    /// The next stop to be served, while standing at a stop the following one.
+    pub fn next_stop(&self) -> Option<&TimetableStop> {
+        self.route.next_stop()
+    }
+
+    /// All stops of the timetable.
+    pub fn stops(&self) -> &[TimetableStop] {
+        self.route.stops()
+    }
+
+    /// Whether the last stop has been reached.
+    pub fn finished(&self) -> bool {
+        self.next >= self.stops().len()
+    }
+
+    /// Restarts the timetable at the first stop from the current position.
+    pub fn restart(&mut self) {
+        self.route.restart();
+        self.records = vec![StopRecord::default(); self.stops().len()];
+        self.next = 0;
+        self.at_stop = None;
+        self.delay = 0.0;
+    }
+
+    fn send_info(&mut self) {
+        let info = TimetableInfo {
+            current_stop: self.current_stop().map(|stop| stop.name.clone()),
+            next_stop: self.next_stop().map(|stop| stop.name.clone()),
+            delay: self.delay.round() as i32,
+        };
+
+        if self.info_last.as_ref() != Some(&info) {
+            send_message(
+                &info,
+                [MessageTarget::Broadcast {
+                    across_couplings: true,
+                    include_self: true,
+                }],
+            );
+            self.info_last = Some(info);
+        }
+    }
+}
+
+/// Difference of two times of day, correct across midnight
+fn time_diff(actual: f32, scheduled: f32) -> f32 {
+    (actual - scheduled + DAY / 2.0).rem_euclid(DAY) - DAY / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_course_with_skipped_stop() {
+        let mut timetable = Timetable::builder(vec![
+            TimetableStop::new("A", Some(0.0), None, Some(100.0)),
+            TimetableStop::new("B", Some(500.0), Some(200.0), Some(230.0)),
+            TimetableStop::new("C", Some(1000.0), Some(300.0), None),
+        ])
+        .build();
+
+        timetable.tick(90.0, 0.0, 0.0, DoorTarget::Release, false);
+        assert_eq!(timetable.at_stop, Some(0));
+
+        // Abfahrt erst mit geschlossenen Türen
+        timetable.tick(100.0, 5.0, 10.0, DoorTarget::Close, false);
+        assert_eq!(timetable.at_stop, Some(0));
+        timetable.tick(101.0, 5.0, 12.0, DoorTarget::Close, true);
+        assert_eq!(timetable.at_stop, None);
+        assert_eq!(timetable.records[0].departure, Some(101.0));
+
+        timetable.tick(150.0, 10.0, 560.0, DoorTarget::Close, true);
+        assert!(timetable.records[1].skipped);
+        assert_eq!(timetable.next, 2);
+
+        timetable.tick(310.0, 0.0, 1000.0, DoorTarget::Release, false);
+        assert_eq!(timetable.at_stop, Some(2));
+        assert_eq!(timetable.delay, 10.0);
+        assert!(timetable.finished());
+    }
+}
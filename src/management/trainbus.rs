@@ -6,6 +6,19 @@ use lotus_script::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::api::{
+    coupler::ApiCoupler,
+    general::delta,
+    random::gen_f32,
+    variable::{get_var, set_var},
+};
+
+/// Number of faults kept per periphery device in the diagnostics
+const FAULT_HISTORY_LEN: usize = 16;
+
+/// Interval in seconds in which the periphery sends its heartbeat
+const HEARTBEAT_INTERVAL: f32 = 1.0;
+
 //===================================================================
 // TrainBus coupling condition
 //===================================================================
@@ -80,9 +93,34 @@ impl EcouplerSender {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct InternTelegram {
     pub master_pos: Option<u32>,
+    /// Claim of the master at `master_pos`, used for the arbitration between several masters
+    #[serde(default)]
+    pub master_claim: Option<MasterClaim>,
     pub vehicle_config: Vec<VehicleConfig>,
 }
 
+/// Claim of a master in the arbitration between several masters in the train
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MasterClaim {
+    /// Vehicle number of the master
+    pub number: String,
+    /// Drawn at start-up, decides between vehicles with the same number
+    pub key: u32,
+}
+
+impl MasterClaim {
+    /// Order of the claim in the arbitration, the lowest wins: vehicle numbers are compared numerically,
+    /// numbers which are not numeric come last.
+    fn rank(&self) -> ((bool, u64), &str, u32) {
+        let numeric = self
+            .number
+            .trim()
+            .parse::<u64>()
+            .map_or((true, 0), |n| (false, n));
+        (numeric, &self.number, self.key)
+    }
+}
+
 message_type!(InternTelegram, "Std_TrainBus_Intern", "TrainBusTelegram");
 
 //===================================================================
@@ -130,6 +168,17 @@ pub struct IbisState {
 
 message_type!(IbisState, "Std_TrainBus", "IbisState");
 
+///
+/// Information from the TrainBus to the Ibis that its master request was rejected, because another master
+/// with a lower vehicle number is active in the train (true), or that it is the master again (false)
+///
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MasterDemoted {
+    pub value: bool,
+}
+
+message_type!(MasterDemoted, "Std_TrainBus", "MasterDemoted");
+
 #[derive(Default, Debug)]
 pub struct IbisStateSender {
     is_master_last: bool,
@@ -189,6 +238,30 @@ impl PeripheryKind {
             (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
         }
     }
+
+    /// Abbreviation of the kind as shown on the IBIS terminal
+    pub fn short_name(&self) -> String {
+        match self {
+            PeripheryKind::MainIbis => "MAS",
+            PeripheryKind::TrainBusModul => "ZB",
+            PeripheryKind::Redbox => "KWR",
+            PeripheryKind::InductivTransmissionModul => "IMU",
+            PeripheryKind::RadioModul => "FUM",
+            PeripheryKind::AnnouncementModul => "ANS",
+            PeripheryKind::DisplayGeneral
+            | PeripheryKind::DisplayOuter
+            | PeripheryKind::DisplayInner => "ANZ",
+            PeripheryKind::Validator => "ENTW",
+            PeripheryKind::IbisTerminal => "TM",
+            PeripheryKind::TrainProtection => "INDUSI",
+            PeripheryKind::TicketMachine => "VVG",
+            PeripheryKind::Printer => "DRU",
+            PeripheryKind::Iris => "IRIS",
+            PeripheryKind::VideoSystem => "VIDEO",
+            PeripheryKind::Other { short_name, .. } => return short_name.clone(),
+        }
+        .to_string()
+    }
 }
 
 //---------------------------------------------
@@ -206,6 +279,21 @@ pub enum PeripheryFault {
     }, // Non-standard message
 }
 
+impl PeripheryFault {
+    /// Short text of the fault as shown on the IBIS terminal
+    pub fn short_text(&self) -> String {
+        match self {
+            PeripheryFault::Defect => "Defekt",
+            PeripheryFault::Disrupted => "Gestört",
+            PeripheryFault::NoAnswer => "Antw. nicht",
+            PeripheryFault::BatteryLow => "Spg. schwach",
+            PeripheryFault::Ok => "Ok",
+            PeripheryFault::Undefined { short_text, .. } => return short_text.clone(),
+        }
+        .to_string()
+    }
+}
+
 //===================================================================
 
 ///
@@ -234,6 +322,19 @@ message_type!(PeripheryFaultReport, "Std_TrainBus", "PeripheryFaultReport");
 
 //---------------------------------------------
 
+///
+/// Cyclic sign of life of the periphery including its current state.
+///
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PeripheryHeartbeat {
+    pub id: u32,
+    pub state: PeripheryFault,
+}
+
+message_type!(PeripheryHeartbeat, "Std_TrainBus", "PeripheryHeartbeat");
+
+//---------------------------------------------
+
 ///
 /// The periphery transmits information about its functional status.
 ///
@@ -252,6 +353,58 @@ message_type!(IbisFaultReport, "Std_TrainBus", "PeripheryReport");
 
 //---------------------------------------------
 
+///
+/// Acknowledgement of a fault reported with `IbisFaultReport`, sent by the IBIS terminal.
+///
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IbisFaultAcknowledge {
+    pub car: u32,
+    /// Coupling of the master car over which the fault was reported, `None` for the master car
+    #[serde(default)]
+    pub coupling: Option<Coupling>,
+    pub kind: PeripheryKind,
+    pub counter: u32,
+}
+
+message_type!(IbisFaultAcknowledge, "Std_TrainBus", "FaultAcknowledge");
+
+//---------------------------------------------
+
+///
+/// The train bus passes an acknowledgement from the master towards the car of the device.
+///
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InternFaultAcknowledge {
+    pub car: u32,
+
+    pub kind: PeripheryKind,
+    pub counter: u32,
+}
+
+message_type!(
+    InternFaultAcknowledge,
+    "Std_TrainBus_Intern",
+    "FaultAcknowledge"
+);
+
+//---------------------------------------------
+
+///
+/// The train bus informs the periphery that its fault was acknowledged on the IBIS terminal.
+///
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PeripheryFaultAcknowledge {
+    pub id: u32,
+}
+
+message_type!(
+    PeripheryFaultAcknowledge,
+    "Std_TrainBus",
+    "PeripheryFaultAcknowledge"
+);
+
+//---------------------------------------------
+
 ///
 /// The train bus sends error messages about the train bus in the direction of the master.
 ///
@@ -296,6 +449,23 @@ pub struct PeripheryElement {
     counter: u32,
 }
 
+///
+/// Diagnostic information of a periphery device of the own vehicle, e.g. for display on the IBIS terminal
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeripheryDiagnostics {
+    pub id: u32,
+    pub kind: PeripheryKind,
+    /// Address (counter) of the device within its kind
+    pub adress: u32,
+    /// Current state, `Ok` if the device never reported a fault
+    pub state: PeripheryFault,
+    /// Reported faults, oldest first
+    pub fault_history: Vec<PeripheryFault>,
+    /// Whether the address had to be changed because of an address conflict
+    pub adress_conflict: bool,
+}
+
 //===================================================================
 // TrainBus
 //===================================================================
@@ -306,18 +476,28 @@ pub struct TrainBusManager {
     my_vehicle_config: VehicleConfig,
     //my_perifery_list: Vec<PeripheryElement>,
     my_perifery_faults: HashMap<u32, PeripheryFault>,
+    my_fault_history: HashMap<u32, Vec<PeripheryFault>>,
+    my_adress_conflicts: Vec<u32>,
+    adress_map_var: Option<String>,
+
+    heartbeat_timeout: f32,
+    heartbeat_timers: HashMap<u32, f32>,
 
     //veh_number: String,
     veh_config_list_received: (Vec<VehicleConfig>, Vec<VehicleConfig>),
     master_pos_received: (Option<u32>, Option<u32>),
+    master_claim_received: (Option<MasterClaim>, Option<MasterClaim>),
 
     veh_config_list_last_send: (Vec<VehicleConfig>, Vec<VehicleConfig>),
     master_pos_last_send: (Option<u32>, Option<u32>),
+    master_claim_last_send: (Option<MasterClaim>, Option<MasterClaim>),
 
     veh_config_list_last_local: (Vec<VehicleConfig>, Vec<VehicleConfig>),
     veh_config_self_last_local: VehicleConfig,
     master_pos_last_local: bool,
     am_i_master: bool,
+    master_demoted: bool,
+    arbitration_key: u32,
 
     train_bus_error: bool,
 
@@ -334,13 +514,21 @@ impl TrainBusManager {
             },
             //my_perifery_list: Vec::new(),
             my_perifery_faults: HashMap::new(),
+            my_fault_history: HashMap::new(),
+            my_adress_conflicts: Vec::new(),
+            adress_map_var: None,
+
+            heartbeat_timeout: 3.0,
+            heartbeat_timers: HashMap::new(),
 
             //veh_number,
             veh_config_list_received: (Vec::new(), Vec::new()),
             master_pos_received: (None, None),
+            master_claim_received: (None, None),
 
             veh_config_list_last_send: (Vec::new(), Vec::new()),
             master_pos_last_send: (None, None),
+            master_claim_last_send: (None, None),
 
             veh_config_list_last_local: (Vec::new(), Vec::new()),
             veh_config_self_last_local: VehicleConfig {
@@ -349,6 +537,8 @@ impl TrainBusManager {
             },
             master_pos_last_local: false,
             am_i_master: false,
+            master_demoted: false,
+            arbitration_key: (gen_f32(0.0..1.0) * u32::MAX as f32) as u32,
 
             train_bus_error: false,
 
@@ -356,6 +546,91 @@ impl TrainBusManager {
         }
     }
 
+    /// Stores the assigned periphery addresses in a script variable, so the addresses stay the same
+    /// in the next session. Stored addresses take precedence over the addresses passed to `new`.
+    pub fn persist_adresses(mut self, var_name: impl Into<String>) -> Self {
+        let var_name = var_name.into();
+
+        let stored = get_var::<String>(&var_name);
+        if let Ok(adress_map) = serde_json::from_str::<HashMap<u32, u32>>(&stored) {
+            self.my_adress_map.extend(adress_map);
+        }
+
+        self.adress_map_var = Some(var_name);
+        self
+    }
+
+    /// Time in seconds without heartbeat after which a device is reported with `PeripheryFault::NoAnswer`
+    pub fn heartbeat_timeout(mut self, timeout: f32) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Supervises the heartbeats of the periphery. Only devices which have sent at least one heartbeat
+    /// are supervised, so devices without heartbeat keep working with self-reported faults only.
+    pub fn tick(&mut self) {
+        let mut timed_out = Vec::new();
+
+        for (id, timer) in self.heartbeat_timers.iter_mut() {
+            if *timer > 0.0 {
+                *timer -= delta();
+                if *timer <= 0.0 {
+                    timed_out.push(*id);
+                }
+            }
+        }
+
+        timed_out.sort();
+        for id in timed_out {
+            self.set_fault(id, PeripheryFault::NoAnswer);
+        }
+    }
+
+    /// Diagnostics of all periphery devices of the own vehicle, sorted by ID
+    pub fn diagnostics(&self) -> Vec<PeripheryDiagnostics> {
+        let mut list: Vec<_> = self
+            .my_vehicle_config
+            .periphery
+            .iter()
+            .map(|pe| PeripheryDiagnostics {
+                id: pe.id,
+                kind: pe.kind.clone(),
+                adress: pe.counter,
+                state: self
+                    .my_perifery_faults
+                    .get(&pe.id)
+                    .cloned()
+                    .unwrap_or(PeripheryFault::Ok),
+                fault_history: self
+                    .my_fault_history
+                    .get(&pe.id)
+                    .cloned()
+                    .unwrap_or_default(),
+                adress_conflict: self.my_adress_conflicts.contains(&pe.id),
+            })
+            .collect();
+
+        list.sort_by_key(|d| d.id);
+        list
+    }
+
+    /// Diagnostics of a single periphery device of the own vehicle
+    pub fn diagnostics_of(&self, id: u32) -> Option<PeripheryDiagnostics> {
+        self.diagnostics().into_iter().find(|d| d.id == id)
+    }
+
+    fn store_adresses(&mut self) {
+        for pe in &self.my_vehicle_config.periphery {
+            self.my_adress_map.insert(pe.id, pe.counter);
+        }
+
+        if let Some(var_name) = &self.adress_map_var {
+            if let Ok(json) = serde_json::to_string(&self.my_adress_map) {
+                set_var(var_name, json);
+            }
+        }
+    }
+
     fn find_adress(&mut self, id: &u32, kind: &PeripheryKind) -> u32 {
         // Check whether an address is stored for the ID in my_address_map
         if let Some(&adress) = self.my_adress_map.get(id) {
@@ -376,6 +651,11 @@ impl TrainBusManager {
                     if !is_taken {
                         // Assign the new address to the conflicting element
                         self.my_vehicle_config.periphery[idx].counter = new_adress;
+
+                        let conflict_id = self.my_vehicle_config.periphery[idx].id;
+                        if !self.my_adress_conflicts.contains(&conflict_id) {
+                            self.my_adress_conflicts.push(conflict_id);
+                        }
                         break;
                     }
                     new_adress += 1;
@@ -403,6 +683,8 @@ impl TrainBusManager {
     }
 
     fn update(&mut self) {
+        self.update_master_arbitration();
+
         let mut new_veh_config_front = Vec::new();
         new_veh_config_front.extend(self.veh_config_list_received.1.clone());
         new_veh_config_front.push(self.my_vehicle_config.clone());
@@ -411,27 +693,22 @@ impl TrainBusManager {
         new_veh_config_rear.extend(self.veh_config_list_received.0.clone());
         new_veh_config_rear.push(self.my_vehicle_config.clone());
 
-        let new_master_pos_front = if self.am_i_master {
-            Some(1)
-        } else {
-            self.master_pos_received.1.map(|s| s + 1)
-        };
-        let new_master_pos_rear = if self.am_i_master {
-            Some(1)
-        } else {
-            self.master_pos_received.0.map(|s| s + 1)
-        };
+        let (new_master_pos_front, new_master_claim_front) = self.claim_towards(Coupling::Front);
+        let (new_master_pos_rear, new_master_claim_rear) = self.claim_towards(Coupling::Rear);
 
         if (new_veh_config_front != self.veh_config_list_last_send.0
-            || new_master_pos_front != self.master_pos_last_send.0)
+            || new_master_pos_front != self.master_pos_last_send.0
+            || new_master_claim_front != self.master_claim_last_send.0)
             && self.is_coupled.0
         {
             self.veh_config_list_last_send.0 = new_veh_config_front.clone();
             self.master_pos_last_send.0 = new_master_pos_front;
+            self.master_claim_last_send.0 = new_master_claim_front.clone();
 
             send_message(
                 &InternTelegram {
                     master_pos: new_master_pos_front,
+                    master_claim: new_master_claim_front,
                     vehicle_config: new_veh_config_front,
                 },
                 [MessageTarget::AcrossCoupling {
@@ -442,15 +719,18 @@ impl TrainBusManager {
         }
 
         if (new_veh_config_rear != self.veh_config_list_last_send.1
-            || new_master_pos_rear != self.master_pos_last_send.1)
+            || new_master_pos_rear != self.master_pos_last_send.1
+            || new_master_claim_rear != self.master_claim_last_send.1)
             && self.is_coupled.1
         {
             self.veh_config_list_last_send.1 = new_veh_config_rear.clone();
             self.master_pos_last_send.1 = new_master_pos_rear;
+            self.master_claim_last_send.1 = new_master_claim_rear.clone();
 
             send_message(
                 &InternTelegram {
                     master_pos: new_master_pos_rear,
+                    master_claim: new_master_claim_rear,
                     vehicle_config: new_veh_config_rear,
                 },
                 [MessageTarget::AcrossCoupling {
@@ -512,7 +792,7 @@ impl TrainBusManager {
         }
 
         // Is a master active? Determine value and propagate on change
-        let new_is_a_master = if self.am_i_master {
+        let new_is_a_master = if self.is_acting_master() {
             Some(0)
         } else if self.master_pos_received.0.is_some() {
             let value = self.master_pos_received.0.unwrap();
@@ -542,18 +822,7 @@ impl TrainBusManager {
     pub fn on_message(&mut self, msg: Message) {
         msg.handle::<InternTelegram>(|m| {
             if let Some(coupler) = msg.source().coupling {
-                match coupler {
-                    Coupling::Front => {
-                        self.veh_config_list_received.0 = m.vehicle_config;
-                        self.master_pos_received.0 = m.master_pos;
-                        self.update();
-                    }
-                    Coupling::Rear => {
-                        self.veh_config_list_received.1 = m.vehicle_config;
-                        self.master_pos_received.1 = m.master_pos;
-                        self.update();
-                    }
-                }
+                self.receive_telegram(coupler, m);
             }
 
             Ok(())
@@ -576,6 +845,7 @@ impl TrainBusManager {
                         if !m.value {
                             self.veh_config_list_received.0 = Vec::new();
                             self.master_pos_received.0 = None;
+                            self.master_claim_received.0 = None;
                         }
                         self.update();
                     }
@@ -587,6 +857,7 @@ impl TrainBusManager {
                         if !m.value {
                             self.veh_config_list_received.1 = Vec::new();
                             self.master_pos_received.1 = None;
+                            self.master_claim_received.1 = None;
                         }
                         self.update();
                     }
@@ -608,28 +879,34 @@ impl TrainBusManager {
             let p = PeripheryElement { id, kind, counter };
 
             self.my_vehicle_config.periphery.push(p);
+            self.store_adresses();
             self.update();
             Ok(())
         })
         .expect("PeripheryRegister: message handle failed");
 
         msg.handle::<PeripheryFaultReport>(|m| {
-            if let Some(pe) = self.my_vehicle_config.find_by_id(m.id) {
-                self.my_perifery_faults.insert(m.id, m.kind.clone());
+            self.set_fault(m.id, m.kind);
+            Ok(())
+        })
+        .expect("PeripheryFaultReport: message handle failed");
+
+        msg.handle::<PeripheryHeartbeat>(|m| {
+            if self.my_vehicle_config.find_by_id(m.id).is_some() {
+                self.heartbeat_timers.insert(m.id, self.heartbeat_timeout);
 
-                // Send directly to master
-                if self.is_master_there() {
-                    self.send_fault_to_master(1, pe.kind.clone(), pe.counter, m.kind);
+                // Device answers again: restore its own state
+                if self.my_perifery_faults.get(&m.id) == Some(&PeripheryFault::NoAnswer) {
+                    self.set_fault(m.id, m.state);
                 }
             }
-
             Ok(())
         })
-        .expect("PeripheryFaultReport: message handle failed");
+        .expect("PeripheryHeartbeat: message handle failed");
 
         msg.handle::<InternFaultReport>(|m| {
             if let Some(side) = msg.source().coupling {
-                if self.am_i_master {
+                if self.is_acting_master() {
                     send_message(
                         &IbisFaultReport {
                             car: m.car + 1,
@@ -680,6 +957,98 @@ impl TrainBusManager {
             Ok(())
         })
         .expect("InternFaultReport: message handle failed");
+
+        msg.handle::<IbisFaultAcknowledge>(|m| {
+            if self.is_acting_master() {
+                match m.coupling {
+                    Some(coupling) if m.car > 1 => {
+                        self.forward_acknowledge(m.car - 1, m.kind, m.counter, coupling)
+                    }
+                    _ => self.acknowledge_local(&m.kind, m.counter),
+                }
+            }
+            Ok(())
+        })
+        .expect("IbisFaultAcknowledge: message handle failed");
+
+        msg.handle::<InternFaultAcknowledge>(|m| {
+            if let Some(side) = msg.source().coupling {
+                if m.car > 1 {
+                    // Weiter in Richtung vom Master weg
+                    let away = match side {
+                        Coupling::Front => Coupling::Rear,
+                        Coupling::Rear => Coupling::Front,
+                    };
+                    self.forward_acknowledge(m.car - 1, m.kind, m.counter, away);
+                } else {
+                    self.acknowledge_local(&m.kind, m.counter);
+                }
+            }
+            Ok(())
+        })
+        .expect("InternFaultAcknowledge: message handle failed");
+    }
+
+    /// Passes an acknowledgement over the coupler, `car` counted from the next car
+    fn forward_acknowledge(&self, car: u32, kind: PeripheryKind, counter: u32, coupling: Coupling) {
+        send_message(
+            &InternFaultAcknowledge { car, kind, counter },
+            MessageTarget::AcrossCoupling {
+                coupling,
+                cascade: false,
+            },
+        );
+    }
+
+    /// Informs the own device of the acknowledgement
+    fn acknowledge_local(&self, kind: &PeripheryKind, counter: u32) {
+        if let Some(pe) = self
+            .my_vehicle_config
+            .periphery
+            .iter()
+            .find(|pe| pe.counter == counter && pe.kind.is_same_kind(kind))
+        {
+            send_message(
+                &PeripheryFaultAcknowledge { id: pe.id },
+                MessageTarget::Broadcast {
+                    across_couplings: false,
+                    include_self: true,
+                },
+            );
+        }
+    }
+
+    fn receive_telegram(&mut self, coupler: Coupling, m: InternTelegram) {
+        match coupler {
+            Coupling::Front => {
+                self.veh_config_list_received.0 = m.vehicle_config;
+                self.master_pos_received.0 = m.master_pos;
+                self.master_claim_received.0 = m.master_claim;
+            }
+            Coupling::Rear => {
+                self.veh_config_list_received.1 = m.vehicle_config;
+                self.master_pos_received.1 = m.master_pos;
+                self.master_claim_received.1 = m.master_claim;
+            }
+        }
+        self.update();
+    }
+
+    fn set_fault(&mut self, id: u32, fault: PeripheryFault) {
+        if let Some(pe) = self.my_vehicle_config.find_by_id(id) {
+            self.my_perifery_faults.insert(id, fault.clone());
+
+            let history = self.my_fault_history.entry(id).or_default();
+            history.push(fault.clone());
+            if history.len() > FAULT_HISTORY_LEN {
+                history.remove(0);
+            }
+
+            // Send directly to master
+            if self.is_master_there() {
+                self.send_fault_to_master(1, pe.kind.clone(), pe.counter, fault);
+            }
+        }
     }
 
     fn send_fault_to_master(
@@ -689,7 +1058,7 @@ impl TrainBusManager {
         counter: u32,
         state: PeripheryFault,
     ) {
-        if self.am_i_master {
+        if self.is_acting_master() {
             send_message(
                 &IbisFaultReport {
                     car,
@@ -703,7 +1072,7 @@ impl TrainBusManager {
                     include_self: true,
                 },
             );
-        } else if self.master_pos_received.0.is_some() {
+        } else if let Some(coupling) = self.master_side() {
             send_message(
                 &InternFaultReport {
                     car,
@@ -712,37 +1081,99 @@ impl TrainBusManager {
                     state,
                 },
                 MessageTarget::AcrossCoupling {
-                    coupling: Coupling::Front,
+                    coupling,
                     cascade: false,
                 },
             );
-        } else if self.master_pos_received.1.is_some() {
+        }
+    }
+
+    fn update_bus(&mut self, side: Coupling, state: bool) {
+        ApiCoupler::new(side).set_bus("TrainBus", state);
+    }
+
+    /// Arbitration between several masters in the train: the master with the lowest vehicle number wins, all
+    /// other masters are demoted to slaves and report the conflict to the master. The claim is the same seen
+    /// from both ends of the train, so the result does not depend on the orientation of the cars.
+    fn update_master_arbitration(&mut self) {
+        let own = Some(self.own_claim());
+        let demoted = self.am_i_master
+            && (outranks(&self.master_claim_received.0, &own)
+                || outranks(&self.master_claim_received.1, &own));
+
+        if demoted != self.master_demoted {
+            self.master_demoted = demoted;
+
             send_message(
-                &InternFaultReport {
-                    car,
-                    kind,
-                    counter,
-                    state,
-                },
-                MessageTarget::AcrossCoupling {
-                    coupling: Coupling::Rear,
-                    cascade: false,
-                },
+                &(MasterDemoted { value: demoted }),
+                [MessageTarget::Broadcast {
+                    across_couplings: false,
+                    include_self: true,
+                }],
             );
+
+            let state = if demoted {
+                PeripheryFault::Undefined {
+                    short_text: "2 MASTER".to_string(),
+                    long_text: "Zwei Master im Zug".to_string(),
+                }
+            } else {
+                PeripheryFault::Ok
+            };
+            self.send_fault_to_master(1, PeripheryKind::TrainBusModul, 1, state);
         }
     }
 
-    fn update_bus(&mut self, side: Coupling, state: bool) {
-        if state {
-            side.open_bus("TrainBus");
-        } else {
-            side.close_bus("TrainBus");
+    /// Master claim sent over the given coupler: the own Ibis if it is master, or the claim received over
+    /// the opposite coupler one car further away, whichever wins the arbitration. A demoted master keeps
+    /// claiming, so the arbitration stays stable on both sides.
+    fn claim_towards(&self, side: Coupling) -> (Option<u32>, Option<MasterClaim>) {
+        let (pos, number) = match side {
+            Coupling::Front => (self.master_pos_received.1, &self.master_claim_received.1),
+            Coupling::Rear => (self.master_pos_received.0, &self.master_claim_received.0),
+        };
+        let own = Some(self.own_claim());
+
+        match pos {
+            Some(pos) if !self.am_i_master || outranks(number, &own) => {
+                (Some(pos + 1), number.clone())
+            }
+            _ if self.am_i_master => (Some(1), own),
+            _ => (None, None),
+        }
+    }
+
+    fn own_claim(&self) -> MasterClaim {
+        MasterClaim {
+            number: self.my_vehicle_config.number.clone(),
+            key: self.arbitration_key,
+        }
+    }
+
+    /// Coupler behind which the winning master is found
+    fn master_side(&self) -> Option<Coupling> {
+        match self.master_pos_received {
+            (Some(_), Some(_)) => {
+                if outranks(&self.master_claim_received.1, &self.master_claim_received.0) {
+                    Some(Coupling::Rear)
+                } else {
+                    Some(Coupling::Front)
+                }
+            }
+            (Some(_), None) => Some(Coupling::Front),
+            (None, Some(_)) => Some(Coupling::Rear),
+            (None, None) => None,
         }
     }
 
+    /// Whether the own Ibis is master and has won the arbitration
+    fn is_acting_master(&self) -> bool {
+        self.am_i_master && !self.master_demoted
+    }
+
     fn is_master_there(&self) -> bool {
         [
-            self.am_i_master,
+            self.is_acting_master(),
             self.master_pos_received.0.is_some(),
             self.master_pos_received.1.is_some(),
         ]
@@ -770,6 +1201,16 @@ impl TrainBusManager {
     }*/
 }
 
+/// Whether the master claim `a` wins the arbitration against `b`. Missing claims come from vehicles without
+/// arbitration and never win.
+fn outranks(a: &Option<MasterClaim>, b: &Option<MasterClaim>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.rank() < b.rank(),
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
 //===================================================================
 // TrainBus - Perifery Interface
 //===================================================================
@@ -780,6 +1221,8 @@ pub struct TrainBusPeriferie {
     slot_index: u32,
 
     state: PeripheryFault,
+    acknowledged: bool,
+    heartbeat_timer: f32,
 }
 
 impl TrainBusPeriferie {
@@ -799,6 +1242,42 @@ impl TrainBusPeriferie {
             kind: perifierie_kind,
             slot_index: slot_index as u32,
             state: PeripheryFault::Ok,
+            acknowledged: false,
+            heartbeat_timer: 0.0,
+        }
+    }
+
+    /// Receives the acknowledgement of the own fault
+    pub fn on_message(&mut self, msg: Message) {
+        msg.handle::<PeripheryFaultAcknowledge>(|m| {
+            if m.id == self.slot_index && self.state != PeripheryFault::Ok {
+                self.acknowledged = true;
+            }
+            Ok(())
+        })
+        .expect("PeripheryFaultAcknowledge: message handle failed");
+    }
+
+    /// Whether the current fault was acknowledged on the IBIS terminal
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged
+    }
+
+    /// Sends the heartbeat to the TrainBus cyclically
+    pub fn tick(&mut self) {
+        self.heartbeat_timer -= delta();
+        if self.heartbeat_timer <= 0.0 {
+            self.heartbeat_timer = HEARTBEAT_INTERVAL;
+            send_message(
+                &PeripheryHeartbeat {
+                    id: self.slot_index,
+                    state: self.state.clone(),
+                },
+                MessageTarget::Broadcast {
+                    across_couplings: false,
+                    include_self: true,
+                },
+            );
         }
     }
 
@@ -807,13 +1286,130 @@ impl TrainBusPeriferie {
             send_message(
                 &PeripheryFaultReport {
                     id: self.slot_index,
-                    kind: fault,
+                    kind: fault.clone(),
                 },
                 MessageTarget::Broadcast {
                     across_couplings: false,
                     include_self: true,
                 },
             );
+            self.state = fault;
+            self.acknowledged = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn car(number: &str, is_master: bool) -> TrainBusManager {
+        let mut car = TrainBusManager::new(number.to_string(), None);
+        car.on_message(Message::new(&IbisState { is_master }));
+        car
+    }
+
+    /// Exchanges the telegrams between two coupled cars for some rounds and returns for each round
+    /// which of the cars acts as master
+    fn run_coupled(
+        a: &mut TrainBusManager,
+        side_a: Coupling,
+        b: &mut TrainBusManager,
+        side_b: Coupling,
+    ) -> Vec<(bool, bool)> {
+        let telegram = |car: &TrainBusManager, side| {
+            let (master_pos, master_claim) = car.claim_towards(side);
+            InternTelegram {
+                master_pos,
+                master_claim,
+                vehicle_config: Vec::new(),
+            }
+        };
+
+        (0..4)
+            .map(|_| {
+                let to_b = telegram(a, side_a);
+                let to_a = telegram(b, side_b);
+                b.receive_telegram(side_b, to_b);
+                a.receive_telegram(side_a, to_a);
+                (a.is_acting_master(), b.is_acting_master())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_arbitration_front_to_front() {
+        let mut a = car("2051", true);
+        let mut b = car("2047", true);
+
+        let rounds = run_coupled(&mut a, Coupling::Front, &mut b, Coupling::Front);
+        assert!(rounds.iter().all(|&r| r == (false, true)));
+        assert!(a.master_demoted);
+        assert_eq!(a.master_side(), Some(Coupling::Front));
+    }
+
+    #[test]
+    fn test_arbitration_rear_to_rear() {
+        let mut a = car("2047", true);
+        let mut b = car("2051", true);
+
+        let rounds = run_coupled(&mut a, Coupling::Rear, &mut b, Coupling::Rear);
+        assert!(rounds.iter().all(|&r| r == (true, false)));
+        assert!(b.master_demoted);
+        assert_eq!(b.master_side(), Some(Coupling::Rear));
+    }
+
+    #[test]
+    fn test_arbitration_same_result_both_orientations() {
+        let mut a = car("2051", true);
+        let mut b = car("2047", true);
+        let rounds = run_coupled(&mut a, Coupling::Rear, &mut b, Coupling::Front);
+        assert_eq!(rounds.last(), Some(&(false, true)));
+
+        let mut a = car("2051", true);
+        let mut b = car("2047", true);
+        let rounds = run_coupled(&mut a, Coupling::Front, &mut b, Coupling::Rear);
+        assert_eq!(rounds.last(), Some(&(false, true)));
+    }
+
+    #[test]
+    fn test_slave_follows_master() {
+        let mut a = car("2051", false);
+        let mut b = car("2047", true);
+
+        let rounds = run_coupled(&mut a, Coupling::Front, &mut b, Coupling::Front);
+        assert!(rounds.iter().all(|&r| r == (false, true)));
+        assert!(!a.master_demoted);
+        assert!(a.is_master_there());
+        assert_eq!(
+            a.claim_towards(Coupling::Rear),
+            (Some(2), Some(b.own_claim()))
+        );
+    }
+
+    #[test]
+    fn test_arbitration_numeric() {
+        let mut a = car("10", true);
+        let mut b = car("9", true);
+
+        let rounds = run_coupled(&mut a, Coupling::Front, &mut b, Coupling::Rear);
+        assert_eq!(rounds.last(), Some(&(false, true)));
+    }
+
+    #[test]
+    fn test_arbitration_same_number() {
+        for (side_a, side_b) in [
+            (Coupling::Front, Coupling::Front),
+            (Coupling::Rear, Coupling::Rear),
+            (Coupling::Front, Coupling::Rear),
+        ] {
+            let mut a = car("2047", true);
+            let mut b = car("2047", true);
+
+            let rounds = run_coupled(&mut a, side_a, &mut b, side_b);
+            let (master_a, master_b) = *rounds.last().unwrap();
+            assert!(master_a != master_b);
+            assert_eq!(master_a, a.arbitration_key < b.arbitration_key);
         }
     }
 }
@@ -0,0 +1,427 @@
+//! # Wear and Maintenance
+//!
+//! This module provides wear models for parts which age with use. The wear
+//! state is stored in variables, so that it is kept between sessions like the
+//! mileage of the [`Odometry`](super::odometry::Odometry):
+//!
+//! - [`DoorWear`]: the door drive gets slower and noisier with the number of
+//!   door cycles counted by the
+//!   [`DoorCycles`](crate::components::doors::door::DoorCycles) of the door
+//! - [`BrakePadWear`]: the brake pads wear with the braking energy and lose
+//!   effectiveness
+//! - [`LampLifetime`]: a lamp burns out after a random lifetime
+//!
+//! Each model is reset by a workshop visit with `maintain()`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! let mut door_wear = DoorWear::builder()
+//!     .persist("Wear_Door_1")
+//!     .snd_squeak("Snd_Door_1_Squeak", "Snd_Door_1_Squeak_Vol")
+//!     .build();
+//!
+//! // In the main loop:
+//! door_wear.tick(&door);
+//! door.drive_factor = door_wear.drive_factor;
+//! ```
+
+use crate::{
+    api::{
+        general::delta,
+        light::Light,
+        random::gen_f32,
+        sound::Sound,
+        variable::{load_persistent, store_persistent},
+    },
+    components::doors::door::Door,
+    management::enums::door_enums::DoorState,
+};
+
+//=========================================================================
+
+pub struct DoorWearBuilder {
+    persist_var: Option<String>,
+    life_cycles: u32,
+    min_drive_factor: f32,
+    snd_squeak: Sound,
+}
+
+impl DoorWearBuilder {
+    /// Name of the variable in which the cycle count is stored.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// Number of cycles until the drive is fully worn and its drive factor
+    /// at that point.
+    pub fn rates(mut self, life_cycles: u32, min_drive_factor: f32) -> Self {
+        self.life_cycles = life_cycles.max(1);
+        self.min_drive_factor = min_drive_factor;
+        self
+    }
+
+    /// Squeaking sound while the door moves, its volume rises with the wear.
+    pub fn snd_squeak(mut self, name: impl Into<String>, vol_name: impl Into<String>) -> Self {
+        self.snd_squeak = Sound::new(Some(&name.into()), Some(&vol_name.into()), None);
+        self
+    }
+
+    pub fn build(self) -> DoorWear {
+        let cycles = self
+            .persist_var
+            .as_ref()
+            .and_then(|var_name| load_persistent::<u32>(var_name))
+            .unwrap_or_default();
+
+        let mut wear = DoorWear {
+            persist_var: self.persist_var,
+            life_cycles: self.life_cycles,
+            min_drive_factor: self.min_drive_factor,
+            snd_squeak: self.snd_squeak,
+            squeaking: false,
+            count_last: None,
+            cycles,
+            wear: 0.0,
+            drive_factor: 1.0,
+        };
+        wear.update();
+        wear
+    }
+}
+
+/// Wear of a door drive by the door cycles.
+#[derive(Debug)]
+pub struct DoorWear {
+    persist_var: Option<String>,
+    life_cycles: u32,
+    min_drive_factor: f32,
+
+    snd_squeak: Sound,
+
+    /// Whether the squeak plays, the sound is only switched when this changes
+    squeaking: bool,
+    count_last: Option<u32>,
+
+    /// Door cycles since the last maintenance
+    pub cycles: u32,
+    /// Wear from 0.0 (new) to 1.0 (fully worn)
+    pub wear: f32,
+    /// Speed factor of the drive, to be passed to the door
+    pub drive_factor: f32,
+}
+
+impl DoorWear {
+    /// Creates a new door wear builder.
+    pub fn builder() -> DoorWearBuilder {
+        DoorWearBuilder {
+            persist_var: None,
+            life_cycles: 200_000,
+            min_drive_factor: 0.7,
+            snd_squeak: Sound::new_simple(None),
+        }
+    }
+
+    /// Adds the cycles completed by the door since the last call.
+    ///
+    /// # Arguments
+    ///
+    /// * `door` - Door whose cycle count is taken over
+    pub fn tick<D: Door + ?Sized>(&mut self, door: &D) {
+        // Die Zyklen zählt die Tür selbst, hier wird nur der Zuwachs übernommen
+        let count = door.cycles().count;
+        let completed = count.wrapping_sub(*self.count_last.get_or_insert(count));
+        self.count_last = Some(count);
+        if completed > 0 {
+            self.cycles = self.cycles.saturating_add(completed);
+            self.update();
+            if let Some(var_name) = &self.persist_var {
+                store_persistent(var_name, self.cycles);
+            }
+        }
+
+        let squeaking = door.state() == DoorState::Other && self.wear > 0.0;
+        if squeaking != self.squeaking {
+            self.squeaking = squeaking;
+            self.snd_squeak.update_volume(self.wear);
+            self.snd_squeak.start_stop(squeaking);
+        }
+    }
+
+    /// Resets the cycle count after a maintenance of the drive.
+    pub fn maintain(&mut self) {
+        self.cycles = 0;
+        self.update();
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(var_name, self.cycles);
+        }
+    }
+
+    fn update(&mut self) {
+        self.wear = (self.cycles as f32 / self.life_cycles as f32).min(1.0);
+        self.drive_factor = 1.0 - self.wear * (1.0 - self.min_drive_factor);
+    }
+}
+
+//=========================================================================
+
+pub struct BrakePadWearBuilder {
+    persist_var: Option<String>,
+    life_energy: f32,
+    min_effectiveness: f32,
+}
+
+impl BrakePadWearBuilder {
+    /// Name of the variable in which the wear is stored.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// Braking energy in MJ until the pads are worn out and the remaining
+    /// effectiveness of worn out pads.
+    pub fn rates(mut self, life_energy: f32, min_effectiveness: f32) -> Self {
+        self.life_energy = life_energy.max(f32::EPSILON);
+        self.min_effectiveness = min_effectiveness;
+        self
+    }
+
+    pub fn build(self) -> BrakePadWear {
+        let wear = self
+            .persist_var
+            .as_ref()
+            .and_then(|var_name| load_persistent::<f32>(var_name))
+            .unwrap_or_default();
+
+        let mut pads = BrakePadWear {
+            persist_var: self.persist_var,
+            life_energy: self.life_energy,
+            min_effectiveness: self.min_effectiveness,
+            wear,
+            effectiveness: 1.0,
+        };
+        pads.update();
+        pads
+    }
+}
+
+/// Wear of the brake pads by the braking energy.
+#[derive(Debug)]
+pub struct BrakePadWear {
+    persist_var: Option<String>,
+    life_energy: f32,
+    min_effectiveness: f32,
+
+    /// Wear from 0.0 (new) to 1.0 (worn out)
+    pub wear: f32,
+    /// Factor for the brake force from worn out to 1.0 for new pads
+    pub effectiveness: f32,
+}
+
+impl BrakePadWear {
+    /// Creates a new brake pad wear builder.
+    pub fn builder() -> BrakePadWearBuilder {
+        BrakePadWearBuilder {
+            persist_var: None,
+            life_energy: 50_000.0,
+            min_effectiveness: 0.6,
+        }
+    }
+
+    /// Adds the braking energy of this tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `brake_force` - Brake force of the pads in N
+    /// * `speed` - Speed of the vehicle in m/s
+    pub fn tick(&mut self, brake_force: f32, speed: f32) {
+        let energy = brake_force.abs() * speed.abs() * delta() / 1_000_000.0;
+        if energy <= 0.0 {
+            return;
+        }
+
+        self.wear = (self.wear + energy / self.life_energy).min(1.0);
+        self.update();
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(var_name, self.wear);
+        }
+    }
+
+    /// Replaces the brake pads.
+    pub fn maintain(&mut self) {
+        self.wear = 0.0;
+        self.update();
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(var_name, self.wear);
+        }
+    }
+
+    fn update(&mut self) {
+        self.effectiveness = 1.0 - self.wear * (1.0 - self.min_effectiveness);
+    }
+}
+
+//=========================================================================
+
+pub struct LampLifetimeBuilder {
+    light: Light,
+    persist_var: Option<String>,
+    mean_life: f32,
+    spread: f32,
+}
+
+impl LampLifetimeBuilder {
+    /// Name of the variable in which the remaining lifetime is stored.
+    pub fn persist(mut self, var_name: impl Into<String>) -> Self {
+        self.persist_var = Some(var_name.into());
+        self
+    }
+
+    /// Mean lifetime in operating hours and the spread relative to it, e.g.
+    /// 0.3 for lamps living 70 % to 130 % of the mean lifetime.
+    pub fn lifetime(mut self, mean_life: f32, spread: f32) -> Self {
+        self.mean_life = mean_life;
+        self.spread = spread;
+        self
+    }
+
+    pub fn build(self) -> LampLifetime {
+        let stored = self
+            .persist_var
+            .as_ref()
+            .and_then(|var_name| load_persistent::<f32>(var_name));
+
+        let mut lamp = LampLifetime {
+            light: self.light,
+            persist_var: self.persist_var,
+            mean_life: self.mean_life,
+            spread: self.spread,
+            remaining: stored.unwrap_or_default(),
+            burnt_out: stored.is_some_and(|remaining| remaining < 0.0),
+        };
+        if stored.is_none() {
+            lamp.remaining = lamp.draw_life();
+        }
+        lamp
+    }
+}
+
+/// Lamp burning out after a random lifetime.
+///
+/// The remaining lifetime is stored in hours, a burnt out lamp is stored as
+/// negative value.
+#[derive(Debug)]
+pub struct LampLifetime {
+    light: Light,
+
+    persist_var: Option<String>,
+    mean_life: f32,
+    spread: f32,
+
+    /// Remaining lifetime in operating hours
+    pub remaining: f32,
+    /// Whether the lamp is burnt out
+    pub burnt_out: bool,
+}
+
+impl LampLifetime {
+    /// Creates a new lamp lifetime builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `light_name` - Name of the light variable
+    pub fn builder(light_name: impl Into<String>) -> LampLifetimeBuilder {
+        LampLifetimeBuilder {
+            light: Light::new(Some(&light_name.into())),
+            persist_var: None,
+            mean_life: 1000.0,
+            spread: 0.3,
+        }
+    }
+
+    /// Sets the brightness of the lamp and consumes its lifetime while lit.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - Brightness of the intact lamp
+    pub fn tick(&mut self, level: f32) {
+        if !self.burnt_out && level > 0.0 {
+            self.remaining -= delta() / 3600.0;
+            if self.remaining <= 0.0 {
+                self.burnt_out = true;
+                self.remaining = -1.0;
+            }
+            if let Some(var_name) = &self.persist_var {
+                store_persistent(var_name, self.remaining);
+            }
+        }
+
+        self.light
+            .set_brightness(if self.burnt_out { 0.0 } else { level });
+    }
+
+    /// Replaces the lamp, the new lamp gets a new random lifetime.
+    pub fn maintain(&mut self) {
+        self.burnt_out = false;
+        self.remaining = self.draw_life();
+        if let Some(var_name) = &self.persist_var {
+            store_persistent(var_name, self.remaining);
+        }
+    }
+
+    fn draw_life(&self) -> f32 {
+        // Summe aus Zufallswerten nähert eine Normalverteilung an
+        let deviation = (gen_f32(-1.0..=1.0) + gen_f32(-1.0..=1.0) + gen_f32(-1.0..=1.0)) / 3.0;
+        (self.mean_life * (1.0 + deviation * self.spread)).max(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{
+            mock::{MockAnimation, MockLight, MockSound},
+            variable::store_persistent,
+        },
+        components::doors::folding_door::{FoldingDoor, FoldingDoorPanels},
+    };
+
+    type MockDoor = FoldingDoor<MockAnimation, MockSound, MockLight>;
+
+    fn door_with_count(count: u32) -> MockDoor {
+        let mut door =
+            MockDoor::builder_with_outputs(0, FoldingDoorPanels::Two, MockAnimation::default())
+                .build();
+        door.cycles.count = count;
+        door
+    }
+
+    #[test]
+    fn test_first_tick_after_restore() {
+        store_persistent("Wear_Door", 1000u32);
+        let mut wear = DoorWear::builder().persist("Wear_Door").build();
+        assert_eq!(wear.cycles, 1000);
+
+        // Die Tür zählt ab Sitzungsbeginn, ihr Stand kommt nicht zum gespeicherten hinzu
+        let mut door = door_with_count(7);
+        wear.tick(&door);
+        assert_eq!(wear.cycles, 1000);
+
+        door.cycles.count = 9;
+        wear.tick(&door);
+        assert_eq!(wear.cycles, 1002);
+    }
+
+    #[test]
+    fn test_door_count_rollover() {
+        let mut wear = DoorWear::builder().build();
+        let mut door = door_with_count(u32::MAX - 1);
+        wear.tick(&door);
+
+        door.cycles.count = 1;
+        wear.tick(&door);
+        assert_eq!(wear.cycles, 3);
+    }
+}
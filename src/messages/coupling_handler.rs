@@ -82,6 +82,67 @@ pub trait MessageLine<T: PartialEq + Clone> {
 
 //-----------------------------------------------------------------------------------
 
+/// A value whose meaning depends on the orientation of the car, e.g. the
+/// driving direction or left and right.
+pub trait Directional {
+    /// Returns the value as seen from a car facing the other way.
+    fn flip(self) -> Self;
+}
+
+/// Turns a value of this car into the form sent through the coupling `side`.
+///
+/// On the coupling a value is always given as seen by a car whose front
+/// coupling faces the sender. Sent through the rear it is left as is, sent
+/// through the front it is flipped.
+pub fn orient_outgoing<T: Directional>(value: T, side: Coupling) -> T {
+    match side {
+        Coupling::Front => value.flip(),
+        Coupling::Rear => value,
+    }
+}
+
+/// Turns a value received through the coupling `side` into the view of this car.
+///
+/// Received at the front, this car faces the sender as the convention
+/// assumes; received at the rear it faces the other way and the value is
+/// flipped. Together with [`orient_outgoing`] a value is flipped exactly once
+/// between two cars coupled front to front or rear to rear, so the relative
+/// orientation of each coupled pair is accounted for without a handshake.
+pub fn orient_incoming<T: Directional>(value: T, side: Coupling) -> T {
+    match side {
+        Coupling::Front => value,
+        Coupling::Rear => value.flip(),
+    }
+}
+
+/// Message handler applying the orientation of the coupled cars to the
+/// values of another handler.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let line = UniversalCouplingLine::new(Oriented(MyDirectionalHandler), (true, true));
+/// ```
+pub struct Oriented<H>(pub H);
+
+impl<T: Directional + PartialEq + Clone, H: MessageLine<T>> MessageLine<T> for Oriented<H> {
+    fn evaluate(&self, a: &T, b: &T) -> T {
+        self.0.evaluate(a, b)
+    }
+
+    fn send(&self, value: T, side: Coupling) {
+        self.0.send(orient_outgoing(value, side), side);
+    }
+
+    fn rcv(&self, msg: Message) -> Option<(Coupling, T)> {
+        self.0
+            .rcv(msg)
+            .map(|(side, value)| (side, orient_incoming(value, side)))
+    }
+}
+
+//-----------------------------------------------------------------------------------
+
 /// A universal coupling line that manages bidirectional communication between train cars.
 ///
 /// This struct handles the complex logic of coupling state management, message routing,
@@ -353,8 +414,8 @@ impl<T: Default + Clone + Serialize + for<'a> Deserialize<'a> + PartialEq, H: Me
 
     /// Updates the coupling state for the specified side.
     ///
-    /// When a coupling is disconnected, the received value for that side
-    /// is reset to default and an update cycle is triggered.
+    /// When a coupling is disconnected, the received and last sent values for
+    /// that side are reset to default and an update cycle is triggered.
     ///
     /// # Arguments
     ///
@@ -368,6 +429,8 @@ impl<T: Default + Clone + Serialize + for<'a> Deserialize<'a> + PartialEq, H: Me
                     if !value {
                         // Reset control line if no longer coupled
                         self.received.0 = T::default();
+                        // Resend on the next coupling
+                        self.last_send.0 = T::default();
                     }
                     self.update();
                 }
@@ -378,6 +441,8 @@ impl<T: Default + Clone + Serialize + for<'a> Deserialize<'a> + PartialEq, H: Me
                     if !value {
                         // Reset control line if no longer coupled
                         self.received.1 = T::default();
+                        // Resend on the next coupling
+                        self.last_send.1 = T::default();
                     }
                     self.update();
                 }
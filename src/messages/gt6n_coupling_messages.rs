@@ -29,8 +29,11 @@ use lotus_script::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    management::enums::{door_enums::DoorTarget, traction_enums::DirectionOfDriving},
-    messages::coupling_handler::MessageLine,
+    management::enums::{
+        door_enums::{DoorCommand, DoorSideTarget, DoorTarget},
+        traction_enums::DirectionOfDriving,
+    },
+    messages::coupling_handler::{orient_incoming, orient_outgoing, Directional, MessageLine},
 };
 
 //===================================================================
@@ -220,7 +223,7 @@ message_type!(Reverser, "Gt6n_Coupler", "Reverser");
 
 /// Handler for reverser state messages across couplings.
 ///
-/// Handles direction flipping for front couplings to ensure consistent
+/// Applies the orientation of the coupled cars to ensure consistent
 /// direction interpretation across the entire train consist.
 pub struct CouplerReverser;
 
@@ -241,21 +244,17 @@ impl MessageLine<DirectionOfDriving> for CouplerReverser {
 
     /// Sends reverser state to the specified coupling.
     ///
-    /// Automatically flips the direction for front couplings to maintain
-    /// consistent direction interpretation throughout the train.
+    /// The direction is oriented with [`orient_outgoing`].
     ///
     /// # Arguments
     ///
     /// * `value` - Current reverser state
     /// * `side` - Which coupling to send through (affects direction interpretation)
     fn send(&self, value: DirectionOfDriving, side: Coupling) {
-        let value = match side {
-            Coupling::Front => value.flip(),
-            Coupling::Rear => value,
-        };
-
         send_message(
-            &Reverser { value },
+            &Reverser {
+                value: orient_outgoing(value, side),
+            },
             [MessageTarget::AcrossCoupling {
                 coupling: side,
                 cascade: false,
@@ -265,8 +264,7 @@ impl MessageLine<DirectionOfDriving> for CouplerReverser {
 
     /// Receives and processes reverser state messages.
     ///
-    /// Handles direction interpretation based on which coupling the message
-    /// came from, flipping direction for rear couplings.
+    /// The direction is turned into the view of this car with [`orient_incoming`].
     ///
     /// # Arguments
     ///
@@ -278,21 +276,9 @@ impl MessageLine<DirectionOfDriving> for CouplerReverser {
     fn rcv(&self, msg: Message) -> Option<(Coupling, DirectionOfDriving)> {
         let mut result = None;
 
-        if msg.source().is_front() || msg.source().is_rear() {
-            let side = if msg.source().is_front() {
-                Coupling::Front
-            } else {
-                Coupling::Rear
-            };
-
+        if let Some(side) = msg.source().coupling {
             msg.handle::<Reverser>(|m| {
-                result = Some((
-                    side,
-                    match side {
-                        Coupling::Front => m.value,
-                        Coupling::Rear => m.value.flip(),
-                    },
-                ));
+                result = Some((side, orient_incoming(m.value, side)));
                 Ok(())
             })
             .expect("Reverser: message handle failed");
@@ -666,21 +652,56 @@ pub struct DoorControl {
 
 message_type!(DoorControl, "Gt6n_Coupler", "DoorControl");
 
+/// Message carrying the door command with platform side and car mask.
+///
+/// Sent under its own message id, the plain [`DoorControl`] target of older
+/// vehicles is still understood.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct DoorCommandControl {
+    /// Door command with target state, platform side and car mask
+    pub value: DoorCommand,
+}
+
+message_type!(DoorCommandControl, "Gt6n_Coupler", "DoorCommand");
+
+impl Directional for DoorSideTarget {
+    fn flip(self) -> Self {
+        match self {
+            DoorSideTarget::Left => DoorSideTarget::Right,
+            DoorSideTarget::Right => DoorSideTarget::Left,
+            side => side,
+        }
+    }
+}
+
+impl Directional for DoorCommand {
+    fn flip(self) -> Self {
+        Self {
+            side: self.side.flip(),
+            ..self
+        }
+    }
+}
+
 /// Handler for door control messages across couplings.
 ///
-/// Merges door control commands from multiple sources.
+/// Merges door control commands from multiple sources and applies the
+/// orientation of the coupled cars to the platform side. A plain
+/// [`DoorControl`] target of an older vehicle applies to both sides.
 pub struct CouplerDoorControl;
 
-impl MessageLine<DoorTarget> for CouplerDoorControl {
+impl MessageLine<DoorCommand> for CouplerDoorControl {
     /// Evaluates combined door control state by merging commands.
-    fn evaluate(&self, a: &DoorTarget, b: &DoorTarget) -> DoorTarget {
-        a.clone().merge(b)
+    fn evaluate(&self, a: &DoorCommand, b: &DoorCommand) -> DoorCommand {
+        a.merge(b)
     }
 
     /// Sends door control command to the specified coupling.
-    fn send(&self, value: DoorTarget, side: Coupling) {
+    fn send(&self, value: DoorCommand, side: Coupling) {
         send_message(
-            &DoorControl { value },
+            &DoorCommandControl {
+                value: orient_outgoing(value, side),
+            },
             [MessageTarget::AcrossCoupling {
                 coupling: side,
                 cascade: false,
@@ -689,12 +710,18 @@ impl MessageLine<DoorTarget> for CouplerDoorControl {
     }
 
     /// Receives and processes door control messages.
-    fn rcv(&self, msg: Message) -> Option<(Coupling, DoorTarget)> {
+    fn rcv(&self, msg: Message) -> Option<(Coupling, DoorCommand)> {
         let mut result = None;
 
         if let Some(side) = msg.source().coupling {
+            msg.handle::<DoorCommandControl>(|m| {
+                result = Some((side, orient_incoming(m.value, side)));
+                Ok(())
+            })
+            .expect("DoorCommandControl: message handle failed");
+
             msg.handle::<DoorControl>(|m| {
-                result = Some((side, m.value));
+                result = Some((side, DoorCommand::new(m.value, DoorSideTarget::Both)));
                 Ok(())
             })
             .expect("DoorControl: message handle failed");
@@ -758,6 +785,73 @@ impl MessageLine<f32> for CouplerPowerlinePower {
     }
 }
 
+//===================================================================
+// Powerline balance
+//===================================================================
+
+/// Power balance of one or more cars on the powerline.
+///
+/// Each car reports its own values, the coupling line adds them up over the
+/// consist. Sent under its own message id, so vehicles using the plain
+/// [`PowerlinePower`] value keep working.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PowerlineBalance {
+    /// Power fed in by raised pantographs in kW
+    pub generation: f32,
+    /// Demand of essential loads in kW
+    pub essential: f32,
+    /// Demand of loads which may be shed in kW
+    pub non_essential: f32,
+    /// Essential demand covered by the pantographs of the same car in kW
+    #[serde(default)]
+    pub self_supplied: f32,
+}
+
+message_type!(PowerlineBalance, "Gt6n_Coupler", "PowerlineBalance");
+
+/// Handler for powerline balance messages across couplings.
+///
+/// Uses additive logic to combine the power balances.
+pub struct CouplerPowerlineBalance;
+
+impl MessageLine<PowerlineBalance> for CouplerPowerlineBalance {
+    /// Evaluates the combined power balance using addition.
+    fn evaluate(&self, a: &PowerlineBalance, b: &PowerlineBalance) -> PowerlineBalance {
+        PowerlineBalance {
+            generation: a.generation + b.generation,
+            essential: a.essential + b.essential,
+            non_essential: a.non_essential + b.non_essential,
+            self_supplied: a.self_supplied + b.self_supplied,
+        }
+    }
+
+    /// Sends the power balance to the specified coupling.
+    fn send(&self, value: PowerlineBalance, side: Coupling) {
+        send_message(
+            &value,
+            [MessageTarget::AcrossCoupling {
+                coupling: side,
+                cascade: false,
+            }],
+        );
+    }
+
+    /// Receives and processes powerline balance messages.
+    fn rcv(&self, msg: Message) -> Option<(Coupling, PowerlineBalance)> {
+        let mut result = None;
+
+        if let Some(side) = msg.source().coupling {
+            msg.handle::<PowerlineBalance>(|m| {
+                result = Some((side, m));
+                Ok(())
+            })
+            .expect("PowerlineBalance: message handle failed");
+        }
+
+        result
+    }
+}
+
 //===================================================================
 // Shunting signal
 //===================================================================
@@ -945,10 +1039,22 @@ impl Indicator {
 
 message_type!(Indicator, "Gt6n_Coupler", "Indicator");
 
+impl Directional for Indicator {
+    fn flip(self) -> Self {
+        Indicator::flip(self)
+    }
+}
+
+impl Directional for DirectionOfDriving {
+    fn flip(self) -> Self {
+        DirectionOfDriving::flip(&self)
+    }
+}
+
 /// Handler for indicator messages across couplings.
 ///
-/// Handles directional flipping to maintain correct indicator
-/// interpretation throughout the train consist.
+/// Applies the orientation of the coupled cars to maintain correct
+/// indicator interpretation throughout the train consist.
 pub struct CouplerIndicator;
 
 impl MessageLine<Indicator> for CouplerIndicator {
@@ -968,21 +1074,15 @@ impl MessageLine<Indicator> for CouplerIndicator {
 
     /// Sends indicator state to the specified coupling.
     ///
-    /// Automatically flips left/right indicators for front couplings
-    /// to maintain correct directional indication.
+    /// Left and right are oriented with [`orient_outgoing`].
     ///
     /// # Arguments
     ///
     /// * `value` - Current indicator states
     /// * `side` - Which coupling to send through (affects direction interpretation)
     fn send(&self, value: Indicator, side: Coupling) {
-        let value = match side {
-            Coupling::Front => value.flip(),
-            Coupling::Rear => value,
-        };
-
         send_message(
-            &value,
+            &orient_outgoing(value, side),
             [MessageTarget::AcrossCoupling {
                 coupling: side,
                 cascade: false,
@@ -992,8 +1092,7 @@ impl MessageLine<Indicator> for CouplerIndicator {
 
     /// Receives and processes indicator messages.
     ///
-    /// Handles directional interpretation based on coupling source,
-    /// flipping indicators for rear couplings.
+    /// Left and right are turned into the view of this car with [`orient_incoming`].
     ///
     /// # Arguments
     ///
@@ -1005,21 +1104,9 @@ impl MessageLine<Indicator> for CouplerIndicator {
     fn rcv(&self, msg: Message) -> Option<(Coupling, Indicator)> {
         let mut result = None;
 
-        if msg.source().is_front() || msg.source().is_rear() {
-            let side = if msg.source().is_front() {
-                Coupling::Front
-            } else {
-                Coupling::Rear
-            };
-
+        if let Some(side) = msg.source().coupling {
             msg.handle::<Indicator>(|m| {
-                result = Some((
-                    side,
-                    match side {
-                        Coupling::Front => m,
-                        Coupling::Rear => m.flip(),
-                    },
-                ));
+                result = Some((side, orient_incoming(m, side)));
                 Ok(())
             })
             .expect("Indicator: message handle failed");
@@ -1340,3 +1427,57 @@ impl MessageLine<bool> for CouplerStopRequest {
         result
     }
 }
+
+//===================================================================
+// Car present
+//===================================================================
+
+/// Message announcing a coupled car to its neighbour.
+///
+/// Used to find the outer ends of a consist, e.g. to light only the
+/// head- and taillights at the ends of the train.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct CarPresent {
+    /// Whether a car is present behind the coupling
+    pub value: bool,
+}
+
+message_type!(CarPresent, "Gt6n_Coupler", "CarPresent");
+
+/// Handler for car presence messages across couplings.
+///
+/// Uses OR logic, a car is present if any car announces itself.
+pub struct CouplerCarPresent;
+
+impl MessageLine<bool> for CouplerCarPresent {
+    /// Evaluates car presence using OR logic.
+    fn evaluate(&self, a: &bool, b: &bool) -> bool {
+        *a || *b
+    }
+
+    /// Sends car presence to the specified coupling.
+    fn send(&self, value: bool, side: Coupling) {
+        send_message(
+            &CarPresent { value },
+            [MessageTarget::AcrossCoupling {
+                coupling: side,
+                cascade: false,
+            }],
+        );
+    }
+
+    /// Receives and processes car presence messages.
+    fn rcv(&self, msg: Message) -> Option<(Coupling, bool)> {
+        let mut result = None;
+
+        if let Some(side) = msg.source().coupling {
+            msg.handle::<CarPresent>(|m| {
+                result = Some((side, m.value));
+                Ok(())
+            })
+            .expect("CarPresent: message handle failed");
+        }
+
+        result
+    }
+}
@@ -0,0 +1,220 @@
+//! IBIS Messages
+//!
+//! This module provides the message types with which the IBIS master supplies
+//! the passenger information periphery (displays, announcements) on the
+//! TrainBus with the current journey data.
+
+use lotus_script::{
+    message::{send_message, MessageTarget},
+    prelude::message_type,
+};
+use serde::{Deserialize, Serialize};
+
+//===================================================================
+// Passenger information
+//===================================================================
+
+/// Journey data for the passenger information, sent by the IBIS master.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::messages::ibis_messages::PassengerInfo;
+///
+/// let info = PassengerInfo {
+///     line: "4".to_string(),
+///     course: "12".to_string(),
+///     destination: "Hauptbahnhof".to_string(),
+///     next_stop: "Marktplatz".to_string(),
+///     connections: vec!["Bus 52".to_string(), "S1 S2".to_string()],
+///     free_text: None,
+/// };
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PassengerInfo {
+    /// Line number
+    pub line: String,
+    /// Course number ("Kurs")
+    pub course: String,
+    /// Destination of the course
+    pub destination: String,
+    /// Name of the next stop
+    pub next_stop: String,
+    /// Connections at the next stop, one entry per line of text
+    pub connections: Vec<String>,
+    /// Optional free text, e.g. a disruption notice
+    pub free_text: Option<String>,
+}
+
+message_type!(PassengerInfo, "Std_TrainBus", "PassengerInfo");
+
+/// Sends the passenger information to the whole train if it has changed.
+///
+/// # Examples
+///
+/// ```rust
+/// use pandemist_vehicle_elements::messages::ibis_messages::{PassengerInfo, PassengerInfoSender};
+///
+/// let mut sender = PassengerInfoSender::default();
+///
+/// // In the IBIS master:
+/// sender.send(&info);
+/// ```
+#[derive(Default, Debug)]
+pub struct PassengerInfoSender {
+    value_last: Option<PassengerInfo>,
+}
+
+impl PassengerInfoSender {
+    /// Sends the passenger information if it differs from the last sent one.
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - The current passenger information
+    pub fn send(&mut self, info: &PassengerInfo) {
+        if self.value_last.as_ref() != Some(info) {
+            send_message(
+                info,
+                [MessageTarget::Broadcast {
+                    across_couplings: true,
+                    include_self: true,
+                }],
+            );
+            self.value_last = Some(info.clone());
+        }
+    }
+}
+
+/// Journey data entered on the IBIS terminal, sent to the IBIS master.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IbisInput {
+    /// Line number
+    pub line: u32,
+    /// Course number ("Kurs")
+    pub course: u32,
+    /// Destination code ("Zielnummer")
+    pub destination: u32,
+}
+
+message_type!(IbisInput, "Std_TrainBus", "IbisInput");
+
+/// Destination code ("Zielnummer") set on the IBIS, sent by the IBIS master
+/// to the destination displays.
+///
+/// Devices with a fixed set of destinations like roller blinds or split-flap
+/// displays select their position by this code.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DestinationCode {
+    /// Destination code, 0 for no destination
+    pub code: u32,
+}
+
+message_type!(DestinationCode, "Std_TrainBus", "DestinationCode");
+
+//===================================================================
+// Announcements
+//===================================================================
+
+/// Page of the interior displays.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DisplayPage {
+    /// Line, destination and next stop
+    #[default]
+    NextStop,
+    /// Connections at the next stop
+    Connections,
+    /// Free text of the IBIS master
+    FreeText,
+}
+
+/// Announcement started by the IBIS master in all coupled cars at the same
+/// time. Each car passes the message on to the next coupling and reduces the
+/// delay by the transmission time of one coupling.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct AnnouncementSync {
+    /// Running number of the trigger
+    pub id: u32,
+    /// Number of the announcement
+    pub announcement: u32,
+    /// Duration of the announcement in seconds
+    pub duration: f32,
+    /// Page the interior displays show during the announcement
+    pub page: Option<DisplayPage>,
+    /// Time in seconds until the announcement starts in the receiving car
+    pub delay: f32,
+    /// Couplings passed since the master
+    pub hops: u32,
+}
+
+message_type!(AnnouncementSync, "Std_TrainBus", "AnnouncementSync");
+
+//===================================================================
+// Energy
+//===================================================================
+
+/// Energy consumption of the current trip, sent by the energy meter of a car
+/// for the eco-driving feedback of the IBIS.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct EnergyReport {
+    /// Energy drawn from the overhead line during the trip in kWh
+    pub consumed_kwh: f64,
+    /// Energy fed back into the overhead line during the trip in kWh
+    pub regenerated_kwh: f64,
+    /// Distance of the trip in km
+    pub distance_km: f64,
+}
+
+message_type!(EnergyReport, "Std_TrainBus", "EnergyReport");
+
+impl EnergyReport {
+    /// Net consumption in kWh per km, or `None` before the first 100 m.
+    pub fn net_per_km(&self) -> Option<f64> {
+        (self.distance_km >= 0.1)
+            .then(|| (self.consumed_kwh - self.regenerated_kwh) / self.distance_km)
+    }
+}
+
+//===================================================================
+// Timetable
+//===================================================================
+
+/// Timetable position and delay, sent by the timetable of the IBIS master to
+/// the displays and the announcements.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TimetableInfo {
+    /// Name of the stop the vehicle is standing at
+    pub current_stop: Option<String>,
+    /// Name of the next stop, `None` after the last stop
+    pub next_stop: Option<String>,
+    /// Delay in whole seconds, negative when early
+    pub delay: i32,
+}
+
+message_type!(TimetableInfo, "Std_TrainBus", "TimetableInfo");
+
+//===================================================================
+// Printing
+//===================================================================
+
+/// Print job for the printer of the car, sent e.g. by the IBIS or the ticket
+/// machine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PrintJob {
+    /// Number of the job, returned with the [`PrintResult`]
+    pub id: u32,
+    /// Lines of text to print
+    pub lines: Vec<String>,
+}
+
+message_type!(PrintJob, "Std_TrainBus", "PrintJob");
+
+/// Result of a print job, sent by the printer.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PrintResult {
+    /// Number of the job
+    pub id: u32,
+    /// Whether the job was printed, `false` if it was lost by a fault
+    pub printed: bool,
+}
+
+message_type!(PrintResult, "Std_TrainBus", "PrintResult");
@@ -1,4 +1,6 @@
 pub mod coupling_handler;
 pub mod diagnostic_messages;
 pub mod gt6n_coupling_messages;
+pub mod ibis_messages;
 pub mod pandemist_messages;
+pub mod std_coupling_messages;
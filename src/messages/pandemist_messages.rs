@@ -10,6 +10,8 @@ use lotus_script::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::management::enums::target_enums::SwitchingTarget;
+
 //===================================================================
 // Choice between GPM and ZB for IBIS
 //===================================================================
@@ -62,3 +64,91 @@ pub fn send_gpm_state() {
         }],
     );
 }
+
+//===================================================================
+// Adhesion between wheel and rail
+//===================================================================
+
+/// Current adhesion between wheel and rail, published by the rail condition.
+///
+/// Traction and brake control limit their forces to the adhesion, sanders
+/// and train protection react on slipping and sliding wheels.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct AdhesionInfo {
+    /// Available adhesion coefficient
+    pub adhesion: f32,
+    /// Whether the wheels slip under traction
+    pub slip: bool,
+    /// Whether the wheels slide under braking
+    pub slide: bool,
+}
+
+message_type!(AdhesionInfo, "Pan", "Adhesion");
+
+/// Sends the adhesion to the own vehicle if it has changed noticeably.
+#[derive(Default, Debug)]
+pub struct AdhesionInfoSender {
+    value_last: Option<AdhesionInfo>,
+}
+
+impl AdhesionInfoSender {
+    /// Sends the adhesion if the slip state has changed or the coefficient
+    /// differs by more than 0.005 from the last sent one.
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - The current adhesion
+    pub fn send(&mut self, info: AdhesionInfo) {
+        let changed = match self.value_last {
+            Some(last) => {
+                last.slip != info.slip
+                    || last.slide != info.slide
+                    || (last.adhesion - info.adhesion).abs() > 0.005
+            }
+            None => true,
+        };
+
+        if changed {
+            send_message(
+                &info,
+                [MessageTarget::Broadcast {
+                    across_couplings: false,
+                    include_self: true,
+                }],
+            );
+            self.value_last = Some(info);
+        }
+    }
+}
+
+//===================================================================
+// Remote control of the main switch
+//===================================================================
+
+/// Switching command for a main switch, e.g. from the MFD or a leading car.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct MainSwitchRemote {
+    /// Id of the main switch, set with `MainSwitchBuilder::remote`
+    pub id: usize,
+    /// Switching target taken over by the main switch
+    pub target: SwitchingTarget,
+}
+
+message_type!(MainSwitchRemote, "Pan", "MainSwitchRemote");
+
+/// Sends a switching command to the main switches with the given id.
+///
+/// # Arguments
+///
+/// * `id` - Id of the main switch
+/// * `target` - Switching target, e.g. `SwitchingTarget::TurnOn(1.0)`
+/// * `across_couplings` - Whether the main switches of coupled vehicles are included
+pub fn send_main_switch_remote(id: usize, target: SwitchingTarget, across_couplings: bool) {
+    send_message(
+        &MainSwitchRemote { id, target },
+        [MessageTarget::Broadcast {
+            across_couplings,
+            include_self: true,
+        }],
+    );
+}
@@ -0,0 +1,306 @@
+//! # Standard Coupling Messages
+//!
+//! This module defines the generic `"Std_Coupler"` dialect and a translation
+//! layer between it and the GT6N coupler messages, so vehicles from different
+//! authors using this crate can couple and still exchange the basic control
+//! signals.
+//!
+//! ## Dialect
+//!
+//! The standard dialect only carries signals every vehicle understands:
+//!
+//! - [`StdDemand`]: Traction and brake demand from 0.0 to 1.0 each
+//! - [`StdEmergencyBrake`]: Emergency brake loop
+//! - [`StdDoorCommand`]: Door release, opening and fast closing per side
+//!
+//! ## Translation
+//!
+//! A GT6N coupler handler which implements [`StdDialect`] is wrapped in a
+//! [`DialectBridge`]. The bridge sends each value in both dialects and accepts
+//! both, so it works towards GT6N vehicles as well as towards vehicles which
+//! only speak the standard dialect.
+//!
+//! ```rust
+//! let mut throttle = UniversalCouplingLine::new(DialectBridge(CouplerThrottle), (true, true));
+//! let mut doors = UniversalCouplingLine::new(DialectBridge(CouplerDoorControl), (true, true));
+//!
+//! // In on_message:
+//! throttle.on_message(msg.clone());
+//! doors.on_message(msg);
+//! ```
+
+use lotus_script::{
+    message::Coupling,
+    prelude::{message_type, send_message, Message, MessageTarget, MessageType},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    management::enums::{
+        door_enums::{DoorCommand, DoorSideTarget, DoorTarget},
+        general_enums::Side,
+    },
+    messages::{
+        coupling_handler::{orient_incoming, orient_outgoing, MessageLine},
+        gt6n_coupling_messages::{CouplerDoorControl, CouplerEmergencyBrake, CouplerThrottle},
+    },
+};
+
+fn send_across<T: MessageType>(msg: &T, side: Coupling) {
+    send_message(
+        msg,
+        [MessageTarget::AcrossCoupling {
+            coupling: side,
+            cascade: false,
+        }],
+    );
+}
+
+//===================================================================
+// Translation layer
+//===================================================================
+
+/// Translation of a coupler handler into the standard dialect.
+pub trait StdDialect<T> {
+    /// Sends the value in the standard dialect to the specified coupling.
+    fn send_std(&self, value: T, side: Coupling);
+
+    /// Receives a message of the standard dialect and translates its value.
+    fn rcv_std(&self, msg: Message) -> Option<(Coupling, T)>;
+}
+
+/// Coupler handler speaking its own dialect and the standard dialect.
+///
+/// Sends each value in both dialects and accepts messages of both.
+pub struct DialectBridge<H>(pub H);
+
+impl<T: PartialEq + Clone, H: MessageLine<T> + StdDialect<T>> MessageLine<T> for DialectBridge<H> {
+    fn evaluate(&self, a: &T, b: &T) -> T {
+        self.0.evaluate(a, b)
+    }
+
+    fn send(&self, value: T, side: Coupling) {
+        self.0.send(value.clone(), side);
+        self.0.send_std(value, side);
+    }
+
+    fn rcv(&self, msg: Message) -> Option<(Coupling, T)> {
+        self.0.rcv(msg.clone()).or_else(|| self.0.rcv_std(msg))
+    }
+}
+
+//===================================================================
+// Demand
+//===================================================================
+
+/// Traction and brake demand in the standard dialect.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct StdDemand {
+    /// Traction demand from 0.0 to 1.0
+    pub traction: f32,
+    /// Brake demand from 0.0 to 1.0
+    pub brake: f32,
+}
+
+message_type!(StdDemand, "Std_Coupler", "Demand");
+
+impl StdDemand {
+    /// Splits a GT6N throttle value, positive for traction and negative for
+    /// braking.
+    pub fn from_throttle(value: f32) -> Self {
+        Self {
+            traction: value.clamp(0.0, 1.0),
+            brake: (-value).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Combines the demand into a GT6N throttle value, braking has priority.
+    pub fn to_throttle(self) -> f32 {
+        if self.brake > 0.0 {
+            -self.brake
+        } else {
+            self.traction
+        }
+    }
+}
+
+impl StdDialect<f32> for CouplerThrottle {
+    fn send_std(&self, value: f32, side: Coupling) {
+        send_across(&StdDemand::from_throttle(value), side);
+    }
+
+    fn rcv_std(&self, msg: Message) -> Option<(Coupling, f32)> {
+        let mut result = None;
+
+        if let Some(side) = msg.source().coupling {
+            msg.handle::<StdDemand>(|m| {
+                result = Some((side, m.to_throttle()));
+                Ok(())
+            })
+            .expect("StdDemand: message handle failed");
+        }
+
+        result
+    }
+}
+
+//===================================================================
+// Emergency brake
+//===================================================================
+
+/// Emergency brake loop in the standard dialect.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct StdEmergencyBrake {
+    /// Whether the emergency brake is applied
+    pub active: bool,
+}
+
+message_type!(StdEmergencyBrake, "Std_Coupler", "EmergencyBrake");
+
+impl StdDialect<bool> for CouplerEmergencyBrake {
+    fn send_std(&self, value: bool, side: Coupling) {
+        send_across(&StdEmergencyBrake { active: value }, side);
+    }
+
+    fn rcv_std(&self, msg: Message) -> Option<(Coupling, bool)> {
+        let mut result = None;
+
+        if let Some(side) = msg.source().coupling {
+            msg.handle::<StdEmergencyBrake>(|m| {
+                result = Some((side, m.active));
+                Ok(())
+            })
+            .expect("StdEmergencyBrake: message handle failed");
+        }
+
+        result
+    }
+}
+
+//===================================================================
+// Doors
+//===================================================================
+
+/// Door command in the standard dialect.
+///
+/// The sides are given as seen by a car whose front coupling faces the
+/// sender, like the GT6N door command.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct StdDoorCommand {
+    /// Doors released for the passengers
+    pub release: bool,
+    /// Doors opened by the driver
+    pub open: bool,
+    /// Doors closed without waiting for the passengers
+    pub fast_close: bool,
+    /// Command applies to the left doors, missing in older vehicles and then
+    /// applying to both sides
+    #[serde(default = "both_sides")]
+    pub left: bool,
+    /// Command applies to the right doors
+    #[serde(default = "both_sides")]
+    pub right: bool,
+}
+
+fn both_sides() -> bool {
+    true
+}
+
+message_type!(StdDoorCommand, "Std_Coupler", "DoorCommand");
+
+impl From<DoorCommand> for StdDoorCommand {
+    fn from(value: DoorCommand) -> Self {
+        Self {
+            release: matches!(value.target, DoorTarget::Release | DoorTarget::Open),
+            open: value.target == DoorTarget::Open,
+            fast_close: value.target == DoorTarget::FastClose,
+            left: value.side.includes(Side::Left),
+            right: value.side.includes(Side::Right),
+        }
+    }
+}
+
+impl From<StdDoorCommand> for DoorCommand {
+    fn from(value: StdDoorCommand) -> Self {
+        let target = if value.open {
+            DoorTarget::Open
+        } else if value.release {
+            DoorTarget::Release
+        } else if value.fast_close {
+            DoorTarget::FastClose
+        } else {
+            DoorTarget::Close
+        };
+
+        let side = match (value.left, value.right) {
+            (true, true) => DoorSideTarget::Both,
+            (true, false) => DoorSideTarget::Left,
+            (false, true) => DoorSideTarget::Right,
+            (false, false) => DoorSideTarget::None,
+        };
+
+        DoorCommand::new(target, side)
+    }
+}
+
+impl StdDialect<DoorCommand> for CouplerDoorControl {
+    fn send_std(&self, value: DoorCommand, side: Coupling) {
+        send_across(&StdDoorCommand::from(orient_outgoing(value, side)), side);
+    }
+
+    fn rcv_std(&self, msg: Message) -> Option<(Coupling, DoorCommand)> {
+        let mut result = None;
+
+        if let Some(side) = msg.source().coupling {
+            msg.handle::<StdDoorCommand>(|m| {
+                result = Some((side, orient_incoming(m.into(), side)));
+                Ok(())
+            })
+            .expect("StdDoorCommand: message handle failed");
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_roundtrip() {
+        for value in [-1.0, -0.4, 0.0, 0.6, 1.0] {
+            assert_eq!(StdDemand::from_throttle(value).to_throttle(), value);
+        }
+    }
+
+    #[test]
+    fn test_door_roundtrip() {
+        for target in [
+            DoorTarget::FastClose,
+            DoorTarget::Close,
+            DoorTarget::Release,
+            DoorTarget::Open,
+        ] {
+            for side in [
+                DoorSideTarget::None,
+                DoorSideTarget::Left,
+                DoorSideTarget::Right,
+                DoorSideTarget::Both,
+            ] {
+                let command = DoorCommand::new(target, side);
+                assert_eq!(DoorCommand::from(StdDoorCommand::from(command)), command);
+            }
+        }
+    }
+
+    #[test]
+    fn test_door_command_without_sides() {
+        let command: StdDoorCommand =
+            serde_json::from_str(r#"{"release":true,"open":false,"fast_close":false}"#).unwrap();
+        assert_eq!(
+            DoorCommand::from(command),
+            DoorCommand::new(DoorTarget::Release, DoorSideTarget::Both)
+        );
+    }
+}